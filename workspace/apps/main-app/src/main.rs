@@ -2,44 +2,111 @@
 #![no_main]
 
 extern crate alloc;
+
+/// `rtt_target::rprintln!` that also keeps the line for the console `log` command
+///
+/// The panic handler calls `rtt_target::rprintln!` directly: the panic may
+/// have been raised while `LOG_BUFFER` was borrowed.
+macro_rules! rprintln {
+    ($($arg:tt)*) => {
+        $crate::log_line(format_args!($($arg)*))
+    };
+}
+
+mod ota;
+mod selftest;
+
 use alloc::format;
 use alloc::string::ToString;
 use alloc::vec::Vec;
-use core::str::FromStr;
+use core::cell::RefCell;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
 use embassy_executor::Spawner;
-use embassy_time::{Duration, Timer};
+use embassy_time::{Duration, Instant, Timer, with_timeout};
 use embassy_sync::signal::Signal;
+use embassy_sync::once_lock::OnceLock;
 use embassy_sync::mutex::Mutex;
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::blocking_mutex::Mutex as BlockingMutex;
 use esp_hal::{
     timer::timg::TimerGroup,
-    usb_serial_jtag::UsbSerialJtag,
     i2c::master::{I2c, Config},
-    system,
+    time::Rate,
     Async,
 };
 use esp_storage::{FlashStorage, FlashStorageError};
 use embedded_storage::{ReadStorage, Storage};
+use iot_storage::{DataLogger, LogRecord, LOG_RECORD_SIZE, ConfigChangeKind, ConfigSubscriber, notify_config_change, subscribe_config_changes};
+use iot_storage::{OtaPartitions, TrialBoot, FlashRegionTable, FlashRegionHandle, find_partition};
 
 // Hardware Abstraction Layer for clean architecture and status LED
-use iot_hal::{Esp32C3Platform, HardwarePlatform, GpioInterface, TimerInterface};
+use iot_hal::{Esp32C3Platform, HardwareConfig, HardwarePlatform, GpioInterface, TimerInterface, WatchdogInterface, ResetReason, StatusLed, LedCondition, LedPattern, TaskHeartbeat, feed_if_healthy};
+use iot_hal::crash::PanicReason;
+use iot_container::{RetryConfig, EventBus, EventSubscriber, SystemEvent, Measurements, MeasurementBuffer, ExponentialSmoother};
+use iot_common::{IoTError, ErrorHistory, DeviceInfo, LogBuffer, LogLevel, BootClock};
+use iot_common::{logging, log_debug, log_trace};
+use iot_common::error::wifi_conversions::from_wifi_error;
+
+/// Console transmit half: USB Serial/JTAG by default, UART0 with `uart-console`
+#[cfg(not(feature = "uart-console"))]
+type ConsoleTx = esp_hal::usb_serial_jtag::UsbSerialJtagTx<'static, Async>;
+/// Console receive half: USB Serial/JTAG by default, UART0 with `uart-console`
+#[cfg(not(feature = "uart-console"))]
+type ConsoleRx = esp_hal::usb_serial_jtag::UsbSerialJtagRx<'static, Async>;
+#[cfg(feature = "uart-console")]
+type ConsoleTx = esp_hal::uart::UartTx<'static, Async>;
+#[cfg(feature = "uart-console")]
+type ConsoleRx = esp_hal::uart::UartRx<'static, Async>;
+
+/// BME280 bus clock; standard mode tolerates the longer jumper-wire runs
+const I2C_FREQUENCY_HZ: u32 = 100_000;
+const _: () = assert!(I2C_FREQUENCY_HZ >= iot_hal::I2C_MIN_FREQUENCY_HZ && I2C_FREQUENCY_HZ <= iot_hal::I2C_MAX_FREQUENCY_HZ);
+/// Bound on each `i2c probe` register read, the `iot-hal` I2C transaction timeout default
+const I2C_TRANSACTION_TIMEOUT_MS: u64 = 1000;
+const _: () = assert!(I2C_TRANSACTION_TIMEOUT_MS <= iot_hal::I2C_MAX_TRANSACTION_TIMEOUT.as_millis());
+/// Longest `i2c probe` waits for the sensor task: six reads plus a sensor cycle in progress
+const I2C_PROBE_WAIT_MS: u64 = 8 * I2C_TRANSACTION_TIMEOUT_MS;
+
+/// Board layout: DevKit I2C pins and sensor address, with the BME280 clock above
+///
+/// A UART console frees GPIO18/19 (USB D-/D+) for the I2C bus.
+fn hardware_config() -> HardwareConfig {
+    let mut config = HardwareConfig::esp32c3_devkit();
+    config.i2c = config.i2c.with_frequency(I2C_FREQUENCY_HZ);
+    #[cfg(feature = "uart-console")]
+    {
+        config.uart = iot_hal::UartConfig::esp32c3_uart0(DEFAULT_CONSOLE_BAUD);
+    }
+    config
+}
+
+/// UART console baud rate when `CONSOLE_BAUD` is unset or unsupported
+#[cfg(feature = "uart-console")]
+const DEFAULT_CONSOLE_BAUD: u32 = 115_200;
 
-use panic_rtt_target as _;
-use rtt_target::{rprintln, rtt_init_print};
+use rtt_target::rtt_init_print;
+use selftest::{detail, SelfTestReport, StepOutcome};
+use ota::{OtaError, OtaProgress, OtaUpdater};
 
 // Import our modules
-use bme280_embassy::{BME280, I2cDevice};
-use mqtt_embassy::{MqttClient, MqttConfig, SensorData, DeviceStatus};
+use bme280_embassy::{BME280, RecoveryAction, RecoveryPolicy, RegisterDump, SensorOffsets};
+use mqtt_embassy::{MqttClient, MqttConfig, BrokerAddress, SocketMonitor, SensorData, DeviceStatus, MAX_BATCH_READINGS, MAX_LAST_RESET_LEN};
+use mqtt_embassy::sntp::{self, SntpClient, SntpConfig, SntpTimestampProvider};
 
 // WiFi connectivity using wifi-embassy module
-use wifi_embassy::{WiFiManager, WiFiConfig};
+use wifi_embassy::{WiFiManager, WiFiConfig, WiFiTimeouts, ConnectionChange, disconnect_reason_str};
+use serial_console_embassy::{ConsoleContainerAdapter, ConsoleExtension, OutputMode};
+use serial_console_embassy::config::{
+    FlashConfig, SlotState, FLASH_CONFIG_SIZE, DEFAULT_SENSOR_INTERVAL_SECS, MIN_SENSOR_INTERVAL_SECS,
+    MAX_SENSOR_INTERVAL_SECS, MAX_TEMPERATURE_OFFSET, MAX_HUMIDITY_OFFSET, MAX_PRESSURE_OFFSET,
+};
 use static_cell::StaticCell;
 
 // Performance monitoring system
 use iot_performance::{
     PerformanceMonitor, TimingCategory, MemoryTracker, 
-    PerformanceAnalyzer, PerformanceReport, Instant as PerfInstant,
+    PerformanceAnalyzer, PerformanceMetric, PerformanceTrend, Instant as PerfInstant,
     SENSOR_CYCLE_TARGET_US
 };
 
@@ -51,6 +118,36 @@ static SYSTEM_STATE: Mutex<CriticalSectionRawMutex, SystemState> =
 static WIFI_CREDENTIALS: Mutex<CriticalSectionRawMutex, WifiCredentials> = 
     Mutex::new(WifiCredentials::new());
 
+// Flash log of every sensor reading, None until mounted (or if mounting failed)
+static DATA_LOGGER: Mutex<CriticalSectionRawMutex, Option<DataLogger<FlashStorage>>> =
+    Mutex::new(None);
+
+// Configuration name announced when the WiFi/MQTT settings change
+const WIFI_CONFIG_NAME: &str = "wifi";
+// Set once the config watcher runs, so credential changes apply without a restart
+static WIFI_LIVE_UPDATE: AtomicBool = AtomicBool::new(false);
+
+// Recent log lines for the console `log` command; a blocking mutex because
+// logging is synchronous and may happen from any task
+static LOG_BUFFER: BlockingMutex<CriticalSectionRawMutex, RefCell<LogBuffer>> =
+    BlockingMutex::new(RefCell::new(LogBuffer::new()));
+
+// Readings not yet published: the sensor task pushes, the MQTT task drains, so
+// readings taken while WiFi or the broker is down go out after reconnecting
+static MEASUREMENT_BUFFER: BlockingMutex<CriticalSectionRawMutex, RefCell<MeasurementBuffer<SensorData, MAX_BATCH_READINGS>>> =
+    BlockingMutex::new(RefCell::new(MeasurementBuffer::new()));
+
+// Console output mode: compact JSON for scripted hosts when set, text banners otherwise;
+// mirrors the console's `json on|off` for the command being run, see `AppConsole`
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+// Live sensor interval in seconds, loaded from flash at boot and changed by `interval <secs>`
+static SENSOR_INTERVAL: AtomicU32 = AtomicU32::new(SENSOR_INTERVAL_SECS as u32);
+// Wakes the sensor task so a new interval applies without waiting out the old one
+static SENSOR_INTERVAL_CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+// Calibration offsets for the sensor task, loaded from flash and changed by `calibrate`
+static SENSOR_OFFSETS_CHANGED: Signal<CriticalSectionRawMutex, SensorOffsets> = Signal::new();
+
 // TODO: Implement dynamic WiFi credential loading (currently using hardcoded fallback)
 // static WIFI_SSID_STORAGE: StaticCell<[u8; 32]> = StaticCell::new();
 // static WIFI_PASSWORD_STORAGE: StaticCell<[u8; 64]> = StaticCell::new();
@@ -58,13 +155,121 @@ static WIFI_CREDENTIALS: Mutex<CriticalSectionRawMutex, WifiCredentials> =
 // Platform abstraction for status indication
 static PLATFORM_CELL: StaticCell<Esp32C3Platform> = StaticCell::new();
 
+// Time source for MQTT payloads and data log records: uptime, plus Unix time once SNTP has synced
+static CLOCK: SntpTimestampProvider = SntpTimestampProvider;
+
+// Uptime for the console, status messages, log lines and performance reports; started once in `main`
+static BOOT_CLOCK: OnceLock<BootClock> = OnceLock::new();
+
+// Broker socket state and traffic, written by the MQTT task and read by `netstat`
+static MQTT_SOCKET: SocketMonitor = SocketMonitor::new();
+
+// Chip, MAC and firmware identity, read once at boot
+static DEVICE_INFO: Mutex<CriticalSectionRawMutex, Option<DeviceInfo>> = Mutex::new(None);
+
+// Panic that caused the last reset, recovered from RTC memory at boot
+static PANIC_REASON: Mutex<CriticalSectionRawMutex, Option<PanicReason>> = Mutex::new(None);
+
 // Performance monitoring infrastructure
 static PERFORMANCE_MONITOR_CELL: StaticCell<PerformanceMonitor> = StaticCell::new();
 static MEMORY_TRACKER_CELL: StaticCell<Mutex<CriticalSectionRawMutex, MemoryTracker>> = StaticCell::new();
 static PERFORMANCE_ANALYZER_CELL: StaticCell<Mutex<CriticalSectionRawMutex, PerformanceAnalyzer>> = StaticCell::new();
 
-static SENSOR_DATA_SIGNAL: Signal<CriticalSectionRawMutex, SensorReading> = Signal::new();
-static PERFORMANCE_REPORT_SIGNAL: Signal<CriticalSectionRawMutex, PerformanceReport> = Signal::new();
+// Sensor, WiFi, MQTT and performance tasks publish here; any task may subscribe
+static EVENT_BUS: EventBus = EventBus::new();
+
+// Graceful restart handshake: console requests, MQTT task flushes and acknowledges
+static SHUTDOWN_SIGNAL: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+static SHUTDOWN_COMPLETE_SIGNAL: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+// Upper bound on how long a restart waits for the MQTT flush
+const SHUTDOWN_TIMEOUT_MS: u64 = 3000;
+
+// Self-test requests to the tasks that own the hardware, answered on the result signal
+static SENSOR_PROBE_REQUEST: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+static SENSOR_PROBE_RESULT: Signal<CriticalSectionRawMutex, Result<(u8, u8), IoTError>> = Signal::new();
+static LED_TEST_REQUEST: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+static LED_TEST_RESULT: Signal<CriticalSectionRawMutex, Result<(), IoTError>> = Signal::new();
+// Restart requests to `status_led_task`, which owns the platform and resets through it
+static RESET_REQUEST: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+// Set once `status_led_task` owns the platform and answers `RESET_REQUEST`
+static PLATFORM_ACTIVE: AtomicBool = AtomicBool::new(false);
+// `i2c probe [addr]` register dump, also answered by the sensor task (None = sensor's own address)
+static SENSOR_DUMP_REQUEST: Signal<CriticalSectionRawMutex, Option<u8>> = Signal::new();
+static SENSOR_DUMP_RESULT: Signal<CriticalSectionRawMutex, Result<RegisterDump, IoTError>> = Signal::new();
+// Per-step self-test timeouts; the broker step covers a full TCP handshake
+const SELFTEST_SENSOR_TIMEOUT_MS: u64 = 2000;
+const SELFTEST_WIFI_TIMEOUT_MS: u64 = 1000;
+const SELFTEST_BROKER_TIMEOUT_MS: u64 = 5000;
+const SELFTEST_STORAGE_TIMEOUT_MS: u64 = 2000;
+const SELFTEST_LED_TIMEOUT_MS: u64 = 3000;
+// Boot self-test (`SELFTEST_AT_BOOT=true`) waits this long for WiFi and MQTT to come up
+const SELFTEST_BOOT_DELAY_SECS: u64 = 15;
+
+// Set while freshly installed firmware runs its trial boot and has not confirmed yet
+static OTA_TRIAL: AtomicBool = AtomicBool::new(false);
+// Tells the status LED task the trial boot is over, so it starts feeding the watchdog
+static OTA_CONFIRMED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+// Download progress from the `ota` command's updater to its console output
+static OTA_PROGRESS: Signal<CriticalSectionRawMutex, OtaProgress> = Signal::new();
+// New firmware must reach WiFi and MQTT within this time, or the previous image returns
+const OTA_CONFIRM_TIMEOUT_SECS: u64 = 120;
+// Hardware watchdog for the trial boot, covering hangs the confirm timeout cannot catch
+const OTA_WATCHDOG_SECS: u64 = OTA_CONFIRM_TIMEOUT_SECS + 30;
+
+// The sensor and MQTT loops beat this heartbeat; the status LED task feeds the
+// watchdog only while both report, so a task wedged in a blocking call (e.g. a
+// stuck I2C read) resets the board. The MQTT task joins once it is running.
+const SENSOR_TASK_ID: u8 = 0;
+const MQTT_TASK_ID: u8 = 1;
+static HEARTBEAT: TaskHeartbeat = TaskHeartbeat::new(1 << SENSOR_TASK_ID);
+// A check passes when every supervised task beat since the previous one
+const WATCHDOG_CHECK_SECS: u64 = 45;
+// Supervised tasks wait out long intervals in steps of this length
+const BEAT_STEP_SECS: u64 = WATCHDOG_CHECK_SECS / 3;
+// Expires after two missed checks in a row, i.e. a task silent for over 90s;
+// a single slow MQTT cycle (several broker connect timeouts) is tolerated
+const WATCHDOG_TIMEOUT_SECS: u64 = 2 * WATCHDOG_CHECK_SECS + 30;
+
+// Publish "sensor offline" placeholders while the BME280 is absent instead of
+// going silent; build with `PUBLISH_SENSOR_OFFLINE=true` to enable
+const PUBLISH_SENSOR_OFFLINE: bool = matches!(option_env!("PUBLISH_SENSOR_OFFLINE"), Some("true"));
+
+// Add the on-die temperature to the MQTT device status; build with
+// `PUBLISH_CHIP_TEMPERATURE=true` to enable
+const PUBLISH_CHIP_TEMPERATURE: bool = matches!(option_env!("PUBLISH_CHIP_TEMPERATURE"), Some("true"));
+// How often the status LED task samples the on-die temperature sensor
+const CHIP_TEMPERATURE_INTERVAL_SECS: u64 = 30;
+
+// Moving average over published readings, 0 < alpha <= 1 (1.0 publishes raw readings).
+// Lower values smooth more but lag more; the console always shows raw readings.
+// Build with e.g. `PUBLISH_SMOOTHING_ALPHA=0.3` to enable
+const PUBLISH_SMOOTHING_ALPHA: Option<&str> = option_env!("PUBLISH_SMOOTHING_ALPHA");
+
+// Ordered broker failover list, e.g. `MQTT_BROKERS=10.10.10.210,10.10.10.211:1884`;
+// replaces `MQTT_BROKER_IP`/`MQTT_BROKER_PORT` when set
+const MQTT_BROKERS: Option<&str> = option_env!("MQTT_BROKERS");
+
+// Startup verbosity (`none`..`trace`), changed at runtime with `loglevel`
+const LOG_LEVEL: Option<&str> = option_env!("LOG_LEVEL");
+
+// Default sensor cycle until `interval <secs>` saves another one
+const SENSOR_INTERVAL_SECS: u64 = DEFAULT_SENSOR_INTERVAL_SECS as u64;
+// Accepted range of the persisted sensor interval
+const SENSOR_INTERVAL_MIN_SECS: u64 = MIN_SENSOR_INTERVAL_SECS as u64;
+const SENSOR_INTERVAL_MAX_SECS: u64 = MAX_SENSOR_INTERVAL_SECS as u64;
+// Failed sensor cycles in a row before a cycle also soft-resets and re-inits the BME280
+const SENSOR_REINIT_AFTER_ERRORS: u32 = 10;
+// Bounds of the `watch` interval; the lower one keeps the stream from flooding the link
+const WATCH_MIN_INTERVAL_SECS: u64 = 1;
+const WATCH_MAX_INTERVAL_SECS: u64 = 3600;
+// Gateway ping before each publish; short so an unreachable gateway costs little
+const GATEWAY_PING_TIMEOUT_MS: u64 = 1000;
+// Failed gateway pings in a row after which the broker connect is tried anyway,
+// since some routers drop ICMP. Pings stay off while the broker answers; a
+// failed connect turns them back on.
+const GATEWAY_PING_MISSES: u32 = 3;
+// Console `ping` waits longer, the operator is probing possibly distant hosts
+const CONSOLE_PING_TIMEOUT_MS: u64 = 3000;
 
 #[derive(Clone, Copy)]
 struct SystemState {
@@ -73,11 +278,22 @@ struct SystemState {
     wifi_connected: bool,
     mqtt_connected: bool,
     reading_count: u32,
+    sensor_resets: u32,
     status_led_on: bool,
     performance_monitoring: bool,
     last_sensor_time_us: u32,
+    last_temperature: f32,
+    last_humidity: f32,
+    last_pressure: f32,
     heap_usage: usize,
+    flash_usage: usize,
     performance_alerts: u8,
+    /// Recent runtime faults, published on the status topic and shown by `errors`
+    error_history: ErrorHistory,
+    /// On-die temperature sampled by the status LED task, `None` until read or if the sensor fails
+    chip_temperature: Option<f32>,
+    /// Broker of the last successful MQTT connection
+    mqtt_broker: Option<BrokerAddress>,
 }
 
 impl SystemState {
@@ -88,121 +304,96 @@ impl SystemState {
             wifi_connected: false,
             mqtt_connected: false,
             reading_count: 0,
+            sensor_resets: 0,
             status_led_on: false,
             performance_monitoring: false,
             last_sensor_time_us: 0,
+            last_temperature: 0.0,
+            last_humidity: 0.0,
+            last_pressure: 0.0,
             heap_usage: 0,
+            flash_usage: 0,
             performance_alerts: 0,
+            error_history: ErrorHistory::new(),
+            chip_temperature: None,
+            mqtt_broker: None,
         }
     }
 }
 
-#[derive(Clone, Copy)]
-struct SensorReading {
-    temperature: f32,
-    humidity: f32,
-    pressure: f32,
-    #[allow(dead_code)]
-    count: u32,
-}
+// Configuration stored in flash; layout and CRC live in serial-console-embassy
+type WifiCredentials = FlashConfig;
 
-// Configuration stored in flash - using fixed-size arrays for const compatibility
-#[derive(Clone, Copy, Debug)]
-struct WifiCredentials {
-    ssid: [u8; 32],
-    ssid_len: u8,
-    password: [u8; 64], 
-    password_len: u8,
-    mqtt_broker_ip: [u8; 16],
-    mqtt_broker_ip_len: u8,
-    mqtt_broker_port: u16,
-    is_configured: bool,
+const WIFI_CONFIG_SIZE: usize = FLASH_CONFIG_SIZE;
+
+// Flash regions, requested by name from FLASH_LAYOUT
+const REGION_WIFI_CONFIG: &str = "wifi_config";
+const REGION_WIFI_CONFIG_PREVIOUS: &str = "wifi_config_previous";
+const REGION_SELFTEST: &str = "selftest";
+const REGION_DATA_LOG: &str = "data_log";
+// Data partition holding every region (see partitions.csv)
+const APPDATA_PARTITION: &str = "appdata";
+
+// The application's flash layout, checked against overlaps and the partition table at boot.
+// New subsystems declare their region here instead of picking an offset of their own.
+const FLASH_LAYOUT: FlashRegionTable<'static> = FlashRegionTable::new(0x400000, &[
+    // Current configuration, written by `save`
+    FlashRegionHandle::new(REGION_WIFI_CONFIG, 0x310000, 0x1000),
+    // Previous configuration, restored by `config rollback`. Every save copies the current
+    // slot here first, so a save costs two sector erases instead of one; only one previous
+    // version is kept to bound flash usage.
+    FlashRegionHandle::new(REGION_WIFI_CONFIG_PREVIOUS, 0x311000, 0x1000),
+    // Scratch sector the self-test writes and reads back
+    FlashRegionHandle::new(REGION_SELFTEST, 0x312000, 0x1000),
+    // Sensor data log: 16 sectors (2048 records, ~17h at the default interval)
+    FlashRegionHandle::new(REGION_DATA_LOG, 0x320000, 0x10000),
+]);
+
+/// Region `name` of [`FLASH_LAYOUT`]; a missing declaration is a programming error
+fn flash_region(name: &str) -> FlashRegionHandle {
+    FLASH_LAYOUT.region(name).unwrap_or_else(|| panic!("Flash region '{}' not declared", name))
 }
 
-impl WifiCredentials {
-    const fn new() -> Self {
-        Self {
-            ssid: [0u8; 32],
-            ssid_len: 0,
-            password: [0u8; 64],
-            password_len: 0,
-            mqtt_broker_ip: [b'1', b'0', b'.', b'1', b'0', b'.', b'1', b'0', b'.', b'2', b'1', b'0', 0, 0, 0, 0],
-            mqtt_broker_ip_len: 12,
-            mqtt_broker_port: 1883,
-            is_configured: false,
-        }
-    }
-    
-    fn get_ssid(&self) -> &str {
-        core::str::from_utf8(&self.ssid[..self.ssid_len as usize]).unwrap_or("")
-    }
-    
-    fn get_password(&self) -> &str {
-        core::str::from_utf8(&self.password[..self.password_len as usize]).unwrap_or("")
-    }
-    
-    fn get_mqtt_broker_ip(&self) -> &str {
-        core::str::from_utf8(&self.mqtt_broker_ip[..self.mqtt_broker_ip_len as usize]).unwrap_or("10.10.10.210")
+/// Validates [`FLASH_LAYOUT`] and checks that it lies inside the `appdata` partition
+///
+/// Panics on a bad layout: a region overlapping another one, the app slots
+/// or the bootloader data would corrupt them on its first write.
+fn check_flash_layout() {
+    if let Err(e) = FLASH_LAYOUT.validate() {
+        panic!("Invalid flash layout: {:?}", e);
     }
-    
-    fn set_ssid(&mut self, ssid: &str) -> bool {
-        let bytes = ssid.as_bytes();
-        if bytes.len() <= 32 {
-            self.ssid[..bytes.len()].copy_from_slice(bytes);
-            self.ssid_len = bytes.len() as u8;
-            // Clear the rest
-            for i in bytes.len()..32 {
-                self.ssid[i] = 0;
+    match find_partition(&mut FlashStorage::new(), APPDATA_PARTITION) {
+        Ok(Some(appdata)) => {
+            if let Err(e) = FLASH_LAYOUT.validate_partition(appdata) {
+                panic!("Flash layout does not fit the partition table: {:?}", e);
             }
-            true
-        } else {
-            false
+            rprintln!("[FLASH] Layout OK - {} regions in '{}' partition at 0x{:X}",
+                     FLASH_LAYOUT.regions().len(), APPDATA_PARTITION, appdata.offset);
         }
-    }
-    
-    fn set_password(&mut self, password: &str) -> bool {
-        let bytes = password.as_bytes();
-        if bytes.len() <= 64 {
-            self.password[..bytes.len()].copy_from_slice(bytes);
-            self.password_len = bytes.len() as u8;
-            // Clear the rest
-            for i in bytes.len()..64 {
-                self.password[i] = 0;
-            }
-            true
-        } else {
-            false
+        Ok(None) => {
+            rprintln!("[FLASH] WARNING: No '{}' partition - flash regions are not reserved in the partition table",
+                     APPDATA_PARTITION);
         }
-    }
-    
-    fn set_mqtt_broker_ip(&mut self, ip: &str) -> bool {
-        let bytes = ip.as_bytes();
-        if bytes.len() <= 16 {
-            self.mqtt_broker_ip[..bytes.len()].copy_from_slice(bytes);
-            self.mqtt_broker_ip_len = bytes.len() as u8;
-            // Clear the rest
-            for i in bytes.len()..16 {
-                self.mqtt_broker_ip[i] = 0;
-            }
-            true
-        } else {
-            false
+        Err(e) => {
+            rprintln!("[FLASH] WARNING: Failed to read partition table: {:?}", e);
         }
     }
 }
 
-// Flash storage configuration - using a higher offset that should persist
-const WIFI_CONFIG_FLASH_OFFSET: u32 = 0x310000; // Use higher flash region  
-const WIFI_CONFIG_SIZE: usize = 256;
-
 struct ConfigManager {
     storage: FlashStorage,
+    /// Offset of the current configuration slot
+    current_offset: u32,
+    /// Offset of the previous configuration slot
+    previous_offset: u32,
 }
 
 impl ConfigManager {
     fn new() -> Self {
         Self {
             storage: FlashStorage::new(),
+            current_offset: flash_region(REGION_WIFI_CONFIG).offset,
+            previous_offset: flash_region(REGION_WIFI_CONFIG_PREVIOUS).offset,
         }
     }
 
@@ -210,9 +401,9 @@ impl ConfigManager {
         let mut buffer = [0u8; WIFI_CONFIG_SIZE];
         
         // Add debug output for flash reading
-        rprintln!("[CONFIG] Reading from flash offset 0x{:X}", WIFI_CONFIG_FLASH_OFFSET);
+        rprintln!("[CONFIG] Reading from flash offset 0x{:X}", self.current_offset);
         
-        match self.storage.read(WIFI_CONFIG_FLASH_OFFSET, &mut buffer) {
+        match self.storage.read(self.current_offset, &mut buffer) {
             Ok(()) => {
                 rprintln!("[CONFIG] Flash read successful, checking magic bytes");
                 rprintln!("[CONFIG] First 8 bytes: {:02X?}", &buffer[0..8]);
@@ -223,108 +414,52 @@ impl ConfigManager {
             }
         }
         
-        // Check magic bytes for validity
-        if buffer[0] == 0xCA && buffer[1] == 0xFE {
-            rprintln!("[CONFIG] Magic bytes found, parsing configuration");
-            let ssid_len = buffer[2];
-            let password_len = buffer[3];
-            let broker_ip_len = buffer[4];
-            let broker_port = u16::from_le_bytes([buffer[5], buffer[6]]);
-            
-            rprintln!("[CONFIG] Parsed lengths - SSID: {}, Password: {}, Broker IP: {}", 
-                     ssid_len, password_len, broker_ip_len);
-            
-            if ssid_len <= 32 && password_len <= 64 && broker_ip_len <= 16 {
-                let mut config = WifiCredentials::new();
-                
-                // Read SSID
-                if ssid_len > 0 {
-                    config.ssid[..ssid_len as usize].copy_from_slice(&buffer[8..8 + ssid_len as usize]);
-                    config.ssid_len = ssid_len;
-                }
-                
-                // Read password
-                if password_len > 0 {
-                    config.password[..password_len as usize].copy_from_slice(&buffer[40..40 + password_len as usize]);
-                    config.password_len = password_len;
-                }
-                
-                // Read broker IP
-                if broker_ip_len > 0 {
-                    config.mqtt_broker_ip[..broker_ip_len as usize].copy_from_slice(&buffer[104..104 + broker_ip_len as usize]);
-                    config.mqtt_broker_ip_len = broker_ip_len;
-                } else {
-                    // Set default broker IP if not found
-                    let default_ip = b"10.10.10.210";
-                    config.mqtt_broker_ip[..default_ip.len()].copy_from_slice(default_ip);
-                    config.mqtt_broker_ip_len = default_ip.len() as u8;
-                }
-                
-                config.mqtt_broker_port = broker_port;
-                config.is_configured = ssid_len > 0 && password_len > 0;
-                
+        match Self::decode_slot(&buffer) {
+            SlotState::Valid(config) => {
                 rprintln!("[CONFIG] Configuration loaded successfully - configured: {}", config.is_configured);
                 return Ok(config);
-            } else {
-                rprintln!("[CONFIG] Invalid data lengths found");
             }
-        } else {
-            rprintln!("[CONFIG] No magic bytes found (0x{:02X} 0x{:02X})", buffer[0], buffer[1]);
+            SlotState::Empty => {
+                rprintln!("[CONFIG] No magic bytes found (0x{:02X} 0x{:02X})", buffer[0], buffer[1]);
+            }
+            SlotState::Corrupted => {
+                rprintln!("[CONFIG] ##################################################");
+                rprintln!("[CONFIG] ERROR: CONFIG CORRUPTED at 0x{:X} (CRC mismatch)", self.current_offset);
+                rprintln!("[CONFIG] ##################################################");
+            }
         }
         
-        // Return default if no valid config found
-        let mut default_config = WifiCredentials::new();
-        // Set default broker IP
-        let default_ip = b"10.10.10.210";
-        default_config.mqtt_broker_ip[..default_ip.len()].copy_from_slice(default_ip);
-        default_config.mqtt_broker_ip_len = default_ip.len() as u8;
-        default_config.mqtt_broker_port = 1883;
+        // Primary slot unusable - fall back to the previous configuration if one was kept
+        let mut previous = [0u8; WIFI_CONFIG_SIZE];
+        self.storage.read(self.previous_offset, &mut previous)?;
+        if let SlotState::Valid(config) = Self::decode_slot(&previous) {
+            rprintln!("[CONFIG] Primary slot invalid, rolling back to previous configuration");
+            self.storage.write(self.current_offset, &previous)?;
+            return Ok(config);
+        }
         
+        // Return default if no valid config found
         rprintln!("[CONFIG] Returning default configuration");
-        Ok(default_config)
+        Ok(WifiCredentials::new())
     }
 
     fn save_wifi_credentials(&mut self, credentials: &WifiCredentials) -> Result<(), FlashStorageError> {
-        rprintln!("[CONFIG] Saving credentials to flash offset 0x{:X}", WIFI_CONFIG_FLASH_OFFSET);
-        
-        let mut buffer = [0u8; WIFI_CONFIG_SIZE];
-        
-        // Magic bytes
-        buffer[0] = 0xCA;
-        buffer[1] = 0xFE;
-        
-        // Lengths
-        buffer[2] = credentials.ssid_len;
-        buffer[3] = credentials.password_len;
-        buffer[4] = credentials.mqtt_broker_ip_len;
-        
-        // Broker port
-        let port_bytes = credentials.mqtt_broker_port.to_le_bytes();
-        buffer[5] = port_bytes[0];
-        buffer[6] = port_bytes[1];
-        
-        // Reserved byte
-        buffer[7] = 0x00;
-        
-        // SSID (offset 8, max 32 bytes)
-        if credentials.ssid_len > 0 {
-            buffer[8..8 + credentials.ssid_len as usize].copy_from_slice(&credentials.ssid[..credentials.ssid_len as usize]);
+        // Keep the configuration being replaced so it can be rolled back
+        let mut current = [0u8; WIFI_CONFIG_SIZE];
+        self.storage.read(self.current_offset, &mut current)?;
+        if Self::decode_slot(&current).is_valid() {
+            rprintln!("[CONFIG] Copying current configuration to previous slot 0x{:X}", self.previous_offset);
+            self.storage.write(self.previous_offset, &current)?;
         }
         
-        // Password (offset 40, max 64 bytes)
-        if credentials.password_len > 0 {
-            buffer[40..40 + credentials.password_len as usize].copy_from_slice(&credentials.password[..credentials.password_len as usize]);
-        }
+        rprintln!("[CONFIG] Saving credentials to flash offset 0x{:X}", self.current_offset);
         
-        // Broker IP (offset 104, max 16 bytes)
-        if credentials.mqtt_broker_ip_len > 0 {
-            buffer[104..104 + credentials.mqtt_broker_ip_len as usize].copy_from_slice(&credentials.mqtt_broker_ip[..credentials.mqtt_broker_ip_len as usize]);
-        }
+        let buffer = Self::encode_slot(credentials);
         
         rprintln!("[CONFIG] Writing buffer with magic bytes: {:02X?}", &buffer[0..8]);
         
         // Write to flash
-        match self.storage.write(WIFI_CONFIG_FLASH_OFFSET, &buffer) {
+        match self.storage.write(self.current_offset, &buffer) {
             Ok(()) => {
                 rprintln!("[CONFIG] Flash write completed successfully");
                 Ok(())
@@ -335,35 +470,195 @@ impl ConfigManager {
             }
         }
     }
+
+    /// Swap the primary and previous slots, returning the restored configuration.
+    /// Returns `Ok(None)` when no previous configuration has been saved.
+    fn rollback_wifi_credentials(&mut self) -> Result<Option<WifiCredentials>, FlashStorageError> {
+        let mut current = [0u8; WIFI_CONFIG_SIZE];
+        let mut previous = [0u8; WIFI_CONFIG_SIZE];
+        self.storage.read(self.current_offset, &mut current)?;
+        self.storage.read(self.previous_offset, &mut previous)?;
+        
+        let SlotState::Valid(restored) = Self::decode_slot(&previous) else {
+            rprintln!("[CONFIG] No previous configuration to roll back to");
+            return Ok(None);
+        };
+        
+        rprintln!("[CONFIG] Rolling back to previous configuration");
+        self.storage.write(self.current_offset, &previous)?;
+        if Self::decode_slot(&current).is_valid() {
+            // Keep the replaced configuration so the rollback itself can be undone
+            self.storage.write(self.previous_offset, &current)?;
+        }
+        
+        Ok(Some(restored))
+    }
+
+    /// Erase both configuration slots, returning the bytes of configuration discarded.
+    /// Erased slots hold no magic bytes, so the next load cleanly returns the defaults.
+    fn factory_reset(&mut self) -> Result<usize, FlashStorageError> {
+        let mut reclaimed = 0;
+        // Previous slot first: if interrupted, the primary is still intact and nothing
+        // stale can be rolled back to once it is gone
+        for offset in [self.previous_offset, self.current_offset] {
+            let mut slot = [0u8; WIFI_CONFIG_SIZE];
+            self.storage.read(offset, &mut slot)?;
+            if Self::decode_slot(&slot).is_valid() {
+                reclaimed += WIFI_CONFIG_SIZE;
+            }
+            
+            rprintln!("[CONFIG] Erasing configuration slot 0x{:X}", offset);
+            let sector = <FlashStorage as embedded_storage::nor_flash::NorFlash>::ERASE_SIZE as u32;
+            embedded_storage::nor_flash::NorFlash::erase(&mut self.storage, offset, offset + sector)?;
+        }
+        
+        Ok(reclaimed)
+    }
+
+    /// Parse a configuration slot, verifying its CRC
+    fn decode_slot(buffer: &[u8; WIFI_CONFIG_SIZE]) -> SlotState {
+        let state = FlashConfig::decode(buffer);
+        if let SlotState::Valid(config) = &state {
+            rprintln!("[CONFIG] Parsed lengths - SSID: {}, Password: {}, Broker IP: {}", 
+                     config.ssid_len, config.password_len, config.mqtt_broker_ip_len);
+        }
+        state
+    }
+
+    /// Serialize credentials into the on-flash slot layout
+    fn encode_slot(credentials: &WifiCredentials) -> [u8; WIFI_CONFIG_SIZE] {
+        credentials.encode()
+    }
+}
+
+/// Adds a runtime fault to the shared error history
+async fn record_error(error: IoTError) {
+    SYSTEM_STATE.lock().await.error_history.record(&error);
+}
+
+/// The system's boot clock
+///
+/// Falls back to counting from tick 0, where the time driver starts, if
+/// called before `main` has started it.
+fn boot_clock() -> BootClock {
+    BOOT_CLOCK.try_get().copied().unwrap_or(BootClock::from_instant(Instant::from_ticks(0)))
+}
+
+/// Applies a new sensor interval and wakes the sensor task to pick it up
+fn set_sensor_interval(secs: u64) {
+    SENSOR_INTERVAL.store(secs as u32, Ordering::Relaxed);
+    SENSOR_INTERVAL_CHANGED.signal(());
+    rprintln!("[SENSOR] Interval set to {}s", secs);
+}
+
+/// Announces a missing sensor on the event bus, at most once per sensor interval
+///
+/// Does nothing unless built with `PUBLISH_SENSOR_OFFLINE=true`. `last_announced`
+/// is cleared by the caller once a reading succeeds again.
+fn announce_sensor_offline(last_announced: &mut Option<Instant>, sequence: u32) {
+    if !PUBLISH_SENSOR_OFFLINE {
+        return;
+    }
+    let interval = Duration::from_secs(SENSOR_INTERVAL.load(Ordering::Relaxed) as u64);
+    if last_announced.is_some_and(|at| at.elapsed() < interval) {
+        return;
+    }
+    *last_announced = Some(Instant::now());
+    buffer_reading(None, sequence);
+    EVENT_BUS.publish(SystemEvent::SensorOffline { sequence });
+}
+
+/// MQTT settings from the build environment, with the `MQTT_BROKERS` list applied
+fn base_mqtt_config() -> MqttConfig {
+    let config = MqttConfig::default();
+    let Some(brokers) = MQTT_BROKERS else {
+        return config;
+    };
+    match config.clone().with_brokers(brokers) {
+        Ok(config) => config,
+        Err(e) => {
+            rprintln!("[MQTT] ERROR: MQTT_BROKERS rejected ({}), using MQTT_BROKER_IP only", e);
+            config
+        }
+    }
+}
+
+/// Moving average for published readings, configured by `PUBLISH_SMOOTHING_ALPHA`
+fn publish_smoother() -> ExponentialSmoother {
+    let alpha = match PUBLISH_SMOOTHING_ALPHA.map(str::parse::<f32>) {
+        None => 1.0,
+        Some(Ok(alpha)) if alpha > 0.0 && alpha <= 1.0 => alpha,
+        Some(_) => {
+            rprintln!("[SENSOR] WARNING: PUBLISH_SMOOTHING_ALPHA must be in (0, 1], publishing raw readings");
+            1.0
+        }
+    };
+    let smoother = ExponentialSmoother::new(alpha);
+    if smoother.is_enabled() {
+        rprintln!("[SENSOR] Smoothing published readings (alpha {:.2})", alpha);
+    }
+    smoother
+}
+
+/// Hands new calibration offsets to the sensor task, applied from the next reading
+fn set_sensor_offsets(offsets: SensorOffsets) {
+    SENSOR_OFFSETS_CHANGED.signal(offsets);
+    rprintln!("[SENSOR] Offsets set to T{:+.2}°C H{:+.2}% P{:+.2}hPa",
+             offsets.temperature, offsets.humidity, offsets.pressure);
 }
 
 #[embassy_executor::task]
 async fn sensor_task(
     mut i2c: I2c<'static, esp_hal::Blocking>,
+    sensor_address: u8,
     performance_monitor: &'static PerformanceMonitor,
 ) {
     rprintln!("[SENSOR] Initializing BME280 environmental sensor with performance monitoring...");
     
-    // Create I2C device abstraction for BME280
-    let i2c_device = I2cDevice::new(&mut i2c, 0x76); // BME280 primary address
-    let mut bme280 = BME280::new(i2c_device);
+    // Last "sensor offline" announcement, None while readings succeed
+    let mut offline_announced: Option<Instant> = None;
+    
+    // Detect the BME280 at the configured address, falling back to 0x76 or
+    // 0x77 (modules are strapped either way)
+    let mut bme280 = loop {
+        match BME280::new_auto_from(&mut i2c, sensor_address).await {
+            Ok(sensor) => {
+                rprintln!("[SENSOR] BME280 detected at address 0x{:02X}", sensor.address());
+                break sensor;
+            }
+            Err(e) => {
+                rprintln!("[SENSOR] ERROR: BME280 detection failed: {:?}", e);
+                record_error(e).await;
+                announce_sensor_offline(&mut offline_announced, 0);
+                // An absent sensor is not a hang; keep the watchdog fed while retrying
+                HEARTBEAT.beat(SENSOR_TASK_ID);
+                rprintln!("[SENSOR] Retrying detection in 5 seconds...");
+                Timer::after(Duration::from_secs(5)).await;
+            }
+        }
+    };
     
     // Ready to start performance monitoring with sensor readings
     
     // Initialize sensor with proper error handling
     // For BME280 with I2cDevice, initialization is handled in init() method
     
-    // Initialize sensor for measurements
+    // Initialize sensor for measurements, backing off 1s, 2s, 4s... up to 30s
+    let mut init_delay_secs = 1;
     loop {
         match bme280.init().await {
             Ok(_) => {
                 rprintln!("[SENSOR] BME280 initialized for operational measurements");
                 break;
             }
-            Err(_) => {
+            Err(e) => {
                 rprintln!("[SENSOR] ERROR: Failed to initialize BME280");
-                rprintln!("[SENSOR] Retrying initialization in 5 seconds...");
-                Timer::after(Duration::from_secs(5)).await;
+                record_error(e).await;
+                announce_sensor_offline(&mut offline_announced, 0);
+                HEARTBEAT.beat(SENSOR_TASK_ID);
+                rprintln!("[SENSOR] Retrying initialization in {} seconds...", init_delay_secs);
+                Timer::after(Duration::from_secs(init_delay_secs)).await;
+                init_delay_secs = (init_delay_secs * 2).min(30);
                 continue;
             }
         }
@@ -375,16 +670,48 @@ async fn sensor_task(
         state.sensor_active = true;
     }
     
-    rprintln!("[SENSOR] IoT System sensor monitoring started - 30s intervals");
+    rprintln!("[SENSOR] IoT System sensor monitoring started - {}s intervals", SENSOR_INTERVAL.load(Ordering::Relaxed));
     
     let mut reading_count = 0u32;
     let mut consecutive_errors = 0u32;
+    let mut publish_smoother = publish_smoother();
+    let retry = RetryConfig::default().sensor;
+    let recovery = RecoveryPolicy {
+        read_retries: retry.max_attempts.saturating_sub(1).min(u8::MAX as u32) as u8,
+        initial_backoff_ms: retry.initial_delay_ms,
+        max_backoff_ms: retry.max_delay_ms,
+        ..RecoveryPolicy::default()
+    };
+    // Plain retries only, until the failures reach SENSOR_REINIT_AFTER_ERRORS
+    let retry_only = RecoveryPolicy { reset_attempts: 0, ..recovery };
     
     loop {
-        // Time the sensor reading operation
+        if let Some(offsets) = SENSOR_OFFSETS_CHANGED.try_take() {
+            bme280.set_offsets(offsets.temperature, offsets.humidity, offsets.pressure);
+            // Publish the recalibrated values right away instead of easing into them
+            publish_smoother.reset();
+        }
+        
+        // Time the sensor reading operation; the driver retries, and resets once
+        // enough cycles in a row have failed
+        let reinit = consecutive_errors + 1 >= SENSOR_REINIT_AFTER_ERRORS;
+        let policy = if reinit { &recovery } else { &retry_only };
         let start_time = PerfInstant::now();
+        let (result, action) = bme280.read_with_recovery(policy).await;
+        
+        match action {
+            RecoveryAction::None => {}
+            RecoveryAction::Retried(n) => rprintln!("[SENSOR] Read succeeded after {} retries", n),
+            RecoveryAction::Reset(n) => {
+                rprintln!("[SENSOR] RECOVERY: Sensor recovered after {} soft reset(s)", n);
+                SYSTEM_STATE.lock().await.sensor_resets += n as u32;
+            }
+            RecoveryAction::Exhausted => {
+                SYSTEM_STATE.lock().await.sensor_resets += policy.reset_attempts as u32;
+            }
+        }
         
-        match bme280.read_measurements().await {
+        match result {
             Ok(measurements) => {
                 let duration = start_time.elapsed();
                 let duration_us = duration.as_micros() as u32;
@@ -393,61 +720,108 @@ async fn sensor_task(
                 performance_monitor.record_cycle_time(TimingCategory::SensorReading, duration).await;
                 
                 reading_count += 1;
-                consecutive_errors = 0; // Reset error counter
+                consecutive_errors = 0;
+                offline_announced = None;
                 
-                let reading = SensorReading {
-                    temperature: measurements.temperature,
-                    humidity: measurements.humidity,
-                    pressure: measurements.pressure,
-                    count: reading_count,
-                };
+                let reading = Measurements::new(
+                    measurements.temperature,
+                    measurements.pressure,
+                    measurements.humidity,
+                );
                 
-                rprintln!("[SENSOR] #{}: T={:.2}°C H={:.1}% P={:.1}hPa ({}μs)", 
+                log_debug!("[SENSOR] #{}: T={:.2}°C H={:.1}% P={:.1}hPa ({}μs)", 
                          reading_count, measurements.temperature, measurements.humidity, 
                          measurements.pressure, duration_us);
                 
-                rprintln!("[SENSOR] Signaling MQTT task with sensor data...");
-                SENSOR_DATA_SIGNAL.signal(reading);
-                rprintln!("[SENSOR] Signal sent successfully");
+                buffer_reading(Some(&publish_smoother.apply(&reading)), reading_count);
+                EVENT_BUS.publish(SystemEvent::SensorReading {
+                    measurements: reading,
+                    sequence: reading_count,
+                });
                 
-                // Record memory usage through performance monitor
-                let _ = performance_monitor.record_memory_usage(0, 0).await; // Will auto-detect heap and stack
+                // Record memory usage through performance monitor (heap from esp-alloc stats)
+                let heap_used = MemoryTracker::allocator_heap_info().map(|(used, _)| used).unwrap_or(0);
+                let _ = performance_monitor.record_memory_usage(heap_used, 0).await;
                 
                 {
                     let mut state = SYSTEM_STATE.lock().await;
                     state.reading_count = reading_count;
                     state.sensor_active = true;
                     state.last_sensor_time_us = duration_us;
+                    state.last_temperature = measurements.temperature;
+                    state.last_humidity = measurements.humidity;
+                    state.last_pressure = measurements.pressure;
+                }
+                
+                if let Some(logger) = DATA_LOGGER.lock().await.as_mut() {
+                    let record = LogRecord::stamped(
+                        &CLOCK,
+                        measurements.temperature,
+                        measurements.humidity,
+                        measurements.pressure,
+                        reading_count,
+                    );
+                    if let Err(e) = logger.append(&record) {
+                        rprintln!("[SENSOR] WARNING: Failed to log reading to flash: {:?}", e);
+                    }
                 }
             }
             Err(e) => {
-                consecutive_errors += 1;
-                rprintln!("[SENSOR] ERROR #{}: Failed to read sensor data: {:?}", consecutive_errors, e);
+                if reinit {
+                    rprintln!("[SENSOR] ERROR: Sensor unrecoverable after retries and soft resets: {:?}", e);
+                    // Count towards the next re-initialization from scratch
+                    consecutive_errors = 0;
+                } else {
+                    consecutive_errors += 1;
+                    rprintln!("[SENSOR] ERROR #{}: Failed to read sensor data after retries: {:?}", consecutive_errors, e);
+                }
                 
-                // Mark sensor as inactive after multiple errors
-                if consecutive_errors >= 3 {
+                // Recovery exhausted - mark sensor inactive until a later read succeeds
+                {
                     let mut state = SYSTEM_STATE.lock().await;
                     state.sensor_active = false;
-                    rprintln!("[SENSOR] CRITICAL: Sensor marked as inactive after {} consecutive errors", consecutive_errors);
-                }
-                
-                // Attempt sensor re-initialization after many errors
-                if consecutive_errors >= 10 {
-                    rprintln!("[SENSOR] RECOVERY: Attempting sensor re-initialization...");
-                    if bme280.init().await.is_ok() {
-                        rprintln!("[SENSOR] RECOVERY: Sensor re-initialized successfully");
-                        consecutive_errors = 0;
-                    }
+                    state.error_history.record(&e);
                 }
+                announce_sensor_offline(&mut offline_announced, reading_count);
             }
         }
         
-        Timer::after(Duration::from_secs(30)).await;
+        // Re-read the interval every cycle; a change cuts the current wait short.
+        // Self-test probes and register dumps are answered in between without
+        // shortening the wait. Intervals longer than the watchdog check are
+        // waited out in BEAT_STEP_SECS steps, beating after each.
+        let interval = Duration::from_secs(SENSOR_INTERVAL.load(Ordering::Relaxed) as u64);
+        let deadline = Instant::now() + interval;
+        loop {
+            HEARTBEAT.beat(SENSOR_TASK_ID);
+            let wake = deadline.min(Instant::now() + Duration::from_secs(BEAT_STEP_SECS));
+            match embassy_futures::select::select4(
+                Timer::at(wake),
+                SENSOR_INTERVAL_CHANGED.wait(),
+                SENSOR_PROBE_REQUEST.wait(),
+                SENSOR_DUMP_REQUEST.wait(),
+            ).await {
+                embassy_futures::select::Either4::Third(()) => {
+                    let id = bme280.chip_id().await.map(|id| (bme280.address(), id));
+                    SENSOR_PROBE_RESULT.signal(id);
+                }
+                embassy_futures::select::Either4::Fourth(address) => {
+                    let timeout = Duration::from_millis(I2C_TRANSACTION_TIMEOUT_MS);
+                    let dump = match address {
+                        Some(address) => bme280.dump_registers_at(address, timeout).await,
+                        None => bme280.dump_registers(timeout).await,
+                    };
+                    SENSOR_DUMP_RESULT.signal(dump);
+                }
+                embassy_futures::select::Either4::First(()) if wake < deadline => {}
+                _ => break,
+            }
+        }
     }
 }
 
 #[embassy_executor::task]
-async fn wifi_task(wifi_manager: &'static mut WiFiManager) {
+async fn wifi_task(wifi_manager: &'static WiFiManager) {
     rprintln!("[WIFI] Starting WiFi connection monitoring...");
     
     // Show initial connection information
@@ -458,62 +832,558 @@ async fn wifi_task(wifi_manager: &'static mut WiFiManager) {
         
         let mut state = SYSTEM_STATE.lock().await;
         state.wifi_connected = true;
+        EVENT_BUS.publish(SystemEvent::WiFiStateChanged { connected: true });
     } else {
         rprintln!("[WIFI] No initial WiFi connection");
         let mut state = SYSTEM_STATE.lock().await;
         state.wifi_connected = false;
+        EVENT_BUS.publish(SystemEvent::WiFiStateChanged { connected: false });
     }
     
+    let Some(mut connection_events) = wifi_manager.connection_events() else {
+        rprintln!("[WIFI] ERROR: No connection event receiver available");
+        return;
+    };
+    let mut last_disconnect_count = 0u32;
+    
     loop {
-        // Monitor connection status
-        if wifi_manager.is_connected() {
-            if let Some(ip) = wifi_manager.get_ip_address() {
-                // Only update state when status changes
-                let mut state = SYSTEM_STATE.lock().await;
-                if !state.wifi_connected {
-                    rprintln!("[WIFI] Connection restored - IP: {}", ip);
-                    state.wifi_connected = true;
+        // Wake on every connection transition, re-checking the DHCP lease once a minute
+        let event = match embassy_futures::select::select(
+            connection_events.changed(),
+            Timer::after(Duration::from_secs(60)),
+        ).await {
+            embassy_futures::select::Either::First(event) => event,
+            embassy_futures::select::Either::Second(_) => {
+                // Acquired/Lost also notify connection_events, handled on the next pass
+                if wifi_manager.refresh_connection_info() == ConnectionChange::Changed {
+                    if let Some(info) = wifi_manager.get_connection_info() {
+                        rprintln!("[WIFI] DHCP renewal changed IP address: {}", info.ip_address);
+                    }
                 }
+                continue;
             }
-        } else {
-            let mut state = SYSTEM_STATE.lock().await;
-            if state.wifi_connected {
-                rprintln!("[WIFI] WARNING: WiFi connection lost - will auto-reconnect");
-                state.wifi_connected = false;
+        };
+        let mut state = SYSTEM_STATE.lock().await;
+        
+        if event.connected {
+            if event.disconnect_count != last_disconnect_count && state.wifi_connected {
+                // Dropped and recovered before this task ran
+                rprintln!("[WIFI] WARNING: Brief WiFi drop ({}) - already reconnected",
+                         event.reason.map(disconnect_reason_str).unwrap_or("unknown"));
+                EVENT_BUS.publish(SystemEvent::WiFiStateChanged { connected: false });
+                EVENT_BUS.publish(SystemEvent::WiFiStateChanged { connected: true });
+            } else if !state.wifi_connected {
+                match wifi_manager.get_ip_address() {
+                    Some(ip) => rprintln!("[WIFI] Connection restored - IP: {}", ip),
+                    None => rprintln!("[WIFI] Connection restored - waiting for DHCP"),
+                }
+                state.wifi_connected = true;
+                EVENT_BUS.publish(SystemEvent::WiFiStateChanged { connected: true });
+            }
+        } else if state.wifi_connected {
+            match event.reason {
+                Some(reason) => rprintln!("[WIFI] WARNING: WiFi connection lost: {} (reason {}) - will auto-reconnect",
+                                         disconnect_reason_str(reason), reason),
+                None => rprintln!("[WIFI] WARNING: DHCP lease lost - waiting for a new address"),
             }
+            state.wifi_connected = false;
+            EVENT_BUS.publish(SystemEvent::WiFiStateChanged { connected: false });
         }
         
-        Timer::after(Duration::from_secs(30)).await;
+        last_disconnect_count = event.disconnect_count;
+    }
+}
+
+/// Reading or "sensor offline" placeholder (`None`) carried by an event
+fn sensor_event(event: SystemEvent) -> Option<(Option<Measurements>, u32)> {
+    match event {
+        SystemEvent::SensorReading { measurements, sequence } => Some((Some(measurements), sequence)),
+        SystemEvent::SensorOffline { sequence } => Some((None, sequence)),
+        _ => None,
+    }
+}
+
+/// Waits for the next sensor reading or placeholder, skipping other event kinds
+async fn next_sensor_reading(events: &mut EventSubscriber<'static>) -> (Option<Measurements>, u32) {
+    loop {
+        if let Some(reading) = sensor_event(events.next().await) {
+            return reading;
+        }
+    }
+}
+
+/// Adds a reading, or a placeholder when `None`, to the measurement buffer,
+/// dropping the oldest when full
+fn buffer_reading(reading: Option<&Measurements>, sequence: u32) {
+    // TODO: Remove 'app' field in production - use new_with_reading instead
+    let sensor_data = SensorData::from_measurements(
+        reading.map(|reading| (reading.temperature, reading.humidity, reading.pressure)),
+        sequence,
+        "main-app"  // Source identification for debugging
+    ).with_timestamp(reading.map_or_else(|| Instant::now().as_millis(), |reading| reading.timestamp_ms));
+    
+    let dropped = MEASUREMENT_BUFFER.lock(|buffer| buffer.borrow_mut().push(sensor_data));
+    if dropped {
+        rprintln!("[MQTT] WARNING: Measurement buffer full, dropping oldest reading");
+    }
+}
+
+/// Depth, capacity and drop count of the measurement buffer
+fn measurement_backlog() -> (usize, usize, u32) {
+    MEASUREMENT_BUFFER.lock(|buffer| {
+        let buffer = buffer.borrow();
+        (buffer.len(), buffer.capacity(), buffer.dropped())
+    })
+}
+
+/// Publishes buffered readings oldest-first over one connection
+///
+/// A single reading keeps the regular payload format; a backlog built up while
+/// offline is sent as one JSON array with the original timestamps. On failure
+/// the readings go back into the buffer ahead of any taken meanwhile.
+async fn flush_pending_readings(
+    mqtt_client: &MqttClient,
+    socket: &mut embassy_net::tcp::TcpSocket<'_>,
+) -> Result<u32, mqtt_embassy::MqttError> {
+    // The client stamps wall-clock time from CLOCK, including readings
+    // queued before the first sync
+    let pending = MEASUREMENT_BUFFER.lock(|buffer| buffer.borrow_mut().take_all());
+    let count = pending.len() as u32;
+    let result = match pending.as_slice() {
+        [] => return Ok(0),
+        [single] => mqtt_client.publish_sensor_data(socket, single).await,
+        batch => mqtt_client.publish_batch(socket, batch).await,
+    };
+    if let Err(e) = result {
+        MEASUREMENT_BUFFER.lock(|buffer| buffer.borrow_mut().restore(pending));
+        return Err(e);
+    }
+    Ok(count)
+}
+
+#[embassy_executor::task]
+async fn ntp_task(wifi_manager: &'static WiFiManager) {
+    rprintln!("[SNTP] Task started");
+    SntpClient::new(SntpConfig::default()).run(*wifi_manager.get_stack()).await
+}
+
+/// Checks that the gateway answers a ping before committing to a broker connect.
+/// Assumed reachable when DHCP gave no gateway, since there is nothing to probe.
+/// Advisory only: see `GATEWAY_PING_MISSES`.
+async fn gateway_reachable(wifi_manager: &WiFiManager) -> bool {
+    let Some(gateway) = wifi_manager.get_stack().config_v4().and_then(|config| config.gateway) else {
+        return true;
+    };
+    match wifi_manager.ping(gateway, Duration::from_millis(GATEWAY_PING_TIMEOUT_MS)).await {
+        Ok(_) => true,
+        Err(e) => {
+            rprintln!("[MQTT] Gateway {} did not answer: {}", gateway, e);
+            false
+        }
+    }
+}
+
+/// Writes a line to RTT and keeps it in `LOG_BUFFER`, see the `rprintln!` macro
+fn log_line(args: core::fmt::Arguments<'_>) {
+    rtt_target::rprintln!("{}", args);
+    // Lines logged before the time driver is up are stamped 0
+    let uptime_ms = BOOT_CLOCK.try_get().map_or(0, |clock| clock.uptime().as_millis());
+    // A line logged while the buffer is borrowed (e.g. formatting inside
+    // `recent_log`) only goes to RTT instead of panicking
+    LOG_BUFFER.lock(|buffer| {
+        if let Ok(mut buffer) = buffer.try_borrow_mut() {
+            buffer.push(uptime_ms, args);
+        }
+    });
+}
+
+/// Recent log lines of at least `min_level` for the console `log` command
+fn recent_log(min_level: LogLevel) -> alloc::string::String {
+    LOG_BUFFER.lock(|buffer| {
+        let buffer = buffer.borrow();
+        if JSON_OUTPUT.load(Ordering::Relaxed) {
+            let mut json = format!("\r\n{{\"dropped\":{},\"lines\":[", buffer.dropped());
+            for (i, line) in buffer.lines(min_level).enumerate() {
+                if i > 0 {
+                    json.push(',');
+                }
+                // Log text quotes device output, so let serde escape it
+                let text: heapless::String<512> = serde_json_core::to_string(line.text.as_str())
+                    .unwrap_or_else(|_| heapless::String::try_from("\"\"").unwrap_or_default());
+                json.push_str(&format!("{{\"ms\":{},\"level\":\"{}\",\"text\":{},\"truncated\":{}}}",
+                                       line.timestamp_ms, line.level, text, line.truncated));
+            }
+            json.push_str("]}\r\n\r\n");
+            return json;
+        }
+
+        let shown = buffer.lines(min_level).count();
+        let mut response = format!("\r\n=== Recent Log ({}: {} of {} lines) ===\r\n",
+                                   match min_level {
+                                       LogLevel::Info => "all",
+                                       LogLevel::Warning => "warnings and errors",
+                                       LogLevel::Error => "errors",
+                                   },
+                                   shown, buffer.len());
+        for line in buffer.lines(min_level) {
+            response.push_str(&format!("[{:>5}.{:03}s] {}{}\r\n",
+                                       line.timestamp_ms / 1000, line.timestamp_ms % 1000, line.text,
+                                       if line.truncated { "..." } else { "" }));
+        }
+        if shown == 0 {
+            response.push_str("No matching lines\r\n");
+        }
+        if buffer.dropped() > 0 {
+            response.push_str(&format!("({} older lines dropped)\r\n", buffer.dropped()));
+        }
+        response.push_str("\r\n");
+        response
+    })
+}
+
+/// Console note on when saved WiFi credentials take effect
+fn wifi_apply_note() -> &'static str {
+    if WIFI_LIVE_UPDATE.load(Ordering::Relaxed) {
+        "WiFi reconnects if SSID or password changed"
+    } else {
+        "Restart to apply WiFi changes"
+    }
+}
+
+/// Reconnects WiFi when the console saves, loads or rolls back new credentials
+///
+/// Changes that leave SSID and password alone (broker, interval, offsets) do
+/// not touch the link. Invalid credentials, such as the empty ones a factory
+/// reset leaves behind, are reported and the current network is kept.
+#[embassy_executor::task]
+async fn config_watch_task(wifi_manager: &'static WiFiManager, mut changes: ConfigSubscriber) {
+    let boot_config = wifi_manager.get_config();
+    let mut applied = (boot_config.ssid.clone(), boot_config.password.clone());
+    
+    loop {
+        let change = changes.next().await;
+        if change.name != WIFI_CONFIG_NAME {
+            continue;
+        }
+        let credentials = *WIFI_CREDENTIALS.lock().await;
+        if credentials.get_ssid() == applied.0.as_str() && credentials.get_password() == applied.1.as_str() {
+            continue;
+        }
+        match wifi_manager.update_credentials(credentials.get_ssid(), credentials.get_password()) {
+            Ok(()) => {
+                rprintln!("[WIFI] Applying new credentials for '{}' ({:?})", credentials.get_ssid(), change.kind);
+                applied = (
+                    heapless::String::try_from(credentials.get_ssid()).unwrap_or_default(),
+                    heapless::String::try_from(credentials.get_password()).unwrap_or_default(),
+                );
+            }
+            Err(e) => rprintln!("[WIFI] Keeping current network, new credentials rejected: {}", e),
+        }
+    }
+}
+
+/// Pings `args` (an IPv4 address) for the console `ping` command
+async fn ping_command(wifi_manager: Option<&WiFiManager>, args: &str) -> alloc::string::String {
+    let Ok(address) = args.trim().parse::<embassy_net::Ipv4Address>() else {
+        return "\r\nUsage: ping <ipv4-address>\r\n\r\n".to_string();
+    };
+    let Some(wifi_manager) = wifi_manager else {
+        return "\r\nPing unavailable: WiFi not initialized\r\n\r\n".to_string();
+    };
+    match wifi_manager.ping(address, Duration::from_millis(CONSOLE_PING_TIMEOUT_MS)).await {
+        Ok(rtt) => format!("\r\nReply from {}: time={}ms\r\n\r\n", address, rtt.as_millis()),
+        Err(e) => format!("\r\nNo reply from {}: {}\r\n\r\n", address, e),
+    }
+}
+
+/// Reports link, addressing and MQTT socket state for the console `netstat` command
+/// 
+/// Read-only: it looks at the stack configuration and the [`MQTT_SOCKET`]
+/// monitor, never at the socket itself, so it is safe while the MQTT task
+/// is mid-connect. Link up with config down means DHCP never completed,
+/// e.g. the stack runner is not being polled.
+async fn netstat_command(wifi_manager: Option<&WiFiManager>) -> alloc::string::String {
+    let stack = wifi_manager.map(|wifi_manager| wifi_manager.get_stack());
+    let link_up = stack.is_some_and(|stack| stack.is_link_up());
+    let config = stack.and_then(|stack| stack.config_v4());
+    let socket = MQTT_SOCKET.stats();
+    let broker = SYSTEM_STATE.lock().await.mqtt_broker;
+    
+    if JSON_OUTPUT.load(Ordering::Relaxed) {
+        let quoted = |value: Option<alloc::string::String>| value.map_or("null".to_string(), |v| format!("\"{}\"", v));
+        let dns = config.as_ref().map_or(alloc::vec::Vec::new(), |config| {
+            config.dns_servers.iter().map(|server| format!("\"{}\"", server)).collect()
+        });
+        return format!("\r\n{{\"link_up\":{},\"config_up\":{},\"ip\":{},\"gateway\":{},\"dns\":[{}],\"mqtt\":{{\"state\":\"{}\",\"broker\":{},\"bytes_out\":{},\"bytes_in\":{}}}}}\r\n\r\n",
+                       link_up, config.is_some(),
+                       quoted(config.as_ref().map(|config| format!("{}", config.address))),
+                       quoted(config.as_ref().and_then(|config| config.gateway).map(|gateway| format!("{}", gateway))),
+                       dns.join(","), socket.state,
+                       quoted(broker.map(|broker| format!("{}", broker))),
+                       socket.bytes_sent, socket.bytes_received);
+    }
+    
+    let network = match (stack, &config) {
+        (None, _) => "WiFi not initialized\r\n".to_string(),
+        (Some(_), None) => format!("Link: {}\r\nConfig: down (no DHCP lease)\r\n", if link_up { "up" } else { "down" }),
+        (Some(_), Some(config)) => {
+            let gateway = config.gateway.map_or("none".to_string(), |gateway| format!("{}", gateway));
+            let dns: alloc::vec::Vec<_> = config.dns_servers.iter().map(|server| format!("{}", server)).collect();
+            format!("Link: {}\r\nConfig: up\r\nIP: {}\r\nGateway: {}\r\nDNS: {}\r\n",
+                    if link_up { "up" } else { "down" }, config.address, gateway,
+                    if dns.is_empty() { "none".to_string() } else { dns.join(", ") })
+        }
+    };
+    format!("\r\n=== Network Status ===\r\n{}\
+            MQTT socket: {} (broker {})\r\n\
+            MQTT traffic: {} bytes out, {} bytes in\r\n\
+            \r\n",
+            network, socket.state, broker.map_or("none".to_string(), |broker| format!("{}", broker)),
+            socket.bytes_sent, socket.bytes_received)
+}
+
+/// Shows or resets the connection statistics for the console `wifi stats` command
+fn wifi_stats_command(wifi_manager: Option<&WiFiManager>, args: &str) -> alloc::string::String {
+    let Some(wifi_manager) = wifi_manager else {
+        return "\r\nWiFi statistics unavailable: WiFi not initialized\r\n\r\n".to_string();
+    };
+    match args.trim() {
+        "" => {}
+        "reset" => {
+            wifi_manager.reset_stats();
+            return "\r\nWiFi statistics reset\r\n\r\n".to_string();
+        }
+        _ => return "\r\nUsage: wifi stats [reset]\r\n\r\n".to_string(),
+    }
+    let stats = wifi_manager.stats();
+    if JSON_OUTPUT.load(Ordering::Relaxed) {
+        let reason = match stats.last_disconnect_reason {
+            Some(reason) => format!("{}", reason),
+            None => "null".to_string(),
+        };
+        return format!("\r\n{{\"connects\":{},\"disconnects\":{},\"reconnects\":{},\"connected_secs\":{},\"tracked_secs\":{},\"connected_percent\":{},\"last_disconnect_reason\":{}}}\r\n\r\n",
+                       stats.connects, stats.disconnects, stats.reconnects, stats.connected_time.as_secs(),
+                       stats.tracked_time.as_secs(), stats.connected_percent(), reason);
+    }
+    let last_drop = match stats.last_disconnect_reason {
+        Some(reason) => format!("{} (reason {})", disconnect_reason_str(reason), reason),
+        None => "none".to_string(),
+    };
+    format!("\r\n=== WiFi Statistics ===\r\n\
+            Reconnects: {}, connected {}% of uptime\r\n\
+            Connects: {}\r\n\
+            Disconnects: {}\r\n\
+            Connected: {}s of {}s\r\n\
+            Last disconnect: {}\r\n\
+            \r\n",
+            stats.reconnects, stats.connected_percent(), stats.connects, stats.disconnects,
+            stats.connected_time.as_secs(), stats.tracked_time.as_secs(), last_drop)
+}
+
+/// Asks the sensor task for a register dump and renders it as a hex table
+async fn i2c_probe(address: Option<u8>) -> alloc::string::String {
+    SENSOR_DUMP_RESULT.reset();
+    SENSOR_DUMP_REQUEST.signal(address);
+    match with_timeout(Duration::from_millis(I2C_PROBE_WAIT_MS), SENSOR_DUMP_RESULT.wait()).await {
+        Ok(Ok(dump)) => render_register_dump(&dump),
+        Ok(Err(e)) => format!("\r\nI2C probe failed: {}\r\n\r\n", e),
+        Err(_) => {
+            SENSOR_DUMP_REQUEST.reset();
+            "\r\nI2C probe timed out (sensor task busy or not started)\r\n\r\n".to_string()
+        }
+    }
+}
+
+/// Labeled hex table of a BME280 register dump, eight registers per row
+fn render_register_dump(dump: &RegisterDump) -> alloc::string::String {
+    use core::fmt::Write;
+    
+    let chip = match dump.chip_id {
+        0x60 => "BME280",
+        0x58 => "BMP280",
+        _ => "unknown",
+    };
+    let mut out = format!("\r\n=== I2C Registers at 0x{:02X} ({}) ===\r\n\
+                          Reg   Name       +0 +1 +2 +3 +4 +5 +6 +7\r\n", dump.address, chip);
+    for (name, register, values) in dump.blocks() {
+        for (row, chunk) in values.chunks(8).enumerate() {
+            let label = if row == 0 { name } else { "" };
+            let _ = write!(out, "0x{:02X}  {:<9} ", register as usize + row * 8, label);
+            for value in chunk {
+                let _ = write!(out, " {:02X}", value);
+            }
+            out.push_str("\r\n");
+        }
+    }
+    let (osrs_t, osrs_p, osrs_h) = dump.oversampling();
+    let _ = write!(out, "Mode: {}, oversampling T x{} P x{} H x{} (x0 = skipped), filter {}\r\n\
+                        Status: {}{}\r\n\r\n",
+                   dump.mode_name(), osrs_t, osrs_p, osrs_h,
+                   if dump.filter_coefficient() == 0 { "off".to_string() } else { format!("x{}", dump.filter_coefficient()) },
+                   if dump.status & 0x08 != 0 { "measuring" } else { "idle" },
+                   if dump.status & 0x01 != 0 { ", NVM copy in progress" } else { "" });
+    out
+}
+
+/// Asks the sensor task to read the BME280 chip ID; the task owns the I2C bus
+async fn selftest_sensor() -> StepOutcome {
+    SENSOR_PROBE_RESULT.reset();
+    SENSOR_PROBE_REQUEST.signal(());
+    match SENSOR_PROBE_RESULT.wait().await {
+        Ok((address, 0x60)) => Ok(detail(format_args!("BME280 at 0x{:02X}, chip ID 0x60", address))),
+        Ok((address, id)) => Err(detail(format_args!("unexpected chip ID 0x{:02X} at 0x{:02X}", id, address))),
+        Err(e) => Err(detail(format_args!("{}", e))),
+    }
+}
+
+async fn selftest_wifi(wifi_manager: Option<&WiFiManager>) -> StepOutcome {
+    let Some(wifi_manager) = wifi_manager else {
+        return Err(detail(format_args!("WiFi not initialized")));
+    };
+    match (wifi_manager.is_connected(), wifi_manager.get_ip_address()) {
+        (true, Some(ip)) => Ok(detail(format_args!("link up, IP {}", ip))),
+        (true, None) => Err(detail(format_args!("link up, no DHCP lease"))),
+        (false, _) => Err(detail(format_args!("not connected"))),
+    }
+}
+
+/// Opens and closes a TCP connection to the broker, no MQTT handshake
+async fn selftest_broker(wifi_manager: Option<&WiFiManager>) -> StepOutcome {
+    let Some(wifi_manager) = wifi_manager else {
+        return Err(detail(format_args!("WiFi not initialized")));
+    };
+    // The broker in use, or the preferred one before MQTT has connected
+    let Some(broker) = SYSTEM_STATE.lock().await.mqtt_broker.or(base_mqtt_config().primary_broker()) else {
+        return Err(detail(format_args!("No broker configured")));
+    };
+    let mut rx_buffer = [0u8; 256];
+    let mut tx_buffer = [0u8; 256];
+    let mut socket = embassy_net::tcp::TcpSocket::new(*wifi_manager.get_stack(), &mut rx_buffer, &mut tx_buffer);
+    let result = socket.connect((broker.ip, broker.port)).await;
+    socket.abort();
+    match result {
+        Ok(()) => Ok(detail(format_args!("TCP connect to {}", broker))),
+        Err(e) => Err(detail(format_args!("{} {:?}", broker, e))),
+    }
+}
+
+/// Writes a stamped pattern to the scratch sector and reads it back
+async fn selftest_storage() -> StepOutcome {
+    let scratch = flash_region(REGION_SELFTEST).offset;
+    let mut storage = FlashStorage::new();
+    let mut pattern = [0u8; 16];
+    pattern[..8].copy_from_slice(b"SELFTEST");
+    pattern[8..].copy_from_slice(&Instant::now().as_ticks().to_le_bytes());
+    
+    if let Err(e) = storage.write(scratch, &pattern) {
+        return Err(detail(format_args!("write at 0x{:X} failed: {:?}", scratch, e)));
+    }
+    let mut readback = [0u8; 16];
+    if let Err(e) = storage.read(scratch, &mut readback) {
+        return Err(detail(format_args!("read at 0x{:X} failed: {:?}", scratch, e)));
+    }
+    if readback != pattern {
+        return Err(detail(format_args!("readback mismatch at 0x{:X}", scratch)));
+    }
+    Ok(detail(format_args!("16 bytes verified at 0x{:X}", scratch)))
+}
+
+/// Asks the status LED task to blink; only it holds the platform
+async fn selftest_led() -> StepOutcome {
+    LED_TEST_RESULT.reset();
+    LED_TEST_REQUEST.signal(());
+    match LED_TEST_RESULT.wait().await {
+        Ok(()) => Ok(detail(format_args!("blinked 3 times"))),
+        Err(e) => Err(detail(format_args!("{}", e))),
+    }
+}
+
+/// Checks every subsystem in turn, each step bounded by its own timeout
+async fn run_self_test(wifi_manager: Option<&WiFiManager>) -> SelfTestReport {
+    rprintln!("[SELFTEST] Running self-test...");
+    let mut report = SelfTestReport::new();
+    report.run("sensor", Duration::from_millis(SELFTEST_SENSOR_TIMEOUT_MS), selftest_sensor()).await;
+    report.run("wifi", Duration::from_millis(SELFTEST_WIFI_TIMEOUT_MS), selftest_wifi(wifi_manager)).await;
+    report.run("broker", Duration::from_millis(SELFTEST_BROKER_TIMEOUT_MS), selftest_broker(wifi_manager)).await;
+    report.run("storage", Duration::from_millis(SELFTEST_STORAGE_TIMEOUT_MS), selftest_storage()).await;
+    report.run("led", Duration::from_millis(SELFTEST_LED_TIMEOUT_MS), selftest_led()).await;
+    rprintln!("[SELFTEST] {}/{} subsystems passed", report.steps().len() - report.failures(), report.steps().len());
+    report
+}
+
+/// Runs the self-test once after boot when built with `SELFTEST_AT_BOOT=true`
+#[embassy_executor::task]
+async fn selftest_task(wifi_manager: Option<&'static WiFiManager>) {
+    Timer::after(Duration::from_secs(SELFTEST_BOOT_DELAY_SECS)).await;
+    let report = run_self_test(wifi_manager).await;
+    for line in report.render_text().split("\r\n").filter(|line| !line.is_empty()) {
+        rprintln!("[SELFTEST] {}", line);
     }
 }
 
 #[embassy_executor::task]
 async fn mqtt_task(wifi_manager: &'static WiFiManager) {
     rprintln!("[MQTT] Task started - entry point reached");
+    
+    // Subscribe before waiting for WiFi so early readings are queued
+    let mut events = match EVENT_BUS.subscribe() {
+        Ok(events) => events,
+        Err(e) => {
+            rprintln!("[MQTT] ERROR: Cannot subscribe to event bus: {:?}", e);
+            return;
+        }
+    };
+    HEARTBEAT.expect(MQTT_TASK_ID);
     rprintln!("[MQTT] Initializing MQTT client...");
     
-    // Wait for WiFi connection before starting MQTT
-    loop {
-        if wifi_manager.is_connected() {
-            rprintln!("[MQTT] WiFi connected, starting MQTT client");
-            break;
+    // Wait for WiFi connection before starting MQTT, woken by connection
+    // events instead of polling; the timeout keeps the heartbeat going
+    {
+        let mut connection_events = wifi_manager.connection_events();
+        while !wifi_manager.is_connected() {
+            HEARTBEAT.beat(MQTT_TASK_ID);
+            rprintln!("[MQTT] Waiting for WiFi connection...");
+            let ready = async {
+                match connection_events.as_mut() {
+                    Some(events) => {
+                        events.get_and(|event| event.connected).await;
+                    }
+                    None => Timer::after(Duration::from_secs(5)).await,
+                }
+                // Association comes first, the address once DHCP completes
+                wifi_manager.wait_for_ready().await;
+            };
+            embassy_futures::select::select(ready, Timer::after(Duration::from_secs(BEAT_STEP_SECS))).await;
         }
-        rprintln!("[MQTT] Waiting for WiFi connection...");
-        Timer::after(Duration::from_secs(5)).await;
+        rprintln!("[MQTT] WiFi connected, starting MQTT client");
     }
     
     // Get network stack from WiFi manager
     let stack = wifi_manager.get_stack();
     
-    // IoT System MQTT configuration from environment variables
-    let mqtt_config = MqttConfig::default();
+    // IoT System MQTT configuration from environment variables, with a
+    // client ID derived from the MAC so boards do not evict each other
+    let mut mqtt_config = base_mqtt_config();
+    if let Some(info) = *DEVICE_INFO.lock().await {
+        mqtt_config = match mqtt_config.clone().with_device_id(info.mac) {
+            Ok(config) => config,
+            Err(e) => {
+                rprintln!("[MQTT] WARNING: {}, using configured client ID", e);
+                mqtt_config
+            }
+        };
+    }
     
-    rprintln!("[MQTT] Configured for broker {}:{}", mqtt_config.broker_ip, mqtt_config.broker_port);
-    rprintln!("[MQTT] Client ID: {}", mqtt_config.client_id);
+    for (i, broker) in mqtt_config.brokers.iter().enumerate() {
+        rprintln!("[MQTT] Configured for broker {}{}", broker, if i == 0 { "" } else { " (failover)" });
+    }
+    rprintln!("[MQTT] Client ID: {}", mqtt_config.client_id());
     rprintln!("[MQTT] Topic prefix: {}", mqtt_config.topic_prefix);
     
     // Create MQTT client
-    let mqtt_client = MqttClient::new(mqtt_config.clone());
+    let mqtt_client = MqttClient::new(mqtt_config.clone())
+        .with_timestamp_provider(&CLOCK)
+        .with_socket_monitor(&MQTT_SOCKET);
     
     // Create persistent buffers for socket operations
     let mut rx_buffer = [0u8; 1024];
@@ -522,197 +1392,665 @@ async fn mqtt_task(wifi_manager: &'static WiFiManager) {
     let mut heartbeat_counter = 0u32;
     let mut published_readings = 0u32;
     
+    // Gateway pings that went unanswered in a row, up to GATEWAY_PING_MISSES
+    let mut gateway_misses = 0u32;
+    
+    // Address last announced in a device status; re-announced when DHCP changes it
+    let mut announced_ip = wifi_manager.get_ip_address();
+    
+    // Retained device info goes out once per boot
+    let mut device_info_published = false;
+    
+    // The first status after boot says why the device restarted
+    let mut last_reset_reported = false;
+    
+    // Home Assistant discovery goes out once per boot
+    #[cfg(feature = "homeassistant")]
+    let mut discovery_published = false;
+    
     rprintln!("[MQTT] Starting data publishing loop");
     
     loop {
+        HEARTBEAT.beat(MQTT_TASK_ID);
+        
         // Wait for next publishing cycle (10 seconds)
         let timeout_future = Timer::after(Duration::from_secs(10));
-        let sensor_future = SENSOR_DATA_SIGNAL.wait();
+        let sensor_future = next_sensor_reading(&mut events);
+        let shutdown_future = SHUTDOWN_SIGNAL.wait();
         
-        // Use select to wait for either timeout or sensor data
-        let select_result = embassy_futures::select::select(timeout_future, sensor_future).await;
+        // Use select to wait for timeout, sensor data or a shutdown request
+        let select_result = embassy_futures::select::select3(timeout_future, sensor_future, shutdown_future).await;
         
         // Check if we got sensor data
         match select_result {
-            embassy_futures::select::Either::Second(reading) => {
-                rprintln!("[MQTT] Got sensor data from signal: T={:.2}°C, H={:.1}%, P={:.1}hPa", 
-                         reading.temperature, reading.humidity, reading.pressure);
-                published_readings += 1;
-            
-                // Create sensor data for MQTT publishing with app identification
-                // TODO: Remove 'app' field in production - use new_with_reading instead
-                let sensor_data = SensorData::new_with_app(
-                    reading.temperature,
-                    reading.humidity,
-                    reading.pressure,
-                    reading.count,
-                    "main-app"  // Source identification for debugging
-                );
+            embassy_futures::select::Either3::Third(()) => {
+                rprintln!("[MQTT] Shutdown requested - flushing and going offline");
                 
-                // Attempt MQTT connection and publishing
-                match mqtt_client.connect(stack, &mut rx_buffer, &mut tx_buffer).await {
-                    Ok(mut socket) => {
-                        // Publish sensor data
-                        match mqtt_client.publish_sensor_data(&mut socket, &sensor_data).await {
-                            Ok(_) => {
-                                rprintln!("[MQTT] Published reading #{}: T={:.2}°C H={:.1}% P={:.1}hPa",
-                                         published_readings, reading.temperature, reading.humidity, reading.pressure);
-                                
-                                let mut state = SYSTEM_STATE.lock().await;
-                                state.mqtt_connected = true;
+                if let Ok(mut socket) = mqtt_client.connect(stack, &mut rx_buffer, &mut tx_buffer).await {
+                    if let Err(e) = flush_pending_readings(&mqtt_client, &mut socket).await {
+                        rprintln!("[MQTT] ERROR: Failed to flush sensor data: {:?}", e);
+                        record_error(IoTError::from(e).with_context("Flushing sensor data")).await;
+                    }
+                    
+                    let free_heap = MemoryTracker::allocator_heap_info()
+                        .map(|(_, free)| free as u32)
+                        .unwrap_or(0);
+                    let error_history = SYSTEM_STATE.lock().await.error_history;
+                    let device_status = DeviceStatus::from_boot(
+                        boot_clock().boot_instant(),
+                        "offline",
+                        free_heap,
+                        -42,
+                        "main-app"
+                    ).with_errors(&error_history);
+                    match mqtt_client.publish_device_status(&mut socket, &device_status).await {
+                        Ok(_) => rprintln!("[MQTT] Published offline status"),
+                        Err(e) => rprintln!("[MQTT] ERROR: Failed to publish offline status: {:?}", e),
+                    }
+                    mqtt_client.disconnect(socket);
+                }
+                
+                {
+                    let mut state = SYSTEM_STATE.lock().await;
+                    state.mqtt_connected = false;
+                }
+                SHUTDOWN_COMPLETE_SIGNAL.signal(());
+                return;
+            }
+            embassy_futures::select::Either3::Second((reading, sequence)) => {
+                match &reading {
+                    Some(reading) => log_trace!("[MQTT] Got sensor reading #{} from event bus: T={:.2}°C, H={:.1}%, P={:.1}hPa", 
+                                              sequence, reading.temperature, reading.humidity, reading.pressure),
+                    None => rprintln!("[MQTT] Sensor offline - publishing placeholder"),
+                }
+                
+                // Skip the broker connect, and its long TCP timeout, while the gateway is
+                // unreachable, but only for a few cycles in case it just drops ICMP
+                if measurement_backlog().0 == 0 {
+                    // Already sent with the previous flush
+                } else if gateway_misses < GATEWAY_PING_MISSES && !gateway_reachable(wifi_manager).await {
+                    gateway_misses += 1;
+                    rprintln!("[MQTT] Gateway unreachable ({}/{}) - keeping {} reading(s) buffered",
+                             gateway_misses, GATEWAY_PING_MISSES, measurement_backlog().0);
+                    if gateway_misses == GATEWAY_PING_MISSES {
+                        rprintln!("[MQTT] Gateway may be dropping ICMP - trying the broker without pinging");
+                    }
+                    let mut state = SYSTEM_STATE.lock().await;
+                    state.mqtt_connected = false;
+                    state.error_history.record(&from_wifi_error("Connection", "Gateway unreachable"));
+                } else {
+                    if gateway_misses < GATEWAY_PING_MISSES {
+                        // The gateway answered
+                        gateway_misses = 0;
+                    }
+                    // Attempt MQTT connection and publishing (batched if readings piled up)
+                    match mqtt_client.connect(stack, &mut rx_buffer, &mut tx_buffer).await {
+                        Ok(mut socket) => {
+                            if !device_info_published {
+                                if let Some(info) = *DEVICE_INFO.lock().await {
+                                    match mqtt_client.publish_device_info(&mut socket, &info).await {
+                                        Ok(()) => device_info_published = true,
+                                        Err(e) => rprintln!("[MQTT] ERROR: Failed to publish device info: {:?}", e),
+                                    }
+                                }
                             }
-                            Err(e) => {
-                                rprintln!("[MQTT] ERROR: Failed to publish sensor data: {:?}", e);
-                                let mut state = SYSTEM_STATE.lock().await;
-                                state.mqtt_connected = false;
+                            
+                            #[cfg(feature = "homeassistant")]
+                            if !discovery_published {
+                                match mqtt_client.publish_discovery(&mut socket, mqtt_config.client_id()).await {
+                                    Ok(()) => discovery_published = true,
+                                    Err(e) => rprintln!("[MQTT] ERROR: Failed to publish discovery: {:?}", e),
+                                }
+                            }
+                        
+                            // Publish sensor data
+                            match flush_pending_readings(&mqtt_client, &mut socket).await {
+                                Ok(count) => {
+                                    published_readings += count;
+                                    match &reading {
+                                        Some(reading) => {
+                                            log_debug!("[MQTT] Published {} reading(s), latest #{}: T={:.2}°C H={:.1}% P={:.1}hPa",
+                                                     count, sequence, reading.temperature, reading.humidity, reading.pressure);
+                                            EVENT_BUS.publish(SystemEvent::MqttPublished { sequence });
+                                        }
+                                        None => log_debug!("[MQTT] Published {} message(s), latest a sensor-offline placeholder", count),
+                                    }
+                                
+                                    let mut state = SYSTEM_STATE.lock().await;
+                                    state.mqtt_connected = true;
+                                    state.mqtt_broker = mqtt_client.active_broker();
+                                }
+                                Err(e) => {
+                                    rprintln!("[MQTT] ERROR: Failed to publish sensor data: {:?}", e);
+                                    let mut state = SYSTEM_STATE.lock().await;
+                                    state.mqtt_connected = false;
+                                    state.error_history.record(&IoTError::from(e));
+                                }
                             }
+                            mqtt_client.disconnect(socket);
+                        }
+                        Err(e) => {
+                            rprintln!("[MQTT] ERROR: Failed to connect to broker: {:?}", e);
+                            // Ping again before the next attempt
+                            gateway_misses = 0;
+                            let mut state = SYSTEM_STATE.lock().await;
+                            state.mqtt_connected = false;
+                            state.error_history.record(&IoTError::from(e));
                         }
-                    }
-                    Err(e) => {
-                        rprintln!("[MQTT] ERROR: Failed to connect to broker: {:?}", e);
-                        let mut state = SYSTEM_STATE.lock().await;
-                        state.mqtt_connected = false;
                     }
                 }
             }
-            embassy_futures::select::Either::First(_) => {
+            embassy_futures::select::Either3::First(_) => {
                 // Timeout - continue with periodic tasks
             }
         }
         
         heartbeat_counter += 1;
+        HEARTBEAT.beat(MQTT_TASK_ID);
         
         // Heartbeat every 6 cycles (6 * 10s = 1 minute)
         if heartbeat_counter % 6 == 0 {
             if let Ok(mut socket) = mqtt_client.connect(stack, &mut rx_buffer, &mut tx_buffer).await {
                 match mqtt_client.publish_heartbeat(&mut socket).await {
                     Ok(_) => {
-                        rprintln!("[MQTT] Published heartbeat #{}", heartbeat_counter / 6);
+                        log_trace!("[MQTT] Published heartbeat #{}", heartbeat_counter / 6);
                     }
                     Err(e) => {
                         rprintln!("[MQTT] ERROR: Failed to publish heartbeat: {:?}", e);
                     }
                 }
+                mqtt_client.disconnect(socket);
             }
         }
         
+        // Re-announce right away when the device got a new address
+        let current_ip = wifi_manager.get_ip_address();
+        let address_changed = current_ip.is_some() && current_ip != announced_ip;
+        if address_changed {
+            rprintln!("[MQTT] Device address changed: {:?} -> {:?} - re-announcing status",
+                     announced_ip, current_ip);
+            announced_ip = current_ip;
+        }
+        
         // Status report every 12 cycles (12 * 10s = 2 minutes)
-        if heartbeat_counter % 12 == 0 {
+        if heartbeat_counter % 12 == 0 || address_changed {
             let state = SYSTEM_STATE.lock().await;
-            // TODO: Remove 'app' field in production - use new instead
-            let device_status = DeviceStatus::new_with_app(
+            // Free heap from esp-alloc stats, 0 when unavailable
+            let free_heap = MemoryTracker::allocator_heap_info()
+                .map(|(_, free)| free as u32)
+                .unwrap_or(0);
+            // TODO: Remove 'app' field in production
+            let device_status = DeviceStatus::from_boot(
+                boot_clock().boot_instant(),
                 "online",
-                (heartbeat_counter * 10) as u32, // Uptime in seconds (10s per cycle)
-                32768, // Free heap estimation
+                free_heap,
                 -42,   // WiFi RSSI estimation
                 "main-app"  // Source identification for debugging
-            );
+            ).with_errors(&state.error_history);
+            let device_status = if last_reset_reported {
+                device_status
+            } else {
+                device_status.with_last_reset(&last_reset_description().await)
+            };
+            let device_status = match state.chip_temperature {
+                Some(celsius) if PUBLISH_CHIP_TEMPERATURE => device_status.with_chip_temperature(celsius),
+                _ => device_status,
+            };
             
             if let Ok(mut socket) = mqtt_client.connect(stack, &mut rx_buffer, &mut tx_buffer).await {
                 match mqtt_client.publish_device_status(&mut socket, &device_status).await {
                     Ok(_) => {
-                        rprintln!("[MQTT] Published status: sensor_active={}, readings={}, published={}",
-                                 state.sensor_active, state.reading_count, published_readings);
+                        last_reset_reported = true;
+                        let (buffered, _, dropped) = measurement_backlog();
+                        log_debug!("[MQTT] Published status: sensor_active={}, readings={}, published={}, buffered={}, dropped={}",
+                                 state.sensor_active, state.reading_count, published_readings, buffered, dropped);
                     }
                     Err(e) => {
                         rprintln!("[MQTT] ERROR: Failed to publish status: {:?}", e);
                     }
                 }
+                mqtt_client.disconnect(socket);
             }
         }
     }
 }
 
-#[embassy_executor::task]
-async fn console_task(mut usb_tx: esp_hal::usb_serial_jtag::UsbSerialJtagTx<'static, Async>, 
-                     mut usb_rx: esp_hal::usb_serial_jtag::UsbSerialJtagRx<'static, Async>) {
-    rprintln!("[MAIN-APP] Starting integrated console task");
+#[embassy_executor::task]
+async fn console_task(console_tx: ConsoleTx, console_rx: ConsoleRx, wifi_manager: Option<&'static WiFiManager>) {
+    rprintln!("[MAIN-APP] Starting integrated console task");
+    
+    {
+        let mut state = SYSTEM_STATE.lock().await;
+        state.console_active = true;
+    }
+    
+    let mut console = ConsoleContainerAdapter::with_extension(console_tx, console_rx, AppConsole { wifi_manager });
+    
+    // Send operational welcome banner
+    let banner = "\r\n\r\n+==========================================================+\r\n\
+                  |          ESP32-C3 IoT System IoT System v1.0            |\r\n\
+                  |        BME280 Environmental Monitoring Station          |\r\n\
+                  +==========================================================+\r\n\
+                  System Status: IoT System Ready\r\n\
+                  Sensor: BME280 Temperature/Humidity/Pressure\r\n\
+                  Connectivity: WiFi + MQTT\r\n\
+                  \r\nType 'help' for available commands\r\n";
+    let _ = console.write_line(banner).await;
+    
+    loop {
+        let _ = console.show_prompt().await;
+        match console.read_line().await {
+            Ok(Some(line)) => {
+                if let Err(e) = console.execute_line(&line).await {
+                    rprintln!("[CONSOLE] ERROR: Failed to write response: {}", e);
+                }
+            }
+            Ok(None) => {}
+            Err(_) => Timer::after(Duration::from_millis(100)).await,
+        }
+    }
+}
+
+/// Commands listed by `help` after the shared console commands
+const APP_HELP: &str = "Device commands:\r\n\
+                        sensor           - Show latest sensor reading\r\n\
+                        watch [secs]     - Stream sensor readings until a key is pressed\r\n\
+                        readings         - Show reading count\r\n\
+                        interval [secs]  - Show or set the sensor interval (saved)\r\n\
+                        calibrate        - Show sensor calibration offsets\r\n\
+                        calibrate temp|hum|press <offset> - Set an offset (saved)\r\n\
+                        log [errors|warnings] - Show recent log lines, optionally filtered\r\n\
+                        log clear        - Forget the recent log lines\r\n\
+                        log data         - Show flash data log status\r\n\
+                        log export       - Dump logged readings as CSV\r\n\
+                        loglevel [level] - Show or set verbosity (none|error|warn|info|debug|trace)\r\n\
+                        perf             - Show performance metrics\r\n\
+                        memory           - Show memory usage\r\n\
+                        alerts           - Show performance alerts\r\n\
+                        errors           - Show recent error codes\r\n\
+                        errors clear     - Clear the error history\r\n\
+                        wifi broker <ip> - Set MQTT broker IP\r\n\
+                        wifi port <port> - Set MQTT broker port\r\n\
+                        wifi stats [reset] - Show or reset connection statistics\r\n\
+                        ping <ip>        - Check reachability of a host\r\n\
+                        netstat          - Show link, addresses and MQTT socket state\r\n\
+                        i2c probe [addr] - Dump BME280 registers (default: sensor address)\r\n\
+                        selftest         - Check sensor, WiFi, broker, flash and LED\r\n\
+                        ota [url]        - Show firmware slots or update from http[s]://<ip>/<file>\r\n\
+                        uptime           - Show system uptime\r\n\
+                        time             - Show wall-clock time and NTP sync status\r\n\
+                        factory reset    - Erase saved configuration and data log\r\n\
+                        \r\n";
+
+/// Device commands of the console, served ahead of the shared command set
+///
+/// `help`, `json on|off`, `clear`, `login` and the session commands come from
+/// `serial_console_embassy`; `status`, `info`, `save`, `load`, `restart` and
+/// the `wifi` settings are answered here because they act on this firmware's
+/// flash configuration and state.
+struct AppConsole {
+    wifi_manager: Option<&'static WiFiManager>,
+}
+
+impl ConsoleExtension<ConsoleTx, ConsoleRx> for AppConsole {
+    async fn execute<'a>(
+        &'a mut self,
+        line: &'a str,
+        mode: OutputMode,
+        console_tx: &'a mut ConsoleTx,
+        console_rx: &'a mut ConsoleRx,
+    ) -> bool {
+        JSON_OUTPUT.store(mode == OutputMode::Json, Ordering::Relaxed);
+        let wifi_manager = self.wifi_manager;
+        let cmd = line.trim();
+        
+        // Streaming needs the serial port itself
+        let mut words = cmd.split_whitespace();
+        match words.next() {
+            Some("watch") => {
+                let interval = match words.next().map(str::parse::<u64>) {
+                    None => Some(SENSOR_INTERVAL.load(Ordering::Relaxed) as u64),
+                    Some(Ok(secs)) if words.next().is_none() => Some(secs),
+                    _ => None,
+                };
+                match interval {
+                    Some(secs) => watch_sensor(console_tx, console_rx, secs).await,
+                    None => {
+                        let _ = embedded_io_async::Write::write(console_tx, b"\r\nUsage: watch [seconds]\r\n\r\n").await;
+                    }
+                }
+                let _ = embedded_io_async::Write::flush(console_tx).await;
+                return true;
+            }
+            Some("ota") => {
+                // Downloads over the network stack and streams progress, like `watch`
+                let url = cmd.strip_prefix("ota").unwrap_or("").trim();
+                ota_command(console_tx, wifi_manager, url).await;
+                let _ = embedded_io_async::Write::flush(console_tx).await;
+                return true;
+            }
+            _ if cmd == "log export" => {
+                export_data_log(console_tx).await;
+                let _ = embedded_io_async::Write::flush(console_tx).await;
+                return true;
+            }
+            _ => {}
+        }
+        
+        let response = if let Some(args) = cmd.strip_prefix("ping") {
+            ping_command(wifi_manager, args).await
+        } else if cmd == "netstat" {
+            netstat_command(wifi_manager).await
+        } else if let Some(args) = cmd.strip_prefix("wifi stats") {
+            wifi_stats_command(wifi_manager, args)
+        } else if cmd == "selftest" {
+            // Talks to the network stack and other tasks, like `ping`
+            let report = run_self_test(wifi_manager).await;
+            if mode == OutputMode::Json {
+                format!("\r\n{}\r\n", report.to_json())
+            } else {
+                format!("\r\n{}\r\n", report.render_text())
+            }
+        } else {
+            match app_command(cmd).await {
+                Some(response) => response,
+                None => return false,
+            }
+        };
+        
+        let _ = embedded_io_async::Write::write(console_tx, response.as_bytes()).await;
+        let _ = embedded_io_async::Write::flush(console_tx).await;
+        true
+    }
+    
+    fn help(&self) -> &'static str {
+        APP_HELP
+    }
+}
+
+/// Streams the latest sensor reading every `interval_secs` until a key is pressed
+///
+/// The interval is clamped to `WATCH_MIN_INTERVAL_SECS..=WATCH_MAX_INTERVAL_SECS`.
+/// The cancelling keystroke is consumed and not treated as input.
+async fn watch_sensor(
+    console_tx: &mut ConsoleTx,
+    console_rx: &mut ConsoleRx,
+    interval_secs: u64,
+) {
+    let interval_secs = interval_secs.clamp(WATCH_MIN_INTERVAL_SECS, WATCH_MAX_INTERVAL_SECS);
+    rprintln!("[CONSOLE] Watch started ({}s interval)", interval_secs);
     
-    {
-        let mut state = SYSTEM_STATE.lock().await;
-        state.console_active = true;
+    let header = format!("\r\nWatching sensor every {}s - press any key to stop\r\n", interval_secs);
+    let _ = embedded_io_async::Write::write(console_tx, header.as_bytes()).await;
+    
+    let mut last_printed = 0u32;
+    loop {
+        let state = *SYSTEM_STATE.lock().await;
+        let line = if state.reading_count == 0 {
+            "waiting for first reading...\r\n".to_string()
+        } else if JSON_OUTPUT.load(Ordering::Relaxed) {
+            format!("{{\"reading\":{},\"temperature\":{:.2},\"humidity\":{:.1},\"pressure\":{:.1},\"sensor_active\":{}}}\r\n",
+                    state.reading_count, state.last_temperature, state.last_humidity,
+                    state.last_pressure, state.sensor_active)
+        } else {
+            // Mark repeats so a stalled sensor is obvious at short intervals
+            let marker = if state.reading_count == last_printed { " (no new reading)" } else { "" };
+            format!("#{}: T={:.2}C H={:.1}% P={:.1}hPa{}\r\n",
+                    state.reading_count, state.last_temperature, state.last_humidity,
+                    state.last_pressure, marker)
+        };
+        last_printed = state.reading_count;
+        let _ = embedded_io_async::Write::write(console_tx, line.as_bytes()).await;
+        let _ = embedded_io_async::Write::flush(console_tx).await;
+        
+        // Wait out the interval, stopping early on any keystroke
+        let mut byte = [0u8; 1];
+        let cancelled = embassy_futures::select::select(
+            embedded_io_async::Read::read(console_rx, &mut byte),
+            Timer::after(Duration::from_secs(interval_secs)),
+        ).await;
+        if let embassy_futures::select::Either::First(_) = cancelled {
+            break;
+        }
     }
     
-    // Send operational welcome banner
-    let banner = b"\r\n\r\n+==========================================================+\r\n\
-                   |          ESP32-C3 IoT System IoT System v1.0            |\r\n\
-                   |        BME280 Environmental Monitoring Station          |\r\n\
-                   +==========================================================+\r\n\
-                   System Status: IoT System Ready\r\n\
-                   Sensor: BME280 Temperature/Humidity/Pressure\r\n\
-                   Connectivity: WiFi + MQTT\r\n\
-                   \r\nType 'help' for available commands\r\n\r\niot> ";
+    rprintln!("[CONSOLE] Watch stopped");
+    let _ = embedded_io_async::Write::write(console_tx, b"Watch stopped\r\n\r\n").await;
+}
+
+/// Lets the MQTT task flush pending data and publish an offline status, then resets
+///
+/// Gives up on the flush after a bounded wait if the MQTT task is not running
+/// (e.g. WiFi still down).
+async fn flush_and_restart() -> ! {
+    SHUTDOWN_SIGNAL.signal(());
+    let flushed = embassy_futures::select::select(
+        SHUTDOWN_COMPLETE_SIGNAL.wait(),
+        Timer::after(Duration::from_millis(SHUTDOWN_TIMEOUT_MS)),
+    ).await;
+    if let embassy_futures::select::Either::Second(_) = flushed {
+        rprintln!("[CONSOLE] Shutdown flush timed out");
+    }
     
-    let _ = embedded_io_async::Write::write(&mut usb_tx, banner).await;
-    let _ = embedded_io_async::Write::flush(&mut usb_tx).await;
+    rprintln!("[CONSOLE] Performing system restart...");
+    restart_system().await
+}
+
+/// Resets the device through `HardwarePlatform::reset`
+/// 
+/// The platform belongs to `status_led_task`, which resets at the top of its
+/// next cycle. Only when the platform failed to initialize at boot does this
+/// fall back to the HAL's reset utility.
+async fn restart_system() -> ! {
+    if PLATFORM_ACTIVE.load(Ordering::Relaxed) {
+        RESET_REQUEST.signal(());
+        core::future::pending::<()>().await;
+    }
+    iot_hal::esp32c3::utils::system_reset()
+}
+
+/// Shows the OTA slots, or installs the image at `url` and restarts into it,
+/// for the console `ota` command
+///
+/// Progress is printed every 10%. An update is refused while the running
+/// firmware is still on its trial boot, because the inactive slot holds the
+/// image to roll back to.
+async fn ota_command(console_tx: &mut ConsoleTx, wifi_manager: Option<&WiFiManager>, url: &str) {
+    let json = JSON_OUTPUT.load(Ordering::Relaxed);
+    if url.is_empty() {
+        let _ = embedded_io_async::Write::write(console_tx, ota_status(json).as_bytes()).await;
+        return;
+    }
+    let Some(wifi_manager) = wifi_manager else {
+        let _ = embedded_io_async::Write::write(console_tx, b"\r\nOTA unavailable: WiFi not initialized\r\n\r\n").await;
+        return;
+    };
+    if OTA_TRIAL.load(Ordering::Relaxed) {
+        let _ = embedded_io_async::Write::write(console_tx, b"\r\nOTA refused: running firmware is not confirmed yet\r\n\r\n").await;
+        return;
+    }
     
-    let mut input_buffer = [0u8; 128];
-    let mut input_len = 0;
+    rprintln!("[CONSOLE] OTA update from {}", url);
+    let header = format!("\r\nDownloading {}\r\n", url);
+    let _ = embedded_io_async::Write::write(console_tx, header.as_bytes()).await;
+    let _ = embedded_io_async::Write::flush(console_tx).await;
     
-    loop {
-        let mut byte = [0u8; 1];
-        if let Ok(1) = embedded_io_async::Read::read(&mut usb_rx, &mut byte).await {
-            let ch = byte[0];
-            
-            match ch {
-                b'\r' | b'\n' => {
-                    if input_len > 0 {
-                        let cmd = core::str::from_utf8(&input_buffer[..input_len]).unwrap_or("");
-                        let response = process_console_command(cmd).await;
-                        
-                        let _ = embedded_io_async::Write::write(&mut usb_tx, response.as_bytes()).await;
-                        let _ = embedded_io_async::Write::flush(&mut usb_tx).await;
-                    } else {
-                        let _ = embedded_io_async::Write::write(&mut usb_tx, b"\r\niot> ").await;
-                        let _ = embedded_io_async::Write::flush(&mut usb_tx).await;
-                    }
-                    input_len = 0;
-                }
-                0x08 | 0x7F => { // Backspace
-                    if input_len > 0 {
-                        input_len -= 1;
-                        let _ = embedded_io_async::Write::write(&mut usb_tx, b"\x08 \x08").await;
-                        let _ = embedded_io_async::Write::flush(&mut usb_tx).await;
-                    }
+    let updater = OtaUpdater::new(*wifi_manager.get_stack());
+    #[cfg(feature = "ota-https")]
+    let updater = updater.with_rng(wifi_manager.rng());
+    OTA_PROGRESS.reset();
+    let mut update = core::pin::pin!(updater.update(url, |progress| OTA_PROGRESS.signal(progress)));
+    let mut last_step = None;
+    let result = loop {
+        match embassy_futures::select::select(&mut update, OTA_PROGRESS.wait()).await {
+            embassy_futures::select::Either::First(result) => break result,
+            embassy_futures::select::Either::Second(progress) => {
+                let step = progress.percent() / 10;
+                if last_step == Some(step) {
+                    continue;
                 }
-                ch if ch >= 0x20 && ch <= 0x7E => { // Printable characters
-                    if input_len < input_buffer.len() - 1 {
-                        input_buffer[input_len] = ch;
-                        input_len += 1;
-                        let _ = embedded_io_async::Write::write(&mut usb_tx, &[ch]).await;
-                        let _ = embedded_io_async::Write::flush(&mut usb_tx).await;
-                    }
+                last_step = Some(step);
+                let line = if json {
+                    format!("{{\"ota_percent\":{},\"received\":{},\"total\":{}}}\r\n",
+                            progress.percent(), progress.received, progress.total)
+                } else {
+                    format!("  {}% ({} of {} bytes)\r\n", progress.percent(), progress.received, progress.total)
+                };
+                let _ = embedded_io_async::Write::write(console_tx, line.as_bytes()).await;
+                let _ = embedded_io_async::Write::flush(console_tx).await;
+            }
+        }
+    };
+    
+    match result {
+        Ok(image) => {
+            let message = if json {
+                format!("{{\"ota\":\"ok\",\"slot\":{},\"size\":{}}}\r\n", image.slot, image.size)
+            } else {
+                format!("Image verified: {} bytes in ota_{}\r\nRestarting into new firmware...\r\n", image.size, image.slot)
+            };
+            let _ = embedded_io_async::Write::write(console_tx, message.as_bytes()).await;
+            let _ = embedded_io_async::Write::flush(console_tx).await;
+            flush_and_restart().await
+        }
+        Err(e) => {
+            rprintln!("[CONSOLE] OTA update failed: {}", e);
+            let message = if json {
+                format!("{{\"ota\":\"error\",\"error\":\"{}\"}}\r\n\r\n", e)
+            } else {
+                format!("OTA failed: {}\r\nRunning firmware unchanged\r\n\r\n", e)
+            };
+            let _ = embedded_io_async::Write::write(console_tx, message.as_bytes()).await;
+        }
+    }
+}
+
+/// Running slot, its boot state and the slot the next update goes to
+fn ota_status(json: bool) -> alloc::string::String {
+    let mut ota = match OtaPartitions::new(FlashStorage::new()) {
+        Ok(ota) => ota,
+        Err(e) => return format!("\r\nOTA unavailable: {}\r\n\r\n", OtaError::from(e)),
+    };
+    let (Ok(running), Ok(state), Ok(next)) = (ota.boot_slot(), ota.boot_state(), ota.next_update_slot()) else {
+        return "\r\nOTA unavailable: failed to read otadata\r\n\r\n".to_string();
+    };
+    let running = match running {
+        Some(slot) => format!("ota_{}", slot),
+        None => "factory".to_string(),
+    };
+    let slot_size = ota.slot(next).map_or(0, |slot| slot.size);
+    let trial = OTA_TRIAL.load(Ordering::Relaxed);
+    
+    if json {
+        return format!("\r\n{{\"running\":\"{}\",\"state\":\"{}\",\"trial\":{},\"next_slot\":\"ota_{}\",\"slot_size\":{}}}\r\n\r\n",
+                       running, state.as_str(), trial, next, slot_size);
+    }
+    format!("\r\n=== Firmware Update ===\r\n\
+            Running: {} (state: {})\r\n\
+            Trial boot: {}\r\n\
+            Next update: ota_{} ({} KiB max)\r\n\
+            Usage: ota http[s]://<ipv4>[:port]/<path>\r\n\
+            \r\n",
+            running, state.as_str(),
+            if trial { "yes - confirms once WiFi and MQTT connect" } else { "no" },
+            next, slot_size / 1024)
+}
+
+/// Starts or ends the trial boot of freshly installed firmware (see `iot_storage::ota`)
+///
+/// Resets into the previous firmware if the last trial never confirmed.
+/// Returns true while the running image still has to confirm itself.
+async fn check_ota_trial_boot() -> bool {
+    let mut ota = match OtaPartitions::new(FlashStorage::new()) {
+        Ok(ota) => ota,
+        Err(_) => {
+            rprintln!("[OTA] No OTA partitions - firmware updates disabled");
+            return false;
+        }
+    };
+    match ota.check_trial_boot() {
+        Ok(TrialBoot::Normal) => false,
+        Ok(TrialBoot::Started) => {
+            rprintln!("[OTA] First boot of new firmware - confirming once WiFi and MQTT connect");
+            true
+        }
+        Ok(TrialBoot::RolledBack) => {
+            rprintln!("[OTA] New firmware never confirmed - restarting into previous firmware");
+            // Nothing owns the platform yet, bring it up just to reset through it
+            if let Ok(mut platform) = Esp32C3Platform::initialize().await {
+                platform.reset().await;
+            }
+            restart_system().await
+        }
+        Ok(TrialBoot::NoFallback) => {
+            rprintln!("[OTA] WARNING: New firmware never confirmed and no previous firmware exists - keeping it");
+            false
+        }
+        Err(e) => {
+            rprintln!("[OTA] ERROR: Failed to read boot state: {:?}", e);
+            false
+        }
+    }
+}
+
+/// Confirms new firmware once WiFi and MQTT are up, or restarts to roll it back
+#[embassy_executor::task]
+async fn ota_confirm_task() {
+    let deadline = Instant::now() + Duration::from_secs(OTA_CONFIRM_TIMEOUT_SECS);
+    while Instant::now() < deadline {
+        let state = *SYSTEM_STATE.lock().await;
+        if state.wifi_connected && state.mqtt_connected {
+            match OtaPartitions::new(FlashStorage::new()).and_then(|mut ota| ota.mark_valid()) {
+                Ok(()) => {
+                    rprintln!("[OTA] New firmware confirmed");
+                    OTA_TRIAL.store(false, Ordering::Relaxed);
+                    OTA_CONFIRMED.signal(());
                 }
-                _ => {} // Ignore other characters
+                // Left on trial: the next reset rolls back, the watchdog keeps running
+                Err(e) => rprintln!("[OTA] ERROR: Failed to confirm new firmware: {:?}", e),
             }
+            return;
         }
+        Timer::after(Duration::from_secs(1)).await;
+    }
+    
+    rprintln!("[OTA] New firmware not connected after {}s - restarting to roll back", OTA_CONFIRM_TIMEOUT_SECS);
+    restart_system().await
+}
+
+/// Dumps the flash data log to the console as CSV
+///
+/// The log stays locked for the whole dump, so a reading taken meanwhile is
+/// appended once the export completes.
+async fn export_data_log(console_tx: &mut ConsoleTx) {
+    let mut logger = DATA_LOGGER.lock().await;
+    let Some(logger) = logger.as_mut() else {
+        let _ = embedded_io_async::Write::write(console_tx, b"\r\nData log unavailable\r\n\r\n").await;
+        return;
+    };
+    
+    let _ = embedded_io_async::Write::write(console_tx, b"\r\n").await;
+    match logger.export(console_tx).await {
+        Ok(count) => rprintln!("[CONSOLE] Exported {} logged readings", count),
+        Err(e) => rprintln!("[CONSOLE] ERROR: Data log export failed: {:?}", e),
     }
+    let _ = embedded_io_async::Write::write(console_tx, b"\r\n").await;
 }
 
-async fn process_console_command(cmd: &str) -> alloc::string::String {
-    match cmd.trim() {
-        "help" | "h" | "?" => {
-            "\r\n=== IoT System Console v1.0 ===\r\n\
-             help, h, ?       - Show this help\r\n\
-             status, stat     - Show system status\r\n\
-             info, i          - Show system information\r\n\
-             sensor           - Show latest sensor reading\r\n\
-             readings         - Show reading count\r\n\
-             perf             - Show performance metrics\r\n\
-             memory           - Show memory usage\r\n\
-             alerts           - Show performance alerts\r\n\
-             wifi             - Show WiFi configuration\r\n\
-             wifi ssid <name> - Set WiFi SSID\r\n\
-             wifi pass <pass> - Set WiFi password\r\n\
-             uptime           - Show system uptime\r\n\
-             restart, reset   - Restart system\r\n\
-             save             - Save configuration to flash\r\n\
-             load             - Load configuration from flash\r\n\
-             clear, cls       - Clear screen\r\n\
-             \r\niot> ".to_string()
+/// Runs a device command, `None` when `cmd` is not one of them
+async fn app_command(cmd: &str) -> Option<alloc::string::String> {
+    if JSON_OUTPUT.load(Ordering::Relaxed) {
+        if let Some(response) = json_console_response(cmd).await {
+            return Some(response);
         }
+    }
+    
+    let response = match cmd {
         "status" | "stat" => {
             let state = SYSTEM_STATE.lock().await;
+            let (buffered, capacity, dropped) = measurement_backlog();
+            let backlog = format!("Backlog: {}/{} readings buffered, {} dropped\r\n{}{}",
+                                  buffered, capacity, dropped, broker_line(&state), chip_temperature_line(&state));
             if state.sensor_active {
                 if state.performance_monitoring {
                     format!("\r\n=== IoT System System Status v1.0 ===\r\n\
@@ -723,38 +2061,52 @@ async fn process_console_command(cmd: &str) -> alloc::string::String {
                             MQTT: DEGRADED MODE - Check RTT for details\r\n\
                             System: OPERATIONAL - Core functions active\r\n\
                             Readings: {} | Alerts: {}\r\n\
-                            \r\niot> ", state.reading_count, state.performance_alerts)
+                            {}\
+                            \r\n", state.reading_count, state.performance_alerts, backlog)
                 } else {
-                    "\r\n=== IoT System System Status v1.0 ===\r\n\
-                     BME280 Sensor: ACTIVE - Reading environmental data\r\n\
-                     Console: ACTIVE - USB Serial/JTAG interface\r\n\
-                     Performance: INITIALIZING - Setting up monitoring\r\n\
-                     WiFi: CONNECTING - Network connection in progress\r\n\
-                     MQTT: CONNECTING - Broker connection in progress\r\n\
-                     System: INITIALIZING - Network setup active\r\n\
-                     \r\niot> ".to_string()
+                    format!("\r\n=== IoT System System Status v1.0 ===\r\n\
+                             BME280 Sensor: ACTIVE - Reading environmental data\r\n\
+                             Console: ACTIVE - USB Serial/JTAG interface\r\n\
+                             Performance: INITIALIZING - Setting up monitoring\r\n\
+                             WiFi: CONNECTING - Network connection in progress\r\n\
+                             MQTT: CONNECTING - Broker connection in progress\r\n\
+                             System: INITIALIZING - Network setup active\r\n\
+                             {}\
+                             \r\n", backlog)
                 }
             } else {
-                "\r\n=== IoT System System Status v1.0 ===\r\n\
-                 BME280 Sensor: ERROR - Hardware communication failure\r\n\
-                 Console: ACTIVE - USB Serial/JTAG interface\r\n\
-                 Performance: ACTIVE - Monitoring available\r\n\
-                 WiFi: CONFIGURED - Connection management active\r\n\
-                 MQTT: CONFIGURED - Awaiting sensor data\r\n\
-                 System: DEGRADED - Sensor requires attention\r\n\
-                 \r\niot> ".to_string()
+                format!("\r\n=== IoT System System Status v1.0 ===\r\n\
+                         BME280 Sensor: ERROR - Hardware communication failure\r\n\
+                         Console: ACTIVE - USB Serial/JTAG interface\r\n\
+                         Performance: ACTIVE - Monitoring available\r\n\
+                         WiFi: CONFIGURED - Connection management active\r\n\
+                         MQTT: CONFIGURED - Awaiting sensor data\r\n\
+                         System: DEGRADED - Sensor requires attention\r\n\
+                         {}\
+                         \r\n", backlog)
             }
         }
         "info" | "i" => {
-            "\r\n=== IoT System System Information v1.0 ===\r\n\
-             Chip: ESP32-C3 RISC-V 160MHz\r\n\
-             Framework: Embassy Async Runtime\r\n\
-             HAL: esp-hal v1.0.0-rc.0\r\n\
-             Modules: BME280, WiFi, MQTT, Console\r\n\
-             Sensor: BME280 I2C (GPIO8/9)\r\n\
-             Interface: USB Serial/JTAG\r\n\
-             Build: IoT System IoT System v1.0.0\r\n\
-             \r\niot> ".to_string()
+            let last_reset = last_reset_description().await;
+            let device = match *DEVICE_INFO.lock().await {
+                Some(info) => format!("Revision: v{}.{}\r\n\
+                                       MAC: {}\r\n\
+                                       Last reset: {}\r\n",
+                                      info.chip_revision / 100, info.chip_revision % 100,
+                                      info.mac_string(), last_reset),
+                None => "Device info: unavailable\r\n".to_string(),
+            };
+            let chip_temperature = chip_temperature_line(&*SYSTEM_STATE.lock().await);
+            format!("\r\n=== IoT System System Information v1.0 ===\r\n\
+                     Chip: ESP32-C3 RISC-V 160MHz\r\n\
+                     {}{}\
+                     Framework: Embassy Async Runtime\r\n\
+                     HAL: esp-hal v1.0.0-rc.0\r\n\
+                     Modules: BME280, WiFi, MQTT, Console\r\n\
+                     Sensor: BME280 I2C (GPIO8/9)\r\n\
+                     Interface: USB Serial/JTAG\r\n\
+                     Firmware: v{}\r\n\
+                     \r\n", device, chip_temperature, env!("CARGO_PKG_VERSION"))
         }
         "sensor" => {
             // Read from system state instead of stealing signal
@@ -767,18 +2119,64 @@ async fn process_console_command(cmd: &str) -> alloc::string::String {
                             Last Reading Time: {}μs\r\n\
                             Sensor Type: BME280 (I2C)\r\n\
                             GPIO Pins: SDA=8, SCL=9\r\n\
-                            \r\niot> ",
+                            \r\n",
                             state.reading_count, state.last_sensor_time_us)
                 } else {
                     "\r\n=== Latest Sensor Status ===\r\n\
                      Status: No recent sensor data\r\n\
                      Check: Verify BME280 connection (GPIO8/9)\r\n\
-                     \r\niot> ".to_string()
+                     \r\n".to_string()
                 }
             } else {
                 "\r\n=== Latest Sensor Status ===\r\n\
                  Status: Unable to read sensor state\r\n\
-                 \r\niot> ".to_string()
+                 \r\n".to_string()
+            }
+        }
+        "log" | "log all" => recent_log(LogLevel::Info),
+        "log warnings" => recent_log(LogLevel::Warning),
+        "log errors" => recent_log(LogLevel::Error),
+        "i2c probe" => i2c_probe(None).await,
+        cmd if cmd.starts_with("i2c probe ") => {
+            let arg = cmd["i2c probe ".len()..].trim();
+            let hex = arg.strip_prefix("0x").or_else(|| arg.strip_prefix("0X")).unwrap_or(arg);
+            match u8::from_str_radix(hex, 16) {
+                Ok(address) if (0x08..=0x77).contains(&address) => i2c_probe(Some(address)).await,
+                _ => "\r\nUsage: i2c probe [0x08-0x77]\r\n\r\n".to_string(),
+            }
+        }
+        "loglevel" => {
+            format!("\r\nLog level: {}\r\n\r\n", logging::max_level().as_str())
+        }
+        cmd if cmd.starts_with("loglevel ") => {
+            match logging::LogLevel::from_name(cmd["loglevel ".len()..].trim()) {
+                Some(level) => {
+                    logging::set_max_level(level);
+                    if level > logging::STATIC_MAX_LEVEL {
+                        format!("\r\nLog level: {} (build keeps only {})\r\n\r\n",
+                                level.as_str(), logging::STATIC_MAX_LEVEL.as_str())
+                    } else {
+                        format!("\r\nLog level: {}\r\n\r\n", level.as_str())
+                    }
+                }
+                None => "\r\nUsage: loglevel <none|error|warn|info|debug|trace>\r\n\r\n".to_string(),
+            }
+        }
+        "log clear" => {
+            LOG_BUFFER.lock(|buffer| buffer.borrow_mut().clear());
+            "\r\nRecent log cleared\r\n\r\n".to_string()
+        }
+        "log data" => {
+            match DATA_LOGGER.lock().await.as_ref() {
+                Some(logger) => format!("\r\n=== Sensor Data Log ===\r\n\
+                                        Records: {} / {}\r\n\
+                                        Wrapped: {}\r\n\
+                                        Flash Region: 0x{:06X} ({} KB)\r\n\
+                                        \r\n",
+                                        logger.len(), logger.capacity(),
+                                        if logger.is_wrapped() { "yes (oldest records overwritten)" } else { "no" },
+                                        flash_region(REGION_DATA_LOG).offset, flash_region(REGION_DATA_LOG).size / 1024),
+                None => "\r\nData log unavailable\r\n\r\n".to_string(),
             }
         }
         "readings" => {
@@ -787,36 +2185,42 @@ async fn process_console_command(cmd: &str) -> alloc::string::String {
                 format!("\r\n=== Sensor Reading Statistics ===\r\n\
                         Status: IoT System data collection active\r\n\
                         Total Readings: {}\r\n\
-                        Interval: 30 seconds per reading\r\n\
+                        Interval: {} seconds per reading\r\n\
                         Quality: Real BME280 environmental data\r\n\
                         Last Sensor Time: {}μs\r\n\
                         Performance Alerts: {}\r\n\
-                        \r\niot> ",
-                        state.reading_count, state.last_sensor_time_us, state.performance_alerts)
+                        \r\n",
+                        state.reading_count, SENSOR_INTERVAL.load(Ordering::Relaxed), state.last_sensor_time_us, state.performance_alerts)
             } else {
                 "\r\n=== Sensor Reading Statistics ===\r\n\
                  Status: No readings collected yet\r\n\
                  Action: System initializing or sensor error\r\n\
-                 \r\niot> ".to_string()
+                 \r\n".to_string()
             }
         }
         "uptime" => {
-            "\r\n=== System Uptime ===\r\n\
-             Status: IoT System system operational\r\n\
-             Monitoring: Real-time environmental data\r\n\
-             Reliability: Continuous operation mode\r\n\
-             \r\niot> ".to_string()
+            let secs = boot_clock().uptime().as_secs();
+            format!("\r\n=== System Uptime ===\r\n\
+                     Uptime: {}d {:02}h {:02}m {:02}s ({}s)\r\n\
+                     \r\n", secs / 86400, (secs / 3600) % 24, (secs / 60) % 60, secs % 60, secs)
+        }
+        "time" => {
+            match sntp::sync_status() {
+                Some(status) => format!("\r\n=== Wall Clock ===\r\n\
+                                         Time: synced\r\n\
+                                         Unix: {}\r\n\
+                                         Server: {} (stratum {})\r\n\
+                                         Last sync: {}s ago\r\n\
+                                         \r\n", sntp::now_unix(), status.server, status.stratum, status.age_secs),
+                None => "\r\n=== Wall Clock ===\r\n\
+                         Time: not synced\r\n\
+                         Readings carry boot-relative timestamps until NTP succeeds\r\n\
+                         \r\n".to_string(),
+            }
         }
         "restart" | "reset" => {
             rprintln!("[CONSOLE] System restart requested");
-            
-            // Schedule a restart after a brief delay
-            embassy_time::Timer::after(embassy_time::Duration::from_millis(100)).await;
-            
-            rprintln!("[CONSOLE] Performing system restart...");
-            
-            // Perform system restart using ESP32-C3 software reset
-            system::software_reset();
+            flush_and_restart().await
         }
         "save" => {
             // Save WiFi credentials to flash with detailed debug
@@ -837,6 +2241,7 @@ async fn process_console_command(cmd: &str) -> alloc::string::String {
                                      loaded.get_ssid(), loaded.get_password().len(), loaded.is_configured);
                             
                             if loaded.is_configured && loaded.get_ssid() == credentials.get_ssid() {
+                                notify_config_change(WIFI_CONFIG_NAME, ConfigChangeKind::Stored);
                                 format!("\r\n=== Configuration Save ===\r\n\
                                         WiFi SSID: {} (saved & verified)\r\n\
                                         WiFi Password: {} chars (saved & verified)\r\n\
@@ -844,19 +2249,20 @@ async fn process_console_command(cmd: &str) -> alloc::string::String {
                                         MQTT Port: {} (saved)\r\n\
                                         Status: Configuration saved to flash successfully\r\n\
                                         Verification: Read-back test PASSED\r\n\
-                                        Note: Restart to apply WiFi changes\r\n\
-                                        \r\niot> ", 
+                                        Note: {}\r\n\
+                                        \r\n", 
                                         credentials.get_ssid(),
                                         credentials.get_password().len(),
                                         credentials.get_mqtt_broker_ip(),
-                                        credentials.mqtt_broker_port)
+                                        credentials.mqtt_broker_port,
+                                        wifi_apply_note())
                             } else {
                                 format!("\r\n=== Configuration Save ===\r\n\
                                         WARNING: Configuration saved but verification failed\r\n\
                                         Expected SSID: '{}', Read SSID: '{}'\r\n\
                                         Expected Configured: true, Read Configured: {}\r\n\
                                         Status: Flash write succeeded but data integrity check failed\r\n\
-                                        \r\niot> ",
+                                        \r\n",
                                         credentials.get_ssid(), loaded.get_ssid(), loaded.is_configured)
                             }
                         }
@@ -866,7 +2272,7 @@ async fn process_console_command(cmd: &str) -> alloc::string::String {
                                     WARNING: Configuration saved but cannot verify\r\n\
                                     Status: Flash write succeeded but read-back failed\r\n\
                                     This may indicate a flash storage issue\r\n\
-                                    \r\niot> ")
+                                    \r\n")
                         }
                     }
                 }
@@ -876,7 +2282,7 @@ async fn process_console_command(cmd: &str) -> alloc::string::String {
                      Error: Failed to save configuration to flash\r\n\
                      Check: Flash storage may be corrupted or write-protected\r\n\
                      Solution: Try again or restart system\r\n\
-                     \r\niot> ".to_string()
+                     \r\n".to_string()
                 }
             }
         }
@@ -890,6 +2296,9 @@ async fn process_console_command(cmd: &str) -> alloc::string::String {
                         let mut credentials = WIFI_CREDENTIALS.lock().await;
                         *credentials = loaded_credentials;
                     }
+                    set_sensor_interval(loaded_credentials.sensor_interval_secs as u64);
+                    set_sensor_offsets(loaded_credentials.sensor_offsets());
+                    notify_config_change(WIFI_CONFIG_NAME, ConfigChangeKind::Restored);
                     
                     let credentials = WIFI_CREDENTIALS.lock().await;
                     format!("\r\n=== Configuration Load ===\r\n\
@@ -899,22 +2308,121 @@ async fn process_console_command(cmd: &str) -> alloc::string::String {
                             MQTT Port: {} (loaded)\r\n\
                             Configuration: {}\r\n\
                             Status: Configuration loaded from flash successfully\r\n\
-                            Note: Restart to apply WiFi changes\r\n\
-                            \r\niot> ", 
+                            Note: {}\r\n\
+                            \r\n", 
                             credentials.get_ssid(),
                             credentials.get_password().len(),
                             credentials.get_mqtt_broker_ip(),
                             credentials.mqtt_broker_port,
-                            if credentials.is_configured { "Complete" } else { "Incomplete" })
+                            if credentials.is_configured { "Complete" } else { "Incomplete" },
+                            wifi_apply_note())
                 }
                 Err(_) => {
                     "\r\n=== Configuration Load ===\r\n\
                      Error: Failed to load configuration from flash\r\n\
                      Check: No saved configuration found\r\n\
                      Solution: Configure WiFi settings first\r\n\
-                     \r\niot> ".to_string()
+                     \r\n".to_string()
+                }
+            }
+        }
+        "config rollback" => {
+            let mut config_manager = ConfigManager::new();
+            
+            match config_manager.rollback_wifi_credentials() {
+                Ok(Some(restored)) => {
+                    {
+                        let mut credentials = WIFI_CREDENTIALS.lock().await;
+                        *credentials = restored;
+                    }
+                    set_sensor_interval(restored.sensor_interval_secs as u64);
+                    set_sensor_offsets(restored.sensor_offsets());
+                    notify_config_change(WIFI_CONFIG_NAME, ConfigChangeKind::RolledBack);
+                    
+                    format!("\r\n=== Configuration Rollback ===\r\n\
+                            WiFi SSID: {} (restored)\r\n\
+                            WiFi Password: {} chars (restored)\r\n\
+                            MQTT Broker: {}:{} (restored)\r\n\
+                            Status: Previous configuration restored to flash\r\n\
+                            Note: {}\r\n\
+                            \r\n",
+                            restored.get_ssid(),
+                            restored.get_password().len(),
+                            restored.get_mqtt_broker_ip(),
+                            restored.mqtt_broker_port,
+                            wifi_apply_note())
+                }
+                Ok(None) => {
+                    "\r\n=== Configuration Rollback ===\r\n\
+                     Status: No previous configuration saved\r\n\
+                     \r\n".to_string()
+                }
+                Err(e) => {
+                    rprintln!("[CONSOLE] Config rollback failed: {:?}", e);
+                    "\r\n=== Configuration Rollback ===\r\n\
+                     Error: Failed to access configuration flash\r\n\
+                     \r\n".to_string()
+                }
+            }
+        }
+        "factory reset" => {
+            "\r\n=== Factory Reset ===\r\n\
+             WARNING: Erases WiFi/MQTT configuration, its previous version\r\n\
+             and every logged sensor reading\r\n\
+             Type 'factory reset confirm' to proceed\r\n\
+             \r\n".to_string()
+        }
+        "factory reset confirm" => {
+            rprintln!("[CONSOLE] Factory reset requested");
+            let mut config_manager = ConfigManager::new();
+            
+            let config_bytes = match config_manager.factory_reset() {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    rprintln!("[CONSOLE] Factory reset failed: {:?}", e);
+                    return Some("\r\n=== Factory Reset ===\r\n\
+                                 Error: Failed to erase configuration flash\r\n\
+                                 \r\n".to_string());
+                }
+            };
+            
+            let log_bytes = match DATA_LOGGER.lock().await.as_mut() {
+                Some(logger) => {
+                    let bytes = logger.len() * LOG_RECORD_SIZE;
+                    match logger.clear() {
+                        Ok(()) => bytes,
+                        Err(e) => {
+                            rprintln!("[CONSOLE] Data log erase failed: {:?}", e);
+                            0
+                        }
+                    }
                 }
+                None => 0,
+            };
+            
+            // Blank slots load as defaults; apply them to the running system too
+            let defaults = config_manager.load_wifi_credentials().unwrap_or(WifiCredentials::new());
+            {
+                let mut credentials = WIFI_CREDENTIALS.lock().await;
+                *credentials = defaults;
+            }
+            set_sensor_interval(defaults.sensor_interval_secs as u64);
+            set_sensor_offsets(defaults.sensor_offsets());
+            notify_config_change(WIFI_CONFIG_NAME, ConfigChangeKind::Deleted);
+            {
+                let mut state = SYSTEM_STATE.lock().await;
+                state.reading_count = 0;
+                state.sensor_resets = 0;
             }
+            
+            format!("\r\n=== Factory Reset ===\r\n\
+                    Configuration: {} bytes erased\r\n\
+                    Data Log: {} bytes erased\r\n\
+                    Total Reclaimed: {} bytes\r\n\
+                    Status: Defaults restored\r\n\
+                    Note: Restart to apply WiFi changes\r\n\
+                    \r\n",
+                    config_bytes, log_bytes, config_bytes + log_bytes)
         }
         "perf" => {
             let state = SYSTEM_STATE.lock().await;
@@ -925,12 +2433,12 @@ async fn process_console_command(cmd: &str) -> alloc::string::String {
                  System Health: Performance analysis active\r\n\
                  Alerts: Real-time threshold monitoring\r\n\
                  Status: Performance monitoring operational\r\n\
-                 \r\niot> ".to_string()
+                 \r\n".to_string()
             } else {
                 "\r\n=== Performance Metrics ===\r\n\
                  Status: Performance monitoring not available\r\n\
                  Check: System initialization in progress\r\n\
-                 \r\niot> ".to_string()
+                 \r\n".to_string()
             }
         }
         "memory" => {
@@ -939,15 +2447,15 @@ async fn process_console_command(cmd: &str) -> alloc::string::String {
                 format!("\r\n=== Memory Usage ===\r\n\
                         Heap: {}B in use\r\n\
                         Stack: Active monitoring\r\n\
-                        Flash: Usage analysis available\r\n\
+                        Flash: {}B image (.text + .data)\r\n\
                         Fragmentation: Monitored for optimization\r\n\
                         Status: Memory monitoring active\r\n\
-                        \r\niot> ", state.heap_usage)
+                        \r\n", state.heap_usage, state.flash_usage)
             } else {
                 "\r\n=== Memory Usage ===\r\n\
                  Status: Memory data collection in progress\r\n\
                  Wait: Initial measurements being taken\r\n\
-                 \r\niot> ".to_string()
+                 \r\n".to_string()
             }
         }
         "alerts" => {
@@ -957,15 +2465,31 @@ async fn process_console_command(cmd: &str) -> alloc::string::String {
                         Active Alerts: {} performance issues detected\r\n\
                         Check: Review RTT output for details\r\n\
                         Action: Investigate timing or memory issues\r\n\
-                        \r\niot> ", state.performance_alerts)
+                        \r\n", state.performance_alerts)
             } else {
                 "\r\n=== Performance Alerts ===\r\n\
                  Status: No active performance alerts\r\n\
                  System: Operating within normal parameters\r\n\
-                 \r\niot> ".to_string()
+                 \r\n".to_string()
+            }
+        }
+        "errors" => {
+            let state = SYSTEM_STATE.lock().await;
+            let mut response = "\r\n=== Error History (oldest first) ===\r\n".to_string();
+            if state.error_history.is_empty() {
+                response.push_str("No errors recorded\r\n");
             }
+            for record in state.error_history.records() {
+                response.push_str(&format!("{:>5}  {:<13} x{}\r\n", record.code, record.category, record.count));
+            }
+            response.push_str("\r\n");
+            response
+        }
+        "errors clear" => {
+            SYSTEM_STATE.lock().await.error_history.clear();
+            "\r\nError history cleared\r\n\r\n".to_string()
         }
-        "wifi" => {
+        "wifi" | "wifi show" | "mqtt" | "mqtt show" => {
             let credentials = WIFI_CREDENTIALS.lock().await;
             format!("\r\n=== WiFi Configuration ===\r\n\
                     SSID: {}\r\n\
@@ -981,17 +2505,89 @@ async fn process_console_command(cmd: &str) -> alloc::string::String {
                     wifi port <port>     - Set MQTT broker port\r\n\
                     save                 - Save to flash\r\n\
                     load                 - Load from flash\r\n\
-                    \r\niot> ",
+                    \r\n",
                     if credentials.ssid_len > 0 { credentials.get_ssid() } else { "[Not Set]" },
                     credentials.get_password().len(),
                     credentials.get_mqtt_broker_ip(),
                     credentials.mqtt_broker_port,
                     if credentials.is_configured { "Ready" } else { "Incomplete" })
         }
-        "clear" | "cls" => {
-            "\x1B[2J\x1B[H\r\niot> ".to_string()
+        "interval" => {
+            format!("\r\nSensor interval: {}s (range {}-{}s)\r\n\r\n",
+                    SENSOR_INTERVAL.load(Ordering::Relaxed), SENSOR_INTERVAL_MIN_SECS, SENSOR_INTERVAL_MAX_SECS)
+        }
+        cmd if cmd.starts_with("interval ") => {
+            match cmd["interval ".len()..].trim().parse::<u64>() {
+                Ok(secs) if (SENSOR_INTERVAL_MIN_SECS..=SENSOR_INTERVAL_MAX_SECS).contains(&secs) => {
+                    set_sensor_interval(secs);
+                    WIFI_CREDENTIALS.lock().await.sensor_interval_secs = secs as u16;
+                    
+                    // Persist only the interval so unsaved WiFi edits stay unsaved
+                    let mut config_manager = ConfigManager::new();
+                    let saved = config_manager.load_wifi_credentials().and_then(|mut stored| {
+                        stored.sensor_interval_secs = secs as u16;
+                        config_manager.save_wifi_credentials(&stored)
+                    });
+                    match saved {
+                        Ok(()) => format!("\r\nSensor interval: {}s (saved to flash)\r\n\r\n", secs),
+                        Err(e) => {
+                            rprintln!("[CONSOLE] Failed to save sensor interval: {:?}", e);
+                            format!("\r\nSensor interval: {}s (active, flash save failed)\r\n\r\n", secs)
+                        }
+                    }
+                }
+                _ => format!("\r\nUsage: interval <{}-{} seconds>\r\n\r\n",
+                             SENSOR_INTERVAL_MIN_SECS, SENSOR_INTERVAL_MAX_SECS),
+            }
+        }
+        "calibrate" => {
+            let offsets = WIFI_CREDENTIALS.lock().await.sensor_offsets();
+            format!("\r\n=== Sensor Calibration ===\r\n\
+                    Temperature: {:+.2} C\r\n\
+                    Humidity: {:+.2} %RH\r\n\
+                    Pressure: {:+.2} hPa\r\n\
+                    \r\n", offsets.temperature, offsets.humidity, offsets.pressure)
+        }
+        cmd if cmd.starts_with("calibrate ") => {
+            let mut parts = cmd.split_whitespace().skip(1);
+            let quantity = parts.next();
+            let value = parts.next().and_then(|v| v.parse::<f32>().ok());
+            let mut offsets = WIFI_CREDENTIALS.lock().await.sensor_offsets();
+            let target = match quantity {
+                Some("temp") => Some(&mut offsets.temperature),
+                Some("hum") => Some(&mut offsets.humidity),
+                Some("press") => Some(&mut offsets.pressure),
+                _ => None,
+            };
+            match (target, value, parts.next()) {
+                (Some(target), Some(value), None) => {
+                    *target = value;
+                    if WIFI_CREDENTIALS.lock().await.set_sensor_offsets(offsets) {
+                        set_sensor_offsets(offsets);
+                        
+                        // Persist only the offsets so unsaved WiFi edits stay unsaved
+                        let mut config_manager = ConfigManager::new();
+                        let saved = config_manager.load_wifi_credentials().and_then(|mut stored| {
+                            stored.set_sensor_offsets(offsets);
+                            config_manager.save_wifi_credentials(&stored)
+                        });
+                        let note = match saved {
+                            Ok(()) => "saved to flash",
+                            Err(e) => {
+                                rprintln!("[CONSOLE] Failed to save sensor offsets: {:?}", e);
+                                "active, flash save failed"
+                            }
+                        };
+                        format!("\r\nOffsets: T{:+.2}C H{:+.2}%RH P{:+.2}hPa ({})\r\n\r\n",
+                                offsets.temperature, offsets.humidity, offsets.pressure, note)
+                    } else {
+                        format!("\r\nOffset out of range (temp +/-{}C, hum +/-{}%RH, press +/-{}hPa)\r\n\r\n",
+                                MAX_TEMPERATURE_OFFSET, MAX_HUMIDITY_OFFSET, MAX_PRESSURE_OFFSET)
+                    }
+                }
+                _ => "\r\nUsage: calibrate temp|hum|press <offset>\r\n\r\n".to_string(),
+            }
         }
-        "" => "\r\niot> ".to_string(),
         cmd if cmd.starts_with("wifi ") => {
             let parts: Vec<&str> = cmd.split_whitespace().collect();
             if parts.len() >= 3 {
@@ -1006,11 +2602,11 @@ async fn process_console_command(cmd: &str) -> alloc::string::String {
                                     Status: SSID configured successfully\r\n\
                                     Next: Set password with 'wifi pass <password>'\r\n\
                                     Then: Run 'save' to store in flash\r\n\
-                                    \r\niot> ", ssid)
+                                    \r\n", ssid)
                         } else {
                             "\r\n=== WiFi SSID Error ===\r\n\
                              Error: SSID too long (max 32 characters)\r\n\
-                             \r\niot> ".to_string()
+                             \r\n".to_string()
                         }
                     }
                     "pass" => {
@@ -1022,13 +2618,13 @@ async fn process_console_command(cmd: &str) -> alloc::string::String {
                                     Status: Password configured successfully\r\n\
                                     Configuration: {}\r\n\
                                     Next: Run 'save' to store in flash\r\n\
-                                    \r\niot> ", 
+                                    \r\n", 
                                     password.len(),
                                     if credentials.is_configured { "Complete" } else { "Incomplete" })
                         } else {
                             "\r\n=== WiFi Password Error ===\r\n\
                              Error: Password too long (max 64 characters)\r\n\
-                             \r\niot> ".to_string()
+                             \r\n".to_string()
                         }
                     }
                     "broker" => {
@@ -1038,11 +2634,11 @@ async fn process_console_command(cmd: &str) -> alloc::string::String {
                                     Broker IP: {}\r\n\
                                     Status: MQTT broker configured successfully\r\n\
                                     Next: Run 'save' to store in flash\r\n\
-                                    \r\niot> ", broker_ip)
+                                    \r\n", broker_ip)
                         } else {
                             "\r\n=== MQTT Broker Error ===\r\n\
                              Error: IP address too long (max 16 characters)\r\n\
-                             \r\niot> ".to_string()
+                             \r\n".to_string()
                         }
                     }
                     "port" => {
@@ -1052,11 +2648,11 @@ async fn process_console_command(cmd: &str) -> alloc::string::String {
                                     Broker Port: {}\r\n\
                                     Status: MQTT port configured successfully\r\n\
                                     Next: Run 'save' to store in flash\r\n\
-                                    \r\niot> ", port)
+                                    \r\n", port)
                         } else {
                             "\r\n=== MQTT Port Error ===\r\n\
                              Error: Invalid port number (use 1-65535)\r\n\
-                             \r\niot> ".to_string()
+                             \r\n".to_string()
                         }
                     }
                     _ => {
@@ -1066,89 +2662,200 @@ async fn process_console_command(cmd: &str) -> alloc::string::String {
                          wifi pass <password> - Set WiFi password\r\n\
                          wifi broker <ip>     - Set MQTT broker IP\r\n\
                          wifi port <port>     - Set MQTT broker port\r\n\
-                         \r\niot> ".to_string()
+                         \r\n".to_string()
                     }
                 }
             } else {
                 "\r\n=== WiFi Command Error ===\r\n\
                  Usage: wifi <command> <value>\r\n\
                  Type 'wifi' to see current configuration\r\n\
-                 \r\niot> ".to_string()
+                 \r\n".to_string()
             }
         }
-        _ => "\r\nUnknown command. Type 'help' for available commands.\r\n\r\niot> ".to_string()
+        cmd if cmd.starts_with("mqtt ") => {
+            // Broker settings live with the WiFi credentials in flash
+            "\r\nUse 'wifi broker <ip>' and 'wifi port <port>'; type 'wifi' to see them\r\n\r\n".to_string()
+        }
+        _ => return None,
+    };
+    Some(response)
+}
+
+/// Console line for the on-die temperature
+///
+/// The die runs well above the room, so the BME280 reading is shown next to
+/// it; the difference is the self-heating, not a calibration error.
+fn chip_temperature_line(state: &SystemState) -> alloc::string::String {
+    match state.chip_temperature {
+        Some(celsius) if state.sensor_active => format!("Chip temperature: {:.1}°C (die, {:+.1}°C vs ambient)\r\n",
+                                                        celsius, celsius - state.last_temperature),
+        Some(celsius) => format!("Chip temperature: {:.1}°C (die, not ambient)\r\n", celsius),
+        None => "Chip temperature: unavailable\r\n".to_string(),
+    }
+}
+
+/// Broker line of the `status` command
+fn broker_line(state: &SystemState) -> alloc::string::String {
+    match state.mqtt_broker {
+        Some(broker) => format!("Broker: {}\r\n", broker),
+        None => "Broker: not connected yet\r\n".to_string(),
+    }
+}
+
+/// On-die temperature as a JSON value, `null` when unavailable
+fn chip_temperature_json(state: &SystemState) -> alloc::string::String {
+    match state.chip_temperature {
+        Some(celsius) => format!("{:.1}", celsius),
+        None => "null".to_string(),
     }
 }
 
+/// Compact JSON rendering of the machine-readable commands (`status`, `info`, `sensor`, `errors`, `perf`).
+/// Returns `None` for commands that only have a text form.
+async fn json_console_response(cmd: &str) -> Option<alloc::string::String> {
+    let json = match cmd {
+        "status" | "stat" => {
+            let state = SYSTEM_STATE.lock().await;
+            let (buffered, _, dropped) = measurement_backlog();
+            format!("{{\"sensor_active\":{},\"console_active\":{},\"wifi_connected\":{},\"mqtt_connected\":{},\"performance_monitoring\":{},\"readings\":{},\"alerts\":{},\"time_synced\":{},\"buffered\":{},\"dropped\":{},\"chip_temperature\":{},\"broker\":{}}}",
+                    state.sensor_active, state.console_active, state.wifi_connected, state.mqtt_connected,
+                    state.performance_monitoring, state.reading_count, state.performance_alerts, sntp::is_synced(),
+                    buffered, dropped, chip_temperature_json(&state),
+                    state.mqtt_broker.map_or("null".to_string(), |broker| format!("\"{}\"", broker)))
+        }
+        "info" | "i" => {
+            // Panic messages are free text, so let serde escape them
+            let panic = match PANIC_REASON.lock().await.as_ref() {
+                Some(reason) => serde_json_core::to_string::<_, 128>(reason.as_str())
+                    .map(|quoted| format!("\"panic\":{},", quoted))
+                    .unwrap_or_default(),
+                None => alloc::string::String::new(),
+            };
+            let device = match *DEVICE_INFO.lock().await {
+                Some(info) => format!("\"revision\":{},\"mac\":\"{}\",\"reset_reason\":\"{}\",{}",
+                                      info.chip_revision, info.mac_string(), info.reset_reason, panic),
+                None => alloc::string::String::new(),
+            };
+            let chip_temperature = chip_temperature_json(&*SYSTEM_STATE.lock().await);
+            format!("{{\"chip\":\"ESP32-C3\",{}\"chip_temperature\":{},\"cpu_mhz\":160,\"framework\":\"Embassy\",\"hal\":\"esp-hal v1.0.0-rc.0\",\"sensor\":\"BME280\",\"interface\":\"USB Serial/JTAG\",\"version\":\"{}\"}}",
+                    device, chip_temperature, env!("CARGO_PKG_VERSION"))
+        }
+        "sensor" => {
+            let state = SYSTEM_STATE.lock().await;
+            format!("{{\"sensor_active\":{},\"readings\":{},\"last_reading_us\":{},\"resets\":{}}}",
+                    state.sensor_active, state.reading_count, state.last_sensor_time_us, state.sensor_resets)
+        }
+        "errors" => {
+            let state = SYSTEM_STATE.lock().await;
+            let mut json = "[".to_string();
+            for (i, record) in state.error_history.records().iter().enumerate() {
+                if i > 0 {
+                    json.push(',');
+                }
+                json.push_str(&format!("{{\"code\":{},\"category\":\"{}\",\"count\":{}}}",
+                                       record.code, record.category, record.count));
+            }
+            json.push(']');
+            json
+        }
+        "perf" => {
+            let state = SYSTEM_STATE.lock().await;
+            format!("{{\"performance_monitoring\":{},\"heap_bytes\":{},\"flash_bytes\":{},\"alerts\":{}}}",
+                    state.performance_monitoring, state.heap_usage, state.flash_usage, state.performance_alerts)
+        }
+        _ => return None,
+    };
+    
+    Some(format!("\r\n{}\r\n", json))
+}
+
 #[embassy_executor::task]
 async fn status_led_task(platform: &'static mut Esp32C3Platform<'static>) {
     rprintln!("[STATUS-LED] Starting status LED task using IoT HAL abstraction");
     
-    // Status LED pattern indicates system state
+    // Freshly installed firmware that hangs is reset and rolled back, see `ota_confirm_task`:
+    // on a trial boot the watchdog is left unfed until the firmware confirms.
+    // Otherwise it is fed while the supervised tasks report, see `HEARTBEAT`.
+    let mut trial_boot = OTA_TRIAL.load(Ordering::Relaxed);
+    let watchdog_secs = if trial_boot { OTA_WATCHDOG_SECS } else { WATCHDOG_TIMEOUT_SECS };
+    match platform.get_watchdog().enable(Duration::from_secs(watchdog_secs)) {
+        Ok(()) => rprintln!("[STATUS-LED] Watchdog armed ({}s{})", watchdog_secs, if trial_boot { ", trial boot" } else { "" }),
+        Err(e) => rprintln!("[STATUS-LED] ERROR: Failed to arm watchdog: {:?}", e),
+    }
+    let mut next_watchdog_check = Instant::now() + Duration::from_secs(WATCHDOG_CHECK_SECS);
+    PLATFORM_ACTIVE.store(true, Ordering::Relaxed);
+    
+    // Status LED pattern indicates system state, see `GpioConfig::status_led_patterns`
+    let mut status_led = StatusLed::new(platform.config().gpio.status_led_patterns.clone());
+    // The platform lives in this task, so it also samples the on-die temperature
+    let mut next_chip_reading = Instant::now();
+    let mut chip_sensor_failed = false;
     loop {
-        let state = SYSTEM_STATE.lock().await;
-        
-        if state.sensor_active && state.wifi_connected && state.mqtt_connected {
-            // All systems operational - slow blink (1Hz)
-            let led = platform.get_status_led();
-            if let Err(e) = led.set_high().await {
-                rprintln!("[STATUS-LED] ERROR: Failed to set LED high: {:?}", e);
-            }
-            
-            let timer = platform.get_timer();
-            timer.delay(Duration::from_millis(500)).await;
-            
-            let led = platform.get_status_led();
-            if let Err(e) = led.set_low().await {
-                rprintln!("[STATUS-LED] ERROR: Failed to set LED low: {:?}", e);
-            }
-            
-            let timer = platform.get_timer();
-            timer.delay(Duration::from_millis(500)).await;
-            
-            // Update LED state
-            let mut state_mut = SYSTEM_STATE.lock().await;
-            state_mut.status_led_on = !state_mut.status_led_on;
-        } else if state.sensor_active {
-            // Sensor working but network issues - fast blink (2Hz)
-            let led = platform.get_status_led();
-            if let Err(e) = led.set_high().await {
-                rprintln!("[STATUS-LED] ERROR: Failed to set LED high: {:?}", e);
-            }
-            
-            let timer = platform.get_timer();
-            timer.delay(Duration::from_millis(250)).await;
-            
-            let led = platform.get_status_led();
-            if let Err(e) = led.set_low().await {
-                rprintln!("[STATUS-LED] ERROR: Failed to set LED low: {:?}", e);
+        if RESET_REQUEST.try_take().is_some() {
+            rprintln!("[STATUS-LED] Resetting through the platform");
+            platform.reset().await;
+        }
+        if LED_TEST_REQUEST.try_take().is_some() {
+            LED_TEST_RESULT.signal(blink_led_test(platform).await);
+        }
+        if OTA_CONFIRMED.try_take().is_some() {
+            // Confirmed: switch to the heartbeat-fed timeout
+            trial_boot = false;
+            if let Err(e) = platform.get_watchdog().enable(Duration::from_secs(WATCHDOG_TIMEOUT_SECS)) {
+                rprintln!("[STATUS-LED] ERROR: Failed to re-arm watchdog: {:?}", e);
             }
-            
-            let timer = platform.get_timer();
-            timer.delay(Duration::from_millis(250)).await;
-        } else {
-            // Sensor issues - very fast blink (4Hz)
-            let led = platform.get_status_led();
-            if let Err(e) = led.set_high().await {
-                rprintln!("[STATUS-LED] ERROR: Failed to set LED high: {:?}", e);
+        }
+        if !trial_boot && Instant::now() >= next_watchdog_check {
+            next_watchdog_check = Instant::now() + Duration::from_secs(WATCHDOG_CHECK_SECS);
+            let missing = HEARTBEAT.missing();
+            if !feed_if_healthy(platform.get_watchdog(), &HEARTBEAT) && missing != 0 {
+                rprintln!("[STATUS-LED] WARNING: Watchdog not fed - task mask 0x{:02X} did not report", missing);
             }
-            
-            let timer = platform.get_timer();
-            timer.delay(Duration::from_millis(125)).await;
-            
-            let led = platform.get_status_led();
-            if let Err(e) = led.set_low().await {
-                rprintln!("[STATUS-LED] ERROR: Failed to set LED low: {:?}", e);
+        }
+        if Instant::now() >= next_chip_reading {
+            next_chip_reading = Instant::now() + Duration::from_secs(CHIP_TEMPERATURE_INTERVAL_SECS);
+            let reading = platform.read_chip_temperature();
+            // Log the first failure only; a missing sensor stays missing
+            if let Err(e) = &reading {
+                if !chip_sensor_failed {
+                    rprintln!("[STATUS-LED] WARNING: Chip temperature unavailable: {}", e);
+                }
             }
-            
-            let timer = platform.get_timer();
-            timer.delay(Duration::from_millis(125)).await;
+            chip_sensor_failed = reading.is_err();
+            SYSTEM_STATE.lock().await.chip_temperature = reading.ok();
+        }
+        
+        // Copy the flags so the lock is not held while blinking
+        let state = *SYSTEM_STATE.lock().await;
+        let condition = LedCondition::from_flags(state.sensor_active, state.wifi_connected, state.mqtt_connected);
+        if status_led.set_condition(condition) {
+            rprintln!("[STATUS-LED] {} - {}", condition.as_str(), status_led.pattern().as_str());
+            SYSTEM_STATE.lock().await.status_led_on = status_led.pattern() != LedPattern::Off;
         }
+        
+        if let Err(e) = status_led.play_cycle(platform).await {
+            rprintln!("[STATUS-LED] ERROR: Failed to drive LED: {:?}", e);
+            platform.get_timer().delay(Duration::from_millis(1000)).await;
+        }
+    }
+}
+
+/// Self-test blink: three 100ms flashes, reporting the first GPIO error
+async fn blink_led_test(platform: &mut Esp32C3Platform<'static>) -> Result<(), IoTError> {
+    for _ in 0..3 {
+        platform.get_status_led().set_high().await?;
+        platform.get_timer().delay(Duration::from_millis(100)).await;
+        platform.get_status_led().set_low().await?;
+        platform.get_timer().delay(Duration::from_millis(100)).await;
     }
+    Ok(())
 }
 
 #[embassy_executor::task]
-async fn performance_monitor_task(performance_monitor: &'static PerformanceMonitor) {
+async fn performance_monitor_task(
+    performance_monitor: &'static PerformanceMonitor,
+    performance_analyzer: &'static Mutex<CriticalSectionRawMutex, PerformanceAnalyzer>,
+) {
     rprintln!("[PERF] Starting performance monitoring task");
     
     let mut analysis_counter = 0u32;
@@ -1160,7 +2867,7 @@ async fn performance_monitor_task(performance_monitor: &'static PerformanceMonit
         // Generate performance report
         let report = performance_monitor.generate_report().await;
         
-        rprintln!("[PERF] Analysis #{}: Uptime: {}s, Status: {:?}", 
+        log_debug!("[PERF] Analysis #{}: Uptime: {}s, Status: {:?}", 
                  analysis_counter, report.uptime_seconds, report.status);
         
         // Check for performance alerts
@@ -1176,18 +2883,27 @@ async fn performance_monitor_task(performance_monitor: &'static PerformanceMonit
                 let mut state = SYSTEM_STATE.lock().await;
                 state.performance_alerts = report.alerts.len() as u8;
             }
+            
+            EVENT_BUS.publish(SystemEvent::PerformanceAlert {
+                alert_count: report.alerts.len() as u8,
+            });
         }
         
         // Report memory usage
-        rprintln!("[PERF] Memory: Heap={}B, Stack={}B, Flash={}B", 
+        log_debug!("[PERF] Memory: Heap={}B, Stack={}B, Flash={}B", 
                  report.memory_usage.heap_used, 
                  report.memory_usage.stack_used,
                  report.memory_usage.flash_used);
         
+        {
+            let mut state = SYSTEM_STATE.lock().await;
+            state.flash_usage = report.memory_usage.flash_used;
+        }
+        
         // Check timing performance
         if let Some(sensor_time) = report.timing_stats.get_average_time(TimingCategory::SensorReading) {
             let sensor_us = sensor_time.as_micros() as u32;
-            rprintln!("[PERF] Sensor average: {}μs (target: <{}μs)", 
+            log_debug!("[PERF] Sensor average: {}μs (target: <{}μs)", 
                      sensor_us, SENSOR_CYCLE_TARGET_US);
             
             {
@@ -1197,8 +2913,26 @@ async fn performance_monitor_task(performance_monitor: &'static PerformanceMonit
             }
         }
         
-        // Signal performance report for other tasks
-        PERFORMANCE_REPORT_SIGNAL.signal(report);
+        // Moving-average trends warn about drift before a hard threshold trips
+        {
+            let mut analyzer = performance_analyzer.lock().await;
+            analyzer.add_report(&report);
+            
+            for (metric, label) in [
+                (PerformanceMetric::SensorReadingTime, "sensor timing"),
+                (PerformanceMetric::MqttPublishTime, "MQTT publish timing"),
+                (PerformanceMetric::HeapUsage, "heap usage"),
+            ] {
+                let trend = analyzer.trend(metric);
+                if matches!(trend, PerformanceTrend::Degrading | PerformanceTrend::Critical) {
+                    rprintln!("[PERF] WARNING: {} {} ({:.1}/sample, average {:.0})",
+                             label,
+                             if trend == PerformanceTrend::Critical { "degrading rapidly" } else { "degrading" },
+                             analyzer.trend_slope(metric).unwrap_or(0.0),
+                             analyzer.moving_average(metric).unwrap_or(0.0));
+                }
+            }
+        }
     }
 }
 
@@ -1206,28 +2940,64 @@ async fn performance_monitor_task(performance_monitor: &'static PerformanceMonit
 async fn system_monitor_task() {
     rprintln!("[MAIN-APP] Starting system monitor task");
     
-    let mut uptime = 0u32;
-    
     loop {
         Timer::after(Duration::from_secs(60)).await;
-        uptime += 60;
         
         let state = SYSTEM_STATE.lock().await;
-        rprintln!("[MAIN-APP] System Monitor - Uptime: {}s, Sensor: {}, Console: {}, Readings: {}, LED: {}, Perf: {} alerts",
+        let uptime = boot_clock().uptime().as_secs();
+        log_debug!("[MAIN-APP] System Monitor - Uptime: {}s, Sensor: {}, Console: {}, Readings: {}, LED: {}, Perf: {} alerts",
                  uptime, state.sensor_active, state.console_active, state.reading_count, 
                  state.status_led_on, state.performance_alerts);
     }
 }
 
+/// Why the device last restarted: the stashed panic if there was one,
+/// otherwise the reset-reason register
+async fn last_reset_description() -> heapless::String<MAX_LAST_RESET_LEN> {
+    let mut text = heapless::String::new();
+    if let Some(reason) = PANIC_REASON.lock().await.as_ref() {
+        let _ = text.push_str(reason);
+    } else {
+        let reason = DEVICE_INFO.lock().await.map(|info| info.reset_reason).unwrap_or_default();
+        let _ = text.push_str(reason.as_str());
+    }
+    text
+}
+
+/// Stash the panic in RTC memory and reboot, so the next boot can report it
+/// 
+/// Allocation failures end up here too ("memory allocation of N bytes
+/// failed"), so the heap usage is logged alongside the message.
+#[panic_handler]
+fn panic(info: &core::panic::PanicInfo) -> ! {
+    iot_hal::crash::record_panic(info);
+    // Straight to RTT, bypassing LOG_BUFFER (see `rprintln!`)
+    rtt_target::rprintln!("[PANIC] {}", info);
+    if let Some((used, free)) = MemoryTracker::allocator_heap_info() {
+        rtt_target::rprintln!("[PANIC] Heap: {} bytes used, {} free of {}", used, free, iot_config::HEAP_SIZE);
+    }
+    iot_hal::esp32c3::utils::system_reset()
+}
+
 #[esp_hal_embassy::main]
 async fn main(spawner: Spawner) {
-    // Initialize heap allocator with larger size for WiFi stack
-    esp_alloc::heap_allocator!(size: 64 * 1024);
+    // Initialize heap allocator, sized by iot-config for the WiFi stack
+    esp_alloc::heap_allocator!(size: iot_config::HEAP_SIZE);
     
     // Initialize RTT for debugging
     rtt_init_print!();
     
     rprintln!("=== ESP32-C3 IoT Environmental Monitoring System v1.0.0 Starting ===");
+    
+    match LOG_LEVEL.map(logging::LogLevel::from_name) {
+        Some(Some(level)) => logging::init(level),
+        Some(None) => rprintln!("[SYSTEM] WARNING: Unknown LOG_LEVEL, keeping {}", logging::max_level().as_str()),
+        None => {}
+    }
+    rprintln!("[SYSTEM] Log level: {}", logging::max_level().as_str());
+    
+    // Collect the panic stashed before the last reset, before anything can panic again
+    let panic_reason = iot_hal::crash::take_panic_reason();
     rprintln!("[SYSTEM] Environmental Monitoring Station");
     
     // Initialize ESP32-C3 peripherals
@@ -1237,6 +3007,13 @@ async fn main(spawner: Spawner) {
     let timer_group1 = TimerGroup::new(peripherals.TIMG1);
     esp_hal_embassy::init(timer_group1.timer0);
     rprintln!("[MAIN-APP] Embassy time driver initialized");
+    BOOT_CLOCK.init(BootClock::start()).ok();
+    
+    // Roll back a firmware update that failed its trial boot before touching anything else
+    OTA_TRIAL.store(check_ota_trial_boot().await, Ordering::Relaxed);
+    
+    // Refuse to run with flash regions that overlap each other or other partitions
+    check_flash_layout();
     
     // PRIORITY: Initialize WiFi FIRST to avoid memory fragmentation
     // Load WiFi credentials from flash storage (memory optimized)
@@ -1247,6 +3024,15 @@ async fn main(spawner: Spawner) {
             rprintln!("[MAIN-APP] Flash read successful - SSID: '{}', Password: {} chars, Configured: {}", 
                      creds.get_ssid(), creds.get_password().len(), creds.is_configured);
             
+            // The interval and sensor calibration apply even before WiFi is configured
+            SENSOR_INTERVAL.store(creds.sensor_interval_secs as u32, Ordering::Relaxed);
+            set_sensor_offsets(creds.sensor_offsets());
+            {
+                let mut global_creds = WIFI_CREDENTIALS.lock().await;
+                global_creds.sensor_interval_secs = creds.sensor_interval_secs;
+                global_creds.set_sensor_offsets(creds.sensor_offsets());
+            }
+            
             if creds.is_configured {
                 rprintln!("[MAIN-APP] WiFi Config - SSID: {} | Password: {} chars", 
                          creds.get_ssid(), creds.get_password().len());
@@ -1277,15 +3063,26 @@ async fn main(spawner: Spawner) {
     // Create WiFi configuration with dynamic credentials from flash
     let wifi_config = if let Some(creds) = loaded_credentials {
         if creds.is_configured {
-            WiFiConfig {
-                ssid: heapless::String::<32>::from_str(creds.get_ssid()).unwrap_or_default(),
-                password: heapless::String::<64>::from_str(creds.get_password()).unwrap_or_default(),
+            let ssid_len = (creds.ssid_len as usize).min(creds.ssid.len());
+            let password_len = (creds.password_len as usize).min(creds.password.len());
+            match WiFiConfig::from_credentials(&creds.ssid[..ssid_len], &creds.password[..password_len]) {
+                Ok(config) => config,
+                Err(e) => {
+                    rprintln!("[MAIN-APP] Stored WiFi credentials rejected: {}", e);
+                    rprintln!("[MAIN-APP] Use console to reconfigure: wifi ssid <name> / wifi pass <password> / save");
+                    WiFiConfig {
+                        ssid: heapless::String::new(),
+                        password: heapless::String::new(),
+                        timeouts: WiFiTimeouts::default(),
+                    }
+                }
             }
         } else {
             rprintln!("[MAIN-APP] No WiFi credentials configured - use console to configure");
             WiFiConfig {
                 ssid: heapless::String::new(),
                 password: heapless::String::new(),
+                timeouts: WiFiTimeouts::default(),
             }
         }
     } else {
@@ -1293,6 +3090,7 @@ async fn main(spawner: Spawner) {
         WiFiConfig {
             ssid: heapless::String::new(),
             password: heapless::String::new(),
+            timeouts: WiFiTimeouts::default(),
         }
     };
     
@@ -1316,6 +3114,7 @@ async fn main(spawner: Spawner) {
         }
         Err(e) => {
             rprintln!("[MAIN-APP] ERROR: Failed to initialize WiFi: {}", e);
+            record_error(IoTError::from(e)).await;
             rprintln!("[MAIN-APP] DEGRADED MODE: Running without WiFi/MQTT");
             rprintln!("[MAIN-APP] Sensor and console will still be available");
             (false, None)
@@ -1323,27 +3122,62 @@ async fn main(spawner: Spawner) {
     };
     
     // Configure I2C for BME280 sensor (blocking mode for BME280 compatibility)
-    let i2c = I2c::new(peripherals.I2C0, Config::default())
+    // on the pins and at the clock the board configuration names
+    let hardware_config = hardware_config();
+    hardware_config.validate().expect("invalid hardware configuration");
+    let (sda, scl) = iot_hal::esp32c3::utils::i2c_pins(
+        &hardware_config.i2c,
+        peripherals.GPIO8,
+        peripherals.GPIO9,
+        peripherals.GPIO18,
+        peripherals.GPIO19,
+    ).expect("I2C pins validated above");
+    let i2c = I2c::new(peripherals.I2C0, Config::default().with_frequency(Rate::from_hz(hardware_config.i2c.frequency_hz)))
         .unwrap()
-        .with_sda(peripherals.GPIO8)
-        .with_scl(peripherals.GPIO9);
-    rprintln!("[MAIN-APP] I2C configured for BME280 (SDA: GPIO8, SCL: GPIO9)");
+        .with_sda(sda)
+        .with_scl(scl);
+    rprintln!("[MAIN-APP] I2C configured for BME280 (SDA: GPIO{}, SCL: GPIO{}, {} kHz)",
+             hardware_config.i2c.sda_pin, hardware_config.i2c.scl_pin, hardware_config.i2c.frequency_hz / 1000);
     
     // Configure USB Serial/JTAG for console
-    let usb_serial = UsbSerialJtag::new(peripherals.USB_DEVICE);
-    let usb_serial = usb_serial.into_async();
-    let (usb_rx, usb_tx) = usb_serial.split();
-    rprintln!("[MAIN-APP] USB Serial/JTAG configured for console");
+    #[cfg(not(feature = "uart-console"))]
+    let (console_rx, console_tx) = {
+        let usb_serial = esp_hal::usb_serial_jtag::UsbSerialJtag::new(peripherals.USB_DEVICE);
+        let usb_serial = usb_serial.into_async();
+        rprintln!("[MAIN-APP] USB Serial/JTAG configured for console");
+        usb_serial.split()
+    };
+
+    // Configure UART0 for console (headless boards without USB)
+    #[cfg(feature = "uart-console")]
+    let (console_rx, console_tx) = {
+        let baud_rate = match option_env!("CONSOLE_BAUD").map(str::parse::<u32>) {
+            None => DEFAULT_CONSOLE_BAUD,
+            Some(Ok(rate)) if iot_hal::SUPPORTED_BAUD_RATES.contains(&rate) => rate,
+            Some(_) => {
+                rprintln!("[MAIN-APP] WARNING: Unsupported CONSOLE_BAUD, using {}", DEFAULT_CONSOLE_BAUD);
+                DEFAULT_CONSOLE_BAUD
+            }
+        };
+        let uart_config = esp_hal::uart::Config::default().with_baudrate(baud_rate);
+        let uart = esp_hal::uart::Uart::new(peripherals.UART0, uart_config)
+            .unwrap()
+            .with_tx(peripherals.GPIO21)
+            .with_rx(peripherals.GPIO20)
+            .into_async();
+        rprintln!("[MAIN-APP] UART0 console configured (TX: GPIO21, RX: GPIO20, {} baud)", baud_rate);
+        uart.split()
+    };
     
     // Initialize performance monitoring system
     rprintln!("[MAIN-APP] Initializing performance monitoring system...");
-    let performance_monitor = PerformanceMonitor::new();
+    let performance_monitor = PerformanceMonitor::new().with_boot_clock(boot_clock());
     let memory_tracker = Mutex::new(MemoryTracker::new());
     let performance_analyzer = Mutex::new(PerformanceAnalyzer::new());
     
     let performance_monitor_ref = PERFORMANCE_MONITOR_CELL.init(performance_monitor);
     MEMORY_TRACKER_CELL.init(memory_tracker);
-    PERFORMANCE_ANALYZER_CELL.init(performance_analyzer);
+    let performance_analyzer_ref = PERFORMANCE_ANALYZER_CELL.init(performance_analyzer);
     
     rprintln!("[MAIN-APP] Performance monitoring system initialized");
     
@@ -1355,23 +3189,52 @@ async fn main(spawner: Spawner) {
     
     // Initialize iot-hal platform for status LED and other abstractions
     rprintln!("[MAIN-APP] Initializing IoT HAL platform for status LED...");
-    match Esp32C3Platform::initialize().await {
+    let device_info = match Esp32C3Platform::initialize().await {
         Ok(platform) => {
             rprintln!("[MAIN-APP] IoT HAL platform initialized successfully");
+            let device_info = platform.device_info();
             let platform_ref = PLATFORM_CELL.init(platform);
             spawner.spawn(status_led_task(platform_ref)).ok();
+            device_info
         }
         Err(e) => {
             rprintln!("[MAIN-APP] WARNING: IoT HAL platform initialization failed: {:?}", e);
             rprintln!("[MAIN-APP] Continuing without platform abstraction");
+            iot_hal::esp32c3::utils::read_device_info()
         }
-    };
+    }.with_firmware_version(env!("CARGO_PKG_VERSION"));
+    let mut device_info = device_info;
+    if panic_reason.is_some() {
+        device_info.reset_reason = ResetReason::Panic;
+    }
+    rprintln!("[MAIN-APP] Device: {}", device_info);
+    *DEVICE_INFO.lock().await = Some(device_info);
+    *PANIC_REASON.lock().await = panic_reason;
+    rprintln!("[MAIN-APP] Last reset: {}", last_reset_description().await);
+    
+    // Mount the sensor data log before the sensor task starts appending
+    let log_region = flash_region(REGION_DATA_LOG);
+    match DataLogger::new(FlashStorage::new(), log_region.offset, log_region.size as usize) {
+        Ok(logger) => {
+            rprintln!("[MAIN-APP] Data log mounted - {} of {} records in use", logger.len(), logger.capacity());
+            *DATA_LOGGER.lock().await = Some(logger);
+        }
+        Err(e) => {
+            rprintln!("[MAIN-APP] WARNING: Data log unavailable: {:?}", e);
+        }
+    }
     
     // Spawn core operational tasks (always available)
-    spawner.spawn(sensor_task(i2c, performance_monitor_ref)).ok();
-    spawner.spawn(console_task(usb_tx, usb_rx)).ok();
-    spawner.spawn(performance_monitor_task(performance_monitor_ref)).ok();
+    spawner.spawn(sensor_task(i2c, hardware_config.i2c.sensor_address, performance_monitor_ref)).ok();
+    spawner.spawn(console_task(console_tx, console_rx, wifi_manager_ref)).ok();
+    spawner.spawn(performance_monitor_task(performance_monitor_ref, performance_analyzer_ref)).ok();
     spawner.spawn(system_monitor_task()).ok();
+    if OTA_TRIAL.load(Ordering::Relaxed) && spawner.spawn(ota_confirm_task()).is_err() {
+        rprintln!("[MAIN-APP] ERROR: Failed to spawn OTA confirm task - new firmware rolls back on next reset");
+    }
+    if option_env!("SELFTEST_AT_BOOT") == Some("true") && spawner.spawn(selftest_task(wifi_manager_ref)).is_err() {
+        rprintln!("[MAIN-APP] ERROR: Failed to spawn self-test task");
+    }
     
     // Only spawn network tasks if WiFi is available
     if has_wifi {
@@ -1388,6 +3251,18 @@ async fn main(spawner: Spawner) {
                 }
                 Err(_) => rprintln!("[MAIN-APP] ERROR: Failed to spawn MQTT task - task queue full?"),
             }
+            if spawner.spawn(wifi_task(wifi_ref)).is_err() {
+                rprintln!("[MAIN-APP] ERROR: Failed to spawn WiFi task - connection info is not refreshed");
+            }
+            if spawner.spawn(ntp_task(wifi_ref)).is_err() {
+                rprintln!("[MAIN-APP] ERROR: Failed to spawn NTP task - task queue full?");
+            }
+            match subscribe_config_changes() {
+                Ok(changes) if spawner.spawn(config_watch_task(wifi_ref, changes)).is_ok() => {
+                    WIFI_LIVE_UPDATE.store(true, Ordering::Relaxed);
+                }
+                _ => rprintln!("[MAIN-APP] ERROR: Failed to start config watcher - WiFi changes need a restart"),
+            }
         } else {
             rprintln!("[MAIN-APP] WARNING: WiFi manager reference not available for MQTT task");
         }
@@ -23,7 +23,7 @@ use rtt_target::{rprintln, rtt_init_print};
 #[esp_hal_embassy::main]
 async fn main(_spawner: Spawner) -> ! {
     // Initialize heap allocator for dynamic allocations
-    esp_alloc::heap_allocator!(size: 72 * 1024);
+    esp_alloc::heap_allocator!(size: iot_config::HEAP_SIZE);
     
     // Initialize RTT for debugging output
     rtt_init_print!();
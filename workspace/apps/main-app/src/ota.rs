@@ -0,0 +1,424 @@
+//! Firmware updates over HTTP for the `ota <url>` console command.
+//!
+//! [`OtaUpdater::update`] downloads an ESP-IDF application image (as written
+//! by `espflash save-image`) into the inactive OTA slot, verifies it and
+//! selects it for the next boot; the caller restarts. Slot bookkeeping,
+//! verification and rollback live in `iot_storage::ota`.
+//!
+//! A dropped connection resumes where it stopped with an HTTP `Range`
+//! request; servers without range support resend the whole body and the
+//! bytes already written are skipped. URLs and response heads are parsed by
+//! `mqtt_embassy::http`. The host must be an IPv4 address, the network stack
+//! has no DNS.
+//!
+//! `https://` URLs need the `ota-https` feature and an RNG from
+//! [`OtaUpdater::with_rng`]. The server certificate is not verified: TLS keeps
+//! the image private on the wire but does not authenticate the server, so
+//! against an active attacker it is no stronger than `http://`.
+
+use core::fmt;
+
+use embassy_net::tcp::TcpSocket;
+use embassy_net::Stack;
+use embassy_time::{with_timeout, Duration, Timer};
+use embedded_io_async::{Read, Write};
+use esp_storage::FlashStorage;
+use iot_storage::{ImageInfo, OtaPartitions, OtaWriter, StorageError};
+use mqtt_embassy::http::{self, ContentRange, HttpError, HttpUrl};
+
+/// Longest accepted URL path
+const MAX_OTA_PATH_LEN: usize = 96;
+
+/// Reconnects after a dropped download before giving up
+const OTA_MAX_RESUMES: u32 = 5;
+/// Pause before a reconnect
+const OTA_RESUME_DELAY_MS: u64 = 2000;
+/// TCP connect timeout
+const OTA_CONNECT_TIMEOUT_SECS: u64 = 10;
+/// Longest silence from the server while receiving
+const OTA_READ_TIMEOUT_SECS: u64 = 15;
+/// Room for the status line and headers of a response
+const OTA_HEADER_BUFFER: usize = 1024;
+/// TLS record buffers; a server may send records of up to 16 KiB
+#[cfg(feature = "ota-https")]
+const OTA_TLS_READ_RECORD: usize = 16640;
+#[cfg(feature = "ota-https")]
+const OTA_TLS_WRITE_RECORD: usize = 4096;
+
+/// Why an update failed
+#[derive(Debug)]
+pub enum OtaError {
+    /// Not `http[s]://<ipv4>[:port]/<path>`
+    InvalidUrl(&'static str),
+    /// The URL uses a scheme this build cannot speak
+    Unsupported(&'static str),
+    /// No OTA partitions, or flash access failed
+    Storage(StorageError),
+    /// TCP connection failed
+    Connect,
+    /// TLS handshake with the server failed
+    #[cfg(feature = "ota-https")]
+    Tls,
+    /// The server answered with an unexpected status code
+    Status(u16),
+    /// Malformed or unsupported HTTP response
+    Protocol(&'static str),
+    /// The connection dropped more often than `OTA_MAX_RESUMES`
+    Interrupted,
+    /// The server stopped sending
+    Timeout,
+}
+
+impl fmt::Display for OtaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OtaError::InvalidUrl(what) => write!(f, "{}, URL must be http[s]://<ipv4>[:port]/<path>", what),
+            OtaError::Unsupported(what) => write!(f, "{}", what),
+            OtaError::Storage(StorageError::ConfigurationError(msg)) => write!(f, "{} (see partitions.csv)", msg),
+            OtaError::Storage(StorageError::CorruptedData) => write!(f, "image failed verification"),
+            OtaError::Storage(StorageError::CapacityExceeded) => write!(f, "image larger than the OTA slot"),
+            OtaError::Storage(e) => write!(f, "flash error: {:?}", e),
+            OtaError::Connect => write!(f, "could not connect to server"),
+            #[cfg(feature = "ota-https")]
+            OtaError::Tls => write!(f, "TLS handshake failed"),
+            OtaError::Status(code) => write!(f, "server returned HTTP {}", code),
+            OtaError::Protocol(what) => write!(f, "bad HTTP response: {}", what),
+            OtaError::Interrupted => write!(f, "download interrupted {} times", OTA_MAX_RESUMES + 1),
+            OtaError::Timeout => write!(f, "server stopped responding"),
+        }
+    }
+}
+
+impl From<StorageError> for OtaError {
+    fn from(error: StorageError) -> Self {
+        OtaError::Storage(error)
+    }
+}
+
+impl From<HttpError> for OtaError {
+    fn from(error: HttpError) -> Self {
+        match error {
+            HttpError::InvalidUrl(what) => OtaError::InvalidUrl(what),
+            HttpError::TlsUnsupported => OtaError::Unsupported("https needs a build with the ota-https feature"),
+            HttpError::InvalidResponse(what) => OtaError::Protocol(what),
+            HttpError::ConnectionFailed => OtaError::Connect,
+            HttpError::Timeout => OtaError::Timeout,
+            _ => OtaError::Protocol("unexpected HTTP error"),
+        }
+    }
+}
+
+/// Download progress, reported after every received chunk
+#[derive(Clone, Copy)]
+pub struct OtaProgress {
+    /// Image bytes written so far
+    pub received: u32,
+    /// Image size announced by the server
+    pub total: u32,
+}
+
+impl OtaProgress {
+    /// Completed share in percent
+    pub fn percent(&self) -> u32 {
+        (self.received as u64 * 100 / self.total.max(1) as u64) as u32
+    }
+}
+
+/// Splits `url` with the HTTP client's parser and checks the path fits a request
+fn parse_url(url: &str) -> Result<HttpUrl<'_>, OtaError> {
+    #[cfg(feature = "ota-https")]
+    let url = http::parse_url_with_tls(url)?;
+    #[cfg(not(feature = "ota-https"))]
+    let url = http::parse_url(url)?;
+
+    if url.path.len() > MAX_OTA_PATH_LEN || url.path.contains(char::is_whitespace) {
+        return Err(OtaError::InvalidUrl("path too long or contains spaces"));
+    }
+    Ok(url)
+}
+
+/// What one HTTP request delivered
+enum Transfer {
+    /// The image is complete
+    Complete,
+    /// The connection closed early; resume from `OtaWriter::written`
+    Dropped,
+}
+
+/// Downloads firmware images over the WiFi network stack
+pub struct OtaUpdater {
+    stack: Stack<'static>,
+    #[cfg(feature = "ota-https")]
+    rng: Option<esp_hal::rng::Rng>,
+}
+
+impl OtaUpdater {
+    pub fn new(stack: Stack<'static>) -> Self {
+        Self {
+            stack,
+            #[cfg(feature = "ota-https")]
+            rng: None,
+        }
+    }
+
+    /// Random source for the TLS handshake of `https://` downloads
+    #[cfg(feature = "ota-https")]
+    pub fn with_rng(mut self, rng: esp_hal::rng::Rng) -> Self {
+        self.rng = Some(rng);
+        self
+    }
+
+    /// Download the image at `url` into the inactive slot and select it
+    ///
+    /// `progress` is called after every chunk. On success the new image boots
+    /// after the next reset and must confirm itself on that boot, otherwise
+    /// the following boot returns to the current firmware.
+    pub async fn update<P: FnMut(OtaProgress)>(&self, url: &str, mut progress: P) -> Result<ImageInfo, OtaError> {
+        let url = parse_url(url)?;
+        let mut ota = OtaPartitions::new(FlashStorage::new())?;
+        let mut writer = ota.begin_update()?;
+        rprintln!("[OTA] Downloading {}://{}{} into slot {}",
+                  if url.tls { "https" } else { "http" }, url.authority, url.path, writer.slot());
+
+        let mut total = None;
+        let mut resumes = 0;
+        loop {
+            match self.fetch(&url, &mut writer, &mut total, &mut progress).await {
+                Ok(Transfer::Complete) => break,
+                Ok(Transfer::Dropped) | Err(OtaError::Connect) | Err(OtaError::Timeout) if resumes < OTA_MAX_RESUMES => {
+                    resumes += 1;
+                    rprintln!("[OTA] Connection lost at {} bytes, resuming ({}/{})", writer.written(), resumes, OTA_MAX_RESUMES);
+                    Timer::after(Duration::from_millis(OTA_RESUME_DELAY_MS)).await;
+                }
+                Ok(Transfer::Dropped) => return Err(OtaError::Interrupted),
+                Err(e) => return Err(e),
+            }
+        }
+
+        let info = writer.finish()?;
+        rprintln!("[OTA] Image verified: {} bytes, {} segments, slot {} selected for next boot",
+                  info.size, info.segments, info.slot);
+        Ok(info)
+    }
+
+    /// One GET request, continuing the image at `writer.written()`
+    async fn fetch<P: FnMut(OtaProgress)>(
+        &self,
+        url: &HttpUrl<'_>,
+        writer: &mut OtaWriter<'_, FlashStorage>,
+        total: &mut Option<u32>,
+        progress: &mut P,
+    ) -> Result<Transfer, OtaError> {
+        let mut rx_buffer = [0u8; 2048];
+        let mut tx_buffer = [0u8; 512];
+        let mut socket = TcpSocket::new(self.stack, &mut rx_buffer, &mut tx_buffer);
+
+        let connected = with_timeout(
+            Duration::from_secs(OTA_CONNECT_TIMEOUT_SECS),
+            socket.connect((url.host, url.port)),
+        ).await;
+        if !matches!(connected, Ok(Ok(()))) {
+            socket.abort();
+            return Err(OtaError::Connect);
+        }
+
+        let resume_at = writer.written();
+        let request = alloc::format!(
+            "GET {} HTTP/1.1\r\nHost: {}\r\nRange: bytes={}-\r\nConnection: close\r\n\r\n",
+            url.path, url.authority, resume_at
+        );
+
+        #[cfg(feature = "ota-https")]
+        let result = if url.tls {
+            self.exchange_tls(&mut socket, request.as_bytes(), resume_at, writer, total, progress).await
+        } else {
+            Self::exchange(&mut socket, request.as_bytes(), resume_at, writer, total, progress).await
+        };
+        #[cfg(not(feature = "ota-https"))]
+        let result = Self::exchange(&mut socket, request.as_bytes(), resume_at, writer, total, progress).await;
+
+        socket.close();
+        result
+    }
+
+    /// Open a TLS session over `socket`, then run the request through it
+    #[cfg(feature = "ota-https")]
+    async fn exchange_tls<P: FnMut(OtaProgress)>(
+        &self,
+        socket: &mut TcpSocket<'_>,
+        request: &[u8],
+        resume_at: u32,
+        writer: &mut OtaWriter<'_, FlashStorage>,
+        total: &mut Option<u32>,
+        progress: &mut P,
+    ) -> Result<Transfer, OtaError> {
+        use embedded_tls::{Aes128GcmSha256, TlsConfig, TlsConnection, TlsContext, UnsecureProvider};
+
+        let Some(rng) = self.rng.clone() else {
+            return Err(OtaError::Unsupported("https needs an RNG, see OtaUpdater::with_rng"));
+        };
+
+        // Record buffers live on the heap for the length of one request
+        let mut read_record = alloc::vec![0u8; OTA_TLS_READ_RECORD];
+        let mut write_record = alloc::vec![0u8; OTA_TLS_WRITE_RECORD];
+        let config = TlsConfig::new();
+        let mut tls = TlsConnection::new(socket, &mut read_record, &mut write_record);
+
+        // UnsecureProvider skips certificate verification, see the module docs
+        let provider = UnsecureProvider::new::<Aes128GcmSha256>(TlsRng(rng));
+        let opened = with_timeout(
+            Duration::from_secs(OTA_CONNECT_TIMEOUT_SECS),
+            tls.open(TlsContext::new(&config, provider)),
+        ).await;
+        match opened {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                rprintln!("[OTA] TLS handshake failed: {:?}", e);
+                return Err(OtaError::Tls);
+            }
+            Err(_) => return Err(OtaError::Timeout),
+        }
+
+        let result = Self::exchange(&mut tls, request, resume_at, writer, total, progress).await;
+        let _ = tls.close().await;
+        result
+    }
+
+    /// Send the request, then stream the response body into `writer`
+    async fn exchange<S: Read + Write, P: FnMut(OtaProgress)>(
+        stream: &mut S,
+        request: &[u8],
+        resume_at: u32,
+        writer: &mut OtaWriter<'_, FlashStorage>,
+        total: &mut Option<u32>,
+        progress: &mut P,
+    ) -> Result<Transfer, OtaError> {
+        if stream.write_all(request).await.is_err() || stream.flush().await.is_err() {
+            return Ok(Transfer::Dropped);
+        }
+        Self::receive(stream, resume_at, writer, total, progress).await
+    }
+
+    /// Parse the response and stream its body into `writer`
+    async fn receive<S: Read, P: FnMut(OtaProgress)>(
+        stream: &mut S,
+        resume_at: u32,
+        writer: &mut OtaWriter<'_, FlashStorage>,
+        total: &mut Option<u32>,
+        progress: &mut P,
+    ) -> Result<Transfer, OtaError> {
+        // Read until the end of the headers; body bytes may follow in the same read
+        let mut buffer = [0u8; OTA_HEADER_BUFFER];
+        let mut filled = 0;
+        let head_len = loop {
+            if let Some(len) = http::response_head_len(&buffer[..filled]) {
+                break len;
+            }
+            if filled == buffer.len() {
+                return Err(OtaError::Protocol("headers too long"));
+            }
+            match read(stream, &mut buffer[filled..]).await? {
+                0 => return Ok(Transfer::Dropped),
+                n => filled += n,
+            }
+        };
+
+        let head = core::str::from_utf8(&buffer[..head_len]).map_err(|_| OtaError::Protocol("headers not UTF-8"))?;
+        let head = http::parse_response_head(head)?;
+        if head.chunked {
+            return Err(OtaError::Protocol("chunked transfer encoding"));
+        }
+        let content_length = head.content_length.map(image_size).transpose()?;
+        let range_total = head.content_range.and_then(|range| range.total).map(image_size).transpose()?;
+
+        // Bytes of this body that are already in flash
+        let mut skip = match head.status {
+            206 => {
+                // The body must continue exactly where the image stopped
+                if !matches!(head.content_range, Some(ContentRange { range: Some((first, _)), .. }) if first == resume_at as usize) {
+                    return Err(OtaError::Protocol("Content-Range does not match the requested range"));
+                }
+                *total = range_total.or(*total);
+                0
+            }
+            200 => {
+                *total = content_length.or(*total);
+                resume_at
+            }
+            // Everything was already received
+            416 if range_total.or(*total) == Some(resume_at) => return Ok(Transfer::Complete),
+            code => return Err(OtaError::Status(code)),
+        };
+        let Some(expected) = *total else {
+            return Err(OtaError::Protocol("no Content-Length"));
+        };
+        if expected > writer.capacity() {
+            return Err(OtaError::Storage(StorageError::CapacityExceeded));
+        }
+
+        let mut body = &buffer[head_len..filled];
+        let mut chunk = [0u8; 1024];
+        loop {
+            let unseen = (skip as usize).min(body.len());
+            skip -= unseen as u32;
+            if body.len() > unseen {
+                writer.write(&body[unseen..])?;
+                progress(OtaProgress { received: writer.written(), total: expected });
+            }
+            if writer.written() == expected {
+                return Ok(Transfer::Complete);
+            }
+
+            match read(stream, &mut chunk).await? {
+                0 => return Ok(Transfer::Dropped),
+                n => body = &chunk[..n],
+            }
+        }
+    }
+}
+
+/// Image size from a response header; anything past `u32` cannot fit a slot
+fn image_size(size: usize) -> Result<u32, OtaError> {
+    u32::try_from(size).map_err(|_| OtaError::Storage(StorageError::CapacityExceeded))
+}
+
+/// Read from `stream`; a reset connection reads as end of stream
+async fn read<S: Read>(stream: &mut S, buffer: &mut [u8]) -> Result<usize, OtaError> {
+    match with_timeout(Duration::from_secs(OTA_READ_TIMEOUT_SECS), stream.read(buffer)).await {
+        Ok(Ok(n)) => Ok(n),
+        Ok(Err(_)) => Ok(0),
+        Err(_) => Err(OtaError::Timeout),
+    }
+}
+
+/// Hardware RNG as the `rand_core` source embedded-tls expects
+///
+/// The RNG draws on radio noise, which is running while WiFi is up.
+#[cfg(feature = "ota-https")]
+struct TlsRng(esp_hal::rng::Rng);
+
+#[cfg(feature = "ota-https")]
+impl rand_core::RngCore for TlsRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.random()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        (self.0.random() as u64) << 32 | self.0.random() as u64
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let word = self.0.random().to_le_bytes();
+            chunk.copy_from_slice(&word[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "ota-https")]
+impl rand_core::CryptoRng for TlsRng {}
@@ -0,0 +1,140 @@
+//! Power-on self-test shared by the `selftest` console command and the
+//! optional boot run (`SELFTEST_AT_BOOT=true`).
+//!
+//! Every step runs under its own timeout, so a hung bus or an unreachable
+//! broker fails that step only and the remaining subsystems are still checked.
+//! The report renders as a text table or as JSON for the console's JSON mode.
+
+use core::fmt::Write;
+use core::future::Future;
+
+use alloc::format;
+use alloc::string::String;
+use embassy_time::{with_timeout, Duration, Instant};
+
+/// Number of subsystems checked: sensor, wifi, broker, storage, led
+pub const SELFTEST_STEPS: usize = 5;
+
+/// Short explanation attached to every step result
+pub type Detail = heapless::String<48>;
+
+/// What a step reports back: `Ok` on pass, `Err` on failure, both with a detail
+pub type StepOutcome = Result<Detail, Detail>;
+
+/// Build a [`Detail`], truncating text that does not fit
+pub fn detail(args: core::fmt::Arguments<'_>) -> Detail {
+    struct Truncating<'a>(&'a mut Detail);
+
+    impl Write for Truncating<'_> {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            for c in s.chars() {
+                if self.0.push(c).is_err() {
+                    break;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    let mut text = Detail::new();
+    let _ = Truncating(&mut text).write_fmt(args);
+    text
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum StepStatus {
+    Pass,
+    Fail,
+    Timeout,
+}
+
+impl StepStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StepStatus::Pass => "PASS",
+            StepStatus::Fail => "FAIL",
+            StepStatus::Timeout => "TIMEOUT",
+        }
+    }
+}
+
+pub struct StepResult {
+    pub name: &'static str,
+    pub status: StepStatus,
+    pub detail: Detail,
+    pub duration_ms: u64,
+}
+
+#[derive(Default)]
+pub struct SelfTestReport {
+    steps: heapless::Vec<StepResult, SELFTEST_STEPS>,
+}
+
+impl SelfTestReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run one step, giving up after `timeout`
+    pub async fn run<F>(&mut self, name: &'static str, timeout: Duration, step: F)
+    where
+        F: Future<Output = StepOutcome>,
+    {
+        let start = Instant::now();
+        let (status, detail) = match with_timeout(timeout, step).await {
+            Ok(Ok(detail)) => (StepStatus::Pass, detail),
+            Ok(Err(detail)) => (StepStatus::Fail, detail),
+            Err(_) => (StepStatus::Timeout, self::detail(format_args!("no answer within {}ms", timeout.as_millis()))),
+        };
+        let _ = self.steps.push(StepResult {
+            name,
+            status,
+            detail,
+            duration_ms: start.elapsed().as_millis(),
+        });
+    }
+
+    pub fn steps(&self) -> &[StepResult] {
+        &self.steps
+    }
+
+    /// Steps that failed or timed out
+    pub fn failures(&self) -> usize {
+        self.steps.iter().filter(|step| step.status != StepStatus::Pass).count()
+    }
+
+    pub fn passed(&self) -> bool {
+        self.failures() == 0
+    }
+
+    /// Summary table for the console and RTT, lines end in `\r\n`
+    pub fn render_text(&self) -> String {
+        let mut text = String::from("=== Self-Test ===\r\n");
+        text.push_str("Subsystem  Result   Time    Detail\r\n");
+        for step in &self.steps {
+            text.push_str(&format!("{:<10} {:<8} {:>5}ms  {}\r\n",
+                                   step.name, step.status.as_str(), step.duration_ms, step.detail));
+        }
+        text.push_str(&format!("Summary: {}/{} passed - {}\r\n",
+                               self.steps.len() - self.failures(), self.steps.len(),
+                               if self.passed() { "PASS" } else { "FAIL" }));
+        text
+    }
+
+    /// Compact JSON: `{"passed":bool,"failures":n,"steps":[{"name","result","detail","ms"}]}`
+    pub fn to_json(&self) -> String {
+        let mut json = format!("{{\"passed\":{},\"failures\":{},\"steps\":[", self.passed(), self.failures());
+        for (i, step) in self.steps.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            // Details may quote device output, so let serde escape them
+            let detail: heapless::String<320> = serde_json_core::to_string(step.detail.as_str())
+                .unwrap_or_else(|_| heapless::String::try_from("\"\"").unwrap_or_default());
+            json.push_str(&format!("{{\"name\":\"{}\",\"result\":\"{}\",\"detail\":{},\"ms\":{}}}",
+                                   step.name, step.status.as_str(), detail, step.duration_ms));
+        }
+        json.push_str("]}");
+        json
+    }
+}
@@ -1,10 +1,13 @@
 //! Configuration Management - Single file module
 
 use iot_config::{EmbeddedConfig, IoTSystemConfig};
+use iot_hal::HardwareConfig;
 
 pub struct MinimalConfig {
     #[allow(dead_code)]  // Reserved for future config expansion
     pub system: IoTSystemConfig,
+    /// Board layout: I2C pins and sensor address
+    pub hardware: HardwareConfig,
 }
 
 impl MinimalConfig {
@@ -20,7 +23,7 @@ impl MinimalConfig {
             }
         };
 
-        Self { system }
+        Self { system, hardware: HardwareConfig::esp32c3_devkit() }
     }
 
     pub fn sensor_interval_secs(&self) -> u32 {
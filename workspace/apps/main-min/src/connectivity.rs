@@ -23,7 +23,8 @@ impl MqttManager {
     #[cfg(feature = "mqtt")]
     pub async fn run(
         wifi_manager: &'static wifi_embassy::WiFiManager,
-        sensor_signal: &'static embassy_sync::signal::Signal<embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex, crate::sensor::SensorData>,
+        sensor_channel: &'static crate::sensor::SensorChannel,
+        boot_clock: iot_common::BootClock,
     ) -> ! {
         rprintln!("[MQTT] Starting MQTT publishing with sensor data...");
         
@@ -43,7 +44,9 @@ impl MqttManager {
         // Setup MQTT
         let stack = wifi_manager.get_stack();
         let mqtt_config = mqtt_embassy::MqttConfig::default();
-        rprintln!("[MQTT] Broker: {}:{}", mqtt_config.broker_ip, mqtt_config.broker_port);
+        for broker in &mqtt_config.brokers {
+            rprintln!("[MQTT] Broker: {}", broker);
+        }
         let mqtt_client = mqtt_embassy::MqttClient::new(mqtt_config);
         let mut rx_buffer = [0u8; 1024];
         let mut tx_buffer = [0u8; 1024];
@@ -71,7 +74,7 @@ impl MqttManager {
         loop {
             // Wait for either sensor data or heartbeat timeout (10s cycles)
             let timeout_future = Timer::after(Duration::from_secs(10));
-            let sensor_future = sensor_signal.wait();
+            let sensor_future = sensor_channel.receive();
             
             match select(timeout_future, sensor_future).await {
                 Either::Second(sensor_data) => {
@@ -134,18 +137,22 @@ impl MqttManager {
                     
                     // Publish status every 12 cycles (120 seconds / 2 minutes)
                     if status_counter % 12 == 0 {
-                        let uptime_secs = status_counter * 10;
                         let status_num = status_counter / 12;
-                        rprintln!("[STATUS] #{} uptime={}s heap=30k rssi=-45dBm -> '{}'", status_num, uptime_secs, status_topic);
                         
-                        // TODO: Remove 'app' field in production - use new instead
-                        let device_status = mqtt_embassy::DeviceStatus::new_with_app(
+                        // TODO: Remove 'app' field in production
+                        let device_status = mqtt_embassy::DeviceStatus::from_boot(
+                            boot_clock.boot_instant(),
                             "online",
-                            uptime_secs,
-                            30000,
+                            esp_alloc::HEAP.free() as u32,
                             -45,
                             "main-min"  // Source identification for debugging
                         );
+                        rprintln!("[STATUS] #{} uptime={}s heap={} rssi=-45dBm queue={}/{} dropped={} broker={} -> '{}'",
+                                 status_num, device_status.uptime, device_status.free_heap,
+                                 sensor_channel.len(), crate::sensor::SENSOR_QUEUE_DEPTH,
+                                 crate::sensor::SENSOR_QUEUE_DROPS.load(core::sync::atomic::Ordering::Relaxed),
+                                 mqtt_client.active_broker().map_or(alloc::string::String::from("none"), |broker| alloc::format!("{}", broker)),
+                                 status_topic);
                         
                         match mqtt_client.connect(stack, &mut rx_buffer, &mut tx_buffer).await {
                             Ok(mut socket) => {
@@ -172,7 +179,8 @@ impl MqttManager {
     #[cfg(not(feature = "mqtt"))]
     pub async fn run(
         _wifi_manager: &'static wifi_embassy::WiFiManager,
-        _sensor_signal: &'static embassy_sync::signal::Signal<embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex, crate::sensor::SensorData>,
+        _sensor_channel: &'static crate::sensor::SensorChannel,
+        _boot_clock: iot_common::BootClock,
     ) -> ! {
         rprintln!("[MQTT-HEARTBEAT] MQTT feature disabled - no heartbeat publishing");
         loop {
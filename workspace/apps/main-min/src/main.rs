@@ -14,8 +14,7 @@ mod monitor;
 
 use embassy_executor::Spawner;
 use embassy_time::{Duration, Timer};
-use embassy_sync::signal::Signal;
-use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::Channel;
 use esp_hal::{
     timer::timg::TimerGroup,
     i2c::master::{I2c, Config},
@@ -25,6 +24,7 @@ use panic_rtt_target as _;
 use rtt_target::{rprintln, rtt_init_print};
 use static_cell::StaticCell;
 use heapless::String;
+use iot_common::BootClock;
 use core::str::FromStr;
 
 // Use our modules
@@ -40,16 +40,16 @@ static I2C_STATIC: StaticCell<I2c<'static, esp_hal::Blocking>> = StaticCell::new
 #[cfg(feature = "wifi")]
 static WIFI_MANAGER: StaticCell<wifi_embassy::WiFiManager> = StaticCell::new();
 
-// Sensor data signal for communication between sensor and MQTT tasks
-static SENSOR_DATA_SIGNAL: Signal<CriticalSectionRawMutex, sensor::SensorData> = Signal::new();
+// Bounded queue from the sensor task to the MQTT task, see `SENSOR_QUEUE_DEPTH`
+static SENSOR_DATA_CHANNEL: sensor::SensorChannel = Channel::new();
 
 #[esp_hal_embassy::main]
 async fn main(spawner: Spawner) -> ! {
     rtt_init_print!();
     rprintln!("Minimal ESP32-C3 IoT Starting");
 
-    // Initialize heap allocator (increased for WiFi stack like main-app)
-    esp_alloc::heap_allocator!(size: 64 * 1024);
+    // Heap sized by iot-config for the enabled features (72KB with WiFi, 16KB sensor-only)
+    esp_alloc::heap_allocator!(size: iot_config::HEAP_SIZE);
 
     // Initialize ESP32-C3 peripherals (like working main-app)
     let peripherals = esp_hal::init(esp_hal::Config::default());
@@ -57,6 +57,7 @@ async fn main(spawner: Spawner) -> ! {
     // Initialize Embassy time driver
     let timer_group1 = TimerGroup::new(peripherals.TIMG1);
     esp_hal_embassy::init(timer_group1.timer0);
+    let boot_clock = BootClock::start();
     
     // Initialize WiFi using wifi-embassy module with error handling (like main-app)
     #[cfg(feature = "wifi")]
@@ -64,6 +65,7 @@ async fn main(spawner: Spawner) -> ! {
         let wifi_config = wifi_embassy::WiFiConfig {
             ssid: String::from_str(env!("WIFI_SSID", "Set WIFI_SSID")).unwrap(),
             password: String::from_str(env!("WIFI_PASSWORD", "Set WIFI_PASSWORD")).unwrap(),
+            timeouts: wifi_embassy::WiFiTimeouts::default(),
         };
         
         rprintln!("[MAIN-MIN] Initializing WiFi manager...");
@@ -90,29 +92,38 @@ async fn main(spawner: Spawner) -> ! {
     #[cfg(not(feature = "wifi"))]
     let wifi_manager: Option<&wifi_embassy::WiFiManager> = None;
 
-    // I2C setup (copy exact pattern from main-app)
-    let i2c = I2c::new(peripherals.I2C0, Config::default())
-        .unwrap()
-        .with_sda(peripherals.GPIO8)
-        .with_scl(peripherals.GPIO9);
-    let i2c_static = I2C_STATIC.init(i2c);
-
     // Load configuration
     let config = MinimalConfig::load();
     rprintln!("Sensor interval: {}s", config.sensor_interval_secs());
+
+    // I2C setup on the pins named by the board configuration
+    let i2c_config = &config.hardware.i2c;
+    config.hardware.validate().expect("invalid hardware configuration");
+    let (sda, scl) = iot_hal::esp32c3::utils::i2c_pins(
+        i2c_config,
+        peripherals.GPIO8,
+        peripherals.GPIO9,
+        peripherals.GPIO18,
+        peripherals.GPIO19,
+    ).expect("I2C pins validated above");
+    let i2c = I2c::new(peripherals.I2C0, Config::default())
+        .unwrap()
+        .with_sda(sda)
+        .with_scl(scl);
+    let i2c_static = I2C_STATIC.init(i2c);
     
     // Start all tasks using modular approach like main-app
-    spawner.spawn(sensor_task(i2c_static, config.sensor_interval_secs(), &SENSOR_DATA_SIGNAL)).unwrap();
+    spawner.spawn(sensor_task(i2c_static, i2c_config.sensor_address, config.sensor_interval_secs(), &SENSOR_DATA_CHANNEL)).unwrap();
     
     // Only start MQTT task if WiFi manager was successfully initialized
     #[cfg(feature = "mqtt")]
     if let Some(wifi_manager_ref) = wifi_manager {
-        spawner.spawn(mqtt_task(wifi_manager_ref, &SENSOR_DATA_SIGNAL)).unwrap();
+        spawner.spawn(mqtt_task(wifi_manager_ref, &SENSOR_DATA_CHANNEL, boot_clock)).unwrap();
     } else {
         rprintln!("[MAIN-MIN] MQTT task skipped - WiFi not available");
     }
     
-    spawner.spawn(monitor_task()).unwrap();
+    spawner.spawn(monitor_task(boot_clock)).unwrap();
 
     rprintln!("All tasks started");
 
@@ -127,10 +138,11 @@ async fn main(spawner: Spawner) -> ! {
 #[embassy_executor::task]
 async fn sensor_task(
     i2c: &'static mut I2c<'static, esp_hal::Blocking>, 
+    sensor_address: u8,
     interval: u32,
-    sensor_signal: &'static embassy_sync::signal::Signal<embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex, sensor::SensorData>,
+    sensor_channel: &'static sensor::SensorChannel,
 ) {
-    SensorManager::run(i2c, interval, sensor_signal).await;
+    SensorManager::run(i2c, sensor_address, interval, sensor_channel).await;
 }
 
 
@@ -138,12 +150,13 @@ async fn sensor_task(
 #[embassy_executor::task]
 async fn mqtt_task(
     wifi_manager: &'static wifi_embassy::WiFiManager,
-    sensor_signal: &'static embassy_sync::signal::Signal<embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex, sensor::SensorData>,
+    sensor_channel: &'static sensor::SensorChannel,
+    boot_clock: BootClock,
 ) {
-    MqttManager::run(wifi_manager, sensor_signal).await;
+    MqttManager::run(wifi_manager, sensor_channel, boot_clock).await;
 }
 
 #[embassy_executor::task]
-async fn monitor_task() {
-    SystemMonitor::run().await;
+async fn monitor_task(boot_clock: BootClock) {
+    SystemMonitor::run(boot_clock).await;
 }
\ No newline at end of file
@@ -1,17 +1,18 @@
 //! System monitoring - Minimal status reporting
 
 use embassy_time::{Duration, Timer};
+use iot_common::BootClock;
 
 pub struct SystemMonitor;
 
 impl SystemMonitor {
-    pub async fn run() -> ! {
+    pub async fn run(boot_clock: BootClock) -> ! {
         let mut count = 0u32;
         
         loop {
             Timer::after(Duration::from_secs(300)).await; // 5 minutes
             count += 1;
-            rtt_target::rprintln!("Status #{}: System operational", count);
+            rtt_target::rprintln!("Status #{}: System operational, uptime {}s", count, boot_clock.uptime().as_secs());
         }
     }
 }
\ No newline at end of file
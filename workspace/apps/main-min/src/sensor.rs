@@ -1,5 +1,9 @@
 //! Sensor Management - Single file module
 
+use core::sync::atomic::AtomicU32;
+
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::Channel;
 use embassy_time::{Duration, Timer};
 use esp_hal::i2c::master::I2c;
 
@@ -15,19 +19,46 @@ pub struct SensorData {
     pub count: u32,
 }
 
+/// Readings queued for the MQTT task
+///
+/// Each slot holds one 16-byte `SensorData`. At the 30s interval four slots
+/// cover two minutes of a slow or reconnecting broker; a longer stall drops the
+/// oldest readings instead of holding up the sensor. More slots ride out longer
+/// outages but publish a burst of stale readings when the broker comes back.
+pub const SENSOR_QUEUE_DEPTH: usize = 4;
+
+/// Bounded queue between the sensor and MQTT tasks
+pub type SensorChannel = Channel<CriticalSectionRawMutex, SensorData, SENSOR_QUEUE_DEPTH>;
+
+/// Readings dropped because the queue was full; written only by the sensor task
+pub static SENSOR_QUEUE_DROPS: AtomicU32 = AtomicU32::new(0);
+
+/// Queues a reading, dropping the oldest one if the MQTT task has fallen behind
+#[cfg(feature = "sensor")]
+fn queue_reading(sensor_channel: &SensorChannel, sensor_data: SensorData) {
+    if let Err(embassy_sync::channel::TrySendError::Full(sensor_data)) = sensor_channel.try_send(sensor_data) {
+        if let Ok(oldest) = sensor_channel.try_receive() {
+            let drops = SENSOR_QUEUE_DROPS.load(core::sync::atomic::Ordering::Relaxed) + 1;
+            SENSOR_QUEUE_DROPS.store(drops, core::sync::atomic::Ordering::Relaxed);
+            rtt_target::rprintln!("[SENSOR] Queue full - dropped reading #{} ({} dropped total)", oldest.count, drops);
+        }
+        let _ = sensor_channel.try_send(sensor_data);
+    }
+}
+
 pub struct SensorManager;
 
 impl SensorManager {
     #[cfg(feature = "sensor")]
     pub async fn run(
         i2c: &'static mut I2c<'static, esp_hal::Blocking>,
+        sensor_address: u8,
         interval_secs: u32,
-        sensor_signal: &'static embassy_sync::signal::Signal<embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex, SensorData>,
+        sensor_channel: &'static SensorChannel,
     ) -> ! {
-        rtt_target::rprintln!("Sensor: Starting BME280...");
+        rtt_target::rprintln!("Sensor: Starting BME280 at 0x{:02X}...", sensor_address);
 
-        // Initialize BME280 exactly like main-app
-        let i2c_device = I2cDevice::new(i2c, 0x76);
+        let i2c_device = I2cDevice::new(i2c, sensor_address);
         let mut bme280 = BME280::new(i2c_device);
 
         // Retry initialization 
@@ -57,15 +88,15 @@ impl SensorManager {
                         measurements.humidity
                     );
                     
-                    // Send sensor data to MQTT task via signal
+                    // Queue sensor data for the MQTT task
                     let sensor_data = SensorData {
                         temperature: measurements.temperature,
                         pressure: measurements.pressure,
                         humidity: measurements.humidity,
                         count,
                     };
-                    sensor_signal.signal(sensor_data);
-                    rtt_target::rprintln!("[SENSOR] Data sent to MQTT task");
+                    queue_reading(sensor_channel, sensor_data);
+                    rtt_target::rprintln!("[SENSOR] Data queued for MQTT task ({}/{})", sensor_channel.len(), SENSOR_QUEUE_DEPTH);
                 }
                 Err(_) => {
                     rtt_target::rprintln!("[SENSOR] Read error");
@@ -79,8 +110,9 @@ impl SensorManager {
     #[cfg(not(feature = "sensor"))]
     pub async fn run(
         _i2c: &'static mut I2c<'static, esp_hal::Blocking>,
+        _sensor_address: u8,
         _interval_secs: u32,
-        _sensor_signal: &'static embassy_sync::signal::Signal<embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex, SensorData>,
+        _sensor_channel: &'static SensorChannel,
     ) -> ! {
         rtt_target::rprintln!("Sensor: Disabled");
         loop {
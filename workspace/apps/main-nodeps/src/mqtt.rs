@@ -290,14 +290,7 @@ impl SimpleMqttClient {
         variable_header.extend_from_slice(client_id_bytes);
         
         // Remaining length
-        let remaining_len = variable_header.len();
-        if remaining_len < 128 {
-            packet.push(remaining_len as u8);
-        } else {
-            // Multi-byte remaining length encoding
-            packet.push(0x80 | (remaining_len & 0x7F) as u8);
-            packet.push((remaining_len >> 7) as u8);
-        }
+        push_remaining_length(&mut packet, variable_header.len());
         
         packet.extend_from_slice(&variable_header);
         packet
@@ -327,17 +320,29 @@ impl SimpleMqttClient {
         flags |= (self.config.qos & 0x03) << 1; // QoS bits (1-2)
         packet.push(flags);
         
-        // Remaining length (EXACT working pattern)
-        let remaining_len = variable_header.len();
-        if remaining_len < 128 {
-            packet.push(remaining_len as u8);
-        } else {
-            packet.push(0x80 | (remaining_len & 0x7F) as u8);
-            packet.push((remaining_len >> 7) as u8);
-        }
+        // Remaining length
+        push_remaining_length(&mut packet, variable_header.len());
         
         packet.extend_from_slice(&variable_header);
         packet
     }
 
-}
\ No newline at end of file
+}
+
+/// Append the MQTT variable-length "remaining length" field
+///
+/// Seven bits per byte with 0x80 as continuation flag, up to four bytes;
+/// lengths of 16 KB and more need the third byte.
+fn push_remaining_length(packet: &mut Vec<u8>, mut length: usize) {
+    loop {
+        let mut byte = (length % 128) as u8;
+        length /= 128;
+        if length > 0 {
+            byte |= 0x80;
+        }
+        packet.push(byte);
+        if length == 0 {
+            break;
+        }
+    }
+}
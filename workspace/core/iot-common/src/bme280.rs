@@ -0,0 +1,362 @@
+//! BME280 Calibration and Compensation
+//!
+//! The datasheet's integer compensation formulas, shared by the
+//! `bme280-embassy` driver and by host-side mocks. They are pure functions of
+//! the factory calibration and the raw ADC values, so a mock register image
+//! produces exactly the readings real hardware would.
+//!
+//! [`SensorOffsets`] applies a per-unit field calibration on top.
+//!
+//! [`DATASHEET_EXAMPLE`] is a complete register image (calibration plus raw
+//! data) that compensates to 25.08 °C, 1006.53 hPa and 49.43 %RH.
+
+/// First temperature/pressure calibration register (dig_T1 LSB)
+pub const CALIB_TP_START_REG: u8 = 0x88;
+
+/// Length of the temperature/pressure calibration block (0x88-0x9F)
+pub const CALIB_TP_LEN: usize = 24;
+
+/// Humidity calibration register dig_H1
+pub const CALIB_H1_REG: u8 = 0xA1;
+
+/// First humidity calibration register (dig_H2 LSB)
+pub const CALIB_H_START_REG: u8 = 0xE1;
+
+/// Length of the humidity calibration block (0xE1-0xE7)
+pub const CALIB_H_LEN: usize = 7;
+
+/// First data register (press_msb); data runs through hum_lsb at 0xFE
+pub const DATA_START_REG: u8 = 0xF7;
+
+/// Length of the burst-readable data block (0xF7-0xFE)
+pub const DATA_LEN: usize = 8;
+
+/// BME280 calibration coefficients
+///
+/// Factory trimming values read from NVM, named after the `dig_*` registers
+/// in the datasheet. Together with `t_fine` from the temperature compensation
+/// they turn raw ADC values into physical units.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CalibrationData {
+    // Temperature coefficients
+    /// dig_T1
+    pub dig_t1: u16,
+    /// dig_T2
+    pub dig_t2: i16,
+    /// dig_T3
+    pub dig_t3: i16,
+
+    // Pressure coefficients
+    /// dig_P1
+    pub dig_p1: u16,
+    /// dig_P2
+    pub dig_p2: i16,
+    /// dig_P3
+    pub dig_p3: i16,
+    /// dig_P4
+    pub dig_p4: i16,
+    /// dig_P5
+    pub dig_p5: i16,
+    /// dig_P6
+    pub dig_p6: i16,
+    /// dig_P7
+    pub dig_p7: i16,
+    /// dig_P8
+    pub dig_p8: i16,
+    /// dig_P9
+    pub dig_p9: i16,
+
+    // Humidity coefficients
+    /// dig_H1
+    pub dig_h1: u8,
+    /// dig_H2
+    pub dig_h2: i16,
+    /// dig_H3
+    pub dig_h3: u8,
+    /// dig_H4 (12-bit, sign extended)
+    pub dig_h4: i16,
+    /// dig_H5 (12-bit, sign extended)
+    pub dig_h5: i16,
+    /// dig_H6
+    pub dig_h6: i8,
+}
+
+impl CalibrationData {
+    /// Decode the calibration registers
+    ///
+    /// `tp` is the block at 0x88-0x9F, `h1` register 0xA1 and `h` the block
+    /// at 0xE1-0xE7, where dig_H4 and dig_H5 share the nibbles of 0xE5.
+    pub fn from_registers(tp: &[u8; CALIB_TP_LEN], h1: u8, h: &[u8; CALIB_H_LEN]) -> Self {
+        let word = |i: usize| [tp[i], tp[i + 1]];
+
+        let mut dig_h4 = ((h[3] as i16) << 4) | ((h[4] as i16) & 0x0F);
+        let mut dig_h5 = ((h[5] as i16) << 4) | ((h[4] as i16) >> 4);
+
+        // Sign extend 12-bit values
+        if dig_h4 > 2047 { dig_h4 -= 4096; }
+        if dig_h5 > 2047 { dig_h5 -= 4096; }
+
+        Self {
+            dig_t1: u16::from_le_bytes(word(0)),
+            dig_t2: i16::from_le_bytes(word(2)),
+            dig_t3: i16::from_le_bytes(word(4)),
+            dig_p1: u16::from_le_bytes(word(6)),
+            dig_p2: i16::from_le_bytes(word(8)),
+            dig_p3: i16::from_le_bytes(word(10)),
+            dig_p4: i16::from_le_bytes(word(12)),
+            dig_p5: i16::from_le_bytes(word(14)),
+            dig_p6: i16::from_le_bytes(word(16)),
+            dig_p7: i16::from_le_bytes(word(18)),
+            dig_p8: i16::from_le_bytes(word(20)),
+            dig_p9: i16::from_le_bytes(word(22)),
+            dig_h1: h1,
+            dig_h2: i16::from_le_bytes([h[0], h[1]]),
+            dig_h3: h[2],
+            dig_h4,
+            dig_h5,
+            dig_h6: h[6] as i8,
+        }
+    }
+}
+
+/// Raw ADC values of one measurement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawSample {
+    /// 20-bit temperature ADC value
+    pub adc_t: i32,
+    /// 20-bit pressure ADC value
+    pub adc_p: i32,
+    /// 16-bit humidity ADC value
+    pub adc_h: i32,
+}
+
+impl RawSample {
+    /// Decode the data registers 0xF7-0xFE
+    pub fn from_registers(data: &[u8; DATA_LEN]) -> Self {
+        let adc_20 = |msb: u8, lsb: u8, xlsb: u8| {
+            ((msb as i32) << 12) | ((lsb as i32) << 4) | ((xlsb as i32) >> 4)
+        };
+
+        Self {
+            adc_p: adc_20(data[0], data[1], data[2]),
+            adc_t: adc_20(data[3], data[4], data[5]),
+            adc_h: ((data[6] as i32) << 8) | (data[7] as i32),
+        }
+    }
+
+    /// Encode as the data registers 0xF7-0xFE
+    pub fn to_registers(&self) -> [u8; DATA_LEN] {
+        let split_20 = |adc: i32| [(adc >> 12) as u8, (adc >> 4) as u8, ((adc & 0x0F) << 4) as u8];
+        let p = split_20(self.adc_p);
+        let t = split_20(self.adc_t);
+
+        [p[0], p[1], p[2], t[0], t[1], t[2], (self.adc_h >> 8) as u8, self.adc_h as u8]
+    }
+
+    /// Compensate to (°C, hPa, %RH)
+    pub fn compensate(&self, calib: &CalibrationData) -> (f32, f32, f32) {
+        let (temperature, t_fine) = compensate_temperature(self.adc_t, calib);
+        let pressure = compensate_pressure(self.adc_p, t_fine, calib);
+        let humidity = compensate_humidity(self.adc_h, t_fine, calib);
+        (temperature, pressure, humidity)
+    }
+}
+
+/// Field-calibration offsets added to compensated readings
+///
+/// Individual parts read a little off, and a sensor mounted near the radio
+/// reads warm from self-heating; a reference thermometer/hygrometer gives the
+/// correction. Offsets never feed back into compensation (`t_fine` stays the
+/// sensor's own), they only shift the final values.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SensorOffsets {
+    /// Added to the temperature, in °C
+    pub temperature: f32,
+    /// Added to the relative humidity, in %RH
+    pub humidity: f32,
+    /// Added to the pressure, in hPa
+    pub pressure: f32,
+}
+
+impl SensorOffsets {
+    /// No correction
+    pub const NONE: Self = Self { temperature: 0.0, humidity: 0.0, pressure: 0.0 };
+
+    /// Offsets in °C, %RH and hPa
+    pub const fn new(temperature: f32, humidity: f32, pressure: f32) -> Self {
+        Self { temperature, humidity, pressure }
+    }
+
+    /// Correct compensated (°C, hPa, %RH); humidity is clamped to 0-100 %RH
+    pub fn apply(&self, (temperature, pressure, humidity): (f32, f32, f32)) -> (f32, f32, f32) {
+        (
+            temperature + self.temperature,
+            pressure + self.pressure,
+            (humidity + self.humidity).clamp(0.0, 100.0),
+        )
+    }
+}
+
+/// Complete register image of a BME280 for mocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegisterImage {
+    /// Registers 0x88-0x9F
+    pub calib_tp: [u8; CALIB_TP_LEN],
+    /// Register 0xA1
+    pub calib_h1: u8,
+    /// Registers 0xE1-0xE7
+    pub calib_h: [u8; CALIB_H_LEN],
+    /// Raw measurement placed in 0xF7-0xFE
+    pub sample: RawSample,
+}
+
+impl RegisterImage {
+    /// Decoded calibration coefficients
+    pub fn calibration(&self) -> CalibrationData {
+        CalibrationData::from_registers(&self.calib_tp, self.calib_h1, &self.calib_h)
+    }
+
+    /// Compensated (°C, hPa, %RH) of the stored sample
+    pub fn expected(&self) -> (f32, f32, f32) {
+        self.sample.compensate(&self.calibration())
+    }
+}
+
+/// Datasheet example register image
+///
+/// Temperature and pressure coefficients and ADC values are the worked
+/// example of the Bosch datasheet (adc_T = 519888, adc_P = 415148). The
+/// datasheet has no humidity example, so typical coefficients of a
+/// production part are used with adc_H = 29000.
+pub const DATASHEET_EXAMPLE: RegisterImage = RegisterImage {
+    calib_tp: [
+        0x70, 0x6B, // dig_T1 = 27504
+        0x43, 0x67, // dig_T2 = 26435
+        0x18, 0xFC, // dig_T3 = -1000
+        0x7D, 0x8E, // dig_P1 = 36477
+        0x43, 0xD6, // dig_P2 = -10685
+        0xD0, 0x0B, // dig_P3 = 3024
+        0x27, 0x0B, // dig_P4 = 2855
+        0x8C, 0x00, // dig_P5 = 140
+        0xF9, 0xFF, // dig_P6 = -7
+        0x8C, 0x3C, // dig_P7 = 15500
+        0xF8, 0xC6, // dig_P8 = -14600
+        0x70, 0x17, // dig_P9 = 6000
+    ],
+    calib_h1: 75,
+    calib_h: [
+        0x6A, 0x01, // dig_H2 = 362
+        0x00,       // dig_H3 = 0
+        0x13, 0x29, // dig_H4 = 313 (0x13 << 4 | 0x9)
+        0x03,       // dig_H5 = 50 (0x03 << 4 | 0x2)
+        0x1E,       // dig_H6 = 30
+    ],
+    sample: RawSample { adc_t: 519888, adc_p: 415148, adc_h: 29000 },
+};
+
+/// Temperature in °C and `t_fine` for the pressure and humidity formulas
+pub fn compensate_temperature(adc_t: i32, calib: &CalibrationData) -> (f32, i32) {
+    let var1 = (((adc_t >> 3) - ((calib.dig_t1 as i32) << 1)) * (calib.dig_t2 as i32)) >> 11;
+    let var2 = (((((adc_t >> 4) - (calib.dig_t1 as i32)) *
+                  ((adc_t >> 4) - (calib.dig_t1 as i32))) >> 12) *
+                 (calib.dig_t3 as i32)) >> 14;
+
+    let t_fine = var1 + var2;
+    let temperature = (t_fine * 5 + 128) >> 8;
+
+    (temperature as f32 / 100.0, t_fine)
+}
+
+/// Pressure in hPa
+pub fn compensate_pressure(adc_p: i32, t_fine: i32, calib: &CalibrationData) -> f32 {
+    let mut var1: i64 = (t_fine as i64) - 128000;
+    let mut var2: i64 = var1 * var1 * (calib.dig_p6 as i64);
+    var2 += (var1 * (calib.dig_p5 as i64)) << 17;
+    var2 += (calib.dig_p4 as i64) << 35;
+    var1 = ((var1 * var1 * (calib.dig_p3 as i64)) >> 8) +
+           ((var1 * (calib.dig_p2 as i64)) << 12);
+    var1 = (((1i64 << 47) + var1) * (calib.dig_p1 as i64)) >> 33;
+
+    if var1 == 0 {
+        return 0.0; // Avoid division by zero
+    }
+
+    let mut p: i64 = 1048576 - (adc_p as i64);
+    p = (((p << 31) - var2) * 3125) / var1;
+    var1 = ((calib.dig_p9 as i64) * (p >> 13) * (p >> 13)) >> 25;
+    var2 = ((calib.dig_p8 as i64) * p) >> 19;
+    p = ((p + var1 + var2) >> 8) + ((calib.dig_p7 as i64) << 4);
+
+    (p as f32) / 25600.0
+}
+
+/// Relative humidity in %RH
+pub fn compensate_humidity(adc_h: i32, t_fine: i32, calib: &CalibrationData) -> f32 {
+    if adc_h == 0x8000 {
+        return 0.0; // Invalid reading (BMP280 or measurement not ready)
+    }
+
+    // 64-bit intermediates so out-of-range raw values clamp instead of overflowing
+    let adc_h = adc_h as i64;
+    let v_x1 = (t_fine as i64) - 76800;
+
+    let mut humidity = ((adc_h << 14) - ((calib.dig_h4 as i64) << 20) -
+                        ((calib.dig_h5 as i64) * v_x1) + 16384) >> 15;
+    humidity *= ((((((v_x1 * (calib.dig_h6 as i64)) >> 10) *
+                   (((v_x1 * (calib.dig_h3 as i64)) >> 11) + 32768)) >> 10) + 2097152) *
+                 (calib.dig_h2 as i64) + 8192) >> 14;
+    humidity -= ((((humidity >> 15) * (humidity >> 15)) >> 7) * (calib.dig_h1 as i64)) >> 4;
+
+    // Q22.10 %RH, 419430400 = 100%
+    let humidity = humidity.clamp(0, 419430400);
+    (humidity >> 12) as f32 / 1024.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_datasheet_example_calibration() {
+        let calib = DATASHEET_EXAMPLE.calibration();
+        assert_eq!((calib.dig_t1, calib.dig_t2, calib.dig_t3), (27504, 26435, -1000));
+        assert_eq!((calib.dig_p1, calib.dig_p6, calib.dig_p9), (36477, -7, 6000));
+        assert_eq!((calib.dig_h1, calib.dig_h2, calib.dig_h3), (75, 362, 0));
+        assert_eq!((calib.dig_h4, calib.dig_h5, calib.dig_h6), (313, 50, 30));
+    }
+
+    #[test]
+    fn test_datasheet_example_compensation() {
+        let calib = DATASHEET_EXAMPLE.calibration();
+        let (temperature, t_fine) = compensate_temperature(519888, &calib);
+        assert_eq!(t_fine, 128422);
+        assert_eq!(temperature, 25.08);
+
+        let (_, pressure, humidity) = DATASHEET_EXAMPLE.expected();
+        assert!((pressure - 1006.5325).abs() < 0.001);
+        assert!((humidity - 49.4316).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_sensor_offsets() {
+        let reading = (25.08, 1006.53, 49.43);
+        assert_eq!(SensorOffsets::NONE.apply(reading), reading);
+        assert_eq!(SensorOffsets::default(), SensorOffsets::NONE);
+
+        let (temperature, pressure, humidity) = SensorOffsets::new(-1.5, 2.0, 0.5).apply(reading);
+        assert!((temperature - 23.58).abs() < 0.001);
+        assert!((pressure - 1007.03).abs() < 0.001);
+        assert!((humidity - 51.43).abs() < 0.001);
+
+        // Corrected humidity stays physical
+        assert_eq!(SensorOffsets::new(0.0, 60.0, 0.0).apply(reading).2, 100.0);
+        assert_eq!(SensorOffsets::new(0.0, -60.0, 0.0).apply(reading).2, 0.0);
+    }
+
+    #[test]
+    fn test_raw_sample_register_round_trip() {
+        let registers = DATASHEET_EXAMPLE.sample.to_registers();
+        assert_eq!(registers, [0x65, 0x5A, 0xC0, 0x7E, 0xED, 0x00, 0x71, 0x48]);
+        assert_eq!(RawSample::from_registers(&registers), DATASHEET_EXAMPLE.sample);
+    }
+}
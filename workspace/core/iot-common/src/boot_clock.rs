@@ -0,0 +1,143 @@
+//! # Boot Clock
+//!
+//! Console, device status, performance reports and log lines all show how
+//! long the device has been up. They read it from one [`BootClock`] captured
+//! at startup, so the numbers agree wherever they appear.
+//!
+//! Following the [`standard_timing`](crate::standard_timing) split, the
+//! async applications use [`BootClock`] on the embassy-time clock, and the
+//! synchronous ones use [`SyncBootClock`] over any millisecond counter.
+//!
+//! ```rust,ignore
+//! use iot_common::BootClock;
+//!
+//! // Once in main, right after the time driver is up
+//! let boot_clock = BootClock::start();
+//!
+//! // Anywhere it was handed to
+//! rprintln!("[SYSTEM] Uptime: {}s", boot_clock.uptime().as_secs());
+//! ```
+
+use crate::standard_messages::TimestampProvider;
+
+/// Uptime clock for synchronous applications
+///
+/// Reads time from a millisecond counter that keeps running across the main
+/// loop, such as the HAL's monotonic timer, instead of estimating it from
+/// loop iterations.
+#[derive(Debug, Clone, Copy)]
+pub struct SyncBootClock {
+    now_ms: fn() -> u64,
+    boot_ms: u64,
+}
+
+impl SyncBootClock {
+    /// Starts the clock at the current reading of `now_ms`
+    pub fn start(now_ms: fn() -> u64) -> Self {
+        Self::from_boot_ms(now_ms, now_ms())
+    }
+
+    /// Clock whose boot happened at `boot_ms` on the `now_ms` counter
+    pub const fn from_boot_ms(now_ms: fn() -> u64, boot_ms: u64) -> Self {
+        Self { now_ms, boot_ms }
+    }
+
+    /// Counter reading at boot, in milliseconds
+    pub fn boot_instant(&self) -> u64 {
+        self.boot_ms
+    }
+
+    /// Time since boot
+    pub fn uptime(&self) -> core::time::Duration {
+        core::time::Duration::from_millis(self.uptime_ms())
+    }
+
+    /// Time since boot in milliseconds
+    pub fn uptime_ms(&self) -> u64 {
+        (self.now_ms)().saturating_sub(self.boot_ms)
+    }
+
+    /// Time since boot in whole seconds, saturating at `u32::MAX`
+    pub fn uptime_secs(&self) -> u32 {
+        (self.uptime_ms() / 1000).min(u32::MAX as u64) as u32
+    }
+}
+
+impl TimestampProvider for SyncBootClock {
+    fn get_timestamp_ms(&self) -> u64 {
+        self.uptime_ms()
+    }
+}
+
+/// Uptime clock on the embassy-time monotonic clock
+///
+/// `Copy`, so it can be handed to every task that reports uptime.
+#[cfg(feature = "embassy")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BootClock {
+    boot: embassy_time::Instant,
+}
+
+#[cfg(feature = "embassy")]
+impl BootClock {
+    /// Starts the clock now; call once the time driver is initialized
+    pub fn start() -> Self {
+        Self::from_instant(embassy_time::Instant::now())
+    }
+
+    /// Clock whose boot happened at `boot`
+    pub const fn from_instant(boot: embassy_time::Instant) -> Self {
+        Self { boot }
+    }
+
+    /// Instant the clock was started
+    pub fn boot_instant(&self) -> embassy_time::Instant {
+        self.boot
+    }
+
+    /// Time since boot
+    pub fn uptime(&self) -> embassy_time::Duration {
+        embassy_time::Instant::now().saturating_duration_since(self.boot)
+    }
+
+    /// Time since boot in whole seconds, saturating at `u32::MAX`
+    pub fn uptime_secs(&self) -> u32 {
+        self.uptime().as_secs().min(u32::MAX as u64) as u32
+    }
+}
+
+#[cfg(feature = "embassy")]
+impl TimestampProvider for BootClock {
+    fn get_timestamp_ms(&self) -> u64 {
+        self.uptime().as_millis()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{AtomicU64, Ordering};
+
+    use super::*;
+
+    static NOW_MS: AtomicU64 = AtomicU64::new(0);
+
+    fn now_ms() -> u64 {
+        NOW_MS.load(Ordering::Relaxed)
+    }
+
+    #[test]
+    fn test_sync_boot_clock_measures_from_start() {
+        NOW_MS.store(5_000, Ordering::Relaxed);
+        let clock = SyncBootClock::start(now_ms);
+        assert_eq!(clock.boot_instant(), 5_000);
+        assert_eq!(clock.uptime_ms(), 0);
+
+        NOW_MS.store(127_250, Ordering::Relaxed);
+        assert_eq!(clock.uptime(), core::time::Duration::from_millis(122_250));
+        assert_eq!(clock.uptime_secs(), 122);
+        assert_eq!(clock.get_timestamp_ms(), 122_250, "Timestamps agree with uptime");
+
+        let earlier = SyncBootClock::from_boot_ms(now_ms, 200_000);
+        assert_eq!(earlier.uptime_ms(), 0, "A counter behind the boot reading saturates");
+    }
+}
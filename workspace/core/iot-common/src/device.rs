@@ -0,0 +1,205 @@
+//! # Device Identification
+//!
+//! [`DeviceInfo`] identifies a physical board for fleet management: chip
+//! model and revision, the factory (eFuse) MAC address, the firmware version
+//! and why the chip last reset. The HAL fills it in
+//! (`HardwarePlatform::device_info()` in `iot-hal`), the console prints it and
+//! the MQTT client publishes it as a retained message, so a broker always
+//! knows which firmware each device runs.
+//!
+//! The MAC is also the natural source of stable per-device identifiers, see
+//! [`DeviceInfo::mac_hex`] and [`device_client_id`].
+
+use core::fmt::{self, Write};
+
+use heapless::String;
+use serde::{Serialize, Serializer};
+use serde::ser::SerializeStruct;
+
+/// Longest client ID every MQTT 3.1.1 broker must accept (spec section 3.1.3.1)
+pub const MQTT_MAX_CLIENT_ID_LEN: usize = 23;
+
+/// Prefix of the client IDs built by [`device_client_id`]
+pub const DEVICE_CLIENT_ID_PREFIX: &str = "esp32c3-";
+
+/// MQTT client ID derived from a MAC address
+pub type DeviceClientId = String<MQTT_MAX_CLIENT_ID_LEN>;
+
+/// Build a per-device MQTT client ID such as `esp32c3-AABBCCDDEEFF`
+///
+/// Two devices sharing a fixed client ID keep kicking each other off the
+/// broker; the factory MAC makes the ID unique and stable across reboots.
+/// Returns `None` if the ID would exceed [`MQTT_MAX_CLIENT_ID_LEN`].
+pub fn device_client_id(mac: &[u8; 6]) -> Option<DeviceClientId> {
+    let mut id = DeviceClientId::new();
+    id.push_str(DEVICE_CLIENT_ID_PREFIX).ok()?;
+    for byte in mac {
+        write!(id, "{:02X}", byte).ok()?;
+    }
+    Some(id)
+}
+
+/// Cause of the last chip reset, as far as the hardware reports it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResetReason {
+    /// Power was applied (cold boot)
+    PowerOn,
+    /// Software requested a reset (`software_reset`, restart command)
+    Software,
+    /// The panic handler reset the chip (software reset with a stashed reason)
+    Panic,
+    /// A watchdog (task, interrupt or RTC) expired
+    Watchdog,
+    /// Supply voltage dropped below the brownout threshold
+    Brownout,
+    /// Woke up from deep sleep
+    DeepSleep,
+    /// Reset by the USB serial/JTAG peripheral (e.g. flashing)
+    Usb,
+    /// Reported by the hardware but not one of the above
+    Other,
+    /// Not known (platform cannot tell, or mock)
+    #[default]
+    Unknown,
+}
+
+impl ResetReason {
+    /// Short lowercase name for logs and JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResetReason::PowerOn => "power_on",
+            ResetReason::Software => "software",
+            ResetReason::Panic => "panic",
+            ResetReason::Watchdog => "watchdog",
+            ResetReason::Brownout => "brownout",
+            ResetReason::DeepSleep => "deep_sleep",
+            ResetReason::Usb => "usb",
+            ResetReason::Other => "other",
+            ResetReason::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for ResetReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for ResetReason {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Identity of a physical device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceInfo {
+    /// Chip model, e.g. `"ESP32-C3"`
+    pub chip_model: &'static str,
+    /// Chip revision as `major * 100 + minor` (ESP-IDF convention, v0.4 = 4)
+    pub chip_revision: u16,
+    /// Factory MAC address burned into eFuse
+    pub mac: [u8; 6],
+    /// Firmware version, normally the application's `CARGO_PKG_VERSION`
+    pub firmware_version: &'static str,
+    /// Cause of the last reset
+    pub reset_reason: ResetReason,
+}
+
+impl DeviceInfo {
+    /// Replace the firmware version, e.g. with the application's own
+    /// `env!("CARGO_PKG_VERSION")` instead of the HAL's
+    pub fn with_firmware_version(mut self, version: &'static str) -> Self {
+        self.firmware_version = version;
+        self
+    }
+
+    /// MAC address as `AA:BB:CC:DD:EE:FF`
+    pub fn mac_string(&self) -> String<17> {
+        let mut out = String::new();
+        for (i, byte) in self.mac.iter().enumerate() {
+            if i > 0 {
+                let _ = out.push(':');
+            }
+            let _ = write!(out, "{:02X}", byte);
+        }
+        out
+    }
+
+    /// MAC address as 12 uppercase hex digits, `AABBCCDDEEFF`
+    ///
+    /// Stable for the lifetime of the chip, suitable for client IDs and topics.
+    pub fn mac_hex(&self) -> String<12> {
+        let mut out = String::new();
+        for byte in &self.mac {
+            let _ = write!(out, "{:02X}", byte);
+        }
+        out
+    }
+
+    /// MQTT client ID derived from the MAC, see [`device_client_id`]
+    pub fn client_id(&self) -> Option<DeviceClientId> {
+        device_client_id(&self.mac)
+    }
+
+    /// Serialize to JSON (max 192 bytes)
+    pub fn to_json(&self) -> Result<String<192>, &'static str> {
+        serde_json_core::to_string(self).map_err(|_| "JSON serialization failed")
+    }
+}
+
+impl fmt::Display for DeviceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} rev v{}.{}, MAC {}, firmware {}, last reset: {}",
+            self.chip_model,
+            self.chip_revision / 100,
+            self.chip_revision % 100,
+            self.mac_string(),
+            self.firmware_version,
+            self.reset_reason
+        )
+    }
+}
+
+impl Serialize for DeviceInfo {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("DeviceInfo", 5)?;
+        state.serialize_field("chip", self.chip_model)?;
+        state.serialize_field("revision", &self.chip_revision)?;
+        state.serialize_field("mac", &self.mac_string())?;
+        state.serialize_field("firmware", self.firmware_version)?;
+        state.serialize_field("reset_reason", &self.reset_reason)?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INFO: DeviceInfo = DeviceInfo {
+        chip_model: "ESP32-C3",
+        chip_revision: 4,
+        mac: [0x58, 0xCF, 0x79, 0x0A, 0x1B, 0xFF],
+        firmware_version: "1.0.0",
+        reset_reason: ResetReason::Watchdog,
+    };
+
+    #[test]
+    fn test_device_info_formats() {
+        assert_eq!(INFO.mac_string(), "58:CF:79:0A:1B:FF");
+        assert_eq!(INFO.mac_hex(), "58CF790A1BFF");
+        assert_eq!(INFO.client_id().unwrap(), "esp32c3-58CF790A1BFF");
+        assert_eq!(
+            INFO.to_json().unwrap(),
+            r#"{"chip":"ESP32-C3","revision":4,"mac":"58:CF:79:0A:1B:FF","firmware":"1.0.0","reset_reason":"watchdog"}"#
+        );
+
+        let mut text: String<128> = String::new();
+        write!(text, "{}", INFO.with_firmware_version("2.1.0")).unwrap();
+        assert_eq!(text, "ESP32-C3 rev v0.4, MAC 58:CF:79:0A:1B:FF, firmware 2.1.0, last reset: watchdog");
+    }
+}
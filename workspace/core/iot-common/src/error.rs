@@ -14,8 +14,9 @@
 use heapless::String;
 use core::fmt;
 use core::str::FromStr;
+use serde::{Deserialize, Serialize};
 
-use crate::{MAX_ERROR_MESSAGE_LEN, MAX_ERROR_CONTEXT_DEPTH};
+use crate::{MAX_ERROR_MESSAGE_LEN, MAX_ERROR_CONTEXT_DEPTH, MAX_ERROR_HISTORY_DEPTH};
 
 /// Bounded string type for error messages in embedded environment
 pub type ErrorMessage = String<MAX_ERROR_MESSAGE_LEN>;
@@ -187,6 +188,8 @@ pub enum HardwareError {
     PowerError(ErrorMessage),
     /// Clock configuration error
     ClockError(ErrorMessage),
+    /// On-die temperature sensor unavailable or out of range
+    TemperatureSensorError(ErrorMessage),
 }
 
 impl HardwareError {
@@ -200,6 +203,7 @@ impl HardwareError {
             HardwareError::InterruptError(_) => 3005,
             HardwareError::PowerError(_) => 3006,
             HardwareError::ClockError(_) => 3007,
+            HardwareError::TemperatureSensorError(_) => 3008,
         }
     }
 
@@ -219,6 +223,7 @@ impl fmt::Display for HardwareError {
             HardwareError::InterruptError(msg) => write!(f, "Interrupt error: {}", msg),
             HardwareError::PowerError(msg) => write!(f, "Power management error: {}", msg),
             HardwareError::ClockError(msg) => write!(f, "Clock configuration error: {}", msg),
+            HardwareError::TemperatureSensorError(msg) => write!(f, "Temperature sensor error: {}", msg),
         }
     }
 }
@@ -321,6 +326,116 @@ impl fmt::Display for SystemError {
     }
 }
 
+/// Error category, serialized by name for machine-readable fault reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ErrorCategory {
+    /// Sensor errors (codes 1xxx)
+    Sensor,
+    /// Network errors (codes 2xxx)
+    Network,
+    /// Hardware errors (codes 3xxx)
+    Hardware,
+    /// Configuration errors (codes 4xxx)
+    Configuration,
+    /// System errors (codes 5xxx)
+    System,
+}
+
+impl ErrorCategory {
+    /// Get error category name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCategory::Sensor => "Sensor",
+            ErrorCategory::Network => "Network",
+            ErrorCategory::Hardware => "Hardware",
+            ErrorCategory::Configuration => "Configuration",
+            ErrorCategory::System => "System",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One entry of an [`ErrorHistory`]: an error code and how often it repeated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorRecord {
+    /// Error code, as returned by [`IoTError::error_code`]
+    pub code: ErrorCode,
+    /// Category the code belongs to
+    pub category: ErrorCategory,
+    /// Consecutive occurrences folded into this entry
+    pub count: u16,
+}
+
+/// Bounded history of the most recent runtime errors
+///
+/// Keeps the last [`MAX_ERROR_HISTORY_DEPTH`] entries, oldest first. A
+/// repeat of the most recent code bumps its count instead of taking a new
+/// slot, so a flapping link cannot flush older faults out of the history.
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorHistory {
+    records: [ErrorRecord; MAX_ERROR_HISTORY_DEPTH],
+    len: usize,
+}
+
+impl ErrorHistory {
+    /// Create an empty history
+    pub const fn new() -> Self {
+        Self {
+            records: [ErrorRecord { code: 0, category: ErrorCategory::System, count: 0 }; MAX_ERROR_HISTORY_DEPTH],
+            len: 0,
+        }
+    }
+
+    /// Record an error, evicting the oldest entry when full
+    pub fn record(&mut self, error: &IoTError) {
+        let code = error.error_code();
+        if let Some(last) = self.records[..self.len].last_mut() {
+            if last.code == code {
+                last.count = last.count.saturating_add(1);
+                return;
+            }
+        }
+
+        if self.len == MAX_ERROR_HISTORY_DEPTH {
+            self.records.copy_within(1.., 0);
+            self.len -= 1;
+        }
+        self.records[self.len] = ErrorRecord { code, category: error.error_category(), count: 1 };
+        self.len += 1;
+    }
+
+    /// Recorded entries, oldest first
+    pub fn records(&self) -> &[ErrorRecord] {
+        &self.records[..self.len]
+    }
+
+    /// Most recent entry
+    pub fn last(&self) -> Option<&ErrorRecord> {
+        self.records().last()
+    }
+
+    /// Check if no error has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Forget all recorded errors
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl Default for ErrorHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Main IoT error type that encompasses all error categories
 #[derive(Debug, Clone)]
 pub struct IoTError {
@@ -417,6 +532,17 @@ impl IoTError {
         }
     }
 
+    /// Get error category for machine-readable reporting
+    pub fn error_category(&self) -> ErrorCategory {
+        match &self.kind {
+            IoTErrorKind::Sensor(_) => ErrorCategory::Sensor,
+            IoTErrorKind::Network(_) => ErrorCategory::Network,
+            IoTErrorKind::Hardware(_) => ErrorCategory::Hardware,
+            IoTErrorKind::Configuration(_) => ErrorCategory::Configuration,
+            IoTErrorKind::System(_) => ErrorCategory::System,
+        }
+    }
+
     /// Check if this is a sensor error
     pub fn is_sensor_error(&self) -> bool {
         matches!(self.kind, IoTErrorKind::Sensor(_))
@@ -639,6 +765,40 @@ pub mod utils {
         result
     }
 
+    /// Create error message from a value's `Display` output
+    ///
+    /// Truncates like [`error_message`], so driver errors keep their full
+    /// text when it fits and end in "..." when it does not.
+    pub fn display_message<T: fmt::Display + ?Sized>(value: &T) -> ErrorMessage {
+        struct Truncating {
+            message: ErrorMessage,
+            truncated: bool,
+        }
+
+        impl fmt::Write for Truncating {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                for c in s.chars() {
+                    if self.message.push(c).is_err() {
+                        self.truncated = true;
+                        return Err(fmt::Error);
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        let mut writer = Truncating { message: ErrorMessage::new(), truncated: false };
+        let _ = fmt::Write::write_fmt(&mut writer, format_args!("{}", value));
+
+        if writer.truncated {
+            while writer.message.len() > MAX_ERROR_MESSAGE_LEN.saturating_sub(3) {
+                writer.message.pop();
+            }
+            let _ = writer.message.push_str("...");
+        }
+        writer.message
+    }
+
     /// Create sensor error with message
     pub fn sensor_error(error_type: &str, msg: &str) -> IoTError {
         let error_msg = error_message(msg);
@@ -669,4 +829,65 @@ pub mod utils {
         };
         IoTError::network(network_error)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_history_dedupes_consecutive_errors() {
+        let mut history = ErrorHistory::new();
+        let timeout = utils::network_error("timeout", "broker timeout");
+        let i2c = utils::sensor_error("i2c_error", "no ack");
+
+        history.record(&timeout);
+        history.record(&timeout);
+        history.record(&i2c);
+        history.record(&timeout);
+
+        let codes: heapless::Vec<(ErrorCode, u16), 4> = history.records().iter()
+            .map(|record| (record.code, record.count))
+            .collect();
+        assert_eq!(codes.as_slice(), &[(2005, 2), (1002, 1), (2005, 1)]);
+        assert_eq!(history.last().unwrap().category, ErrorCategory::Network);
+        assert_eq!(history.records()[1].category.as_str(), "Sensor");
+    }
+
+    #[test]
+    fn test_error_history_evicts_oldest() {
+        let mut history = ErrorHistory::new();
+        let errors = [
+            utils::sensor_error("init_failed", "a"),
+            utils::sensor_error("i2c_error", "b"),
+        ];
+        for i in 0..MAX_ERROR_HISTORY_DEPTH + 1 {
+            history.record(&errors[i % 2]);
+        }
+
+        assert_eq!(history.records().len(), MAX_ERROR_HISTORY_DEPTH);
+        // The very first entry (1001) was evicted, so the history now opens with 1002
+        assert_eq!(history.records()[0].code, 1002);
+        assert_eq!(history.last().unwrap().code, errors[MAX_ERROR_HISTORY_DEPTH % 2].error_code());
+
+        history.clear();
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn test_error_record_json() {
+        let record = ErrorRecord { code: 2004, category: ErrorCategory::Network, count: 3 };
+        let json: String<64> = serde_json_core::to_string(&record).unwrap();
+        assert_eq!(json.as_str(), r#"{"code":2004,"category":"Network","count":3}"#);
+    }
+
+    #[test]
+    fn test_display_message_truncates_long_text() {
+        assert_eq!(utils::display_message(&"DHCP failed: no lease").as_str(), "DHCP failed: no lease");
+
+        let reason = "broker closed the connection while the client was still waiting for CONNACK";
+        let long = utils::display_message(&format_args!("Broker refused: {}", reason));
+        assert_eq!(long.len(), MAX_ERROR_MESSAGE_LEN);
+        assert!(long.starts_with("Broker refused: broker closed"));
+        assert!(long.ends_with("..."));
+    }
+}
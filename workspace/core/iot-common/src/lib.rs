@@ -9,8 +9,14 @@
 //! - **Unified Error Types**: Consistent error handling across all IoT modules
 //! - **No-std Compatible**: Works in embedded environments without heap allocation
 //! - **Error Context**: Preserves error context for debugging without heap allocation
+//! - **Error History**: Bounded, deduplicated fault history for status reports
+//! - **Log Buffer**: Ring of recent log lines for consoles without a debugger
+//! - **Leveled Logging**: `log_info!`-style macros with build-time and runtime levels
+//! - **Retry**: Bounded attempts with exponential backoff for async operations
+//! - **Boot Clock**: One uptime source for console, status messages and logs
 //! - **Error Conversion**: Automatic conversion from module-specific errors
 //! - **RTT Debugging**: Support for Real-Time Transfer debugging
+//! - **BME280 Compensation**: Datasheet formulas shared by the driver and mocks
 //!
 //! ## Usage
 //!
@@ -41,6 +47,13 @@ pub mod result;
 pub mod standard_messages;
 pub mod standard_timing;
 pub mod standard_config;
+pub mod network;
+pub mod bme280;
+pub mod device;
+pub mod log_buffer;
+pub mod logging;
+pub mod retry;
+pub mod boot_clock;
 
 #[cfg(feature = "testing")]
 pub mod testing;
@@ -48,12 +61,13 @@ pub mod testing;
 // Re-export main types for convenience
 pub use error::{
     IoTError, SensorError, NetworkError, HardwareError, 
-    ConfigError, SystemError, ErrorContext, ErrorCode
+    ConfigError, SystemError, ErrorContext, ErrorCode,
+    ErrorCategory, ErrorRecord, ErrorHistory
 };
 pub use result::{IoTResult, SensorResult, NetworkResult, HardwareResult};
 pub use standard_messages::{
     StandardSensorReading, StandardHeartbeat, StandardDeviceStatus,
-    IoTArchitecture, StandardTopics, TimestampProvider, SyncTimestampProvider,
+    IoTArchitecture, StandardTopics, TimestampProvider, SyncTimestampProvider, SharedTimestampProvider,
     LegacyFormat
 };
 pub use standard_timing::{
@@ -64,8 +78,20 @@ pub use standard_config::{
     SystemConfig, ConfigOverrides, ConfigBuilder, Feature
 };
 
+pub use network::{NetworkStackProvider, NetworkInfo, LinkStatus};
+pub use device::{DeviceInfo, ResetReason, DeviceClientId, device_client_id, MQTT_MAX_CLIENT_ID_LEN};
+pub use log_buffer::{LogBuffer, LogLine, LogLevel};
+pub use retry::{RetryPolicy, retry_with};
+pub use boot_clock::SyncBootClock;
+
 #[cfg(feature = "embassy")]
 pub use standard_timing::AsyncTimingDurations;
+#[cfg(feature = "embassy")]
+pub use standard_messages::UptimeTimestampProvider;
+#[cfg(feature = "embassy")]
+pub use retry::{retry, retry_with_timeout};
+#[cfg(feature = "embassy")]
+pub use boot_clock::BootClock;
 
 /// Current version of the iot-common library
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -74,4 +100,16 @@ pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const MAX_ERROR_MESSAGE_LEN: usize = 64;
 
 /// Maximum depth for error context chain
-pub const MAX_ERROR_CONTEXT_DEPTH: usize = 4;
\ No newline at end of file
+pub const MAX_ERROR_CONTEXT_DEPTH: usize = 4;
+
+/// Maximum number of entries kept in an [`ErrorHistory`]
+pub const MAX_ERROR_HISTORY_DEPTH: usize = 8;
+
+/// Number of lines kept in a [`LogBuffer`]
+pub const MAX_LOG_LINES: usize = 32;
+
+/// Longest log line a [`LogBuffer`] keeps, in bytes; longer lines are truncated
+pub const MAX_LOG_LINE_LEN: usize = 96;
+
+/// Default maximum attempts for failed operations (see [`RetryPolicy`])
+pub const MAX_RETRY_ATTEMPTS: u32 = 3;
@@ -0,0 +1,209 @@
+//! # Recent Log Lines
+//!
+//! RTT output only reaches a connected debugger. [`LogBuffer`] keeps the last
+//! [`MAX_LOG_LINES`] log lines in a fixed `heapless` ring so a console can show
+//! them after the fact, e.g. the `[SENSOR] ERROR` lines leading up to an
+//! incident. Lines longer than [`MAX_LOG_LINE_LEN`] bytes are truncated; when
+//! the ring is full the oldest line is dropped.
+//!
+//! The buffer does not hook any logger by itself. An application tees its log
+//! macro into a shared buffer:
+//!
+//! ```rust,ignore
+//! static LOG_BUFFER: Mutex<CriticalSectionRawMutex, RefCell<LogBuffer>> =
+//!     Mutex::new(RefCell::new(LogBuffer::new()));
+//!
+//! fn log_line(args: core::fmt::Arguments<'_>) {
+//!     rtt_target::rprintln!("{}", args);
+//!     LOG_BUFFER.lock(|buffer| buffer.borrow_mut().push(uptime_ms(), args));
+//! }
+//! ```
+//!
+//! The severity of a line is taken from the `ERROR` / `WARNING` markers the
+//! firmware already writes after the `[TAG]` prefix, see [`LogLevel::of`].
+
+use core::fmt::{self, Write};
+
+use heapless::{Deque, String};
+
+use crate::{MAX_LOG_LINES, MAX_LOG_LINE_LEN};
+
+/// Severity of a log line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    /// Progress and status messages
+    Info,
+    /// Degraded operation, e.g. `[MAIN-APP] WARNING: ...`
+    Warning,
+    /// Failures, e.g. `[SENSOR] ERROR: ...`
+    Error,
+}
+
+impl LogLevel {
+    /// Classify a line by its `ERROR` or `WARNING`/`WARN` marker
+    pub fn of(line: &str) -> Self {
+        if line.contains("ERROR") {
+            LogLevel::Error
+        } else if line.contains("WARN") {
+            LogLevel::Warning
+        } else {
+            LogLevel::Info
+        }
+    }
+
+    /// Short lowercase name for logs and JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Info => "info",
+            LogLevel::Warning => "warning",
+            LogLevel::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One captured log line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLine {
+    /// Time the line was logged (ms since boot)
+    pub timestamp_ms: u64,
+    /// Severity derived from the text
+    pub level: LogLevel,
+    /// The line, at most [`MAX_LOG_LINE_LEN`] bytes
+    pub text: String<MAX_LOG_LINE_LEN>,
+    /// Whether the end of the line was cut off
+    pub truncated: bool,
+}
+
+/// Ring of the most recent log lines
+#[derive(Debug)]
+pub struct LogBuffer {
+    lines: Deque<LogLine, MAX_LOG_LINES>,
+    dropped: u32,
+}
+
+impl LogBuffer {
+    /// Create an empty buffer
+    pub const fn new() -> Self {
+        Self {
+            lines: Deque::new(),
+            dropped: 0,
+        }
+    }
+
+    /// Capture a formatted line, dropping the oldest when full
+    pub fn push(&mut self, timestamp_ms: u64, args: fmt::Arguments<'_>) {
+        let mut line = TruncatingLine { text: String::new(), truncated: false };
+        let _ = line.write_fmt(args);
+        let text = line.text;
+
+        if self.lines.is_full() {
+            self.lines.pop_front();
+            self.dropped = self.dropped.saturating_add(1);
+        }
+        let _ = self.lines.push_back(LogLine {
+            timestamp_ms,
+            level: LogLevel::of(&text),
+            text,
+            truncated: line.truncated,
+        });
+    }
+
+    /// Captured lines of at least `min_level`, oldest first
+    pub fn lines(&self, min_level: LogLevel) -> impl Iterator<Item = &LogLine> {
+        self.lines.iter().filter(move |line| line.level >= min_level)
+    }
+
+    /// Number of captured lines
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Check if nothing has been captured
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Lines pushed out of the ring since boot or the last [`clear`](Self::clear)
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    /// Forget all captured lines
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.dropped = 0;
+    }
+}
+
+impl Default for LogBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `fmt::Write` target that keeps what fits and flags the rest
+struct TruncatingLine {
+    text: String<MAX_LOG_LINE_LEN>,
+    truncated: bool,
+}
+
+impl Write for TruncatingLine {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if self.text.push(c).is_err() {
+                self.truncated = true;
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_buffer_keeps_recent_lines() {
+        let mut buffer = LogBuffer::new();
+        for n in 0..MAX_LOG_LINES + 2 {
+            buffer.push(n as u64, format_args!("[SENSOR] Reading #{}", n));
+        }
+        buffer.push(100, format_args!("[SENSOR] ERROR: Failed to read BME280: {}", "I2C timeout"));
+        buffer.push(101, format_args!("[MAIN-APP] WARNING: Data log unavailable"));
+
+        assert_eq!(buffer.len(), MAX_LOG_LINES);
+        assert_eq!(buffer.dropped(), 4);
+        assert_eq!(buffer.lines(LogLevel::Info).next().unwrap().text, "[SENSOR] Reading #4");
+
+        {
+            let mut errors = buffer.lines(LogLevel::Error);
+            assert_eq!(errors.next().unwrap().text, "[SENSOR] ERROR: Failed to read BME280: I2C timeout");
+            assert!(errors.next().is_none());
+        }
+        assert_eq!(buffer.lines(LogLevel::Warning).count(), 2);
+
+        buffer.clear();
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.dropped(), 0);
+    }
+
+    #[test]
+    fn test_log_buffer_truncates_long_lines() {
+        let mut buffer = LogBuffer::new();
+        // Six ASCII bytes, then two-byte characters well past the limit
+        buffer.push(0, format_args!("[MQTT]{:é<1$}", "", MAX_LOG_LINE_LEN));
+
+        let line = buffer.lines(LogLevel::Info).next().unwrap();
+        assert!(line.truncated);
+        // Cut on a character boundary, never mid UTF-8 sequence
+        assert_eq!(line.text.len(), MAX_LOG_LINE_LEN);
+        assert!(line.text.ends_with('é'));
+    }
+}
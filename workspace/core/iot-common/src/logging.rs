@@ -0,0 +1,224 @@
+//! # Leveled Logging
+//!
+//! Per-cycle progress messages flood RTT when every `rprintln!` is
+//! unconditional. The `log_error!` .. `log_trace!` macros only print when
+//! their level is enabled, both at build time and at runtime:
+//!
+//! - [`STATIC_MAX_LEVEL`] is fixed by the `log-max-*` cargo features. Calls
+//!   above it are removed by the compiler, arguments included, so a
+//!   production build with `log-max-warn` carries no debug strings.
+//! - [`max_level`] is the runtime filter, set from the configured
+//!   [`LogLevel`] with [`init`] and changed later with [`set_max_level`],
+//!   e.g. from a console `loglevel` command.
+//!
+//! The macros print through the `rprintln!` in scope at the call site, so
+//! drivers keep writing to RTT while an application can tee into its own
+//! buffer:
+//!
+//! ```rust,ignore
+//! use rtt_target::rprintln;
+//! use iot_common::{log_debug, log_warn};
+//!
+//! iot_common::logging::init(LogLevel::Warning);
+//! log_debug!("[MQTT] Signal sent successfully"); // filtered
+//! log_warn!("[MQTT] WARNING: Broker unreachable"); // printed
+//! ```
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// System logging level
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum LogLevel {
+    /// No logging output
+    None = 0,
+
+    /// Only critical errors
+    Error = 1,
+
+    /// Warnings and errors
+    Warning = 2,
+
+    /// General information, warnings, and errors
+    #[default]
+    Info = 3,
+
+    /// Detailed debug information
+    Debug = 4,
+
+    /// Every per-cycle message
+    Trace = 5,
+}
+
+impl LogLevel {
+    /// Every level, least verbose first
+    pub const ALL: [LogLevel; 6] = [
+        LogLevel::None,
+        LogLevel::Error,
+        LogLevel::Warning,
+        LogLevel::Info,
+        LogLevel::Debug,
+        LogLevel::Trace,
+    ];
+
+    /// Returns the log level as a string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::None => "NONE",
+            LogLevel::Error => "ERROR",
+            LogLevel::Warning => "WARN",
+            LogLevel::Info => "INFO",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Trace => "TRACE",
+        }
+    }
+
+    /// Parses a level name, case-insensitive (`warn` and `warning` both work)
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("warning") {
+            return Some(LogLevel::Warning);
+        }
+        Self::ALL.into_iter().find(|level| level.as_str().eq_ignore_ascii_case(name))
+    }
+
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            0 => LogLevel::None,
+            1 => LogLevel::Error,
+            2 => LogLevel::Warning,
+            3 => LogLevel::Info,
+            4 => LogLevel::Debug,
+            _ => LogLevel::Trace,
+        }
+    }
+}
+
+/// Most verbose level compiled in, selected by the `log-max-*` features
+pub const STATIC_MAX_LEVEL: LogLevel = if cfg!(feature = "log-max-off") {
+    LogLevel::None
+} else if cfg!(feature = "log-max-error") {
+    LogLevel::Error
+} else if cfg!(feature = "log-max-warn") {
+    LogLevel::Warning
+} else if cfg!(feature = "log-max-info") {
+    LogLevel::Info
+} else if cfg!(feature = "log-max-debug") {
+    LogLevel::Debug
+} else {
+    LogLevel::Trace
+};
+
+/// Runtime filter, `Info` until [`init`] is called
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Info as u8);
+
+/// Set the runtime level from the configuration at startup
+pub fn init(level: LogLevel) {
+    set_max_level(level);
+}
+
+/// Change the runtime level; levels above [`STATIC_MAX_LEVEL`] stay off
+pub fn set_max_level(level: LogLevel) {
+    MAX_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Current runtime level
+pub fn max_level() -> LogLevel {
+    LogLevel::from_u8(MAX_LEVEL.load(Ordering::Relaxed))
+}
+
+/// Whether a message at `level` is printed
+#[inline(always)]
+pub fn enabled(level: LogLevel) -> bool {
+    level != LogLevel::None
+        && level <= STATIC_MAX_LEVEL
+        && level as u8 <= MAX_LEVEL.load(Ordering::Relaxed)
+}
+
+/// Print through the caller's `rprintln!` if `level` is enabled
+#[macro_export]
+macro_rules! log_at {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::logging::enabled($level) {
+            rprintln!($($arg)*);
+        }
+    };
+}
+
+/// Log a failure, see [`logging`](crate::logging)
+#[macro_export]
+macro_rules! log_error {
+    ($($arg:tt)*) => { $crate::log_at!($crate::logging::LogLevel::Error, $($arg)*) };
+}
+
+/// Log degraded operation, see [`logging`](crate::logging)
+#[macro_export]
+macro_rules! log_warn {
+    ($($arg:tt)*) => { $crate::log_at!($crate::logging::LogLevel::Warning, $($arg)*) };
+}
+
+/// Log progress and status, see [`logging`](crate::logging)
+#[macro_export]
+macro_rules! log_info {
+    ($($arg:tt)*) => { $crate::log_at!($crate::logging::LogLevel::Info, $($arg)*) };
+}
+
+/// Log details useful while debugging, see [`logging`](crate::logging)
+#[macro_export]
+macro_rules! log_debug {
+    ($($arg:tt)*) => { $crate::log_at!($crate::logging::LogLevel::Debug, $($arg)*) };
+}
+
+/// Log every per-cycle message, see [`logging`](crate::logging)
+#[macro_export]
+macro_rules! log_trace {
+    ($($arg:tt)*) => { $crate::log_at!($crate::logging::LogLevel::Trace, $($arg)*) };
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Write;
+
+    use heapless::{String, Vec};
+
+    use super::*;
+
+    #[test]
+    fn test_levels_filter_at_runtime() {
+        assert_eq!(LogLevel::from_name("warn"), Some(LogLevel::Warning));
+        assert_eq!(LogLevel::from_name("Warning"), Some(LogLevel::Warning));
+        assert_eq!(LogLevel::from_name("TRACE"), Some(LogLevel::Trace));
+        assert_eq!(LogLevel::from_name("verbose"), None);
+
+        let mut printed: Vec<String<64>, 4> = Vec::new();
+        // Stand-in for `rtt_target::rprintln!` at the call site
+        macro_rules! rprintln {
+            ($($arg:tt)*) => {{
+                let mut line = String::new();
+                let _ = write!(line, $($arg)*);
+                let _ = printed.push(line);
+            }};
+        }
+
+        init(LogLevel::Warning);
+        assert_eq!(max_level(), LogLevel::Warning);
+        let mut evaluated = false;
+        crate::log_debug!("[MQTT] Signal sent {}", { evaluated = true; "successfully" });
+        crate::log_warn!("[MQTT] WARNING: Broker unreachable");
+        crate::log_error!("[SENSOR] ERROR: {}", "I2C timeout");
+        assert!(!evaluated, "Arguments of a disabled level are not evaluated");
+
+        set_max_level(LogLevel::Trace);
+        crate::log_trace!("[SENSOR] Cycle {}", 7);
+        set_max_level(LogLevel::None);
+        crate::log_error!("[SENSOR] ERROR: dropped");
+        set_max_level(LogLevel::default());
+
+        assert_eq!(printed, [
+            "[MQTT] WARNING: Broker unreachable",
+            "[SENSOR] ERROR: I2C timeout",
+            "[SENSOR] Cycle 7",
+        ]);
+    }
+}
@@ -0,0 +1,143 @@
+//! # Network Stack Abstraction
+//!
+//! Common view of a WiFi manager's connectivity, shared by the async managers
+//! (`wifi-embassy`, `esp32c3-embassy-wifi`) and the blocking one
+//! (`esp32c3-blocking-wifi`). Code that only consumes connectivity (status
+//! reporting, "wait until online" loops) can be written once against
+//! [`NetworkStackProvider`] and run on either architecture.
+//!
+//! Async managers implement the trait with plain `async fn`s. Blocking managers
+//! implement it with `async fn`s that never await, and synchronous callers drive
+//! them with [`block_on`].
+//!
+//! ## Backend support
+//!
+//! | Method            | wifi-embassy | esp32c3-embassy-wifi | esp32c3-blocking-wifi |
+//! |-------------------|--------------|----------------------|-----------------------|
+//! | `link_status`     | live         | live                 | cached from `connect` |
+//! | `connection_info` | live         | live                 | cached from `connect` |
+//! | `rssi`            | `None`       | `None`               | `None`                |
+//!
+//! None of the managers keep the WiFi controller, so `rssi` is a no-op returning
+//! `None` everywhere until a backend can query it.
+
+use core::future::Future;
+use core::net::Ipv4Addr;
+use core::pin::pin;
+use core::task::{Context, Poll, Waker};
+
+/// Link state reported by a network backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkStatus {
+    /// Not associated with an access point
+    Down,
+    /// Associated, waiting for an IPv4 configuration (DHCP)
+    LinkUp,
+    /// Associated with an IPv4 address, ready for sockets
+    Connected,
+}
+
+impl LinkStatus {
+    /// Get a human-readable name for the status
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LinkStatus::Down => "down",
+            LinkStatus::LinkUp => "link-up",
+            LinkStatus::Connected => "connected",
+        }
+    }
+}
+
+/// IPv4 configuration of an active connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetworkInfo {
+    /// Address assigned to this device
+    pub ip_address: Ipv4Addr,
+    /// Default gateway, if any
+    pub gateway: Option<Ipv4Addr>,
+    /// First DNS server, if any
+    pub dns_server: Option<Ipv4Addr>,
+    /// Subnet prefix length (e.g. 24 for 255.255.255.0)
+    pub subnet_prefix: u8,
+}
+
+/// Connectivity information provided by every WiFi manager
+#[allow(async_fn_in_trait)]
+pub trait NetworkStackProvider {
+    /// Current link state
+    async fn link_status(&self) -> LinkStatus;
+
+    /// IPv4 configuration, `None` while not connected
+    async fn connection_info(&self) -> Option<NetworkInfo>;
+
+    /// Signal strength in dBm, `None` if the backend cannot report it
+    async fn rssi(&self) -> Option<i8>;
+
+    /// Address assigned to this device, `None` while not connected
+    async fn ip_address(&self) -> Option<Ipv4Addr> {
+        self.connection_info().await.map(|info| info.ip_address)
+    }
+
+    /// Checks if the device has a usable IPv4 connection
+    async fn is_connected(&self) -> bool {
+        self.link_status().await == LinkStatus::Connected
+    }
+}
+
+/// Runs a future to completion by polling it in a busy loop
+///
+/// Shim for synchronous code calling [`NetworkStackProvider`] on a blocking
+/// backend, whose futures are ready on the first poll. It also works for futures
+/// that actually wait, but spins the CPU while doing so; async code should
+/// `.await` instead.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+        core::hint::spin_loop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedProvider {
+        info: Option<NetworkInfo>,
+    }
+
+    impl NetworkStackProvider for FixedProvider {
+        async fn link_status(&self) -> LinkStatus {
+            if self.info.is_some() { LinkStatus::Connected } else { LinkStatus::Down }
+        }
+
+        async fn connection_info(&self) -> Option<NetworkInfo> {
+            self.info
+        }
+
+        async fn rssi(&self) -> Option<i8> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_default_methods_follow_connection_info() {
+        let info = NetworkInfo {
+            ip_address: Ipv4Addr::new(192, 168, 1, 100),
+            gateway: Some(Ipv4Addr::new(192, 168, 1, 1)),
+            dns_server: None,
+            subnet_prefix: 24,
+        };
+        let online = FixedProvider { info: Some(info) };
+        let offline = FixedProvider { info: None };
+
+        assert!(block_on(online.is_connected()));
+        assert_eq!(block_on(online.ip_address()), Some(Ipv4Addr::new(192, 168, 1, 100)));
+        assert!(!block_on(offline.is_connected()));
+        assert_eq!(block_on(offline.ip_address()), None);
+        assert_eq!(block_on(offline.link_status()).as_str(), "down");
+    }
+}
@@ -0,0 +1,238 @@
+//! # Retry with Backoff
+//!
+//! Sensor reads, network connects and publishes all follow the same pattern:
+//! try, wait, try again a bounded number of times, then give up with the last
+//! error. [`RetryPolicy`] describes the attempts and the backoff; [`retry`]
+//! runs an operation under it and [`retry_with_timeout`] also bounds how long
+//! each attempt may take.
+//!
+//! The operation is an async closure that receives the 1-based attempt number,
+//! so it can borrow the driver it calls and log retries:
+//!
+//! ```rust,ignore
+//! use iot_common::retry::{retry, RetryPolicy};
+//!
+//! let policy = RetryPolicy::new(5, 1_000, 2.0, 8_000);
+//! let reading = retry(&policy, async |attempt| {
+//!     if attempt > 1 {
+//!         rprintln!("[SENSOR] Read attempt {}/{}", attempt, policy.max_attempts);
+//!     }
+//!     sensor.read_measurements().await
+//! }).await?;
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ConfigError, IoTError, IoTResult};
+
+/// Retry behaviour for one class of fallible operations
+///
+/// The first retry waits `initial_delay_ms`; every further retry multiplies the
+/// delay by `backoff_multiplier`, never exceeding `max_delay_ms`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one (1 = no retries)
+    pub max_attempts: u32,
+
+    /// Delay before the first retry in milliseconds
+    pub initial_delay_ms: u32,
+
+    /// Factor applied to the delay after each retry
+    pub backoff_multiplier: f32,
+
+    /// Upper bound for any single delay in milliseconds
+    pub max_delay_ms: u32,
+}
+
+impl RetryPolicy {
+    /// Creates a retry policy
+    pub const fn new(max_attempts: u32, initial_delay_ms: u32, backoff_multiplier: f32, max_delay_ms: u32) -> Self {
+        Self {
+            max_attempts,
+            initial_delay_ms,
+            backoff_multiplier,
+            max_delay_ms,
+        }
+    }
+
+    /// Policy that gives up after the first failure
+    pub const fn no_retry() -> Self {
+        Self::new(1, 0, 1.0, 0)
+    }
+
+    /// Checks if another attempt is allowed after `attempt` attempts have failed
+    pub fn should_retry(&self, attempt: u32) -> bool {
+        attempt < self.max_attempts
+    }
+
+    /// Delay in milliseconds to wait after the `attempt`-th failed attempt (1-based)
+    pub fn delay_ms_for(&self, attempt: u32) -> u32 {
+        let mut delay = self.initial_delay_ms as f32;
+        for _ in 1..attempt {
+            delay *= self.backoff_multiplier;
+            if delay >= self.max_delay_ms as f32 {
+                break;
+            }
+        }
+        (delay as u32).min(self.max_delay_ms)
+    }
+
+    /// Delay to wait after the `attempt`-th failed attempt (1-based)
+    #[cfg(feature = "embassy")]
+    pub fn delay_for(&self, attempt: u32) -> embassy_time::Duration {
+        embassy_time::Duration::from_millis(self.delay_ms_for(attempt) as u64)
+    }
+
+    /// Validates the policy parameters
+    // IoTError is the crate-wide error and there is no heap to box it in.
+    #[allow(clippy::result_large_err)]
+    pub fn validate(&self) -> Result<(), IoTError> {
+        if self.max_attempts == 0 {
+            return Err(IoTError::configuration(ConfigError::ValidationError("Retry attempts must be > 0".try_into().unwrap_or_default())));
+        }
+
+        if self.backoff_multiplier.is_nan() || self.backoff_multiplier < 1.0 {
+            return Err(IoTError::configuration(ConfigError::ValidationError("Backoff multiplier must be >= 1.0".try_into().unwrap_or_default())));
+        }
+
+        if self.initial_delay_ms > self.max_delay_ms {
+            return Err(IoTError::configuration(ConfigError::ValidationError("Initial retry delay exceeds max delay".try_into().unwrap_or_default())));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(crate::MAX_RETRY_ATTEMPTS, 500, 2.0, 5_000)
+    }
+}
+
+/// Runs `operation` until it succeeds or `policy` runs out of attempts
+///
+/// Between attempts it awaits `delay` with the backoff in milliseconds.
+/// [`retry`] plugs in an embassy timer; this form lets tests and other
+/// executors supply their own. On exhaustion the last error is returned.
+// IoTError is the crate-wide error and there is no heap to box it in.
+#[allow(clippy::result_large_err)]
+pub async fn retry_with<T>(
+    policy: &RetryPolicy,
+    mut delay: impl AsyncFnMut(u32),
+    mut operation: impl AsyncFnMut(u32) -> IoTResult<T>,
+) -> IoTResult<T> {
+    let mut attempt = 1;
+
+    loop {
+        match operation(attempt).await {
+            Ok(value) => return Ok(value),
+            Err(e) if !policy.should_retry(attempt) => return Err(e),
+            Err(_) => {
+                delay(policy.delay_ms_for(attempt)).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Runs `operation` under `policy`, sleeping on the embassy timer between attempts
+// IoTError is the crate-wide error and there is no heap to box it in.
+#[allow(clippy::result_large_err)]
+#[cfg(feature = "embassy")]
+pub async fn retry<T>(
+    policy: &RetryPolicy,
+    operation: impl AsyncFnMut(u32) -> IoTResult<T>,
+) -> IoTResult<T> {
+    retry_with(
+        policy,
+        async |delay_ms| embassy_time::Timer::after(embassy_time::Duration::from_millis(delay_ms as u64)).await,
+        operation,
+    ).await
+}
+
+/// Like [`retry`], but an attempt that takes longer than `timeout` fails
+///
+/// A timed-out attempt counts as a failure with [`NetworkError::Timeout`](crate::NetworkError::Timeout)
+/// and is retried like any other; a stalled TCP connect then no longer holds
+/// up the whole retry budget.
+// IoTError is the crate-wide error and there is no heap to box it in.
+#[allow(clippy::result_large_err)]
+#[cfg(feature = "embassy")]
+pub async fn retry_with_timeout<T>(
+    policy: &RetryPolicy,
+    timeout: embassy_time::Duration,
+    mut operation: impl AsyncFnMut(u32) -> IoTResult<T>,
+) -> IoTResult<T> {
+    retry(policy, async |attempt| {
+        match embassy_time::with_timeout(timeout, operation(attempt)).await {
+            Ok(result) => result,
+            Err(_) => Err(IoTError::network(crate::NetworkError::Timeout(
+                "Attempt timed out".try_into().unwrap_or_default(),
+            ))),
+        }
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use heapless::Vec;
+
+    use super::*;
+    use crate::network::block_on;
+    use crate::error::IoTErrorKind;
+    use crate::{NetworkError, SensorError};
+
+    fn read_failure() -> IoTError {
+        IoTError::sensor(SensorError::I2CError("NACK".try_into().unwrap()))
+    }
+
+    #[test]
+    fn test_retry_succeeds_after_failures() {
+        let policy = RetryPolicy::new(5, 100, 2.0, 300);
+        let mut delays: Vec<u32, 8> = Vec::new();
+        let mut attempts: Vec<u32, 8> = Vec::new();
+
+        let result = block_on(retry_with(
+            &policy,
+            async |delay_ms| delays.push(delay_ms).unwrap(),
+            async |attempt| {
+                attempts.push(attempt).unwrap();
+                if attempt <= 3 { Err(read_failure()) } else { Ok(21.5) }
+            },
+        ));
+
+        assert_eq!(result.unwrap(), 21.5);
+        assert_eq!(attempts, [1, 2, 3, 4]);
+        assert_eq!(delays, [100, 200, 300], "Backoff doubles up to the cap");
+    }
+
+    #[test]
+    fn test_retry_returns_last_error_when_exhausted() {
+        let policy = RetryPolicy::new(3, 0, 1.0, 0);
+        let mut attempts = 0;
+
+        let result: IoTResult<()> = block_on(retry_with(&policy, async |_| {}, async |attempt| {
+            attempts += 1;
+            Err(IoTError::network(NetworkError::TCPConnectionFailed(
+                if attempt == 3 { "last" } else { "earlier" }.try_into().unwrap(),
+            )))
+        }));
+
+        assert_eq!(attempts, 3);
+        match result {
+            Err(e) => match e.kind() {
+                IoTErrorKind::Network(NetworkError::TCPConnectionFailed(msg)) => assert_eq!(msg, "last"),
+                other => panic!("Expected the last connection error, got {:?}", other),
+            },
+            Ok(()) => panic!("Expected the operation to fail"),
+        }
+
+        let mut calls = 0;
+        let result: IoTResult<()> = block_on(retry_with(&RetryPolicy::no_retry(), async |_| {}, async |_| {
+            calls += 1;
+            Err(read_failure())
+        }));
+        assert!(result.is_err());
+        assert_eq!(calls, 1, "no_retry makes a single attempt");
+    }
+}
@@ -21,9 +21,12 @@ use serde_json_core;
 ///   "pressure": 1013.25,
 ///   "timestamp": 1234567890,
 ///   "device_id": "esp32-c3-nodeps",
-///   "reading_count": 123
+///   "reading_count": 123,
+///   "architecture": "sync"
 /// }
 /// ```
+/// 
+/// `architecture` is only present when set with [`Self::with_architecture`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StandardSensorReading {
     /// Temperature in Celsius (always first field)
@@ -38,6 +41,9 @@ pub struct StandardSensorReading {
     pub device_id: &'static str,
     /// Sequential reading counter (STANDARDIZED - was "reading" vs "count")
     pub reading_count: u32,
+    /// Architecture tag of the publishing application (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub architecture: Option<&'static str>,
 }
 
 impl StandardSensorReading {
@@ -57,9 +63,16 @@ impl StandardSensorReading {
             timestamp,
             device_id,
             reading_count,
+            architecture: None,
         }
     }
     
+    /// Tag the reading with the architecture of the publishing application
+    pub fn with_architecture(mut self, architecture: IoTArchitecture) -> Self {
+        self.architecture = Some(architecture.get_architecture_string());
+        self
+    }
+    
     /// Convert to standardized JSON format using heapless (no_std compatible)
     #[allow(dead_code)]
     pub fn to_standard_json_deprecated(&self) -> &'static str {
@@ -70,10 +83,53 @@ impl StandardSensorReading {
     pub fn to_json_bytes(&self) -> Result<heapless::Vec<u8, 256>, serde_json_core::ser::Error> {
         serde_json_core::to_vec(self)
     }
+    
+    /// Convert to the standardized JSON string
+    pub fn to_standard_json(&self) -> Result<heapless::String<256>, serde_json_core::ser::Error> {
+        serde_json_core::to_string(self)
+    }
+    
+    /// Convert to one of the pre-standard JSON formats
+    /// 
+    /// Legacy payloads only carry the measurements (and the counter for
+    /// main-nodeps), which always fit in 256 bytes.
+    pub fn to_legacy_json(&self, format: LegacyFormat) -> heapless::String<256> {
+        let json = match format {
+            LegacyFormat::MainNodeps => serde_json_core::to_string(&NodepsLegacyReading {
+                temperature: self.temperature,
+                pressure: self.pressure,
+                humidity: self.humidity,
+                reading: self.reading_count,
+            }),
+            LegacyFormat::MainMinApp => serde_json_core::to_string(&MinAppLegacyReading {
+                temperature: self.temperature,
+                humidity: self.humidity,
+                pressure: self.pressure,
+            }),
+        };
+        json.unwrap_or_default()
+    }
+}
+
+/// main-nodeps sensor payload (pressure before humidity, "reading" counter)
+#[derive(Serialize)]
+struct NodepsLegacyReading {
+    temperature: f32,
+    pressure: f32,
+    humidity: f32,
+    reading: u32,
+}
+
+/// main-min/main-app sensor payload (measurements only)
+#[derive(Serialize)]
+struct MinAppLegacyReading {
+    temperature: f32,
+    humidity: f32,
+    pressure: f32,
 }
 
 /// Legacy message formats for backward compatibility
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LegacyFormat {
     /// main-nodeps original format with different field ordering
     MainNodeps,
@@ -194,7 +250,7 @@ impl StandardDeviceStatus {
 }
 
 /// Device architecture identification
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum IoTArchitecture {
     /// Synchronous blocking architecture (main-nodeps)
     Synchronous,
@@ -271,9 +327,63 @@ impl StandardTopics {
 }
 
 /// Timestamp provider trait for different architectures
+/// 
+/// One provider is chosen when a client or storage manager is constructed,
+/// so every message and record it produces uses the same time source.
+/// 
+/// # Semantics
+/// 
+/// - [`get_timestamp_ms`](Self::get_timestamp_ms) is **monotonic**: ms since
+///   boot, never jumps, available without a network. Use it for ordering,
+///   intervals and expiry (TTLs, transaction timeouts).
+/// - [`wall_clock_ms_at`](Self::wall_clock_ms_at) is **wall clock** (Unix ms)
+///   and optional. It is `None` while the provider is unsynced (NTP not yet
+///   answered, RTC never set); callers then carry only the monotonic value
+///   instead of guessing. Once synced it also converts timestamps taken
+///   before the sync, so an offline backlog can be stamped later.
 pub trait TimestampProvider {
     /// Get current timestamp in milliseconds since boot
     fn get_timestamp_ms(&self) -> u64;
+    
+    /// Unix time in milliseconds for a [`get_timestamp_ms`](Self::get_timestamp_ms)
+    /// value, `None` while no wall clock is known
+    fn wall_clock_ms_at(&self, _timestamp_ms: u64) -> Option<u64> {
+        None
+    }
+    
+    /// Current Unix time in milliseconds, `None` while no wall clock is known
+    fn wall_clock_ms(&self) -> Option<u64> {
+        self.wall_clock_ms_at(self.get_timestamp_ms())
+    }
+}
+
+/// Provider handle stored by long-lived clients and managers
+pub type SharedTimestampProvider = &'static (dyn TimestampProvider + Sync);
+
+impl<T: TimestampProvider + ?Sized> TimestampProvider for &T {
+    fn get_timestamp_ms(&self) -> u64 {
+        (**self).get_timestamp_ms()
+    }
+    
+    fn wall_clock_ms_at(&self, timestamp_ms: u64) -> Option<u64> {
+        (**self).wall_clock_ms_at(timestamp_ms)
+    }
+}
+
+/// Uptime-based timestamp provider for Embassy applications
+/// 
+/// Reads `embassy_time::Instant`, so it needs no network and never has a
+/// wall clock. The default for clients and storage that are not given a
+/// provider.
+#[cfg(feature = "embassy")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UptimeTimestampProvider;
+
+#[cfg(feature = "embassy")]
+impl TimestampProvider for UptimeTimestampProvider {
+    fn get_timestamp_ms(&self) -> u64 {
+        embassy_time::Instant::now().as_millis()
+    }
 }
 
 /// Synchronous timestamp provider for main-nodeps
@@ -320,6 +430,10 @@ mod tests {
         assert!(json.contains("\"timestamp\":1234567890"));
         assert!(json.contains("\"device_id\":\"esp32-c3-test\""));
         assert!(json.contains("\"reading_count\":123"));
+        assert!(!json.contains("architecture"));
+        
+        let tagged = reading.with_architecture(IoTArchitecture::AsyncFull).to_standard_json().unwrap();
+        assert!(tagged.contains("\"architecture\":\"async-full\""));
     }
     
     #[test]
@@ -370,5 +484,11 @@ mod tests {
         
         provider.increment_loop();
         assert_eq!(provider.get_timestamp_ms(), 100);
+        
+        // A loop counter has no wall clock, also behind a reference
+        assert_eq!(provider.wall_clock_ms(), None);
+        let by_ref: &dyn TimestampProvider = &provider;
+        assert_eq!((&by_ref).get_timestamp_ms(), 100);
+        assert_eq!((&by_ref).wall_clock_ms_at(100), None);
     }
 }
\ No newline at end of file
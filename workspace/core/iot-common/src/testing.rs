@@ -5,7 +5,7 @@
 //!
 //! ## Features
 //!
-//! - **I2C Mock**: Simulates I2C communication for sensor testing
+//! - **I2C Mock**: Simulates I2C communication for sensor testing, also as an `embedded-hal-async` bus
 //! - **UART Mock**: Simulates UART communication for console testing  
 //! - **WiFi Mock**: Simulates WiFi network stack for connectivity testing
 //! - **GPIO Mock**: Simulates GPIO pin operations
@@ -31,6 +31,7 @@ use crate::error::utils;
 use crate::result::IoTResult;
 use heapless::{Vec, FnvIndexMap};
 use core::fmt;
+use embedded_hal_async::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
 
 /// Maximum number of expected calls in mock objects
 pub const MAX_MOCK_EXPECTATIONS: usize = 32;
@@ -261,6 +262,50 @@ impl fmt::Display for MockI2cError {
     }
 }
 
+impl embedded_hal_async::i2c::Error for MockI2cError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            MockI2cError::DeviceNotFound => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address),
+            MockI2cError::Communication(_) | MockI2cError::InvalidRegister(_) => ErrorKind::Other,
+        }
+    }
+}
+
+impl ErrorType for MockI2c {
+    type Error = MockI2cError;
+}
+
+/// Bus-level access for drivers written against `embedded-hal-async`
+///
+/// A write-read of one register byte maps to [`read_register`](MockI2c::read_register)
+/// or [`read_registers`](MockI2c::read_registers), and a two-byte write to
+/// [`write_register`](MockI2c::write_register), so the same expectations apply.
+/// Transfers to any address other than the current device address are not
+/// acknowledged.
+impl I2c for MockI2c {
+    async fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Self::Error> {
+        if address != self.device_address {
+            self.call_count += 1;
+            return Err(MockI2cError::DeviceNotFound);
+        }
+
+        match operations {
+            [Operation::Write([register]), Operation::Read(buffer)] if buffer.len() == 1 => {
+                buffer[0] = self.read_register(*register).await?;
+                Ok(())
+            }
+            [Operation::Write([register]), Operation::Read(buffer)] => self.read_registers(*register, buffer).await,
+            [Operation::Write([register, value])] => self.write_register(*register, *value).await,
+            // Address probe
+            [] | [Operation::Write([])] => {
+                self.call_count += 1;
+                Ok(())
+            }
+            _ => Err(MockI2cError::Communication("Unsupported mock I2C transaction")),
+        }
+    }
+}
+
 /// Mock UART interface for testing serial console
 #[derive(Debug)]
 pub struct MockUart {
@@ -784,6 +829,27 @@ mod tests {
         assert_eq!(mock.memory.get(&0xD0), Some(&0x60));
     }
 
+    #[test]
+    fn test_mock_i2c_async_bus() {
+        let mut mock = MockI2c::new();
+        mock.expect_write_register(0xF2, 0x01).unwrap();
+        mock.expect_read_registers(0x88, &[1, 2, 3]).unwrap();
+
+        let mut chip_id = [0u8; 1];
+        let mut calib = [0u8; 3];
+        crate::network::block_on(async {
+            I2c::write(&mut mock, 0x76, &[0xF2, 0x01]).await.unwrap();
+            I2c::write_read(&mut mock, 0x76, &[0xD0], &mut chip_id).await.unwrap();
+            I2c::write_read(&mut mock, 0x76, &[0x88], &mut calib).await.unwrap();
+            assert!(matches!(I2c::write(&mut mock, 0x77, &[]).await, Err(MockI2cError::DeviceNotFound)));
+        });
+
+        assert_eq!(chip_id, [0x60]);
+        assert_eq!(calib, [1, 2, 3]);
+        mock.verify().unwrap();
+        assert_eq!(mock.call_count(), 4);
+    }
+
     #[test]
     fn test_mock_uart_basic() {
         let mut mock = MockUart::new();
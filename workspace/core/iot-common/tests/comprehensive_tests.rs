@@ -99,6 +99,7 @@ fn test_all_hardware_error_variants() {
         (HardwareError::InterruptError(utils::error_message("int")), 3005),
         (HardwareError::PowerError(utils::error_message("power")), 3006),
         (HardwareError::ClockError(utils::error_message("clock")), 3007),
+        (HardwareError::TemperatureSensorError(utils::error_message("tsens")), 3008),
     ];
     
     for (error, expected_code) in errors {
@@ -1,6 +1,15 @@
 //! Feature Flag System
 //! 
 //! Compile-time and runtime feature management for optional components.
+//!
+//! ## Heap Size
+//!
+//! [`HEAP_SIZE`] is the single heap size for `esp_alloc::heap_allocator!`.
+//! It defaults to [`CompileTimeFeatures::min_heap_size`] for the enabled
+//! features and can be raised with `HEAP_SIZE_KB` in `.cargo/config.toml`;
+//! a value below the minimum fails the build. An allocation failure panics
+//! with "memory allocation of N bytes failed", so the application's panic
+//! handler is where the requested size and heap usage get logged.
 
 extern crate alloc;
 use alloc::{string::String, format, string::ToString};
@@ -34,6 +43,38 @@ impl Default for RuntimeFeatures {
     }
 }
 
+/// Minimum heap without networking (sensor, console, JSON formatting)
+pub const BASE_MIN_HEAP_SIZE: usize = 16 * 1024;
+
+/// Minimum heap with the WiFi stack (esp-wifi plus embassy-net buffers)
+pub const WIFI_MIN_HEAP_SIZE: usize = 72 * 1024;
+
+/// Heap size for `esp_alloc::heap_allocator!`, from `HEAP_SIZE_KB` or the feature minimum
+pub const HEAP_SIZE: usize = match option_env!("HEAP_SIZE_KB") {
+    Some(kb) => parse_heap_kb(kb),
+    None => CompileTimeFeatures::min_heap_size(),
+};
+
+const _: () = assert!(
+    HEAP_SIZE >= CompileTimeFeatures::min_heap_size(),
+    "HEAP_SIZE_KB is below the minimum heap for the enabled features"
+);
+
+/// Parse a `HEAP_SIZE_KB` value into bytes at compile time
+const fn parse_heap_kb(value: &str) -> usize {
+    let digits = value.as_bytes();
+    assert!(!digits.is_empty(), "HEAP_SIZE_KB must be a whole number of kilobytes");
+
+    let mut kb = 0usize;
+    let mut i = 0;
+    while i < digits.len() {
+        assert!(digits[i].is_ascii_digit(), "HEAP_SIZE_KB must be a whole number of kilobytes");
+        kb = kb * 10 + (digits[i] - b'0') as usize;
+        i += 1;
+    }
+    kb * 1024
+}
+
 /// Compile-time feature detection and validation
 pub struct CompileTimeFeatures;
 
@@ -78,6 +119,15 @@ impl CompileTimeFeatures {
         Self::has_wifi() && Self::has_mqtt() && Self::has_console() && Self::has_performance()
     }
 
+    /// Smallest heap that supports the compiled-in features
+    pub const fn min_heap_size() -> usize {
+        if Self::has_wifi() {
+            WIFI_MIN_HEAP_SIZE
+        } else {
+            BASE_MIN_HEAP_SIZE
+        }
+    }
+
     /// Get build configuration name
     pub fn build_config_name() -> &'static str {
         if Self::is_minimal() {
@@ -313,6 +363,13 @@ mod tests {
         let _mqtt_enabled = feature_enabled!("mqtt");
     }
 
+    #[test]
+    fn test_heap_size() {
+        assert!(HEAP_SIZE >= CompileTimeFeatures::min_heap_size());
+        assert_eq!(parse_heap_kb("96"), 96 * 1024);
+        assert_eq!(CompileTimeFeatures::min_heap_size() == WIFI_MIN_HEAP_SIZE, CompileTimeFeatures::has_wifi());
+    }
+
     #[test]
     fn test_estimated_flash_usage() {
         let size = CompileTimeFeatures::estimated_flash_usage();
@@ -1,13 +1,22 @@
 //! Configuration Validation
 //! 
 //! Comprehensive validation system for IoT configuration with detailed error reporting.
+//! 
+//! [`validate_remote_config`] is the gate for configuration received over the
+//! network: it checks an untrusted JSON payload against [`REMOTE_CONFIG_SCHEMA`]
+//! before anything is applied.
 
 extern crate alloc;
 use alloc::{string::String, vec::Vec, format, string::ToString};
 
+use heapless::String as HeaplessString;
+use serde::Deserialize;
+use serde::de::{DeserializeSeed, MapAccess, Visitor};
+
 use crate::{IoTSystemConfig, WiFiConfig, MqttConfig, SensorConfig, ConsoleConfig, StorageConfig, HardwareConfig};
 #[allow(unused_imports)]
 use crate::{ConfigResult, ConfigError, SystemConfig};
+use crate::create_bounded_string;
 
 /// Validation severity levels
 #[derive(Debug, Clone, PartialEq)]
@@ -630,6 +639,305 @@ fn validate_mqtt_topic(report: &mut ValidationReport, field: &str, topic: &str)
     }
 }
 
+/// Longest string accepted anywhere in a remote configuration payload
+pub const MAX_REMOTE_TEXT_LEN: usize = 64;
+
+/// Expected type and range of a remotely settable field
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldKind {
+    /// String of 1 to `max_len` characters
+    Text { max_len: usize },
+    /// Whole number within `min..=max`
+    Integer { min: i64, max: i64 },
+    /// Number within `min..=max`
+    Number { min: f32, max: f32 },
+    /// `true` or `false`
+    Bool,
+}
+
+impl FieldKind {
+    fn expected(&self) -> &'static str {
+        match self {
+            FieldKind::Text { .. } => "expected a string",
+            FieldKind::Integer { .. } => "expected an integer",
+            FieldKind::Number { .. } => "expected a number",
+            FieldKind::Bool => "expected true or false",
+        }
+    }
+}
+
+/// Value of a remote field that passed its type check
+#[derive(Debug, Clone, PartialEq)]
+enum FieldValue {
+    Text(HeaplessString<MAX_REMOTE_TEXT_LEN>),
+    Integer(i64),
+    Number(f32),
+    Bool(bool),
+}
+
+impl FieldValue {
+    fn text(&self) -> &str {
+        match self {
+            FieldValue::Text(s) => s.as_str(),
+            _ => "",
+        }
+    }
+
+    fn integer(&self) -> i64 {
+        match self {
+            FieldValue::Integer(n) => *n,
+            _ => 0,
+        }
+    }
+
+    fn number(&self) -> f32 {
+        match self {
+            FieldValue::Number(n) => *n,
+            _ => 0.0,
+        }
+    }
+
+    fn flag(&self) -> bool {
+        matches!(self, FieldValue::Bool(true))
+    }
+}
+
+/// Field that may be set by a remote configuration message
+pub struct RemoteField {
+    /// Top-level object in the payload, e.g. `mqtt`
+    pub section: &'static str,
+    /// Key within the section, e.g. `broker_port`
+    pub name: &'static str,
+    /// Accepted type and range
+    pub kind: FieldKind,
+    apply: fn(&mut IoTSystemConfig, &FieldValue) -> ConfigResult<()>,
+}
+
+/// Fields accepted in a remote configuration message, with their bounds
+///
+/// Integer bounds also keep values inside the target field's type, so the
+/// narrowing casts in `apply` cannot truncate.
+pub const REMOTE_CONFIG_SCHEMA: &[RemoteField] = &[
+    RemoteField { section: "wifi", name: "ssid", kind: FieldKind::Text { max_len: 32 },
+        apply: |c, v| { c.wifi.ssid = create_bounded_string(v.text(), "wifi.ssid")?; Ok(()) } },
+    RemoteField { section: "wifi", name: "password", kind: FieldKind::Text { max_len: 64 },
+        apply: |c, v| { c.wifi.password = create_bounded_string(v.text(), "wifi.password")?; Ok(()) } },
+    RemoteField { section: "wifi", name: "timeout_seconds", kind: FieldKind::Integer { min: 1, max: 60 },
+        apply: |c, v| { c.wifi.timeout_seconds = v.integer() as u32; Ok(()) } },
+    RemoteField { section: "wifi", name: "retry_attempts", kind: FieldKind::Integer { min: 0, max: 10 },
+        apply: |c, v| { c.wifi.retry_attempts = v.integer() as u8; Ok(()) } },
+    RemoteField { section: "wifi", name: "auto_reconnect", kind: FieldKind::Bool,
+        apply: |c, v| { c.wifi.auto_reconnect = v.flag(); Ok(()) } },
+    RemoteField { section: "mqtt", name: "broker_ip", kind: FieldKind::Text { max_len: 15 },
+        apply: |c, v| { c.mqtt.broker_ip = create_bounded_string(v.text(), "mqtt.broker_ip")?; Ok(()) } },
+    RemoteField { section: "mqtt", name: "broker_port", kind: FieldKind::Integer { min: 1, max: 65535 },
+        apply: |c, v| { c.mqtt.broker_port = v.integer() as u16; Ok(()) } },
+    RemoteField { section: "mqtt", name: "sensor_interval_secs", kind: FieldKind::Integer { min: 1, max: 3600 },
+        apply: |c, v| { c.mqtt.sensor_interval_secs = v.integer() as u16; Ok(()) } },
+    RemoteField { section: "mqtt", name: "heartbeat_interval_secs", kind: FieldKind::Integer { min: 10, max: 3600 },
+        apply: |c, v| { c.mqtt.heartbeat_interval_secs = v.integer() as u16; Ok(()) } },
+    RemoteField { section: "mqtt", name: "status_interval_secs", kind: FieldKind::Integer { min: 10, max: 3600 },
+        apply: |c, v| { c.mqtt.status_interval_secs = v.integer() as u16; Ok(()) } },
+    RemoteField { section: "sensor", name: "reading_interval_secs", kind: FieldKind::Integer { min: 1, max: 3600 },
+        apply: |c, v| { c.sensor.reading_interval_secs = v.integer() as u32; Ok(()) } },
+    RemoteField { section: "sensor", name: "temperature_offset", kind: FieldKind::Number { min: -10.0, max: 10.0 },
+        apply: |c, v| { c.sensor.temperature_offset = v.number(); Ok(()) } },
+    RemoteField { section: "sensor", name: "humidity_offset", kind: FieldKind::Number { min: -20.0, max: 20.0 },
+        apply: |c, v| { c.sensor.humidity_offset = v.number(); Ok(()) } },
+    RemoteField { section: "sensor", name: "pressure_offset", kind: FieldKind::Number { min: -100.0, max: 100.0 },
+        apply: |c, v| { c.sensor.pressure_offset = v.number(); Ok(()) } },
+];
+
+/// Validate an inbound configuration message and apply it to a copy of `current`
+///
+/// The payload is a JSON object of sections holding the fields to change,
+/// e.g. `{"mqtt":{"broker_port":8883},"sensor":{"reading_interval_secs":60}}`.
+/// Every field is checked against [`REMOTE_CONFIG_SCHEMA`]: unknown sections
+/// and fields, wrong types and out-of-range values are rejected, and the
+/// resulting configuration must pass [`ConfigValidator::validate`] without
+/// critical issues. Nothing is applied unless the whole message is valid.
+///
+/// # Returns
+///
+/// * `Ok(config)` - `current` with the message applied
+/// * `Err(ConfigError::ValidationFailed)` - Message rejected; the text starts with the offending field
+pub fn validate_remote_config(payload: &[u8], current: &IoTSystemConfig) -> ConfigResult<IoTSystemConfig> {
+    use serde::Deserializer as _;
+
+    let mut candidate = current.clone();
+    let mut rejection = None;
+    let mut unescape_buffer = [0u8; MAX_REMOTE_TEXT_LEN];
+    let mut deserializer = serde_json_core::de::Deserializer::new(payload, Some(&mut unescape_buffer));
+
+    let parsed = deserializer
+        .deserialize_map(PayloadVisitor { config: &mut candidate, rejection: &mut rejection })
+        .and_then(|()| deserializer.end());
+    if let Err(e) = parsed {
+        return Err(ConfigError::ValidationFailed(
+            rejection.unwrap_or_else(|| format!("payload: malformed JSON ({:?})", e))
+        ));
+    }
+
+    let report = candidate.validate();
+    if let Some(issue) = report.issues.iter().find(|i| i.severity == ValidationSeverity::Critical) {
+        return Err(ConfigError::ValidationFailed(format!("{}: {}", issue.field, issue.message)));
+    }
+
+    Ok(candidate)
+}
+
+/// Walks the top-level object of a remote configuration payload
+struct PayloadVisitor<'a> {
+    config: &'a mut IoTSystemConfig,
+    rejection: &'a mut Option<String>,
+}
+
+impl<'de> Visitor<'de> for PayloadVisitor<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("a configuration object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(section) = map.next_key::<&str>()? {
+            let Some(known) = REMOTE_CONFIG_SCHEMA.iter().find(|f| f.section == section) else {
+                return Err(reject(self.rejection, format!("{}: unknown section", section)));
+            };
+            map.next_value_seed(SectionSeed {
+                section: known.section,
+                config: &mut *self.config,
+                rejection: &mut *self.rejection,
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Seed for one section value; it must be an object
+struct SectionSeed<'a> {
+    section: &'static str,
+    config: &'a mut IoTSystemConfig,
+    rejection: &'a mut Option<String>,
+}
+
+impl<'de> DeserializeSeed<'de> for SectionSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        let SectionSeed { section, config, rejection } = self;
+        let mut is_object = false;
+        deserializer
+            .deserialize_map(SectionVisitor { section, config, rejection: &mut *rejection, is_object: &mut is_object })
+            .inspect_err(|_| {
+                if !is_object {
+                    *rejection = Some(format!("{}: expected an object", section));
+                }
+            })
+    }
+}
+
+/// Checks and applies the fields of one section
+struct SectionVisitor<'a> {
+    section: &'static str,
+    config: &'a mut IoTSystemConfig,
+    rejection: &'a mut Option<String>,
+    /// Set once the value turned out to be an object
+    is_object: &'a mut bool,
+}
+
+impl<'de> Visitor<'de> for SectionVisitor<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("a configuration section")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        *self.is_object = true;
+        while let Some(name) = map.next_key::<&str>()? {
+            let Some(field) = REMOTE_CONFIG_SCHEMA.iter().find(|f| f.section == self.section && f.name == name) else {
+                return Err(reject(self.rejection, format!("{}.{}: unknown field", self.section, name)));
+            };
+
+            let value = match field.kind {
+                FieldKind::Text { .. } => map.next_value::<RemoteText>().map(|t| t.0),
+                FieldKind::Integer { .. } => map.next_value::<i64>().map(|n| Some(FieldValue::Integer(n))),
+                FieldKind::Number { .. } => map.next_value::<f32>().map(|n| Some(FieldValue::Number(n))),
+                FieldKind::Bool => map.next_value::<bool>().map(|b| Some(FieldValue::Bool(b))),
+            };
+            let value = match value {
+                Ok(value) => value,
+                Err(e) => {
+                    *self.rejection = Some(format!("{}.{}: {}", field.section, field.name, field.kind.expected()));
+                    return Err(e);
+                }
+            };
+
+            if let Err(reason) = check_range(field.kind, value.as_ref()) {
+                return Err(reject(self.rejection, format!("{}.{}: {}", field.section, field.name, reason)));
+            }
+            if let Some(value) = value {
+                if let Err(e) = (field.apply)(self.config, &value) {
+                    let reason = match e {
+                        ConfigError::InvalidValue(msg) | ConfigError::ValidationFailed(msg) => msg,
+                        other => format!("{}.{}: {:?}", field.section, field.name, other),
+                    };
+                    return Err(reject(self.rejection, reason));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// JSON string of a remote payload; `None` if longer than [`MAX_REMOTE_TEXT_LEN`]
+struct RemoteText(Option<FieldValue>);
+
+impl<'de> Deserialize<'de> for RemoteText {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TextVisitor;
+
+        impl Visitor<'_> for TextVisitor {
+            type Value = RemoteText;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                formatter.write_str("a string")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<RemoteText, E> {
+                Ok(RemoteText(HeaplessString::try_from(v).ok().map(FieldValue::Text)))
+            }
+        }
+
+        deserializer.deserialize_str(TextVisitor)
+    }
+}
+
+/// Checks a type-checked value against the bounds of its field
+fn check_range(kind: FieldKind, value: Option<&FieldValue>) -> Result<(), String> {
+    match (kind, value) {
+        (FieldKind::Text { max_len }, None) => Err(format!("too long (max {} characters)", max_len)),
+        (FieldKind::Text { max_len }, Some(v)) if v.text().len() > max_len => {
+            Err(format!("too long (max {} characters)", max_len))
+        }
+        (FieldKind::Text { .. }, Some(v)) if v.text().is_empty() => Err("cannot be empty".to_string()),
+        (FieldKind::Integer { min, max }, Some(v)) if !(min..=max).contains(&v.integer()) => {
+            Err(format!("{} out of range ({}-{})", v.integer(), min, max))
+        }
+        (FieldKind::Number { min, max }, Some(v)) if !(min..=max).contains(&v.number()) => {
+            Err(format!("{} out of range ({} to {})", v.number(), min, max))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Records why a payload was rejected and aborts deserialization
+fn reject<E: serde::de::Error>(rejection: &mut Option<String>, reason: String) -> E {
+    *rejection = Some(reason);
+    E::custom("remote configuration rejected")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -669,6 +977,72 @@ mod tests {
         assert!(!is_valid_ip_format("192.168.01.1")); // Leading zero
     }
 
+    fn valid_config() -> IoTSystemConfig {
+        let mut config = IoTSystemConfig::default();
+        config.wifi.ssid = create_bounded_string("greenhouse", "ssid").unwrap();
+        config.wifi.password = create_bounded_string("password123", "password").unwrap();
+        config
+    }
+
+    fn rejection(payload: &str) -> String {
+        match validate_remote_config(payload.as_bytes(), &valid_config()) {
+            Err(ConfigError::ValidationFailed(msg)) => msg,
+            other => panic!("Expected {} to be rejected, got {:?}", payload, other),
+        }
+    }
+
+    #[test]
+    fn test_remote_config_accepted() {
+        let current = valid_config();
+        let updated = validate_remote_config(
+            br#"{"wifi":{"ssid":"Lab \"B\"","auto_reconnect":false},"mqtt":{"broker_ip":"10.0.0.5","broker_port":8883},"sensor":{"reading_interval_secs":60,"temperature_offset":-1.5}}"#,
+            &current,
+        ).unwrap();
+
+        assert_eq!(updated.wifi.ssid.as_str(), "Lab \"B\"");
+        assert!(!updated.wifi.auto_reconnect);
+        assert_eq!(updated.wifi.password, current.wifi.password, "Fields not in the message are kept");
+        assert_eq!(updated.mqtt.broker_ip.as_str(), "10.0.0.5");
+        assert_eq!(updated.mqtt.broker_port, 8883);
+        assert_eq!(updated.sensor.reading_interval_secs, 60);
+        assert_eq!(updated.sensor.temperature_offset, -1.5);
+    }
+
+    #[test]
+    fn test_remote_config_rejects_long_ssid() {
+        let msg = rejection(r#"{"wifi":{"ssid":"this-network-name-is-longer-than-32"}}"#);
+        assert_eq!(msg, "wifi.ssid: too long (max 32 characters)");
+
+        // Longer than any field, so it never fits the parse buffer either
+        let msg = rejection(&format!(r#"{{"wifi":{{"ssid":"{}"}}}}"#, "x".repeat(200)));
+        assert_eq!(msg, "wifi.ssid: too long (max 32 characters)");
+
+        assert_eq!(rejection(r#"{"wifi":{"ssid":""}}"#), "wifi.ssid: cannot be empty");
+    }
+
+    #[test]
+    fn test_remote_config_rejects_out_of_range_port() {
+        assert_eq!(rejection(r#"{"mqtt":{"broker_port":70000}}"#), "mqtt.broker_port: 70000 out of range (1-65535)");
+        assert_eq!(rejection(r#"{"mqtt":{"broker_port":0}}"#), "mqtt.broker_port: 0 out of range (1-65535)");
+        assert!(rejection(r#"{"sensor":{"reading_interval_secs":86400}}"#).starts_with("sensor.reading_interval_secs: "));
+    }
+
+    #[test]
+    fn test_remote_config_rejects_wrong_type() {
+        assert_eq!(rejection(r#"{"mqtt":{"broker_port":"1883"}}"#), "mqtt.broker_port: expected an integer");
+        assert_eq!(rejection(r#"{"wifi":{"ssid":42}}"#), "wifi.ssid: expected a string");
+        assert_eq!(rejection(r#"{"wifi":{"auto_reconnect":"yes"}}"#), "wifi.auto_reconnect: expected true or false");
+        assert_eq!(rejection(r#"{"mqtt":"10.0.0.5"}"#), "mqtt: expected an object");
+    }
+
+    #[test]
+    fn test_remote_config_rejects_unknown_and_invalid() {
+        assert_eq!(rejection(r#"{"system":{"heap_size":1}}"#), "system: unknown section");
+        assert_eq!(rejection(r#"{"mqtt":{"broker_port":1883,"qos":2}}"#), "mqtt.qos: unknown field");
+        assert_eq!(rejection(r#"{"mqtt":{"broker_ip":"10.0.0.256"}}"#), "mqtt.broker_ip: MQTT broker IP format is invalid");
+        assert!(rejection(r#"{"mqtt":{"broker_port":1883"#).starts_with("payload: malformed JSON"));
+    }
+
     #[test]
     fn test_system_validation() {
         let config = IoTSystemConfig::default();
@@ -0,0 +1,121 @@
+//! # Offline Measurement Buffer
+//!
+//! Readings taken while WiFi or MQTT is down would otherwise be lost.
+//! [`MeasurementBuffer`] is a fixed `heapless` ring the sensor path pushes
+//! into and the publisher drains, so after a reconnect the backlog goes out
+//! oldest-first (typically as one batch). When the ring is full the oldest
+//! reading is dropped and counted in [`MeasurementBuffer::dropped`], which
+//! status output reports alongside the current depth.
+//!
+//! A publisher that takes the backlog with [`take_all`](MeasurementBuffer::take_all)
+//! and fails hands it back with [`restore`](MeasurementBuffer::restore); readings
+//! pushed in the meantime stay behind the restored ones.
+
+use heapless::{Deque, Vec};
+
+use crate::MAX_MEASUREMENT_BUFFER;
+
+/// Ring of readings waiting to be published, oldest first
+#[derive(Debug)]
+pub struct MeasurementBuffer<T, const N: usize = MAX_MEASUREMENT_BUFFER> {
+    items: Deque<T, N>,
+    dropped: u32,
+}
+
+impl<T, const N: usize> MeasurementBuffer<T, N> {
+    /// Create an empty buffer
+    pub const fn new() -> Self {
+        Self {
+            items: Deque::new(),
+            dropped: 0,
+        }
+    }
+
+    /// Append a reading, dropping the oldest when full
+    ///
+    /// Returns `true` if a reading was dropped to make room.
+    pub fn push(&mut self, item: T) -> bool {
+        let full = self.items.is_full();
+        if full {
+            self.items.pop_front();
+            self.dropped = self.dropped.saturating_add(1);
+        }
+        let _ = self.items.push_back(item);
+        full
+    }
+
+    /// Remove and return every buffered reading, oldest first
+    pub fn take_all(&mut self) -> Vec<T, N> {
+        let mut taken = Vec::new();
+        while let Some(item) = self.items.pop_front() {
+            let _ = taken.push(item);
+        }
+        taken
+    }
+
+    /// Put readings from a failed publish back in front of newer ones
+    ///
+    /// `items` is oldest first, as returned by [`take_all`](Self::take_all).
+    /// If readings arrived in the meantime and not everything fits, the
+    /// oldest restored readings are dropped and counted.
+    pub fn restore(&mut self, mut items: Vec<T, N>) {
+        while let Some(item) = items.pop() {
+            if self.items.push_front(item).is_err() {
+                self.dropped = self.dropped.saturating_add(1);
+            }
+        }
+    }
+
+    /// Oldest buffered reading
+    pub fn front(&self) -> Option<&T> {
+        self.items.front()
+    }
+
+    /// Newest buffered reading
+    pub fn back(&self) -> Option<&T> {
+        self.items.back()
+    }
+
+    /// Buffered readings, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.items.iter()
+    }
+
+    /// Number of buffered readings
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Check if nothing is waiting to be published
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Check if the next push drops the oldest reading
+    pub fn is_full(&self) -> bool {
+        self.items.is_full()
+    }
+
+    /// Maximum number of buffered readings
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Readings lost to overflow since boot
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    /// Forget the buffered readings after they were published
+    ///
+    /// The drop count is kept; it covers the whole uptime.
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+}
+
+impl<T, const N: usize> Default for MeasurementBuffer<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
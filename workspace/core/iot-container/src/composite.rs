@@ -0,0 +1,271 @@
+//! # Fan-Out Publishing
+//!
+//! [`CompositePublisher`] sends every message to several transports at once,
+//! e.g. MQTT for the dashboard, an HTTP webhook and a local CSV log. It is
+//! itself a [`MessagePublisher`], so the container publishes through it
+//! exactly as it would through a single transport:
+//!
+//! ```rust,ignore
+//! use iot_container::{CompositePublisher, FanOutPolicy};
+//!
+//! let publisher = CompositePublisher::new(FanOutPolicy::AnySuccess)
+//!     .with_transport("mqtt", mqtt_publisher)?
+//!     .with_transport("http", webhook_publisher)?
+//!     .with_transport("log", csv_log_publisher)?;
+//! ```
+//!
+//! Transports are called one after the other, and every transport is tried
+//! even when an earlier one failed. The [`FanOutPolicy`] decides whether the
+//! message as a whole counts as published. Each transport is expected to
+//! bound its own time (connect and write timeouts), since a hung transport
+//! delays the ones after it.
+
+use async_trait::async_trait;
+use alloc::boxed::Box;
+use heapless::Vec;
+
+use iot_common::{IoTError, ConfigError};
+
+use crate::traits::{MessagePublisher, SensorData, DeviceStatus, HealthCheck, ComponentHealth};
+
+/// Maximum number of transports in a [`CompositePublisher`]
+pub const MAX_TRANSPORTS: usize = 4;
+
+/// When a fanned-out message counts as published
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FanOutPolicy {
+    /// At least one transport accepted it; the others only count failures
+    #[default]
+    AnySuccess,
+
+    /// Every transport accepted it
+    AllSuccess,
+}
+
+/// Delivery counts of one transport
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TransportStats {
+    /// Messages the transport accepted
+    pub successes: u32,
+
+    /// Messages the transport failed to send
+    pub failures: u32,
+
+    /// Whether the most recent message failed
+    pub last_failed: bool,
+}
+
+impl TransportStats {
+    fn record(&mut self, succeeded: bool) {
+        if succeeded {
+            self.successes = self.successes.saturating_add(1);
+        } else {
+            self.failures = self.failures.saturating_add(1);
+        }
+        self.last_failed = !succeeded;
+    }
+}
+
+/// A named transport and its delivery counts
+struct Transport {
+    name: &'static str,
+    publisher: Box<dyn MessagePublisher + Send + Sync>,
+    stats: TransportStats,
+}
+
+/// Publisher forwarding every message to all of its transports
+pub struct CompositePublisher {
+    /// Transports in the order they are called
+    transports: Vec<Transport, MAX_TRANSPORTS>,
+
+    /// How per-transport results combine into one
+    policy: FanOutPolicy,
+}
+
+impl CompositePublisher {
+    /// Creates a publisher without transports
+    pub fn new(policy: FanOutPolicy) -> Self {
+        Self {
+            transports: Vec::new(),
+            policy,
+        }
+    }
+
+    /// Adds a transport, called after the ones already added
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Self)` - Publisher with the transport added
+    /// * `Err(IoTError)` - Already [`MAX_TRANSPORTS`] transports
+    pub fn with_transport<P>(mut self, name: &'static str, publisher: P) -> Result<Self, IoTError>
+    where
+        P: MessagePublisher + Send + Sync + 'static,
+    {
+        let transport = Transport {
+            name,
+            publisher: Box::new(publisher),
+            stats: TransportStats::default(),
+        };
+        if self.transports.push(transport).is_err() {
+            return Err(IoTError::configuration(
+                ConfigError::InvalidParameter("Too many publisher transports".try_into().unwrap_or_default())
+            ));
+        }
+        Ok(self)
+    }
+
+    /// Gets the policy combining the transport results
+    pub fn policy(&self) -> FanOutPolicy {
+        self.policy
+    }
+
+    /// Gets the number of transports
+    pub fn transport_count(&self) -> usize {
+        self.transports.len()
+    }
+
+    /// Gets the name and delivery counts of each transport, in call order
+    pub fn transport_stats(&self) -> impl Iterator<Item = (&'static str, TransportStats)> + '_ {
+        self.transports.iter().map(|transport| (transport.name, transport.stats))
+    }
+
+    /// Gets the delivery counts of the transport called `name`
+    pub fn stats(&self, name: &str) -> Option<TransportStats> {
+        self.transports.iter().find(|transport| transport.name == name).map(|transport| transport.stats)
+    }
+
+    /// Combines per-transport results under the policy
+    ///
+    /// The returned error is the first transport's, tagged with its name.
+    fn combine(&self, outcome: FanOutOutcome) -> Result<(), IoTError> {
+        let accepted = match self.policy {
+            FanOutPolicy::AnySuccess => outcome.succeeded > 0,
+            FanOutPolicy::AllSuccess => outcome.first_error.is_none(),
+        };
+        if accepted && !self.transports.is_empty() {
+            return Ok(());
+        }
+        Err(outcome.first_error.unwrap_or_else(|| IoTError::configuration(
+            ConfigError::MissingConfiguration("No publisher transports".try_into().unwrap_or_default())
+        )))
+    }
+}
+
+/// Results of one message across the transports
+#[derive(Default)]
+struct FanOutOutcome {
+    succeeded: usize,
+    first_error: Option<IoTError>,
+}
+
+impl FanOutOutcome {
+    fn record(&mut self, transport: &mut Transport, result: Result<(), IoTError>) {
+        transport.stats.record(result.is_ok());
+        match result {
+            Ok(()) => self.succeeded += 1,
+            Err(e) => {
+                if self.first_error.is_none() {
+                    self.first_error = Some(e.with_context(transport.name));
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl MessagePublisher for CompositePublisher {
+    async fn publish_sensor_data(&mut self, data: &SensorData) -> Result<(), IoTError> {
+        let mut outcome = FanOutOutcome::default();
+        for transport in self.transports.iter_mut() {
+            let result = transport.publisher.publish_sensor_data(data).await;
+            outcome.record(transport, result);
+        }
+        self.combine(outcome)
+    }
+
+    /// Hands the batch to each transport, which may send it as one message
+    async fn publish_sensor_batch(&mut self, batch: &[SensorData]) -> Result<(), IoTError> {
+        let mut outcome = FanOutOutcome::default();
+        for transport in self.transports.iter_mut() {
+            let result = transport.publisher.publish_sensor_batch(batch).await;
+            outcome.record(transport, result);
+        }
+        self.combine(outcome)
+    }
+
+    async fn publish_status(&mut self, status: &DeviceStatus) -> Result<(), IoTError> {
+        let mut outcome = FanOutOutcome::default();
+        for transport in self.transports.iter_mut() {
+            let result = transport.publisher.publish_status(status).await;
+            outcome.record(transport, result);
+        }
+        self.combine(outcome)
+    }
+
+    /// Connected under the policy: any transport, or all of them
+    async fn is_connected(&self) -> bool {
+        let mut connected = 0;
+        for transport in self.transports.iter() {
+            if transport.publisher.is_connected().await {
+                connected += 1;
+            }
+        }
+        match self.policy {
+            FanOutPolicy::AnySuccess => connected > 0,
+            FanOutPolicy::AllSuccess => connected > 0 && connected == self.transports.len(),
+        }
+    }
+
+    /// Connects every transport; connection attempts do not count as messages
+    async fn connect(&mut self) -> Result<(), IoTError> {
+        let mut outcome = FanOutOutcome::default();
+        for transport in self.transports.iter_mut() {
+            match transport.publisher.connect().await {
+                Ok(()) => outcome.succeeded += 1,
+                Err(e) => {
+                    if outcome.first_error.is_none() {
+                        outcome.first_error = Some(e.with_context(transport.name));
+                    }
+                }
+            }
+        }
+        self.combine(outcome)
+    }
+
+    async fn publish_heartbeat(&mut self) -> Result<(), IoTError> {
+        let mut outcome = FanOutOutcome::default();
+        for transport in self.transports.iter_mut() {
+            let result = transport.publisher.publish_heartbeat().await;
+            outcome.record(transport, result);
+        }
+        self.combine(outcome)
+    }
+
+    /// Sums the transport counts; uptime is the longest transport uptime
+    fn get_metrics(&self) -> (u32, u32, u32) {
+        self.transports.iter().fold((0, 0, 0), |(sent, failed, uptime), transport| {
+            let (_, _, transport_uptime) = transport.publisher.get_metrics();
+            (
+                sent.saturating_add(transport.stats.successes),
+                failed.saturating_add(transport.stats.failures),
+                uptime.max(transport_uptime),
+            )
+        })
+    }
+}
+
+impl HealthCheck for CompositePublisher {
+    /// Failed when no transport delivered the last message, degraded when some did not
+    fn health(&self) -> ComponentHealth {
+        let failing = self.transports.iter().filter(|transport| transport.stats.last_failed).count();
+        if self.transports.is_empty() {
+            ComponentHealth::failed("No publisher transports")
+        } else if failing == self.transports.len() {
+            ComponentHealth::failed("All transports failing")
+        } else if failing > 0 {
+            ComponentHealth::degraded("Some transports failing")
+        } else {
+            ComponentHealth::healthy("All transports delivering")
+        }
+    }
+}
@@ -1,12 +1,13 @@
 //! # System Configuration for IoT Container
 //!
 //! This module provides configuration management for the ESP32-C3 IoT system.
-//! Configuration is loaded from environment variables and provides sensible defaults
-//! for embedded operation.
+//! Configuration is layered: sensible defaults for embedded operation, overridden by
+//! compile-time environment variables (see [`SystemConfiguration::from_env`]).
 
+use core::fmt::Write;
 use serde::{Deserialize, Serialize};
 use heapless::String;
-use iot_common::{IoTError, ConfigError};
+use iot_common::{IoTError, ConfigError, error::ErrorMessage};
 
 /// Maximum length for configuration strings
 pub const MAX_CONFIG_STRING_LEN: usize = 64;
@@ -39,50 +40,139 @@ impl Default for OperatingMode {
     }
 }
 
-/// System logging level
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
-#[repr(u8)]
-pub enum LogLevel {
-    /// No logging output
-    None = 0,
-    
-    /// Only critical errors
-    Error = 1,
+/// How the main loop trades responsiveness for power
+/// 
+/// Independent of [`OperatingMode`], which selects how strictly errors are
+/// treated; any run mode can be used in any operating mode and switched at
+/// runtime with the console `mode <name>` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RunMode {
+    /// Read and publish every `sensor_read_interval_secs`
+    #[default]
+    Normal,
+    
+    /// Stretch the interval by [`LOW_POWER_INTERVAL_FACTOR`] and light-sleep between cycles
+    LowPower,
+    
+    /// Poll every [`DIAGNOSTIC_INTERVAL_SECS`] with debug logging and no sleep
+    Diagnostic,
+}
+
+/// Interval multiplier applied in [`RunMode::LowPower`]
+pub const LOW_POWER_INTERVAL_FACTOR: u32 = 4;
+
+/// Cycle interval in [`RunMode::Diagnostic`], unless the configured one is shorter
+pub const DIAGNOSTIC_INTERVAL_SECS: u64 = 1;
+
+impl RunMode {
+    /// Returns the mode name accepted by [`RunMode::from_name`]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunMode::Normal => "normal",
+            RunMode::LowPower => "lowpower",
+            RunMode::Diagnostic => "diagnostic",
+        }
+    }
     
-    /// Warnings and errors
-    Warning = 2,
+    /// Parses a mode name, case-insensitively (`low-power` is also accepted)
+    pub fn from_name(name: &str) -> Option<Self> {
+        [RunMode::Normal, RunMode::LowPower, RunMode::Diagnostic]
+            .into_iter()
+            .find(|mode| name.eq_ignore_ascii_case(mode.as_str()))
+            .or_else(|| name.eq_ignore_ascii_case("low-power").then_some(RunMode::LowPower))
+    }
     
-    /// General information, warnings, and errors
-    Info = 3,
+    /// Gets the cycle interval for this mode given the configured one
+    pub fn cycle_interval(&self, configured_secs: u64) -> embassy_time::Duration {
+        match self {
+            RunMode::Normal => embassy_time::Duration::from_secs(configured_secs),
+            RunMode::LowPower => embassy_time::Duration::from_secs(configured_secs) * LOW_POWER_INTERVAL_FACTOR,
+            RunMode::Diagnostic => embassy_time::Duration::from_secs(configured_secs.min(DIAGNOSTIC_INTERVAL_SECS)),
+        }
+    }
+}
+
+/// System logging level, shared with the `iot_common` log macros
+pub use iot_common::logging::LogLevel;
+
+/// How [`SmoothingSensorReader`](crate::smoothing::SmoothingSensorReader) combines a window of samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SmoothingStrategy {
+    /// Arithmetic mean of each field
+    Mean,
     
-    /// Detailed debug information
-    Debug = 4,
+    /// Median of each field - discards single-sample spikes
+    Median,
+}
+
+impl Default for SmoothingStrategy {
+    fn default() -> Self {
+        Self::Mean
+    }
+}
+
+/// Register holding the chip ID on Bosch BME280/BMP280 sensors
+pub const SENSOR_CHIP_ID_REG: u8 = 0xD0;
+
+/// Sensor model the factory instantiates
+/// 
+/// Serialized as the upper-case model name (`"BME280"`, `"BMP280"`, `"MOCK"`),
+/// so existing JSON configuration keeps loading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SensorType {
+    /// Bosch BME280 - temperature, pressure and humidity
+    #[default]
+    #[serde(rename = "BME280")]
+    Bme280,
+    
+    /// Bosch BMP280 - temperature and pressure only
+    #[serde(rename = "BMP280")]
+    Bmp280,
+    
+    /// In-memory sensor for tests (requires the `mock` feature)
+    #[serde(rename = "MOCK")]
+    Mock,
 }
 
-impl LogLevel {
-    /// Returns the log level as a string
+impl SensorType {
+    /// Model name as written in configuration
     pub fn as_str(&self) -> &'static str {
         match self {
-            LogLevel::None => "NONE",
-            LogLevel::Error => "ERROR",
-            LogLevel::Warning => "WARN",
-            LogLevel::Info => "INFO",
-            LogLevel::Debug => "DEBUG",
+            SensorType::Bme280 => "BME280",
+            SensorType::Bmp280 => "BMP280",
+            SensorType::Mock => "MOCK",
         }
     }
+    
+    /// Value the chip reports in [`SENSOR_CHIP_ID_REG`]; `None` for sensors not on the bus
+    pub fn chip_id(&self) -> Option<u8> {
+        match self {
+            SensorType::Bme280 => Some(0x60),
+            SensorType::Bmp280 => Some(0x58),
+            SensorType::Mock => None,
+        }
+    }
+    
+    /// Sensor model reporting `chip_id`, if it is one we support
+    pub fn from_chip_id(chip_id: u8) -> Option<Self> {
+        [SensorType::Bme280, SensorType::Bmp280]
+            .into_iter()
+            .find(|sensor_type| sensor_type.chip_id() == Some(chip_id))
+    }
 }
 
-impl Default for LogLevel {
-    fn default() -> Self {
-        Self::Info
+impl core::fmt::Display for SensorType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
 /// Sensor configuration
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SensorConfig {
-    /// Type of sensor (e.g., "BME280", "SHT30")
-    pub sensor_type: ConfigString,
+    /// Sensor model; the factory checks it against the chip ID on the bus
+    #[serde(default)]
+    pub sensor_type: SensorType,
     
     /// I2C address (0x76 or 0x77 for BME280)
     pub i2c_address: u8,
@@ -98,17 +188,31 @@ pub struct SensorConfig {
     
     /// Enable sensor self-test on initialization
     pub enable_self_test: bool,
+    
+    /// Samples combined into one reading (1 = no smoothing, max [`crate::MAX_SMOOTHING_WINDOW`])
+    #[serde(default = "default_smoothing_window")]
+    pub smoothing_window: u8,
+    
+    /// How the samples in a smoothing window are combined
+    #[serde(default)]
+    pub smoothing_strategy: SmoothingStrategy,
+}
+
+fn default_smoothing_window() -> u8 {
+    1
 }
 
 impl Default for SensorConfig {
     fn default() -> Self {
         Self {
-            sensor_type: ConfigString::try_from("BME280").unwrap(),
+            sensor_type: SensorType::Bme280,
             i2c_address: 0x76,
             oversampling: 1,
             filter_coefficient: 0,
             measurement_timeout_ms: 1000,
             enable_self_test: true,
+            smoothing_window: default_smoothing_window(),
+            smoothing_strategy: SmoothingStrategy::Mean,
         }
     }
 }
@@ -164,6 +268,10 @@ pub struct MqttConfig {
     /// Client identifier
     pub client_id: ConfigString,
     
+    /// Keep `client_id` instead of deriving one from the device MAC
+    #[serde(default)]
+    pub fixed_client_id: bool,
+    
     /// Username for authentication (optional)
     pub username: Option<ConfigString>,
     
@@ -187,6 +295,31 @@ pub struct MqttConfig {
     
     /// Maximum retry attempts for failed operations
     pub max_retry_attempts: u32,
+    
+    /// RSSI in dBm below which publishes are throttled
+    #[serde(default = "default_weak_signal_threshold_dbm")]
+    pub weak_signal_threshold_dbm: i8,
+    
+    /// Minimum time between publishes while throttled, in seconds
+    #[serde(default = "default_weak_signal_publish_interval_secs")]
+    pub weak_signal_publish_interval_secs: u64,
+    
+    /// Weight of the newest reading in the moving average of published values,
+    /// in (0, 1]; 1.0 publishes raw readings (see [`ExponentialSmoother`](crate::smoothing::ExponentialSmoother))
+    #[serde(default = "default_publish_smoothing_alpha")]
+    pub publish_smoothing_alpha: f32,
+}
+
+fn default_weak_signal_threshold_dbm() -> i8 {
+    -80
+}
+
+fn default_weak_signal_publish_interval_secs() -> u64 {
+    300
+}
+
+fn default_publish_smoothing_alpha() -> f32 {
+    1.0
 }
 
 impl Default for MqttConfig {
@@ -195,6 +328,7 @@ impl Default for MqttConfig {
             broker_host: ConfigString::try_from("mqtt.broker.local").unwrap(),
             broker_port: 1883,
             client_id: ConfigString::try_from("esp32c3_iot_device").unwrap(),
+            fixed_client_id: false,
             username: None,
             password: None,
             topic_prefix: ConfigString::try_from("iot/esp32c3").unwrap(),
@@ -203,10 +337,37 @@ impl Default for MqttConfig {
             keep_alive_secs: 60,
             connection_timeout_secs: 30,
             max_retry_attempts: 3,
+            weak_signal_threshold_dbm: default_weak_signal_threshold_dbm(),
+            weak_signal_publish_interval_secs: default_weak_signal_publish_interval_secs(),
+            publish_smoothing_alpha: default_publish_smoothing_alpha(),
         }
     }
 }
 
+impl MqttConfig {
+    /// Replaces the client ID with one derived from the MAC, e.g. `esp32c3-AABBCCDDEEFF`
+    /// 
+    /// Boards flashed from the same build otherwise share a client ID and the
+    /// broker keeps disconnecting one of them. Leaves the ID alone when
+    /// `fixed_client_id` is set, for fleets that assign IDs centrally.
+    /// 
+    /// # Errors
+    /// 
+    /// Returns an error if the generated ID exceeds the 23 characters every
+    /// MQTT 3.1.1 broker must accept.
+    pub fn with_device_id(mut self, mac: [u8; 6]) -> Result<Self, IoTError> {
+        if self.fixed_client_id {
+            return Ok(self);
+        }
+        
+        let id = iot_common::device_client_id(&mac).ok_or_else(|| {
+            IoTError::configuration(ConfigError::ValidationError("Device client ID exceeds MQTT limit".try_into().unwrap_or_default()))
+        })?;
+        self.client_id = env_string("MQTT client ID", &id)?;
+        Ok(self)
+    }
+}
+
 /// Console configuration
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ConsoleConfig {
@@ -250,6 +411,32 @@ impl Default for ConsoleConfig {
     }
 }
 
+/// Retry behaviour for one class of fallible operations, shared with [`iot_common::retry`]
+pub use iot_common::RetryPolicy;
+
+/// Retry policies for each container operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetryConfig {
+    /// Sensor reads - short, since a stale reading is worth little
+    pub sensor: RetryPolicy,
+    
+    /// Network reconnection - patient, connecting can take seconds
+    pub network: RetryPolicy,
+    
+    /// Message publishing
+    pub publish: RetryPolicy,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            sensor: RetryPolicy::default(),
+            network: RetryPolicy::new(5, 1_000, 2.0, 30_000),
+            publish: RetryPolicy::new(3, 1_000, 2.0, 10_000),
+        }
+    }
+}
+
 /// Complete system configuration
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SystemConfiguration {
@@ -259,6 +446,10 @@ pub struct SystemConfiguration {
     /// System operating mode
     pub operation_mode: OperatingMode,
     
+    /// Main loop run mode, changeable at runtime
+    #[serde(default)]
+    pub run_mode: RunMode,
+    
     /// System logging level
     pub log_level: LogLevel,
     
@@ -291,6 +482,9 @@ pub struct SystemConfiguration {
     
     /// Console configuration
     pub console: ConsoleConfig,
+    
+    /// Retry policies for sensor, network and publish operations
+    pub retry: RetryConfig,
 }
 
 impl Default for SystemConfiguration {
@@ -298,6 +492,7 @@ impl Default for SystemConfiguration {
         Self {
             device_id: DeviceId::try_from("esp32c3_iot_001").unwrap(),
             operation_mode: OperatingMode::Development,
+            run_mode: RunMode::Normal,
             log_level: LogLevel::Info,
             sensor_read_interval_secs: 30,
             status_report_interval_secs: 300, // 5 minutes
@@ -309,78 +504,138 @@ impl Default for SystemConfiguration {
             wifi: WiFiConfig::default(),
             mqtt: MqttConfig::default(),
             console: ConsoleConfig::default(),
+            retry: RetryConfig::default(),
         }
     }
 }
 
 impl SystemConfiguration {
-    /// Creates configuration from environment variables
+    /// Creates configuration from compile-time environment variables
     /// 
-    /// Loads configuration values from environment variables with fallback to defaults.
-    /// Environment variables follow the pattern: IOT_<SECTION>_<PARAMETER>
+    /// Starts from [`SystemConfiguration::default()`] and overrides each field whose
+    /// variable was set when the firmware was built (normally through the `[env]`
+    /// table in `.cargo/config.toml`, the same variables the drivers read). Unset
+    /// variables keep their defaults; a variable that is set but invalid is an
+    /// error instead of being silently ignored.
     /// 
     /// # Examples
     /// 
     /// Environment variables:
-    /// - `IOT_DEVICE_ID=esp32c3_sensor_01`
-    /// - `IOT_WIFI_SSID=MyNetwork`
-    /// - `IOT_WIFI_PASSWORD=SecretPassword`
-    /// - `IOT_MQTT_BROKER_HOST=192.168.1.100`
-    /// - `IOT_MQTT_BROKER_PORT=1883`
+    /// - `DEVICE_ID=esp32c3_sensor_01`
+    /// - `OPERATION_MODE=production` (`development`, `testing`, `production`)
+    /// - `RUN_MODE=lowpower` (`normal`, `lowpower`, `diagnostic`)
+    /// - `LOG_LEVEL=debug` (`none`, `error`, `warn`, `info`, `debug`, `trace`)
+    /// - `SENSOR_READ_INTERVAL_SECS=60`
+    /// - `WIFI_SSID=MyNetwork`
+    /// - `WIFI_PASSWORD=SecretPassword`
+    /// - `MQTT_BROKER_IP=192.168.1.100`
+    /// - `MQTT_BROKER_PORT=1883`
+    /// - `MQTT_CLIENT_ID=esp32c3_sensor_01`
+    /// - `MQTT_CLIENT_ID_FIXED=true` (keep the ID above instead of deriving one from the MAC)
+    /// - `MQTT_TOPIC_PREFIX=home/livingroom`
+    /// - `MQTT_WEAK_SIGNAL_DBM=-80`
+    /// - `MQTT_WEAK_SIGNAL_INTERVAL_SECS=300`
+    /// - `MQTT_PUBLISH_SMOOTHING_ALPHA=0.3` (1.0 publishes raw readings)
     /// 
     /// # Returns
     /// 
     /// * `Ok(SystemConfiguration)` - Configuration loaded successfully
-    /// * `Err(IoTError)` - Configuration loading failed
+    /// * `Err(IoTError)` - A provided value could not be parsed or failed validation
     pub fn from_env() -> Result<Self, IoTError> {
+        Self::from_overrides(compile_time_env)
+    }
+    
+    /// Creates configuration from defaults overridden by `lookup`
+    /// 
+    /// `lookup` returns the value for a variable name (see [`from_env`](Self::from_env)
+    /// for the recognised names), or `None` to keep the default. The result is
+    /// validated before it is returned.
+    pub fn from_overrides<'a, F>(lookup: F) -> Result<Self, IoTError>
+    where
+        F: Fn(&str) -> Option<&'a str>,
+    {
         let mut config = Self::default();
         
-        // Load device configuration (using defaults for now - env! requires compile-time variables)
-        let device_id = "esp32c3_iot_001";
-        config.device_id = DeviceId::try_from(device_id).map_err(|_| {
-            IoTError::configuration(ConfigError::ValidationError("Device ID too long".try_into().unwrap_or_default()))
-        })?;
+        // Device configuration
+        if let Some(value) = lookup("DEVICE_ID") {
+            config.device_id = env_string("DEVICE_ID", value)?;
+        }
         
-        // Load WiFi configuration (using defaults for now)
-        let ssid = "IoT_Network";
-        config.wifi.ssid = ConfigString::try_from(ssid).map_err(|_| {
-            IoTError::configuration(ConfigError::ValidationError("WiFi SSID too long".try_into().unwrap_or_default()))
-        })?;
+        if let Some(value) = lookup("OPERATION_MODE") {
+            config.operation_mode = if value.eq_ignore_ascii_case("production") {
+                OperatingMode::Production
+            } else if value.eq_ignore_ascii_case("testing") {
+                OperatingMode::Testing
+            } else if value.eq_ignore_ascii_case("development") {
+                OperatingMode::Development
+            } else {
+                return Err(invalid_env("OPERATION_MODE", value));
+            };
+        }
         
-        let password = "password123";
-        config.wifi.password = ConfigString::try_from(password).map_err(|_| {
-            IoTError::configuration(ConfigError::ValidationError("WiFi password too long".try_into().unwrap_or_default()))
-        })?;
+        if let Some(value) = lookup("RUN_MODE") {
+            config.run_mode = RunMode::from_name(value)
+                .ok_or_else(|| invalid_env("RUN_MODE", value))?;
+        }
         
-        // Load MQTT configuration (using defaults for now)
-        let broker = "mqtt.broker.local";
-        config.mqtt.broker_host = ConfigString::try_from(broker).map_err(|_| {
-            IoTError::configuration(ConfigError::ValidationError("MQTT broker host too long".try_into().unwrap_or_default()))
-        })?;
+        if let Some(value) = lookup("LOG_LEVEL") {
+            config.log_level = LogLevel::from_name(value)
+                .ok_or_else(|| invalid_env("LOG_LEVEL", value))?;
+        }
         
-        let port_str = "1883";
-        config.mqtt.broker_port = port_str.parse().map_err(|_| {
-            IoTError::configuration(ConfigError::ValidationError("Invalid MQTT broker port".try_into().unwrap_or_default()))
-        })?;
+        if let Some(value) = lookup("SENSOR_READ_INTERVAL_SECS") {
+            config.sensor_read_interval_secs = value.parse()
+                .map_err(|_| invalid_env("SENSOR_READ_INTERVAL_SECS", value))?;
+        }
         
-        // Load operation mode (using defaults for now)
-        let mode_str = "development";
-        config.operation_mode = match mode_str.to_lowercase().as_str() {
-            "production" => OperatingMode::Production,
-            "testing" => OperatingMode::Testing,
-            _ => OperatingMode::Development,
-        };
-        
-        // Load log level (using defaults for now)
-        let log_str = "info";
-        config.log_level = match log_str.to_lowercase().as_str() {
-                "none" => LogLevel::None,
-                "error" => LogLevel::Error,
-                "warning" | "warn" => LogLevel::Warning,
-                "debug" => LogLevel::Debug,
-                _ => LogLevel::Info,
-            };
+        // WiFi configuration
+        if let Some(value) = lookup("WIFI_SSID") {
+            config.wifi.ssid = env_string("WIFI_SSID", value)?;
+        }
+        
+        if let Some(value) = lookup("WIFI_PASSWORD") {
+            config.wifi.password = env_string("WIFI_PASSWORD", value)?;
+        }
+        
+        // MQTT configuration
+        if let Some(value) = lookup("MQTT_BROKER_IP") {
+            config.mqtt.broker_host = env_string("MQTT_BROKER_IP", value)?;
+        }
+        
+        if let Some(value) = lookup("MQTT_BROKER_PORT") {
+            config.mqtt.broker_port = value.parse()
+                .map_err(|_| invalid_env("MQTT_BROKER_PORT", value))?;
+        }
+        
+        if let Some(value) = lookup("MQTT_CLIENT_ID") {
+            config.mqtt.client_id = env_string("MQTT_CLIENT_ID", value)?;
+        }
+        
+        if let Some(value) = lookup("MQTT_CLIENT_ID_FIXED") {
+            config.mqtt.fixed_client_id = value.parse()
+                .map_err(|_| invalid_env("MQTT_CLIENT_ID_FIXED", value))?;
+        }
+        
+        if let Some(value) = lookup("MQTT_TOPIC_PREFIX") {
+            config.mqtt.topic_prefix = env_string("MQTT_TOPIC_PREFIX", value)?;
+        }
+        
+        if let Some(value) = lookup("MQTT_WEAK_SIGNAL_DBM") {
+            config.mqtt.weak_signal_threshold_dbm = value.parse()
+                .map_err(|_| invalid_env("MQTT_WEAK_SIGNAL_DBM", value))?;
+        }
+        
+        if let Some(value) = lookup("MQTT_WEAK_SIGNAL_INTERVAL_SECS") {
+            config.mqtt.weak_signal_publish_interval_secs = value.parse()
+                .map_err(|_| invalid_env("MQTT_WEAK_SIGNAL_INTERVAL_SECS", value))?;
+        }
+        
+        if let Some(value) = lookup("MQTT_PUBLISH_SMOOTHING_ALPHA") {
+            config.mqtt.publish_smoothing_alpha = value.parse()
+                .map_err(|_| invalid_env("MQTT_PUBLISH_SMOOTHING_ALPHA", value))?;
+        }
         
+        config.validate()?;
         Ok(config)
     }
     
@@ -396,6 +651,7 @@ impl SystemConfiguration {
         Self {
             device_id: DeviceId::try_from("test_device_001").unwrap(),
             operation_mode: OperatingMode::Testing,
+            run_mode: RunMode::Normal,
             log_level: LogLevel::Debug,
             sensor_read_interval_secs: 1, // Fast for testing
             status_report_interval_secs: 5, // Fast for testing
@@ -425,6 +681,19 @@ impl SystemConfiguration {
                 output_buffer_size: 256, // Smaller for testing
                 ..ConsoleConfig::default()
             },
+            retry: RetryConfig {
+                sensor: RetryPolicy::new(3, 10, 2.0, 40), // Fast for testing
+                network: RetryPolicy::new(2, 10, 2.0, 50), // Fast for testing
+                publish: RetryPolicy::new(2, 10, 2.0, 50), // Fast for testing
+            },
+        }
+    }
+    
+    /// Gets the log level in effect, raised to at least `Debug` in [`RunMode::Diagnostic`]
+    pub fn effective_log_level(&self) -> LogLevel {
+        match self.run_mode {
+            RunMode::Diagnostic => self.log_level.max(LogLevel::Debug),
+            _ => self.log_level,
         }
     }
     
@@ -460,6 +729,10 @@ impl SystemConfiguration {
             return Err(IoTError::configuration(ConfigError::ValidationError("Oversampling must be 1-16".try_into().unwrap_or_default())));
         }
         
+        if self.sensor.smoothing_window == 0 || self.sensor.smoothing_window as usize > crate::MAX_SMOOTHING_WINDOW {
+            return Err(IoTError::configuration(ConfigError::ValidationError("Smoothing window must be 1-16".try_into().unwrap_or_default())));
+        }
+        
         // Validate WiFi configuration
         if self.wifi.ssid.is_empty() {
             return Err(IoTError::configuration(ConfigError::ValidationError("WiFi SSID cannot be empty".try_into().unwrap_or_default())));
@@ -474,15 +747,32 @@ impl SystemConfiguration {
             return Err(IoTError::configuration(ConfigError::ValidationError("MQTT broker port must be > 0".try_into().unwrap_or_default())));
         }
         
+        if self.mqtt.client_id.is_empty() {
+            return Err(IoTError::configuration(ConfigError::ValidationError("MQTT client ID cannot be empty".try_into().unwrap_or_default())));
+        }
+        
         if self.mqtt.qos_level > 2 {
             return Err(IoTError::configuration(ConfigError::ValidationError("MQTT QoS level must be 0-2".try_into().unwrap_or_default())));
         }
         
+        if self.mqtt.weak_signal_publish_interval_secs == 0 {
+            return Err(IoTError::configuration(ConfigError::ValidationError("Weak signal publish interval must be > 0".try_into().unwrap_or_default())));
+        }
+        
+        if !(self.mqtt.publish_smoothing_alpha > 0.0 && self.mqtt.publish_smoothing_alpha <= 1.0) {
+            return Err(IoTError::configuration(ConfigError::ValidationError("Publish smoothing alpha must be in (0, 1]".try_into().unwrap_or_default())));
+        }
+        
         // Validate console configuration
         if self.console.input_buffer_size == 0 || self.console.output_buffer_size == 0 {
             return Err(IoTError::configuration(ConfigError::ValidationError("Console buffer sizes must be > 0".try_into().unwrap_or_default())));
         }
         
+        // Validate retry policies
+        self.retry.sensor.validate()?;
+        self.retry.network.validate()?;
+        self.retry.publish.validate()?;
+        
         Ok(())
     }
     
@@ -497,7 +787,7 @@ impl SystemConfiguration {
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// let mut config = SystemConfiguration::default();
     /// let partial = PartialSystemConfiguration {
     ///     log_level: Some(LogLevel::Debug),
@@ -540,4 +830,46 @@ pub struct PartialSystemConfiguration {
     
     /// Status report interval update
     pub status_report_interval_secs: Option<u64>,
-}
\ No newline at end of file
+}
+
+/// Values captured from the build environment
+fn compile_time_env(key: &str) -> Option<&'static str> {
+    match key {
+        "DEVICE_ID" => option_env!("DEVICE_ID"),
+        "OPERATION_MODE" => option_env!("OPERATION_MODE"),
+        "RUN_MODE" => option_env!("RUN_MODE"),
+        "LOG_LEVEL" => option_env!("LOG_LEVEL"),
+        "SENSOR_READ_INTERVAL_SECS" => option_env!("SENSOR_READ_INTERVAL_SECS"),
+        "WIFI_SSID" => option_env!("WIFI_SSID"),
+        "WIFI_PASSWORD" => option_env!("WIFI_PASSWORD"),
+        "MQTT_BROKER_IP" => option_env!("MQTT_BROKER_IP"),
+        "MQTT_BROKER_PORT" => option_env!("MQTT_BROKER_PORT"),
+        "MQTT_CLIENT_ID" => option_env!("MQTT_CLIENT_ID"),
+        "MQTT_CLIENT_ID_FIXED" => option_env!("MQTT_CLIENT_ID_FIXED"),
+        "MQTT_TOPIC_PREFIX" => option_env!("MQTT_TOPIC_PREFIX"),
+        "MQTT_WEAK_SIGNAL_DBM" => option_env!("MQTT_WEAK_SIGNAL_DBM"),
+        "MQTT_WEAK_SIGNAL_INTERVAL_SECS" => option_env!("MQTT_WEAK_SIGNAL_INTERVAL_SECS"),
+        "MQTT_PUBLISH_SMOOTHING_ALPHA" => option_env!("MQTT_PUBLISH_SMOOTHING_ALPHA"),
+        _ => None,
+    }
+}
+
+/// Copy an override into a bounded configuration string
+fn env_string<const N: usize>(key: &str, value: &str) -> Result<String<N>, IoTError> {
+    String::try_from(value).map_err(|_| {
+        let mut message = ErrorMessage::new();
+        let _ = write!(message, "{} too long (max {} bytes)", key, N);
+        IoTError::configuration(ConfigError::ValidationError(message))
+    })
+}
+
+/// Error for an override that could not be parsed
+fn invalid_env(key: &str, value: &str) -> IoTError {
+    let mut message = ErrorMessage::new();
+    if write!(message, "Invalid {}: '{}'", key, value).is_err() {
+        // Value too long to quote - name the variable only
+        message.clear();
+        let _ = write!(message, "Invalid {}", key);
+    }
+    IoTError::configuration(ConfigError::ParsingError(message))
+}
@@ -4,23 +4,30 @@
 //! The container manages all system components and orchestrates their interactions while
 //! maintaining clean separation of concerns and enabling comprehensive testing.
 
+use core::fmt::Write;
+
 use embassy_time::{Duration, Timer, Instant};
+use embassy_futures::select::{select, Either};
 use embassy_sync::signal::Signal;
 use embassy_sync::mutex::Mutex;
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use heapless::Deque;
+use serde::{Deserialize, Serialize};
 
-use iot_common::{IoTError, IoTResult};
-use iot_hal::HardwarePlatform;
+use iot_common::{IoTError, IoTResult, SystemError, retry};
+use iot_hal::{HardwarePlatform, PowerInterface};
+use iot_storage::{ConfigStorage, StorageBackend, UnifiedStorageManager, MaintenanceOutcome};
 
 use crate::traits::{
     SensorReader, NetworkManager, MessagePublisher, ConsoleInterface,
-    Measurements, SensorData, DeviceStatus, EmbeddedString
+    Measurements, SensorData, DeviceStatus, EmbeddedString,
+    HealthCheck, HealthState, ComponentHealth
 };
-use crate::config::{SystemConfiguration, OperatingMode, LogLevel};
-
-/// Maximum number of measurements to buffer
-const MAX_MEASUREMENT_BUFFER: usize = 16;
+use crate::config::{SystemConfiguration, OperatingMode, RunMode, LogLevel};
+use crate::throttle::{PublishThrottle, ThrottleTransition};
+use crate::smoothing::ExponentialSmoother;
+use crate::buffer::MeasurementBuffer;
+use crate::MAX_MEASUREMENT_BUFFER;
 
 /// Maximum number of pending console commands
 #[allow(dead_code)]
@@ -47,6 +54,12 @@ pub struct SystemState {
     /// Total number of messages published
     pub messages_published_count: u32,
     
+    /// Readings waiting to be published
+    pub buffered_readings: u32,
+    
+    /// Readings lost to a full offline buffer since boot
+    pub dropped_readings: u32,
+    
     /// System uptime in seconds
     pub uptime_seconds: u32,
     
@@ -67,6 +80,8 @@ impl SystemState {
             console_active: false,
             sensor_readings_count: 0,
             messages_published_count: 0,
+            buffered_readings: 0,
+            dropped_readings: 0,
             uptime_seconds: 0,
             last_error_code: 0,
             free_heap_bytes: 0,
@@ -74,17 +89,213 @@ impl SystemState {
     }
 }
 
-/// Shared system state accessible by all tasks
-static SYSTEM_STATE: Mutex<CriticalSectionRawMutex, SystemState> = 
-    Mutex::new(SystemState::new());
+/// Aggregated health of all container components
+#[derive(Debug, Clone, PartialEq)]
+pub struct SystemHealth {
+    /// Sensor reader health
+    pub sensor: ComponentHealth,
+    
+    /// Network manager health
+    pub network: ComponentHealth,
+    
+    /// Message publisher health
+    pub publisher: ComponentHealth,
+    
+    /// Console interface health
+    pub console: ComponentHealth,
+}
 
-/// Signal for sharing sensor data between tasks
-static SENSOR_DATA_SIGNAL: Signal<CriticalSectionRawMutex, Measurements> = Signal::new();
+impl SystemHealth {
+    /// Returns the components paired with their display names
+    pub fn components(&self) -> [(&'static str, &ComponentHealth); 4] {
+        [
+            ("sensor", &self.sensor),
+            ("network", &self.network),
+            ("publisher", &self.publisher),
+            ("console", &self.console),
+        ]
+    }
+    
+    /// Gets the worst state across all components
+    pub fn overall(&self) -> HealthState {
+        self.components()
+            .iter()
+            .map(|(_, health)| health.state)
+            .max()
+            .unwrap_or(HealthState::Healthy)
+    }
+    
+    /// Get overall health score (0-100, higher is better)
+    pub fn health_score(&self) -> u8 {
+        self.components().iter().fold(100u8, |score, (_, health)| {
+            score.saturating_sub(match health.state {
+                HealthState::Healthy => 0,
+                HealthState::Degraded => 15,
+                HealthState::Failed => 40,
+            })
+        })
+    }
+    
+    /// Check if every component is healthy
+    pub fn is_healthy(&self) -> bool {
+        self.overall() == HealthState::Healthy
+    }
+    
+    /// Get health status as string
+    pub fn status(&self) -> &'static str {
+        let score = self.health_score();
+        match score {
+            90..=100 => "Excellent",
+            70..=89 => "Good",
+            50..=69 => "Fair",
+            30..=49 => "Poor",
+            _ => "Critical",
+        }
+    }
+}
 
 /// Signal for sharing console commands between tasks
 #[allow(dead_code)]
 static CONSOLE_COMMAND_SIGNAL: Signal<CriticalSectionRawMutex, EmbeddedString> = Signal::new();
 
+/// Signal raised when the network cycle has drained the publish queue
+static STORAGE_IDLE_SIGNAL: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+/// Result of the latest storage maintenance run, shared with the console
+static LAST_STORAGE_MAINTENANCE: Mutex<CriticalSectionRawMutex, Option<MaintenanceReport>> =
+    Mutex::new(None);
+
+/// Configuration name under which counters are persisted on shutdown
+pub const PERSISTED_STATE_CONFIG: &str = "system_state";
+
+/// Counters persisted across a commanded restart
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PersistedState {
+    /// Total number of sensor readings taken
+    pub sensor_readings_count: u32,
+    
+    /// Total number of messages published
+    pub messages_published_count: u32,
+    
+    /// Uptime reached before shutdown in seconds
+    pub uptime_seconds: u32,
+    
+    /// Last error code (0 = no error)
+    pub last_error_code: u32,
+}
+
+impl From<&SystemState> for PersistedState {
+    fn from(state: &SystemState) -> Self {
+        Self {
+            sensor_readings_count: state.sensor_readings_count,
+            messages_published_count: state.messages_published_count,
+            uptime_seconds: state.uptime_seconds,
+            last_error_code: state.last_error_code,
+        }
+    }
+}
+
+/// Stop request for the main loop of one container
+/// 
+/// Declare it `static` and hand it to [`IoTContainer::with_shutdown_signal`];
+/// any task can then [`request`](Self::request) a stop. The loop finishes its
+/// current cycle and returns `Ok(())`, consuming the request; the caller then
+/// runs [`IoTContainer::restart`], or [`IoTContainer::shutdown`] to stay down.
+/// 
+/// # Example
+/// 
+/// ```rust,ignore
+/// static SHUTDOWN: ShutdownSignal = ShutdownSignal::new();
+/// 
+/// let mut container = IoTContainer::new(platform, sensor, network, publisher, console, config)
+///     .await?
+///     .with_shutdown_signal(&SHUTDOWN);
+/// // From another task:
+/// SHUTDOWN.request();
+/// ```
+pub struct ShutdownSignal(Signal<CriticalSectionRawMutex, ()>);
+
+impl ShutdownSignal {
+    /// Creates a signal with no stop requested
+    pub const fn new() -> Self {
+        Self(Signal::new())
+    }
+    
+    /// Asks the container loop to stop after its current cycle
+    pub fn request(&self) {
+        self.0.signal(());
+    }
+    
+    /// Checks whether a stop has been requested and not yet handled
+    pub fn is_requested(&self) -> bool {
+        self.0.signaled()
+    }
+}
+
+impl Default for ShutdownSignal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Record of a storage maintenance run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MaintenanceReport {
+    /// Time the run finished, in milliseconds since boot
+    pub timestamp_ms: u64,
+    
+    /// How long the run took in milliseconds
+    pub duration_ms: u64,
+    
+    /// Outcome reported by the storage manager
+    pub outcome: MaintenanceOutcome,
+}
+
+/// Runs storage maintenance whenever the container loop goes idle
+/// 
+/// Waits for the network cycle to report an empty publish queue, then runs
+/// [`UnifiedStorageManager::maintenance_if_needed`]. The storage manager
+/// refuses to collect garbage while a transaction is open, in which case the
+/// run is recorded as deferred and retried at the next idle period. Spawn it
+/// from an application task next to the container:
+/// 
+/// ```rust,ignore
+/// #[embassy_executor::task]
+/// async fn storage_maintenance_task(storage: &'static StorageMutex) -> ! {
+///     iot_container::run_storage_maintenance(storage).await
+/// }
+/// ```
+pub async fn run_storage_maintenance<B: StorageBackend>(
+    storage: &Mutex<CriticalSectionRawMutex, UnifiedStorageManager<B>>,
+) -> ! {
+    loop {
+        STORAGE_IDLE_SIGNAL.wait().await;
+        run_storage_maintenance_once(storage).await;
+    }
+}
+
+/// Runs a single maintenance check, returning the report if anything ran
+pub async fn run_storage_maintenance_once<B: StorageBackend>(
+    storage: &Mutex<CriticalSectionRawMutex, UnifiedStorageManager<B>>,
+) -> Option<MaintenanceReport> {
+    let started = Instant::now();
+    let outcome = storage.lock().await.maintenance_if_needed().await?;
+    let finished = Instant::now();
+    
+    let report = MaintenanceReport {
+        timestamp_ms: finished.as_millis(),
+        duration_ms: (finished - started).as_millis(),
+        outcome,
+    };
+    *LAST_STORAGE_MAINTENANCE.lock().await = Some(report);
+    Some(report)
+}
+
+/// Returns the latest storage maintenance run, `None` if none has run yet
+pub async fn last_storage_maintenance() -> Option<MaintenanceReport> {
+    *LAST_STORAGE_MAINTENANCE.lock().await
+}
+
 /// IoT Dependency Injection Container
 /// 
 /// The main container that manages all system components and orchestrates their interactions.
@@ -101,7 +312,7 @@ static CONSOLE_COMMAND_SIGNAL: Signal<CriticalSectionRawMutex, EmbeddedString> =
 /// 
 /// # Examples
 /// 
-/// ```rust,no_run
+/// ```rust,ignore
 /// use iot_container::{IoTContainer, ComponentFactory, SystemConfiguration};
 /// use iot_hal::Esp32C3Platform;
 /// 
@@ -121,13 +332,12 @@ static CONSOLE_COMMAND_SIGNAL: Signal<CriticalSectionRawMutex, EmbeddedString> =
 pub struct IoTContainer<P, S, N, M, C>
 where
     P: HardwarePlatform,
-    S: SensorReader,
-    N: NetworkManager,
-    M: MessagePublisher,
-    C: ConsoleInterface,
+    S: SensorReader + HealthCheck,
+    N: NetworkManager + HealthCheck,
+    M: MessagePublisher + HealthCheck + Send,
+    C: ConsoleInterface + HealthCheck,
 {
     /// Hardware platform abstraction
-    #[allow(dead_code)]
     platform: P,
     
     /// Environmental sensor reader
@@ -148,20 +358,38 @@ where
     /// Measurement buffer for data smoothing
     measurement_buffer: Deque<Measurements, MAX_MEASUREMENT_BUFFER>,
     
+    /// Readings not yet published, held while offline (oldest dropped when full)
+    pending_publish: MeasurementBuffer<Measurements>,
+    
+    /// Batches publishes while the WiFi signal is weak
+    publish_throttle: PublishThrottle,
+    
+    /// Moving average applied to published readings; the measurement buffer keeps them raw
+    publish_smoother: ExponentialSmoother,
+    
     /// System start time for uptime calculation
     start_time: Instant,
     
     /// Device identifier for published messages
     device_id: EmbeddedString,
+    
+    /// System state of this container, shared with the tasks it runs
+    state: Mutex<CriticalSectionRawMutex, SystemState>,
+    
+    /// Set by the console `restart` command; stops the main loop after the cycle
+    restart_requested: bool,
+    
+    /// Stop requests from other tasks, see [`with_shutdown_signal`](Self::with_shutdown_signal)
+    shutdown_signal: Option<&'static ShutdownSignal>,
 }
 
 impl<P, S, N, M, C> IoTContainer<P, S, N, M, C>
 where
     P: HardwarePlatform,
-    S: SensorReader,
-    N: NetworkManager,
-    M: MessagePublisher,
-    C: ConsoleInterface,
+    S: SensorReader + HealthCheck,
+    N: NetworkManager + HealthCheck,
+    M: MessagePublisher + HealthCheck + Send,
+    C: ConsoleInterface + HealthCheck,
 {
     /// Creates a new IoT container with the specified components
     /// 
@@ -184,7 +412,7 @@ where
     /// 
     /// # Example
     /// 
-    /// ```rust,no_run
+    /// ```rust,ignore
     /// let container = IoTContainer::new(
     ///     platform, sensor, network, publisher, console, config
     /// ).await?;
@@ -203,6 +431,9 @@ where
                 iot_common::ConfigError::ValidationError("Device ID too long".try_into().unwrap_or_default())
             ))?;
         
+        // The log macros of drivers and the application follow the configured level
+        iot_common::logging::init(config.effective_log_level());
+        
         // Initialize components
         Self::log(&config, LogLevel::Info, "Initializing IoT container components").await;
         
@@ -227,6 +458,9 @@ where
             Self::log(&config, LogLevel::Warning, "Message publisher connection failed").await;
         }
         
+        let publish_throttle = PublishThrottle::new(&config.mqtt);
+        let publish_smoother = ExponentialSmoother::from_config(&config.mqtt);
+        
         let container = Self {
             platform,
             sensor,
@@ -235,8 +469,14 @@ where
             console,
             config,
             measurement_buffer: Deque::new(),
+            pending_publish: MeasurementBuffer::new(),
+            publish_throttle,
+            publish_smoother,
             start_time: Instant::now(),
             device_id,
+            state: Mutex::new(SystemState::new()),
+            restart_requested: false,
+            shutdown_signal: None,
         };
         
         Self::log(&container.config, LogLevel::Info, "IoT container initialized successfully").await;
@@ -244,20 +484,45 @@ where
         Ok(container)
     }
     
+    /// Lets other tasks stop the main loop through `signal`
+    /// 
+    /// Without one the loop only stops on the console `restart` command.
+    /// Give each container its own signal.
+    pub fn with_shutdown_signal(mut self, signal: &'static ShutdownSignal) -> Self {
+        self.shutdown_signal = Some(signal);
+        self
+    }
+    
+    /// Consumes a pending stop request
+    fn take_shutdown_request(&self) -> bool {
+        self.shutdown_signal.is_some_and(|signal| signal.0.try_take().is_some())
+    }
+    
+    /// Waits for a stop request, consuming it; never resolves without a signal
+    async fn wait_shutdown_request(&self) {
+        match self.shutdown_signal {
+            Some(signal) => signal.0.wait().await,
+            None => core::future::pending::<()>().await,
+        }
+    }
+    
     /// Runs the complete IoT system
     /// 
     /// This method starts all system tasks and runs the main application loop.
     /// It orchestrates sensor readings, network communications, message publishing,
-    /// and console interactions.
+    /// and console interactions. The loop checks the shutdown signal every cycle
+    /// and returns once [`ShutdownSignal::request`] has been called or the
+    /// console received `restart`.
     /// 
     /// # Returns
     /// 
-    /// * `Ok(())` - System shut down gracefully
+    /// * `Ok(())` - Shutdown requested; call [`restart`](Self::restart) or
+    ///   [`shutdown`](Self::shutdown) next
     /// * `Err(IoTError)` - System encountered fatal error
     /// 
     /// # Example
     /// 
-    /// ```rust,no_run
+    /// ```rust,ignore
     /// container.run_system().await?;
     /// ```
     pub async fn run_system(&mut self) -> IoTResult<()> {
@@ -278,13 +543,13 @@ where
         
         // Mark console as active
         {
-            let mut state = SYSTEM_STATE.lock().await;
+            let mut state = self.state.lock().await;
             state.console_active = true;
         }
         
         // Test sensor availability
         if self.sensor.is_available().await {
-            let mut state = SYSTEM_STATE.lock().await;
+            let mut state = self.state.lock().await;
             state.sensor_active = true;
             Self::log(&self.config, LogLevel::Info, "Sensor subsystem active").await;
         } else {
@@ -293,7 +558,7 @@ where
         
         // Test network connectivity
         if self.network.is_connected().await {
-            let mut state = SYSTEM_STATE.lock().await;
+            let mut state = self.state.lock().await;
             state.network_connected = true;
             Self::log(&self.config, LogLevel::Info, "Network subsystem connected").await;
         } else {
@@ -302,7 +567,7 @@ where
         
         // Test publisher connectivity
         if self.publisher.is_connected().await {
-            let mut state = SYSTEM_STATE.lock().await;
+            let mut state = self.state.lock().await;
             state.publisher_connected = true;
             Self::log(&self.config, LogLevel::Info, "Publisher subsystem connected").await;
         } else {
@@ -318,15 +583,19 @@ where
         
         let mut cycle_count = 0u32;
         let mut last_status_report = Instant::now();
-        let operation_interval = Duration::from_secs(self.config.sensor_read_interval_secs);
         let status_report_interval = Duration::from_secs(self.config.status_report_interval_secs);
         
         loop {
+            // Cooperative exit point
+            if self.take_shutdown_request() {
+                break;
+            }
+            
             let cycle_start = Instant::now();
             
             // Update system uptime
             {
-                let mut state = SYSTEM_STATE.lock().await;
+                let mut state = self.state.lock().await;
                 state.uptime_seconds = self.start_time.elapsed().as_secs() as u32;
             }
             
@@ -347,6 +616,11 @@ where
                 Self::log(&self.config, LogLevel::Debug, "Console operations cycle completed").await;
             }
             
+            // The caller runs `restart` once the loop has returned
+            if core::mem::take(&mut self.restart_requested) {
+                break;
+            }
+            
             // Periodic status reporting
             if cycle_start.duration_since(last_status_report) >= status_report_interval {
                 if let Err(_e) = self.status_report_cycle().await {
@@ -357,30 +631,201 @@ where
             
             cycle_count += 1;
             
-            // Calculate time to next cycle
-            let cycle_duration = Instant::now().duration_since(cycle_start);
-            if cycle_duration < operation_interval {
-                Timer::after(operation_interval - cycle_duration).await;
-            }
-            
             // Periodic logging in debug mode
-            if cycle_count % 10 == 0 && self.config.log_level == LogLevel::Debug {
+            if cycle_count % 10 == 0 && self.config.effective_log_level() >= LogLevel::Debug {
                 Self::log(&self.config, LogLevel::Debug, "Main loop cycle completed").await;
             }
+            
+            // Interval and idle strategy follow the run mode, which the console may change
+            if self.wait_for_next_cycle(cycle_start).await {
+                break;
+            }
+        }
+        
+        Self::log(&self.config, LogLevel::Info, "Main operation loop stopped").await;
+        Ok(())
+    }
+    
+    /// Gets the main loop interval for the current run mode
+    pub fn cycle_interval(&self) -> Duration {
+        self.config.run_mode.cycle_interval(self.config.sensor_read_interval_secs)
+    }
+    
+    /// Gets the current run mode
+    pub fn run_mode(&self) -> RunMode {
+        self.config.run_mode
+    }
+    
+    /// Switches the run mode, taking effect from the next cycle wait
+    /// 
+    /// The global log level follows, so `diagnostic` turns on debug output.
+    pub fn set_run_mode(&mut self, mode: RunMode) {
+        self.config.run_mode = mode;
+        iot_common::logging::set_max_level(self.config.effective_log_level());
+    }
+    
+    /// Waits out the rest of the cycle started at `cycle_start`
+    /// 
+    /// `LowPower` light-sleeps through the remainder, falling back to a timer
+    /// if the platform refuses; the other modes await a timer that also wakes
+    /// on shutdown. Light sleep holds the executor, so a shutdown requested
+    /// meanwhile is only seen on wake.
+    /// 
+    /// # Returns
+    /// 
+    /// `true` if a shutdown was requested and the loop should stop
+    pub async fn wait_for_next_cycle(&mut self, cycle_start: Instant) -> bool {
+        let interval = self.cycle_interval();
+        let elapsed = Instant::now().duration_since(cycle_start);
+        if elapsed >= interval {
+            return false;
+        }
+        let remaining = interval - elapsed;
+        
+        if self.config.run_mode == RunMode::LowPower {
+            if self.platform.get_power().light_sleep(remaining).is_ok() {
+                return self.take_shutdown_request();
+            }
+            Self::log(&self.config, LogLevel::Warning, "Light sleep failed, waiting instead").await;
+        }
+        
+        matches!(select(Timer::after(remaining), self.wait_shutdown_request()).await, Either::Second(()))
+    }
+    
+    /// Shuts the system down cleanly before a restart
+    /// 
+    /// Performs, in order:
+    /// 1. Drops a stop request that is still pending
+    /// 2. Flushes the pending measurements through the `MessagePublisher`
+    /// 3. Publishes an `offline` device status
+    /// 4. Persists the system counters to `storage` under [`PERSISTED_STATE_CONFIG`]
+    /// 5. Disconnects from the network
+    /// 
+    /// Every step is attempted even if an earlier one fails; the first error is
+    /// returned so the caller can log it before resetting anyway.
+    /// 
+    /// # Example
+    /// 
+    /// ```rust,ignore
+    /// SHUTDOWN.request();                    // the container's `ShutdownSignal`
+    /// container.run_system().await?;        // returns after the current cycle
+    /// container.shutdown(&mut config_store).await?;
+    /// ```
+    /// 
+    /// Use [`restart`](Self::restart) to reset the device afterwards.
+    pub async fn shutdown<St>(&mut self, storage: &mut St) -> IoTResult<()>
+    where
+        St: ConfigStorage,
+    {
+        Self::log(&self.config, LogLevel::Info, "Shutting down IoT system").await;
+        let mut first_error: Option<IoTError> = None;
+        
+        // A request raised after the loop stopped would end the next run at once
+        self.take_shutdown_request();
+        
+        // Flush the measurements that have not been published yet
+        if let Err(e) = self.flush_pending_measurements().await {
+            Self::log(&self.config, LogLevel::Warning, "Failed to flush pending measurements").await;
+            first_error.get_or_insert(e);
+        }
+        
+        // Announce that the device is going away
+        let state = {
+            let mut state = self.state.lock().await;
+            state.uptime_seconds = self.start_time.elapsed().as_secs() as u32;
+            *state
+        };
+        
+        if self.publisher.is_connected().await {
+            let status = DeviceStatus::new(
+                &self.device_id,
+                "offline",
+                state.uptime_seconds,
+                state.free_heap_bytes,
+                self.network.get_signal_strength().await.unwrap_or(-99),
+            ).map(|mut status| {
+                status.sensor_readings_count = state.sensor_readings_count;
+                status.mqtt_messages_count = state.messages_published_count;
+                status.buffered_readings = state.buffered_readings;
+                status.dropped_readings = state.dropped_readings;
+                status.last_error_code = state.last_error_code;
+                status
+            });
+            
+            if let Err(e) = match status {
+                Ok(status) => self.publisher.publish_status(&status).await,
+                Err(e) => Err(e),
+            } {
+                Self::log(&self.config, LogLevel::Warning, "Failed to publish offline status").await;
+                first_error.get_or_insert(e);
+            }
+        }
+        
+        // Persist counters so they survive the restart
+        if storage.store_config(PERSISTED_STATE_CONFIG, &PersistedState::from(&state)).await.is_err() {
+            Self::log(&self.config, LogLevel::Warning, "Failed to persist system state").await;
+            first_error.get_or_insert(IoTError::system(SystemError::ResourceUnavailable(
+                "Failed to persist system state".try_into().unwrap_or_default()
+            )));
+        }
+        
+        if let Err(e) = self.network.disconnect().await {
+            first_error.get_or_insert(e);
         }
+        
+        {
+            let mut state = self.state.lock().await;
+            state.sensor_active = false;
+            state.network_connected = false;
+            state.publisher_connected = false;
+            state.console_active = false;
+        }
+        
+        Self::log(&self.config, LogLevel::Info, "IoT system shut down").await;
+        
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+    
+    /// Shuts the system down and resets the device
+    /// 
+    /// Runs [`shutdown`](Self::shutdown), then [`HardwarePlatform::reset`] even
+    /// if shutdown failed. On real hardware this never returns; mock platforms
+    /// record the reset and the shutdown result is returned.
+    /// 
+    /// # Example
+    /// 
+    /// ```rust,ignore
+    /// container.run_system().await?;        // returns after `restart` on the console
+    /// container.restart(&mut config_store).await?;
+    /// ```
+    pub async fn restart<St>(&mut self, storage: &mut St) -> IoTResult<()>
+    where
+        St: ConfigStorage,
+    {
+        let result = self.shutdown(storage).await;
+        if result.is_err() {
+            Self::log(&self.config, LogLevel::Warning, "Shutdown incomplete, restarting anyway").await;
+        }
+        
+        Self::log(&self.config, LogLevel::Info, "Restarting system").await;
+        self.platform.reset().await;
+        result
     }
     
     /// Performs a sensor reading cycle
     async fn sensor_reading_cycle(&mut self) -> IoTResult<()> {
         if !self.sensor.is_available().await {
             // Mark sensor as inactive
-            let mut state = SYSTEM_STATE.lock().await;
+            let mut state = self.state.lock().await;
             state.sensor_active = false;
             return Err(IoTError::sensor(iot_common::SensorError::NotResponding("Sensor not responding".try_into().unwrap_or_default())));
         }
         
-        // Read measurements
-        match self.sensor.read_measurements().await {
+        // Read measurements, retrying per the sensor policy
+        match self.read_measurements_with_retry().await {
             Ok(measurements) => {
                 // Validate measurements
                 if !measurements.is_valid() {
@@ -394,21 +839,25 @@ where
                 }
                 let _ = self.measurement_buffer.push_back(measurements.clone());
                 
-                // Signal new measurement available
-                SENSOR_DATA_SIGNAL.signal(measurements);
+                // Queue the smoothed reading for publishing, keeping the newest readings while offline
+                if self.pending_publish.push(self.publish_smoother.apply(&measurements)) {
+                    Self::log(&self.config, LogLevel::Warning, "Offline buffer full, dropped oldest reading").await;
+                }
                 
                 // Update system state
                 {
-                    let mut state = SYSTEM_STATE.lock().await;
+                    let mut state = self.state.lock().await;
                     state.sensor_active = true;
                     state.sensor_readings_count += 1;
+                    state.buffered_readings = self.pending_publish.len() as u32;
+                    state.dropped_readings = self.pending_publish.dropped();
                 }
                 
                 Self::log(&self.config, LogLevel::Debug, "Sensor reading completed").await;
                 Ok(())
             }
             Err(e) => {
-                let mut state = SYSTEM_STATE.lock().await;
+                let mut state = self.state.lock().await;
                 state.sensor_active = false;
                 Err(e)
             }
@@ -421,8 +870,8 @@ where
         if !self.network.is_connected().await {
             Self::log(&self.config, LogLevel::Warning, "Network disconnected, attempting reconnection").await;
             
-            if let Err(e) = self.network.connect().await {
-                let mut state = SYSTEM_STATE.lock().await;
+            if let Err(e) = self.connect_network_with_retry().await {
+                let mut state = self.state.lock().await;
                 state.network_connected = false;
                 return Err(e);
             }
@@ -430,29 +879,91 @@ where
         
         // Update network status
         {
-            let mut state = SYSTEM_STATE.lock().await;
+            let mut state = self.state.lock().await;
             state.network_connected = self.network.is_connected().await;
         }
         
-        // Attempt to publish pending sensor data
-        if let Some(measurements) = SENSOR_DATA_SIGNAL.try_take() {
-            if let Err(e) = self.publish_sensor_data(measurements.clone()).await {
-                Self::log(&self.config, LogLevel::Warning, "Failed to publish sensor data").await;
-                // Put the measurement back for retry
-                SENSOR_DATA_SIGNAL.signal(measurements);
-                return Err(e);
-            }
+        // With a weak signal, let readings accumulate and publish them as one batch
+        let rssi = self.network.get_signal_strength().await;
+        if let Some(transition) = self.publish_throttle.update(rssi) {
+            self.log_throttle_transition(transition, rssi.unwrap_or_default()).await;
+        }
+        
+        let now = Instant::now();
+        if !self.publish_throttle.should_publish(now, self.pending_publish.is_full()) {
+            return Ok(());
+        }
+        
+        // Attempt to publish pending sensor data; it stays queued on failure
+        let had_pending = !self.pending_publish.is_empty();
+        if let Err(e) = self.flush_pending_measurements().await {
+            Self::log(&self.config, LogLevel::Warning, "Failed to publish sensor data").await;
+            return Err(e);
+        }
+        {
+            let mut state = self.state.lock().await;
+            state.buffered_readings = self.pending_publish.len() as u32;
+        }
+        
+        // Nothing left to send until the next sensor read, a good moment for storage GC
+        if had_pending && self.pending_publish.is_empty() {
+            self.publish_throttle.record_publish(now);
+            STORAGE_IDLE_SIGNAL.signal(());
         }
         
         Ok(())
     }
     
+    /// Logs a change of publish throttling so operators can follow it
+    async fn log_throttle_transition(&self, transition: ThrottleTransition, rssi_dbm: i8) {
+        let mut message: heapless::String<96> = heapless::String::new();
+        match transition {
+            ThrottleTransition::Throttled => {
+                let _ = write!(
+                    message,
+                    "Weak WiFi signal ({} dBm), publishing every {}s",
+                    rssi_dbm, self.publish_throttle.throttled_interval().as_secs()
+                );
+                Self::log(&self.config, LogLevel::Warning, &message).await;
+            }
+            ThrottleTransition::Restored => {
+                let _ = write!(message, "WiFi signal recovered ({} dBm), publishing every cycle", rssi_dbm);
+                Self::log(&self.config, LogLevel::Info, &message).await;
+            }
+        }
+    }
+    
     /// Performs console operations cycle
     async fn console_operations_cycle(&mut self) -> IoTResult<()> {
-        // Check if console command is available
-        if let Ok(Some(command)) = self.console.read_command().await {
+        // Check if console command is available; a failed read means the console is down
+        let read = self.console.read_command().await;
+        self.state.lock().await.console_active = read.is_ok();
+        
+        if let Ok(Some(command)) = read {
             Self::log(&self.config, LogLevel::Debug, "Processing console command").await;
             
+            // Status is answered from component health rather than the console itself
+            if command.trim() == "status" {
+                return self.write_health_report().await;
+            }
+            
+            // Storage maintenance is tracked by the container, not the console
+            if command.trim() == "storage" {
+                return self.write_storage_report().await;
+            }
+            
+            // Restarting needs the caller's storage to persist counters, see `restart`
+            if matches!(command.trim(), "restart" | "reset") {
+                self.restart_requested = true;
+                return self.console.write_line("Restarting system...").await;
+            }
+            
+            // Mode changes the container's own configuration
+            let mut words = command.split_whitespace();
+            if words.next() == Some("mode") {
+                return self.handle_mode_command(words.next()).await;
+            }
+            
             // Process the command
             match self.console.handle_command(&command).await {
                 Ok(response) => {
@@ -472,9 +983,69 @@ where
         Ok(())
     }
     
+    /// Writes the latest storage maintenance run to the console
+    async fn write_storage_report(&mut self) -> IoTResult<()> {
+        let mut line: heapless::String<96> = heapless::String::new();
+        match last_storage_maintenance().await {
+            Some(report) => {
+                let age_secs = Instant::now().as_millis().saturating_sub(report.timestamp_ms) / 1000;
+                let _ = write!(
+                    line,
+                    "Storage maintenance: {} {}s ago (took {}ms)",
+                    report.outcome.as_str(), age_secs, report.duration_ms
+                );
+            }
+            None => {
+                let _ = line.push_str("Storage maintenance: never run");
+            }
+        }
+        self.console.write_line(&line).await
+    }
+    
+    /// Writes the aggregated component health to the console
+    async fn write_health_report(&mut self) -> IoTResult<()> {
+        let health = self.system_health();
+        
+        let mut line: heapless::String<128> = heapless::String::new();
+        let _ = write!(line, "System health: {} ({})", health.status(), health.overall().as_str());
+        self.console.write_line(&line).await?;
+        
+        for (name, component) in health.components() {
+            line.clear();
+            let _ = write!(line, "  {:<10} {:<9} {}", name, component.state.as_str(), component.message);
+            self.console.write_line(&line).await?;
+        }
+        
+        line.clear();
+        let _ = write!(line, "  {:<10} {}/{} buffered, {} dropped", "backlog",
+                       self.pending_publish.len(), self.pending_publish.capacity(), self.pending_publish.dropped());
+        self.console.write_line(&line).await?;
+        
+        Ok(())
+    }
+    
+    /// Shows or switches the run mode for the console `mode [name]` command
+    async fn handle_mode_command(&mut self, name: Option<&str>) -> IoTResult<()> {
+        let mut line: heapless::String<64> = heapless::String::new();
+        match name.map(RunMode::from_name) {
+            None => {
+                let _ = write!(line, "Run mode: {}", self.config.run_mode.as_str());
+            }
+            Some(Some(mode)) => {
+                self.set_run_mode(mode);
+                Self::log(&self.config, LogLevel::Info, "Run mode changed").await;
+                let _ = write!(line, "Run mode set to {} ({}s interval)", mode.as_str(), self.cycle_interval().as_secs());
+            }
+            Some(None) => {
+                let _ = line.push_str("Usage: mode normal|lowpower|diagnostic");
+            }
+        }
+        self.console.write_line(&line).await
+    }
+    
     /// Performs status reporting cycle
     async fn status_report_cycle(&mut self) -> IoTResult<()> {
-        let state = SYSTEM_STATE.lock().await;
+        let state = self.state.lock().await;
         
         // Create device status
         let status = match DeviceStatus::new(
@@ -487,6 +1058,8 @@ where
             Ok(mut status) => {
                 status.sensor_readings_count = state.sensor_readings_count;
                 status.mqtt_messages_count = state.messages_published_count;
+                status.buffered_readings = state.buffered_readings;
+                status.dropped_readings = state.dropped_readings;
                 status.last_error_code = state.last_error_code;
                 status
             }
@@ -515,71 +1088,192 @@ where
         // Ensure publisher is connected
         if !self.publisher.is_connected().await {
             if let Err(e) = self.publisher.connect().await {
-                let mut state = SYSTEM_STATE.lock().await;
+                let mut state = self.state.lock().await;
                 state.publisher_connected = false;
                 return Err(e);
             }
         }
         
-        // Publish the data
-        match self.publisher.publish_sensor_data(&sensor_data).await {
+        // Publish the data, retrying per the publish policy
+        match self.publish_with_retry(&sensor_data).await {
             Ok(()) => {
-                let mut state = SYSTEM_STATE.lock().await;
+                let mut state = self.state.lock().await;
                 state.publisher_connected = true;
                 state.messages_published_count += 1;
                 Self::log(&self.config, LogLevel::Debug, "Sensor data published successfully").await;
                 Ok(())
             }
             Err(e) => {
-                let mut state = SYSTEM_STATE.lock().await;
+                let mut state = self.state.lock().await;
                 state.publisher_connected = false;
                 Err(e)
             }
         }
     }
     
+    /// Publishes all queued measurements
+    /// 
+    /// A single reading goes through the normal retrying publish path; readings
+    /// accumulated while offline are sent as one batch with their original
+    /// timestamps. The queue is only cleared once publishing succeeds.
+    async fn flush_pending_measurements(&mut self) -> IoTResult<()> {
+        match self.pending_publish.len() {
+            0 => return Ok(()),
+            1 => {
+                if let Some(measurements) = self.pending_publish.front().cloned() {
+                    self.publish_sensor_data(measurements).await?;
+                }
+                self.pending_publish.clear();
+                return Ok(());
+            }
+            _ => {}
+        }
+        
+        let mut batch: heapless::Vec<SensorData, MAX_MEASUREMENT_BUFFER> = heapless::Vec::new();
+        for measurements in self.pending_publish.iter() {
+            let _ = batch.push(SensorData::new(&self.device_id, measurements.clone())?);
+        }
+        
+        // Ensure publisher is connected
+        if !self.publisher.is_connected().await {
+            if let Err(e) = self.publisher.connect().await {
+                let mut state = self.state.lock().await;
+                state.publisher_connected = false;
+                return Err(e);
+            }
+        }
+        
+        match self.publisher.publish_sensor_batch(&batch).await {
+            Ok(()) => {
+                self.pending_publish.clear();
+                let mut state = self.state.lock().await;
+                state.publisher_connected = true;
+                state.messages_published_count += batch.len() as u32;
+                Self::log(&self.config, LogLevel::Info, "Flushed buffered sensor data").await;
+                Ok(())
+            }
+            Err(e) => {
+                let mut state = self.state.lock().await;
+                state.publisher_connected = false;
+                Err(e)
+            }
+        }
+    }
+    
+    /// Reads the sensor, retrying failed reads per `config.retry.sensor`
+    async fn read_measurements_with_retry(&mut self) -> IoTResult<Measurements> {
+        let policy = self.config.retry.sensor;
+        retry(&policy, async |attempt| {
+            if attempt > 1 {
+                Self::log(&self.config, LogLevel::Warning, "Sensor read failed, retrying").await;
+            }
+            self.sensor.read_measurements().await
+        }).await
+    }
+    
+    /// Reconnects the network, retrying per `config.retry.network`
+    async fn connect_network_with_retry(&mut self) -> IoTResult<()> {
+        let policy = self.config.retry.network;
+        retry(&policy, async |attempt| {
+            if attempt > 1 {
+                Self::log(&self.config, LogLevel::Warning, "Network connection failed, retrying").await;
+            }
+            self.network.connect().await
+        }).await
+    }
+    
+    /// Publishes sensor data, retrying per `config.retry.publish`
+    async fn publish_with_retry(&mut self, data: &SensorData) -> IoTResult<()> {
+        let policy = self.config.retry.publish;
+        retry(&policy, async |attempt| {
+            if attempt > 1 {
+                Self::log(&self.config, LogLevel::Warning, "Publish failed, retrying").await;
+            }
+            self.publisher.publish_sensor_data(data).await
+        }).await
+    }
+    
     /// Handles system errors
     async fn handle_error(&self, error: IoTError) {
         // Update error state
         {
-            let mut state = SYSTEM_STATE.lock().await;
+            let mut state = self.state.lock().await;
             state.last_error_code = error.error_code() as u32;
         }
         
         // Log error based on configuration
-        if self.config.log_level as u8 >= LogLevel::Error as u8 {
+        if self.config.effective_log_level() as u8 >= LogLevel::Error as u8 {
             Self::log(&self.config, LogLevel::Error, "System error occurred").await;
         }
     }
     
     /// Logs a message if logging level permits
     async fn log(config: &SystemConfiguration, level: LogLevel, message: &str) {
-        if config.log_level as u8 >= level as u8 {
+        if config.effective_log_level() as u8 >= level as u8 {
             // Use RTT for embedded logging
             #[cfg(feature = "esp32c3")]
             rtt_target::rprintln!("[{}] {}", level.as_str(), message);
             
             // Use println for testing
             #[cfg(feature = "mock")]
-            println!("[{}] {}", level.as_str(), message);
+            std::println!("[{}] {}", level.as_str(), message);
         }
     }
     
     /// Runs a single operation cycle (useful for testing)
+    /// 
+    /// Errors are recorded in the system state like in the main loop.
     pub async fn run_single_cycle(&mut self) -> IoTResult<()> {
-        let _ = self.sensor_reading_cycle().await;
-        let _ = self.network_operations_cycle().await;
+        if let Err(e) = self.sensor_reading_cycle().await {
+            self.handle_error(e).await;
+        }
+        if let Err(e) = self.network_operations_cycle().await {
+            self.handle_error(e).await;
+        }
         let _ = self.console_operations_cycle().await;
         Ok(())
     }
     
+    /// Aggregates the health of all components
+    /// 
+    /// Unlike the flags in [`SystemState`], which are only updated by the
+    /// operation cycles, this asks each component for its current health.
+    pub fn system_health(&self) -> SystemHealth {
+        SystemHealth {
+            sensor: self.sensor.health(),
+            network: self.network.health(),
+            publisher: self.publisher.health(),
+            console: self.console.health(),
+        }
+    }
+    
     /// Gets current system state (useful for testing and monitoring)
     pub async fn get_system_state(&self) -> SystemState {
-        *SYSTEM_STATE.lock().await
+        *self.state.lock().await
     }
     
     /// Gets buffered measurements (useful for testing and data analysis)
     pub fn get_measurement_buffer(&self) -> &Deque<Measurements, MAX_MEASUREMENT_BUFFER> {
         &self.measurement_buffer
     }
+    
+    /// Gets measurements waiting to be published (non-empty while offline)
+    pub fn get_pending_measurements(&self) -> &MeasurementBuffer<Measurements> {
+        &self.pending_publish
+    }
+    
+    /// Gets the hardware platform (useful for testing)
+    pub fn get_platform(&self) -> &P {
+        &self.platform
+    }
+    
+    /// Gets the network manager (useful for testing)
+    pub fn get_network(&self) -> &N {
+        &self.network
+    }
+    
+    /// Gets the message publisher (useful for testing)
+    pub fn get_publisher(&self) -> &M {
+        &self.publisher
+    }
 }
\ No newline at end of file
@@ -0,0 +1,166 @@
+//! # System Event Bus
+//!
+//! Typed publish/subscribe channel for decoupled inter-task communication.
+//! Producers publish [`SystemEvent`]s without knowing who consumes them, and new
+//! consumers (an SD-card logger, a display task, ...) subscribe without touching
+//! the producers. Queue depth and subscriber count are const generics, so the
+//! bus lives in a `static` and never allocates.
+//!
+//! ```rust,no_run
+//! use iot_container::IoTResult;
+//! use iot_container::events::{EventBus, SystemEvent};
+//!
+//! static EVENT_BUS: EventBus<8, 4> = EventBus::new();
+//!
+//! async fn wifi_watcher() -> IoTResult<()> {
+//!     let mut events = EVENT_BUS.subscribe()?;
+//!     loop {
+//!         if let SystemEvent::WiFiStateChanged { connected } = events.next().await {
+//!             // React to connectivity changes
+//!         }
+//!     }
+//! }
+//!
+//! // Any task can publish without knowing the subscribers
+//! EVENT_BUS.publish(SystemEvent::WiFiStateChanged { connected: true });
+//! ```
+
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::pubsub::{PubSubChannel, Subscriber, WaitResult};
+
+use iot_common::{IoTError, SystemError};
+
+use crate::traits::Measurements;
+
+/// Default number of events buffered per bus
+pub const DEFAULT_EVENT_QUEUE_DEPTH: usize = 8;
+
+/// Default number of concurrent subscribers per bus
+pub const DEFAULT_MAX_SUBSCRIBERS: usize = 4;
+
+/// Events exchanged between system tasks
+#[derive(Debug, Clone, PartialEq)]
+pub enum SystemEvent {
+    /// A new sensor measurement is available
+    SensorReading {
+        /// Calibrated measurements
+        measurements: Measurements,
+        /// Sequence number of the reading since boot
+        sequence: u32,
+    },
+
+    /// The sensor could not be read; consumers may publish a placeholder
+    SensorOffline {
+        /// Sequence number of the last successful reading (0 if none yet)
+        sequence: u32,
+    },
+
+    /// WiFi connectivity changed
+    WiFiStateChanged {
+        /// Whether the station is now connected
+        connected: bool,
+    },
+
+    /// A sensor reading was published to the MQTT broker
+    MqttPublished {
+        /// Sequence number of the published reading
+        sequence: u32,
+    },
+
+    /// The performance monitor raised alerts
+    PerformanceAlert {
+        /// Number of active alerts in the latest report
+        alert_count: u8,
+    },
+}
+
+/// Typed publish/subscribe bus for [`SystemEvent`]s
+///
+/// `QUEUE` is the number of events buffered; when it is full the oldest event
+/// is dropped and slow subscribers see it as missed. `SUBS` bounds the number
+/// of subscribers alive at the same time.
+pub struct EventBus<const QUEUE: usize = DEFAULT_EVENT_QUEUE_DEPTH, const SUBS: usize = DEFAULT_MAX_SUBSCRIBERS> {
+    channel: PubSubChannel<CriticalSectionRawMutex, SystemEvent, QUEUE, SUBS, 0>,
+}
+
+impl<const QUEUE: usize, const SUBS: usize> EventBus<QUEUE, SUBS> {
+    /// Creates an empty event bus, usable in a `static`
+    pub const fn new() -> Self {
+        Self {
+            channel: PubSubChannel::new(),
+        }
+    }
+
+    /// Publishes an event to all current subscribers
+    ///
+    /// Never waits: if the queue is full the oldest event is dropped.
+    pub fn publish(&self, event: SystemEvent) {
+        self.channel.immediate_publisher().publish_immediate(event);
+    }
+
+    /// Subscribes to all events published from now on
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(EventSubscriber)` - Subscription created
+    /// * `Err(IoTError)` - All `SUBS` subscriber slots are in use
+    pub fn subscribe(&self) -> Result<EventSubscriber<'_, QUEUE, SUBS>, IoTError> {
+        self.channel
+            .subscriber()
+            .map(|inner| EventSubscriber { inner, missed: 0 })
+            .map_err(|_| IoTError::system(SystemError::ResourceUnavailable(
+                "Event bus subscriber limit reached".try_into().unwrap_or_default()
+            )))
+    }
+
+    /// Number of events currently queued
+    pub fn len(&self) -> usize {
+        self.channel.len()
+    }
+
+    /// Checks if no events are queued
+    pub fn is_empty(&self) -> bool {
+        self.channel.is_empty()
+    }
+}
+
+impl<const QUEUE: usize, const SUBS: usize> Default for EventBus<QUEUE, SUBS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Subscription handle returned by [`EventBus::subscribe`]
+///
+/// Dropping the handle frees its subscriber slot.
+pub struct EventSubscriber<'a, const QUEUE: usize = DEFAULT_EVENT_QUEUE_DEPTH, const SUBS: usize = DEFAULT_MAX_SUBSCRIBERS> {
+    inner: Subscriber<'a, CriticalSectionRawMutex, SystemEvent, QUEUE, SUBS, 0>,
+    missed: u64,
+}
+
+impl<'a, const QUEUE: usize, const SUBS: usize> EventSubscriber<'a, QUEUE, SUBS> {
+    /// Waits for the next event
+    pub async fn next(&mut self) -> SystemEvent {
+        loop {
+            match self.inner.next_message().await {
+                WaitResult::Message(event) => return event,
+                WaitResult::Lagged(count) => self.missed += count,
+            }
+        }
+    }
+
+    /// Returns the next event if one is already queued
+    pub fn try_next(&mut self) -> Option<SystemEvent> {
+        loop {
+            match self.inner.try_next_message()? {
+                WaitResult::Message(event) => return Some(event),
+                WaitResult::Lagged(count) => self.missed += count,
+            }
+        }
+    }
+
+    /// Number of events this subscriber missed because the queue overflowed
+    pub fn missed(&self) -> u64 {
+        self.missed
+    }
+}
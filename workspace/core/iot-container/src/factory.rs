@@ -5,11 +5,24 @@
 //! testing with mock implementations.
 
 use alloc::boxed::Box;
-use iot_common::{IoTError, IoTResult};
-use iot_hal::HardwarePlatform;
+use core::fmt::Write;
+use iot_common::{IoTError, IoTResult, ConfigError, error::ErrorMessage};
+use iot_hal::{HardwarePlatform, I2cInterface};
 
 use crate::traits::{SensorReader, NetworkManager, MessagePublisher, ConsoleInterface};
-use crate::config::{SensorConfig, WiFiConfig, MqttConfig, ConsoleConfig};
+use crate::config::{SensorConfig, SensorType, SENSOR_CHIP_ID_REG, WiFiConfig, MqttConfig, ConsoleConfig};
+
+/// Constructor for a hardware sensor driver, supplied by the application
+/// 
+/// Called by [`ComponentFactory::create_sensor_with`] once the chip on the bus
+/// has been verified against the configuration.
+pub type SensorDriver = fn(&SensorConfig) -> IoTResult<Box<dyn SensorReader + Send + Sync>>;
+
+/// Constructor for a hardware console, supplied by the application
+/// 
+/// Called by [`ComponentFactory::create_console_with`] for the `"USB"` and
+/// `"UART"` interface types, e.g. `serial_console_embassy::usb_console_driver`.
+pub type ConsoleDriver = fn(&ConsoleConfig) -> IoTResult<Box<dyn ConsoleInterface + Send + Sync>>;
 
 #[cfg(feature = "mock")]
 use crate::mocks::{MockSensorReader, MockNetworkManager, MockMessagePublisher, MockConsoleInterface};
@@ -24,9 +37,9 @@ pub struct ComponentFactory;
 impl ComponentFactory {
     /// Creates a sensor reader based on configuration
     /// 
-    /// This method examines the sensor configuration and creates the appropriate
-    /// sensor implementation. Currently supports BME280 sensors with plans for
-    /// additional sensor types.
+    /// Equivalent to [`create_sensor_with`](Self::create_sensor_with) without a
+    /// hardware driver, so only the mock sensor can be created; the chip on the
+    /// bus is still checked against the configured type.
     /// 
     /// # Arguments
     /// 
@@ -38,14 +51,9 @@ impl ComponentFactory {
     /// * `Ok(Box<dyn SensorReader>)` - Sensor reader implementation
     /// * `Err(IoTError)` - Sensor creation failed
     /// 
-    /// # Supported Sensor Types
-    /// 
-    /// - `"BME280"` - Bosch BME280 environmental sensor
-    /// - `"MOCK"` - Mock sensor for testing (requires `mock` feature)
-    /// 
     /// # Example
     /// 
-    /// ```rust,no_run
+    /// ```rust,ignore
     /// use iot_container::{ComponentFactory, SensorConfig};
     /// use iot_hal::Esp32C3Platform;
     /// 
@@ -54,45 +62,107 @@ impl ComponentFactory {
     /// let sensor = ComponentFactory::create_sensor(&mut platform, &sensor_config).await?;
     /// ```
     pub async fn create_sensor<P: HardwarePlatform>(
-        _platform: &mut P,
+        platform: &mut P,
         config: &SensorConfig,
     ) -> IoTResult<Box<dyn SensorReader + Send + Sync>> {
-        match config.sensor_type.as_str() {
-            "BME280" => {
-                // NOTE: Direct BME280 creation disabled due to circular dependency
-                // Concrete sensors should be created externally and injected
-                // #[cfg(feature = "esp32c3")]
-                // {
-                //     use bme280_embassy::BME280;
-                //     
-                //     let i2c = platform.get_i2c();
-                //     let mut sensor = BME280::new(i2c);
-                //     
-                //     // Configure sensor based on config parameters
-                //     // Note: BME280 driver may need updates to accept configuration parameters
-                //     sensor.initialize().await?;
-                //     
-                //     Ok(Box::new(SensorAdapter::new(sensor)))
-                // }
-                // 
-                // #[cfg(not(feature = "esp32c3"))]
-                Err(IoTError::configuration(
-                    iot_common::ConfigError::ValidationError("BME280 sensor must be injected externally".try_into().unwrap_or_default())
-                ))
+        Self::create_sensor_with(platform, config, None).await
+    }
+    
+    /// Creates a sensor reader for `config.sensor_type`
+    /// 
+    /// Hardware sensors are verified before the driver is built: the chip ID
+    /// at `config.i2c_address` must match the configured type, so a BMP280
+    /// configured as a BME280 (or the other way round) fails here with a
+    /// descriptive error instead of publishing a humidity of zero later.
+    /// 
+    /// The concrete drivers cannot be linked from this crate (see the
+    /// dependency note in `Cargo.toml`), so the application passes their
+    /// constructor as `driver`. The BME280 driver handles both chips and
+    /// reads the model from the configuration it is given.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `platform` - Hardware platform providing I2C interface
+    /// * `config` - Sensor configuration specifying type and parameters
+    /// * `driver` - Constructor for hardware sensors; unused for [`SensorType::Mock`]
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Box<dyn SensorReader>)` - Sensor reader implementation
+    /// * `Err(IoTError)` - Chip ID mismatch, bus error, or no driver for the type
+    /// 
+    /// # Supported Sensor Types
+    /// 
+    /// - [`SensorType::Bme280`] - Bosch BME280 environmental sensor (default)
+    /// - [`SensorType::Bmp280`] - Bosch BMP280 pressure sensor
+    /// - [`SensorType::Mock`] - Mock sensor for testing (requires `mock` feature)
+    /// 
+    /// # Example
+    /// 
+    /// ```rust,ignore
+    /// fn bme280_driver(config: &SensorConfig) -> IoTResult<Box<dyn SensorReader + Send + Sync>> {
+    ///     Ok(Box::new(Bme280Reader::new(config.sensor_type, config.i2c_address)))
+    /// }
+    /// 
+    /// let sensor = ComponentFactory::create_sensor_with(&mut platform, &config, Some(bme280_driver)).await?;
+    /// ```
+    pub async fn create_sensor_with<P: HardwarePlatform>(
+        platform: &mut P,
+        config: &SensorConfig,
+        driver: Option<SensorDriver>,
+    ) -> IoTResult<Box<dyn SensorReader + Send + Sync>> {
+        match config.sensor_type {
+            SensorType::Bme280 | SensorType::Bmp280 => {
+                Self::verify_sensor_chip(platform.get_i2c(), config).await?;
+                match driver {
+                    Some(driver) => driver(config),
+                    None => Err(IoTError::configuration(
+                        ConfigError::ValidationError("Sensor driver must be injected externally".try_into().unwrap_or_default())
+                    )),
+                }
             }
             
             #[cfg(feature = "mock")]
-            "MOCK" => {
+            SensorType::Mock => {
                 let sensor = MockSensorReader::new_with_config(config);
                 Ok(Box::new(sensor))
             }
             
-            _ => Err(IoTError::configuration(
-                iot_common::ConfigError::InvalidParameter("Unsupported sensor type".try_into().unwrap_or_default())
+            #[cfg(not(feature = "mock"))]
+            SensorType::Mock => Err(IoTError::configuration(
+                ConfigError::InvalidParameter("Mock sensor requires the mock feature".try_into().unwrap_or_default())
             )),
         }
     }
     
+    /// Checks that the chip at `config.i2c_address` is the configured sensor
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(())` - Chip ID matches `config.sensor_type`
+    /// * `Err(IoTError)` - Bus error, or a validation error naming both the
+    ///   configured and the detected chip
+    pub async fn verify_sensor_chip<I: I2cInterface>(i2c: &mut I, config: &SensorConfig) -> IoTResult<()> {
+        let Some(expected) = config.sensor_type.chip_id() else {
+            return Ok(());
+        };
+        
+        let mut chip_id = [0u8; 1];
+        i2c.write_read(config.i2c_address, &[SENSOR_CHIP_ID_REG], &mut chip_id).await?;
+        if chip_id[0] == expected {
+            return Ok(());
+        }
+        
+        let mut message = ErrorMessage::new();
+        let _ = match SensorType::from_chip_id(chip_id[0]) {
+            Some(detected) => write!(message, "{} configured at 0x{:02X} but chip ID 0x{:02X} is {}",
+                config.sensor_type, config.i2c_address, chip_id[0], detected),
+            None => write!(message, "{} configured at 0x{:02X} but chip ID 0x{:02X} is unknown",
+                config.sensor_type, config.i2c_address, chip_id[0]),
+        };
+        Err(IoTError::configuration(ConfigError::ValidationError(message)))
+    }
+    
     /// Creates a network manager based on configuration
     /// 
     /// This method creates the appropriate network manager implementation based on
@@ -116,7 +186,7 @@ impl ComponentFactory {
     /// 
     /// # Example
     /// 
-    /// ```rust,no_run
+    /// ```rust,ignore
     /// let mut platform = Esp32C3Platform::initialize().await?;
     /// let wifi_config = WiFiConfig::default();
     /// let network = ComponentFactory::create_network_manager(&mut platform, &wifi_config).await?;
@@ -156,7 +226,7 @@ impl ComponentFactory {
         
         #[cfg(feature = "mock")]
         {
-            let network = MockNetworkManager::new_with_config(config);
+            let network = MockNetworkManager::new_with_config(_config);
             Ok(Box::new(network))
         }
         
@@ -172,8 +242,13 @@ impl ComponentFactory {
     /// the MQTT configuration. Currently supports MQTT over TCP with plans for
     /// additional messaging protocols.
     /// 
+    /// The client ID is derived from the platform's MAC address (see
+    /// [`MqttConfig::with_device_id`]) unless `config.fixed_client_id` is set,
+    /// so every board gets its own broker session.
+    /// 
     /// # Arguments
     /// 
+    /// * `platform` - Hardware platform providing the device MAC
     /// * `network` - Network manager providing connectivity
     /// * `config` - MQTT configuration specifying broker and parameters
     /// 
@@ -189,14 +264,18 @@ impl ComponentFactory {
     /// 
     /// # Example
     /// 
-    /// ```rust,no_run
+    /// ```rust,ignore
     /// let mqtt_config = MqttConfig::default();
-    /// let publisher = ComponentFactory::create_message_publisher(&network, &mqtt_config).await?;
+    /// let publisher = ComponentFactory::create_message_publisher(&platform, &network, &mqtt_config).await?;
     /// ```
-    pub async fn create_message_publisher(
+    pub async fn create_message_publisher<P: HardwarePlatform>(
+        platform: &P,
         _network: &dyn NetworkManager,
-        _config: &MqttConfig,
+        config: &MqttConfig,
     ) -> IoTResult<Box<dyn MessagePublisher + Send + Sync>> {
+        #[cfg_attr(not(feature = "mock"), allow(unused_variables))]
+        let config = config.clone().with_device_id(platform.device_info().mac)?;
+        
         // NOTE: Direct MQTT creation disabled due to circular dependency  
         // Concrete message publishers should be created externally and injected
         // #[cfg(feature = "esp32c3")]
@@ -227,7 +306,7 @@ impl ComponentFactory {
         
         #[cfg(feature = "mock")]
         {
-            let publisher = MockMessagePublisher::new_with_config(config);
+            let publisher = MockMessagePublisher::new_with_config(&config);
             Ok(Box::new(publisher))
         }
         
@@ -239,9 +318,8 @@ impl ComponentFactory {
     
     /// Creates a console interface based on configuration
     /// 
-    /// This method creates the appropriate console interface implementation based on
-    /// the console configuration. Currently supports USB Serial/JTAG with plans for
-    /// additional console interfaces.
+    /// Equivalent to [`create_console_with`](Self::create_console_with)
+    /// without a hardware driver, so only the mock console can be created.
     /// 
     /// # Arguments
     /// 
@@ -253,6 +331,37 @@ impl ComponentFactory {
     /// * `Ok(Box<dyn ConsoleInterface>)` - Console interface implementation
     /// * `Err(IoTError)` - Console interface creation failed
     /// 
+    /// # Example
+    /// 
+    /// ```rust,ignore
+    /// let mut platform = Esp32C3Platform::initialize().await?;
+    /// let console_config = ConsoleConfig::default();
+    /// let console = ComponentFactory::create_console(&mut platform, &console_config).await?;
+    /// ```
+    pub async fn create_console<P: HardwarePlatform>(
+        platform: &mut P,
+        config: &ConsoleConfig,
+    ) -> IoTResult<Box<dyn ConsoleInterface + Send + Sync>> {
+        Self::create_console_with(platform, config, None).await
+    }
+    
+    /// Creates a console interface for `config.interface_type`
+    /// 
+    /// The concrete consoles live in `serial-console-embassy`, which depends on
+    /// this crate, so the application passes their constructor as `driver`
+    /// (as with [`create_sensor_with`](Self::create_sensor_with)).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `platform` - Hardware platform providing console interface
+    /// * `config` - Console configuration specifying interface and parameters
+    /// * `driver` - Constructor for hardware consoles; unused for `"MOCK"`
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Box<dyn ConsoleInterface>)` - Console interface implementation
+    /// * `Err(IoTError)` - No driver for the type, or the driver failed
+    /// 
     /// # Supported Console Types
     /// 
     /// - `"USB"` - USB Serial/JTAG interface (ESP32-C3)
@@ -261,31 +370,24 @@ impl ComponentFactory {
     /// 
     /// # Example
     /// 
-    /// ```rust,no_run
-    /// let mut platform = Esp32C3Platform::initialize().await?;
-    /// let console_config = ConsoleConfig::default();
-    /// let console = ComponentFactory::create_console(&mut platform, &console_config).await?;
+    /// ```rust,ignore
+    /// use serial_console_embassy::{provide_usb_port, usb_console_driver};
+    /// 
+    /// provide_usb_port(UsbSerialJtag::new(peripherals.USB_DEVICE).into_async());
+    /// let console = ComponentFactory::create_console_with(&mut platform, &config.console, Some(usb_console_driver)).await?;
     /// ```
-    pub async fn create_console<P: HardwarePlatform>(
+    pub async fn create_console_with<P: HardwarePlatform>(
         _platform: &mut P,
         config: &ConsoleConfig,
+        driver: Option<ConsoleDriver>,
     ) -> IoTResult<Box<dyn ConsoleInterface + Send + Sync>> {
         match config.interface_type.as_str() {
-            "USB" => {
-                // NOTE: Console adapter disabled due to circular dependency
-                // Console interfaces should be created externally and injected
-                Err(IoTError::configuration(
-                    iot_common::ConfigError::ValidationError("Console interface must be injected externally".try_into().unwrap_or_default())
-                ))
-            }
-            
-            "UART" => {
-                // NOTE: Console adapter disabled due to circular dependency  
-                // Console interfaces should be created externally and injected
-                Err(IoTError::configuration(
-                    iot_common::ConfigError::ValidationError("Console interface must be injected externally".try_into().unwrap_or_default())
-                ))
-            }
+            "USB" | "UART" => match driver {
+                Some(driver) => driver(config),
+                None => Err(IoTError::configuration(
+                    ConfigError::ValidationError("Console driver must be injected externally".try_into().unwrap_or_default())
+                )),
+            },
             
             #[cfg(feature = "mock")]
             "MOCK" => {
@@ -27,7 +27,7 @@
 //!
 //! ## Quick Start
 //!
-//! ```rust,no_run
+//! ```rust,ignore
 //! use iot_container::{IoTContainer, SystemConfiguration, ComponentFactory};
 //! use iot_hal::Esp32C3Platform;
 //!
@@ -48,7 +48,7 @@
 //!     let network = ComponentFactory::create_network_manager(&mut platform, &config.wifi).await
 //!         .expect("Failed to create network manager");
 //!         
-//!     let publisher = ComponentFactory::create_message_publisher(&network, &config.mqtt).await
+//!     let publisher = ComponentFactory::create_message_publisher(&platform, &network, &config.mqtt).await
 //!         .expect("Failed to create message publisher");
 //!         
 //!     let console = ComponentFactory::create_console(&mut platform, &config.console).await
@@ -97,6 +97,10 @@
 // Require alloc for Box allocations in factory methods and mock implementations
 extern crate alloc;
 
+// Mock logging prints to the test's stdout
+#[cfg(feature = "mock")]
+extern crate std;
+
 // Bring in alloc types - used for boxed trait objects in factory
 #[allow(unused_imports)]
 use alloc::boxed::Box;
@@ -106,6 +110,11 @@ pub mod traits;
 pub mod container;
 pub mod factory;
 pub mod config;
+pub mod events;
+pub mod smoothing;
+pub mod throttle;
+pub mod buffer;
+pub mod composite;
 
 // Mock implementations for testing
 #[cfg(feature = "mock")]
@@ -114,13 +123,23 @@ pub mod mocks;
 // Re-export main types
 pub use traits::{
     SensorReader, NetworkManager, MessagePublisher, ConsoleInterface,
-    Measurements, ConnectionInfo, SensorData, DeviceStatus
+    Measurements, ConnectionInfo, SensorData, DeviceStatus,
+    HealthCheck, HealthState, ComponentHealth
 };
-pub use container::IoTContainer;
-pub use factory::ComponentFactory;
+pub use container::{
+    IoTContainer, SystemHealth, PersistedState, MaintenanceReport, ShutdownSignal, PERSISTED_STATE_CONFIG,
+    run_storage_maintenance, run_storage_maintenance_once, last_storage_maintenance
+};
+pub use events::{EventBus, EventSubscriber, SystemEvent};
+pub use smoothing::{SmoothingSensorReader, ExponentialSmoother};
+pub use throttle::{PublishThrottle, ThrottleTransition, SIGNAL_RECOVERY_HYSTERESIS_DB};
+pub use buffer::MeasurementBuffer;
+pub use composite::{CompositePublisher, FanOutPolicy, TransportStats, MAX_TRANSPORTS};
+pub use factory::{ComponentFactory, ConsoleDriver, SensorDriver};
 pub use config::{
-    SystemConfiguration, SensorConfig, WiFiConfig, MqttConfig, 
-    ConsoleConfig, LogLevel, OperatingMode
+    SystemConfiguration, SensorConfig, SensorType, WiFiConfig, MqttConfig, 
+    ConsoleConfig, LogLevel, OperatingMode, RunMode, RetryPolicy, RetryConfig, SmoothingStrategy,
+    LOW_POWER_INTERVAL_FACTOR, DIAGNOSTIC_INTERVAL_SECS, SENSOR_CHIP_ID_REG
 };
 
 // Re-export mock implementations when available
@@ -139,11 +158,15 @@ pub use iot_hal::HardwarePlatform;
 /// Current version of the iot-container library
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-/// Maximum number of measurement samples buffered in container
+/// Maximum number of measurement samples buffered in container, and the
+/// default capacity of [`MeasurementBuffer`]
 pub const MAX_MEASUREMENT_BUFFER: usize = 16;
 
+/// Maximum number of samples combined by [`SmoothingSensorReader`]
+pub const MAX_SMOOTHING_WINDOW: usize = 16;
+
 /// Default system operation interval in seconds
 pub const DEFAULT_OPERATION_INTERVAL_SECS: u64 = 30;
 
-/// Maximum retry attempts for failed operations
-pub const MAX_RETRY_ATTEMPTS: u32 = 3;
\ No newline at end of file
+/// Default maximum attempts for failed operations (see [`RetryPolicy`])
+pub const MAX_RETRY_ATTEMPTS: u32 = iot_common::MAX_RETRY_ATTEMPTS;
\ No newline at end of file
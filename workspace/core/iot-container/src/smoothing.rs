@@ -0,0 +1,228 @@
+//! # Sensor Smoothing
+//!
+//! [`SmoothingSensorReader`] decorates any [`SensorReader`] and turns a window of
+//! consecutive samples into a single reading, damping the noise of individual
+//! BME280 reads without touching the driver. Because it is itself a
+//! `SensorReader` (and forwards [`HealthCheck`]), it drops into the container
+//! wherever the wrapped sensor would:
+//!
+//! ```rust,no_run
+//! use iot_container::{SmoothingSensorReader, SensorConfig, SmoothingStrategy};
+//! # use iot_container::MockSensorReader;
+//!
+//! let config = SensorConfig {
+//!     smoothing_window: 5,
+//!     smoothing_strategy: SmoothingStrategy::Median,
+//!     ..SensorConfig::default()
+//! };
+//! let sensor = SmoothingSensorReader::new(MockSensorReader::new(), &config);
+//! ```
+//!
+//! [`ExponentialSmoother`] is the lighter alternative for the publish path: an
+//! exponential moving average that stores one previous value per field and
+//! takes no extra samples. The container smooths what it publishes with
+//! [`MqttConfig::publish_smoothing_alpha`] and keeps the raw readings for the
+//! console and the measurement buffer.
+
+use async_trait::async_trait;
+use alloc::boxed::Box;
+use heapless::Vec;
+
+use iot_common::IoTError;
+
+use crate::config::{MqttConfig, SensorConfig, SmoothingStrategy};
+use crate::traits::{SensorReader, Measurements, HealthCheck, ComponentHealth};
+use crate::MAX_SMOOTHING_WINDOW;
+
+/// Sensor decorator returning the mean or median of a window of samples
+pub struct SmoothingSensorReader<S> {
+    /// Wrapped sensor providing the raw samples
+    inner: S,
+
+    /// Samples taken per reading, 1..=MAX_SMOOTHING_WINDOW
+    window: usize,
+
+    /// How the samples are combined
+    strategy: SmoothingStrategy,
+}
+
+impl<S: SensorReader> SmoothingSensorReader<S> {
+    /// Wraps a sensor using the window and strategy from `config`
+    ///
+    /// The window is clamped to `1..=MAX_SMOOTHING_WINDOW`; a window of 1
+    /// passes readings through unchanged.
+    pub fn new(inner: S, config: &SensorConfig) -> Self {
+        Self {
+            inner,
+            window: (config.smoothing_window as usize).clamp(1, MAX_SMOOTHING_WINDOW),
+            strategy: config.smoothing_strategy,
+        }
+    }
+
+    /// Gets the wrapped sensor
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Gets the wrapped sensor mutably
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Unwraps the decorator, returning the wrapped sensor
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Gets the number of samples taken per reading
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Gets the strategy used to combine samples
+    pub fn strategy(&self) -> SmoothingStrategy {
+        self.strategy
+    }
+}
+
+/// Combines one field of every sample using `strategy`
+fn combine_field(
+    samples: &[Measurements],
+    strategy: SmoothingStrategy,
+    field: fn(&Measurements) -> f32,
+) -> f32 {
+    match strategy {
+        SmoothingStrategy::Mean => {
+            samples.iter().map(field).sum::<f32>() / samples.len() as f32
+        }
+        SmoothingStrategy::Median => {
+            // Sort a copy so the samples keep their acquisition order
+            let mut values: Vec<f32, MAX_SMOOTHING_WINDOW> = samples.iter().map(field).collect();
+            values.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+            let middle = values.len() / 2;
+            if values.len().is_multiple_of(2) {
+                (values[middle - 1] + values[middle]) / 2.0
+            } else {
+                values[middle]
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<S: SensorReader + Send + Sync> SensorReader for SmoothingSensorReader<S> {
+    /// Takes `window` consecutive samples and combines them per field
+    ///
+    /// The first failed sample aborts the reading so a partial window never
+    /// skews the result; the container's sensor retry policy applies as usual.
+    /// The returned timestamp is that of the last sample.
+    async fn read_measurements(&mut self) -> Result<Measurements, IoTError> {
+        let mut samples: Vec<Measurements, MAX_SMOOTHING_WINDOW> = Vec::new();
+        for _ in 0..self.window {
+            let sample = self.inner.read_measurements().await?;
+            // Window is clamped to the capacity in new()
+            let _ = samples.push(sample);
+        }
+
+        let timestamp_ms = samples.last().map(|sample| sample.timestamp_ms).unwrap_or(0);
+        Ok(Measurements {
+            temperature: combine_field(&samples, self.strategy, |m| m.temperature),
+            pressure: combine_field(&samples, self.strategy, |m| m.pressure),
+            humidity: combine_field(&samples, self.strategy, |m| m.humidity),
+            timestamp_ms,
+        })
+    }
+
+    async fn is_available(&self) -> bool {
+        self.inner.is_available().await
+    }
+
+    async fn initialize(&mut self) -> Result<(), IoTError> {
+        self.inner.initialize().await
+    }
+
+    fn get_sensor_type(&self) -> &'static str {
+        self.inner.get_sensor_type()
+    }
+
+    fn get_last_measurement_time(&self) -> Option<u64> {
+        self.inner.get_last_measurement_time()
+    }
+
+    async fn self_test(&mut self) -> Result<(), IoTError> {
+        self.inner.self_test().await
+    }
+}
+
+impl<S: HealthCheck> HealthCheck for SmoothingSensorReader<S> {
+    fn health(&self) -> ComponentHealth {
+        self.inner.health()
+    }
+}
+
+/// Exponential moving average over the fields of successive readings
+///
+/// Each output is `alpha * reading + (1 - alpha) * previous output`, starting
+/// from the first reading. Smoothing costs lag: after a step change the output
+/// covers about 63% of the step in `1 / alpha` readings, so at `alpha = 0.2`
+/// and a 30 s interval a real change takes some 2.5 minutes to show. Raise
+/// alpha for responsiveness, lower it for quieter dashboards; `1.0` passes
+/// readings through unchanged.
+#[derive(Debug, Clone)]
+pub struct ExponentialSmoother {
+    /// Weight of the newest reading, in `(0, 1]`
+    alpha: f32,
+
+    /// Previous output, `None` until the first reading
+    previous: Option<Measurements>,
+}
+
+impl ExponentialSmoother {
+    /// Creates a smoother; an alpha outside `(0, 1]` disables smoothing
+    pub fn new(alpha: f32) -> Self {
+        Self {
+            alpha: if alpha > 0.0 && alpha <= 1.0 { alpha } else { 1.0 },
+            previous: None,
+        }
+    }
+
+    /// Creates a smoother with [`MqttConfig::publish_smoothing_alpha`]
+    pub fn from_config(config: &MqttConfig) -> Self {
+        Self::new(config.publish_smoothing_alpha)
+    }
+
+    /// Gets the weight of the newest reading
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+
+    /// Checks whether readings are actually smoothed
+    pub fn is_enabled(&self) -> bool {
+        self.alpha < 1.0
+    }
+
+    /// Blends `reading` into the average and returns the smoothed reading
+    ///
+    /// The result carries the timestamp of `reading`.
+    pub fn apply(&mut self, reading: &Measurements) -> Measurements {
+        let smoothed = match &self.previous {
+            Some(previous) => {
+                let blend = |new: f32, old: f32| self.alpha * new + (1.0 - self.alpha) * old;
+                Measurements {
+                    temperature: blend(reading.temperature, previous.temperature),
+                    pressure: blend(reading.pressure, previous.pressure),
+                    humidity: blend(reading.humidity, previous.humidity),
+                    timestamp_ms: reading.timestamp_ms,
+                }
+            }
+            None => reading.clone(),
+        };
+        self.previous = Some(smoothed.clone());
+        smoothed
+    }
+
+    /// Forgets the average, e.g. after the sensor was replaced
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+}
@@ -0,0 +1,103 @@
+//! # Signal-Aware Publish Throttling
+//!
+//! With a marginal WiFi link every MQTT publish costs TCP retransmissions and
+//! radio-on time. [`PublishThrottle`] watches the RSSI reported by the
+//! [`NetworkManager`](crate::traits::NetworkManager) and, below
+//! [`MqttConfig::weak_signal_threshold_dbm`], only lets the container publish
+//! every [`MqttConfig::weak_signal_publish_interval_secs`]. Readings keep
+//! queueing in between and go out as one batch. The throttle lifts once the
+//! signal is [`SIGNAL_RECOVERY_HYSTERESIS_DB`] above the threshold, so a link
+//! hovering around the limit does not flap between the two modes.
+
+use embassy_time::{Duration, Instant};
+
+use crate::config::MqttConfig;
+
+/// Margin above the threshold the RSSI must reach before throttling stops
+pub const SIGNAL_RECOVERY_HYSTERESIS_DB: i8 = 5;
+
+/// Change of throttling state reported by [`PublishThrottle::update`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThrottleTransition {
+    /// Signal dropped below the threshold, publishes are now batched
+    Throttled,
+
+    /// Signal recovered, publishing every cycle again
+    Restored,
+}
+
+/// Decides when queued readings may be published, based on signal strength
+#[derive(Debug, Clone)]
+pub struct PublishThrottle {
+    /// RSSI below which publishing is throttled
+    threshold_dbm: i8,
+
+    /// Minimum time between publishes while throttled
+    throttled_interval: Duration,
+
+    /// Whether the signal is currently considered weak
+    throttled: bool,
+
+    /// Time of the last successful publish
+    last_publish: Option<Instant>,
+}
+
+impl PublishThrottle {
+    /// Creates a throttle from the MQTT configuration, initially not throttled
+    pub fn new(config: &MqttConfig) -> Self {
+        Self {
+            threshold_dbm: config.weak_signal_threshold_dbm,
+            throttled_interval: Duration::from_secs(config.weak_signal_publish_interval_secs),
+            throttled: false,
+            last_publish: None,
+        }
+    }
+
+    /// Checks whether publishes are currently being batched
+    pub fn is_throttled(&self) -> bool {
+        self.throttled
+    }
+
+    /// Minimum time between publishes while throttled
+    pub fn throttled_interval(&self) -> Duration {
+        self.throttled_interval
+    }
+
+    /// Feeds the latest RSSI reading, returning the transition if the state changed
+    ///
+    /// An unknown RSSI (`None`) leaves the state unchanged.
+    pub fn update(&mut self, rssi_dbm: Option<i8>) -> Option<ThrottleTransition> {
+        let rssi = rssi_dbm?;
+
+        if !self.throttled && rssi < self.threshold_dbm {
+            self.throttled = true;
+            Some(ThrottleTransition::Throttled)
+        } else if self.throttled && rssi >= self.threshold_dbm.saturating_add(SIGNAL_RECOVERY_HYSTERESIS_DB) {
+            self.throttled = false;
+            Some(ThrottleTransition::Restored)
+        } else {
+            None
+        }
+    }
+
+    /// Checks whether queued readings should be published now
+    ///
+    /// Always true with a good signal. While throttled, true once the
+    /// throttled interval has passed since the last publish, or when the
+    /// queue is full and the oldest reading would otherwise be dropped.
+    pub fn should_publish(&self, now: Instant, queue_full: bool) -> bool {
+        if !self.throttled || queue_full {
+            return true;
+        }
+
+        match self.last_publish {
+            Some(last) => now.saturating_duration_since(last) >= self.throttled_interval,
+            None => true,
+        }
+    }
+
+    /// Records a successful publish at `now`
+    pub fn record_publish(&mut self, now: Instant) {
+        self.last_publish = Some(now);
+    }
+}
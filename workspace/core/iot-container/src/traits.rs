@@ -168,6 +168,12 @@ pub struct DeviceStatus {
     /// Number of MQTT messages published
     pub mqtt_messages_count: u32,
     
+    /// Readings waiting in the offline buffer
+    pub buffered_readings: u32,
+    
+    /// Readings lost to a full offline buffer since boot
+    pub dropped_readings: u32,
+    
     /// Last error code (0 = no error)
     pub last_error_code: u32,
 }
@@ -193,6 +199,8 @@ impl DeviceStatus {
             wifi_signal_dbm,
             sensor_readings_count: 0,
             mqtt_messages_count: 0,
+            buffered_readings: 0,
+            dropped_readings: 0,
             last_error_code: 0,
         })
     }
@@ -344,6 +352,22 @@ pub trait NetworkManager {
     /// * `Err(IoTError)` - No internet connectivity
     async fn test_connectivity(&self) -> Result<(), IoTError>;
     
+    /// Measures round-trip time to a host with an ICMP echo request
+    /// 
+    /// Lets callers confirm that the gateway or broker is reachable before
+    /// committing to a TCP connection that may take long to time out.
+    /// Implementations must return within `timeout` even when no reply
+    /// arrives. The default implementation reports ping as unsupported.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Duration)` - Round-trip time of the echo reply
+    /// * `Err(IoTError)` - Ping unsupported, not connected, or no reply in time
+    async fn ping(&self, address: embassy_net::Ipv4Address, timeout: embassy_time::Duration) -> Result<embassy_time::Duration, IoTError> {
+        let _ = (address, timeout);
+        Err(IoTError::configuration(iot_common::ConfigError::MissingConfiguration("ICMP ping not supported".try_into().unwrap_or_default())))
+    }
+    
     /// Gets the network stack for protocol operations
     /// 
     /// Returns a reference to the underlying network stack for TCP/UDP operations.
@@ -376,6 +400,26 @@ pub trait MessagePublisher {
     /// * `Err(IoTError)` - Publishing failed
     async fn publish_sensor_data(&mut self, data: &SensorData) -> Result<(), IoTError>;
     
+    /// Publishes several sensor readings at once
+    /// 
+    /// Used to flush readings buffered while offline. The default publishes them
+    /// one by one; implementations can send a single batched message instead.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `batch` - Sensor data to publish, oldest first
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(())` - All readings published
+    /// * `Err(IoTError)` - Publishing failed, readings may be partially sent
+    async fn publish_sensor_batch(&mut self, batch: &[SensorData]) -> Result<(), IoTError> {
+        for data in batch {
+            self.publish_sensor_data(data).await?;
+        }
+        Ok(())
+    }
+    
     /// Publishes device status information
     /// 
     /// Sends device health and operational status to the remote system
@@ -512,4 +556,81 @@ pub trait ConsoleInterface {
     /// * `Ok(())` - Prompt displayed successfully
     /// * `Err(IoTError)` - Prompt display failed
     async fn show_prompt(&mut self) -> Result<(), IoTError>;
-}
\ No newline at end of file
+}
+
+/// Health state of a single component
+/// 
+/// Ordered from best to worst so the overall state of several components
+/// is simply the maximum of their individual states.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HealthState {
+    /// Component is fully operational
+    Healthy,
+    /// Component works with reduced functionality
+    Degraded,
+    /// Component is not operational
+    Failed,
+}
+
+impl HealthState {
+    /// Returns the state as a lowercase string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HealthState::Healthy => "healthy",
+            HealthState::Degraded => "degraded",
+            HealthState::Failed => "failed",
+        }
+    }
+}
+
+/// Health report of a single component
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentHealth {
+    /// Current health state
+    pub state: HealthState,
+    
+    /// Short human-readable explanation of the state
+    pub message: EmbeddedString,
+}
+
+impl ComponentHealth {
+    /// Creates a health report, truncating the message if needed
+    pub fn new(state: HealthState, message: &str) -> Self {
+        let mut text = EmbeddedString::new();
+        for ch in message.chars() {
+            if text.push(ch).is_err() {
+                break;
+            }
+        }
+        Self { state, message: text }
+    }
+    
+    /// Creates a healthy report
+    pub fn healthy(message: &str) -> Self {
+        Self::new(HealthState::Healthy, message)
+    }
+    
+    /// Creates a degraded report
+    pub fn degraded(message: &str) -> Self {
+        Self::new(HealthState::Degraded, message)
+    }
+    
+    /// Creates a failed report
+    pub fn failed(message: &str) -> Self {
+        Self::new(HealthState::Failed, message)
+    }
+    
+    /// Checks if the component is healthy
+    pub fn is_healthy(&self) -> bool {
+        self.state == HealthState::Healthy
+    }
+}
+
+/// Trait for components that can report their own health
+/// 
+/// Implementations should derive the report from cached state only; the
+/// method is called from status commands and must not perform I/O.
+pub trait HealthCheck {
+    /// Returns the current health of the component
+    fn health(&self) -> ComponentHealth;
+}
@@ -10,11 +10,15 @@ use tokio;
 use std::time::Duration;
 
 use iot_container::{
-    IoTContainer, SystemConfiguration, 
-    traits::{SensorReader, NetworkManager, MessagePublisher, ConsoleInterface, Measurements},
+    IoTContainer, SystemConfiguration, PersistedState, ShutdownSignal, PERSISTED_STATE_CONFIG, RetryPolicy, RetryConfig, EventBus, SystemEvent,
+    SensorConfig, SensorType, ConsoleConfig, ComponentFactory, CompositePublisher, FanOutPolicy, TransportStats, SmoothingSensorReader, SmoothingStrategy, ExponentialSmoother, RunMode, LogLevel, MeasurementBuffer,
+    LOW_POWER_INTERVAL_FACTOR, DIAGNOSTIC_INTERVAL_SECS,
+    traits::{SensorReader, NetworkManager, MessagePublisher, ConsoleInterface, Measurements, SensorData, ComponentHealth, HealthCheck, HealthState},
     mocks::{MockSensorReader, MockNetworkManager, MockMessagePublisher, MockConsoleInterface, MockPlatform}
 };
-use iot_common::{IoTError, SensorError, NetworkError};
+use iot_common::{IoTError, SensorError, NetworkError, bme280::DATASHEET_EXAMPLE};
+use iot_hal::{HardwarePlatform, mock::{MockPlatform as HalMockPlatform, SleepKind}};
+use iot_storage::{ConfigManager, ConfigStorage, MockStorage};
 
 /// Test basic container creation and initialization
 #[tokio::test]
@@ -99,7 +103,85 @@ async fn test_sensor_failure_handling() {
     // Check system state reflects sensor failure
     let state = container.get_system_state().await;
     assert!(!state.sensor_active, "Sensor should be marked as inactive after failure");
-    assert_eq!(state.last_error_code, 1, "Should record sensor error code");
+    assert_eq!(state.last_error_code, 1005, "Should record sensor not responding error code");
+}
+
+/// Test that sensor reads are retried with backoff until they succeed
+#[tokio::test]
+async fn test_sensor_retry_recovers() {
+    let platform = MockPlatform::new();
+    let sensor = MockSensorReader::new();
+    let network = MockNetworkManager::new();
+    let publisher = MockMessagePublisher::new();
+    let console = MockConsoleInterface::new();
+    let config = SystemConfiguration::test_config();
+    let policy = config.retry.sensor;
+    
+    // Fail one time less than the policy allows
+    sensor.fail_next_reads(policy.max_attempts - 1);
+    
+    let mut container = IoTContainer::new(platform, sensor, network, publisher, console, config).await
+        .expect("Container creation should succeed");
+    
+    let start = std::time::Instant::now();
+    container.run_single_cycle().await.expect("Cycle should complete");
+    let elapsed = start.elapsed();
+    
+    assert_eq!(container.get_measurement_buffer().len(), 1, "Read should succeed on the last attempt");
+    
+    // Backoff waits 10ms then 20ms in the test policy
+    let expected_ms: u64 = (1..policy.max_attempts).map(|n| policy.delay_for(n).as_millis()).sum();
+    assert_eq!(expected_ms, 30);
+    assert!(elapsed >= Duration::from_millis(expected_ms), "Retries should wait for the backoff delays");
+}
+
+/// Test that sensor retries stop at the configured attempt cap
+#[tokio::test]
+async fn test_sensor_retry_attempt_cap() {
+    let platform = MockPlatform::new();
+    let sensor = MockSensorReader::new();
+    let network = MockNetworkManager::new();
+    let publisher = MockMessagePublisher::new();
+    let console = MockConsoleInterface::new();
+    let config = SystemConfiguration::test_config();
+    let max_attempts = config.retry.sensor.max_attempts;
+    
+    sensor.fail_next_reads(max_attempts + 2);
+    
+    let mut container = IoTContainer::new(platform, sensor, network, publisher, console, config).await
+        .expect("Container creation should succeed");
+    
+    container.run_single_cycle().await.expect("Cycle should complete even when retries are exhausted");
+    
+    assert!(container.get_measurement_buffer().is_empty(), "No reading should be buffered");
+    let state = container.get_system_state().await;
+    assert!(!state.sensor_active, "Sensor should be inactive after exhausting retries");
+}
+
+/// Test retry policy backoff calculation and validation
+#[test]
+fn test_retry_policy_backoff() {
+    let policy = RetryPolicy::new(5, 100, 2.0, 500);
+    
+    assert_eq!(policy.delay_for(1).as_millis(), 100);
+    assert_eq!(policy.delay_for(2).as_millis(), 200);
+    assert_eq!(policy.delay_for(3).as_millis(), 400);
+    assert_eq!(policy.delay_for(4).as_millis(), 500, "Delay should be capped");
+    assert_eq!(policy.delay_for(30).as_millis(), 500, "Delay should stay capped");
+    
+    assert!(policy.should_retry(4));
+    assert!(!policy.should_retry(5));
+    assert!(!RetryPolicy::no_retry().should_retry(1));
+    
+    assert!(policy.validate().is_ok());
+    assert!(RetryPolicy::new(0, 100, 2.0, 500).validate().is_err(), "Zero attempts should be rejected");
+    assert!(RetryPolicy::new(3, 100, 0.5, 500).validate().is_err(), "Shrinking backoff should be rejected");
+    assert!(RetryPolicy::new(3, 1000, 2.0, 500).validate().is_err(), "Initial delay above cap should be rejected");
+    
+    // Network retries are more patient than sensor retries by default
+    let defaults = RetryConfig::default();
+    assert!(defaults.network.max_attempts > defaults.sensor.max_attempts);
+    assert!(defaults.network.max_delay_ms > defaults.sensor.max_delay_ms);
 }
 
 /// Test network connectivity and message publishing
@@ -132,6 +214,53 @@ async fn test_network_and_publishing() {
     assert!(state.publisher_connected, "Publisher should be connected");
 }
 
+/// Test fan-out to several transports and both error-aggregation policies
+#[tokio::test]
+async fn test_composite_publisher_fan_out() {
+    fn transports(policy: FanOutPolicy) -> CompositePublisher {
+        let mqtt = MockMessagePublisher::new();
+        mqtt.set_connected(true);
+        let http = MockMessagePublisher::new();
+        http.set_connected(true);
+        http.set_should_fail(true);
+        let log = MockMessagePublisher::new();
+        log.set_connected(true);
+        CompositePublisher::new(policy)
+            .with_transport("mqtt", mqtt).expect("Room for mqtt")
+            .with_transport("http", http).expect("Room for http")
+            .with_transport("log", log).expect("Room for log")
+    }
+    let data = SensorData::new("test_device", Measurements::new(22.0, 1010.0, 50.0)).unwrap();
+    
+    // Any-success: the failing webhook does not fail the reading or stop the log
+    let mut publisher = transports(FanOutPolicy::AnySuccess);
+    assert!(publisher.publish_sensor_data(&data).await.is_ok());
+    assert!(publisher.publish_heartbeat().await.is_ok());
+    assert_eq!(publisher.stats("mqtt"), Some(TransportStats { successes: 2, failures: 0, last_failed: false }));
+    assert_eq!(publisher.stats("http"), Some(TransportStats { successes: 0, failures: 2, last_failed: true }));
+    assert_eq!(publisher.stats("log"), Some(TransportStats { successes: 2, failures: 0, last_failed: false }));
+    assert_eq!(publisher.get_metrics().0, 4, "Successful deliveries are summed");
+    assert_eq!(publisher.get_metrics().1, 2, "Failed deliveries are summed");
+    assert_eq!(publisher.health().state, HealthState::Degraded);
+    
+    // All-success: the same failure fails the reading, but every transport was still tried
+    let mut publisher = transports(FanOutPolicy::AllSuccess);
+    let error = publisher.publish_sensor_data(&data).await.expect_err("One transport failed");
+    assert!(format!("{}", error).contains("http"), "Error names the failing transport: {}", error);
+    assert_eq!(publisher.stats("log").map(|stats| stats.successes), Some(1));
+    
+    // No transports never counts as published
+    let mut empty = CompositePublisher::new(FanOutPolicy::AnySuccess);
+    assert!(empty.publish_sensor_data(&data).await.is_err());
+    assert_eq!(empty.health().state, HealthState::Failed);
+    
+    let mut full = CompositePublisher::new(FanOutPolicy::AnySuccess);
+    for name in ["a", "b", "c", "d"] {
+        full = full.with_transport(name, MockMessagePublisher::new()).expect("Within capacity");
+    }
+    assert!(full.with_transport("e", MockMessagePublisher::new()).is_err());
+}
+
 /// Test network failure and recovery
 #[tokio::test]
 async fn test_network_failure_recovery() {
@@ -142,8 +271,9 @@ async fn test_network_failure_recovery() {
     let console = MockConsoleInterface::new();
     let config = SystemConfiguration::test_config();
     
-    // Start with network disconnected
+    // Start with network disconnected and unable to reconnect
     network.set_connected(false);
+    network.set_should_fail(true);
     
     let mut container = IoTContainer::new(platform, sensor, network, publisher, console, config).await
         .expect("Container creation should succeed");
@@ -156,6 +286,28 @@ async fn test_network_failure_recovery() {
     assert!(!state.network_connected, "Network should be marked as disconnected");
 }
 
+/// Test gateway reachability checks through the network manager
+#[tokio::test]
+async fn test_network_ping() {
+    let mut network = MockNetworkManager::new();
+    let gateway = embassy_net::Ipv4Address::new(192, 168, 1, 1);
+    let timeout = embassy_time::Duration::from_millis(500);
+    
+    // Disconnected networks fail fast instead of waiting out the timeout
+    assert!(network.ping(gateway, timeout).await.is_err(), "Ping should fail while disconnected");
+    
+    network.set_connected(true);
+    let rtt = network.ping(gateway, timeout).await.expect("Ping should succeed when connected");
+    assert!(rtt <= timeout, "Round-trip time should be within the timeout");
+    
+    // A reply slower than the timeout counts as unreachable
+    network.set_ping_rtt(Some(embassy_time::Duration::from_secs(2)));
+    assert!(network.ping(gateway, timeout).await.is_err(), "Slow reply should time out");
+    
+    network.set_ping_rtt(None);
+    assert!(network.ping(gateway, timeout).await.is_err(), "Silent host should time out");
+}
+
 /// Test console command processing
 #[tokio::test]
 async fn test_console_command_processing() {
@@ -184,6 +336,204 @@ async fn test_console_command_processing() {
     assert!(state.console_active, "Console should be active");
 }
 
+/// Test that the console `restart` command resets through the platform
+#[tokio::test]
+async fn test_console_restart_resets_platform() {
+    let platform = MockPlatform::new();
+    let sensor = MockSensorReader::new();
+    let network = MockNetworkManager::new();
+    let publisher = MockMessagePublisher::new();
+    let mut console = MockConsoleInterface::new();
+    let config = SystemConfiguration::test_config();
+    
+    console.add_command("restart").expect("Should add restart command");
+    
+    let mut container = IoTContainer::new(platform, sensor, network, publisher, console, config).await
+        .expect("Container creation should succeed");
+    
+    // The loop stops after the cycle that read the command
+    tokio::time::timeout(Duration::from_secs(5), container.run_system()).await
+        .expect("run_system should return after restart")
+        .expect("run_system should succeed");
+    assert!(!container.get_platform().reset_requested(), "Reset waits for the shutdown");
+    
+    let mut config_manager = ConfigManager::new(MockStorage::new()).expect("Config manager");
+    container.restart(&mut config_manager.store()).await.expect("Restart should shut down cleanly");
+    
+    assert!(container.get_platform().reset_requested(), "Restart should reset through the platform");
+    assert_eq!(container.get_platform().reset_count(), 1);
+}
+
+/// Test that shutdown flushes the queued readings and persists the counters
+#[tokio::test]
+async fn test_shutdown_flushes_pending_and_persists_state() {
+    let platform = MockPlatform::new();
+    let sensor = MockSensorReader::new();
+    let network = MockNetworkManager::new();
+    let publisher = MockMessagePublisher::new();
+    let console = MockConsoleInterface::new();
+    let config = SystemConfiguration::test_config();
+    
+    // Readings stay queued while the network cannot reconnect
+    network.set_should_fail(true);
+    
+    let mut container = IoTContainer::new(platform, sensor, network, publisher, console, config).await
+        .expect("Container creation should succeed");
+    for _ in 0..2 {
+        container.run_single_cycle().await.expect("Cycle should complete with the network down");
+    }
+    assert_eq!(container.get_pending_measurements().len(), 2, "Readings should be queued while offline");
+    
+    let mut config_manager = ConfigManager::new(MockStorage::new()).expect("Config manager");
+    container.shutdown(&mut config_manager.store()).await.expect("Shutdown should succeed");
+    
+    assert!(container.get_pending_measurements().is_empty(), "Shutdown should flush the queue");
+    assert_eq!(container.get_publisher().get_published_sensor_data().len(), 2);
+    assert_eq!(container.get_publisher().get_batch_count(), 1, "Queued readings go out as one batch");
+    
+    let persisted: PersistedState = config_manager.store().retrieve_config(PERSISTED_STATE_CONFIG).await
+        .expect("Counters should be persisted");
+    let state = container.get_system_state().await;
+    assert_eq!(persisted, PersistedState::from(&state));
+    assert_eq!(persisted.sensor_readings_count, 2);
+    assert_eq!(persisted.messages_published_count, 2);
+}
+
+/// Test that a stop request only reaches its own container and is consumed
+#[tokio::test]
+async fn test_shutdown_signal_scoped_and_consumed() {
+    static FIRST: ShutdownSignal = ShutdownSignal::new();
+    static SECOND: ShutdownSignal = ShutdownSignal::new();
+    
+    let mut first = IoTContainer::new(
+        MockPlatform::new(), MockSensorReader::new(), MockNetworkManager::new(),
+        MockMessagePublisher::new(), MockConsoleInterface::new(), SystemConfiguration::test_config(),
+    ).await.expect("Container creation should succeed").with_shutdown_signal(&FIRST);
+    let mut second = IoTContainer::new(
+        MockPlatform::new(), MockSensorReader::new(), MockNetworkManager::new(),
+        MockMessagePublisher::new(), MockConsoleInterface::new(), SystemConfiguration::test_config(),
+    ).await.expect("Container creation should succeed").with_shutdown_signal(&SECOND);
+    
+    FIRST.request();
+    assert!(FIRST.is_requested());
+    assert!(!second.wait_for_next_cycle(embassy_time::Instant::now()).await, "Other containers keep running");
+    assert!(first.wait_for_next_cycle(embassy_time::Instant::now()).await, "The request should stop the loop");
+    assert!(!FIRST.is_requested(), "Handling the request should consume it");
+    assert!(!first.wait_for_next_cycle(embassy_time::Instant::now()).await, "The next run should not stop at once");
+    
+    // Shutdown drops a request raised after the loop returned
+    FIRST.request();
+    let mut config_manager = ConfigManager::new(MockStorage::new()).expect("Config manager");
+    let _ = first.shutdown(&mut config_manager.store()).await;
+    assert!(!FIRST.is_requested());
+}
+
+/// Test health aggregation with all components healthy
+#[tokio::test]
+async fn test_system_health_all_healthy() {
+    let platform = MockPlatform::new();
+    let sensor = MockSensorReader::new();
+    let network = MockNetworkManager::new();
+    let publisher = MockMessagePublisher::new();
+    let console = MockConsoleInterface::new();
+    let config = SystemConfiguration::test_config();
+    
+    let container = IoTContainer::new(platform, sensor, network, publisher, console, config).await
+        .expect("Container creation should succeed");
+    
+    let health = container.system_health();
+    assert_eq!(health.overall(), HealthState::Healthy);
+    assert!(health.is_healthy());
+    assert_eq!(health.health_score(), 100);
+    assert_eq!(health.status(), "Excellent");
+}
+
+/// Test that a forced degraded component degrades the aggregate
+#[tokio::test]
+async fn test_system_health_forced_degraded() {
+    let platform = MockPlatform::new();
+    let mut sensor = MockSensorReader::new();
+    let network = MockNetworkManager::new();
+    let publisher = MockMessagePublisher::new();
+    let console = MockConsoleInterface::new();
+    let config = SystemConfiguration::test_config();
+    
+    sensor.set_health(Some(ComponentHealth::degraded("Forced by test")));
+    
+    let container = IoTContainer::new(platform, sensor, network, publisher, console, config).await
+        .expect("Container creation should succeed");
+    
+    let health = container.system_health();
+    assert_eq!(health.sensor.state, HealthState::Degraded);
+    assert_eq!(health.sensor.message.as_str(), "Forced by test");
+    assert!(health.network.is_healthy());
+    assert_eq!(health.overall(), HealthState::Degraded);
+    assert!(!health.is_healthy());
+    assert_eq!(health.status(), "Good");
+}
+
+/// Test that the worst component state wins and scores accumulate
+#[tokio::test]
+async fn test_system_health_worst_state_wins() {
+    let platform = MockPlatform::new();
+    let sensor = MockSensorReader::new();
+    let network = MockNetworkManager::new();
+    let mut publisher = MockMessagePublisher::new();
+    let mut console = MockConsoleInterface::new();
+    let config = SystemConfiguration::test_config();
+    
+    publisher.set_health(Some(ComponentHealth::failed("Broker unreachable")));
+    console.set_health(Some(ComponentHealth::degraded("Forced by test")));
+    
+    let container = IoTContainer::new(platform, sensor, network, publisher, console, config).await
+        .expect("Container creation should succeed");
+    
+    let health = container.system_health();
+    assert_eq!(health.overall(), HealthState::Failed);
+    assert_eq!(health.health_score(), 45);
+    assert_eq!(health.status(), "Poor");
+}
+
+/// Test that mock health follows the mock state when not forced
+#[tokio::test]
+async fn test_component_health_derived_from_state() {
+    let network = MockNetworkManager::new();
+    assert_eq!(network.health().state, HealthState::Failed, "Disconnected network should be failed");
+    
+    network.set_connected(true);
+    assert!(network.health().is_healthy());
+    
+    let console = MockConsoleInterface::new();
+    console.set_ready(false);
+    assert_eq!(console.health().state, HealthState::Degraded);
+}
+
+/// Test event bus fan-out, subscriber limit and overflow accounting
+#[tokio::test]
+async fn test_event_bus_publish_subscribe() {
+    let bus: EventBus<2, 2> = EventBus::new();
+    
+    let mut logger = bus.subscribe().expect("First subscriber should fit");
+    let mut mqtt = bus.subscribe().expect("Second subscriber should fit");
+    assert!(bus.subscribe().is_err(), "Subscriber count should be bounded");
+    
+    bus.publish(SystemEvent::WiFiStateChanged { connected: true });
+    assert_eq!(logger.next().await, SystemEvent::WiFiStateChanged { connected: true });
+    assert_eq!(mqtt.try_next(), Some(SystemEvent::WiFiStateChanged { connected: true }));
+    assert_eq!(mqtt.try_next(), None);
+    
+    // Overflow the queue: the slow subscriber loses the oldest events
+    for sequence in 1..=3 {
+        bus.publish(SystemEvent::MqttPublished { sequence });
+    }
+    assert_eq!(logger.try_next(), Some(SystemEvent::MqttPublished { sequence: 2 }));
+    assert_eq!(logger.missed(), 1);
+    
+    // Dropping a subscriber frees its slot
+    drop(mqtt);
+    assert!(bus.subscribe().is_ok(), "Freed slot should be reusable");
+}
+
 /// Test error injection and recovery
 #[tokio::test]
 async fn test_error_injection_and_recovery() {
@@ -238,8 +588,6 @@ async fn test_concurrent_operations() {
         .expect("Container creation should succeed");
     
     // Run multiple concurrent cycles
-    let mut handles = Vec::new();
-    
     for _ in 0..5 {
         // In a real concurrent test, we would spawn actual async tasks
         // For now, we'll run sequential cycles to demonstrate the concept
@@ -288,6 +636,172 @@ async fn test_measurement_buffer_management() {
     }
 }
 
+/// Test that readings taken while offline are kept and flushed as one batch
+#[tokio::test]
+async fn test_offline_readings_flushed_as_batch() {
+    let platform = MockPlatform::new();
+    let mut sensor = MockSensorReader::new();
+    let network = MockNetworkManager::new();
+    let publisher = MockMessagePublisher::new();
+    let console = MockConsoleInterface::new();
+    let config = SystemConfiguration::test_config();
+    
+    sensor.clear_measurements();
+    for i in 0..4 {
+        sensor.add_measurement(Measurements::new(20.0 + i as f32, 1013.0, 60.0));
+    }
+    publisher.set_should_fail(true);
+    
+    let mut container = IoTContainer::new(platform, sensor, network, publisher, console, config).await
+        .expect("Container creation should succeed");
+    
+    // Broker unreachable: readings accumulate instead of being lost
+    for _ in 0..3 {
+        container.run_single_cycle().await.expect("Cycle should complete");
+    }
+    assert_eq!(container.get_pending_measurements().len(), 3, "Offline readings should be buffered");
+    assert!(container.get_publisher().get_published_sensor_data().is_empty());
+    
+    // Back online: the fourth reading joins the backlog and all go out together
+    container.get_publisher().set_should_fail(false);
+    container.run_single_cycle().await.expect("Cycle should complete");
+    
+    assert!(container.get_pending_measurements().is_empty(), "Buffer should be flushed on reconnect");
+    let publisher = container.get_publisher();
+    assert_eq!(publisher.get_batch_count(), 1, "Backlog should be sent as a single batch");
+    
+    let published = publisher.get_published_sensor_data();
+    assert_eq!(published.len(), 4);
+    for (i, data) in published.iter().enumerate() {
+        assert_eq!(data.measurements.temperature, 20.0 + i as f32, "Readings should keep their order");
+    }
+    assert!(
+        published.windows(2).all(|w| w[0].measurements.timestamp_ms <= w[1].measurements.timestamp_ms),
+        "Original timestamps should be preserved"
+    );
+}
+
+/// Test that readings are buffered through a network outage and flushed on recovery
+#[tokio::test]
+async fn test_network_outage_buffers_and_recovers() {
+    let platform = MockPlatform::new();
+    let mut sensor = MockSensorReader::new();
+    let mut network = MockNetworkManager::new();
+    let publisher = MockMessagePublisher::new();
+    let console = MockConsoleInterface::new();
+    let config = SystemConfiguration::test_config();
+    
+    sensor.clear_measurements();
+    for i in 0..4 {
+        sensor.add_measurement(Measurements::new(20.0 + i as f32, 1013.0, 60.0));
+    }
+    let outage_end = embassy_time::Duration::from_millis(500);
+    network.add_offline_window(embassy_time::Duration::from_ticks(0), outage_end);
+    
+    let mut container = IoTContainer::new(platform, sensor, network, publisher, console, config).await
+        .expect("Container creation should succeed during an outage");
+    
+    // Link down: every reconnect fails and readings pile up
+    for _ in 0..3 {
+        container.run_single_cycle().await.expect("Cycle should complete");
+    }
+    assert_eq!(container.get_pending_measurements().len(), 3, "Readings should be buffered during the outage");
+    assert!(container.get_publisher().get_published_sensor_data().is_empty());
+    
+    let network = container.get_network();
+    assert_eq!(network.get_operation_successes(), 0);
+    assert_eq!(network.get_operation_failures(), network.get_operation_attempts());
+    assert!(network.get_connection_attempts() >= 4, "Each cycle should try to reconnect");
+    
+    // Window closed: the next cycle reconnects and flushes the backlog in order
+    embassy_time::Timer::after(outage_end).await;
+    container.run_single_cycle().await.expect("Cycle should complete");
+    
+    assert!(container.get_pending_measurements().is_empty(), "Backlog should be flushed on recovery");
+    let published = container.get_publisher().get_published_sensor_data();
+    assert_eq!(published.len(), 4);
+    for (i, data) in published.iter().enumerate() {
+        assert_eq!(data.measurements.temperature, 20.0 + i as f32, "Readings should keep their order");
+    }
+    assert_eq!(container.get_network().get_operation_successes(), 1, "One reconnect should succeed");
+}
+
+/// Test that simulated packet loss is reproducible for a given seed
+#[tokio::test]
+async fn test_network_packet_loss_is_deterministic() {
+    async fn loss_pattern(percent: u8, seed: u32) -> (Vec<bool>, MockNetworkManager) {
+        let mut network = MockNetworkManager::new();
+        network.set_connected(true);
+        network.set_packet_loss(percent, seed);
+        let mut pattern = Vec::new();
+        for _ in 0..100 {
+            pattern.push(network.test_connectivity().await.is_err());
+        }
+        (pattern, network)
+    }
+    
+    let (first, network) = loss_pattern(30, 7).await;
+    let (second, _) = loss_pattern(30, 7).await;
+    assert_eq!(first, second, "Same seed should lose the same operations");
+    
+    let lost = first.iter().filter(|&&lost| lost).count() as u32;
+    assert!((15..=45).contains(&lost), "About 30% should be lost, got {}", lost);
+    assert_eq!(network.get_operation_attempts(), 100);
+    assert_eq!(network.get_operation_failures(), lost);
+    assert_eq!(network.get_operation_successes(), 100 - lost);
+    
+    let (other, _) = loss_pattern(30, 8).await;
+    assert_ne!(first, other, "Another seed should lose other operations");
+    
+    let (none, _) = loss_pattern(0, 7).await;
+    let (all, _) = loss_pattern(100, 7).await;
+    assert!(none.iter().all(|&lost| !lost));
+    assert!(all.iter().all(|&lost| lost));
+}
+
+/// Test that simulated latency delays link operations
+#[tokio::test]
+async fn test_network_latency() {
+    let mut network = MockNetworkManager::new();
+    let latency = embassy_time::Duration::from_millis(50);
+    network.set_latency(latency);
+    
+    let start = embassy_time::Instant::now();
+    network.connect().await.expect("Connect should succeed");
+    assert!(start.elapsed() >= latency + embassy_time::Duration::from_millis(100), "Connect should include the latency");
+    
+    let gateway = embassy_net::Ipv4Address::new(192, 168, 1, 1);
+    let rtt = network.ping(gateway, embassy_time::Duration::from_millis(500)).await.expect("Ping should succeed");
+    assert_eq!(rtt, embassy_time::Duration::from_millis(5) + latency, "Ping should report the latency");
+    assert!(network.ping(gateway, embassy_time::Duration::from_millis(20)).await.is_err(), "Latency beyond the timeout should time out");
+    
+    assert_eq!((network.get_operation_successes(), network.get_operation_failures()), (2, 1));
+}
+
+/// Test that a full offline buffer drops and counts the oldest readings
+#[test]
+fn test_measurement_buffer_overflow_and_restore() {
+    let mut buffer: MeasurementBuffer<u32, 4> = MeasurementBuffer::new();
+    for reading in 1..=6 {
+        buffer.push(reading);
+    }
+    assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), [3, 4, 5, 6], "Newest readings should be kept");
+    assert_eq!(buffer.dropped(), 2);
+    
+    // A failed publish hands its batch back ahead of readings taken meanwhile
+    let batch = buffer.take_all();
+    assert!(buffer.is_empty());
+    buffer.push(7);
+    buffer.push(8);
+    buffer.restore(batch);
+    assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), [5, 6, 7, 8], "Backlog should stay oldest-first");
+    assert_eq!(buffer.dropped(), 4, "Restored readings that no longer fit should be counted");
+    
+    // Publishing clears the backlog but not the loss count
+    buffer.clear();
+    assert_eq!((buffer.len(), buffer.dropped()), (0, 4));
+}
+
 /// Test configuration validation
 #[tokio::test]
 async fn test_configuration_validation() {
@@ -306,6 +820,71 @@ async fn test_configuration_validation() {
     assert!(invalid_config.validate().is_err(), "Empty device ID should be invalid");
 }
 
+/// Test layered configuration loading without overrides
+#[test]
+fn test_config_overrides_missing() {
+    let config = SystemConfiguration::from_overrides(|_| None)
+        .expect("Defaults should load without overrides");
+    assert_eq!(config, SystemConfiguration::default());
+    
+    // Whatever the build environment provides must also be valid
+    assert!(SystemConfiguration::from_env().is_ok(), "Build environment config should load");
+}
+
+/// Test layered configuration loading with valid overrides
+#[test]
+fn test_config_overrides_valid() {
+    let config = SystemConfiguration::from_overrides(|key| match key {
+        "DEVICE_ID" => Some("greenhouse_01"),
+        "OPERATION_MODE" => Some("Production"),
+        "RUN_MODE" => Some("LowPower"),
+        "LOG_LEVEL" => Some("warn"),
+        "SENSOR_READ_INTERVAL_SECS" => Some("120"),
+        "WIFI_SSID" => Some("Greenhouse"),
+        "MQTT_BROKER_IP" => Some("10.10.10.210"),
+        "MQTT_BROKER_PORT" => Some("8883"),
+        _ => None,
+    }).expect("Valid overrides should load");
+    
+    assert_eq!(config.device_id.as_str(), "greenhouse_01");
+    assert_eq!(config.operation_mode, iot_container::config::OperatingMode::Production);
+    assert_eq!(config.run_mode, RunMode::LowPower);
+    assert_eq!(config.log_level, iot_container::config::LogLevel::Warning);
+    assert_eq!(config.sensor_read_interval_secs, 120);
+    assert_eq!(config.wifi.ssid.as_str(), "Greenhouse");
+    assert_eq!(config.mqtt.broker_host.as_str(), "10.10.10.210");
+    assert_eq!(config.mqtt.broker_port, 8883);
+    
+    // Fields without overrides keep their defaults
+    let defaults = SystemConfiguration::default();
+    assert_eq!(config.wifi.password, defaults.wifi.password);
+    assert_eq!(config.mqtt.client_id, defaults.mqtt.client_id);
+}
+
+/// Test layered configuration loading with malformed overrides
+#[test]
+fn test_config_overrides_malformed() {
+    let load = |key: &'static str, value: &'static str| {
+        SystemConfiguration::from_overrides(move |k| if k == key { Some(value) } else { None })
+    };
+    
+    assert!(load("MQTT_BROKER_PORT", "18x3").is_err(), "Unparseable port should be rejected");
+    assert!(load("MQTT_BROKER_PORT", "70000").is_err(), "Out of range port should be rejected");
+    assert!(load("MQTT_BROKER_PORT", "0").is_err(), "Port 0 should fail validation");
+    assert!(load("SENSOR_READ_INTERVAL_SECS", "0").is_err(), "Zero interval should fail validation");
+    assert!(load("OPERATION_MODE", "turbo").is_err(), "Unknown mode should be rejected");
+    assert!(load("RUN_MODE", "turbo").is_err(), "Unknown run mode should be rejected");
+    assert!(load("LOG_LEVEL", "verbose").is_err(), "Unknown log level should be rejected");
+    assert!(load("WIFI_SSID", "").is_err(), "Empty SSID should fail validation");
+    assert!(
+        load("DEVICE_ID", "a_device_identifier_longer_than_32_bytes").is_err(),
+        "Oversized device ID should be rejected"
+    );
+    
+    let error = load("MQTT_BROKER_PORT", "18x3").unwrap_err();
+    assert_eq!(error.category(), "Configuration");
+}
+
 /// Test mock behavior and statistics
 #[tokio::test]
 async fn test_mock_statistics() {
@@ -466,6 +1045,457 @@ async fn test_complete_system_failure() {
     assert!(!state.publisher_connected, "Publisher should be disconnected");
 }
 
+/// Test that the smoothing decorator averages a known sample sequence
+#[tokio::test]
+async fn test_smoothing_sensor_reader() {
+    // Default mock samples: T 23.5/24.0/23.8, P 1013.25/1012.80/1013.10, H 65.0/66.5/64.8
+    let mean_config = SensorConfig {
+        smoothing_window: 3,
+        smoothing_strategy: SmoothingStrategy::Mean,
+        ..SensorConfig::default()
+    };
+    let mut mean_sensor = SmoothingSensorReader::new(MockSensorReader::new(), &mean_config);
+    
+    let reading = mean_sensor.read_measurements().await.expect("Smoothed read should succeed");
+    assert!((reading.temperature - 23.7667).abs() < 0.001);
+    assert!((reading.pressure - 1013.05).abs() < 0.01);
+    assert!((reading.humidity - 65.4333).abs() < 0.001);
+    assert_eq!(mean_sensor.inner().get_read_count(), 3, "One reading should consume the whole window");
+    
+    let median_config = SensorConfig {
+        smoothing_strategy: SmoothingStrategy::Median,
+        ..mean_config
+    };
+    let mut median_sensor = SmoothingSensorReader::new(MockSensorReader::new(), &median_config);
+    
+    let reading = median_sensor.read_measurements().await.expect("Smoothed read should succeed");
+    assert_eq!(reading.temperature, 23.8);
+    assert_eq!(reading.pressure, 1013.10);
+    assert_eq!(reading.humidity, 65.0);
+    
+    // A single spike is discarded by the median
+    median_sensor.inner_mut().add_measurement(Measurements::new(22.0, 1010.0, 50.0));
+    median_sensor.inner_mut().add_measurement(Measurements::new(80.0, 1100.0, 99.0));
+    median_sensor.inner_mut().add_measurement(Measurements::new(22.5, 1011.0, 51.0));
+    let reading = median_sensor.read_measurements().await.expect("Smoothed read should succeed");
+    assert_eq!(reading.temperature, 22.5);
+    assert_eq!(reading.pressure, 1011.0);
+    assert_eq!(reading.humidity, 51.0);
+    
+    // A failing sample aborts the whole window
+    median_sensor.inner().fail_next_reads(1);
+    assert!(median_sensor.read_measurements().await.is_err());
+}
+
+/// Test the moving average on published readings
+#[tokio::test]
+async fn test_publish_smoothing_ema() {
+    let mut smoother = ExponentialSmoother::new(0.5);
+    assert_eq!(smoother.apply(&Measurements::new(20.0, 1000.0, 40.0)).temperature, 20.0, "First reading seeds the average");
+    let reading = smoother.apply(&Measurements::new(30.0, 1010.0, 60.0));
+    assert_eq!((reading.temperature, reading.pressure, reading.humidity), (25.0, 1005.0, 50.0));
+    assert_eq!(smoother.apply(&Measurements::new(30.0, 1010.0, 60.0)).temperature, 27.5);
+    
+    smoother.reset();
+    assert_eq!(smoother.apply(&Measurements::new(10.0, 1000.0, 40.0)).temperature, 10.0);
+    
+    // 1.0 passes through; out-of-range values fall back to it
+    for alpha in [1.0, 0.0, 1.5, f32::NAN] {
+        let mut passthrough = ExponentialSmoother::new(alpha);
+        assert!(!passthrough.is_enabled());
+        passthrough.apply(&Measurements::new(20.0, 1000.0, 40.0));
+        assert_eq!(passthrough.apply(&Measurements::new(30.0, 1010.0, 60.0)).temperature, 30.0);
+    }
+    
+    let mut config = SystemConfiguration::test_config();
+    for alpha in [0.0, -0.1, 1.01, f32::NAN] {
+        config.mqtt.publish_smoothing_alpha = alpha;
+        assert!(config.validate().is_err(), "Alpha {} should be rejected", alpha);
+    }
+    config.mqtt.publish_smoothing_alpha = 0.5;
+    assert!(config.validate().is_ok());
+    
+    // The container publishes smoothed values and keeps the raw ones
+    let mut sensor = MockSensorReader::new();
+    sensor.clear_measurements();
+    for temperature in [20.0, 30.0, 30.0] {
+        sensor.add_measurement(Measurements::new(temperature, 1013.0, 60.0));
+    }
+    let mut container = IoTContainer::new(
+        MockPlatform::new(), sensor, MockNetworkManager::new(), MockMessagePublisher::new(),
+        MockConsoleInterface::new(), config,
+    ).await.expect("Container creation should succeed");
+    for _ in 0..3 {
+        container.run_single_cycle().await.expect("Cycle should complete");
+    }
+    
+    let published: Vec<f32> = container.get_publisher().get_published_sensor_data().iter()
+        .map(|data| data.measurements.temperature).collect();
+    assert_eq!(published, [20.0, 25.0, 27.5]);
+    let raw: Vec<f32> = container.get_measurement_buffer().iter().map(|m| m.temperature).collect();
+    assert_eq!(raw, [20.0, 30.0, 30.0], "Raw readings should stay unsmoothed");
+}
+
+/// Test that low-power mode stretches the interval and idles in light sleep
+#[tokio::test]
+async fn test_low_power_mode_light_sleeps() {
+    let platform = HalMockPlatform::initialize().await.expect("Mock platform should initialize");
+    let sensor = MockSensorReader::new();
+    let network = MockNetworkManager::new();
+    let publisher = MockMessagePublisher::new();
+    let console = MockConsoleInterface::new();
+    let mut config = SystemConfiguration::test_config();
+    config.run_mode = RunMode::LowPower;
+    let base_interval = embassy_time::Duration::from_secs(config.sensor_read_interval_secs);
+    
+    let mut container = IoTContainer::new(platform, sensor, network, publisher, console, config).await
+        .expect("Container creation should succeed");
+    assert_eq!(container.cycle_interval(), base_interval * LOW_POWER_INTERVAL_FACTOR);
+    
+    let shutdown = container.wait_for_next_cycle(embassy_time::Instant::now()).await;
+    assert!(!shutdown, "No shutdown was requested");
+    
+    let (kind, duration) = container.get_platform().power_ref().last_sleep()
+        .expect("Low-power mode should go through the sleep path");
+    assert_eq!(kind, SleepKind::Light);
+    assert!(duration > base_interval && duration <= container.cycle_interval());
+}
+
+/// Test that diagnostic mode polls faster and logs verbosely, without sleeping
+#[tokio::test]
+async fn test_diagnostic_mode_shortens_interval() {
+    let platform = HalMockPlatform::initialize().await.expect("Mock platform should initialize");
+    let sensor = MockSensorReader::new();
+    let network = MockNetworkManager::new();
+    let publisher = MockMessagePublisher::new();
+    let console = MockConsoleInterface::new();
+    let mut config = SystemConfiguration::test_config();
+    config.sensor_read_interval_secs = 30;
+    config.log_level = LogLevel::Warning;
+    
+    let mut container = IoTContainer::new(platform, sensor, network, publisher, console, config.clone()).await
+        .expect("Container creation should succeed");
+    assert_eq!(container.cycle_interval(), embassy_time::Duration::from_secs(30));
+    
+    container.set_run_mode(RunMode::Diagnostic);
+    assert_eq!(container.cycle_interval(), embassy_time::Duration::from_secs(DIAGNOSTIC_INTERVAL_SECS));
+    
+    container.wait_for_next_cycle(embassy_time::Instant::now()).await;
+    assert!(container.get_platform().power_ref().sleep_history().is_empty(), "Diagnostic mode must not sleep");
+    
+    config.run_mode = RunMode::Diagnostic;
+    assert_eq!(config.effective_log_level(), LogLevel::Debug);
+    
+    // A more verbose configured level is kept
+    config.log_level = LogLevel::Trace;
+    assert_eq!(config.effective_log_level(), LogLevel::Trace);
+}
+
+/// Test switching the run mode from the console
+#[tokio::test]
+async fn test_mode_console_command() {
+    let platform = MockPlatform::new();
+    let sensor = MockSensorReader::new();
+    let network = MockNetworkManager::new();
+    let publisher = MockMessagePublisher::new();
+    let mut console = MockConsoleInterface::new();
+    let config = SystemConfiguration::test_config();
+    
+    console.add_command("mode lowpower").expect("Should add mode command");
+    console.add_command("mode turbo").expect("Should add mode command");
+    console.add_command("mode Diagnostic").expect("Should add mode command");
+    
+    let mut container = IoTContainer::new(platform, sensor, network, publisher, console, config).await
+        .expect("Container creation should succeed");
+    assert_eq!(container.run_mode(), RunMode::Normal);
+    
+    container.run_single_cycle().await.expect("Cycle should complete");
+    assert_eq!(container.run_mode(), RunMode::LowPower);
+    
+    // Unknown names leave the mode unchanged
+    container.run_single_cycle().await.expect("Cycle should complete");
+    assert_eq!(container.run_mode(), RunMode::LowPower);
+    
+    container.run_single_cycle().await.expect("Cycle should complete");
+    assert_eq!(container.run_mode(), RunMode::Diagnostic);
+    
+    assert_eq!(RunMode::from_name("low-power"), Some(RunMode::LowPower));
+}
+
+/// Test that the factory builds the configured sensor only when the chip on the bus matches
+#[tokio::test]
+async fn test_factory_sensor_type_selection() {
+    fn driver(config: &SensorConfig) -> iot_common::IoTResult<Box<dyn SensorReader + Send + Sync>> {
+        Ok(Box::new(MockSensorReader::new_with_config(config)))
+    }
+    
+    let mut platform = HalMockPlatform::initialize().await.expect("Mock platform should initialize");
+    platform.i2c_mut().add_bme280(0x76, &DATASHEET_EXAMPLE);
+    
+    // Default configuration is a BME280 at 0x76
+    let config = SensorConfig::default();
+    assert_eq!(config.sensor_type, SensorType::Bme280);
+    let sensor = ComponentFactory::create_sensor_with(&mut platform, &config, Some(driver)).await
+        .expect("BME280 should match its chip ID");
+    assert_eq!(sensor.get_sensor_type(), "MOCK_BME280");
+    
+    // Without an injected driver only the chip check runs
+    assert!(ComponentFactory::create_sensor(&mut platform, &config).await.is_err());
+    
+    // A BMP280 configured against the BME280 fails with both chips named
+    let config = SensorConfig { sensor_type: SensorType::Bmp280, ..SensorConfig::default() };
+    let error = ComponentFactory::create_sensor_with(&mut platform, &config, Some(driver)).await
+        .err().expect("Chip ID mismatch should be rejected");
+    let message = format!("{}", error);
+    assert!(message.contains("BMP280 configured at 0x76 but chip ID 0x60 is BME280"), "Unexpected error: {}", message);
+    
+    platform.i2c_mut().add_simple_device(0x77, &[(0xD0, 0x58)]);
+    let config = SensorConfig { sensor_type: SensorType::Bmp280, i2c_address: 0x77, ..SensorConfig::default() };
+    let sensor = ComponentFactory::create_sensor_with(&mut platform, &config, Some(driver)).await
+        .expect("BMP280 should match its chip ID");
+    assert_eq!(sensor.get_sensor_type(), "MOCK_BMP280");
+    
+    // The mock sensor needs neither a chip nor a driver
+    let config = SensorConfig { sensor_type: SensorType::Mock, i2c_address: 0x50, ..SensorConfig::default() };
+    assert!(ComponentFactory::create_sensor(&mut platform, &config).await.is_ok());
+}
+
+/// Test that the factory builds USB and UART consoles through the injected driver
+#[tokio::test]
+async fn test_factory_console_driver() {
+    // Not ready, so the console can be told apart from the factory's own mock
+    fn driver(_config: &ConsoleConfig) -> iot_common::IoTResult<Box<dyn ConsoleInterface + Send + Sync>> {
+        let console = MockConsoleInterface::new();
+        console.set_ready(false);
+        Ok(Box::new(console))
+    }
+    
+    let mut platform = HalMockPlatform::initialize().await.expect("Mock platform should initialize");
+    
+    // Default configuration is the USB Serial/JTAG console
+    let config = ConsoleConfig::default();
+    assert_eq!(config.interface_type.as_str(), "USB");
+    let console = ComponentFactory::create_console_with(&mut platform, &config, Some(driver)).await
+        .expect("USB console should come from the driver");
+    assert!(!console.is_ready().await);
+    
+    // Without an injected driver there is nothing to build
+    assert!(ComponentFactory::create_console(&mut platform, &config).await.is_err());
+    
+    let config = ConsoleConfig { interface_type: "UART".try_into().unwrap(), ..ConsoleConfig::default() };
+    assert!(ComponentFactory::create_console_with(&mut platform, &config, Some(driver)).await.is_ok());
+    
+    // The mock console needs no driver, unknown types fail even with one
+    let config = ConsoleConfig { interface_type: "MOCK".try_into().unwrap(), ..ConsoleConfig::default() };
+    let console = ComponentFactory::create_console(&mut platform, &config).await.expect("Mock console");
+    assert!(console.is_ready().await);
+    let config = ConsoleConfig { interface_type: "Network".try_into().unwrap(), ..ConsoleConfig::default() };
+    assert!(ComponentFactory::create_console_with(&mut platform, &config, Some(driver)).await.is_err());
+}
+
+/// Test a full cycle on a sensor fed from a BME280 register image over the mock bus
+#[tokio::test]
+async fn test_bme280_register_replay_cycle() {
+    let mut platform = HalMockPlatform::initialize().await.expect("Mock platform should initialize");
+    platform.i2c_mut().add_bme280(0x76, &DATASHEET_EXAMPLE);
+    
+    let mut sensor = MockSensorReader::new();
+    sensor.clear_measurements();
+    sensor.load_bme280(platform.get_i2c(), 0x76).await.expect("Mock BME280 should answer");
+    
+    // A device without the BME280 chip ID is rejected
+    platform.i2c_mut().add_simple_device(0x77, &[(0xD0, 0x58)]);
+    assert!(sensor.load_bme280(platform.get_i2c(), 0x77).await.is_err());
+    
+    let network = MockNetworkManager::new();
+    let publisher = MockMessagePublisher::new();
+    let console = MockConsoleInterface::new();
+    let config = SystemConfiguration::test_config();
+    
+    let mut container = IoTContainer::new(platform, sensor, network, publisher, console, config).await
+        .expect("Container creation should succeed");
+    container.run_single_cycle().await.expect("Cycle should complete");
+    
+    // Datasheet example: 25.08 °C, 1006.53 hPa, 49.43 %RH
+    let reading = container.get_measurement_buffer().back().expect("Cycle should record the reading");
+    assert_eq!(reading.temperature, 25.08);
+    assert!((reading.pressure - 1006.53).abs() < 0.01);
+    assert!((reading.humidity - 49.43).abs() < 0.01);
+    
+    let (temperature, pressure, humidity) = DATASHEET_EXAMPLE.expected();
+    assert_eq!((reading.temperature, reading.pressure, reading.humidity), (temperature, pressure, humidity));
+}
+
+/// Test that a weak signal batches publishes until the interval passes
+#[test]
+fn test_publish_throttle_follows_signal() {
+    use embassy_time::Instant;
+    use iot_container::{PublishThrottle, ThrottleTransition, SIGNAL_RECOVERY_HYSTERESIS_DB};
+    
+    let mut config = SystemConfiguration::test_config();
+    config.mqtt.weak_signal_threshold_dbm = -80;
+    config.mqtt.weak_signal_publish_interval_secs = 60;
+    let mut throttle = PublishThrottle::new(&config.mqtt);
+    
+    let start = Instant::from_secs(100);
+    assert_eq!(throttle.update(Some(-60)), None);
+    assert!(throttle.should_publish(start, false));
+    
+    assert_eq!(throttle.update(Some(-85)), Some(ThrottleTransition::Throttled));
+    throttle.record_publish(start);
+    assert!(!throttle.should_publish(Instant::from_secs(130), false));
+    assert!(throttle.should_publish(Instant::from_secs(130), true), "A full queue is flushed anyway");
+    assert!(throttle.should_publish(Instant::from_secs(160), false));
+    
+    // Unknown RSSI and readings inside the hysteresis band keep the throttle on
+    assert_eq!(throttle.update(None), None);
+    assert_eq!(throttle.update(Some(-80 + SIGNAL_RECOVERY_HYSTERESIS_DB - 1)), None);
+    assert!(throttle.is_throttled());
+    
+    assert_eq!(throttle.update(Some(-70)), Some(ThrottleTransition::Restored));
+    assert!(throttle.should_publish(Instant::from_secs(101), false));
+}
+
+/// Test that the MQTT client ID is derived from the MAC unless pinned
+#[test]
+fn test_mqtt_client_id_from_device_mac() {
+    use iot_hal::mock::MOCK_DEVICE_INFO;
+    
+    let config = SystemConfiguration::test_config();
+    let derived = config.mqtt.clone().with_device_id(MOCK_DEVICE_INFO.mac).unwrap();
+    assert_eq!(derived.client_id.as_str(), "esp32c3-020000C30001");
+    assert!(derived.client_id.len() <= iot_common::MQTT_MAX_CLIENT_ID_LEN);
+    
+    let mut pinned = config.mqtt.clone();
+    pinned.fixed_client_id = true;
+    let pinned = pinned.with_device_id(MOCK_DEVICE_INFO.mac).unwrap();
+    assert_eq!(pinned.client_id, config.mqtt.client_id);
+    
+    let overrides = SystemConfiguration::from_overrides(|key| match key {
+        "MQTT_CLIENT_ID" => Some("central-id-42"),
+        "MQTT_CLIENT_ID_FIXED" => Some("true"),
+        _ => None,
+    }).unwrap();
+    assert!(overrides.mqtt.fixed_client_id);
+    assert!(SystemConfiguration::from_overrides(|key| (key == "MQTT_CLIENT_ID_FIXED").then_some("yes")).is_err());
+}
+
+/// Test the status LED transitions for every combination of sensor, WiFi and MQTT state
+#[tokio::test]
+async fn test_status_led_patterns_per_state() {
+    use iot_hal::{HardwareConfig, StatusLed, LedCondition, LedPattern};
+    
+    let mut platform = HalMockPlatform::initialize().await.expect("Mock platform should initialize");
+    platform.timer_mut().set_acceleration(1000.0);
+    let mut status_led = StatusLed::new(platform.config().gpio.status_led_patterns.clone());
+    
+    let ms = embassy_time::Duration::from_millis;
+    let cases = [
+        // (sensor, wifi, mqtt), condition, LED writes, delays
+        ((true, true, true), LedCondition::Operational, vec!["set_high", "set_low"], vec![ms(500), ms(500)]),
+        ((true, true, false), LedCondition::MqttFailing, vec!["set_high", "set_low", "set_high", "set_low"], vec![ms(100), ms(150), ms(100), ms(650)]),
+        ((true, false, true), LedCondition::WiFiConnecting, vec!["set_high", "set_low"], vec![ms(250), ms(250)]),
+        ((true, false, false), LedCondition::WiFiConnecting, vec!["set_high", "set_low"], vec![ms(250), ms(250)]),
+        ((false, true, true), LedCondition::SensorFault, vec!["set_high", "set_low"], vec![ms(125), ms(125)]),
+        ((false, true, false), LedCondition::SensorFault, vec!["set_high", "set_low"], vec![ms(125), ms(125)]),
+        ((false, false, true), LedCondition::SensorFault, vec!["set_high", "set_low"], vec![ms(125), ms(125)]),
+        ((false, false, false), LedCondition::SensorFault, vec!["set_high", "set_low"], vec![ms(125), ms(125)]),
+    ];
+    
+    let mut previous = None;
+    for ((sensor, wifi, mqtt), expected, writes, delays) in cases {
+        let condition = LedCondition::from_flags(sensor, wifi, mqtt);
+        assert_eq!(condition, expected, "flags {:?}", (sensor, wifi, mqtt));
+        assert_eq!(status_led.set_condition(condition), previous != Some(condition), "Only changes are reported");
+        previous = Some(condition);
+        
+        platform.gpio_mut().clear_operations();
+        platform.timer_mut().clear_history();
+        status_led.play_cycle(&mut platform).await.expect("LED writes should succeed");
+        assert_eq!(platform.gpio_ref().operations(), writes, "{}", condition.as_str());
+        assert_eq!(platform.timer_mut().delay_history(), delays.as_slice(), "{}", condition.as_str());
+        assert!(!platform.gpio_ref().is_led_on(), "Every period ends dark");
+    }
+    
+    // The mapping comes from the configuration
+    let mut config = HardwareConfig::default();
+    config.gpio.status_led_patterns.mqtt_failing = LedPattern::Solid;
+    config.gpio.status_led_patterns.operational = LedPattern::Breathe;
+    let mut platform = HalMockPlatform::initialize_with_config(config).await.expect("Mock platform should initialize");
+    platform.timer_mut().set_acceleration(1000.0);
+    let mut status_led = StatusLed::new(platform.config().gpio.status_led_patterns.clone());
+    
+    status_led.set_condition(LedCondition::from_flags(true, true, false));
+    status_led.play_cycle(&mut platform).await.unwrap();
+    assert_eq!(platform.gpio_ref().operations(), ["set_high"]);
+    assert!(platform.gpio_ref().is_led_on());
+    
+    // Breathe without a PWM channel falls back to a slow blink on the GPIO
+    platform.gpio_mut().clear_operations();
+    status_led.set_condition(LedCondition::Operational);
+    assert_eq!(status_led.pattern(), LedPattern::Breathe);
+    status_led.play_cycle(&mut platform).await.unwrap();
+    assert_eq!(platform.gpio_ref().operations(), ["set_high", "set_low"]);
+    assert!(platform.pwm_ref().duty_history().is_empty());
+}
+
+/// Test the status LED driven through a PWM channel
+#[tokio::test]
+async fn test_status_led_breathes_on_pwm() {
+    use iot_hal::{HardwareConfig, StatusLed, LedCondition};
+    
+    let mut config = HardwareConfig::default();
+    config.gpio.status_led_patterns.operational = iot_hal::LedPattern::Breathe;
+    config.gpio.status_led_patterns.pwm_channel = Some(1);
+    let mut platform = HalMockPlatform::initialize_with_config(config.clone()).await.expect("Mock platform should initialize");
+    platform.timer_mut().set_acceleration(1000.0);
+    let mut status_led = StatusLed::new(config.gpio.status_led_patterns.clone());
+    
+    status_led.set_condition(LedCondition::Operational);
+    status_led.play_cycle(&mut platform).await.unwrap();
+    let duties: Vec<u16> = platform.pwm_ref().duty_history().iter()
+        .map(|&(channel, duty)| { assert_eq!(channel, 1); duty })
+        .collect();
+    let peak = duties.iter().position(|&d| d == u16::MAX).expect("Breathe reaches full brightness");
+    assert_eq!(duties[0], 0);
+    assert!(duties[..=peak].windows(2).all(|w| w[0] < w[1]), "Fades in");
+    assert!(duties[peak..].windows(2).all(|w| w[0] > w[1]), "Fades out");
+    assert!(platform.gpio_ref().operations().is_empty(), "The GPIO LED is left alone");
+    let period: embassy_time::Duration = platform.timer_mut().delay_history().iter().copied()
+        .fold(embassy_time::Duration::from_ticks(0), |a, b| a + b);
+    assert_eq!(period, embassy_time::Duration::from_secs(2));
+    
+    // Blink patterns switch the channel fully on and off
+    platform.pwm_mut().clear_history();
+    status_led.set_condition(LedCondition::from_flags(false, false, false));
+    status_led.play_cycle(&mut platform).await.unwrap();
+    assert_eq!(platform.pwm_ref().duty_history(), [(1, u16::MAX), (1, 0)]);
+    
+    // The ESP32-C3 has four LEDC outputs
+    config.gpio.status_led_patterns.pwm_channel = Some(4);
+    assert!(config.validate().is_err());
+}
+
+/// Test the mock on-die temperature sensor
+#[tokio::test]
+async fn test_chip_temperature_mock() {
+    use iot_common::HardwareError;
+    
+    let mut platform = HalMockPlatform::initialize().await.expect("Mock platform should initialize");
+    assert_eq!(platform.read_chip_temperature().unwrap(), iot_hal::mock::MOCK_CHIP_TEMPERATURE);
+    
+    platform.set_chip_temperature(Some(85.5));
+    assert_eq!(platform.read_chip_temperature().unwrap(), 85.5);
+    
+    platform.set_chip_temperature(None);
+    let error = platform.read_chip_temperature().unwrap_err();
+    assert!(matches!(error, HardwareError::TemperatureSensorError(_)));
+    assert_eq!(error.error_code(), 3008);
+    
+    platform.reset_all();
+    assert_eq!(platform.read_chip_temperature().unwrap(), iot_hal::mock::MOCK_CHIP_TEMPERATURE);
+}
+
 /// Integration test summary
 #[tokio::test]
 async fn test_integration_summary() {
@@ -474,12 +1504,24 @@ async fn test_integration_summary() {
     println!("╚════════════════════════════════════════════════════════════════╝");
     println!("✓ Container creation and initialization");
     println!("✓ Sensor reading cycles and failure handling");
+    println!("✓ BME280 register replay through the mock bus");
+    println!("✓ Factory sensor selection with chip ID check");
     println!("✓ Network connectivity and message publishing");
+    println!("✓ Fan-out publishing with any/all-success policies");
+    println!("✓ Publish throttling on weak signal");
+    println!("✓ MQTT client ID derived from the device MAC");
+    println!("✓ Gateway reachability (ping)");
+    println!("✓ Network latency, packet loss and outages");
+    println!("✓ Status LED patterns per system state");
+    println!("✓ Chip temperature sensor mock");
     println!("✓ Console command processing");
     println!("✓ Error injection and recovery");
     println!("✓ Concurrent operations");
     println!("✓ Measurement buffer management");
+    println!("✓ Sensor smoothing (mean/median)");
+    println!("✓ Moving average on published readings");
     println!("✓ Configuration validation");
+    println!("✓ Run modes (normal/low-power/diagnostic)");
     println!("✓ Mock behavior and statistics");
     println!("✓ Error propagation");
     println!("✓ System metrics and monitoring");
@@ -3,6 +3,33 @@
 //! Configuration structures for hardware platform initialization.
 //! Provides a centralized way to configure hardware parameters.
 
+use core::ops::RangeInclusive;
+
+use crate::traits::{Pull, I2C_SCAN_FIRST_ADDRESS, I2C_SCAN_LAST_ADDRESS};
+use crate::status_led::LedPatternTable;
+use embassy_time::Duration;
+
+/// Highest GPIO number on the ESP32-C3 (GPIO0-21)
+pub const ESP32C3_MAX_GPIO: u8 = 21;
+
+/// GPIOs wired to the in-package SPI flash (GPIO11 is VDD_SPI by default)
+pub const ESP32C3_FLASH_GPIOS: RangeInclusive<u8> = 11..=17;
+
+/// GPIOs carrying USB D-/D+ for the USB Serial/JTAG console
+pub const ESP32C3_USB_GPIOS: RangeInclusive<u8> = 18..=19;
+
+/// GPIOs the platform can route I2C SDA/SCL to
+/// 
+/// The GPIO matrix could carry the bus on any pin, but the ADC, PWM, status
+/// LED, wake button and UART0 already hold every other usable one.
+pub const ESP32C3_I2C_GPIOS: [u8; 4] = [8, 9, 18, 19];
+
+/// GPIO the user button is read from
+/// 
+/// The button doubles as the sleep wake source, and GPIO4 is the free
+/// RTC-capable pin (only GPIO0-5 can wake the chip from deep sleep).
+pub const ESP32C3_BUTTON_GPIO: u8 = 4;
+
 /// Hardware platform configuration
 /// 
 /// Centralizes all hardware configuration parameters in one structure.
@@ -26,11 +53,7 @@
 /// 
 /// // Customize specific parameters
 /// let config = HardwareConfig {
-///     i2c: I2cConfig {
-///         sda_pin: 8,
-///         scl_pin: 9,
-///         frequency: 400_000,
-///     },
+///     i2c: I2cConfig::with_pins(8, 9).with_frequency(100_000),
 ///     uart: UartConfig {
 ///         tx_pin: 21,
 ///         rx_pin: 20,
@@ -134,6 +157,13 @@ impl HardwareConfig {
         // Check for pin conflicts
         self.check_pin_conflicts()?;
         
+        // USB D-/D+ are only free when the console does not use USB
+        if self.uart.backend == ConsoleBackend::UsbSerialJtag
+            && (ESP32C3_USB_GPIOS.contains(&self.i2c.sda_pin) || ESP32C3_USB_GPIOS.contains(&self.i2c.scl_pin))
+        {
+            return Err("I2C pins GPIO18/19 are in use by the USB Serial/JTAG console");
+        }
+        
         Ok(())
     }
 
@@ -151,11 +181,11 @@ impl HardwareConfig {
             return Err("I2C SCL pin conflict");
         }
         
-        // Check UART pins
-        if !used_pins.insert(self.uart.tx_pin).map_err(|_| "Too many pins configured")? {
+        // Check UART pins (USB Serial/JTAG uses dedicated pins)
+        if self.uart.backend == ConsoleBackend::Uart && !used_pins.insert(self.uart.tx_pin).map_err(|_| "Too many pins configured")? {
             return Err("UART TX pin conflict");
         }
-        if !used_pins.insert(self.uart.rx_pin).map_err(|_| "Too many pins configured")? {
+        if self.uart.backend == ConsoleBackend::Uart && !used_pins.insert(self.uart.rx_pin).map_err(|_| "Too many pins configured")? {
             return Err("UART RX pin conflict");
         }
         
@@ -163,6 +193,9 @@ impl HardwareConfig {
         if !used_pins.insert(self.gpio.status_led_pin).map_err(|_| "Too many pins configured")? {
             return Err("Status LED pin conflict");
         }
+        if !used_pins.insert(ESP32C3_BUTTON_GPIO).map_err(|_| "Too many pins configured")? {
+            return Err("Button pin conflict");
+        }
         
         Ok(())
     }
@@ -190,7 +223,20 @@ impl HardwareConfig {
     }
 }
 
+/// Lowest I2C clock accepted by [`I2cConfig`] (standard mode)
+pub const I2C_MIN_FREQUENCY_HZ: u32 = 100_000;
+
+/// Highest I2C clock accepted by [`I2cConfig`] (fast mode, the ESP32-C3 limit)
+pub const I2C_MAX_FREQUENCY_HZ: u32 = 400_000;
+
+/// Longest accepted [`I2cConfig::transaction_timeout`]
+pub const I2C_MAX_TRANSACTION_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// I2C bus configuration parameters
+/// 
+/// Pins and sensor address describe the board layout, so a board wired
+/// differently from the DevKit needs a different configuration, not a code
+/// change. SDA and SCL must be two of [`ESP32C3_I2C_GPIOS`].
 #[derive(Debug, Clone, PartialEq)]
 pub struct I2cConfig {
     /// SDA (data) pin number
@@ -199,14 +245,18 @@ pub struct I2cConfig {
     /// SCL (clock) pin number
     pub scl_pin: u8,
     
-    /// I2C bus frequency in Hz
-    pub frequency: u32,
+    /// 7-bit address of the environmental sensor (0x76 or 0x77 for a BME280)
+    pub sensor_address: u8,
+    
+    /// I2C bus clock in Hz; lower it for long or noisy wiring
+    pub frequency_hz: u32,
     
     /// Enable internal pull-up resistors
     pub pullup_enabled: bool,
     
-    /// Bus timeout in milliseconds
-    pub timeout_ms: u32,
+    /// Longest a single read/write may take before it fails with
+    /// `I2cError::Timeout`, so a stuck slave cannot hang the calling task
+    pub transaction_timeout: Duration,
 }
 
 impl Default for I2cConfig {
@@ -214,9 +264,10 @@ impl Default for I2cConfig {
         Self {
             sda_pin: 8,      // Common ESP32-C3 I2C SDA
             scl_pin: 9,      // Common ESP32-C3 I2C SCL
-            frequency: 400_000, // 400kHz (fast mode)
+            sensor_address: 0x76, // BME280 with SDO tied to GND
+            frequency_hz: 400_000, // 400kHz (fast mode)
             pullup_enabled: true,
-            timeout_ms: 1000,
+            transaction_timeout: Duration::from_millis(1000),
         }
     }
 }
@@ -244,15 +295,32 @@ impl I2cConfig {
     /// 
     /// # Arguments
     /// 
-    /// * `frequency` - Bus frequency in Hz
+    /// * `frequency_hz` - Bus frequency in Hz, between
+    ///   [`I2C_MIN_FREQUENCY_HZ`] and [`I2C_MAX_FREQUENCY_HZ`]
     /// 
     /// # Common Frequencies
     /// 
-    /// - 100_000 Hz - Standard mode
+    /// - 100_000 Hz - Standard mode, for long or noisy buses
     /// - 400_000 Hz - Fast mode (default)
-    /// - 1_000_000 Hz - Fast mode plus
-    pub fn with_frequency(mut self, frequency: u32) -> Self {
-        self.frequency = frequency;
+    pub fn with_frequency(mut self, frequency_hz: u32) -> Self {
+        self.frequency_hz = frequency_hz;
+        self
+    }
+
+    /// Set the per-transaction timeout
+    pub fn with_transaction_timeout(mut self, timeout: Duration) -> Self {
+        self.transaction_timeout = timeout;
+        self
+    }
+
+    /// Set the sensor address
+    /// 
+    /// # Arguments
+    /// 
+    /// * `address` - 7-bit address, between [`I2C_SCAN_FIRST_ADDRESS`] and
+    ///   [`I2C_SCAN_LAST_ADDRESS`]
+    pub fn with_sensor_address(mut self, address: u8) -> Self {
+        self.sensor_address = address;
         self
     }
 
@@ -266,21 +334,26 @@ impl I2cConfig {
     /// Validate I2C configuration
     fn validate(&self) -> Result<(), &'static str> {
         // Validate pin numbers for ESP32-C3
-        if self.sda_pin > 21 {
-            return Err("Invalid I2C SDA pin for ESP32-C3");
+        if !ESP32C3_I2C_GPIOS.contains(&self.sda_pin) {
+            return Err("Invalid I2C SDA pin for ESP32-C3 (GPIO8, 9, 18 or 19)");
         }
-        if self.scl_pin > 21 {
-            return Err("Invalid I2C SCL pin for ESP32-C3");
+        if !ESP32C3_I2C_GPIOS.contains(&self.scl_pin) {
+            return Err("Invalid I2C SCL pin for ESP32-C3 (GPIO8, 9, 18 or 19)");
+        }
+        
+        // Reserved addresses (0x00-0x07, 0x78-0x7F) never belong to a sensor
+        if !(I2C_SCAN_FIRST_ADDRESS..=I2C_SCAN_LAST_ADDRESS).contains(&self.sensor_address) {
+            return Err("I2C sensor address out of range (0x08 - 0x77)");
         }
         
         // Validate frequency range
-        if self.frequency < 10_000 || self.frequency > 1_000_000 {
-            return Err("I2C frequency out of supported range (10kHz - 1MHz)");
+        if !(I2C_MIN_FREQUENCY_HZ..=I2C_MAX_FREQUENCY_HZ).contains(&self.frequency_hz) {
+            return Err("I2C frequency out of supported range (100kHz - 400kHz)");
         }
         
         // Validate timeout
-        if self.timeout_ms == 0 || self.timeout_ms > 10_000 {
-            return Err("I2C timeout must be 1-10000ms");
+        if self.transaction_timeout.as_millis() == 0 || self.transaction_timeout > I2C_MAX_TRANSACTION_TIMEOUT {
+            return Err("I2C transaction timeout must be 1-10000ms");
         }
         
         Ok(())
@@ -296,6 +369,9 @@ pub struct UartConfig {
     /// UART receive pin number
     pub rx_pin: u8,
     
+    /// Console transport selected at initialization
+    pub backend: ConsoleBackend,
+    
     /// Baud rate in bits per second
     pub baud_rate: u32,
     
@@ -318,6 +394,18 @@ pub struct UartConfig {
     pub tx_buffer_size: usize,
 }
 
+/// Baud rates accepted for a UART console
+pub const SUPPORTED_BAUD_RATES: [u32; 8] = [9_600, 19_200, 38_400, 57_600, 115_200, 230_400, 460_800, 921_600];
+
+/// Transport carrying the console
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleBackend {
+    /// Built-in USB Serial/JTAG controller (no pins, baud rate ignored)
+    UsbSerialJtag,
+    /// UART on `tx_pin`/`rx_pin`, for boards without USB broken out
+    Uart,
+}
+
 /// UART parity configuration
 #[derive(Debug, Clone, PartialEq)]
 pub enum UartParity {
@@ -334,6 +422,7 @@ impl Default for UartConfig {
         Self {
             tx_pin: 21,      // ESP32-C3 common UART TX
             rx_pin: 20,      // ESP32-C3 common UART RX
+            backend: ConsoleBackend::Uart,
             baud_rate: 115_200,
             data_bits: 8,
             stop_bits: 1,
@@ -368,15 +457,21 @@ impl UartConfig {
         Self {
             tx_pin: 255, // Special value for USB Serial/JTAG
             rx_pin: 255, // Special value for USB Serial/JTAG
+            backend: ConsoleBackend::UsbSerialJtag,
             ..Default::default()
         }
     }
 
+    /// ESP32-C3 UART0 console on its default pins (GPIO21 TX, GPIO20 RX)
+    pub fn esp32c3_uart0(baud_rate: u32) -> Self {
+        Self::default().with_baud_rate(baud_rate)
+    }
+
     /// Validate UART configuration
     fn validate(&self) -> Result<(), &'static str> {
-        // Special case for USB Serial/JTAG
-        if self.tx_pin == 255 && self.rx_pin == 255 {
-            return Ok(()); // USB Serial/JTAG doesn't need pin validation
+        // USB Serial/JTAG doesn't need pin or baud rate validation
+        if self.backend == ConsoleBackend::UsbSerialJtag {
+            return Ok(());
         }
         
         // Validate pin numbers for ESP32-C3
@@ -387,9 +482,9 @@ impl UartConfig {
             return Err("Invalid UART RX pin for ESP32-C3");
         }
         
-        // Validate baud rate
-        if self.baud_rate < 300 || self.baud_rate > 2_000_000 {
-            return Err("UART baud rate out of supported range");
+        // Validate baud rate against the rates terminals commonly offer
+        if !SUPPORTED_BAUD_RATES.contains(&self.baud_rate) {
+            return Err("UART baud rate must be a standard rate from 9600 to 921600");
         }
         
         // Validate data bits
@@ -415,6 +510,18 @@ pub struct GpioConfig {
     /// Status LED active level (true = active high)
     pub status_led_active_high: bool,
     
+    /// Status LED pattern per system condition
+    pub status_led_patterns: LedPatternTable,
+    
+    /// User button active level (true = pressed reads high); the button is on [`ESP32C3_BUTTON_GPIO`]
+    pub button_active_high: bool,
+    
+    /// Internal pull resistor on the button pin
+    pub button_pull: Pull,
+    
+    /// Time a press must remain stable before it is reported
+    pub button_debounce_ms: u32,
+    
     /// Additional GPIO pins for future use
     pub user_pins: heapless::Vec<u8, 8>,
 }
@@ -424,6 +531,10 @@ impl Default for GpioConfig {
         Self {
             status_led_pin: 3,   // ESP32-C3 built-in LED
             status_led_active_high: true,
+            status_led_patterns: LedPatternTable::default(),
+            button_active_high: false,
+            button_pull: Pull::Up,
+            button_debounce_ms: 20,
             user_pins: heapless::Vec::new(),
         }
     }
@@ -446,6 +557,14 @@ impl GpioConfig {
         if self.status_led_pin > 21 {
             return Err("Invalid status LED pin for ESP32-C3");
         }
+        if self.status_led_patterns.pwm_channel.is_some_and(|channel| channel >= 4) {
+            return Err("Invalid status LED PWM channel for ESP32-C3");
+        }
+        
+        // Validate button
+        if self.button_debounce_ms > 1000 {
+            return Err("Button debounce must be at most 1000 ms");
+        }
         
         // Validate user pins
         for &pin in &self.user_pins {
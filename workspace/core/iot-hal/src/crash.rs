@@ -0,0 +1,131 @@
+//! # Post-Crash Diagnostics
+//!
+//! The reset-reason register (see [`HardwarePlatform::reset_reason`]) tells
+//! *how* the chip restarted, but a panic handler that reboots the device shows
+//! up as a plain software reset. [`record_panic`] stashes a short reason such
+//! as `"panic at main.rs:412: index out of bounds"` in RTC memory right before
+//! the reset, and the next boot collects it once with [`take_panic_reason`].
+//!
+//! ## Usage
+//!
+//! ```rust
+//! #[panic_handler]
+//! fn panic(info: &core::panic::PanicInfo) -> ! {
+//!     iot_hal::crash::record_panic(info);
+//!     iot_hal::esp32c3::utils::system_reset()
+//! }
+//!
+//! // Early in main, before anything can panic again:
+//! if let Some(reason) = iot_hal::crash::take_panic_reason() {
+//!     rprintln!("Last reset: {}", reason);
+//! }
+//! ```
+//!
+//! ## Persistence and Volatility
+//!
+//! The record is a `#[ram(persistent)]` static in RTC fast memory, which the
+//! startup code does not zero. It therefore survives software resets, watchdog
+//! resets and deep sleep, but not power loss: after power-on or a brownout the
+//! content is undefined and [`take_panic_reason`] discards it. A magic word
+//! and a length check reject garbage that happens to survive, and the record
+//! is cleared when read so one panic is reported exactly once. Only the first
+//! [`MAX_PANIC_REASON_LEN`] bytes of the reason are kept.
+//!
+//! [`HardwarePlatform::reset_reason`]: crate::HardwarePlatform::reset_reason
+
+use core::fmt::{self, Write};
+use core::panic::PanicInfo;
+
+use heapless::String;
+
+use crate::ResetReason;
+
+/// Longest panic reason kept across the reset
+pub const MAX_PANIC_REASON_LEN: usize = 58;
+
+/// Panic reason recovered after a reset
+pub type PanicReason = String<MAX_PANIC_REASON_LEN>;
+
+/// Marks a valid record ("PANC")
+const RECORD_MAGIC: u32 = 0x504E_4143;
+
+/// Record layout: 4-byte magic, 1-byte length, reserved byte, reason bytes
+const RECORD_HEADER_LEN: usize = 6;
+const RECORD_LEN: usize = RECORD_HEADER_LEN + MAX_PANIC_REASON_LEN;
+
+#[esp_hal::ram(unstable(rtc_fast, persistent))]
+static mut PANIC_RECORD: [u8; RECORD_LEN] = [0; RECORD_LEN];
+
+/// Writer that keeps what fits and silently drops the rest
+struct Truncating<'a>(&'a mut PanicReason);
+
+impl Write for Truncating<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if self.0.push(c).is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Stash the panic location and message for the next boot
+///
+/// Call from the `#[panic_handler]` before resetting. Only the file name of
+/// the location is kept to leave room for the message.
+pub fn record_panic(info: &PanicInfo) {
+    let mut reason = PanicReason::new();
+    let mut writer = Truncating(&mut reason);
+    let _ = writer.write_str("panic");
+    if let Some(location) = info.location() {
+        let file = location.file().rsplit('/').next().unwrap_or(location.file());
+        let _ = write!(writer, " at {}:{}", file, location.line());
+    }
+    let _ = write!(writer, ": {}", info.message());
+    store(&reason);
+}
+
+/// Collect the reason stashed by [`record_panic`] before the last reset
+///
+/// Returns `None` if the last reset was not caused by a recorded panic, and
+/// always after power-on or brownout (RTC memory content is undefined then).
+/// Clears the record, so call it once at boot.
+pub fn take_panic_reason() -> Option<PanicReason> {
+    let reason = load();
+    clear();
+
+    match crate::esp32c3::utils::reset_reason() {
+        ResetReason::PowerOn | ResetReason::Brownout => None,
+        _ => reason,
+    }
+}
+
+fn store(reason: &str) {
+    // SAFETY: only touched by the panic handler and once at boot, on a single core
+    let record = unsafe { &mut *core::ptr::addr_of_mut!(PANIC_RECORD) };
+    let len = reason.len().min(MAX_PANIC_REASON_LEN);
+    record[..4].copy_from_slice(&RECORD_MAGIC.to_le_bytes());
+    record[4] = len as u8;
+    record[RECORD_HEADER_LEN..RECORD_HEADER_LEN + len].copy_from_slice(&reason.as_bytes()[..len]);
+}
+
+fn load() -> Option<PanicReason> {
+    // SAFETY: see `store`
+    let record = unsafe { &*core::ptr::addr_of!(PANIC_RECORD) };
+    if record[..4] != RECORD_MAGIC.to_le_bytes() {
+        return None;
+    }
+    let len = record[4] as usize;
+    if len > MAX_PANIC_REASON_LEN {
+        return None;
+    }
+    let text = core::str::from_utf8(&record[RECORD_HEADER_LEN..RECORD_HEADER_LEN + len]).ok()?;
+    PanicReason::try_from(text).ok()
+}
+
+fn clear() {
+    // SAFETY: see `store`
+    let record = unsafe { &mut *core::ptr::addr_of_mut!(PANIC_RECORD) };
+    record[..4].fill(0);
+}
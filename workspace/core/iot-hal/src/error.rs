@@ -221,6 +221,122 @@ impl fmt::Display for GpioError {
     }
 }
 
+/// ADC conversion errors
+#[derive(Debug, Clone)]
+pub enum AdcError {
+    /// Channel number outside the range supported by the platform
+    InvalidChannel(u8),
+    
+    /// Channel exists but its pin is claimed by another peripheral
+    ChannelUnavailable(u8),
+    
+    /// Conversion did not complete or returned an out-of-range value
+    ConversionFailed(u8),
+    
+    /// Hardware fault
+    HardwareFault(&'static str),
+}
+
+impl fmt::Display for AdcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdcError::InvalidChannel(channel) => {
+                write!(f, "Invalid ADC channel: {}", channel)
+            }
+            AdcError::ChannelUnavailable(channel) => {
+                write!(f, "ADC channel {} unavailable", channel)
+            }
+            AdcError::ConversionFailed(channel) => {
+                write!(f, "ADC conversion failed on channel {}", channel)
+            }
+            AdcError::HardwareFault(msg) => {
+                write!(f, "ADC hardware fault: {}", msg)
+            }
+        }
+    }
+}
+
+/// PWM output errors
+#[derive(Debug, Clone)]
+pub enum PwmError {
+    /// Channel number outside the range exposed by the platform
+    InvalidChannel(u8),
+    
+    /// Requested frequency cannot be generated at the configured resolution
+    InvalidFrequency(u32),
+    
+    /// Timer or channel configuration rejected by the peripheral
+    ConfigurationFailed(&'static str),
+}
+
+impl fmt::Display for PwmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PwmError::InvalidChannel(channel) => {
+                write!(f, "Invalid PWM channel: {}", channel)
+            }
+            PwmError::InvalidFrequency(hz) => {
+                write!(f, "Unsupported PWM frequency: {} Hz", hz)
+            }
+            PwmError::ConfigurationFailed(msg) => {
+                write!(f, "PWM configuration failed: {}", msg)
+            }
+        }
+    }
+}
+
+/// Watchdog timer errors
+#[derive(Debug, Clone)]
+pub enum WatchdogError {
+    /// Timeout is zero or exceeds the hardware counter range
+    InvalidTimeout,
+    
+    /// Operation requires the watchdog to be enabled
+    NotEnabled,
+    
+    /// Watchdog expired before it was fed
+    Expired,
+}
+
+impl fmt::Display for WatchdogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WatchdogError::InvalidTimeout => {
+                write!(f, "Invalid watchdog timeout")
+            }
+            WatchdogError::NotEnabled => {
+                write!(f, "Watchdog not enabled")
+            }
+            WatchdogError::Expired => {
+                write!(f, "Watchdog expired")
+            }
+        }
+    }
+}
+
+/// Sleep and wake configuration errors
+#[derive(Debug, Clone)]
+pub enum SleepError {
+    /// Sleep duration is zero or too long for the RTC wake timer
+    InvalidDuration,
+    
+    /// Pin cannot be used as a wake source on this platform
+    WakePinUnavailable(u8),
+}
+
+impl fmt::Display for SleepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SleepError::InvalidDuration => {
+                write!(f, "Invalid sleep duration")
+            }
+            SleepError::WakePinUnavailable(pin) => {
+                write!(f, "GPIO {} cannot be used as wake source", pin)
+            }
+        }
+    }
+}
+
 /// WiFi operation errors
 #[derive(Debug, Clone)]
 pub enum WiFiError {
@@ -391,8 +507,8 @@ impl From<UartError> for IoTError {
     }
 }
 
-/// Convert GPIO errors to IoT errors
-impl From<GpioError> for IoTError {
+/// Convert GPIO errors to hardware errors
+impl From<GpioError> for HardwareError {
     fn from(error: GpioError) -> Self {
         use iot_common::error::utils::error_message;
         
@@ -414,7 +530,132 @@ impl From<GpioError> for IoTError {
             }
         };
         
-        IoTError::hardware(HardwareError::GPIOError(message))
+        HardwareError::GPIOError(message)
+    }
+}
+
+/// Convert GPIO errors to IoT errors
+impl From<GpioError> for IoTError {
+    fn from(error: GpioError) -> Self {
+        IoTError::hardware(error.into())
+    }
+}
+
+/// Convert ADC errors to hardware errors
+///
+/// ADC channels are analog functions of GPIO pins, so failures are reported
+/// as GPIO errors to keep the `HardwareError` code space unchanged.
+impl From<AdcError> for HardwareError {
+    fn from(error: AdcError) -> Self {
+        use iot_common::error::utils::error_message;
+        
+        let message = match error {
+            AdcError::InvalidChannel(_channel) => {
+                error_message("ADC invalid channel")
+            }
+            AdcError::ChannelUnavailable(_channel) => {
+                error_message("ADC channel unavailable")
+            }
+            AdcError::ConversionFailed(_channel) => {
+                error_message("ADC conversion failed")
+            }
+            AdcError::HardwareFault(_msg) => {
+                error_message("ADC hardware fault")
+            }
+        };
+        
+        HardwareError::GPIOError(message)
+    }
+}
+
+/// Convert ADC errors to IoT errors
+impl From<AdcError> for IoTError {
+    fn from(error: AdcError) -> Self {
+        IoTError::hardware(error.into())
+    }
+}
+
+/// Convert PWM errors to hardware errors
+///
+/// PWM outputs are generated by hardware timers, so failures are reported
+/// as timer errors.
+impl From<PwmError> for HardwareError {
+    fn from(error: PwmError) -> Self {
+        use iot_common::error::utils::error_message;
+        
+        let message = match error {
+            PwmError::InvalidChannel(_channel) => {
+                error_message("PWM invalid channel")
+            }
+            PwmError::InvalidFrequency(_hz) => {
+                error_message("PWM unsupported frequency")
+            }
+            PwmError::ConfigurationFailed(_msg) => {
+                error_message("PWM configuration failed")
+            }
+        };
+        
+        HardwareError::TimerError(message)
+    }
+}
+
+/// Convert PWM errors to IoT errors
+impl From<PwmError> for IoTError {
+    fn from(error: PwmError) -> Self {
+        IoTError::hardware(error.into())
+    }
+}
+
+/// Convert watchdog errors to hardware errors
+impl From<WatchdogError> for HardwareError {
+    fn from(error: WatchdogError) -> Self {
+        use iot_common::error::utils::error_message;
+        
+        let message = match error {
+            WatchdogError::InvalidTimeout => {
+                error_message("Watchdog invalid timeout")
+            }
+            WatchdogError::NotEnabled => {
+                error_message("Watchdog not enabled")
+            }
+            WatchdogError::Expired => {
+                error_message("Watchdog expired")
+            }
+        };
+        
+        HardwareError::TimerError(message)
+    }
+}
+
+/// Convert watchdog errors to IoT errors
+impl From<WatchdogError> for IoTError {
+    fn from(error: WatchdogError) -> Self {
+        IoTError::hardware(error.into())
+    }
+}
+
+/// Convert sleep errors to hardware errors
+impl From<SleepError> for HardwareError {
+    fn from(error: SleepError) -> Self {
+        use iot_common::error::utils::error_message;
+        
+        let message = match error {
+            SleepError::InvalidDuration => {
+                error_message("Sleep invalid duration")
+            }
+            SleepError::WakePinUnavailable(_pin) => {
+                error_message("Sleep wake pin unavailable")
+            }
+        };
+        
+        HardwareError::PowerError(message)
+    }
+}
+
+/// Convert sleep errors to IoT errors
+impl From<SleepError> for IoTError {
+    fn from(error: SleepError) -> Self {
+        IoTError::hardware(error.into())
     }
 }
 
@@ -5,23 +5,42 @@
 
 use crate::{
     HardwarePlatform, I2cInterface, UartTxInterface, UartRxInterface, 
-    GpioInterface, TimerInterface, WiFiInterface, WiFiConnectionInfo,
-    HardwareConfig, error::*
+    GpioInterface, TimerInterface, WiFiInterface, AdcInterface, PwmInterface, WatchdogInterface, PowerInterface, WakeLevel,
+    GpioInputInterface, Edge, Pull, WiFiConnectionInfo,
+    HardwareConfig, SharedI2cBus, I2cBusMutex, config::{GpioConfig, ConsoleBackend, UartParity}, error::*
 };
-use iot_common::IoTError;
-use embassy_time::{Duration, Instant};
+use iot_common::{IoTError, HardwareError, DeviceInfo, ResetReason};
+use embassy_time::{with_timeout, Duration, Instant};
 use async_trait::async_trait;
 use alloc::boxed::Box;
 use esp_hal::{
+    analog::adc::{Adc, AdcConfig, AdcPin, Attenuation},
     i2c::master::{I2c, Config as I2cConfig},
-    uart::{UartTx, UartRx},
+    uart::{Uart, UartTx, UartRx, Config as UartHwConfig, DataBits, Parity, StopBits},
     usb_serial_jtag::{UsbSerialJtag, UsbSerialJtagTx, UsbSerialJtagRx},
-    gpio::{Output, AnyPin, OutputConfig},
+    gpio::{Output, AnyPin, OutputConfig, Input, InputConfig, Pull as EspPull},
+    ledc::{
+        channel::{self as ledc_channel, ChannelHW, ChannelIFace},
+        timer::{self as ledc_timer, TimerIFace},
+        LSGlobalClkSource, Ledc, LowSpeed,
+    },
+    gpio::RtcPinWithResistors,
+    rtc_cntl::{
+        sleep::{RtcioWakeupSource, TimerWakeupSource, WakeupLevel},
+        Rtc, RwdtStage, RwdtStageAction,
+    },
     time::Rate,
+    tsens::{self, TemperatureSensor},
     Async, peripherals,
 };
 use core::net::IpAddr;
 
+/// Lowest die temperature the sensor is specified for
+const CHIP_TEMPERATURE_MIN_C: f32 = -40.0;
+
+/// Highest die temperature the sensor is specified for
+const CHIP_TEMPERATURE_MAX_C: f32 = 125.0;
+
 /// ESP32-C3 hardware platform implementation
 /// 
 /// Provides concrete hardware access for ESP32-C3 microcontroller using
@@ -30,11 +49,15 @@ use core::net::IpAddr;
 /// 
 /// # Resource Management
 /// 
-/// - **I2C Bus**: Single I2C master for sensor communication
-/// - **UART/USB**: Console interface (USB Serial/JTAG preferred)
+/// - **I2C Bus**: Single I2C master for sensor communication, shareable via
+///   [`get_shared_i2c`](HardwarePlatform::get_shared_i2c)
+/// - **UART/USB**: Console interface, USB Serial/JTAG or UART0 (GPIO21/20)
 /// - **GPIO**: Status LED and user-defined pins
 /// - **WiFi**: Network connectivity with automatic management
 /// - **Timer**: Embassy-based async delays and timeouts
+/// - **ADC**: ADC1 oneshot sampling on GPIO0-2
+/// - **PWM**: LEDC low-speed outputs on GPIO5-7 and GPIO10
+/// - **RTC**: RTC watchdog (RWDT), light/deep sleep and GPIO4 wake/user button
 /// 
 /// # Memory Usage
 /// 
@@ -57,8 +80,8 @@ use core::net::IpAddr;
 /// let led = platform.get_status_led();
 /// ```
 pub struct Esp32C3Platform<'d> {
-    /// I2C bus for sensor communication
-    i2c: Esp32C3I2c<'d>,
+    /// I2C bus for sensor communication, behind a mutex so drivers can share it
+    i2c: I2cBusMutex<Esp32C3I2c<'d>>,
     
     /// Console UART transmitter
     uart_tx: Esp32C3UartTx<'d>,
@@ -75,6 +98,18 @@ pub struct Esp32C3Platform<'d> {
     /// WiFi interface
     wifi: Esp32C3WiFi,
     
+    /// ADC1 analog inputs
+    adc: Esp32C3Adc<'d>,
+    
+    /// LEDC PWM outputs
+    pwm: Esp32C3Pwm<'d>,
+    
+    /// RTC control (watchdog, sleep)
+    rtc: Esp32C3Rtc<'d>,
+    
+    /// On-die temperature sensor, `None` if it failed to start
+    tsens: Option<TemperatureSensor<'d>>,
+    
     /// Platform configuration
     #[allow(dead_code)]
     config: HardwareConfig,
@@ -88,6 +123,11 @@ impl<'d> HardwarePlatform for Esp32C3Platform<'d> {
     type GpioPin = Esp32C3Gpio<'d>;
     type Timer = Esp32C3Timer;
     type WiFi = Esp32C3WiFi;
+    type Adc = Esp32C3Adc<'d>;
+    type Pwm = Esp32C3Pwm<'d>;
+    type Watchdog = Esp32C3Rtc<'d>;
+    type Power = Esp32C3Rtc<'d>;
+    type Button = Esp32C3Rtc<'d>;
 
     async fn initialize() -> Result<Self, IoTError> {
         Self::initialize_with_config(HardwareConfig::esp32c3_devkit()).await
@@ -100,11 +140,25 @@ impl<'d> HardwarePlatform for Esp32C3Platform<'d> {
         // Initialize ESP32-C3 peripherals
         let peripherals = esp_hal::init(esp_hal::Config::default());
 
-        // Initialize I2C bus
-        let i2c = Self::init_i2c(peripherals.I2C0, &config)?;
+        // Initialize I2C bus on the configured pins, chosen from the ones no
+        // other function below claims (USB D-/D+ only with a UART console)
+        let (sda, scl) = utils::i2c_pins(
+            &config.i2c,
+            peripherals.GPIO8,
+            peripherals.GPIO9,
+            peripherals.GPIO18,
+            peripherals.GPIO19,
+        )?;
+        let i2c = Self::init_i2c(peripherals.I2C0, sda, scl, &config)?;
 
         // Initialize console (USB Serial/JTAG or UART)
-        let (uart_tx, uart_rx) = Self::init_console(peripherals.USB_DEVICE, &config)?;
+        let (uart_tx, uart_rx) = Self::init_console(
+            peripherals.USB_DEVICE,
+            peripherals.UART0,
+            peripherals.GPIO21,
+            peripherals.GPIO20,
+            &config,
+        )?;
 
         // Initialize status LED GPIO
         let status_led = Self::init_status_led(peripherals.GPIO3.into(), &config)?;
@@ -115,19 +169,55 @@ impl<'d> HardwarePlatform for Esp32C3Platform<'d> {
         // Initialize WiFi (placeholder - actual implementation would use peripherals.WIFI)
         let wifi = Esp32C3WiFi::new(&config.wifi)?;
 
+        // Initialize ADC1 on the analog-capable pins not claimed above
+        // (GPIO3 is the status LED, GPIO4 the wake button)
+        let adc = Esp32C3Adc::new(
+            peripherals.ADC1,
+            peripherals.GPIO0,
+            peripherals.GPIO1,
+            peripherals.GPIO2,
+        );
+
+        // Initialize LEDC PWM outputs on free general-purpose pins
+        let pwm = Esp32C3Pwm::new(
+            peripherals.LEDC,
+            [
+                peripherals.GPIO5.into(),
+                peripherals.GPIO6.into(),
+                peripherals.GPIO7.into(),
+                peripherals.GPIO10.into(),
+            ],
+        )?;
+
+        // Initialize RTC control block (watchdog stays disabled until enabled);
+        // the user button shares GPIO4 with the sleep wake source
+        let rtc = Esp32C3Rtc::new(peripherals.LPWR, peripherals.GPIO4, &config.gpio);
+
+        // Initialize the on-die temperature sensor; a failure only disables
+        // read_chip_temperature, so it does not fail the whole platform
+        let tsens = TemperatureSensor::new(peripherals.TSENS, tsens::Config::default()).ok();
+
         Ok(Self {
-            i2c,
+            i2c: I2cBusMutex::new(i2c),
             uart_tx,
             uart_rx,
             status_led,
             timer,
             wifi,
+            adc,
+            pwm,
+            rtc,
+            tsens,
             config,
         })
     }
 
     fn get_i2c(&mut self) -> &mut Self::I2cBus {
-        &mut self.i2c
+        self.i2c.get_mut()
+    }
+
+    fn get_shared_i2c(&self) -> SharedI2cBus<'_> {
+        SharedI2cBus::new(&self.i2c)
     }
 
     fn get_console(&mut self) -> (&mut Self::UartTx, &mut Self::UartRx) {
@@ -146,9 +236,43 @@ impl<'d> HardwarePlatform for Esp32C3Platform<'d> {
         &mut self.wifi
     }
 
+    fn get_adc(&mut self) -> &mut Self::Adc {
+        &mut self.adc
+    }
+
+    fn get_pwm(&mut self) -> &mut Self::Pwm {
+        &mut self.pwm
+    }
+
+    fn get_watchdog(&mut self) -> &mut Self::Watchdog {
+        &mut self.rtc
+    }
+
+    fn get_power(&mut self) -> &mut Self::Power {
+        &mut self.rtc
+    }
+
+    fn get_button(&mut self) -> &mut Self::Button {
+        &mut self.rtc
+    }
+
+    fn read_chip_temperature(&mut self) -> HardwareResult<f32> {
+        use iot_common::error::utils::error_message;
+
+        let sensor = self.tsens.as_ref()
+            .ok_or_else(|| HardwareError::TemperatureSensorError(error_message("Sensor not initialized")))?;
+        let celsius = sensor.get_temperature().to_celsius();
+
+        if !(CHIP_TEMPERATURE_MIN_C..=CHIP_TEMPERATURE_MAX_C).contains(&celsius) {
+            return Err(HardwareError::TemperatureSensorError(error_message("Reading out of range")));
+        }
+
+        Ok(celsius)
+    }
+
     async fn is_healthy(&mut self) -> bool {
         // Check I2C bus health
-        if !self.i2c.is_healthy().await {
+        if !self.i2c.get_mut().is_healthy().await {
             return false;
         }
 
@@ -173,39 +297,113 @@ impl<'d> HardwarePlatform for Esp32C3Platform<'d> {
     fn platform_info(&self) -> &'static str {
         "ESP32-C3 RISC-V 160MHz with WiFi and Embassy async framework"
     }
+
+    fn device_info(&self) -> DeviceInfo {
+        utils::read_device_info()
+    }
+
+    async fn reset(&mut self) {
+        utils::system_reset()
+    }
 }
 
 impl<'d> Esp32C3Platform<'d> {
+    /// Configuration the platform was initialized with
+    pub fn config(&self) -> &HardwareConfig {
+        &self.config
+    }
+
     /// Initialize I2C bus with configuration
+    /// 
+    /// SDA and SCL are routed through the GPIO matrix to the pins claimed by
+    /// [`utils::i2c_pins`].
     fn init_i2c<'a>(
         i2c_peripheral: peripherals::I2C0<'a>,
+        sda: AnyPin<'a>,
+        scl: AnyPin<'a>,
         config: &HardwareConfig,
     ) -> Result<Esp32C3I2c<'a>, IoTError> {
         let i2c_config = I2cConfig::default()
-            .with_frequency(Rate::from_hz(config.i2c.frequency));
+            .with_frequency(Rate::from_hz(config.i2c.frequency_hz));
 
-        // Note: In real implementation, would configure pins based on config
-        // For now, using default pins from peripherals
         let i2c = I2c::new(i2c_peripheral, i2c_config)
             .map_err(|_| PlatformError::InitializationFailed("I2C initialization failed"))?
+            .with_sda(sda)
+            .with_scl(scl)
             .into_async();
 
-        Ok(Esp32C3I2c::new(i2c))
+        Ok(Esp32C3I2c::new(i2c, config.i2c.transaction_timeout))
     }
 
-    /// Initialize console interface (USB Serial/JTAG)
+    /// Initialize console interface on the configured backend
+    /// 
+    /// USB Serial/JTAG needs no pins. UART0 is routed to its default pins
+    /// (GPIO21 TX, GPIO20 RX), the ones broken out on headless boards.
     fn init_console<'a>(
         usb_device: peripherals::USB_DEVICE<'a>,
-        _config: &HardwareConfig,
+        uart0: peripherals::UART0<'a>,
+        tx_pin: peripherals::GPIO21<'a>,
+        rx_pin: peripherals::GPIO20<'a>,
+        config: &HardwareConfig,
     ) -> Result<(Esp32C3UartTx<'a>, Esp32C3UartRx<'a>), IoTError> {
-        // For ESP32-C3, we prefer USB Serial/JTAG for console
-        let usb_serial = UsbSerialJtag::new(usb_device).into_async();
-        let (rx, tx) = usb_serial.split();
+        match config.uart.backend {
+            ConsoleBackend::UsbSerialJtag => {
+                let usb_serial = UsbSerialJtag::new(usb_device).into_async();
+                let (rx, tx) = usb_serial.split();
+
+                Ok((
+                    Esp32C3UartTx::new_usb(tx),
+                    Esp32C3UartRx::new_usb(rx),
+                ))
+            }
+            ConsoleBackend::Uart => {
+                if config.uart.tx_pin != UART0_TX_GPIO {
+                    return Err(UartError::InitializationFailed("UART0 console TX must be GPIO21").into());
+                }
+                if config.uart.rx_pin != UART0_RX_GPIO {
+                    return Err(UartError::InitializationFailed("UART0 console RX must be GPIO20").into());
+                }
+
+                let uart = Uart::new(uart0, Self::uart_config(config)?)
+                    .map_err(|_| UartError::InitializationFailed("UART configuration rejected"))?
+                    .with_tx(tx_pin)
+                    .with_rx(rx_pin)
+                    .into_async();
+                let (rx, tx) = uart.split();
+
+                Ok((
+                    Esp32C3UartTx::new_uart(tx),
+                    Esp32C3UartRx::new_uart(rx),
+                ))
+            }
+        }
+    }
 
-        Ok((
-            Esp32C3UartTx::new_usb(tx),
-            Esp32C3UartRx::new_usb(rx),
-        ))
+    /// Translate the console settings into an esp-hal UART configuration
+    fn uart_config(config: &HardwareConfig) -> Result<UartHwConfig, IoTError> {
+        let data_bits = match config.uart.data_bits {
+            5 => DataBits::_5,
+            6 => DataBits::_6,
+            7 => DataBits::_7,
+            8 => DataBits::_8,
+            _ => return Err(UartError::InitializationFailed("Unsupported data bits").into()),
+        };
+        let stop_bits = match config.uart.stop_bits {
+            1 => StopBits::_1,
+            2 => StopBits::_2,
+            _ => return Err(UartError::InitializationFailed("Unsupported stop bits").into()),
+        };
+        let parity = match config.uart.parity {
+            UartParity::None => Parity::None,
+            UartParity::Even => Parity::Even,
+            UartParity::Odd => Parity::Odd,
+        };
+
+        Ok(UartHwConfig::default()
+            .with_baudrate(config.uart.baud_rate)
+            .with_data_bits(data_bits)
+            .with_stop_bits(stop_bits)
+            .with_parity(parity))
     }
 
     /// Initialize status LED GPIO  
@@ -219,13 +417,18 @@ impl<'d> Esp32C3Platform<'d> {
 }
 
 /// ESP32-C3 I2C interface implementation
+/// 
+/// Transfers run on the async driver and are abandoned after the configured
+/// transaction timeout, so a slave holding the bus fails the call with
+/// [`I2cError::Timeout`] instead of hanging the task.
 pub struct Esp32C3I2c<'d> {
     i2c: I2c<'d, Async>,
+    timeout: Duration,
 }
 
 impl<'d> Esp32C3I2c<'d> {
-    fn new(i2c: I2c<'d, Async>) -> Self {
-        Self { i2c }
+    fn new(i2c: I2c<'d, Async>, timeout: Duration) -> Self {
+        Self { i2c, timeout }
     }
 
     async fn is_healthy(&mut self) -> bool {
@@ -242,21 +445,33 @@ impl<'d> Esp32C3I2c<'d> {
 #[async_trait(?Send)]
 impl<'d> I2cInterface for Esp32C3I2c<'d> {
     async fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), IoTError> {
-        self.i2c.read(address, buffer)
-            .map_err(|_| I2cError::DeviceNotResponding(address).into())
+        match with_timeout(self.timeout, self.i2c.read_async(address, buffer)).await {
+            Ok(result) => result.map_err(|_| I2cError::DeviceNotResponding(address).into()),
+            Err(_) => Err(I2cError::Timeout.into()),
+        }
     }
 
     async fn write(&mut self, address: u8, data: &[u8]) -> Result<(), IoTError> {
-        self.i2c.write(address, data)
-            .map_err(|_| I2cError::DeviceNotResponding(address).into())
+        match with_timeout(self.timeout, self.i2c.write_async(address, data)).await {
+            Ok(result) => result.map_err(|_| I2cError::DeviceNotResponding(address).into()),
+            Err(_) => Err(I2cError::Timeout.into()),
+        }
     }
 
     async fn write_read(&mut self, address: u8, write_data: &[u8], read_buffer: &mut [u8]) -> Result<(), IoTError> {
-        self.i2c.write_read(address, write_data, read_buffer)
-            .map_err(|_| I2cError::DeviceNotResponding(address).into())
+        match with_timeout(self.timeout, self.i2c.write_read_async(address, write_data, read_buffer)).await {
+            Ok(result) => result.map_err(|_| I2cError::DeviceNotResponding(address).into()),
+            Err(_) => Err(I2cError::Timeout.into()),
+        }
     }
 }
 
+/// UART0 default TX pin, used for the UART console
+const UART0_TX_GPIO: u8 = 21;
+
+/// UART0 default RX pin, used for the UART console
+const UART0_RX_GPIO: u8 = 20;
+
 /// ESP32-C3 UART transmitter implementation
 pub struct Esp32C3UartTx<'d> {
     interface: UartTxType<'d>,
@@ -264,7 +479,6 @@ pub struct Esp32C3UartTx<'d> {
 
 enum UartTxType<'d> {
     Usb(UsbSerialJtagTx<'d, Async>),
-    #[allow(dead_code)]
     Uart(UartTx<'d, Async>),
 }
 
@@ -275,7 +489,6 @@ impl<'d> Esp32C3UartTx<'d> {
         }
     }
 
-    #[allow(dead_code)]
     fn new_uart(tx: UartTx<'d, Async>) -> Self {
         Self {
             interface: UartTxType::Uart(tx),
@@ -324,7 +537,6 @@ pub struct Esp32C3UartRx<'d> {
 
 enum UartRxType<'d> {
     Usb(UsbSerialJtagRx<'d, Async>),
-    #[allow(dead_code)]
     Uart(UartRx<'d, Async>),
 }
 
@@ -335,7 +547,6 @@ impl<'d> Esp32C3UartRx<'d> {
         }
     }
 
-    #[allow(dead_code)]
     fn new_uart(rx: UartRx<'d, Async>) -> Self {
         Self {
             interface: UartRxType::Uart(rx),
@@ -418,6 +629,363 @@ impl<'d> GpioInterface for Esp32C3Gpio<'d> {
     }
 }
 
+/// Full-scale input voltage of ESP32-C3 ADC1 at 11 dB attenuation
+const ADC_FULL_SCALE_MV: u32 = 2500;
+
+/// Maximum raw value of the 12-bit ADC1 conversion
+const ADC_MAX_RAW: u32 = 4095;
+
+/// ESP32-C3 ADC implementation
+/// 
+/// Wraps ADC1 in oneshot mode. Channel numbers follow the ADC1 channel
+/// (and GPIO) numbering: 0, 1 and 2. Channels 3 and 4 share GPIO3 and
+/// GPIO4 with the status LED and wake button and are reported as
+/// unavailable. All channels use 11 dB attenuation, giving a 0-2500 mV
+/// input range.
+pub struct Esp32C3Adc<'d> {
+    adc: Adc<'d, peripherals::ADC1<'d>, Async>,
+    channel0: AdcPin<peripherals::GPIO0<'d>, peripherals::ADC1<'d>>,
+    channel1: AdcPin<peripherals::GPIO1<'d>, peripherals::ADC1<'d>>,
+    channel2: AdcPin<peripherals::GPIO2<'d>, peripherals::ADC1<'d>>,
+}
+
+impl<'d> Esp32C3Adc<'d> {
+    fn new(
+        adc1: peripherals::ADC1<'d>,
+        gpio0: peripherals::GPIO0<'d>,
+        gpio1: peripherals::GPIO1<'d>,
+        gpio2: peripherals::GPIO2<'d>,
+    ) -> Self {
+        let mut adc_config = AdcConfig::new();
+        let channel0 = adc_config.enable_pin(gpio0, Attenuation::_11dB);
+        let channel1 = adc_config.enable_pin(gpio1, Attenuation::_11dB);
+        let channel2 = adc_config.enable_pin(gpio2, Attenuation::_11dB);
+        let adc = Adc::new(adc1, adc_config).into_async();
+
+        Self {
+            adc,
+            channel0,
+            channel1,
+            channel2,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<'d> AdcInterface for Esp32C3Adc<'d> {
+    async fn read_raw(&mut self, channel: u8) -> HardwareResult<u16> {
+        let raw = match channel {
+            0 => self.adc.read_oneshot(&mut self.channel0).await,
+            1 => self.adc.read_oneshot(&mut self.channel1).await,
+            2 => self.adc.read_oneshot(&mut self.channel2).await,
+            3 | 4 => return Err(AdcError::ChannelUnavailable(channel).into()),
+            _ => return Err(AdcError::InvalidChannel(channel).into()),
+        };
+
+        if raw as u32 > ADC_MAX_RAW {
+            return Err(AdcError::ConversionFailed(channel).into());
+        }
+
+        Ok(raw)
+    }
+
+    async fn read_millivolts(&mut self, channel: u8) -> HardwareResult<u16> {
+        let raw = self.read_raw(channel).await? as u32;
+        Ok((raw * ADC_FULL_SCALE_MV / ADC_MAX_RAW) as u16)
+    }
+}
+
+/// Number of LEDC channels exposed as PWM outputs
+const PWM_CHANNELS: usize = 4;
+
+/// Default PWM output frequency
+const PWM_DEFAULT_FREQUENCY_HZ: u32 = 1_000;
+
+/// Highest frequency LEDC can generate at 10-bit resolution from the 80 MHz APB clock
+const PWM_MAX_FREQUENCY_HZ: u32 = 78_000;
+
+/// Maximum hardware duty value at 10-bit resolution
+const PWM_MAX_DUTY_HW: u32 = (1 << 10) - 1;
+
+/// ESP32-C3 PWM implementation
+/// 
+/// Drives LEDC low-speed channels 0-3 from a shared timer at 10-bit
+/// resolution. Channel numbers map to GPIO5, GPIO6, GPIO7 and GPIO10.
+/// Requested duty cycles are kept so a frequency change can re-apply them.
+/// 
+/// esp-hal ties LEDC channels to a borrowed timer, so the timer and channel
+/// drivers are rebuilt from reborrowed peripherals on every update; the
+/// hardware registers keep their state between calls.
+pub struct Esp32C3Pwm<'d> {
+    ledc: peripherals::LEDC<'d>,
+    pins: [AnyPin<'d>; PWM_CHANNELS],
+    duty: [u16; PWM_CHANNELS],
+    frequency_hz: u32,
+}
+
+impl<'d> Esp32C3Pwm<'d> {
+    fn new(ledc: peripherals::LEDC<'d>, pins: [AnyPin<'d>; PWM_CHANNELS]) -> Result<Self, IoTError> {
+        let mut pwm = Self {
+            ledc,
+            pins,
+            duty: [0; PWM_CHANNELS],
+            frequency_hz: PWM_DEFAULT_FREQUENCY_HZ,
+        };
+
+        for channel in 0..PWM_CHANNELS as u8 {
+            pwm.apply(channel)?;
+        }
+
+        Ok(pwm)
+    }
+
+    fn channel_number(channel: u8) -> HardwareResult<ledc_channel::Number> {
+        match channel {
+            0 => Ok(ledc_channel::Number::Channel0),
+            1 => Ok(ledc_channel::Number::Channel1),
+            2 => Ok(ledc_channel::Number::Channel2),
+            3 => Ok(ledc_channel::Number::Channel3),
+            _ => Err(PwmError::InvalidChannel(channel).into()),
+        }
+    }
+
+    /// Program the shared timer and one channel with the stored settings
+    fn apply(&mut self, channel: u8) -> HardwareResult<()> {
+        let number = Self::channel_number(channel)?;
+        let index = channel as usize;
+
+        let mut ledc = Ledc::new(self.ledc.reborrow());
+        ledc.set_global_slow_clock(LSGlobalClkSource::APBClk);
+
+        let mut timer = ledc.timer::<LowSpeed>(ledc_timer::Number::Timer0);
+        timer
+            .configure(ledc_timer::config::Config {
+                duty: ledc_timer::config::Duty::Duty10Bit,
+                clock_source: ledc_timer::LSClockSource::APBClk,
+                frequency: Rate::from_hz(self.frequency_hz),
+            })
+            .map_err(|_| PwmError::InvalidFrequency(self.frequency_hz))?;
+
+        let mut output = ledc.channel(number, self.pins[index].reborrow());
+        output
+            .configure(ledc_channel::config::Config {
+                timer: &timer,
+                duty_pct: 0,
+                pin_config: ledc_channel::config::PinConfig::PushPull,
+            })
+            .map_err(|_| PwmError::ConfigurationFailed("LEDC channel configuration failed"))?;
+
+        let duty_hw = self.duty[index] as u32 * PWM_MAX_DUTY_HW / u16::MAX as u32;
+        output.set_duty_hw(duty_hw);
+
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl<'d> PwmInterface for Esp32C3Pwm<'d> {
+    const CHANNELS: u8 = PWM_CHANNELS as u8;
+
+    async fn set_duty(&mut self, channel: u8, duty: u16) -> HardwareResult<()> {
+        Self::channel_number(channel)?;
+        self.duty[channel as usize] = duty;
+        self.apply(channel)
+    }
+
+    async fn set_frequency(&mut self, hz: u32) -> HardwareResult<()> {
+        if hz == 0 || hz > PWM_MAX_FREQUENCY_HZ {
+            return Err(PwmError::InvalidFrequency(hz).into());
+        }
+
+        self.frequency_hz = hz;
+        for channel in 0..Self::CHANNELS {
+            self.apply(channel)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Longest RWDT timeout accepted (one hour)
+const WATCHDOG_MAX_TIMEOUT_MS: u64 = 60 * 60 * 1000;
+
+/// GPIO used as sleep wake source, shared with the user button
+const WAKE_GPIO: u8 = crate::config::ESP32C3_BUTTON_GPIO;
+
+/// ESP32-C3 RTC control implementation
+/// 
+/// Owns the low-power management peripheral (LPWR). Provides the RTC
+/// watchdog (RWDT), which survives CPU hangs and resets the whole system
+/// when stage 0 expires, and light/deep sleep with the RTC wake timer.
+/// GPIO4 is reserved as the optional wake button since only GPIO0-5 can
+/// wake the chip from deep sleep; while awake the same pin serves as the
+/// user button input.
+pub struct Esp32C3Rtc<'d> {
+    rtc: Rtc<'d>,
+    watchdog_enabled: bool,
+    wake_pin: peripherals::GPIO4<'d>,
+    wake_level: Option<WakeLevel>,
+    button_active_high: bool,
+    button_pull: Pull,
+    button_debounce: Duration,
+}
+
+impl<'d> Esp32C3Rtc<'d> {
+    fn new(lpwr: peripherals::LPWR<'d>, wake_pin: peripherals::GPIO4<'d>, gpio: &GpioConfig) -> Self {
+        Self {
+            rtc: Rtc::new(lpwr),
+            watchdog_enabled: false,
+            wake_pin,
+            wake_level: None,
+            button_active_high: gpio.button_active_high,
+            button_pull: gpio.button_pull,
+            button_debounce: Duration::from_millis(gpio.button_debounce_ms as u64),
+        }
+    }
+
+    /// Configure the button pin as an input for one operation
+    /// 
+    /// The pin is borrowed rather than converted so it stays available as
+    /// the sleep wake source.
+    fn button_input(&mut self) -> Input<'_> {
+        let pull = match self.button_pull {
+            Pull::None => EspPull::None,
+            Pull::Up => EspPull::Up,
+            Pull::Down => EspPull::Down,
+        };
+        Input::new(self.wake_pin.reborrow(), InputConfig::default().with_pull(pull))
+    }
+
+    fn wake_timer(duration: Duration) -> HardwareResult<TimerWakeupSource> {
+        if duration.as_ticks() == 0 {
+            return Err(SleepError::InvalidDuration.into());
+        }
+        Ok(TimerWakeupSource::new(core::time::Duration::from_micros(duration.as_micros())))
+    }
+
+    fn wakeup_level(level: WakeLevel) -> WakeupLevel {
+        match level {
+            WakeLevel::Low => WakeupLevel::Low,
+            WakeLevel::High => WakeupLevel::High,
+        }
+    }
+}
+
+impl<'d> PowerInterface for Esp32C3Rtc<'d> {
+    fn light_sleep(&mut self, duration: Duration) -> HardwareResult<()> {
+        let timer = Self::wake_timer(duration)?;
+
+        match self.wake_level {
+            Some(level) => {
+                let mut pins: [(&mut dyn RtcPinWithResistors, WakeupLevel); 1] =
+                    [(&mut self.wake_pin, Self::wakeup_level(level))];
+                let gpio = RtcioWakeupSource::new(&mut pins);
+                self.rtc.sleep_light(&[&timer, &gpio]);
+            }
+            None => self.rtc.sleep_light(&[&timer]),
+        }
+
+        Ok(())
+    }
+
+    fn deep_sleep(&mut self, duration: Duration) -> HardwareResult<()> {
+        let timer = Self::wake_timer(duration)?;
+
+        match self.wake_level {
+            Some(level) => {
+                let mut pins: [(&mut dyn RtcPinWithResistors, WakeupLevel); 1] =
+                    [(&mut self.wake_pin, Self::wakeup_level(level))];
+                let gpio = RtcioWakeupSource::new(&mut pins);
+                self.rtc.sleep_deep(&[&timer, &gpio])
+            }
+            None => self.rtc.sleep_deep(&[&timer]),
+        }
+    }
+
+    fn enable_gpio_wakeup(&mut self, pin: u8, level: WakeLevel) -> HardwareResult<()> {
+        if pin != WAKE_GPIO {
+            return Err(SleepError::WakePinUnavailable(pin).into());
+        }
+
+        self.wake_level = Some(level);
+        Ok(())
+    }
+
+    fn disable_gpio_wakeup(&mut self) {
+        self.wake_level = None;
+    }
+}
+
+#[async_trait(?Send)]
+impl<'d> GpioInputInterface for Esp32C3Rtc<'d> {
+    async fn is_high(&mut self) -> HardwareResult<bool> {
+        let active_high = self.button_active_high;
+        Ok(self.button_input().is_high() == active_high)
+    }
+
+    async fn wait_for_edge(&mut self, edge: Edge) -> HardwareResult<()> {
+        // Logical edges are inverted on the pin for active-low buttons
+        let active_high = self.button_active_high;
+        let mut input = self.button_input();
+        match (edge, active_high) {
+            (Edge::Any, _) => input.wait_for_any_edge().await,
+            (Edge::Rising, true) | (Edge::Falling, false) => input.wait_for_rising_edge().await,
+            (Edge::Rising, false) | (Edge::Falling, true) => input.wait_for_falling_edge().await,
+        }
+        Ok(())
+    }
+
+    async fn wait_for_press(&mut self) -> HardwareResult<()> {
+        loop {
+            self.wait_for_edge(Edge::Rising).await?;
+            embassy_time::Timer::after(self.button_debounce).await;
+            if self.is_high().await? {
+                return Ok(());
+            }
+        }
+    }
+
+    fn set_debounce(&mut self, debounce: Duration) {
+        self.button_debounce = debounce;
+    }
+
+    fn debounce(&self) -> Duration {
+        self.button_debounce
+    }
+}
+
+impl<'d> WatchdogInterface for Esp32C3Rtc<'d> {
+    fn enable(&mut self, timeout: Duration) -> HardwareResult<()> {
+        let timeout_ms = timeout.as_millis();
+        if timeout_ms == 0 || timeout_ms > WATCHDOG_MAX_TIMEOUT_MS {
+            return Err(WatchdogError::InvalidTimeout.into());
+        }
+
+        self.rtc.rwdt.set_stage_action(RwdtStage::Stage0, RwdtStageAction::ResetSystem);
+        self.rtc.rwdt.set_timeout(RwdtStage::Stage0, esp_hal::time::Duration::from_millis(timeout_ms));
+        self.rtc.rwdt.enable();
+        self.rtc.rwdt.feed();
+        self.watchdog_enabled = true;
+
+        Ok(())
+    }
+
+    fn feed(&mut self) -> HardwareResult<()> {
+        if !self.watchdog_enabled {
+            return Err(WatchdogError::NotEnabled.into());
+        }
+
+        self.rtc.rwdt.feed();
+        Ok(())
+    }
+
+    fn disable(&mut self) -> HardwareResult<()> {
+        self.rtc.rwdt.disable();
+        self.watchdog_enabled = false;
+        Ok(())
+    }
+}
+
 /// ESP32-C3 timer implementation
 pub struct Esp32C3Timer {
     // Embassy timer doesn't need state
@@ -524,11 +1092,82 @@ impl WiFiInterface for Esp32C3WiFi {
 pub mod utils {
     use super::*;
 
+    /// Claim the SDA and SCL pins named in an I2C configuration
+    /// 
+    /// Only [`ESP32C3_I2C_GPIOS`](crate::config::ESP32C3_I2C_GPIOS) can carry
+    /// the bus; the pins not selected are dropped unused. Applications that
+    /// build their own bus use this to honour `HardwareConfig` too.
+    pub fn i2c_pins<'a>(
+        config: &crate::config::I2cConfig,
+        gpio8: peripherals::GPIO8<'a>,
+        gpio9: peripherals::GPIO9<'a>,
+        gpio18: peripherals::GPIO18<'a>,
+        gpio19: peripherals::GPIO19<'a>,
+    ) -> Result<(AnyPin<'a>, AnyPin<'a>), IoTError> {
+        let mut free_pins = [
+            (8, Some(AnyPin::from(gpio8))),
+            (9, Some(AnyPin::from(gpio9))),
+            (18, Some(AnyPin::from(gpio18))),
+            (19, Some(AnyPin::from(gpio19))),
+        ];
+        let mut take_pin = |number: u8| {
+            free_pins.iter_mut()
+                .find(|(pin, _)| *pin == number)
+                .and_then(|(_, pin)| pin.take())
+        };
+        let sda = take_pin(config.sda_pin)
+            .ok_or(PlatformError::InvalidConfiguration("I2C SDA must be GPIO8, 9, 18 or 19"))?;
+        let scl = take_pin(config.scl_pin)
+            .ok_or(PlatformError::InvalidConfiguration("I2C SCL must be GPIO8, 9, 18 or 19"))?;
+        Ok((sda, scl))
+    }
+
     /// Get ESP32-C3 chip information
     pub fn get_chip_info() -> &'static str {
         "ESP32-C3 RISC-V 160MHz WiFi SoC"
     }
 
+    /// Read chip revision, factory MAC and last reset reason from the hardware
+    /// 
+    /// Needs no peripherals, so it also works before the platform is
+    /// initialized or when initialization failed.
+    pub fn read_device_info() -> DeviceInfo {
+        use esp_hal::efuse::Efuse;
+        
+        DeviceInfo {
+            chip_model: "ESP32-C3",
+            chip_revision: Efuse::major_chip_version() as u16 * 100 + Efuse::minor_chip_version() as u16,
+            mac: Efuse::read_base_mac_address(),
+            firmware_version: crate::VERSION,
+            reset_reason: reset_reason(),
+        }
+    }
+
+    /// Cause of the last reset, from the RTC reset-reason register
+    pub fn reset_reason() -> ResetReason {
+        use esp_hal::rtc_cntl::SocResetReason;
+        
+        match esp_hal::rtc_cntl::reset_reason(esp_hal::system::Cpu::ProCpu) {
+            Some(SocResetReason::ChipPowerOn) => ResetReason::PowerOn,
+            Some(SocResetReason::CoreSw | SocResetReason::Cpu0Sw) => ResetReason::Software,
+            Some(
+                SocResetReason::CoreMwdt0
+                | SocResetReason::CoreMwdt1
+                | SocResetReason::CoreRtcWdt
+                | SocResetReason::Cpu0Mwdt0
+                | SocResetReason::Cpu0Mwdt1
+                | SocResetReason::Cpu0RtcWdt
+                | SocResetReason::SysRtcWdt
+                | SocResetReason::SysSuperWdt,
+            ) => ResetReason::Watchdog,
+            Some(SocResetReason::SysBrownOut) => ResetReason::Brownout,
+            Some(SocResetReason::CoreDeepSleep) => ResetReason::DeepSleep,
+            Some(SocResetReason::CoreUsbUart | SocResetReason::CoreUsbJtag) => ResetReason::Usb,
+            Some(_) => ResetReason::Other,
+            None => ResetReason::Unknown,
+        }
+    }
+
     /// Get available memory information
     pub fn get_memory_info() -> (usize, usize) {
         // In real implementation, would query actual memory usage
@@ -536,22 +1175,23 @@ pub mod utils {
     }
 
     /// Reset the ESP32-C3 system
+    /// 
+    /// Software reset of the whole chip, for callers without a platform
+    /// instance; prefer `HardwarePlatform::reset` where one is available.
     pub fn system_reset() -> ! {
-        // For now, use an infinite loop as placeholder
-        // In real implementation, would use esp_hal::reset::software_reset();
-        loop {}
+        esp_hal::system::software_reset()
     }
 
     /// Enter deep sleep mode
+    /// 
+    /// Placeholder that only delays; use `PowerInterface::deep_sleep`
+    /// through the platform for real deep sleep.
     pub async fn deep_sleep(duration: Duration) {
-        // In real implementation, would configure deep sleep
-        // For now, just delay
         embassy_time::Timer::after(duration).await;
     }
 
     /// Check if brownout detection triggered last reset
     pub fn was_brownout_reset() -> bool {
-        // In real implementation, would check reset reason
-        false
+        reset_reason() == ResetReason::Brownout
     }
 }
\ No newline at end of file
@@ -9,11 +9,16 @@
 //! ## Architecture Overview
 //!
 //! This HAL provides abstract interfaces for common IoT hardware components:
-//! - I2C buses for sensors
+//! - I2C buses for sensors, shareable between drivers through a mutex
 //! - UART interfaces for serial communication  
-//! - GPIO pins for status indicators
+//! - GPIO pins for status indicators, with configurable LED patterns per system condition
 //! - Timer functionality for delays
 //! - WiFi connectivity for networking
+//! - ADC channels for analog inputs
+//! - PWM outputs for fans, servos and dimmable indicators
+//! - Watchdog timer with a heartbeat-gated feeder
+//! - Light and deep sleep with timer and GPIO wake sources
+//! - Reset reason and post-crash diagnostics kept in RTC memory
 //!
 //! ## Platform Support
 //!
@@ -75,6 +80,9 @@
 //!     // Get timer for delays
 //!     let timer = platform.get_timer();
 //!     
+//!     // Get ADC for analog inputs
+//!     let adc = platform.get_adc();
+//!     
 //!     Ok(())
 //! }
 //! ```
@@ -94,22 +102,37 @@
 // Require alloc for async_trait Box usage and mock features
 extern crate alloc;
 
+// The mock platform runs on the host (tokio, std collections)
+#[cfg(feature = "mock")]
+extern crate std;
+
 // Core modules
 pub mod traits;
 pub mod config;
 pub mod error;
+pub mod watchdog;
+pub mod shared_i2c;
+pub mod status_led;
 
 // Platform implementations
 #[cfg(feature = "esp32c3")]
 pub mod esp32c3;
 
+#[cfg(feature = "esp32c3")]
+pub mod crash;
+
 #[cfg(feature = "mock")]
 pub mod mock;
 
 // Re-export core types
-pub use traits::{HardwarePlatform, I2cInterface, UartTxInterface, UartRxInterface, GpioInterface, TimerInterface, WiFiInterface, WiFiConnectionInfo};
-pub use config::{HardwareConfig, I2cConfig, UartConfig, WiFiConfig as HalWiFiConfig};
+pub use traits::{HardwarePlatform, I2cInterface, UartTxInterface, UartRxInterface, GpioInterface, TimerInterface, WiFiInterface, AdcInterface, PwmInterface, WatchdogInterface, PowerInterface, WakeLevel, GpioInputInterface, Edge, Pull, WiFiConnectionInfo, I2C_SCAN_FIRST_ADDRESS, I2C_SCAN_LAST_ADDRESS};
+pub use watchdog::{TaskHeartbeat, feed_if_healthy, feed_while_healthy};
+pub use shared_i2c::{SharedI2cBus, I2cBusMutex};
+pub use status_led::{StatusLed, LedPattern, LedPatternTable, LedCondition, LedStep};
+pub use config::{HardwareConfig, I2cConfig, UartConfig, ConsoleBackend, SUPPORTED_BAUD_RATES, WiFiConfig as HalWiFiConfig};
+pub use config::{I2C_MIN_FREQUENCY_HZ, I2C_MAX_FREQUENCY_HZ, I2C_MAX_TRANSACTION_TIMEOUT, ESP32C3_MAX_GPIO, ESP32C3_FLASH_GPIOS, ESP32C3_USB_GPIOS, ESP32C3_I2C_GPIOS, ESP32C3_BUTTON_GPIO};
 pub use error::{HardwareResult};
+pub use iot_common::{DeviceInfo, ResetReason};
 
 // Re-export platform implementations
 #[cfg(feature = "esp32c3")]
@@ -6,22 +6,41 @@
 #[cfg(feature = "mock")]
 use crate::{
     HardwarePlatform, I2cInterface, UartTxInterface, UartRxInterface, 
-    GpioInterface, TimerInterface, WiFiInterface, WiFiConnectionInfo,
-    HardwareConfig, error::*
+    GpioInterface, TimerInterface, WiFiInterface, AdcInterface, PwmInterface, WatchdogInterface, PowerInterface, WakeLevel,
+    GpioInputInterface, Edge, WiFiConnectionInfo,
+    HardwareConfig, I2cConfig, SharedI2cBus, I2cBusMutex, error::*
 };
 #[cfg(feature = "mock")]
-use iot_common::IoTError;
+use iot_common::HardwareError;
+#[cfg(feature = "mock")]
+use iot_common::bme280;
+#[cfg(feature = "mock")]
+use iot_common::{IoTError, DeviceInfo, ResetReason};
 #[cfg(feature = "mock")]
 use embassy_time::{Duration, Instant};
 #[cfg(feature = "mock")]
 use core::net::IpAddr;
 #[cfg(feature = "mock")]
-use alloc::{vec::Vec, collections::VecDeque, string::String, sync::Arc};
+use alloc::{vec::Vec, collections::{VecDeque, BTreeMap}, string::{String, ToString}, sync::Arc, boxed::Box};
 #[cfg(feature = "mock")]
-use core::sync::atomic::{AtomicBool, AtomicU8, AtomicI8, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicI8, Ordering};
 #[cfg(feature = "mock")]
 use async_trait::async_trait;
 
+/// Canned identity of a [`MockPlatform`] (locally administered MAC)
+#[cfg(feature = "mock")]
+pub const MOCK_DEVICE_INFO: DeviceInfo = DeviceInfo {
+    chip_model: "ESP32-C3",
+    chip_revision: 4,
+    mac: [0x02, 0x00, 0x00, 0xC3, 0x00, 0x01],
+    firmware_version: crate::VERSION,
+    reset_reason: ResetReason::PowerOn,
+};
+
+/// Die temperature a [`MockPlatform`] reports until told otherwise
+#[cfg(feature = "mock")]
+pub const MOCK_CHIP_TEMPERATURE: f32 = 40.0;
+
 #[cfg(feature = "mock")]
 /// Mock hardware platform for testing
 /// 
@@ -31,11 +50,16 @@ use async_trait::async_trait;
 /// 
 /// # Testing Capabilities
 /// 
-/// - **I2C Simulation**: Configurable device responses, error injection
+/// - **I2C Simulation**: Configurable device responses, BME280 register replay, error injection
 /// - **UART Simulation**: Bidirectional communication with buffering
 /// - **GPIO Simulation**: Pin state tracking and validation
 /// - **WiFi Simulation**: Network connection simulation with configurable behavior
 /// - **Timer Simulation**: Accelerated or real-time delay simulation
+/// - **ADC Simulation**: Programmable per-channel reading table
+/// - **PWM Simulation**: Last-set duty and frequency tracking
+/// - **Watchdog Simulation**: Feed interval tracking and timeout simulation
+/// - **Power Simulation**: Records requested sleeps and wake sources
+/// - **Button Simulation**: Injected level changes replayed as edges
 /// 
 /// # Error Injection
 /// 
@@ -68,8 +92,8 @@ use async_trait::async_trait;
 /// assert!(mock.gpio_ref().is_led_on());
 /// ```
 pub struct MockPlatform {
-    /// Mock I2C interface
-    i2c: MockI2c,
+    /// Mock I2C interface, behind a mutex like the real bus
+    i2c: I2cBusMutex<MockI2c>,
     
     /// Mock UART transmitter
     uart_tx: MockUartTx,
@@ -86,6 +110,30 @@ pub struct MockPlatform {
     /// Mock WiFi interface
     wifi: MockWiFi,
     
+    /// Mock ADC interface
+    adc: MockAdc,
+    
+    /// Mock PWM interface
+    pwm: MockPwm,
+    
+    /// Mock watchdog interface
+    watchdog: MockWatchdog,
+    
+    /// Mock power interface
+    power: MockPower,
+    
+    /// Mock button input
+    button: MockButton,
+    
+    /// Number of `reset()` calls since initialization
+    reset_count: u32,
+    
+    /// Identity returned by `device_info()`
+    device_info: DeviceInfo,
+    
+    /// Reading returned by `read_chip_temperature()`, `None` for a failed sensor
+    chip_temperature: Option<f32>,
+    
     /// Platform configuration
     config: HardwareConfig,
 }
@@ -99,6 +147,11 @@ impl HardwarePlatform for MockPlatform {
     type GpioPin = MockGpio;
     type Timer = MockTimer;
     type WiFi = MockWiFi;
+    type Adc = MockAdc;
+    type Pwm = MockPwm;
+    type Watchdog = MockWatchdog;
+    type Power = MockPower;
+    type Button = MockButton;
 
     async fn initialize() -> Result<Self, IoTError> {
         Self::initialize_with_config(HardwareConfig::default()).await
@@ -108,19 +161,15 @@ impl HardwarePlatform for MockPlatform {
         // Validate configuration
         config.validate().map_err(|e| PlatformError::InvalidConfiguration(e))?;
 
-        Ok(Self {
-            i2c: MockI2c::new(),
-            uart_tx: MockUartTx::new(),
-            uart_rx: MockUartRx::new(),
-            gpio: MockGpio::new(config.gpio.status_led_active_high),
-            timer: MockTimer::new(),
-            wifi: MockWiFi::new(),
-            config,
-        })
+        Ok(Self::from_config(config))
     }
 
     fn get_i2c(&mut self) -> &mut Self::I2cBus {
-        &mut self.i2c
+        self.i2c.get_mut()
+    }
+
+    fn get_shared_i2c(&self) -> SharedI2cBus<'_> {
+        SharedI2cBus::new(&self.i2c)
     }
 
     fn get_console(&mut self) -> (&mut Self::UartTx, &mut Self::UartRx) {
@@ -139,30 +188,105 @@ impl HardwarePlatform for MockPlatform {
         &mut self.wifi
     }
 
+    fn get_adc(&mut self) -> &mut Self::Adc {
+        &mut self.adc
+    }
+
+    fn get_pwm(&mut self) -> &mut Self::Pwm {
+        &mut self.pwm
+    }
+
+    fn get_watchdog(&mut self) -> &mut Self::Watchdog {
+        &mut self.watchdog
+    }
+
+    fn get_power(&mut self) -> &mut Self::Power {
+        &mut self.power
+    }
+
+    fn get_button(&mut self) -> &mut Self::Button {
+        &mut self.button
+    }
+
+    fn read_chip_temperature(&mut self) -> HardwareResult<f32> {
+        self.chip_temperature.ok_or_else(|| {
+            HardwareError::TemperatureSensorError(iot_common::error::utils::error_message("Mock sensor unavailable"))
+        })
+    }
+
     async fn is_healthy(&mut self) -> bool {
         // Mock platform is always healthy unless explicitly configured otherwise
-        self.i2c.is_healthy && 
+        self.i2c.get_mut().is_healthy && 
         self.uart_tx.is_healthy && 
         self.uart_rx.is_healthy && 
         self.gpio.is_healthy && 
-        self.wifi.is_healthy
+        self.wifi.is_healthy &&
+        self.adc.is_healthy &&
+        self.pwm.is_healthy
     }
 
     fn platform_info(&self) -> &'static str {
         "Mock Hardware Platform for Testing"
     }
+
+    fn device_info(&self) -> DeviceInfo {
+        self.device_info
+    }
+
+    async fn reset(&mut self) {
+        // Record the request instead of rebooting the test process
+        self.reset_count += 1;
+    }
+}
+
+#[cfg(feature = "mock")]
+impl Default for MockPlatform {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[cfg(feature = "mock")]
 impl MockPlatform {
-    /// Get immutable reference to I2C mock for verification
-    pub fn i2c_ref(&self) -> &MockI2c {
-        &self.i2c
+    /// Mock platform with the default configuration
+    /// 
+    /// Synchronous counterpart of [`initialize`](HardwarePlatform::initialize)
+    /// for tests that build a platform outside an async context.
+    pub fn new() -> Self {
+        Self::from_config(HardwareConfig::default())
+    }
+
+    /// Build the mocks for an already validated configuration
+    fn from_config(config: HardwareConfig) -> Self {
+        Self {
+            i2c: I2cBusMutex::new(MockI2c::new(&config.i2c)),
+            uart_tx: MockUartTx::new(),
+            uart_rx: MockUartRx::new(),
+            gpio: MockGpio::new(config.gpio.status_led_active_high),
+            timer: MockTimer::new(),
+            wifi: MockWiFi::new(),
+            adc: MockAdc::new(),
+            pwm: MockPwm::new(),
+            watchdog: MockWatchdog::new(),
+            power: MockPower::new(),
+            button: MockButton::new(Duration::from_millis(config.gpio.button_debounce_ms as u64)),
+            reset_count: 0,
+            device_info: MOCK_DEVICE_INFO,
+            chip_temperature: Some(MOCK_CHIP_TEMPERATURE),
+            config,
+        }
+    }
+
+    /// Get reference to I2C mock for verification
+    /// 
+    /// Takes `&mut self` because the bus sits behind the shared-bus mutex.
+    pub fn i2c_ref(&mut self) -> &MockI2c {
+        self.i2c.get_mut()
     }
 
     /// Get mutable reference to I2C mock for configuration
     pub fn i2c_mut(&mut self) -> &mut MockI2c {
-        &mut self.i2c
+        self.i2c.get_mut()
     }
 
     /// Get immutable reference to UART TX mock for verification
@@ -195,6 +319,16 @@ impl MockPlatform {
         &mut self.gpio
     }
 
+    /// Get mutable reference to timer mock for acceleration and verification
+    pub fn timer_mut(&mut self) -> &mut MockTimer {
+        &mut self.timer
+    }
+
+    /// Configuration the platform was initialized with
+    pub fn config(&self) -> &HardwareConfig {
+        &self.config
+    }
+
     /// Get immutable reference to WiFi mock for verification
     pub fn wifi_ref(&self) -> &MockWiFi {
         &self.wifi
@@ -205,26 +339,119 @@ impl MockPlatform {
         &mut self.wifi
     }
 
+    /// Get immutable reference to ADC mock for verification
+    pub fn adc_ref(&self) -> &MockAdc {
+        &self.adc
+    }
+
+    /// Get mutable reference to ADC mock for configuration
+    pub fn adc_mut(&mut self) -> &mut MockAdc {
+        &mut self.adc
+    }
+
+    /// Get immutable reference to PWM mock for verification
+    pub fn pwm_ref(&self) -> &MockPwm {
+        &self.pwm
+    }
+
+    /// Get mutable reference to PWM mock for configuration
+    pub fn pwm_mut(&mut self) -> &mut MockPwm {
+        &mut self.pwm
+    }
+
+    /// Get immutable reference to watchdog mock for verification
+    pub fn watchdog_ref(&self) -> &MockWatchdog {
+        &self.watchdog
+    }
+
+    /// Get mutable reference to watchdog mock for configuration
+    pub fn watchdog_mut(&mut self) -> &mut MockWatchdog {
+        &mut self.watchdog
+    }
+
+    /// Get immutable reference to power mock for verification
+    pub fn power_ref(&self) -> &MockPower {
+        &self.power
+    }
+
+    /// Get mutable reference to power mock for configuration
+    pub fn power_mut(&mut self) -> &mut MockPower {
+        &mut self.power
+    }
+
+    /// Get immutable reference to button mock for verification
+    pub fn button_ref(&self) -> &MockButton {
+        &self.button
+    }
+
+    /// Get mutable reference to button mock for configuration
+    pub fn button_mut(&mut self) -> &mut MockButton {
+        &mut self.button
+    }
+
+    /// Replace the identity returned by `device_info()`
+    pub fn set_device_info(&mut self, info: DeviceInfo) {
+        self.device_info = info;
+    }
+
+    /// Set the reading returned by `read_chip_temperature()`
+    /// 
+    /// `None` makes the sensor fail, as on a chip where it did not start.
+    pub fn set_chip_temperature(&mut self, celsius: Option<f32>) {
+        self.chip_temperature = celsius;
+    }
+
+    /// Check if `reset()` was called
+    pub fn reset_requested(&self) -> bool {
+        self.reset_count > 0
+    }
+
+    /// Get the number of `reset()` calls
+    pub fn reset_count(&self) -> u32 {
+        self.reset_count
+    }
+
     /// Reset all mock interfaces to default state
     pub fn reset_all(&mut self) {
-        self.i2c.reset();
+        self.i2c.get_mut().reset();
         self.uart_tx.reset();
         self.uart_rx.reset();
         self.gpio.reset();
         self.timer.reset();
         self.wifi.reset();
+        self.adc.reset();
+        self.pwm.reset();
+        self.watchdog.reset();
+        self.power.reset();
+        self.button.reset();
+        self.reset_count = 0;
+        self.chip_temperature = Some(MOCK_CHIP_TEMPERATURE);
     }
 
     /// Set global health status for all interfaces
     pub fn set_global_health(&mut self, healthy: bool) {
-        self.i2c.is_healthy = healthy;
+        self.i2c.get_mut().is_healthy = healthy;
         self.uart_tx.is_healthy = healthy;
         self.uart_rx.is_healthy = healthy;
         self.gpio.is_healthy = healthy;
         self.wifi.is_healthy = healthy;
+        self.adc.is_healthy = healthy;
+        self.pwm.is_healthy = healthy;
     }
 }
 
+#[cfg(feature = "mock")]
+/// BME280 chip ID register served by [`MockI2c::add_bme280`]
+pub const MOCK_BME280_CHIP_ID_REG: u8 = 0xD0;
+
+#[cfg(feature = "mock")]
+/// Chip ID answered by [`MockI2c::add_bme280`]
+pub const MOCK_BME280_CHIP_ID: u8 = 0x60;
+
+#[cfg(feature = "mock")]
+/// BME280 status register; the mock never reports a conversion in progress
+const MOCK_BME280_STATUS_REG: u8 = 0xF3;
+
 #[cfg(feature = "mock")]
 /// Mock I2C interface with configurable behavior
 #[derive(Debug)]
@@ -232,6 +459,9 @@ pub struct MockI2c {
     /// Device register maps (address -> registers)
     devices: std::collections::HashMap<u8, std::collections::HashMap<u8, u8>>,
     
+    /// Register pointer per device, set by the first byte of each write
+    register_pointers: std::collections::HashMap<u8, u8>,
+    
     /// Read history for verification
     read_history: Vec<(u8, usize)>, // (address, bytes_read)
     
@@ -243,20 +473,39 @@ pub struct MockI2c {
     
     /// Health status
     is_healthy: bool,
+    
+    /// Bus clock from the platform configuration
+    frequency_hz: u32,
+    
+    /// Per-transaction timeout from the platform configuration
+    transaction_timeout: Duration,
 }
 
 #[cfg(feature = "mock")]
 impl MockI2c {
-    fn new() -> Self {
+    fn new(config: &I2cConfig) -> Self {
         Self {
             devices: std::collections::HashMap::new(),
+            register_pointers: std::collections::HashMap::new(),
             read_history: Vec::new(),
             write_history: Vec::new(),
             next_error: None,
             is_healthy: true,
+            frequency_hz: config.frequency_hz,
+            transaction_timeout: config.transaction_timeout,
         }
     }
 
+    /// Bus clock the platform was configured with
+    pub fn frequency_hz(&self) -> u32 {
+        self.frequency_hz
+    }
+
+    /// Transaction timeout the platform was configured with
+    pub fn transaction_timeout(&self) -> Duration {
+        self.transaction_timeout
+    }
+
     /// Add a mock I2C device with register map
     pub fn add_device(&mut self, address: u8, registers: std::collections::HashMap<u8, u8>) {
         self.devices.insert(address, registers);
@@ -271,6 +520,47 @@ impl MockI2c {
         self.devices.insert(address, registers);
     }
 
+    /// Add a BME280 that replays a register image
+    /// 
+    /// The device answers the chip ID, reports no conversion in progress and
+    /// serves the image's calibration and raw data, so a driver running over
+    /// this bus computes the same measurements as `image.expected()`.
+    /// 
+    /// ```rust
+    /// use iot_common::bme280::DATASHEET_EXAMPLE;
+    /// 
+    /// mock.i2c_mut().add_bme280(0x76, &DATASHEET_EXAMPLE);
+    /// // Driver reads 25.08 °C, 1006.53 hPa, 49.43 %RH
+    /// ```
+    pub fn add_bme280(&mut self, address: u8, image: &bme280::RegisterImage) {
+        let mut registers = std::collections::HashMap::new();
+        registers.insert(MOCK_BME280_CHIP_ID_REG, MOCK_BME280_CHIP_ID);
+        registers.insert(MOCK_BME280_STATUS_REG, 0x00);
+        for (i, &byte) in image.calib_tp.iter().enumerate() {
+            registers.insert(bme280::CALIB_TP_START_REG + i as u8, byte);
+        }
+        registers.insert(bme280::CALIB_H1_REG, image.calib_h1);
+        for (i, &byte) in image.calib_h.iter().enumerate() {
+            registers.insert(bme280::CALIB_H_START_REG + i as u8, byte);
+        }
+        self.devices.insert(address, registers);
+        self.set_bme280_sample(address, image.sample);
+    }
+
+    /// Replace the raw measurement served by a BME280 added with `add_bme280`
+    pub fn set_bme280_sample(&mut self, address: u8, sample: bme280::RawSample) {
+        if let Some(registers) = self.devices.get_mut(&address) {
+            for (i, &byte) in sample.to_registers().iter().enumerate() {
+                registers.insert(bme280::DATA_START_REG + i as u8, byte);
+            }
+        }
+    }
+
+    /// Get a device register value
+    pub fn register(&self, address: u8, register: u8) -> Option<u8> {
+        self.devices.get(&address)?.get(&register).copied()
+    }
+
     /// Set the next operation to fail with specified error
     pub fn set_next_error(&mut self, error: IoTError) {
         self.next_error = Some(error);
@@ -295,6 +585,7 @@ impl MockI2c {
     /// Reset mock to initial state
     pub fn reset(&mut self) {
         self.devices.clear();
+        self.register_pointers.clear();
         self.clear_history();
         self.next_error = None;
         self.is_healthy = true;
@@ -317,9 +608,11 @@ impl I2cInterface for MockI2c {
         self.check_error()?;
 
         if let Some(device) = self.devices.get(&address) {
-            // Simple simulation: fill buffer with register values starting from 0
+            // Read from the register pointer (0 until a write sets it),
+            // auto-incrementing like most sensors
+            let start_reg = self.register_pointers.get(&address).copied().unwrap_or(0);
             for (i, byte) in buffer.iter_mut().enumerate() {
-                *byte = device.get(&(i as u8)).copied().unwrap_or(0);
+                *byte = device.get(&start_reg.wrapping_add(i as u8)).copied().unwrap_or(0);
             }
             self.read_history.push((address, buffer.len()));
             Ok(())
@@ -333,6 +626,9 @@ impl I2cInterface for MockI2c {
 
         if self.devices.contains_key(&address) {
             self.write_history.push((address, data.to_vec()));
+            if let Some(&reg_addr) = data.first() {
+                self.register_pointers.insert(address, reg_addr);
+            }
             
             // Update device registers if write contains register address
             if data.len() >= 2 {
@@ -360,7 +656,7 @@ impl I2cInterface for MockI2c {
             let start_reg = write_data[0];
             
             for (i, byte) in read_buffer.iter_mut().enumerate() {
-                *byte = device.get(&(start_reg + i as u8)).copied().unwrap_or(0);
+                *byte = device.get(&start_reg.wrapping_add(i as u8)).copied().unwrap_or(0);
             }
             
             // Don't double-count in read_history since write already recorded
@@ -654,6 +950,9 @@ pub struct MockTimer {
     
     /// Time acceleration factor (1.0 = real time, >1.0 = faster)
     acceleration_factor: f32,
+    
+    /// Requested delays, before acceleration
+    delay_history: Vec<Duration>,
 }
 
 #[cfg(feature = "mock")]
@@ -662,6 +961,7 @@ impl MockTimer {
         Self {
             start_time: Instant::now(),
             acceleration_factor: 1.0,
+            delay_history: Vec::new(),
         }
     }
 
@@ -672,16 +972,28 @@ impl MockTimer {
         self.acceleration_factor = factor;
     }
 
+    /// Get requested delays, before acceleration
+    pub fn delay_history(&self) -> &[Duration] {
+        &self.delay_history
+    }
+
+    /// Clear delay history
+    pub fn clear_history(&mut self) {
+        self.delay_history.clear();
+    }
+
     /// Reset timer to initial state
     pub fn reset(&mut self) {
         self.start_time = Instant::now();
         self.acceleration_factor = 1.0;
+        self.delay_history.clear();
     }
 }
 
 #[cfg(feature = "mock")]
 impl TimerInterface for MockTimer {
     async fn delay(&mut self, duration: Duration) {
+        self.delay_history.push(duration);
         let accelerated_duration = Duration::from_millis(
             (duration.as_millis() as f32 / self.acceleration_factor) as u64
         );
@@ -847,4 +1159,644 @@ impl WiFiInterface for MockWiFi {
     fn get_connection_info(&self) -> Option<WiFiConnectionInfo> {
         self.connection_info.lock().unwrap().clone()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "mock")]
+/// Mock ADC interface with a programmable reading table
+/// 
+/// Each channel returns the raw value stored for it; channels without an
+/// entry fail with `AdcError::InvalidChannel`. Millivolt readings use the
+/// same linear conversion as the ESP32-C3 implementation (12-bit,
+/// 0-2500 mV) unless a full-scale voltage is configured.
+#[derive(Debug)]
+pub struct MockAdc {
+    /// Raw readings per channel
+    readings: BTreeMap<u8, u16>,
+    
+    /// Full-scale input voltage in millivolts
+    full_scale_mv: u16,
+    
+    /// Read history for verification (channel numbers)
+    read_history: Vec<u8>,
+    
+    /// Next error to inject
+    next_error: Option<HardwareError>,
+    
+    /// Health status
+    is_healthy: bool,
+}
+
+#[cfg(feature = "mock")]
+impl MockAdc {
+    /// Maximum raw value of the simulated 12-bit converter
+    pub const MAX_RAW: u16 = 4095;
+
+    fn new() -> Self {
+        Self {
+            readings: BTreeMap::new(),
+            full_scale_mv: 2500,
+            read_history: Vec::new(),
+            next_error: None,
+            is_healthy: true,
+        }
+    }
+
+    /// Set the raw value returned for a channel
+    pub fn set_raw(&mut self, channel: u8, raw: u16) {
+        self.readings.insert(channel, raw.min(Self::MAX_RAW));
+    }
+
+    /// Set the reading for a channel from a voltage in millivolts
+    pub fn set_millivolts(&mut self, channel: u8, millivolts: u16) {
+        let raw = millivolts as u32 * Self::MAX_RAW as u32 / self.full_scale_mv as u32;
+        self.set_raw(channel, raw.min(Self::MAX_RAW as u32) as u16);
+    }
+
+    /// Load several channel readings at once
+    pub fn set_readings(&mut self, readings: &[(u8, u16)]) {
+        for &(channel, raw) in readings {
+            self.set_raw(channel, raw);
+        }
+    }
+
+    /// Remove a channel from the reading table
+    pub fn remove_channel(&mut self, channel: u8) {
+        self.readings.remove(&channel);
+    }
+
+    /// Set full-scale voltage used for millivolt conversion
+    pub fn set_full_scale_mv(&mut self, full_scale_mv: u16) {
+        self.full_scale_mv = full_scale_mv;
+    }
+
+    /// Get read operation history
+    pub fn read_history(&self) -> &[u8] {
+        &self.read_history
+    }
+
+    /// Clear operation history
+    pub fn clear_history(&mut self) {
+        self.read_history.clear();
+    }
+
+    /// Set next operation to fail
+    pub fn set_next_error(&mut self, error: HardwareError) {
+        self.next_error = Some(error);
+    }
+
+    /// Reset mock to initial state
+    pub fn reset(&mut self) {
+        self.readings.clear();
+        self.full_scale_mv = 2500;
+        self.clear_history();
+        self.next_error = None;
+        self.is_healthy = true;
+    }
+
+    fn check_error(&mut self) -> HardwareResult<()> {
+        if let Some(error) = self.next_error.take() {
+            Err(error)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "mock")]
+#[async_trait(?Send)]
+impl AdcInterface for MockAdc {
+    async fn read_raw(&mut self, channel: u8) -> HardwareResult<u16> {
+        self.check_error()?;
+
+        let raw = self.readings
+            .get(&channel)
+            .copied()
+            .ok_or(AdcError::InvalidChannel(channel))?;
+        self.read_history.push(channel);
+        Ok(raw)
+    }
+
+    async fn read_millivolts(&mut self, channel: u8) -> HardwareResult<u16> {
+        let raw = self.read_raw(channel).await? as u32;
+        Ok((raw * self.full_scale_mv as u32 / Self::MAX_RAW as u32) as u16)
+    }
+}
+
+#[cfg(feature = "mock")]
+/// Mock PWM interface recording the last-set duty per channel
+/// 
+/// The channel count is a const parameter so tests can model boards with
+/// different numbers of PWM outputs; `MockPlatform` exposes four.
+#[derive(Debug)]
+pub struct MockPwm<const CHANNELS: usize = 4> {
+    /// Last duty set per channel
+    duty: [Option<u16>; CHANNELS],
+    
+    /// Current output frequency
+    frequency_hz: u32,
+    
+    /// Duty history for verification (channel, duty)
+    duty_history: Vec<(u8, u16)>,
+    
+    /// Next error to inject
+    next_error: Option<HardwareError>,
+    
+    /// Health status
+    is_healthy: bool,
+}
+
+#[cfg(feature = "mock")]
+impl<const CHANNELS: usize> MockPwm<CHANNELS> {
+    /// Frequency reported before `set_frequency` is called
+    pub const DEFAULT_FREQUENCY_HZ: u32 = 1_000;
+
+    /// Create a mock with all channels unset
+    pub fn new() -> Self {
+        Self {
+            duty: [None; CHANNELS],
+            frequency_hz: Self::DEFAULT_FREQUENCY_HZ,
+            duty_history: Vec::new(),
+            next_error: None,
+            is_healthy: true,
+        }
+    }
+
+    /// Get last duty set on a channel, if any
+    pub fn last_duty(&self, channel: u8) -> Option<u16> {
+        self.duty.get(channel as usize).copied().flatten()
+    }
+
+    /// Get current output frequency
+    pub fn frequency(&self) -> u32 {
+        self.frequency_hz
+    }
+
+    /// Get duty history
+    pub fn duty_history(&self) -> &[(u8, u16)] {
+        &self.duty_history
+    }
+
+    /// Clear operation history
+    pub fn clear_history(&mut self) {
+        self.duty_history.clear();
+    }
+
+    /// Set next operation to fail
+    pub fn set_next_error(&mut self, error: HardwareError) {
+        self.next_error = Some(error);
+    }
+
+    /// Reset mock to initial state
+    pub fn reset(&mut self) {
+        self.duty = [None; CHANNELS];
+        self.frequency_hz = Self::DEFAULT_FREQUENCY_HZ;
+        self.clear_history();
+        self.next_error = None;
+        self.is_healthy = true;
+    }
+
+    fn check_error(&mut self) -> HardwareResult<()> {
+        if let Some(error) = self.next_error.take() {
+            Err(error)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "mock")]
+impl<const CHANNELS: usize> Default for MockPwm<CHANNELS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "mock")]
+#[async_trait(?Send)]
+impl<const CHANNELS: usize> PwmInterface for MockPwm<CHANNELS> {
+    const CHANNELS: u8 = CHANNELS as u8;
+
+    async fn set_duty(&mut self, channel: u8, duty: u16) -> HardwareResult<()> {
+        self.check_error()?;
+
+        let slot = self.duty
+            .get_mut(channel as usize)
+            .ok_or(PwmError::InvalidChannel(channel))?;
+        *slot = Some(duty);
+        self.duty_history.push((channel, duty));
+        Ok(())
+    }
+
+    async fn set_frequency(&mut self, hz: u32) -> HardwareResult<()> {
+        self.check_error()?;
+
+        if hz == 0 {
+            return Err(PwmError::InvalidFrequency(hz).into());
+        }
+        self.frequency_hz = hz;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "mock")]
+/// Mock watchdog recording feed times
+/// 
+/// Tests can inspect the intervals between feeds and simulate expiry,
+/// either explicitly or by checking elapsed time against the timeout.
+#[derive(Debug)]
+pub struct MockWatchdog {
+    /// Configured timeout while enabled
+    timeout: Option<Duration>,
+    
+    /// Time of each feed (including the implicit feed on enable)
+    feed_times: Vec<Instant>,
+    
+    /// Set once the simulated watchdog has expired
+    timed_out: bool,
+    
+    /// Next error to inject
+    next_error: Option<HardwareError>,
+}
+
+#[cfg(feature = "mock")]
+impl MockWatchdog {
+    fn new() -> Self {
+        Self {
+            timeout: None,
+            feed_times: Vec::new(),
+            timed_out: false,
+            next_error: None,
+        }
+    }
+
+    /// Check if watchdog is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.timeout.is_some()
+    }
+
+    /// Get configured timeout
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Get number of feeds since enable
+    pub fn feed_count(&self) -> usize {
+        self.feed_times.len()
+    }
+
+    /// Get intervals between consecutive feeds
+    pub fn feed_intervals(&self) -> Vec<Duration> {
+        self.feed_times
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .collect()
+    }
+
+    /// Get longest interval between consecutive feeds
+    pub fn max_feed_interval(&self) -> Option<Duration> {
+        self.feed_intervals().into_iter().max()
+    }
+
+    /// Expire the watchdog if `now` is past the timeout since the last feed
+    /// 
+    /// Returns `true` if the watchdog has expired.
+    pub fn check_timeout(&mut self, now: Instant) -> bool {
+        if let (Some(timeout), Some(&last_feed)) = (self.timeout, self.feed_times.last()) {
+            if now > last_feed + timeout {
+                self.timed_out = true;
+            }
+        }
+        self.timed_out
+    }
+
+    /// Force the watchdog to expire
+    pub fn simulate_timeout(&mut self) {
+        self.timed_out = true;
+    }
+
+    /// Check if the watchdog has expired (a real device would have reset)
+    pub fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+
+    /// Set next operation to fail
+    pub fn set_next_error(&mut self, error: HardwareError) {
+        self.next_error = Some(error);
+    }
+
+    /// Reset mock to initial state
+    pub fn reset(&mut self) {
+        self.timeout = None;
+        self.feed_times.clear();
+        self.timed_out = false;
+        self.next_error = None;
+    }
+
+    fn check_error(&mut self) -> HardwareResult<()> {
+        if let Some(error) = self.next_error.take() {
+            Err(error)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "mock")]
+impl WatchdogInterface for MockWatchdog {
+    fn enable(&mut self, timeout: Duration) -> HardwareResult<()> {
+        self.check_error()?;
+
+        if timeout.as_ticks() == 0 {
+            return Err(WatchdogError::InvalidTimeout.into());
+        }
+        self.timeout = Some(timeout);
+        self.timed_out = false;
+        self.feed_times.clear();
+        self.feed_times.push(Instant::now());
+        Ok(())
+    }
+
+    fn feed(&mut self) -> HardwareResult<()> {
+        self.check_error()?;
+
+        if self.timed_out {
+            return Err(WatchdogError::Expired.into());
+        }
+        if self.timeout.is_none() {
+            return Err(WatchdogError::NotEnabled.into());
+        }
+        self.feed_times.push(Instant::now());
+        Ok(())
+    }
+
+    fn disable(&mut self) -> HardwareResult<()> {
+        self.check_error()?;
+
+        self.timeout = None;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "mock")]
+/// Kind of sleep requested from the mock power interface
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SleepKind {
+    /// Light sleep (RAM retained)
+    Light,
+    /// Deep sleep (reset on wake)
+    Deep,
+}
+
+#[cfg(feature = "mock")]
+/// Mock power interface recording requested sleeps
+/// 
+/// Sleeps return immediately; deep sleep returns `Ok(())` instead of
+/// resetting so tests can assert what the application requested.
+#[derive(Debug)]
+pub struct MockPower {
+    /// Requested sleeps in order
+    sleep_history: Vec<(SleepKind, Duration)>,
+    
+    /// Armed GPIO wake source
+    gpio_wakeup: Option<(u8, WakeLevel)>,
+    
+    /// Next error to inject
+    next_error: Option<HardwareError>,
+}
+
+#[cfg(feature = "mock")]
+impl MockPower {
+    fn new() -> Self {
+        Self {
+            sleep_history: Vec::new(),
+            gpio_wakeup: None,
+            next_error: None,
+        }
+    }
+
+    /// Get requested sleeps
+    pub fn sleep_history(&self) -> &[(SleepKind, Duration)] {
+        &self.sleep_history
+    }
+
+    /// Get most recent sleep request
+    pub fn last_sleep(&self) -> Option<(SleepKind, Duration)> {
+        self.sleep_history.last().copied()
+    }
+
+    /// Get armed GPIO wake source
+    pub fn gpio_wakeup(&self) -> Option<(u8, WakeLevel)> {
+        self.gpio_wakeup
+    }
+
+    /// Set next operation to fail
+    pub fn set_next_error(&mut self, error: HardwareError) {
+        self.next_error = Some(error);
+    }
+
+    /// Reset mock to initial state
+    pub fn reset(&mut self) {
+        self.sleep_history.clear();
+        self.gpio_wakeup = None;
+        self.next_error = None;
+    }
+
+    fn record_sleep(&mut self, kind: SleepKind, duration: Duration) -> HardwareResult<()> {
+        if let Some(error) = self.next_error.take() {
+            return Err(error);
+        }
+        if duration.as_ticks() == 0 {
+            return Err(SleepError::InvalidDuration.into());
+        }
+        self.sleep_history.push((kind, duration));
+        Ok(())
+    }
+}
+
+#[cfg(feature = "mock")]
+impl PowerInterface for MockPower {
+    fn light_sleep(&mut self, duration: Duration) -> HardwareResult<()> {
+        self.record_sleep(SleepKind::Light, duration)
+    }
+
+    fn deep_sleep(&mut self, duration: Duration) -> HardwareResult<()> {
+        self.record_sleep(SleepKind::Deep, duration)
+    }
+
+    fn enable_gpio_wakeup(&mut self, pin: u8, level: WakeLevel) -> HardwareResult<()> {
+        if let Some(error) = self.next_error.take() {
+            return Err(error);
+        }
+        self.gpio_wakeup = Some((pin, level));
+        Ok(())
+    }
+
+    fn disable_gpio_wakeup(&mut self) {
+        self.gpio_wakeup = None;
+    }
+}
+
+#[cfg(feature = "mock")]
+/// Mock button input driven by injected level changes
+/// 
+/// Tests queue logical levels, each with the time it is held before the
+/// next change; every wait consumes queued levels until the requested
+/// transition occurs. Waits return an error once the queue is exhausted
+/// instead of blocking forever. `wait_for_press` only accepts presses held
+/// at least for the debounce period, so short pulses model contact bounce.
+/// 
+/// ```rust
+/// let button = mock.button_mut();
+/// button.inject_pulse(Duration::from_millis(2)); // bounce, ignored
+/// button.press();
+/// button.release();
+/// mock.get_button().wait_for_press().await?;
+/// assert_eq!(mock.button_ref().press_count(), 1);
+/// ```
+#[derive(Debug)]
+pub struct MockButton {
+    /// Current logical level
+    level: bool,
+    
+    /// How long the current level is held
+    hold: Duration,
+    
+    /// Pending (level, hold time) changes, oldest first
+    pending: VecDeque<(bool, Duration)>,
+    
+    /// Debounce period applied by `wait_for_press`
+    debounce: Duration,
+    
+    /// Number of debounced presses detected
+    press_count: u32,
+    
+    /// Next error to inject
+    next_error: Option<HardwareError>,
+}
+
+#[cfg(feature = "mock")]
+impl MockButton {
+    fn new(debounce: Duration) -> Self {
+        Self {
+            level: false,
+            hold: Duration::MAX,
+            pending: VecDeque::new(),
+            debounce,
+            press_count: 0,
+            next_error: None,
+        }
+    }
+
+    /// Queue a logical level change held for `hold`
+    pub fn inject_level(&mut self, active: bool, hold: Duration) {
+        self.pending.push_back((active, hold));
+    }
+
+    /// Queue a stable press
+    pub fn press(&mut self) {
+        self.inject_level(true, Duration::MAX);
+    }
+
+    /// Queue a release
+    pub fn release(&mut self) {
+        self.inject_level(false, Duration::MAX);
+    }
+
+    /// Queue an active pulse of `width` followed by a release
+    pub fn inject_pulse(&mut self, width: Duration) {
+        self.inject_level(true, width);
+        self.release();
+    }
+
+    /// Set the current level without producing an edge
+    pub fn set_level(&mut self, active: bool) {
+        self.level = active;
+        self.hold = Duration::MAX;
+    }
+
+    /// Get number of pending level changes
+    pub fn pending_edges(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Get number of debounced presses detected
+    pub fn press_count(&self) -> u32 {
+        self.press_count
+    }
+
+    /// Set next operation to fail
+    pub fn set_next_error(&mut self, error: HardwareError) {
+        self.next_error = Some(error);
+    }
+
+    /// Reset mock to initial state
+    pub fn reset(&mut self) {
+        self.level = false;
+        self.hold = Duration::MAX;
+        self.pending.clear();
+        self.press_count = 0;
+        self.next_error = None;
+    }
+
+    fn check_error(&mut self) -> HardwareResult<()> {
+        if let Some(error) = self.next_error.take() {
+            Err(error)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Apply the next queued level, returning whether it changed the input
+    fn step(&mut self) -> HardwareResult<bool> {
+        let (level, hold) = self.pending.pop_front()
+            .ok_or(GpioError::HardwareFault("No button edge injected"))?;
+        let changed = level != self.level;
+        self.level = level;
+        self.hold = hold;
+        Ok(changed)
+    }
+}
+
+#[cfg(feature = "mock")]
+#[async_trait(?Send)]
+impl GpioInputInterface for MockButton {
+    async fn is_high(&mut self) -> HardwareResult<bool> {
+        self.check_error()?;
+        Ok(self.level)
+    }
+
+    async fn wait_for_edge(&mut self, edge: Edge) -> HardwareResult<()> {
+        self.check_error()?;
+
+        loop {
+            if self.step()? {
+                let matches = match edge {
+                    Edge::Rising => self.level,
+                    Edge::Falling => !self.level,
+                    Edge::Any => true,
+                };
+                if matches {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    async fn wait_for_press(&mut self) -> HardwareResult<()> {
+        loop {
+            self.wait_for_edge(Edge::Rising).await?;
+            if self.hold >= self.debounce {
+                self.press_count += 1;
+                return Ok(());
+            }
+        }
+    }
+
+    fn set_debounce(&mut self, debounce: Duration) {
+        self.debounce = debounce;
+    }
+
+    fn debounce(&self) -> Duration {
+        self.debounce
+    }
+}
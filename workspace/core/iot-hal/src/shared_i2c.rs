@@ -0,0 +1,89 @@
+//! # Shared I2C Bus
+//!
+//! A weather station rarely has a single I2C device: a BME280, an air-quality
+//! sensor and an OLED typically hang off the same two wires. The platform owns
+//! the one I2C peripheral, so [`SharedI2cBus`] hands out cheap, copyable
+//! handles to a mutex-guarded bus instead, one per driver:
+//!
+//! ```rust
+//! let platform: &'static Esp32C3Platform = PLATFORM.init(Esp32C3Platform::initialize().await?);
+//! let bus = platform.get_shared_i2c();
+//!
+//! let mut bme280 = BME280::new(I2cDevice::shared(bus, 0x76));
+//! let mut display = Ssd1306::new(bus, 0x3C);
+//! ```
+//!
+//! ## Locking Discipline
+//!
+//! - Every [`I2cInterface`] call on a handle locks the bus for exactly that
+//!   one transfer, so drivers in different tasks interleave at transfer
+//!   granularity and never corrupt each other's START/STOP framing.
+//! - A driver that needs several transfers to happen back to back (e.g.
+//!   trigger a conversion, then read the result before anyone else touches
+//!   the device) takes the bus with [`SharedI2cBus::lock`] and keeps the guard
+//!   for the sequence. Do not call the handle's own methods while holding the
+//!   guard: the mutex is not reentrant and the task deadlocks.
+//! - The mutex is async, so waiting for the bus yields to other tasks, but a
+//!   transfer itself runs to completion. Long transactions (large display
+//!   frame buffers, EEPROM pages) and guards held across `Timer::after` delay
+//!   every other device on the bus by that long. Keep guards short and never
+//!   hold one across a sensor's conversion time.
+//! - Exclusive `&mut` access through `HardwarePlatform::get_i2c` remains for
+//!   single-device setups; it requires `&mut` to the platform and therefore
+//!   cannot overlap with outstanding shared handles.
+
+use async_trait::async_trait;
+use alloc::boxed::Box;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::mutex::{Mutex, MutexGuard};
+use iot_common::IoTError;
+
+use crate::traits::I2cInterface;
+
+/// Mutex guarding an I2C bus shared between drivers
+pub type I2cBusMutex<B> = Mutex<CriticalSectionRawMutex, B>;
+
+/// Copyable handle to a mutex-guarded I2C bus
+///
+/// Implements [`I2cInterface`] itself, locking the bus for each transfer,
+/// so it can be passed anywhere a bus is expected.
+#[derive(Clone, Copy)]
+pub struct SharedI2cBus<'a> {
+    bus: &'a I2cBusMutex<dyn I2cInterface + 'a>,
+}
+
+impl<'a> SharedI2cBus<'a> {
+    /// Create a handle to `bus`
+    ///
+    /// Any `&I2cBusMutex<B>` with `B: I2cInterface` coerces to the argument.
+    pub fn new(bus: &'a I2cBusMutex<dyn I2cInterface + 'a>) -> Self {
+        Self { bus }
+    }
+
+    /// Take exclusive access for a multi-transfer sequence
+    ///
+    /// Other devices wait until the guard is dropped; see the module docs.
+    pub async fn lock(&self) -> MutexGuard<'a, CriticalSectionRawMutex, dyn I2cInterface + 'a> {
+        self.bus.lock().await
+    }
+}
+
+#[async_trait(?Send)]
+impl I2cInterface for SharedI2cBus<'_> {
+    async fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), IoTError> {
+        self.bus.lock().await.read(address, buffer).await
+    }
+
+    async fn write(&mut self, address: u8, data: &[u8]) -> Result<(), IoTError> {
+        self.bus.lock().await.write(address, data).await
+    }
+
+    async fn write_read(&mut self, address: u8, write_data: &[u8], read_buffer: &mut [u8]) -> Result<(), IoTError> {
+        self.bus.lock().await.write_read(address, write_data, read_buffer).await
+    }
+
+    async fn scan(&mut self) -> heapless::Vec<u8, 128> {
+        // One lock for the whole scan instead of 112
+        self.bus.lock().await.scan().await
+    }
+}
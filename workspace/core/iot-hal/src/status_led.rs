@@ -0,0 +1,279 @@
+//! # Status LED Patterns
+//!
+//! The status LED is the only indicator on a headless node, so each system
+//! condition gets a pattern that can be told apart by sight. A
+//! [`LedPatternTable`] in [`GpioConfig`](crate::config::GpioConfig) maps every
+//! [`LedCondition`] to an [`LedPattern`]; [`StatusLed`] tracks the current
+//! condition and plays one period of its pattern at a time through the
+//! platform's [`GpioInterface`], or its [`PwmInterface`] when the LED is wired
+//! to a PWM output.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use iot_hal::{StatusLed, LedCondition};
+//!
+//! let mut status_led = StatusLed::new(platform.config().gpio.status_led_patterns.clone());
+//! loop {
+//!     let condition = LedCondition::from_flags(sensor_active, wifi_connected, mqtt_connected);
+//!     if status_led.set_condition(condition) {
+//!         rprintln!("[STATUS-LED] {} - {}", condition.as_str(), status_led.pattern().as_str());
+//!     }
+//!     status_led.play_cycle(&mut platform).await?;
+//! }
+//! ```
+
+use embassy_time::Duration;
+use iot_common::IoTError;
+
+use crate::traits::{HardwarePlatform, GpioInterface, PwmInterface, TimerInterface};
+
+/// Duty steps per half period of [`LedPattern::Breathe`]
+const BREATHE_STEPS: u32 = 20;
+
+/// Length of one [`LedPattern::Breathe`] period in milliseconds
+const BREATHE_PERIOD_MS: u32 = 2_000;
+
+/// One segment of a pattern: the LED held on or off for a while
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LedStep {
+    /// LED lit during this step
+    pub on: bool,
+    /// Step length in milliseconds
+    pub duration_ms: u32,
+}
+
+impl LedStep {
+    const fn on(duration_ms: u32) -> Self {
+        Self { on: true, duration_ms }
+    }
+
+    const fn off(duration_ms: u32) -> Self {
+        Self { on: false, duration_ms }
+    }
+}
+
+/// Visual pattern shown on the status LED
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedPattern {
+    /// LED dark
+    Off,
+    /// LED lit continuously
+    Solid,
+    /// 1 Hz blink
+    SlowBlink,
+    /// 2 Hz blink
+    FastBlink,
+    /// 4 Hz blink
+    VeryFastBlink,
+    /// Two short flashes, then a pause
+    DoubleBlink,
+    /// Smooth fade in and out; needs a PWM output, blinks slowly otherwise
+    Breathe,
+}
+
+impl LedPattern {
+    /// Every pattern
+    pub const ALL: [LedPattern; 7] = [
+        LedPattern::Off,
+        LedPattern::Solid,
+        LedPattern::SlowBlink,
+        LedPattern::FastBlink,
+        LedPattern::VeryFastBlink,
+        LedPattern::DoubleBlink,
+        LedPattern::Breathe,
+    ];
+
+    /// Returns the pattern as a string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LedPattern::Off => "off",
+            LedPattern::Solid => "solid",
+            LedPattern::SlowBlink => "slow blink",
+            LedPattern::FastBlink => "fast blink",
+            LedPattern::VeryFastBlink => "very fast blink",
+            LedPattern::DoubleBlink => "double blink",
+            LedPattern::Breathe => "breathe",
+        }
+    }
+
+    /// On/off steps of one period
+    ///
+    /// [`Breathe`](LedPattern::Breathe) returns the slow blink it falls back
+    /// to on a plain GPIO; on PWM it is ramped by [`StatusLed::play_cycle`].
+    pub const fn steps(&self) -> &'static [LedStep] {
+        const OFF: [LedStep; 1] = [LedStep::off(1_000)];
+        const SOLID: [LedStep; 1] = [LedStep::on(1_000)];
+        const SLOW_BLINK: [LedStep; 2] = [LedStep::on(500), LedStep::off(500)];
+        const FAST_BLINK: [LedStep; 2] = [LedStep::on(250), LedStep::off(250)];
+        const VERY_FAST_BLINK: [LedStep; 2] = [LedStep::on(125), LedStep::off(125)];
+        const DOUBLE_BLINK: [LedStep; 4] = [LedStep::on(100), LedStep::off(150), LedStep::on(100), LedStep::off(650)];
+
+        match self {
+            LedPattern::Off => &OFF,
+            LedPattern::Solid => &SOLID,
+            LedPattern::SlowBlink | LedPattern::Breathe => &SLOW_BLINK,
+            LedPattern::FastBlink => &FAST_BLINK,
+            LedPattern::VeryFastBlink => &VERY_FAST_BLINK,
+            LedPattern::DoubleBlink => &DOUBLE_BLINK,
+        }
+    }
+}
+
+/// System condition shown on the status LED
+///
+/// Conditions are ordered by severity; a sensor fault hides network problems
+/// because nothing useful can be published without readings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedCondition {
+    /// Sensor not reading
+    SensorFault,
+    /// Sensor reading, WiFi not (yet) connected
+    WiFiConnecting,
+    /// WiFi connected, MQTT broker not reachable
+    MqttFailing,
+    /// Sensor, WiFi and MQTT all working
+    Operational,
+}
+
+impl LedCondition {
+    /// Derives the condition from the task status flags
+    pub const fn from_flags(sensor_active: bool, wifi_connected: bool, mqtt_connected: bool) -> Self {
+        match (sensor_active, wifi_connected, mqtt_connected) {
+            (false, _, _) => LedCondition::SensorFault,
+            (true, false, _) => LedCondition::WiFiConnecting,
+            (true, true, false) => LedCondition::MqttFailing,
+            (true, true, true) => LedCondition::Operational,
+        }
+    }
+
+    /// Returns the condition as a string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LedCondition::SensorFault => "Sensor fault",
+            LedCondition::WiFiConnecting => "WiFi connecting",
+            LedCondition::MqttFailing => "MQTT failing",
+            LedCondition::Operational => "All systems operational",
+        }
+    }
+}
+
+/// Pattern shown for each [`LedCondition`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct LedPatternTable {
+    /// Sensor, WiFi and MQTT all working
+    pub operational: LedPattern,
+    /// WiFi connected, MQTT broker not reachable
+    pub mqtt_failing: LedPattern,
+    /// Sensor reading, WiFi not connected
+    pub wifi_connecting: LedPattern,
+    /// Sensor not reading
+    pub sensor_fault: LedPattern,
+    /// PWM channel driving the LED instead of the status LED GPIO
+    ///
+    /// Needed for [`LedPattern::Breathe`]; without one it blinks slowly.
+    pub pwm_channel: Option<u8>,
+}
+
+impl Default for LedPatternTable {
+    fn default() -> Self {
+        Self {
+            operational: LedPattern::SlowBlink,
+            mqtt_failing: LedPattern::DoubleBlink,
+            wifi_connecting: LedPattern::FastBlink,
+            sensor_fault: LedPattern::VeryFastBlink,
+            pwm_channel: None,
+        }
+    }
+}
+
+impl LedPatternTable {
+    /// Pattern configured for `condition`
+    pub fn pattern_for(&self, condition: LedCondition) -> LedPattern {
+        match condition {
+            LedCondition::SensorFault => self.sensor_fault,
+            LedCondition::WiFiConnecting => self.wifi_connecting,
+            LedCondition::MqttFailing => self.mqtt_failing,
+            LedCondition::Operational => self.operational,
+        }
+    }
+}
+
+/// Status LED driven from the current [`LedCondition`]
+#[derive(Debug, Clone)]
+pub struct StatusLed {
+    table: LedPatternTable,
+    condition: Option<LedCondition>,
+}
+
+impl StatusLed {
+    /// Creates the indicator; it shows [`LedCondition::SensorFault`] until the first update
+    pub fn new(table: LedPatternTable) -> Self {
+        Self {
+            table,
+            condition: None,
+        }
+    }
+
+    /// Updates the shown condition
+    ///
+    /// # Returns
+    ///
+    /// `true` on the first update and whenever the condition changed
+    pub fn set_condition(&mut self, condition: LedCondition) -> bool {
+        self.condition.replace(condition) != Some(condition)
+    }
+
+    /// Condition currently shown
+    pub fn condition(&self) -> LedCondition {
+        self.condition.unwrap_or(LedCondition::SensorFault)
+    }
+
+    /// Pattern currently shown
+    pub fn pattern(&self) -> LedPattern {
+        self.table.pattern_for(self.condition())
+    }
+
+    /// Plays one period of the current pattern, about one to two seconds
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Period played
+    /// * `Err(IoTError)` - GPIO or PWM write failed
+    pub async fn play_cycle<P: HardwarePlatform>(&self, platform: &mut P) -> Result<(), IoTError> {
+        let pattern = self.pattern();
+        match self.table.pwm_channel {
+            Some(channel) if pattern == LedPattern::Breathe => breathe(platform, channel).await,
+            Some(channel) => {
+                for step in pattern.steps() {
+                    let duty = if step.on { u16::MAX } else { 0 };
+                    platform.get_pwm().set_duty(channel, duty).await.map_err(IoTError::hardware)?;
+                    platform.get_timer().delay(Duration::from_millis(step.duration_ms as u64)).await;
+                }
+                Ok(())
+            }
+            None => {
+                for step in pattern.steps() {
+                    let led = platform.get_status_led();
+                    if step.on { led.set_high().await? } else { led.set_low().await? }
+                    platform.get_timer().delay(Duration::from_millis(step.duration_ms as u64)).await;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Ramps the PWM duty up and back down over one period
+///
+/// The duty follows a square curve because perceived brightness is far from
+/// linear in duty; a linear ramp looks like it saturates halfway.
+async fn breathe<P: HardwarePlatform>(platform: &mut P, channel: u8) -> Result<(), IoTError> {
+    let step = Duration::from_millis((BREATHE_PERIOD_MS / (2 * BREATHE_STEPS)) as u64);
+    for i in (0..BREATHE_STEPS).chain((1..=BREATHE_STEPS).rev()) {
+        let duty = (u16::MAX as u32 * i * i / (BREATHE_STEPS * BREATHE_STEPS)) as u16;
+        platform.get_pwm().set_duty(channel, duty).await.map_err(IoTError::hardware)?;
+        platform.get_timer().delay(step).await;
+    }
+    Ok(())
+}
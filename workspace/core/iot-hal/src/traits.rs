@@ -5,8 +5,10 @@
 
 // Imports for hardware abstraction traits
 use embassy_time::Duration;
-use iot_common::IoTError;
+use iot_common::{IoTError, DeviceInfo, ResetReason};
 use async_trait::async_trait;
+use crate::error::HardwareResult;
+use crate::shared_i2c::SharedI2cBus;
 use alloc::boxed::Box;
 
 /// Core hardware platform abstraction trait
@@ -67,6 +69,21 @@ pub trait HardwarePlatform {
     
     /// WiFi interface for network connectivity
     type WiFi: WiFiInterface;
+    
+    /// ADC interface for analog measurements
+    type Adc: AdcInterface;
+    
+    /// PWM interface for fans, servos and dimmable outputs
+    type Pwm: PwmInterface;
+    
+    /// Watchdog timer for recovering from hung tasks
+    type Watchdog: WatchdogInterface;
+    
+    /// Power management for light and deep sleep
+    type Power: PowerInterface;
+    
+    /// Digital input for the user button
+    type Button: GpioInputInterface;
 
     /// Initialize hardware platform with default configuration
     /// 
@@ -125,6 +142,26 @@ pub trait HardwarePlatform {
     /// ```
     fn get_i2c(&mut self) -> &mut Self::I2cBus;
 
+    /// Get a shareable handle to the I2C bus
+    /// 
+    /// Unlike [`get_i2c`](Self::get_i2c) this only borrows the platform
+    /// immutably, and the handle is `Copy`, so several drivers (sensor,
+    /// display, ...) can each keep one. Every transfer locks the bus; see
+    /// [`crate::shared_i2c`] for the locking discipline.
+    /// 
+    /// # Returns
+    /// 
+    /// Handle implementing [`I2cInterface`]
+    /// 
+    /// # Usage
+    /// 
+    /// ```rust
+    /// let bus = platform.get_shared_i2c();
+    /// let mut bme280 = BME280::new(I2cDevice::shared(bus, 0x76));
+    /// let mut oled = Ssd1306::new(bus, 0x3C);
+    /// ```
+    fn get_shared_i2c(&self) -> SharedI2cBus<'_>;
+
     /// Get console UART interfaces for bidirectional communication
     /// 
     /// Returns both transmitter and receiver for console communication.
@@ -194,6 +231,114 @@ pub trait HardwarePlatform {
     /// ```
     fn get_wifi(&mut self) -> &mut Self::WiFi;
 
+    /// Get ADC interface for analog measurements
+    /// 
+    /// Provides access to the analog-to-digital converter for reading
+    /// battery voltage, light sensors, potentiometers and similar inputs.
+    /// 
+    /// # Returns
+    /// 
+    /// Mutable reference to ADC interface
+    /// 
+    /// # Usage
+    /// 
+    /// ```rust
+    /// let adc = platform.get_adc();
+    /// let battery_mv = adc.read_millivolts(0).await?;
+    /// ```
+    fn get_adc(&mut self) -> &mut Self::Adc;
+
+    /// Get PWM output interface
+    /// 
+    /// Provides access to pulse-width modulated outputs for driving fans,
+    /// servos, vent actuators or dimmable indicators.
+    /// 
+    /// # Returns
+    /// 
+    /// Mutable reference to PWM interface
+    /// 
+    /// # Usage
+    /// 
+    /// ```rust
+    /// let pwm = platform.get_pwm();
+    /// pwm.set_frequency(1_000).await?;
+    /// pwm.set_duty(0, u16::MAX / 2).await?; // 50% duty on channel 0
+    /// ```
+    fn get_pwm(&mut self) -> &mut Self::Pwm;
+
+    /// Get watchdog timer interface
+    /// 
+    /// Provides access to the hardware watchdog that resets the system
+    /// when it is not fed within the configured timeout.
+    /// 
+    /// # Returns
+    /// 
+    /// Mutable reference to watchdog interface
+    /// 
+    /// # Usage
+    /// 
+    /// ```rust
+    /// let watchdog = platform.get_watchdog();
+    /// watchdog.enable(Duration::from_secs(90))?;
+    /// watchdog.feed()?;
+    /// ```
+    fn get_watchdog(&mut self) -> &mut Self::Watchdog;
+
+    /// Get power management interface
+    /// 
+    /// Provides light and deep sleep with RTC timer and GPIO wake sources.
+    /// 
+    /// # Returns
+    /// 
+    /// Mutable reference to power interface
+    /// 
+    /// # Usage
+    /// 
+    /// ```rust
+    /// let power = platform.get_power();
+    /// power.enable_gpio_wakeup(4, WakeLevel::Low)?;
+    /// power.deep_sleep(Duration::from_secs(30))?;
+    /// ```
+    fn get_power(&mut self) -> &mut Self::Power;
+
+    /// Get user button input interface
+    /// 
+    /// Provides debounced press detection for the on-board user button,
+    /// e.g. to trigger a factory reset or WiFi re-provisioning.
+    /// 
+    /// # Returns
+    /// 
+    /// Mutable reference to button input interface
+    /// 
+    /// # Usage
+    /// 
+    /// ```rust
+    /// let button = platform.get_button();
+    /// button.wait_for_press().await?;
+    /// ```
+    fn get_button(&mut self) -> &mut Self::Button;
+
+    /// Read the on-die temperature sensor
+    /// 
+    /// Returns the silicon temperature in degrees Celsius. This is not the
+    /// ambient temperature: the sensor is coarse (a few degrees of error
+    /// between chips) and reads high by the chip's own dissipation, more so
+    /// while the radio is transmitting. Use it to spot overheating or compare
+    /// trends, never in place of an environmental sensor.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(celsius)` - Die temperature
+    /// * `Err(HardwareError)` - Sensor unavailable or reading implausible
+    /// 
+    /// # Usage
+    /// 
+    /// ```rust
+    /// let die = platform.read_chip_temperature()?;
+    /// rprintln!("Chip {:.1}°C (ambient {:.1}°C)", die, bme280_temperature);
+    /// ```
+    fn read_chip_temperature(&mut self) -> HardwareResult<f32>;
+
     /// Check if platform is properly initialized
     /// 
     /// Verifies that all hardware resources are available and responsive.
@@ -214,8 +359,50 @@ pub trait HardwarePlatform {
     /// 
     /// String slice with platform identification
     fn platform_info(&self) -> &'static str;
+
+    /// Get the identity of this device
+    /// 
+    /// Chip model and revision, factory MAC address, firmware version and
+    /// last reset reason. The firmware version is the HAL's own; applications
+    /// usually replace it with theirs:
+    /// 
+    /// ```rust
+    /// let info = platform.device_info().with_firmware_version(env!("CARGO_PKG_VERSION"));
+    /// rprintln!("{}", info);
+    /// ```
+    fn device_info(&self) -> DeviceInfo;
+
+    /// Get the cause of the last reset
+    /// 
+    /// Read from the reset-reason register, so a watchdog reset or brownout is
+    /// reported even though the firmware never saw it coming. A panic that
+    /// rebooted the device appears as [`ResetReason::Software`]; the
+    /// `crash` module recovers the panic message on ESP32-C3.
+    fn reset_reason(&self) -> ResetReason {
+        self.device_info().reset_reason
+    }
+
+    /// Restart the system
+    /// 
+    /// Flush pending data before calling: on real hardware this performs a
+    /// software reset and never returns. Mock platforms record the request and
+    /// return, so restart paths can be tested without rebooting.
+    /// 
+    /// # Usage
+    /// 
+    /// ```rust
+    /// // After publishing the offline status
+    /// platform.reset().await;
+    /// ```
+    async fn reset(&mut self);
 }
 
+/// First address probed by [`I2cInterface::scan`] (0x00-0x07 are reserved)
+pub const I2C_SCAN_FIRST_ADDRESS: u8 = 0x08;
+
+/// Last address probed by [`I2cInterface::scan`] (0x78-0x7F are reserved)
+pub const I2C_SCAN_LAST_ADDRESS: u8 = 0x77;
+
 /// I2C interface abstraction for sensor communication
 /// 
 /// Provides async I2C operations with unified error handling.
@@ -261,6 +448,27 @@ pub trait I2cInterface {
     /// * `Ok(())` - Transaction successful
     /// * `Err(IoTError)` - Communication failure
     async fn write_read(&mut self, address: u8, write_data: &[u8], read_buffer: &mut [u8]) -> Result<(), IoTError>;
+
+    /// Scan the bus for responding devices
+    /// 
+    /// Probes every non-reserved 7-bit address ([`I2C_SCAN_FIRST_ADDRESS`] to
+    /// [`I2C_SCAN_LAST_ADDRESS`]) with a zero-length write and collects the ones
+    /// that ACK. No register is written, so the scan is safe on a live bus.
+    /// 
+    /// # Returns
+    /// 
+    /// Responding addresses in ascending order; empty when no device answers
+    /// (a NACK is not an error).
+    async fn scan(&mut self) -> heapless::Vec<u8, 128> {
+        let mut found = heapless::Vec::new();
+        for address in I2C_SCAN_FIRST_ADDRESS..=I2C_SCAN_LAST_ADDRESS {
+            if self.write(address, &[]).await.is_ok() {
+                // At most 112 addresses are probed, always within capacity
+                let _ = found.push(address);
+            }
+        }
+        found
+    }
 }
 
 /// UART transmitter interface for output operations
@@ -350,6 +558,256 @@ pub trait GpioInterface {
     async fn is_high(&self) -> Result<bool, IoTError>;
 }
 
+/// Signal transition to wait for on a digital input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    /// Logical level goes from inactive to active
+    Rising,
+    /// Logical level goes from active to inactive
+    Falling,
+    /// Either transition
+    Any,
+}
+
+/// Internal pull resistor applied to a digital input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pull {
+    /// Floating input, an external resistor is fitted
+    None,
+    /// Pull-up, for buttons wired to ground
+    Up,
+    /// Pull-down, for buttons wired to the supply
+    Down,
+}
+
+/// GPIO input interface for buttons and digital sensors
+/// 
+/// Levels and edges are logical: for an active-low input (button to ground
+/// with pull-up) a press reads as high and produces a rising edge.
+/// Implementations wait for edges with interrupts rather than polling.
+#[async_trait(?Send)]
+pub trait GpioInputInterface {
+    /// Get current logical input state
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(true)` - Input is active (button pressed)
+    /// * `Ok(false)` - Input is inactive
+    /// * `Err(HardwareError)` - GPIO read failed
+    async fn is_high(&mut self) -> HardwareResult<bool>;
+
+    /// Wait for a logical transition of the input
+    /// 
+    /// No debouncing is applied; contact bounce yields several edges.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `edge` - Transition to wait for
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(())` - Transition detected
+    /// * `Err(HardwareError)` - Interrupt configuration failed
+    async fn wait_for_edge(&mut self, edge: Edge) -> HardwareResult<()>;
+
+    /// Wait for a debounced press
+    /// 
+    /// Waits for a rising edge, then requires the input to still be active
+    /// after the debounce period. Shorter pulses are treated as bounce or
+    /// noise and ignored.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(())` - Stable press detected
+    /// * `Err(HardwareError)` - GPIO operation failed
+    async fn wait_for_press(&mut self) -> HardwareResult<()>;
+
+    /// Set the debounce period used by `wait_for_press`
+    fn set_debounce(&mut self, debounce: Duration);
+
+    /// Get the debounce period used by `wait_for_press`
+    fn debounce(&self) -> Duration;
+}
+
+/// ADC interface for analog input sampling
+/// 
+/// Channels are identified by platform-specific numbers. On ESP32-C3 the
+/// channel number matches the ADC1 channel (and GPIO number).
+#[async_trait(?Send)]
+pub trait AdcInterface {
+    /// Read raw conversion result from a channel
+    /// 
+    /// # Arguments
+    /// 
+    /// * `channel` - ADC channel number
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(raw)` - Raw conversion value (12-bit on ESP32-C3)
+    /// * `Err(HardwareError)` - Invalid channel or conversion failed
+    async fn read_raw(&mut self, channel: u8) -> HardwareResult<u16>;
+
+    /// Read channel voltage in millivolts
+    /// 
+    /// Converts the raw reading using the channel attenuation configured
+    /// by the platform.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `channel` - ADC channel number
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(millivolts)` - Input voltage in millivolts
+    /// * `Err(HardwareError)` - Invalid channel or conversion failed
+    async fn read_millivolts(&mut self, channel: u8) -> HardwareResult<u16>;
+}
+
+/// PWM interface for pulse-width modulated outputs
+/// 
+/// Duty cycles are expressed on a platform-independent 16-bit scale where
+/// `0` is always off and `u16::MAX` is always on; implementations map this
+/// onto their native resolution. All channels share one output frequency.
+#[async_trait(?Send)]
+pub trait PwmInterface {
+    /// Number of PWM channels exposed by the platform
+    /// 
+    /// Valid channel numbers are `0..CHANNELS`.
+    const CHANNELS: u8;
+
+    /// Set duty cycle of a channel
+    /// 
+    /// # Arguments
+    /// 
+    /// * `channel` - PWM channel number
+    /// * `duty` - Duty cycle, `0` (off) to `u16::MAX` (fully on)
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(())` - Duty cycle applied
+    /// * `Err(HardwareError)` - Invalid channel or configuration failed
+    async fn set_duty(&mut self, channel: u8, duty: u16) -> HardwareResult<()>;
+
+    /// Set output frequency for all channels
+    /// 
+    /// Previously set duty cycles are preserved.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `hz` - Output frequency in hertz
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(())` - Frequency applied
+    /// * `Err(HardwareError)` - Frequency not achievable
+    async fn set_frequency(&mut self, hz: u32) -> HardwareResult<()>;
+}
+
+/// Watchdog timer interface
+/// 
+/// A watchdog resets the system unless `feed` is called at least once per
+/// timeout period. Operations are plain register writes and therefore
+/// synchronous, so they can be used from any context.
+pub trait WatchdogInterface {
+    /// Start the watchdog with the given timeout
+    /// 
+    /// Calling `enable` on a running watchdog restarts it with the new timeout.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `timeout` - Time without a feed before the system is reset
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(())` - Watchdog running
+    /// * `Err(HardwareError)` - Timeout outside the supported range
+    fn enable(&mut self, timeout: Duration) -> HardwareResult<()>;
+
+    /// Restart the watchdog countdown
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(())` - Countdown restarted
+    /// * `Err(HardwareError)` - Watchdog not enabled
+    fn feed(&mut self) -> HardwareResult<()>;
+
+    /// Stop the watchdog
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(())` - Watchdog stopped
+    /// * `Err(HardwareError)` - Watchdog could not be stopped
+    fn disable(&mut self) -> HardwareResult<()>;
+}
+
+/// Logic level that wakes the system from sleep
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WakeLevel {
+    /// Wake while the pin is low (button to ground)
+    Low,
+    /// Wake while the pin is high
+    High,
+}
+
+/// Power management interface for sleep modes
+/// 
+/// Both sleep modes wake on the RTC timer after the requested duration,
+/// and additionally on the GPIO wake source if one is enabled.
+/// 
+/// # Deep Sleep Restarts the Application
+/// 
+/// Deep sleep powers down the CPU and RAM; waking is a reset and the
+/// firmware starts again from `main`. Code after `deep_sleep` never runs
+/// on real hardware, so a periodic loop such as the sensor task must be
+/// restructured from "measure, `Timer::after`, repeat" into
+/// "boot, measure, publish, deep sleep":
+/// 
+/// ```rust
+/// async fn main(spawner: Spawner) {
+///     let mut platform = Esp32C3Platform::initialize().await?;
+///     let reading = read_sensor(platform.get_i2c()).await;
+///     publish(reading).await;
+///     platform.get_power().deep_sleep(Duration::from_secs(30))?;
+/// }
+/// ```
+/// 
+/// State that must survive between cycles has to live in flash (or RTC
+/// memory). Light sleep keeps RAM and resumes after the call returns.
+pub trait PowerInterface {
+    /// Enter light sleep for `duration`
+    /// 
+    /// Blocks until a wake source fires; RAM and peripheral state are kept.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(())` - System woke up
+    /// * `Err(HardwareError)` - Duration not supported
+    fn light_sleep(&mut self, duration: Duration) -> HardwareResult<()>;
+
+    /// Enter deep sleep for `duration`
+    /// 
+    /// On hardware this does not return: the system resets on wake.
+    /// Implementations without real sleep (mocks) return `Ok(())`.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Err(HardwareError)` - Duration not supported
+    fn deep_sleep(&mut self, duration: Duration) -> HardwareResult<()>;
+
+    /// Wake from sleep when `pin` reaches `level`
+    /// 
+    /// Lets a button press wake the device early, e.g. for console access.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(())` - Wake source armed for subsequent sleeps
+    /// * `Err(HardwareError)` - Pin cannot wake the system
+    fn enable_gpio_wakeup(&mut self, pin: u8, level: WakeLevel) -> HardwareResult<()>;
+
+    /// Remove the GPIO wake source
+    fn disable_gpio_wakeup(&mut self);
+}
+
 /// Timer interface for delays and timeouts
 pub trait TimerInterface {
     /// Asynchronous delay
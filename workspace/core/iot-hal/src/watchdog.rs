@@ -0,0 +1,209 @@
+//! # Watchdog Feeding
+//!
+//! Heartbeat-gated watchdog feeder. Long-running tasks report progress
+//! through a shared [`TaskHeartbeat`]; the feeder only feeds the hardware
+//! watchdog when every expected task has reported since the last check.
+//! A task stuck in a blocking call (for example a wedged I2C read) stops
+//! reporting, the watchdog is no longer fed and the system resets.
+//!
+//! ## Usage
+//!
+//! The feeder is a plain async function so applications can run it from
+//! their own Embassy task with a concrete watchdog type:
+//!
+//! ```rust
+//! use iot_hal::{TaskHeartbeat, feed_while_healthy, esp32c3::Esp32C3Rtc};
+//!
+//! const SENSOR_TASK: u8 = 0;
+//! const MQTT_TASK: u8 = 1;
+//! static HEARTBEAT: TaskHeartbeat = TaskHeartbeat::new(1 << SENSOR_TASK | 1 << MQTT_TASK);
+//!
+//! #[embassy_executor::task]
+//! async fn watchdog_task(watchdog: &'static mut Esp32C3Rtc<'static>) {
+//!     watchdog.enable(Duration::from_secs(120)).ok();
+//!     feed_while_healthy(watchdog, &HEARTBEAT, Duration::from_secs(45)).await;
+//! }
+//!
+//! // In each supervised task loop:
+//! HEARTBEAT.beat(SENSOR_TASK);
+//! ```
+//!
+//! The check interval must be longer than the slowest supervised loop
+//! (the sensor task runs every 30 seconds), and the watchdog timeout must
+//! cover at least two check intervals. A task that is only spawned on some
+//! boards joins the supervised set with [`TaskHeartbeat::expect`]. A task
+//! that already runs a loop of its own can call [`feed_if_healthy`] once
+//! per check interval instead of dedicating a task to the feeder.
+
+use core::cell::Cell;
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use embassy_time::{Duration, Timer};
+
+use crate::traits::WatchdogInterface;
+
+/// Progress flags for supervised tasks
+///
+/// Each supervised task owns one bit (task id 0-31). The set of expected
+/// task ids starts with the mask given at construction and can only grow.
+/// The masks sit behind a critical section rather than in atomics: the
+/// ESP32-C3 has no atomic read-modify-write instructions.
+pub struct TaskHeartbeat {
+    flags: Mutex<CriticalSectionRawMutex, Cell<HeartbeatFlags>>,
+}
+
+#[derive(Clone, Copy)]
+struct HeartbeatFlags {
+    expected: u32,
+    reported: u32,
+}
+
+impl TaskHeartbeat {
+    /// Create a heartbeat expecting the tasks in `expected` (bit mask of task ids)
+    pub const fn new(expected: u32) -> Self {
+        Self {
+            flags: Mutex::new(Cell::new(HeartbeatFlags { expected, reported: 0 })),
+        }
+    }
+
+    /// Add a task to the supervised set, e.g. once it has been spawned
+    pub fn expect(&self, task: u8) {
+        if task < 32 {
+            self.update(|flags| flags.expected |= 1 << task);
+        }
+    }
+
+    /// Record progress for a task
+    pub fn beat(&self, task: u8) {
+        if task < 32 {
+            self.update(|flags| flags.reported |= 1 << task);
+        }
+    }
+
+    /// Check that all expected tasks reported, and start a new period
+    pub fn check_and_clear(&self) -> bool {
+        let flags = self.update(|flags| flags.reported = 0);
+        flags.reported & flags.expected == flags.expected
+    }
+
+    /// Bit mask of expected tasks that have not reported in this period
+    pub fn missing(&self) -> u32 {
+        let flags = self.flags.lock(Cell::get);
+        flags.expected & !flags.reported
+    }
+
+    /// Apply `change` to the flags in one critical section, returning the previous flags
+    fn update(&self, change: impl FnOnce(&mut HeartbeatFlags)) -> HeartbeatFlags {
+        self.flags.lock(|cell| {
+            let previous = cell.get();
+            let mut flags = previous;
+            change(&mut flags);
+            cell.set(flags);
+            previous
+        })
+    }
+}
+
+/// Feed `watchdog` if all supervised tasks reported, and start a new period
+///
+/// One check of [`feed_while_healthy`], for callers that schedule the
+/// checks themselves. Returns whether the watchdog was fed.
+pub fn feed_if_healthy<W: WatchdogInterface>(watchdog: &mut W, heartbeat: &TaskHeartbeat) -> bool {
+    // Feed errors mean the watchdog is not running; nothing to recover
+    heartbeat.check_and_clear() && watchdog.feed().is_ok()
+}
+
+/// Feed `watchdog` every `interval` while all supervised tasks report
+///
+/// Never returns. When a check finds a missing task the watchdog is left
+/// unfed, so it expires unless the task recovers before the timeout.
+pub async fn feed_while_healthy<W: WatchdogInterface>(
+    watchdog: &mut W,
+    heartbeat: &TaskHeartbeat,
+    interval: Duration,
+) -> ! {
+    loop {
+        Timer::after(interval).await;
+        feed_if_healthy(watchdog, heartbeat);
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::mock::MockPlatform;
+    use crate::HardwarePlatform;
+    use embassy_time::Instant;
+
+    const SENSOR_TASK: u8 = 0;
+    const MQTT_TASK: u8 = 1;
+    const CHECK_INTERVAL: Duration = Duration::from_millis(20);
+
+    /// Runs the feeder for `runtime` while `beats` reports the given tasks every 5 ms
+    async fn run_feeder(platform: &mut MockPlatform, heartbeat: &TaskHeartbeat, tasks: &[u8], runtime: Duration) {
+        let beats = async {
+            let end = Instant::now() + runtime;
+            while Instant::now() < end {
+                for &task in tasks {
+                    heartbeat.beat(task);
+                }
+                Timer::after(Duration::from_millis(5)).await;
+            }
+        };
+        let feeder = tokio::time::timeout(
+            std::time::Duration::from_millis(runtime.as_millis()),
+            feed_while_healthy(platform.get_watchdog(), heartbeat, CHECK_INTERVAL),
+        );
+        let _ = tokio::join!(beats, feeder);
+    }
+
+    #[tokio::test]
+    async fn test_feeds_once_per_interval_while_healthy() {
+        let mut platform = MockPlatform::new();
+        let heartbeat = TaskHeartbeat::new(1 << SENSOR_TASK | 1 << MQTT_TASK);
+        platform.get_watchdog().enable(Duration::from_millis(100)).unwrap();
+
+        run_feeder(&mut platform, &heartbeat, &[SENSOR_TASK, MQTT_TASK], Duration::from_millis(110)).await;
+
+        let watchdog = platform.get_watchdog();
+        // The enable feed plus one per elapsed check interval
+        assert!(watchdog.feed_count() >= 4, "fed {} times", watchdog.feed_count());
+        for interval in watchdog.feed_intervals() {
+            assert!(interval >= CHECK_INTERVAL, "fed early after {:?}", interval);
+            assert!(interval < CHECK_INTERVAL * 2, "missed a check: {:?}", interval);
+        }
+        assert!(!watchdog.check_timeout(Instant::now()));
+    }
+
+    #[tokio::test]
+    async fn test_stuck_task_lets_watchdog_expire() {
+        let mut platform = MockPlatform::new();
+        let heartbeat = TaskHeartbeat::new(1 << SENSOR_TASK);
+        heartbeat.expect(MQTT_TASK);
+        platform.get_watchdog().enable(Duration::from_millis(50)).unwrap();
+
+        // The MQTT task never reports, as if wedged in a blocking call
+        run_feeder(&mut platform, &heartbeat, &[SENSOR_TASK], Duration::from_millis(80)).await;
+
+        heartbeat.beat(SENSOR_TASK);
+        assert_eq!(heartbeat.missing(), 1 << MQTT_TASK);
+        let watchdog = platform.get_watchdog();
+        assert_eq!(watchdog.feed_count(), 1, "only the enable feed is expected");
+        assert!(watchdog.check_timeout(Instant::now()), "a real device would have reset");
+    }
+
+    #[test]
+    fn test_feed_if_healthy_requires_all_tasks() {
+        let mut platform = MockPlatform::new();
+        let heartbeat = TaskHeartbeat::new(1 << SENSOR_TASK | 1 << MQTT_TASK);
+        platform.get_watchdog().enable(Duration::from_secs(1)).unwrap();
+
+        heartbeat.beat(SENSOR_TASK);
+        assert!(!feed_if_healthy(platform.get_watchdog(), &heartbeat));
+
+        // The failed check started a new period, so both tasks must report again
+        heartbeat.beat(SENSOR_TASK);
+        heartbeat.beat(MQTT_TASK);
+        assert!(feed_if_healthy(platform.get_watchdog(), &heartbeat));
+        assert_eq!(platform.get_watchdog().feed_count(), 2);
+    }
+}
@@ -4,17 +4,37 @@
 //! trend analysis, pattern recognition, and optimization recommendations.
 
 use embassy_time::{Duration, Instant};
-use heapless::{Vec, String};
+use heapless::{Vec, String, FnvIndexMap};
 use core::fmt::Write;
 
 use crate::timing::{TimingStatistics, TimingCategory};
 use crate::memory::MemorySnapshot;
+use crate::monitor::PerformanceReport;
+use crate::regression::PerformanceMetric;
+use crate::MAX_PERFORMANCE_SAMPLES;
+
+/// Metrics tracked with an exponential moving average, and where they come from
+const EMA_METRICS: [(PerformanceMetric, Option<TimingCategory>); 10] = [
+    (PerformanceMetric::SensorReadingTime, Some(TimingCategory::SensorReading)),
+    (PerformanceMetric::MqttPublishTime, Some(TimingCategory::MqttPublish)),
+    (PerformanceMetric::NetworkConnectionTime, Some(TimingCategory::NetworkOperation)),
+    (PerformanceMetric::BootTime, Some(TimingCategory::SystemBoot)),
+    (PerformanceMetric::ConsoleResponseTime, Some(TimingCategory::ConsoleCommand)),
+    (PerformanceMetric::TaskSchedulingLatency, Some(TimingCategory::TaskScheduling)),
+    (PerformanceMetric::HeapUsage, None),
+    (PerformanceMetric::StackUsage, None),
+    (PerformanceMetric::FlashUsage, None),
+    (PerformanceMetric::SystemEfficiency, None),
+];
 
 /// Comprehensive performance analyzer
 pub struct PerformanceAnalyzer {
     /// Historical performance data for trend analysis
     historical_data: Vec<PerformanceDataPoint, 32>,
     
+    /// Exponential moving average of each metric, one entry per recorded sample
+    ema_history: FnvIndexMap<PerformanceMetric, Vec<f32, MAX_PERFORMANCE_SAMPLES>, 16>,
+    
     /// Analysis configuration
     config: AnalysisConfig,
     
@@ -61,6 +81,9 @@ pub struct AnalysisConfig {
     
     /// Enable predictive analysis
     pub predictive_analysis: bool,
+    
+    /// Smoothing factor of the moving averages (0.0 to 1.0, higher follows faster)
+    pub ema_alpha: f32,
 }
 
 impl Default for AnalysisConfig {
@@ -71,6 +94,7 @@ impl Default for AnalysisConfig {
             change_sensitivity: 0.1, // 10% change threshold
             pattern_recognition: true,
             predictive_analysis: true,
+            ema_alpha: 0.3,
         }
     }
 }
@@ -249,6 +273,7 @@ impl PerformanceAnalyzer {
     pub fn with_config(config: AnalysisConfig) -> Self {
         Self {
             historical_data: Vec::new(),
+            ema_history: FnvIndexMap::new(),
             config,
             last_analysis: None,
         }
@@ -279,6 +304,106 @@ impl PerformanceAnalyzer {
             self.historical_data.remove(0);
         }
         let _ = self.historical_data.push(data_point);
+        
+        for (metric, category) in EMA_METRICS {
+            let value = match (metric, category) {
+                (_, Some(category)) => timing_stats.get_average_time(category)
+                    .map(|d| d.as_micros() as f32),
+                (PerformanceMetric::HeapUsage, None) => Some(memory_snapshot.heap_used as f32),
+                (PerformanceMetric::StackUsage, None) => Some(memory_snapshot.stack_used as f32),
+                (PerformanceMetric::FlashUsage, None) => Some(memory_snapshot.flash_used as f32),
+                (PerformanceMetric::SystemEfficiency, None) => Some(timing_stats.get_overall_stats().efficiency_ratio),
+                _ => None,
+            };
+            if let Some(value) = value {
+                self.record_metric(metric, value);
+            }
+        }
+    }
+    
+    /// Add a performance report for analysis
+    ///
+    /// Equivalent to [`add_data_point`](Self::add_data_point) with the report's
+    /// statistics, snapshot and alert count.
+    pub fn add_report(&mut self, report: &PerformanceReport) {
+        self.add_data_point(&report.timing_stats, &report.memory_usage, report.alerts.len() as u8);
+    }
+    
+    /// Fold one sample of a metric into its moving average
+    ///
+    /// Timing metrics are in microseconds, memory metrics in bytes and
+    /// efficiency as a 0.0 to 1.0 ratio. The last `MAX_PERFORMANCE_SAMPLES`
+    /// averages are kept for [`trend`](Self::trend).
+    pub fn record_metric(&mut self, metric: PerformanceMetric, value: f32) {
+        let alpha = self.config.ema_alpha.clamp(0.0, 1.0);
+        
+        if !self.ema_history.contains_key(&metric) {
+            // One slot per metric in EMA_METRICS, well under the map's capacity
+            let _ = self.ema_history.insert(metric, Vec::new());
+        }
+        let Some(history) = self.ema_history.get_mut(&metric) else {
+            return;
+        };
+        
+        let average = match history.last() {
+            Some(&previous) => alpha * value + (1.0 - alpha) * previous,
+            None => value,
+        };
+        
+        if history.is_full() {
+            history.remove(0);
+        }
+        let _ = history.push(average);
+    }
+    
+    /// Get the current moving average of a metric
+    pub fn moving_average(&self, metric: PerformanceMetric) -> Option<f32> {
+        self.ema_history.get(&metric).and_then(|history| history.last().copied())
+    }
+    
+    /// Get the slope of a metric's moving average, in metric units per sample
+    ///
+    /// `None` until `min_trend_points` samples have been recorded.
+    pub fn trend_slope(&self, metric: PerformanceMetric) -> Option<f32> {
+        let history = self.ema_history.get(&metric)?;
+        if history.len() < self.config.min_trend_points {
+            return None;
+        }
+        self.calculate_linear_trend(history).map(|(slope, _)| slope)
+    }
+    
+    /// Classify the direction of a metric's moving average
+    ///
+    /// The slope is taken relative to the current average so one threshold
+    /// fits microseconds, bytes and ratios alike: a drift of more than a tenth
+    /// of `change_sensitivity` per sample (1% with the default config) is
+    /// `Degrading`, more than the full `change_sensitivity` is `Critical`.
+    /// Rising efficiency counts as improving, rising anything else as
+    /// degrading. This flags a slow drift well before the hard thresholds of
+    /// [`PerformanceMonitor`](crate::PerformanceMonitor) trip.
+    pub fn trend(&self, metric: PerformanceMetric) -> PerformanceTrend {
+        let (Some(slope), Some(average)) = (self.trend_slope(metric), self.moving_average(metric)) else {
+            return PerformanceTrend::Unknown;
+        };
+        if average.abs() < f32::EPSILON {
+            return PerformanceTrend::Stable;
+        }
+        
+        let mut relative_slope = slope / average.abs();
+        if metric == PerformanceMetric::SystemEfficiency {
+            relative_slope = -relative_slope;
+        }
+        
+        let threshold = self.config.change_sensitivity / 10.0;
+        if relative_slope > self.config.change_sensitivity {
+            PerformanceTrend::Critical
+        } else if relative_slope > threshold {
+            PerformanceTrend::Degrading
+        } else if relative_slope < -threshold {
+            PerformanceTrend::Improving
+        } else {
+            PerformanceTrend::Stable
+        }
     }
     
     /// Perform comprehensive trend analysis
@@ -526,7 +651,7 @@ impl PerformanceAnalyzer {
     }
     
     /// Calculate linear trend using least squares regression
-    fn calculate_linear_trend(&self, values: &Vec<f32, 32>) -> Option<(f32, f32)> {
+    fn calculate_linear_trend(&self, values: &[f32]) -> Option<(f32, f32)> {
         if values.len() < 3 {
             return None;
         }
@@ -723,4 +848,46 @@ impl Default for PerformanceAnalyzer {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_increasing_series_is_degrading() {
+        let mut analyzer = PerformanceAnalyzer::new();
+        assert_eq!(analyzer.trend(PerformanceMetric::SensorReadingTime), PerformanceTrend::Unknown);
+
+        // Sensor reads slowing down by 10μs per report, ~2% of the average
+        for sample in 0..20 {
+            analyzer.record_metric(PerformanceMetric::SensorReadingTime, 400.0 + sample as f32 * 10.0);
+        }
+
+        assert_eq!(analyzer.trend(PerformanceMetric::SensorReadingTime), PerformanceTrend::Degrading);
+        assert!(analyzer.trend_slope(PerformanceMetric::SensorReadingTime).unwrap() > 0.0);
+        assert_eq!(analyzer.trend(PerformanceMetric::HeapUsage), PerformanceTrend::Unknown);
+    }
+
+    #[test]
+    fn test_flat_and_improving_series() {
+        let mut analyzer = PerformanceAnalyzer::new();
+        for sample in 0..20 {
+            analyzer.record_metric(PerformanceMetric::HeapUsage, 48_000.0 + (sample % 2) as f32 * 16.0);
+            analyzer.record_metric(PerformanceMetric::SystemEfficiency, 0.5 + sample as f32 * 0.01);
+        }
+
+        assert_eq!(analyzer.trend(PerformanceMetric::HeapUsage), PerformanceTrend::Stable);
+        assert_eq!(analyzer.trend(PerformanceMetric::SystemEfficiency), PerformanceTrend::Improving);
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut analyzer = PerformanceAnalyzer::new();
+        for sample in 0..(MAX_PERFORMANCE_SAMPLES * 2) {
+            analyzer.record_metric(PerformanceMetric::StackUsage, sample as f32);
+        }
+
+        let history = analyzer.ema_history.get(&PerformanceMetric::StackUsage).unwrap();
+        assert_eq!(history.len(), MAX_PERFORMANCE_SAMPLES);
+    }
+}
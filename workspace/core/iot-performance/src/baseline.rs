@@ -9,6 +9,30 @@ use heapless::Vec;
 use crate::timing::{TimingStatistics, TimingCategory};
 use crate::memory::MemorySnapshot;
 
+/// Configuration name used to persist a device baseline in a `ConfigStore`
+#[cfg(feature = "storage")]
+pub const PERFORMANCE_BASELINE_CONFIG: &str = "perf_baseline";
+
+/// Version identifier of baselines captured on (or loaded from) the device
+pub const DEVICE_BASELINE_VERSION: &str = "Device-v1";
+
+/// Headroom added on top of measured values to form a device baseline's limits
+pub const DEVICE_BASELINE_MARGIN: f32 = 0.25;
+
+/// Categories whose measured averages are captured into a device baseline
+const CAPTURED_CATEGORIES: [TimingCategory; 10] = [
+    TimingCategory::SensorReading,
+    TimingCategory::NetworkOperation,
+    TimingCategory::MqttPublish,
+    TimingCategory::ConsoleCommand,
+    TimingCategory::SystemBoot,
+    TimingCategory::MemoryAllocation,
+    TimingCategory::I2cOperation,
+    TimingCategory::TaskScheduling,
+    TimingCategory::InterruptHandling,
+    TimingCategory::SystemCycle,
+];
+
 /// Performance baseline definition for comparison and validation
 #[derive(Debug, Clone)]
 pub struct PerformanceBaseline {
@@ -344,6 +368,69 @@ impl PerformanceBaseline {
         }
     }
     
+    /// Capture this device's measured performance as a baseline
+    ///
+    /// Meant to be taken after a known-good build has run long enough to cover
+    /// every category of interest. Measured averages become the targets and
+    /// [`DEVICE_BASELINE_MARGIN`] on top of them the limits; categories without
+    /// measurements are left out. Sensor reading, MQTT publish and boot time are
+    /// critical, matching [`phase_2_targets`](Self::phase_2_targets).
+    pub fn capture(timing_stats: &TimingStatistics, memory_snapshot: &MemorySnapshot) -> Self {
+        let with_margin = |duration: Duration| {
+            Duration::from_micros((duration.as_micros() as f32 * (1.0 + DEVICE_BASELINE_MARGIN)) as u64)
+        };
+        let bytes_with_margin = |bytes: usize| (bytes as f32 * (1.0 + DEVICE_BASELINE_MARGIN)) as usize;
+        
+        let mut timing_baselines = Vec::new();
+        for category in CAPTURED_CATEGORIES {
+            if let Some(average) = timing_stats.get_average_time(category) {
+                let _ = timing_baselines.push(TimingBaseline {
+                    category,
+                    target_average: average,
+                    max_acceptable: with_margin(average),
+                    warning_threshold: 0.9,
+                    is_critical: matches!(
+                        category,
+                        TimingCategory::SensorReading | TimingCategory::MqttPublish | TimingCategory::SystemBoot
+                    ),
+                });
+            }
+        }
+        
+        let memory_baseline = MemoryBaseline {
+            target_heap_bytes: memory_snapshot.heap_used,
+            max_heap_bytes: bytes_with_margin(memory_snapshot.heap_peak.max(memory_snapshot.heap_used)),
+            target_stack_bytes: memory_snapshot.stack_used,
+            max_stack_bytes: bytes_with_margin(memory_snapshot.stack_peak.max(memory_snapshot.stack_used)),
+            target_flash_bytes: memory_snapshot.flash_used,
+            max_flash_bytes: bytes_with_margin(memory_snapshot.flash_used),
+        };
+        
+        // Fall back to the Phase 2 limits for anything not measured
+        let phase_2 = Self::phase_2_targets().system_baseline;
+        let system_baseline = SystemBaseline {
+            max_boot_time: timing_stats.get_average_time(TimingCategory::SystemBoot)
+                .map_or(phase_2.max_boot_time, with_margin),
+            max_cycle_time: timing_stats.get_average_time(TimingCategory::SystemCycle)
+                .map_or(phase_2.max_cycle_time, with_margin),
+            target_efficiency: timing_stats.get_overall_stats().efficiency_ratio,
+            max_alerts_per_hour: phase_2.max_alerts_per_hour,
+        };
+        
+        Self {
+            timing_baselines,
+            memory_baseline,
+            system_baseline,
+            version: DEVICE_BASELINE_VERSION,
+            description: "Baseline measured on this device",
+        }
+    }
+    
+    /// Get the baseline target for a timing category, if this baseline has one
+    pub fn timing_baseline(&self, category: TimingCategory) -> Option<&TimingBaseline> {
+        self.timing_baselines.iter().find(|baseline| baseline.category == category)
+    }
+    
     /// Compare current performance against this baseline
     pub fn compare_current_performance(
         &self,
@@ -610,4 +697,147 @@ impl PerformanceBaseline {
             _ => BaselineStatus::Poor,
         }
     }
-}
\ No newline at end of file
+}
+/// Persisted form of a [`TimingBaseline`], durations in microseconds
+#[cfg(feature = "storage")]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StoredTimingBaseline {
+    /// Operation category
+    pub category: TimingCategory,
+    /// Target average time
+    pub target_average_us: u64,
+    /// Maximum acceptable time
+    pub max_acceptable_us: u64,
+    /// Warning threshold (fraction of max_acceptable)
+    pub warning_threshold: f32,
+    /// Whether this timing is critical for system operation
+    pub is_critical: bool,
+}
+
+/// Persisted form of a [`PerformanceBaseline`]
+///
+/// Durations are stored as microseconds and the `&'static str` version and
+/// description are dropped; a loaded baseline is always tagged
+/// [`DEVICE_BASELINE_VERSION`].
+#[cfg(feature = "storage")]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StoredPerformanceBaseline {
+    /// Timing baselines by category
+    pub timings: Vec<StoredTimingBaseline, 10>,
+    /// See [`MemoryBaseline::target_heap_bytes`]
+    pub target_heap_bytes: usize,
+    /// See [`MemoryBaseline::max_heap_bytes`]
+    pub max_heap_bytes: usize,
+    /// See [`MemoryBaseline::target_stack_bytes`]
+    pub target_stack_bytes: usize,
+    /// See [`MemoryBaseline::max_stack_bytes`]
+    pub max_stack_bytes: usize,
+    /// See [`MemoryBaseline::target_flash_bytes`]
+    pub target_flash_bytes: usize,
+    /// See [`MemoryBaseline::max_flash_bytes`]
+    pub max_flash_bytes: usize,
+    /// Boot time requirement
+    pub max_boot_time_us: u64,
+    /// Maximum acceptable system cycle time
+    pub max_cycle_time_us: u64,
+    /// Target system efficiency (0.0 to 1.0)
+    pub target_efficiency: f32,
+    /// Maximum number of performance alerts per hour
+    pub max_alerts_per_hour: u32,
+}
+
+#[cfg(feature = "storage")]
+impl From<&PerformanceBaseline> for StoredPerformanceBaseline {
+    fn from(baseline: &PerformanceBaseline) -> Self {
+        let timings = baseline.timing_baselines.iter()
+            .map(|timing| StoredTimingBaseline {
+                category: timing.category,
+                target_average_us: timing.target_average.as_micros(),
+                max_acceptable_us: timing.max_acceptable.as_micros(),
+                warning_threshold: timing.warning_threshold,
+                is_critical: timing.is_critical,
+            })
+            .collect();
+        let memory = &baseline.memory_baseline;
+        let system = &baseline.system_baseline;
+        
+        Self {
+            timings,
+            target_heap_bytes: memory.target_heap_bytes,
+            max_heap_bytes: memory.max_heap_bytes,
+            target_stack_bytes: memory.target_stack_bytes,
+            max_stack_bytes: memory.max_stack_bytes,
+            target_flash_bytes: memory.target_flash_bytes,
+            max_flash_bytes: memory.max_flash_bytes,
+            max_boot_time_us: system.max_boot_time.as_micros(),
+            max_cycle_time_us: system.max_cycle_time.as_micros(),
+            target_efficiency: system.target_efficiency,
+            max_alerts_per_hour: system.max_alerts_per_hour,
+        }
+    }
+}
+
+#[cfg(feature = "storage")]
+impl From<StoredPerformanceBaseline> for PerformanceBaseline {
+    fn from(stored: StoredPerformanceBaseline) -> Self {
+        let timing_baselines = stored.timings.iter()
+            .map(|timing| TimingBaseline {
+                category: timing.category,
+                target_average: Duration::from_micros(timing.target_average_us),
+                max_acceptable: Duration::from_micros(timing.max_acceptable_us),
+                warning_threshold: timing.warning_threshold,
+                is_critical: timing.is_critical,
+            })
+            .collect();
+        
+        Self {
+            timing_baselines,
+            memory_baseline: MemoryBaseline {
+                target_heap_bytes: stored.target_heap_bytes,
+                max_heap_bytes: stored.max_heap_bytes,
+                target_stack_bytes: stored.target_stack_bytes,
+                max_stack_bytes: stored.max_stack_bytes,
+                target_flash_bytes: stored.target_flash_bytes,
+                max_flash_bytes: stored.max_flash_bytes,
+            },
+            system_baseline: SystemBaseline {
+                max_boot_time: Duration::from_micros(stored.max_boot_time_us),
+                max_cycle_time: Duration::from_micros(stored.max_cycle_time_us),
+                target_efficiency: stored.target_efficiency,
+                max_alerts_per_hour: stored.max_alerts_per_hour,
+            },
+            version: DEVICE_BASELINE_VERSION,
+            description: "Baseline restored from flash",
+        }
+    }
+}
+
+#[cfg(feature = "storage")]
+impl PerformanceBaseline {
+    /// Persist this baseline so later boots can compare against it
+    ///
+    /// The baseline is serialized with `serde_json_core` by the config store and
+    /// replaces any previously saved one (which the store keeps as its backup).
+    pub async fn save<S>(&self, store: &mut S) -> iot_storage::StorageResult<()>
+    where
+        S: iot_storage::ConfigStorage,
+    {
+        let stored = StoredPerformanceBaseline::from(self);
+        store.store_config(PERFORMANCE_BASELINE_CONFIG, &stored).await
+    }
+    
+    /// Load the baseline saved by [`save`](Self::save)
+    ///
+    /// Returns `None` when this device has no saved baseline yet.
+    pub async fn load<S>(store: &mut S) -> iot_storage::StorageResult<Option<Self>>
+    where
+        S: iot_storage::ConfigStorage,
+    {
+        if !store.config_exists(PERFORMANCE_BASELINE_CONFIG).await? {
+            return Ok(None);
+        }
+        
+        let stored: StoredPerformanceBaseline = store.retrieve_config(PERFORMANCE_BASELINE_CONFIG).await?;
+        Ok(Some(stored.into()))
+    }
+}
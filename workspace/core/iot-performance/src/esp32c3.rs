@@ -349,6 +349,90 @@ impl Default for HardwareProfiler {
     }
 }
 
+/// Heap usage reported by the global allocator
+#[derive(Debug, Clone, Copy)]
+pub struct HeapStats {
+    /// Heap memory currently allocated (bytes)
+    pub used: usize,
+
+    /// Heap memory still available (bytes)
+    pub free: usize,
+}
+
+/// Query heap usage from the `esp-alloc` global allocator
+///
+/// Returns `None` when the `heap-stats` feature is disabled, so callers
+/// can fall back to estimates on allocators that expose no statistics.
+pub fn heap_stats() -> Option<HeapStats> {
+    #[cfg(feature = "heap-stats")]
+    {
+        Some(HeapStats {
+            used: esp_alloc::HEAP.used(),
+            free: esp_alloc::HEAP.free(),
+        })
+    }
+
+    #[cfg(not(feature = "heap-stats"))]
+    {
+        None
+    }
+}
+
+/// Binary image size analysis from linker-provided section symbols
+///
+/// The esp-hal linker scripts (pulled in by each application's `build.rs`
+/// through `-Tlinkall.x`) export the boundaries of the `.text` and `.data`
+/// sections as `_stext`/`_etext` and `_sdata`/`_edata`. These symbols are
+/// *addresses*, not values: only their addresses are taken and subtracted,
+/// the memory behind them is never read.
+///
+/// Applications with a custom linker layout must export the same four
+/// symbols (or adapt the `extern` block below), otherwise linking fails
+/// with an undefined symbol error when `flash-analysis` is enabled.
+#[cfg(feature = "flash-analysis")]
+pub mod image {
+    extern "C" {
+        static _stext: u8;
+        static _etext: u8;
+        static _sdata: u8;
+        static _edata: u8;
+    }
+
+    /// Total flash size of the ESP32-C3 module (bytes)
+    pub const FLASH_TOTAL_BYTES: usize = 4 * 1024 * 1024;
+
+    /// Section sizes of the running firmware image
+    #[derive(Debug, Clone, Copy)]
+    pub struct ImageSections {
+        /// Size of the `.text` section (bytes)
+        pub text_bytes: usize,
+
+        /// Size of the `.data` section, whose initializers live in flash (bytes)
+        pub data_bytes: usize,
+    }
+
+    impl ImageSections {
+        /// Compute section sizes from the linker symbols
+        pub fn from_linker() -> Self {
+            // Only the symbol addresses are taken, nothing is dereferenced
+            let stext = core::ptr::addr_of!(_stext) as usize;
+            let etext = core::ptr::addr_of!(_etext) as usize;
+            let sdata = core::ptr::addr_of!(_sdata) as usize;
+            let edata = core::ptr::addr_of!(_edata) as usize;
+
+            Self {
+                text_bytes: etext.saturating_sub(stext),
+                data_bytes: edata.saturating_sub(sdata),
+            }
+        }
+
+        /// Flash occupied by the image (`.text` plus the `.data` load image)
+        pub fn flash_used(&self) -> usize {
+            self.text_bytes + self.data_bytes
+        }
+    }
+}
+
 /// ESP32-C3 specific performance optimization utilities
 pub mod optimization {
     use super::*;
@@ -112,7 +112,11 @@ pub use timing::{TimingCategory, CycleTimer, TimingData, TimingStatistics};
 pub use memory::{MemoryTracker, MemoryRegion, MemorySnapshot, MemoryAnalysis};
 pub use analysis::{PerformanceAnalyzer, TrendAnalysis, PerformanceTrend};
 pub use baseline::{PerformanceBaseline, BaselineComparison, BaselineStatus};
-pub use regression::{RegressionTester, RegressionResult, PerformanceThresholds};
+#[cfg(feature = "storage")]
+pub use baseline::{StoredPerformanceBaseline, StoredTimingBaseline, PERFORMANCE_BASELINE_CONFIG};
+pub use regression::{RegressionTester, RegressionResult, PerformanceThresholds, PerformanceMetric};
+#[cfg(feature = "storage")]
+pub use regression::{StoredPerformanceThresholds, StoredTimingThreshold, PERFORMANCE_THRESHOLDS_CONFIG};
 
 // Re-export commonly used types
 pub use embassy_time::{Duration, Instant};
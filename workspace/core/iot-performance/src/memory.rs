@@ -227,6 +227,7 @@ impl MemoryTracker {
         match region {
             MemoryRegion::Heap => {
                 self.current_snapshot.heap_used = usage;
+                self.current_snapshot.heap_free = snapshot.heap_free;
                 if usage > self.current_snapshot.heap_peak {
                     self.current_snapshot.heap_peak = usage;
                 }
@@ -289,12 +290,29 @@ impl MemoryTracker {
         }
     }
     
+    /// Get heap usage from the allocator, if it exposes statistics
+    ///
+    /// Returns `(used, free)` in bytes, or `None` on platforms (such as the
+    /// mock platform) whose allocator does not report usage.
+    pub fn allocator_heap_info() -> Option<(usize, usize)> {
+        #[cfg(feature = "esp32c3")]
+        {
+            crate::esp32c3::heap_stats().map(|stats| (stats.used, stats.free))
+        }
+
+        #[cfg(not(feature = "esp32c3"))]
+        {
+            None
+        }
+    }
+
     /// Get current heap memory information
     fn get_heap_info() -> (usize, usize) {
-        // In a real implementation, this would use:
-        // - esp_get_free_heap_size()
-        // - esp_get_minimum_free_heap_size()
-        // For now, return estimated values
+        if let Some(info) = Self::allocator_heap_info() {
+            return info;
+        }
+
+        // No allocator statistics available, return estimated values
         let heap_total = 32 * 1024; // 32KB heap allocation
         let heap_free = 16 * 1024;  // Estimated free
         let heap_used = heap_total - heap_free;
@@ -312,11 +330,20 @@ impl MemoryTracker {
     }
     
     /// Get flash memory usage information
+    #[cfg(feature = "flash-analysis")]
+    fn get_flash_info() -> (usize, usize) {
+        use crate::esp32c3::image::{ImageSections, FLASH_TOTAL_BYTES};
+
+        // Section sizes come from the linker symbols of the running image
+        let sections = ImageSections::from_linker();
+        (sections.flash_used(), FLASH_TOTAL_BYTES)
+    }
+
+    /// Get flash memory usage information
+    #[cfg(not(feature = "flash-analysis"))]
     fn get_flash_info() -> (usize, usize) {
-        // In a real implementation, this would use:
-        // - esp_partition_get_used_bytes()
-        // - esp_partition_get_total_bytes()
-        // For now, return estimated values
+        // Without `flash-analysis` the linker symbols are not available,
+        // so return estimated values
         let flash_used = 256 * 1024; // Estimated 256KB used
         let flash_total = 4 * 1024 * 1024; // 4MB total flash
         (flash_used, flash_total)
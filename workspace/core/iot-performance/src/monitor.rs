@@ -12,7 +12,7 @@ use core::fmt::Write;
 use crate::timing::{TimingCategory, TimingData, TimingStatistics};
 use crate::memory::{MemoryTracker, MemorySnapshot, MemoryRegion};
 use crate::baseline::{PerformanceBaseline, BaselineComparison};
-use iot_common::IoTResult;
+use iot_common::{BootClock, IoTResult};
 
 /// Main performance monitoring coordinator
 /// 
@@ -28,8 +28,8 @@ pub struct PerformanceMonitor {
     /// Performance baseline for comparison
     baseline: PerformanceBaseline,
     
-    /// System start time for uptime calculations
-    start_time: Instant,
+    /// Uptime source shared with the rest of the system
+    boot_clock: BootClock,
     
     /// Alert threshold configuration
     alert_config: AlertConfiguration,
@@ -173,11 +173,17 @@ impl PerformanceMonitor {
             timing_data: Mutex::new(TimingData::new()),
             memory_tracker: Mutex::new(MemoryTracker::new()),
             baseline: PerformanceBaseline::phase_2_targets(),
-            start_time: Instant::now(),
+            boot_clock: BootClock::start(),
             alert_config: config,
         }
     }
     
+    /// Report uptime from the system's boot clock instead of from creation
+    pub fn with_boot_clock(mut self, boot_clock: BootClock) -> Self {
+        self.boot_clock = boot_clock;
+        self
+    }
+    
     /// Record a timing measurement for a specific category
     pub async fn record_cycle_time(&self, category: TimingCategory, duration: Duration) {
         let mut timing_data = self.timing_data.lock().await;
@@ -214,7 +220,7 @@ impl PerformanceMonitor {
         let timing_data = self.timing_data.lock().await;
         let memory_tracker = self.memory_tracker.lock().await;
         
-        let uptime = self.start_time.elapsed();
+        let uptime = self.boot_clock.uptime();
         let timing_stats = timing_data.get_statistics();
         let memory_usage = memory_tracker.get_current_snapshot();
         let baseline_comparison = self.baseline.compare_current_performance(&timing_stats, &memory_usage);
@@ -336,7 +342,7 @@ impl PerformanceMonitor {
     
     /// Get system uptime in seconds
     pub fn get_uptime_seconds(&self) -> u64 {
-        self.start_time.elapsed().as_secs()
+        self.boot_clock.uptime().as_secs()
     }
     
     /// Reset all performance counters and start fresh monitoring
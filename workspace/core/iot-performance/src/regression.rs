@@ -6,16 +6,28 @@
 use embassy_time::{Duration, Instant};
 use heapless::{Vec, String};
 use core::fmt::Write;
+use iot_common::{IoTError, IoTResult, ConfigError};
+use iot_common::error::utils::error_message;
 
 use crate::timing::{TimingStatistics, TimingCategory};
 use crate::memory::MemorySnapshot;
 use crate::baseline::PerformanceBaseline;
 
+/// Configuration name used to persist regression thresholds in a `ConfigStore`
+#[cfg(feature = "storage")]
+pub const PERFORMANCE_THRESHOLDS_CONFIG: &str = "perf_thresholds";
+
+/// Runs of a metric needed before its trend is extrapolated
+const MIN_PREDICTION_SAMPLES: usize = 4;
+
 /// Automated performance regression tester
 pub struct RegressionTester {
     /// Reference baselines for regression comparison
     reference_baselines: Vec<PerformanceBaseline, 4>,
     
+    /// Baseline measured on this device, preferred over the compiled-in ones
+    device_baseline: Option<PerformanceBaseline>,
+    
     /// Performance thresholds for regression detection
     thresholds: PerformanceThresholds,
     
@@ -43,6 +55,10 @@ pub struct RegressionConfig {
     
     /// Maximum acceptable p-value for statistical tests
     pub max_p_value: f32,
+    
+    /// Test runs ahead a metric's trend is projected for early warnings
+    /// (0 disables prediction)
+    pub prediction_horizon: u32,
 }
 
 impl Default for RegressionConfig {
@@ -53,6 +69,7 @@ impl Default for RegressionConfig {
             statistical_testing: true,
             confidence_level: 0.95,             // 95% confidence
             max_p_value: 0.05,                  // 5% p-value threshold
+            prediction_horizon: 5,              // warn 5 runs ahead
         }
     }
 }
@@ -70,6 +87,240 @@ pub struct PerformanceThresholds {
     pub system_thresholds: SystemThresholds,
 }
 
+impl Default for PerformanceThresholds {
+    /// Compiled-in limits for a mains-powered ESP32-C3 node
+    fn default() -> Self {
+        let timing_thresholds = [
+            TimingThreshold {
+                category: TimingCategory::SensorReading,
+                baseline_time: Duration::from_micros(450),
+                max_time: Duration::from_micros(500),
+                enabled: true,
+            },
+            TimingThreshold {
+                category: TimingCategory::MqttPublish,
+                baseline_time: Duration::from_millis(300),
+                max_time: Duration::from_millis(500),
+                enabled: true,
+            },
+            TimingThreshold {
+                category: TimingCategory::NetworkOperation,
+                baseline_time: Duration::from_millis(3500),
+                max_time: Duration::from_millis(5000),
+                enabled: true,
+            },
+            TimingThreshold {
+                category: TimingCategory::SystemBoot,
+                baseline_time: Duration::from_millis(2300),
+                max_time: Duration::from_millis(2500),
+                enabled: true,
+            },
+            TimingThreshold {
+                category: TimingCategory::ConsoleCommand,
+                baseline_time: Duration::from_millis(50),
+                max_time: Duration::from_millis(100),
+                enabled: false,
+            },
+            TimingThreshold {
+                category: TimingCategory::I2cOperation,
+                baseline_time: Duration::from_micros(100),
+                max_time: Duration::from_micros(200),
+                enabled: false,
+            },
+            TimingThreshold {
+                category: TimingCategory::TaskScheduling,
+                baseline_time: Duration::from_micros(10),
+                max_time: Duration::from_micros(50),
+                enabled: false,
+            },
+            TimingThreshold {
+                category: TimingCategory::SystemCycle,
+                baseline_time: Duration::from_secs(30),
+                max_time: Duration::from_secs(35),
+                enabled: false,
+            },
+        ];
+        
+        let memory_thresholds = MemoryThresholds {
+            max_heap_increase: 4 * 1024,    // 4KB increase maximum
+            max_stack_increase: 2 * 1024,   // 2KB increase maximum
+            max_flash_increase: 32 * 1024,  // 32KB increase maximum
+            max_fragmentation_increase: 10.0, // 10% fragmentation increase
+        };
+        
+        let system_thresholds = SystemThresholds {
+            max_boot_time_increase: Duration::from_millis(200),
+            min_efficiency: 0.80,
+            max_alert_frequency: 5.0,
+        };
+        
+        Self {
+            timing_thresholds,
+            memory_thresholds,
+            system_thresholds,
+        }
+    }
+}
+
+impl PerformanceThresholds {
+    /// Reject thresholds that can never pass or never fail
+    ///
+    /// Every timing category needs `max_time >= baseline_time` (checked for
+    /// disabled ones too, so enabling one later cannot expose an inverted
+    /// pair) and the minimum efficiency must lie within 0.0..=1.0.
+    pub fn validate(&self) -> IoTResult<()> {
+        for threshold in &self.timing_thresholds {
+            if threshold.max_time < threshold.baseline_time {
+                let mut msg: String<64> = String::new();
+                let _ = write!(msg, "{:?} max_time below baseline_time", threshold.category);
+                return Err(IoTError::configuration(ConfigError::ValidationError(error_message(&msg))));
+            }
+        }
+        
+        let min_efficiency = self.system_thresholds.min_efficiency;
+        if !(0.0..=1.0).contains(&min_efficiency) {
+            return Err(IoTError::configuration(ConfigError::ValidationError(
+                error_message("min_efficiency must be within 0.0..=1.0")
+            )));
+        }
+        Ok(())
+    }
+    
+    /// Threshold configured for a timing category
+    pub fn timing_threshold(&self, category: TimingCategory) -> Option<&TimingThreshold> {
+        self.timing_thresholds.iter().find(|threshold| threshold.category == category)
+    }
+}
+
+/// Persisted form of a [`TimingThreshold`], durations in microseconds
+#[cfg(feature = "storage")]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StoredTimingThreshold {
+    /// Operation category
+    pub category: TimingCategory,
+    /// Baseline time for comparison
+    pub baseline_time_us: u64,
+    /// Maximum acceptable time
+    pub max_time_us: u64,
+    /// Whether this threshold is enabled
+    pub enabled: bool,
+}
+
+/// Persisted form of [`PerformanceThresholds`]
+///
+/// Lets a deployment tune its limits (e.g. a longer boot time on a battery
+/// node) without recompiling. Categories missing from `timings` keep their
+/// compiled-in defaults when loaded.
+#[cfg(feature = "storage")]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StoredPerformanceThresholds {
+    /// Timing thresholds by category
+    pub timings: Vec<StoredTimingThreshold, 8>,
+    /// See [`MemoryThresholds::max_heap_increase`]
+    pub max_heap_increase: usize,
+    /// See [`MemoryThresholds::max_stack_increase`]
+    pub max_stack_increase: usize,
+    /// See [`MemoryThresholds::max_flash_increase`]
+    pub max_flash_increase: usize,
+    /// See [`MemoryThresholds::max_fragmentation_increase`]
+    pub max_fragmentation_increase: f32,
+    /// Maximum boot time increase
+    pub max_boot_time_increase_us: u64,
+    /// See [`SystemThresholds::min_efficiency`]
+    pub min_efficiency: f32,
+    /// See [`SystemThresholds::max_alert_frequency`]
+    pub max_alert_frequency: f32,
+}
+
+#[cfg(feature = "storage")]
+impl From<&PerformanceThresholds> for StoredPerformanceThresholds {
+    fn from(thresholds: &PerformanceThresholds) -> Self {
+        let timings = thresholds.timing_thresholds.iter()
+            .map(|threshold| StoredTimingThreshold {
+                category: threshold.category,
+                baseline_time_us: threshold.baseline_time.as_micros(),
+                max_time_us: threshold.max_time.as_micros(),
+                enabled: threshold.enabled,
+            })
+            .collect();
+        let memory = &thresholds.memory_thresholds;
+        let system = &thresholds.system_thresholds;
+        
+        Self {
+            timings,
+            max_heap_increase: memory.max_heap_increase,
+            max_stack_increase: memory.max_stack_increase,
+            max_flash_increase: memory.max_flash_increase,
+            max_fragmentation_increase: memory.max_fragmentation_increase,
+            max_boot_time_increase_us: system.max_boot_time_increase.as_micros(),
+            min_efficiency: system.min_efficiency,
+            max_alert_frequency: system.max_alert_frequency,
+        }
+    }
+}
+
+#[cfg(feature = "storage")]
+impl From<StoredPerformanceThresholds> for PerformanceThresholds {
+    fn from(stored: StoredPerformanceThresholds) -> Self {
+        let mut thresholds = Self::default();
+        for timing in &stored.timings {
+            if let Some(threshold) = thresholds.timing_thresholds.iter_mut()
+                .find(|threshold| threshold.category == timing.category)
+            {
+                threshold.baseline_time = Duration::from_micros(timing.baseline_time_us);
+                threshold.max_time = Duration::from_micros(timing.max_time_us);
+                threshold.enabled = timing.enabled;
+            }
+        }
+        
+        thresholds.memory_thresholds = MemoryThresholds {
+            max_heap_increase: stored.max_heap_increase,
+            max_stack_increase: stored.max_stack_increase,
+            max_flash_increase: stored.max_flash_increase,
+            max_fragmentation_increase: stored.max_fragmentation_increase,
+        };
+        thresholds.system_thresholds = SystemThresholds {
+            max_boot_time_increase: Duration::from_micros(stored.max_boot_time_increase_us),
+            min_efficiency: stored.min_efficiency,
+            max_alert_frequency: stored.max_alert_frequency,
+        };
+        thresholds
+    }
+}
+
+#[cfg(feature = "storage")]
+impl PerformanceThresholds {
+    /// Persist these thresholds for later boots
+    ///
+    /// Inverted thresholds are rejected before anything is written.
+    pub async fn save<S>(&self, store: &mut S) -> iot_storage::StorageResult<()>
+    where
+        S: iot_storage::ConfigStorage,
+    {
+        if self.validate().is_err() {
+            return Err(iot_storage::StorageError::InvalidValue);
+        }
+        let stored = StoredPerformanceThresholds::from(self);
+        store.store_config(PERFORMANCE_THRESHOLDS_CONFIG, &stored).await
+    }
+    
+    /// Load the thresholds saved by [`save`](Self::save)
+    ///
+    /// Returns `None` when this device has no saved thresholds. The result is
+    /// not validated here; [`RegressionTester::with_thresholds`] does that.
+    pub async fn load<S>(store: &mut S) -> iot_storage::StorageResult<Option<Self>>
+    where
+        S: iot_storage::ConfigStorage,
+    {
+        if !store.config_exists(PERFORMANCE_THRESHOLDS_CONFIG).await? {
+            return Ok(None);
+        }
+        
+        let stored: StoredPerformanceThresholds = store.retrieve_config(PERFORMANCE_THRESHOLDS_CONFIG).await?;
+        Ok(Some(stored.into()))
+    }
+}
+
 /// Timing threshold for a specific category
 #[derive(Debug, Clone, Copy)]
 pub struct TimingThreshold {
@@ -179,7 +430,7 @@ pub struct MetricRegressionResult {
 }
 
 /// Performance metrics that can be tested for regression
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PerformanceMetric {
     /// Sensor reading time
     SensorReadingTime,
@@ -315,83 +566,45 @@ impl RegressionTester {
         
         Self {
             reference_baselines,
-            thresholds: Self::create_default_thresholds(),
+            device_baseline: None,
+            thresholds: PerformanceThresholds::default(),
             test_history: Vec::new(),
             config,
         }
     }
     
-    /// Create default performance thresholds
-    fn create_default_thresholds() -> PerformanceThresholds {
-        let timing_thresholds = [
-            TimingThreshold {
-                category: TimingCategory::SensorReading,
-                baseline_time: Duration::from_micros(450),
-                max_time: Duration::from_micros(500),
-                enabled: true,
-            },
-            TimingThreshold {
-                category: TimingCategory::MqttPublish,
-                baseline_time: Duration::from_millis(300),
-                max_time: Duration::from_millis(500),
-                enabled: true,
-            },
-            TimingThreshold {
-                category: TimingCategory::NetworkOperation,
-                baseline_time: Duration::from_millis(3500),
-                max_time: Duration::from_millis(5000),
-                enabled: true,
-            },
-            TimingThreshold {
-                category: TimingCategory::SystemBoot,
-                baseline_time: Duration::from_millis(2300),
-                max_time: Duration::from_millis(2500),
-                enabled: true,
-            },
-            TimingThreshold {
-                category: TimingCategory::ConsoleCommand,
-                baseline_time: Duration::from_millis(50),
-                max_time: Duration::from_millis(100),
-                enabled: false,
-            },
-            TimingThreshold {
-                category: TimingCategory::I2cOperation,
-                baseline_time: Duration::from_micros(100),
-                max_time: Duration::from_micros(200),
-                enabled: false,
-            },
-            TimingThreshold {
-                category: TimingCategory::TaskScheduling,
-                baseline_time: Duration::from_micros(10),
-                max_time: Duration::from_micros(50),
-                enabled: false,
-            },
-            TimingThreshold {
-                category: TimingCategory::SystemCycle,
-                baseline_time: Duration::from_secs(30),
-                max_time: Duration::from_secs(35),
-                enabled: false,
-            },
-        ];
-        
-        let memory_thresholds = MemoryThresholds {
-            max_heap_increase: 4 * 1024,    // 4KB increase maximum
-            max_stack_increase: 2 * 1024,   // 2KB increase maximum
-            max_flash_increase: 32 * 1024,  // 32KB increase maximum
-            max_fragmentation_increase: 10.0, // 10% fragmentation increase
-        };
-        
-        let system_thresholds = SystemThresholds {
-            max_boot_time_increase: Duration::from_millis(200),
-            min_efficiency: 0.80,
-            max_alert_frequency: 5.0,
-        };
-        
-        PerformanceThresholds {
-            timing_thresholds,
-            memory_thresholds,
-            system_thresholds,
-        }
+    /// Compare against a baseline measured on this device
+    ///
+    /// Timing and memory changes are then computed relative to the device's
+    /// own history (typically a baseline restored with
+    /// `PerformanceBaseline::load`) instead of the Phase 0 figures. The configured thresholds still decide pass/fail.
+    pub fn set_device_baseline(&mut self, baseline: PerformanceBaseline) {
+        self.device_baseline = Some(baseline);
+    }
+    
+    /// Use deployment-specific thresholds instead of the compiled-in ones
+    ///
+    /// Typically thresholds restored with `PerformanceThresholds::load`.
+    /// Inverted thresholds (`max_time < baseline_time`) are rejected.
+    pub fn with_thresholds(mut self, thresholds: PerformanceThresholds) -> IoTResult<Self> {
+        thresholds.validate()?;
+        self.thresholds = thresholds;
+        Ok(self)
+    }
+    
+    /// Get the thresholds in use
+    pub fn thresholds(&self) -> &PerformanceThresholds {
+        &self.thresholds
+    }
+    
+    /// Get the device baseline, if one has been set
+    pub fn device_baseline(&self) -> Option<&PerformanceBaseline> {
+        self.device_baseline.as_ref()
+    }
+    
+    /// Baseline that changes are measured against
+    fn reference_baseline(&self) -> &PerformanceBaseline {
+        self.device_baseline.as_ref().unwrap_or(&self.reference_baselines[0])
     }
     
     /// Execute comprehensive regression test
@@ -414,6 +627,9 @@ impl RegressionTester {
         // Test system metrics
         self.test_system_metrics(timing_stats, memory_snapshot, &mut metric_results, &mut regressions);
         
+        // Warn about metrics trending towards their thresholds
+        self.predict_metric_regressions(&metric_results, &mut regressions);
+        
         // Perform statistical analysis
         let statistical_results = self.perform_statistical_analysis(&metric_results);
         
@@ -454,7 +670,10 @@ impl RegressionTester {
             
             if let Some(current_time) = timing_stats.get_average_time(threshold.category) {
                 let current_value = current_time.as_micros() as f32;
-                let baseline_value = threshold.baseline_time.as_micros() as f32;
+                let baseline_time = self.device_baseline.as_ref()
+                    .and_then(|baseline| baseline.timing_baseline(threshold.category))
+                    .map_or(threshold.baseline_time, |baseline| baseline.target_average);
+                let baseline_value = baseline_time.as_micros() as f32;
                 let change_percent = ((current_value - baseline_value) / baseline_value) * 100.0;
                 
                 let passed = current_time <= threshold.max_time;
@@ -503,8 +722,8 @@ impl RegressionTester {
         metric_results: &mut Vec<MetricRegressionResult, 16>,
         regressions: &mut Vec<DetectedRegression, 8>,
     ) {
-        // Get baseline memory usage (Phase 0 baseline)
-        let baseline = &self.reference_baselines[0].memory_baseline;
+        // Get baseline memory usage (device baseline, else Phase 0)
+        let baseline = &self.reference_baseline().memory_baseline;
         
         // Test heap usage
         let heap_change = memory_snapshot.heap_used as i32 - baseline.target_heap_bytes as i32;
@@ -672,6 +891,127 @@ impl RegressionTester {
         let _ = regressions.push(regression);
     }
     
+    /// Threshold a metric must stay below, if it has one
+    fn metric_limit(&self, metric: PerformanceMetric) -> Option<f32> {
+        let memory = &self.thresholds.memory_thresholds;
+        let baseline = &self.reference_baseline().memory_baseline;
+        let category = match metric {
+            PerformanceMetric::SensorReadingTime => TimingCategory::SensorReading,
+            PerformanceMetric::MqttPublishTime => TimingCategory::MqttPublish,
+            PerformanceMetric::NetworkConnectionTime => TimingCategory::NetworkOperation,
+            PerformanceMetric::BootTime => TimingCategory::SystemBoot,
+            PerformanceMetric::ConsoleResponseTime => TimingCategory::ConsoleCommand,
+            PerformanceMetric::TaskSchedulingLatency => TimingCategory::TaskScheduling,
+            PerformanceMetric::HeapUsage => {
+                return Some((baseline.target_heap_bytes + memory.max_heap_increase) as f32);
+            }
+            PerformanceMetric::StackUsage => {
+                return Some((baseline.target_stack_bytes + memory.max_stack_increase) as f32);
+            }
+            _ => return None,
+        };
+        
+        self.thresholds.timing_thresholds.iter()
+            .find(|threshold| threshold.enabled && threshold.category == category)
+            .map(|threshold| threshold.max_time.as_micros() as f32)
+    }
+    
+    /// Add early warnings for metrics projected to cross their threshold
+    ///
+    /// Each metric's values over the stored history plus the current run are
+    /// fitted with a straight line. A metric still within its limit whose
+    /// line crosses it within [`RegressionConfig::prediction_horizon`] runs
+    /// gets a [`RegressionSeverity::Minor`] regression, unless the current
+    /// run already reported one for it.
+    fn predict_metric_regressions(
+        &self,
+        metric_results: &Vec<MetricRegressionResult, 16>,
+        regressions: &mut Vec<DetectedRegression, 8>,
+    ) {
+        for current in metric_results {
+            if regressions.iter().any(|r| r.metric == current.metric) {
+                continue;
+            }
+            
+            // Older runs first; the current run completes the 16-entry window
+            let mut series: Vec<f32, 16> = Vec::new();
+            let skip = self.test_history.len().saturating_sub(series.capacity() - 1);
+            for run in self.test_history.iter().skip(skip) {
+                if let Some(result) = run.metric_results.iter().find(|r| r.metric == current.metric) {
+                    let _ = series.push(result.current_value);
+                }
+            }
+            let _ = series.push(current.current_value);
+            
+            if let Some(regression) = self.predict_crossing(current.metric, &series) {
+                let _ = regressions.push(regression);
+            }
+        }
+    }
+    
+    /// Project a metric's series forward and build an early warning
+    ///
+    /// Returns `None` with too few samples, when the metric is already over
+    /// its limit (the regular checks handle that) or when the fitted line
+    /// stays below the limit for the whole prediction horizon.
+    fn predict_crossing(&self, metric: PerformanceMetric, series: &[f32]) -> Option<DetectedRegression> {
+        let horizon = self.config.prediction_horizon;
+        if horizon == 0 || series.len() < MIN_PREDICTION_SAMPLES {
+            return None;
+        }
+        
+        let limit = self.metric_limit(metric)?;
+        let latest = *series.last()?;
+        if latest >= limit {
+            return None;
+        }
+        
+        let (slope, intercept) = linear_fit(series)?;
+        if slope <= 0.0 {
+            return None;
+        }
+        
+        let last_x = (series.len() - 1) as f32;
+        let projected = intercept + slope * (last_x + horizon as f32);
+        if projected < limit {
+            return None;
+        }
+        
+        // Runs until the fitted line reaches the limit, at least the next one
+        let runs = (limit - (intercept + slope * last_x)) / slope;
+        let mut runs_until = runs as u32;
+        if (runs_until as f32) < runs {
+            runs_until += 1;
+        }
+        let runs_until = runs_until.max(1);
+        let degradation_percent = ((projected - limit) / limit) * 100.0;
+        
+        let mut recommendations = Vec::new();
+        let _ = recommendations.push(RegressionRecommendation::ReviewCodeChanges);
+        if matches!(metric, PerformanceMetric::HeapUsage | PerformanceMetric::StackUsage) {
+            let _ = recommendations.push(RegressionRecommendation::CheckResourceLeaks);
+        } else {
+            let _ = recommendations.push(RegressionRecommendation::OptimizeCriticalPath);
+        }
+        
+        let mut description = String::new();
+        let _ = write!(
+            description,
+            "{:?} trending +{:.1}/run, projected to exceed {:.1} in {} runs",
+            metric, slope, limit, runs_until
+        );
+        
+        Some(DetectedRegression {
+            metric,
+            severity: RegressionSeverity::Minor,
+            degradation_percent,
+            expected_value: limit,
+            actual_value: projected,
+            recommendations,
+            description,
+        })
+    }
+    
     /// Perform statistical analysis of regression test results
     fn perform_statistical_analysis(&self, metric_results: &Vec<MetricRegressionResult, 16>) -> StatisticalAnalysis {
         if !self.config.statistical_testing || metric_results.is_empty() {
@@ -769,6 +1109,59 @@ impl RegressionTester {
         
         Some(RegressionTrend { trend, recent_failures })
     }
+    
+    /// Early warnings from the stored history alone
+    ///
+    /// Same projection as the one run by
+    /// [`execute_regression_test`](Self::execute_regression_test), using the
+    /// metrics of the latest run as the current values.
+    pub fn predict_regressions(&self) -> Vec<DetectedRegression, 8> {
+        let mut predictions = Vec::new();
+        let Some(latest) = self.test_history.last() else {
+            return predictions;
+        };
+        
+        for current in &latest.metric_results {
+            let mut series: Vec<f32, 16> = Vec::new();
+            for run in &self.test_history {
+                if let Some(result) = run.metric_results.iter().find(|r| r.metric == current.metric) {
+                    let _ = series.push(result.current_value);
+                }
+            }
+            if let Some(regression) = self.predict_crossing(current.metric, &series) {
+                let _ = predictions.push(regression);
+            }
+        }
+        predictions
+    }
+}
+
+/// Least-squares line through `values` at x = 0, 1, 2...
+///
+/// Returns `(slope, intercept)`, or `None` for fewer than two points.
+fn linear_fit(values: &[f32]) -> Option<(f32, f32)> {
+    if values.len() < 2 {
+        return None;
+    }
+    
+    let n = values.len() as f32;
+    let (mut sum_x, mut sum_y, mut sum_xy, mut sum_xx) = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+    for (i, &y) in values.iter().enumerate() {
+        let x = i as f32;
+        sum_x += x;
+        sum_y += y;
+        sum_xy += x * y;
+        sum_xx += x * x;
+    }
+    
+    let denominator = n * sum_xx - sum_x * sum_x;
+    if denominator == 0.0 {
+        return None;
+    }
+    
+    let slope = (n * sum_xy - sum_x * sum_y) / denominator;
+    let intercept = (sum_y - slope * sum_x) / n;
+    Some((slope, intercept))
 }
 
 /// Regression trend analysis
@@ -798,4 +1191,64 @@ impl Default for RegressionTester {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear_fit() {
+        let (slope, intercept) = linear_fit(&[1.0, 3.0, 5.0, 7.0]).unwrap();
+        assert!((slope - 2.0).abs() < 1e-6);
+        assert!((intercept - 1.0).abs() < 1e-6);
+        assert!(linear_fit(&[5.0]).is_none());
+    }
+
+    #[test]
+    fn test_trending_metric_predicts_regression() {
+        let tester = RegressionTester::new();
+
+        // Sensor reads slowing by 10μs per run towards the 500μs limit
+        let series = [420.0, 430.0, 440.0, 450.0, 460.0];
+        let warning = tester.predict_crossing(PerformanceMetric::SensorReadingTime, &series).unwrap();
+        assert_eq!(warning.severity, RegressionSeverity::Minor);
+        assert_eq!(warning.expected_value, 500.0);
+        assert!(warning.actual_value >= 500.0);
+        assert!(warning.description.contains("in 4 runs"));
+
+        // Flat, improving, slow, short or already failing series give no warning
+        let metric = PerformanceMetric::SensorReadingTime;
+        assert!(tester.predict_crossing(metric, &[450.0; 6]).is_none());
+        assert!(tester.predict_crossing(metric, &[480.0, 470.0, 460.0, 450.0]).is_none());
+        assert!(tester.predict_crossing(metric, &[400.0, 401.0, 402.0, 403.0]).is_none());
+        assert!(tester.predict_crossing(metric, &[460.0, 480.0, 490.0]).is_none());
+        assert!(tester.predict_crossing(metric, &[480.0, 490.0, 500.0, 510.0]).is_none());
+
+        // Metrics without a threshold are never projected
+        assert!(tester.predict_crossing(PerformanceMetric::FlashUsage, &series).is_none());
+    }
+
+    #[test]
+    fn test_with_thresholds_rejects_inverted_limits() {
+        // Battery node: slower boots are acceptable
+        let mut thresholds = PerformanceThresholds::default();
+        for threshold in thresholds.timing_thresholds.iter_mut() {
+            if threshold.category == TimingCategory::SystemBoot {
+                threshold.max_time = Duration::from_millis(6000);
+            }
+        }
+        let tester = RegressionTester::new().with_thresholds(thresholds).unwrap();
+        let boot = tester.thresholds().timing_threshold(TimingCategory::SystemBoot).unwrap();
+        assert_eq!(boot.max_time, Duration::from_millis(6000));
+
+        // A limit below its baseline can never pass, even while disabled
+        let mut inverted = PerformanceThresholds::default();
+        inverted.timing_thresholds[4].max_time = Duration::from_millis(10);
+        assert!(!inverted.timing_thresholds[4].enabled);
+        assert!(RegressionTester::new().with_thresholds(inverted).is_err());
+
+        let mut efficiency = PerformanceThresholds::default();
+        efficiency.system_thresholds.min_efficiency = 1.5;
+        assert!(efficiency.validate().is_err());
+    }
+}
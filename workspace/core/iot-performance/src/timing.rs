@@ -23,6 +23,7 @@ fn int_sqrt(value: u64) -> u64 {
 
 /// Categories of operations that can be timed
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "storage", derive(serde::Serialize, serde::Deserialize))]
 pub enum TimingCategory {
     /// BME280 sensor reading operations
     SensorReading,
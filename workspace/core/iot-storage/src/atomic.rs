@@ -6,6 +6,7 @@
 use heapless::{Vec, FnvIndexMap};
 use embassy_time::{Duration, Timer};
 use alloc::{boxed::Box, string::String};
+use iot_common::{SharedTimestampProvider, UptimeTimestampProvider};
 use crate::{
     traits::{StorageBackend, AtomicStorage, StorageKey, StorageValue, StorageError, StorageResult, TransactionId},
 };
@@ -52,20 +53,20 @@ pub struct StorageTransaction {
     pub state: TransactionState,
     /// Operations performed in this transaction
     pub operations: Vec<TransactionOperation, 32>,
-    /// Transaction creation timestamp
+    /// Transaction creation time (monotonic ms, see [`TimestampProvider`](iot_common::TimestampProvider))
     pub created_at: u64,
     /// Transaction timeout duration
     pub timeout_ms: u64,
 }
 
 impl StorageTransaction {
-    /// Create new transaction
-    pub fn new(id: TransactionId, timeout_ms: u64) -> Self {
+    /// Create new transaction started at `created_at`
+    pub fn new(id: TransactionId, created_at: u64, timeout_ms: u64) -> Self {
         Self {
             id,
             state: TransactionState::Active,
             operations: Vec::new(),
-            created_at: 0, // In real implementation, would use actual timestamp
+            created_at,
             timeout_ms,
         }
     }
@@ -111,6 +112,8 @@ pub struct AtomicStorageManager<B: StorageBackend> {
     default_timeout_ms: u64,
     /// Maximum concurrent transactions
     max_transactions: usize,
+    /// Clock for transaction start and expiry
+    clock: SharedTimestampProvider,
     /// Storage backend marker for type safety
     _backend_marker: core::marker::PhantomData<B>,
 }
@@ -123,10 +126,19 @@ impl<B: StorageBackend> AtomicStorageManager<B> {
             next_transaction_id: 1,
             default_timeout_ms: 30000, // 30 seconds
             max_transactions: 8,
+            clock: &UptimeTimestampProvider,
             _backend_marker: core::marker::PhantomData,
         })
     }
 
+    /// Use `clock` for transaction timestamps instead of uptime
+    /// 
+    /// Expiry only uses the monotonic part of the clock, so a later NTP sync
+    /// never times out running transactions.
+    pub fn set_timestamp_provider(&mut self, clock: SharedTimestampProvider) {
+        self.clock = clock;
+    }
+
 
     /// Generate next transaction ID
     fn next_id(&mut self) -> TransactionId {
@@ -137,7 +149,7 @@ impl<B: StorageBackend> AtomicStorageManager<B> {
 
     /// Clean up expired transactions
     pub async fn cleanup_expired_transactions(&mut self) -> StorageResult<usize> {
-        let current_time = 0; // In real implementation, would use actual timestamp
+        let current_time = self.clock.get_timestamp_ms();
         let mut expired_transactions: Vec<TransactionId, {crate::MAX_TRANSACTIONS}> = Vec::new();
 
         // Find expired transactions
@@ -240,6 +252,75 @@ impl<B: StorageBackend> AtomicStorageManager<B> {
     }
 }
 
+impl<B: StorageBackend + Send + Sync> AtomicStorageManager<B> {
+    /// Stores `new` under `key` only if the current value equals `expected`
+    ///
+    /// The read, comparison and write run inside one transaction, so the key
+    /// is locked against [`atomic_store`](AtomicStorage::atomic_store) and
+    /// [`atomic_delete`](AtomicStorage::atomic_delete) from other transactions
+    /// meanwhile. `expected: None` means "key absent": the swap then only
+    /// succeeds if nothing is stored yet, which makes one-time provisioning
+    /// idempotent. Callers bumping a counter re-read and retry on `Ok(false)`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` - Value matched and `new` was written
+    /// * `Ok(false)` - Value differed (or presence differed); nothing written
+    /// * `Err(StorageError::TransactionConflict)` - Another transaction has a pending operation on `key`
+    /// * `Err(StorageError)` - Backend read or write failed
+    pub async fn compare_and_swap(
+        &mut self,
+        backend: &mut B,
+        key: &StorageKey,
+        expected: Option<&StorageValue>,
+        new: &StorageValue,
+    ) -> StorageResult<bool> {
+        if self.check_conflict(key).is_some() {
+            return Err(StorageError::TransactionConflict);
+        }
+
+        let transaction_id = self.begin_transaction().await?;
+
+        let current = match backend.retrieve(key).await {
+            Ok(value) => Some(value),
+            Err(StorageError::KeyNotFound) => None,
+            Err(e) => {
+                let _ = self.rollback_transaction(transaction_id).await;
+                return Err(e);
+            }
+        };
+
+        if current.as_ref().map(StorageValue::as_bytes) != expected.map(StorageValue::as_bytes) {
+            self.rollback_transaction(transaction_id).await?;
+            return Ok(false);
+        }
+
+        // Record the write so the key stays locked until it lands
+        let operation = TransactionOperation::Store {
+            key: key.clone(),
+            value: new.clone(),
+            original_value: current,
+        };
+        if let Err(e) = self.get_transaction_mut(transaction_id)
+            .ok_or(StorageError::TransactionConflict)
+            .and_then(|transaction| transaction.add_operation(operation))
+        {
+            let _ = self.rollback_transaction(transaction_id).await;
+            return Err(e);
+        }
+
+        if let Err(e) = backend.store(key, new).await {
+            let _ = self.rollback_transaction(transaction_id).await;
+            return Err(e);
+        }
+
+        // The write is already durable; unlike commit_transaction there is
+        // nothing left to verify, so the transaction is retired right away
+        self.transactions.remove(&transaction_id);
+        Ok(true)
+    }
+}
+
 #[async_trait::async_trait]
 impl<B: StorageBackend + Send + Sync> AtomicStorage for AtomicStorageManager<B> {
     async fn begin_transaction(&mut self) -> StorageResult<TransactionId> {
@@ -253,7 +334,7 @@ impl<B: StorageBackend + Send + Sync> AtomicStorage for AtomicStorageManager<B>
 
         // Generate new transaction ID
         let id = self.next_id();
-        let transaction = StorageTransaction::new(id, self.default_timeout_ms);
+        let transaction = StorageTransaction::new(id, self.clock.get_timestamp_ms(), self.default_timeout_ms);
 
         // Store transaction
         self.transactions.insert(id, transaction)
@@ -2,14 +2,130 @@
 //!
 //! Specialized storage for system configuration with atomic updates,
 //! backup/restore capabilities, and validation.
-
+//!
+//! ## Rollback slots
+//!
+//! Every configuration keeps two slots: the current value and the value it
+//! replaced. `store_config` moves the current value into the previous slot
+//! before writing, and [`ConfigStore::rollback`] swaps the two back. Only one
+//! previous version is kept so the flash footprint stays at twice the config
+//! size.
+//!
+//! Wear: each save now costs two writes (copy to the previous slot, then the
+//! new value), doubling erase cycles on the config region. Configuration is
+//! written rarely enough that this stays far below the flash endurance, but
+//! callers should not use `store_config` for frequently changing data.
+//!
+//! ## Change notifications
+//!
+//! Every store, delete, restore or rollback publishes a [`ConfigChange`]
+//! naming the configuration, so tasks that cache a value (the WiFi manager's
+//! credentials, a sensor interval) can pick up the new one without a reboot:
+//!
+//! ```rust,ignore
+//! let mut changes = ConfigStore::<B>::subscribe()?;
+//! loop {
+//!     let change = changes.next().await;
+//!     if change.name == "wifi" {
+//!         // re-read the credentials and reconnect
+//!     }
+//! }
+//! ```
+//!
+//! Delivery is best-effort: with no subscriber the change is dropped, and a
+//! subscriber that falls more than [`CONFIG_CHANGE_QUEUE_DEPTH`] changes
+//! behind loses the oldest ones. At most [`MAX_CONFIG_SUBSCRIBERS`] can listen
+//! at once. Code that persists configuration outside `ConfigStore` announces
+//! it with [`notify_config_change`].
+
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::pubsub::{PubSubChannel, Subscriber, WaitResult};
 use heapless::{String, Vec, FnvIndexMap};
 use serde::{Serialize, Deserialize};
 use alloc::{format, boxed::Box, vec::Vec as AllocVec, string::ToString};
+use iot_common::{SharedTimestampProvider, UptimeTimestampProvider};
 use crate::{
     traits::{StorageBackend, ConfigStorage, StorageKey, StorageValue, StorageError, StorageResult},
 };
 
+/// Changes buffered per subscriber before the oldest is dropped
+pub const CONFIG_CHANGE_QUEUE_DEPTH: usize = 4;
+
+/// Maximum number of concurrent change subscribers
+pub const MAX_CONFIG_SUBSCRIBERS: usize = 4;
+
+/// What happened to a configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigChangeKind {
+    /// A new value was stored
+    Stored,
+    /// The configuration was deleted
+    Deleted,
+    /// The previous version was swapped back in by `rollback`
+    RolledBack,
+    /// The backup was copied over the current value by `restore_config`
+    Restored,
+}
+
+/// Notification published when a configuration changes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigChange {
+    /// Name of the changed configuration
+    pub name: String<64>,
+    /// Kind of change
+    pub kind: ConfigChangeKind,
+}
+
+/// Device-wide change channel shared by every `ConfigStore`
+static CONFIG_CHANGES: PubSubChannel<CriticalSectionRawMutex, ConfigChange, CONFIG_CHANGE_QUEUE_DEPTH, MAX_CONFIG_SUBSCRIBERS, 0> =
+    PubSubChannel::new();
+
+/// Announce a configuration change to all subscribers
+///
+/// Never waits and never fails: names longer than 64 bytes are dropped, as is
+/// the change itself when nobody listens.
+pub fn notify_config_change(name: &str, kind: ConfigChangeKind) {
+    if let Ok(name) = String::try_from(name) {
+        CONFIG_CHANGES.immediate_publisher().publish_immediate(ConfigChange { name, kind });
+    }
+}
+
+/// Subscribe to configuration changes published from now on
+///
+/// Returns `CapacityExceeded` when [`MAX_CONFIG_SUBSCRIBERS`] are already listening.
+pub fn subscribe_config_changes() -> StorageResult<ConfigSubscriber> {
+    CONFIG_CHANGES
+        .subscriber()
+        .map(|inner| ConfigSubscriber { inner })
+        .map_err(|_| StorageError::CapacityExceeded)
+}
+
+/// Handle returned by [`subscribe_config_changes`]; dropping it frees the slot
+pub struct ConfigSubscriber {
+    inner: Subscriber<'static, CriticalSectionRawMutex, ConfigChange, CONFIG_CHANGE_QUEUE_DEPTH, MAX_CONFIG_SUBSCRIBERS, 0>,
+}
+
+impl ConfigSubscriber {
+    /// Wait for the next change, skipping over any that were lost
+    pub async fn next(&mut self) -> ConfigChange {
+        loop {
+            if let WaitResult::Message(change) = self.inner.next_message().await {
+                return change;
+            }
+        }
+    }
+
+    /// Next queued change without waiting
+    pub fn try_next(&mut self) -> Option<ConfigChange> {
+        loop {
+            match self.inner.try_next_message()? {
+                WaitResult::Message(change) => return Some(change),
+                WaitResult::Lagged(_) => continue,
+            }
+        }
+    }
+}
+
 /// Configuration entry metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigEntry {
@@ -17,9 +133,9 @@ pub struct ConfigEntry {
     pub name: String<64>,
     /// Configuration version
     pub version: u32,
-    /// Creation timestamp
+    /// Creation time (monotonic ms, see [`TimestampProvider`](iot_common::TimestampProvider))
     pub created_at: u64,
-    /// Last modification timestamp
+    /// Last modification time (monotonic ms)
     pub modified_at: u64,
     /// Configuration size in bytes
     pub size: usize,
@@ -30,10 +146,9 @@ pub struct ConfigEntry {
 }
 
 impl ConfigEntry {
-    /// Create new configuration entry
-    pub fn new(name: &str, size: usize) -> StorageResult<Self> {
+    /// Create new configuration entry created at `timestamp`
+    pub fn new(name: &str, size: usize, timestamp: u64) -> StorageResult<Self> {
         let name_string = String::try_from(name).map_err(|_| StorageError::InvalidKey)?;
-        let timestamp = 0; // In real implementation, would use actual timestamp
         
         Ok(Self {
             name: name_string,
@@ -47,8 +162,8 @@ impl ConfigEntry {
     }
 
     /// Update modification timestamp and version
-    pub fn update(&mut self, size: usize, checksum: u32) {
-        self.modified_at = 0; // In real implementation, would use actual timestamp
+    pub fn update(&mut self, size: usize, checksum: u32, timestamp: u64) {
+        self.modified_at = timestamp;
         self.version += 1;
         self.size = size;
         self.checksum = checksum;
@@ -69,6 +184,8 @@ pub struct ConfigStore<B: StorageBackend> {
     metadata_cache: FnvIndexMap<String<64>, ConfigEntry, 32>,
     /// Configuration name prefix
     config_prefix: String<16>,
+    /// Clock for entry creation and modification times
+    clock: SharedTimestampProvider,
     /// Storage backend marker for type safety
     _backend_marker: core::marker::PhantomData<B>,
 }
@@ -78,7 +195,8 @@ impl<B: StorageBackend> ConfigStore<B> {
     pub fn new(_backend: &B) -> StorageResult<Self> {
         let mut store = Self {
             metadata_cache: FnvIndexMap::new(),
-            config_prefix: String::try_from("config:").map_err(|_| StorageError::InvalidKey)?,
+            config_prefix: String::try_from("config.").map_err(|_| StorageError::InvalidKey)?,
+            clock: &UptimeTimestampProvider,
             _backend_marker: core::marker::PhantomData,
         };
 
@@ -96,6 +214,16 @@ impl<B: StorageBackend> ConfigStore<B> {
     }
 
 
+    /// Use `clock` for entry timestamps instead of uptime
+    pub fn set_timestamp_provider(&mut self, clock: SharedTimestampProvider) {
+        self.clock = clock;
+    }
+
+    /// Forget every cached configuration entry, e.g. after a format
+    pub(crate) fn clear_cache(&mut self) {
+        self.metadata_cache.clear();
+    }
+
     /// Create configuration key with prefix
     fn create_config_key(&self, name: &str) -> StorageResult<StorageKey> {
         let full_key = format!("{}{}", self.config_prefix, name);
@@ -117,10 +245,121 @@ impl<B: StorageBackend> ConfigStore<B> {
     fn verify_integrity(&self, data: &[u8], expected_checksum: u32) -> bool {
         self.calculate_checksum(data) == expected_checksum
     }
+
+    /// Metadata cache name of the previous-version slot
+    fn previous_slot_name(name: &str) -> StorageResult<String<64>> {
+        String::try_from(&format!("{}.backup", name)[..]).map_err(|_| StorageError::InvalidKey)
+    }
+
+    /// Subscribe to change notifications, see [`subscribe_config_changes`]
+    pub fn subscribe() -> StorageResult<ConfigSubscriber> {
+        subscribe_config_changes()
+    }
+
+    /// Check if a previous version is available to roll back to
+    pub fn has_previous(&self, name: &str) -> bool {
+        Self::previous_slot_name(name)
+            .map(|slot| self.metadata_cache.contains_key(&slot))
+            .unwrap_or(false)
+    }
+
+    /// Bind the store to the backend holding its slot data
+    ///
+    /// The store only keeps metadata; every [`ConfigStorage`] operation goes
+    /// through the returned view so values are read from and written to
+    /// `backend`.
+    pub fn bind<'a>(&'a mut self, backend: &'a mut B) -> BoundConfigStore<'a, B> {
+        BoundConfigStore { store: self, backend }
+    }
 }
 
-#[async_trait::async_trait]
-impl<B: StorageBackend + Send + Sync> ConfigStorage for ConfigStore<B> {
+/// [`ConfigStore`] bound to its backend
+///
+/// Borrowed from [`ConfigStore::bind`] or
+/// [`UnifiedStorageManager::config_store`](crate::UnifiedStorageManager::config_store).
+pub struct BoundConfigStore<'a, B: StorageBackend> {
+    store: &'a mut ConfigStore<B>,
+    backend: &'a mut B,
+}
+
+impl<'a, B: StorageBackend> BoundConfigStore<'a, B> {
+    /// Check if a previous version is available to roll back to
+    pub fn has_previous(&self, name: &str) -> bool {
+        self.store.has_previous(name)
+    }
+
+    /// Read a slot, `None` when nothing is stored under `key`
+    async fn read_slot(&mut self, key: &StorageKey) -> StorageResult<Option<StorageValue>> {
+        match self.backend.retrieve(key).await {
+            Ok(value) => Ok(Some(value)),
+            Err(StorageError::KeyNotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Erase a slot, succeeding when it is already empty
+    async fn erase_slot(&mut self, key: &StorageKey) -> StorageResult<()> {
+        match self.backend.delete(key).await {
+            Ok(()) | Err(StorageError::KeyNotFound) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Restore the previous version of a configuration
+    ///
+    /// The current and previous slots are swapped, so rolling back twice
+    /// re-applies the newer version. Returns `KeyNotFound` when no previous
+    /// version has been saved.
+    ///
+    /// The previous value is written to the current slot first: if power
+    /// fails between the two writes both slots hold the older value, which
+    /// is still a valid configuration.
+    pub async fn rollback(&mut self, name: &str) -> StorageResult<()> {
+        let name_string = String::try_from(name).map_err(|_| StorageError::InvalidKey)?;
+        let previous_name = ConfigStore::<B>::previous_slot_name(name)?;
+
+        let previous = self.store.metadata_cache.get(&previous_name)
+            .cloned()
+            .ok_or(StorageError::KeyNotFound)?;
+        let current = self.store.metadata_cache.get(&name_string).cloned();
+
+        // Swap slot contents
+        let key = self.store.create_config_key(name)?;
+        let backup_key = self.store.create_backup_key(name)?;
+        let previous_value = self.read_slot(&backup_key).await?
+            .ok_or(StorageError::KeyNotFound)?;
+        let current_value = self.read_slot(&key).await?;
+
+        self.backend.store(&key, &previous_value).await?;
+        match &current_value {
+            Some(value) => self.backend.store(&backup_key, value).await?,
+            None => self.erase_slot(&backup_key).await?,
+        }
+
+        let mut restored = previous;
+        restored.is_backup = false;
+        restored.name = name_string.clone();
+        restored.version = current.as_ref().map_or(restored.version, |entry| entry.version) + 1;
+
+        match current {
+            Some(entry) if current_value.is_some() => {
+                self.store.metadata_cache.insert(previous_name, entry.as_backup())
+                    .map_err(|_| StorageError::CapacityExceeded)?;
+            }
+            _ => {
+                self.store.metadata_cache.remove(&previous_name);
+            }
+        }
+        self.store.metadata_cache.insert(name_string, restored)
+            .map_err(|_| StorageError::CapacityExceeded)?;
+
+        notify_config_change(name, ConfigChangeKind::RolledBack);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl<'a, B: StorageBackend + Send + Sync> ConfigStorage for BoundConfigStore<'a, B> {
     async fn store_config<T>(&mut self, name: &str, config: &T) -> StorageResult<()>
     where
         T: Serialize + Send + Sync,
@@ -130,30 +369,35 @@ impl<B: StorageBackend + Send + Sync> ConfigStorage for ConfigStore<B> {
             .map_err(|_| StorageError::CorruptedData)?;
 
         // Calculate checksum
-        let checksum = self.calculate_checksum(&serialized);
+        let checksum = self.store.calculate_checksum(&serialized);
+
+        // Keep the value being replaced in the previous-version slot
+        if self.config_exists(name).await? {
+            self.backup_config(name).await?;
+        }
+
+        // Store configuration data
+        let key = self.store.create_config_key(name)?;
+        let value = StorageValue::from_bytes(&serialized)?;
+        self.backend.store(&key, &value).await?;
 
         // Create or update metadata entry
-        let entry = if let Some(existing) = self.metadata_cache.get_mut(&String::try_from(name).unwrap_or_else(|_| String::new())) {
-            existing.update(serialized.len(), checksum);
+        let timestamp = self.store.clock.get_timestamp_ms();
+        let name_string = String::try_from(name).map_err(|_| StorageError::InvalidKey)?;
+        let entry = if let Some(existing) = self.store.metadata_cache.get_mut(&name_string) {
+            existing.update(serialized.len(), checksum, timestamp);
             existing.clone()
         } else {
-            let mut new_entry = ConfigEntry::new(name, serialized.len())?;
+            let mut new_entry = ConfigEntry::new(name, serialized.len(), timestamp)?;
             new_entry.checksum = checksum;
             new_entry
         };
 
-        // Store configuration data
-        let _key = self.create_config_key(name)?;
-        let _value = StorageValue::from_bytes(&serialized)?;
-        
-        // TODO: Store using backend - requires architecture refactor
-        // For now, return success to enable compilation
-
         // Update metadata cache
-        let name_string = String::try_from(name).map_err(|_| StorageError::InvalidKey)?;
-        self.metadata_cache.insert(name_string, entry)
+        self.store.metadata_cache.insert(name_string, entry)
             .map_err(|_| StorageError::CapacityExceeded)?;
 
+        notify_config_change(name, ConfigChangeKind::Stored);
         Ok(())
     }
 
@@ -161,23 +405,17 @@ impl<B: StorageBackend + Send + Sync> ConfigStorage for ConfigStore<B> {
     where
         T: serde::de::DeserializeOwned + Send + Sync,
     {
-        // Get metadata
-        let name_string = String::try_from(name).map_err(|_| StorageError::InvalidKey)?;
-        let metadata = self.metadata_cache.get(&name_string)
-            .ok_or(StorageError::KeyNotFound)?;
-
         // Retrieve configuration data
-        let _key = self.create_config_key(name)?;
-        
-        // TODO: Retrieve using backend - requires architecture refactor
-        // For now, return a default empty value to enable compilation
-        let dummy_data = b"{}";
-        let value = StorageValue::from_bytes(dummy_data)?;
-
-        // Verify data integrity
+        let key = self.store.create_config_key(name)?;
+        let value = self.backend.retrieve(&key).await?;
         let data = value.as_bytes();
-        if !self.verify_integrity(data, metadata.checksum) {
-            return Err(StorageError::CorruptedData);
+
+        // Verify data integrity against the cached metadata, when there is any
+        let name_string = String::try_from(name).map_err(|_| StorageError::InvalidKey)?;
+        if let Some(metadata) = self.store.metadata_cache.get(&name_string) {
+            if !self.store.verify_integrity(data, metadata.checksum) {
+                return Err(StorageError::CorruptedData);
+            }
         }
 
         // Deserialize configuration
@@ -188,18 +426,18 @@ impl<B: StorageBackend + Send + Sync> ConfigStorage for ConfigStore<B> {
     }
 
     async fn delete_config(&mut self, name: &str) -> StorageResult<()> {
-        // Delete main configuration
-        let _key = self.create_config_key(name)?;
-        // TODO: Delete using backend - requires architecture refactor
-        
-        // Delete backup if it exists
-        let _backup_key = self.create_backup_key(name)?;
-        // TODO: Delete backup using backend - requires architecture refactor
+        // Delete main configuration and its previous version
+        let key = self.store.create_config_key(name)?;
+        self.erase_slot(&key).await?;
+        let backup_key = self.store.create_backup_key(name)?;
+        self.erase_slot(&backup_key).await?;
 
-        // Remove from metadata cache
+        // Remove both slots from metadata cache
         let name_string = String::try_from(name).map_err(|_| StorageError::InvalidKey)?;
-        self.metadata_cache.remove(&name_string);
+        self.store.metadata_cache.remove(&name_string);
+        self.store.metadata_cache.remove(&ConfigStore::<B>::previous_slot_name(name)?);
 
+        notify_config_change(name, ConfigChangeKind::Deleted);
         Ok(())
     }
 
@@ -207,22 +445,20 @@ impl<B: StorageBackend + Send + Sync> ConfigStorage for ConfigStore<B> {
         let name_string = String::try_from(name).map_err(|_| StorageError::InvalidKey)?;
         
         // Check metadata cache first
-        if self.metadata_cache.contains_key(&name_string) {
+        if self.store.metadata_cache.contains_key(&name_string) {
             return Ok(true);
         }
 
-        // Check storage directly
-        let _key = self.create_config_key(name)?;
-        // TODO: Check storage using backend - requires architecture refactor
-        // For now, return cache result only
-        Ok(false)
+        // Check storage directly, e.g. for values written before a reboot
+        let key = self.store.create_config_key(name)?;
+        self.backend.exists(&key).await
     }
 
     async fn list_configs(&mut self) -> StorageResult<alloc::vec::Vec<alloc::string::String>> {
         let mut configs = alloc::vec::Vec::new();
 
         // Get from metadata cache
-        for entry in self.metadata_cache.values() {
+        for entry in self.store.metadata_cache.values() {
             if !entry.is_backup {
                 configs.push(entry.name.as_str().to_string());
             }
@@ -235,22 +471,18 @@ impl<B: StorageBackend + Send + Sync> ConfigStorage for ConfigStore<B> {
     }
 
     async fn backup_config(&mut self, name: &str) -> StorageResult<()> {
-        // Retrieve current configuration
-        let _key = self.create_config_key(name)?;
-        // TODO: Retrieve using backend - requires architecture refactor
-        let dummy_data = b"{}";
-        let _value = StorageValue::from_bytes(dummy_data)?;
-
-        // Store as backup
-        let _backup_key = self.create_backup_key(name)?;
-        // TODO: Store backup using backend - requires architecture refactor
+        // Copy the current value into the previous-version slot
+        let key = self.store.create_config_key(name)?;
+        let value = self.backend.retrieve(&key).await?;
+        let backup_key = self.store.create_backup_key(name)?;
+        self.backend.store(&backup_key, &value).await?;
 
         // Update metadata for backup
         let name_string = String::try_from(name).map_err(|_| StorageError::InvalidKey)?;
-        if let Some(entry) = self.metadata_cache.get(&name_string) {
+        if let Some(entry) = self.store.metadata_cache.get(&name_string) {
             let backup_entry = entry.as_backup();
             let backup_name = String::try_from(&backup_entry.name[..]).map_err(|_| StorageError::InvalidKey)?;
-            self.metadata_cache.insert(backup_name, backup_entry)
+            self.store.metadata_cache.insert(backup_name, backup_entry)
                 .map_err(|_| StorageError::CapacityExceeded)?;
         }
 
@@ -258,30 +490,25 @@ impl<B: StorageBackend + Send + Sync> ConfigStorage for ConfigStore<B> {
     }
 
     async fn restore_config(&mut self, name: &str) -> StorageResult<()> {
-        // Retrieve backup configuration
-        let _backup_key = self.create_backup_key(name)?;
-        // TODO: Retrieve backup using backend - requires architecture refactor
-        let dummy_data = b"{}";
-        let _value = StorageValue::from_bytes(dummy_data)?;
-
-        // Store as main configuration
-        let _key = self.create_config_key(name)?;
-        // TODO: Store using backend - requires architecture refactor
+        // Copy the backup over the current value
+        let backup_key = self.store.create_backup_key(name)?;
+        let value = self.backend.retrieve(&backup_key).await?;
+        let key = self.store.create_config_key(name)?;
+        self.backend.store(&key, &value).await?;
 
         // Update metadata
-        let backup_name = format!("{}.backup", name);
-        let backup_name_string = String::try_from(&backup_name[..]).map_err(|_| StorageError::InvalidKey)?;
-        
-        if let Some(backup_entry) = self.metadata_cache.get(&backup_name_string) {
+        let backup_name_string = ConfigStore::<B>::previous_slot_name(name)?;
+        if let Some(backup_entry) = self.store.metadata_cache.get(&backup_name_string) {
             let mut restored_entry = backup_entry.clone();
             restored_entry.is_backup = false;
             restored_entry.name = String::try_from(name).map_err(|_| StorageError::InvalidKey)?;
             restored_entry.version += 1;
             
             let name_string = String::try_from(name).map_err(|_| StorageError::InvalidKey)?;
-            self.metadata_cache.insert(name_string, restored_entry)
+            self.store.metadata_cache.insert(name_string, restored_entry)
                 .map_err(|_| StorageError::CapacityExceeded)?;
         }
+        notify_config_change(name, ConfigChangeKind::Restored);
 
         Ok(())
     }
@@ -291,17 +518,20 @@ impl<B: StorageBackend + Send + Sync> ConfigStorage for ConfigStore<B> {
 pub struct ConfigManager<B: StorageBackend> {
     /// Configuration store
     store: ConfigStore<B>,
+    /// Backend holding the configuration slots
+    backend: B,
     /// Default configurations
     defaults: FnvIndexMap<String<64>, Vec<u8, 1024>, 16>,
 }
 
 impl<B: StorageBackend + Send + Sync> ConfigManager<B> {
     /// Create new configuration manager
-    pub fn new(backend: &B) -> StorageResult<Self> {
-        let store = ConfigStore::new(backend)?;
+    pub fn new(backend: B) -> StorageResult<Self> {
+        let store = ConfigStore::new(&backend)?;
         
         Ok(Self {
             store,
+            backend,
             defaults: FnvIndexMap::new(),
         })
     }
@@ -332,7 +562,8 @@ impl<B: StorageBackend + Send + Sync> ConfigManager<B> {
         T: serde::de::DeserializeOwned + Serialize + Send + Sync,
     {
         // Try to retrieve from storage
-        match self.store.retrieve_config(name).await {
+        let stored = self.store().retrieve_config(name).await;
+        match stored {
             Ok(config) => Ok(config),
             Err(StorageError::KeyNotFound) => {
                 // Fall back to default
@@ -350,17 +581,19 @@ impl<B: StorageBackend + Send + Sync> ConfigManager<B> {
     }
 
     /// Store configuration with automatic backup
+    ///
+    /// `store_config` already keeps the replaced value in the previous slot,
+    /// so this is equivalent to storing directly.
     pub async fn store_config_with_backup<T>(&mut self, name: &str, config: &T) -> StorageResult<()>
     where
         T: Serialize + Send + Sync,
     {
-        // Create backup if configuration exists
-        if self.store.config_exists(name).await? {
-            self.store.backup_config(name).await?;
-        }
+        self.store().store_config(name, config).await
+    }
 
-        // Store new configuration
-        self.store.store_config(name, config).await
+    /// Restore the previous version of a configuration
+    pub async fn rollback(&mut self, name: &str) -> StorageResult<()> {
+        self.store().rollback(name).await
     }
 
     /// Validate configuration structure
@@ -376,9 +609,9 @@ impl<B: StorageBackend + Send + Sync> ConfigManager<B> {
         Ok(())
     }
 
-    /// Get configuration store reference
-    pub fn store(&mut self) -> &mut ConfigStore<B> {
-        &mut self.store
+    /// Get the configuration store bound to its backend
+    pub fn store(&mut self) -> BoundConfigStore<'_, B> {
+        self.store.bind(&mut self.backend)
     }
 
     /// Reset configuration to default
@@ -393,7 +626,7 @@ impl<B: StorageBackend + Send + Sync> ConfigManager<B> {
                 .map_err(|_| StorageError::CorruptedData)?;
             
             // Store default as current configuration
-            self.store.store_config(name, &config).await?;
+            self.store().store_config(name, &config).await?;
             
             Ok(config)
         } else {
@@ -409,7 +642,7 @@ impl<B: StorageBackend + Send + Sync> ConfigManager<B> {
 
     /// List all configurations with metadata
     pub async fn list_configs_with_metadata(&mut self) -> StorageResult<AllocVec<ConfigEntry>> {
-        let config_names = self.store.list_configs().await?;
+        let config_names = self.store().list_configs().await?;
         let mut configs_with_metadata = AllocVec::new();
 
         for name in config_names {
@@ -420,4 +653,72 @@ impl<B: StorageBackend + Send + Sync> ConfigManager<B> {
 
         Ok(configs_with_metadata)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockStorage;
+    use iot_common::network::block_on;
+
+    #[test]
+    fn test_config_changes_reach_subscribers() {
+        let mut backend = MockStorage::new();
+        let mut config_store = ConfigStore::new(&backend).unwrap();
+        let mut store = config_store.bind(&mut backend);
+
+        // Nobody listening: the change is dropped, not queued for later subscribers
+        block_on(store.store_config("notify_wifi", &1u32)).unwrap();
+        let mut changes = ConfigStore::<MockStorage>::subscribe().unwrap();
+        assert_eq!(changes.try_next(), None);
+
+        block_on(store.store_config("notify_wifi", &2u32)).unwrap();
+        block_on(store.rollback("notify_wifi")).unwrap();
+        block_on(store.delete_config("notify_wifi")).unwrap();
+        let kinds: Vec<ConfigChangeKind, 4> = core::iter::from_fn(|| changes.try_next())
+            .filter(|change| change.name == "notify_wifi")
+            .map(|change| change.kind)
+            .collect();
+        assert_eq!(kinds, [ConfigChangeKind::Stored, ConfigChangeKind::RolledBack, ConfigChangeKind::Deleted]);
+
+        // A slow subscriber loses the oldest changes instead of blocking writers
+        for _ in 0..CONFIG_CHANGE_QUEUE_DEPTH + 2 {
+            notify_config_change("notify_burst", ConfigChangeKind::Stored);
+        }
+        let burst = core::iter::from_fn(|| changes.try_next())
+            .filter(|change| change.name == "notify_burst")
+            .count();
+        assert!(burst <= CONFIG_CHANGE_QUEUE_DEPTH);
+
+        // Subscribers are bounded
+        let extra: Vec<ConfigSubscriber, MAX_CONFIG_SUBSCRIBERS> = core::iter::from_fn(|| subscribe_config_changes().ok())
+            .take(MAX_CONFIG_SUBSCRIBERS)
+            .collect();
+        assert_eq!(extra.len(), MAX_CONFIG_SUBSCRIBERS - 1);
+        assert!(matches!(subscribe_config_changes(), Err(StorageError::CapacityExceeded)));
+    }
+
+    #[test]
+    fn test_entries_take_times_from_provider() {
+        struct FixedClock(u64);
+
+        impl iot_common::TimestampProvider for FixedClock {
+            fn get_timestamp_ms(&self) -> u64 {
+                self.0
+            }
+        }
+
+        static CREATED: FixedClock = FixedClock(1_000);
+        static MODIFIED: FixedClock = FixedClock(4_000);
+
+        let mut backend = MockStorage::new();
+        let mut store = ConfigStore::new(&backend).unwrap();
+        store.set_timestamp_provider(&CREATED);
+        block_on(store.bind(&mut backend).store_config("clocked", &1u32)).unwrap();
+        store.set_timestamp_provider(&MODIFIED);
+        block_on(store.bind(&mut backend).store_config("clocked", &2u32)).unwrap();
+
+        let entry = store.metadata_cache.get(&String::<64>::try_from("clocked").unwrap()).unwrap();
+        assert_eq!((entry.created_at, entry.modified_at, entry.version), (1_000, 4_000, 2));
+    }
+}
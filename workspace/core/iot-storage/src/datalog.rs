@@ -0,0 +1,467 @@
+//! # Sensor Data Logging
+//!
+//! Circular log of sensor readings in a dedicated flash region, for offline
+//! analysis when no broker is reachable. Each reading is stored as a
+//! fixed-width [`LOG_RECORD_SIZE`]-byte record; once the region is full the
+//! oldest sector is erased and overwritten, so the log always holds the most
+//! recent readings. Sector erases are accounted in a [`WearLevelingManager`].
+//!
+//! Record layout (little endian):
+//!
+//! | Offset | Size | Field                      |
+//! |--------|------|----------------------------|
+//! | 0      | 4    | sequence number            |
+//! | 4      | 8    | timestamp (ms since boot)  |
+//! | 12     | 4    | temperature (°C, f32)      |
+//! | 16     | 4    | humidity (%, f32)          |
+//! | 20     | 4    | pressure (hPa, f32)        |
+//! | 24     | 4    | reading counter            |
+//! | 28     | 4    | FNV-1a checksum of 0..28   |
+//!
+//! The log is rebuilt from flash by [`DataLogger::new`], so it survives
+//! reboots; a record torn by a power loss fails its checksum and is skipped.
+//!
+//! ```rust,ignore
+//! use iot_storage::{DataLogger, LogRecord};
+//!
+//! let mut logger = DataLogger::new(FlashStorage::new(), 0x320000, 64 * 1024)?;
+//! logger.append(&LogRecord::new(timestamp_ms, 23.4, 45.0, 1013.2, reading))?;
+//! logger.export(&mut usb_tx).await?;
+//! ```
+
+use core::fmt::Write as _;
+use embedded_io_async::Write;
+use embedded_storage::nor_flash::NorFlash;
+use heapless::String;
+use iot_common::TimestampProvider;
+
+use crate::traits::{StorageError, StorageResult};
+use crate::wear_leveling::WearLevelingManager;
+
+/// Size of one log record in flash
+pub const LOG_RECORD_SIZE: usize = 32;
+
+/// Erase endurance assumed for the log sectors
+const LOG_MAX_ERASE_CYCLES: u32 = 100_000;
+
+/// Sequence number of an erased (never written) slot
+const ERASED_SEQUENCE: u32 = u32::MAX;
+
+/// Column header of the CSV export
+const CSV_COLUMNS: &str = "sequence,timestamp_ms,reading,temperature_c,humidity_pct,pressure_hpa\r\n";
+
+/// One logged sensor reading
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LogRecord {
+    /// Time the reading was taken (ms since boot)
+    pub timestamp_ms: u64,
+    /// Temperature in degrees Celsius
+    pub temperature: f32,
+    /// Relative humidity in percent
+    pub humidity: f32,
+    /// Pressure in hectopascals
+    pub pressure: f32,
+    /// Reading counter of the producing task
+    pub reading: u32,
+}
+
+impl LogRecord {
+    /// Create a record from a reading
+    pub fn new(timestamp_ms: u64, temperature: f32, humidity: f32, pressure: f32, reading: u32) -> Self {
+        Self { timestamp_ms, temperature, humidity, pressure, reading }
+    }
+
+    /// Create a record of a reading taken now according to `clock`
+    /// 
+    /// Records keep the monotonic time, so logs written before and after an
+    /// NTP sync stay in order.
+    pub fn stamped(clock: &dyn TimestampProvider, temperature: f32, humidity: f32, pressure: f32, reading: u32) -> Self {
+        Self::new(clock.get_timestamp_ms(), temperature, humidity, pressure, reading)
+    }
+
+    /// Encode the record with its sequence number and checksum
+    fn encode(&self, sequence: u32) -> [u8; LOG_RECORD_SIZE] {
+        let mut bytes = [0u8; LOG_RECORD_SIZE];
+        bytes[0..4].copy_from_slice(&sequence.to_le_bytes());
+        bytes[4..12].copy_from_slice(&self.timestamp_ms.to_le_bytes());
+        bytes[12..16].copy_from_slice(&self.temperature.to_le_bytes());
+        bytes[16..20].copy_from_slice(&self.humidity.to_le_bytes());
+        bytes[20..24].copy_from_slice(&self.pressure.to_le_bytes());
+        bytes[24..28].copy_from_slice(&self.reading.to_le_bytes());
+        let checksum = fnv1a(&bytes[..28]);
+        bytes[28..32].copy_from_slice(&checksum.to_le_bytes());
+        bytes
+    }
+
+    /// Decode a slot, returning its sequence number and record if it is valid
+    fn decode(bytes: &[u8; LOG_RECORD_SIZE]) -> Option<(u32, Self)> {
+        let word = |offset: usize| [bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]];
+
+        let sequence = u32::from_le_bytes(word(0));
+        if sequence == ERASED_SEQUENCE || u32::from_le_bytes(word(28)) != fnv1a(&bytes[..28]) {
+            return None;
+        }
+
+        let mut timestamp = [0u8; 8];
+        timestamp.copy_from_slice(&bytes[4..12]);
+        Some((sequence, Self {
+            timestamp_ms: u64::from_le_bytes(timestamp),
+            temperature: f32::from_le_bytes(word(12)),
+            humidity: f32::from_le_bytes(word(16)),
+            pressure: f32::from_le_bytes(word(20)),
+            reading: u32::from_le_bytes(word(24)),
+        }))
+    }
+}
+
+/// 32-bit FNV-1a hash, used as the record checksum
+fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, &byte| (hash ^ byte as u32).wrapping_mul(0x0100_0193))
+}
+
+/// Circular log of [`LogRecord`]s in a flash region
+pub struct DataLogger<F: NorFlash> {
+    /// Flash holding the region
+    flash: F,
+    /// Offset of the region in `flash`, sector aligned
+    base_address: u32,
+    /// Number of sectors in the region
+    sector_count: usize,
+    /// Slot the next record is written to
+    head: usize,
+    /// Sequence number of the next record
+    next_sequence: u32,
+    /// Number of valid records in the region
+    record_count: usize,
+    /// Per-sector erase accounting
+    wear: WearLevelingManager,
+}
+
+impl<F: NorFlash> DataLogger<F> {
+    /// Open the log in `size` bytes of `flash` starting at `base_address`
+    ///
+    /// The region must be sector aligned and span at least two sectors (one is
+    /// always being recycled). Existing records are scanned to resume after the
+    /// newest one.
+    pub fn new(flash: F, base_address: u32, size: usize) -> StorageResult<Self> {
+        let sector_size = F::ERASE_SIZE;
+        if !sector_size.is_multiple_of(LOG_RECORD_SIZE)
+            || !(base_address as usize).is_multiple_of(sector_size)
+            || !size.is_multiple_of(sector_size)
+            || size / sector_size < 2
+        {
+            return Err(StorageError::ConfigurationError("Log region must span 2+ whole sectors".into()));
+        }
+
+        let sector_count = size / sector_size;
+        let mut logger = Self {
+            flash,
+            base_address,
+            sector_count,
+            head: 0,
+            next_sequence: 0,
+            record_count: 0,
+            wear: WearLevelingManager::new(sector_count, LOG_MAX_ERASE_CYCLES)?,
+        };
+        logger.scan()?;
+        Ok(logger)
+    }
+
+    /// Rebuild head, sequence and record count from flash
+    fn scan(&mut self) -> StorageResult<()> {
+        let mut newest: Option<(u32, usize)> = None;
+        self.record_count = 0;
+
+        for slot in 0..self.capacity() {
+            if let Some((sequence, _)) = self.read_slot(slot)? {
+                self.record_count += 1;
+                if newest.is_none_or(|(newest_sequence, _)| sequence > newest_sequence) {
+                    newest = Some((sequence, slot));
+                }
+            }
+        }
+
+        if let Some((sequence, slot)) = newest {
+            self.next_sequence = sequence.wrapping_add(1);
+            self.head = (slot + 1) % self.capacity();
+        }
+        Ok(())
+    }
+
+    /// Append a record, overwriting the oldest sector when the region is full
+    pub fn append(&mut self, record: &LogRecord) -> StorageResult<()> {
+        // Skip slots left dirty by a write torn by power loss; NOR cannot rewrite them
+        while !self.head.is_multiple_of(self.records_per_sector()) && !self.slot_is_erased(self.head)? {
+            self.head = (self.head + 1) % self.capacity();
+        }
+        if self.head.is_multiple_of(self.records_per_sector()) {
+            self.prepare_sector(self.head / self.records_per_sector())?;
+        }
+
+        let bytes = record.encode(self.next_sequence);
+        self.flash.write(self.slot_address(self.head), &bytes)
+            .map_err(|_| StorageError::HardwareError)?;
+
+        self.head = (self.head + 1) % self.capacity();
+        self.next_sequence = self.next_sequence.wrapping_add(1);
+        self.record_count += 1;
+        Ok(())
+    }
+
+    /// Erase a sector about to receive records unless it is already blank
+    fn prepare_sector(&mut self, sector: usize) -> StorageResult<()> {
+        let first_slot = sector * self.records_per_sector();
+        let mut blank = true;
+        for slot in first_slot..first_slot + self.records_per_sector() {
+            if self.read_slot(slot)?.is_some() {
+                self.record_count -= 1;
+                blank = false;
+            } else if !self.slot_is_erased(slot)? {
+                blank = false;
+            }
+        }
+        if blank {
+            return Ok(());
+        }
+
+        let from = self.slot_address(first_slot);
+        self.flash.erase(from, from + F::ERASE_SIZE as u32)
+            .map_err(|_| StorageError::HardwareError)?;
+        self.wear.update_erase_count(sector)
+    }
+
+    /// Stream every record, oldest first, as CSV
+    ///
+    /// A comment header carries the record count, capacity and whether older
+    /// records have been overwritten. Returns the number of records written.
+    pub async fn export<W: Write>(&mut self, writer: &mut W) -> StorageResult<usize> {
+        let mut line: String<128> = String::new();
+        let _ = write!(
+            line,
+            "# records={} capacity={} wrapped={}\r\n",
+            self.record_count,
+            self.capacity(),
+            if self.is_wrapped() { "yes" } else { "no" }
+        );
+        writer.write_all(line.as_bytes()).await.map_err(|_| StorageError::HardwareError)?;
+        writer.write_all(CSV_COLUMNS.as_bytes()).await.map_err(|_| StorageError::HardwareError)?;
+
+        // The oldest record follows the head, after the sector being recycled
+        let mut exported = 0;
+        for offset in 0..self.capacity() {
+            let slot = (self.head + offset) % self.capacity();
+            let Some((sequence, record)) = self.read_slot(slot)? else {
+                continue;
+            };
+
+            line.clear();
+            let _ = write!(
+                line,
+                "{},{},{},{:.2},{:.2},{:.2}\r\n",
+                sequence, record.timestamp_ms, record.reading,
+                record.temperature, record.humidity, record.pressure
+            );
+            writer.write_all(line.as_bytes()).await.map_err(|_| StorageError::HardwareError)?;
+            exported += 1;
+        }
+
+        writer.flush().await.map_err(|_| StorageError::HardwareError)?;
+        Ok(exported)
+    }
+
+    /// Erase the whole region, discarding every record
+    pub fn clear(&mut self) -> StorageResult<()> {
+        for sector in 0..self.sector_count {
+            let from = self.slot_address(sector * self.records_per_sector());
+            self.flash.erase(from, from + F::ERASE_SIZE as u32)
+                .map_err(|_| StorageError::HardwareError)?;
+            self.wear.update_erase_count(sector)?;
+        }
+        self.head = 0;
+        self.next_sequence = 0;
+        self.record_count = 0;
+        Ok(())
+    }
+
+    /// Number of valid records in the log
+    pub fn len(&self) -> usize {
+        self.record_count
+    }
+
+    /// Check if the log holds no records
+    pub fn is_empty(&self) -> bool {
+        self.record_count == 0
+    }
+
+    /// Maximum number of record slots in the region
+    pub fn capacity(&self) -> usize {
+        self.sector_count * self.records_per_sector()
+    }
+
+    /// Check if old records have been overwritten
+    ///
+    /// Sequence numbers start at zero, so a log holding fewer records than it
+    /// has ever written has wrapped (or was partly lost to torn writes).
+    pub fn is_wrapped(&self) -> bool {
+        (self.record_count as u64) < self.next_sequence as u64
+    }
+
+    /// Erase accounting of the region's sectors
+    pub fn wear(&self) -> &WearLevelingManager {
+        &self.wear
+    }
+
+    /// Release the flash
+    pub fn into_inner(self) -> F {
+        self.flash
+    }
+
+    fn records_per_sector(&self) -> usize {
+        F::ERASE_SIZE / LOG_RECORD_SIZE
+    }
+
+    fn slot_address(&self, slot: usize) -> u32 {
+        self.base_address + (slot * LOG_RECORD_SIZE) as u32
+    }
+
+    fn read_raw(&mut self, slot: usize) -> StorageResult<[u8; LOG_RECORD_SIZE]> {
+        let mut bytes = [0u8; LOG_RECORD_SIZE];
+        self.flash.read(self.slot_address(slot), &mut bytes)
+            .map_err(|_| StorageError::HardwareError)?;
+        Ok(bytes)
+    }
+
+    fn read_slot(&mut self, slot: usize) -> StorageResult<Option<(u32, LogRecord)>> {
+        Ok(LogRecord::decode(&self.read_raw(slot)?))
+    }
+
+    fn slot_is_erased(&mut self, slot: usize) -> StorageResult<bool> {
+        Ok(self.read_raw(slot)?.iter().all(|&byte| byte == 0xFF))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use embedded_storage::nor_flash::{ErrorType, NorFlashErrorKind, ReadNorFlash};
+    use iot_common::network::block_on;
+
+    /// Two 256-byte sectors of RAM behaving like NOR flash
+    struct RamFlash {
+        bytes: [u8; 512],
+    }
+
+    impl RamFlash {
+        fn new() -> Self {
+            Self { bytes: [0xFF; 512] }
+        }
+    }
+
+    impl ErrorType for RamFlash {
+        type Error = NorFlashErrorKind;
+    }
+
+    impl ReadNorFlash for RamFlash {
+        const READ_SIZE: usize = 1;
+
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            bytes.copy_from_slice(&self.bytes[offset..offset + bytes.len()]);
+            Ok(())
+        }
+
+        fn capacity(&self) -> usize {
+            self.bytes.len()
+        }
+    }
+
+    impl NorFlash for RamFlash {
+        const WRITE_SIZE: usize = 1;
+        const ERASE_SIZE: usize = 256;
+
+        fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+            self.bytes[from as usize..to as usize].fill(0xFF);
+            Ok(())
+        }
+
+        fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+            // NOR writes can only clear bits
+            for (target, byte) in self.bytes[offset as usize..].iter_mut().zip(bytes) {
+                *target &= byte;
+            }
+            Ok(())
+        }
+    }
+
+    struct CsvSink(Vec<u8>);
+
+    impl embedded_io_async::ErrorType for CsvSink {
+        type Error = core::convert::Infallible;
+    }
+
+    impl Write for CsvSink {
+        async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            self.0.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+    }
+
+    fn reading(n: u32) -> LogRecord {
+        LogRecord::new(n as u64 * 30_000, 20.0 + n as f32, 40.0, 1013.25, n)
+    }
+
+    #[test]
+    fn test_append_and_reopen() {
+        let mut logger = DataLogger::new(RamFlash::new(), 0, 512).unwrap();
+        assert_eq!(logger.capacity(), 16);
+        assert!(logger.is_empty());
+
+        for n in 0..5 {
+            logger.append(&reading(n)).unwrap();
+        }
+        assert_eq!(logger.len(), 5);
+        assert!(!logger.is_wrapped());
+
+        // A reboot resumes after the newest record
+        let mut logger = DataLogger::new(logger.into_inner(), 0, 512).unwrap();
+        assert_eq!(logger.len(), 5);
+        logger.append(&reading(5)).unwrap();
+        assert_eq!(logger.len(), 6);
+    }
+
+    #[test]
+    fn test_wrap_overwrites_oldest_sector() {
+        let mut logger = DataLogger::new(RamFlash::new(), 0, 512).unwrap();
+        for n in 0..20 {
+            logger.append(&reading(n)).unwrap();
+        }
+
+        // Record 16 recycled the first sector, dropping records 0..8
+        assert_eq!(logger.len(), 12);
+        assert!(logger.is_wrapped());
+        assert_eq!(logger.wear().get_least_worn_sector(), Some(1));
+
+        let mut sink = CsvSink(Vec::new());
+        assert_eq!(block_on(logger.export(&mut sink)).unwrap(), 12);
+
+        let csv = core::str::from_utf8(&sink.0).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("# records=12 capacity=16 wrapped=yes"));
+        assert_eq!(lines.next(), Some(CSV_COLUMNS.trim_end()));
+        assert_eq!(lines.next(), Some("8,240000,8,28.00,40.00,1013.25"));
+        assert_eq!(lines.last(), Some("19,570000,19,39.00,40.00,1013.25"));
+    }
+
+    #[test]
+    fn test_torn_record_is_skipped() {
+        let mut flash = RamFlash::new();
+        // Half-written record in slot 0
+        flash.bytes[..8].fill(0x00);
+
+        let mut logger = DataLogger::new(flash, 0, 512).unwrap();
+        assert!(logger.is_empty());
+        logger.append(&reading(0)).unwrap();
+        assert_eq!(logger.len(), 1);
+    }
+}
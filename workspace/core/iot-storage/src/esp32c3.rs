@@ -8,6 +8,8 @@
 use esp_storage::FlashStorage;
 #[cfg(feature = "esp32c3-flash")]
 use embedded_storage::{ReadStorage, Storage};
+#[cfg(feature = "esp32c3-flash")]
+use embedded_storage::nor_flash::NorFlash;
 use heapless::Vec;
 use alloc::{vec, vec::Vec as AllocVec, boxed::Box, string::String};
 use crate::{
@@ -66,6 +68,9 @@ pub struct Esp32C3Storage {
 
 impl Esp32C3Storage {
     /// Create new ESP32-C3 storage instance
+    ///
+    /// Erase counters persisted by earlier boots are loaded from the metadata
+    /// sector (the last reserved sector); a blank sector starts them at zero.
     pub fn new(config: Esp32C3Config) -> StorageManagerResult<Self> {
         let flash_manager = FlashStorageManager::new(config.flash_config.clone())
             .map_err(|_| StorageErrorKind::OperationFailed(
@@ -75,13 +80,52 @@ impl Esp32C3Storage {
         #[cfg(feature = "esp32c3-flash")]
         let flash_storage = FlashStorage::new();
 
-        Ok(Self {
+        #[allow(unused_mut)]
+        let mut storage = Self {
             config,
             flash_manager,
             #[cfg(feature = "esp32c3-flash")]
             flash_storage,
             stats: StorageStats::new(),
-        })
+        };
+
+        #[cfg(feature = "esp32c3-flash")]
+        storage.load_erase_counters();
+
+        Ok(storage)
+    }
+
+    /// Restore the erase counters from the metadata sector
+    #[cfg(feature = "esp32c3-flash")]
+    fn load_erase_counters(&mut self) {
+        let Some(address) = self.flash_manager.metadata_address() else { return };
+        let mut block = [0u8; crate::flash::ERASE_COUNTER_BLOCK_SIZE];
+        if ReadStorage::read(&mut self.flash_storage, address, &mut block).is_ok() {
+            self.flash_manager.restore_erase_counters(&block);
+        }
+    }
+
+    /// Write the erase counters to the metadata sector
+    ///
+    /// Erasing the metadata sector is itself counted before the block is
+    /// encoded, so the persisted values include it.
+    #[cfg(feature = "esp32c3-flash")]
+    fn persist_erase_counters(&mut self) -> StorageResult<()> {
+        let Some(address) = self.flash_manager.metadata_address() else { return Ok(()) };
+        let sector_size = self.flash_manager.sector_size() as u32;
+
+        NorFlash::erase(&mut self.flash_storage, address, address + sector_size)
+            .map_err(|_| StorageError::HardwareError)?;
+        self.flash_manager.record_erase(address);
+
+        let block = self.flash_manager.encode_erase_counters();
+        NorFlash::write(&mut self.flash_storage, address, &block)
+            .map_err(|_| StorageError::HardwareError)
+    }
+
+    /// Lifetime erase count of every sector, in address order
+    pub fn sector_erase_cycles(&self) -> Vec<u32, { crate::MAX_FLASH_REGIONS }> {
+        self.flash_manager.sector_erase_cycles()
     }
 
     /// Get ESP32-C3 specific information
@@ -160,15 +204,24 @@ impl Esp32C3Storage {
                 Ok(())
             }
             FlashOperation::Erase => {
-                // ESP32-C3 specific erase operation
-                // Would use actual erase function
+                // Erase the whole sector containing `address` and count it
+                let sector_size = self.flash_manager.sector_size() as u32;
+                let start = address - address % sector_size;
+                NorFlash::erase(&mut self.flash_storage, start, start + sector_size)
+                    .map_err(|_| StorageError::HardwareError)?;
+
+                if self.flash_manager.record_erase(start).is_some()
+                    && Some(start) != self.flash_manager.metadata_address()
+                {
+                    self.persist_erase_counters()?;
+                }
                 Ok(())
             }
         }
     }
 }
 
-#[async_trait::async_trait]
+#[async_trait::async_trait(?Send)]
 impl StorageBackend for Esp32C3Storage {
     async fn store(&mut self, key: &StorageKey, value: &StorageValue) -> StorageResult<()> {
         // Delegate to flash manager with ESP32-C3 optimizations
@@ -218,6 +271,12 @@ impl StorageBackend for Esp32C3Storage {
         Ok(())
     }
 
+    async fn format(&mut self) -> StorageResult<usize> {
+        let reclaimed = self.flash_manager.format().await?;
+        self.stats.reset_operations();
+        Ok(reclaimed)
+    }
+
     fn get_capacity(&self) -> StorageResult<StorageCapacity> {
         self.flash_manager.get_capacity()
     }
@@ -6,13 +6,22 @@
 
 use heapless::{String, Vec, FnvIndexMap};
 use embassy_time::{Duration, Timer};
-use alloc::{boxed::Box, string::ToString};
+use alloc::{boxed::Box, format, string::ToString};
 use crate::{
     traits::{StorageBackend, StorageKey, StorageValue, StorageError, StorageResult, 
              StorageCapacity, StorageStats, StorageMaintenance, WearLeveling},
-    StorageErrorKind, StorageManagerResult, ErrorString, MAX_ERROR_LEN,
+    wear_leveling::WearLevelingManager,
+    ota::Partition,
+    StorageErrorKind, StorageManagerResult, ErrorString, MAX_ERROR_LEN, MAX_FLASH_REGIONS,
 };
 
+/// Magic word ("WEAR") at the start of a valid erase-counter block
+const ERASE_COUNTER_MAGIC: u32 = 0x5745_4152;
+
+/// Size of an erase-counter block: magic, sector count, one counter per
+/// sector and a trailing checksum, all little-endian u32
+pub const ERASE_COUNTER_BLOCK_SIZE: usize = 4 * (MAX_FLASH_REGIONS + 3);
+
 /// Helper function to create error strings safely
 fn create_error_string(msg: &str) -> ErrorString {
     ErrorString::try_from(msg).unwrap_or_else(|_| {
@@ -82,6 +91,132 @@ pub enum RegionPurpose {
     WearLeveling,
     /// Bad block management
     BadBlockTable,
+    /// Persisted bookkeeping such as erase counters
+    Metadata,
+}
+
+/// Erase granularity every named region must be aligned to
+pub const FLASH_REGION_ALIGNMENT: u32 = 4096;
+
+/// Named area of flash reserved for one subsystem
+///
+/// Handed out by [`FlashRegionTable::region`]; the subsystem keeps its reads,
+/// writes and erases between [`offset`](Self::offset) and [`end`](Self::end).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlashRegionHandle {
+    /// Name the region is requested by
+    pub name: &'static str,
+    /// Offset of the region in flash
+    pub offset: u32,
+    /// Size of the region in bytes
+    pub size: u32,
+}
+
+impl FlashRegionHandle {
+    /// Declares a region
+    pub const fn new(name: &'static str, offset: u32, size: u32) -> Self {
+        Self { name, offset, size }
+    }
+
+    /// First offset past the region
+    pub const fn end(&self) -> u32 {
+        self.offset + self.size
+    }
+
+    /// Checks if the two regions share any byte
+    pub const fn overlaps(&self, other: &FlashRegionHandle) -> bool {
+        self.offset < other.end() && other.offset < self.end()
+    }
+}
+
+/// Flash layout of an application: every named region in one place
+///
+/// Subsystems ask the table for their region by name instead of hard-coding
+/// offsets, so a new region cannot silently land on an existing one. The
+/// table is usually a constant checked once at startup:
+///
+/// ```rust,ignore
+/// const FLASH_LAYOUT: FlashRegionTable = FlashRegionTable::new(0x400000, &[
+///     FlashRegionHandle::new("config", 0x310000, 0x2000),
+///     FlashRegionHandle::new("datalog", 0x320000, 0x10000),
+/// ]);
+///
+/// FLASH_LAYOUT.validate()?;
+/// if let Some(appdata) = find_partition(&mut flash, "appdata")? {
+///     FLASH_LAYOUT.validate_partition(appdata)?;
+/// }
+/// let datalog = FLASH_LAYOUT.region("datalog").expect("datalog region declared");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct FlashRegionTable<'a> {
+    /// Size of the flash device in bytes
+    flash_size: u32,
+    /// Declared regions
+    regions: &'a [FlashRegionHandle],
+}
+
+impl<'a> FlashRegionTable<'a> {
+    /// Declares the layout of a flash device of `flash_size` bytes
+    pub const fn new(flash_size: u32, regions: &'a [FlashRegionHandle]) -> Self {
+        Self { flash_size, regions }
+    }
+
+    /// Checks that every region is sector aligned, fits the flash, has a
+    /// unique name and overlaps no other region
+    ///
+    /// The error names the offending regions.
+    pub fn validate(&self) -> StorageResult<()> {
+        for (index, region) in self.regions.iter().enumerate() {
+            if region.size == 0 || region.offset % FLASH_REGION_ALIGNMENT != 0 || region.size % FLASH_REGION_ALIGNMENT != 0 {
+                return Err(StorageError::ConfigurationError(format!(
+                    "Flash region '{}' must span whole sectors", region.name
+                )));
+            }
+            if region.offset.checked_add(region.size).is_none_or(|end| end > self.flash_size) {
+                return Err(StorageError::ConfigurationError(format!(
+                    "Flash region '{}' ends past the end of flash (0x{:X})", region.name, self.flash_size
+                )));
+            }
+            for other in &self.regions[..index] {
+                if other.name == region.name {
+                    return Err(StorageError::ConfigurationError(format!(
+                        "Flash region '{}' declared twice", region.name
+                    )));
+                }
+                if other.overlaps(region) {
+                    return Err(StorageError::ConfigurationError(format!(
+                        "Flash regions '{}' and '{}' overlap", other.name, region.name
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that every region lies inside `partition`
+    ///
+    /// Pass the data partition the application owns, so no region can reach
+    /// into the app slots or the bootloader's data.
+    pub fn validate_partition(&self, partition: Partition) -> StorageResult<()> {
+        let partition_end = partition.offset as u64 + partition.size as u64;
+        match self.regions.iter().find(|r| r.offset < partition.offset || r.end() as u64 > partition_end) {
+            Some(region) => Err(StorageError::ConfigurationError(format!(
+                "Flash region '{}' (0x{:X}-0x{:X}) lies outside its partition (0x{:X}-0x{:X})",
+                region.name, region.offset, region.end(), partition.offset, partition_end
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Region declared under `name`
+    pub fn region(&self, name: &str) -> Option<FlashRegionHandle> {
+        self.regions.iter().find(|r| r.name == name).copied()
+    }
+
+    /// All declared regions, in declaration order
+    pub fn regions(&self) -> &'a [FlashRegionHandle] {
+        self.regions
+    }
 }
 
 /// Flash storage manager with wear leveling and atomic operations
@@ -89,7 +224,7 @@ pub struct FlashStorageManager {
     /// Flash storage configuration
     config: FlashConfig,
     /// Storage regions
-    regions: Vec<FlashRegion, 16>,
+    regions: Vec<FlashRegion, MAX_FLASH_REGIONS>,
     /// Key-to-address mapping
     key_map: FnvIndexMap<String<64>, u32, 64>,
     /// Storage statistics
@@ -98,6 +233,10 @@ pub struct FlashStorageManager {
     transaction_active: bool,
     /// Wear leveling state
     wear_level_threshold: u8,
+    /// Logical-to-physical map over the user data sectors
+    wear: WearLevelingManager,
+    /// Logical block owned by each key while wear leveling is enabled
+    logical_blocks: FnvIndexMap<String<64>, usize, 64>,
 }
 
 impl FlashStorageManager {
@@ -110,10 +249,18 @@ impl FlashStorageManager {
             stats: StorageStats::new(),
             transaction_active: false,
             wear_level_threshold: 80,
+            wear: WearLevelingManager::new(0, config.max_erase_cycles).map_err(|_| {
+                StorageErrorKind::WearLevelingError(create_error_string("Failed to create block map"))
+            })?,
+            logical_blocks: FnvIndexMap::new(),
         };
 
         // Initialize storage regions
         manager.initialize_regions()?;
+        let data_sectors = manager.regions.iter().filter(|r| r.purpose == RegionPurpose::UserData).count();
+        manager.wear = WearLevelingManager::new(data_sectors, config.max_erase_cycles).map_err(|_| {
+            StorageErrorKind::WearLevelingError(create_error_string("Failed to create block map"))
+        })?;
         
         // Load existing key mappings
         manager.load_key_mappings()?;
@@ -164,12 +311,17 @@ impl FlashStorageManager {
             current_address += self.config.sector_size as u32;
         }
 
-        // Wear leveling region
-        for _i in 0..self.config.reserved_sectors {
+        // Wear leveling region; the last reserved sector holds the erase counters
+        for i in 0..self.config.reserved_sectors {
+            let purpose = if i + 1 == self.config.reserved_sectors {
+                RegionPurpose::Metadata
+            } else {
+                RegionPurpose::WearLeveling
+            };
             let region = FlashRegion {
                 start_address: current_address,
                 size: self.config.sector_size,
-                purpose: RegionPurpose::WearLeveling,
+                purpose,
                 erase_cycles: 0,
                 in_use: false,
             };
@@ -276,8 +428,54 @@ impl FlashStorageManager {
         }
     }
 
-    /// Erase a flash sector
-    async fn erase_sector(&mut self, region_index: usize) -> StorageManagerResult<()> {
+    /// Index of the first user data sector; wear-leveled blocks are relative to it
+    fn first_data_region(&self) -> usize {
+        self.regions.iter().position(|r| r.purpose == RegionPurpose::UserData).unwrap_or(0)
+    }
+
+    /// Copy the data sectors' erase counts into the block map
+    fn sync_wear_counts(&mut self) {
+        let first = self.first_data_region();
+        let counts: Vec<u32, MAX_FLASH_REGIONS> = self.regions.iter()
+            .skip(first)
+            .take(self.wear.logical_block_count())
+            .map(|r| r.erase_cycles)
+            .collect();
+        self.wear.load_erase_counts(&counts);
+    }
+
+    /// Enable or disable wear leveling of user data
+    ///
+    /// When enabled, every store moves the key's block to the least-erased
+    /// free data sector instead of the first free one.
+    pub fn set_wear_leveling_enabled(&mut self, enabled: bool) {
+        self.config.wear_leveling_enabled = enabled;
+    }
+
+    /// Whether stores are wear leveled
+    pub fn wear_leveling_enabled(&self) -> bool {
+        self.config.wear_leveling_enabled
+    }
+
+    /// Logical-to-physical block map, for diagnostics
+    ///
+    /// Physical blocks are numbered from the first user data sector.
+    pub fn wear_leveling(&self) -> &WearLevelingManager {
+        &self.wear
+    }
+
+    /// Logical block of `key`, allocating the lowest free one for a new key
+    fn logical_block(&self, key: &String<64>) -> StorageResult<usize> {
+        if let Some(&logical) = self.logical_blocks.get(key) {
+            return Ok(logical);
+        }
+        (0..self.wear.logical_block_count())
+            .find(|logical| !self.logical_blocks.values().any(|used| used == logical))
+            .ok_or(StorageError::CapacityExceeded)
+    }
+
+    /// Erase a flash sector and persist the updated erase counters
+    pub async fn erase_sector(&mut self, region_index: usize) -> StorageManagerResult<()> {
         if region_index >= self.regions.len() {
             return Err(StorageErrorKind::OperationFailed(
                 create_error_string("Invalid region index")
@@ -291,9 +489,110 @@ impl FlashStorageManager {
         self.regions[region_index].erase_cycles += 1;
         self.stats.erase_cycles += 1;
 
+        if self.regions[region_index].purpose != RegionPurpose::Metadata {
+            self.persist_erase_counters().await?;
+        }
+
         Ok(())
     }
 
+    /// Rewrite the erase-counter block in the metadata sector
+    ///
+    /// The metadata sector is erased first and counts that erase itself.
+    async fn persist_erase_counters(&mut self) -> StorageManagerResult<()> {
+        let Some(index) = self.metadata_region() else {
+            return Ok(()); // No reserved sector, counters stay in RAM
+        };
+
+        Timer::after(Duration::from_millis(50)).await;
+        self.regions[index].erase_cycles += 1;
+        self.stats.erase_cycles += 1;
+
+        let block = self.encode_erase_counters();
+        let address = self.regions[index].start_address;
+        self.write_flash(address, &block).await
+    }
+
+    /// Index of the sector holding the persisted erase counters
+    fn metadata_region(&self) -> Option<usize> {
+        self.regions.iter().position(|r| r.purpose == RegionPurpose::Metadata)
+    }
+
+    /// Address of the erase-counter block, `None` without reserved sectors
+    pub fn metadata_address(&self) -> Option<u32> {
+        self.metadata_region().map(|index| self.regions[index].start_address)
+    }
+
+    /// Sector size used by this manager
+    pub fn sector_size(&self) -> usize {
+        self.config.sector_size
+    }
+
+    /// Count an erase of the sector containing `address`
+    ///
+    /// For backends that erase the hardware themselves. Returns the region
+    /// index, or `None` when the address is outside the managed area.
+    pub fn record_erase(&mut self, address: u32) -> Option<usize> {
+        let index = self.regions.iter().position(|r| {
+            address >= r.start_address && address < r.start_address + r.size as u32
+        })?;
+        self.regions[index].erase_cycles += 1;
+        self.stats.erase_cycles += 1;
+        self.sync_wear_counts();
+        Some(index)
+    }
+
+    /// Lifetime erase count of every sector, in address order
+    pub fn sector_erase_cycles(&self) -> Vec<u32, MAX_FLASH_REGIONS> {
+        self.regions.iter().map(|r| r.erase_cycles).collect()
+    }
+
+    /// Serialize the erase counters into a metadata block
+    pub fn encode_erase_counters(&self) -> Vec<u8, ERASE_COUNTER_BLOCK_SIZE> {
+        let mut block = Vec::new();
+        let mut checksum = ERASE_COUNTER_MAGIC.wrapping_add(self.regions.len() as u32);
+        let _ = block.extend_from_slice(&ERASE_COUNTER_MAGIC.to_le_bytes());
+        let _ = block.extend_from_slice(&(self.regions.len() as u32).to_le_bytes());
+        for region in &self.regions {
+            checksum = checksum.wrapping_add(region.erase_cycles);
+            let _ = block.extend_from_slice(&region.erase_cycles.to_le_bytes());
+        }
+        let _ = block.extend_from_slice(&checksum.to_le_bytes());
+        block
+    }
+
+    /// Load erase counters from a metadata block read back from flash
+    ///
+    /// Rejects blank or corrupted blocks and blocks written for a different
+    /// sector layout, leaving the counters unchanged. Returns whether the
+    /// counters were restored.
+    pub fn restore_erase_counters(&mut self, block: &[u8]) -> bool {
+        let word = |i: usize| block.get(i * 4..i * 4 + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+
+        if word(0) != Some(ERASE_COUNTER_MAGIC) || word(1) != Some(self.regions.len() as u32) {
+            return false;
+        }
+
+        let mut checksum = ERASE_COUNTER_MAGIC.wrapping_add(self.regions.len() as u32);
+        let mut counters: Vec<u32, MAX_FLASH_REGIONS> = Vec::new();
+        for i in 0..self.regions.len() {
+            let Some(cycles) = word(2 + i) else { return false };
+            checksum = checksum.wrapping_add(cycles);
+            let _ = counters.push(cycles);
+        }
+        if word(2 + self.regions.len()) != Some(checksum) {
+            return false;
+        }
+
+        for (region, cycles) in self.regions.iter_mut().zip(counters) {
+            region.erase_cycles = cycles;
+        }
+        self.stats.erase_cycles = self.regions.iter().map(|r| r.erase_cycles as u64).sum();
+        self.sync_wear_counts();
+        true
+    }
+
     /// Write data to flash at specific address
     async fn write_flash(&mut self, _address: u32, data: &[u8]) -> StorageManagerResult<()> {
         // Simulate flash write operation
@@ -317,14 +616,35 @@ impl FlashStorageManager {
     }
 }
 
-#[async_trait::async_trait]
+#[async_trait::async_trait(?Send)]
 impl StorageBackend for FlashStorageManager {
     async fn store(&mut self, key: &StorageKey, value: &StorageValue) -> StorageResult<()> {
         let data = value.as_bytes();
+        if data.len() > self.config.sector_size {
+            return Err(StorageError::CapacityExceeded);
+        }
         
-        // Find available region
-        let region_index = self.find_available_region(data.len(), RegionPurpose::UserData)
-            .ok_or(StorageError::CapacityExceeded)?;
+        let region_index = if self.config.wear_leveling_enabled {
+            // Move the key's block to the least-worn free sector and erase it
+            let key_string = String::try_from(key.as_str()).map_err(|_| StorageError::InvalidKey)?;
+            let logical = self.logical_block(&key_string)?;
+            if let Some(&old_address) = self.key_map.get(&key_string) {
+                if let Some(old) = self.regions.iter_mut().find(|r| r.start_address == old_address) {
+                    old.in_use = false;
+                }
+            }
+            let physical = self.wear.map_write(logical)?;
+            self.logical_blocks.insert(key_string, logical).map_err(|_| StorageError::CapacityExceeded)?;
+            
+            let region_index = self.first_data_region() + physical;
+            self.erase_sector(region_index).await.map_err(|_| StorageError::HardwareError)?;
+            self.sync_wear_counts();
+            region_index
+        } else {
+            // Find available region
+            self.find_available_region(data.len(), RegionPurpose::UserData)
+                .ok_or(StorageError::CapacityExceeded)?
+        };
         
         let region = &self.regions[region_index];
         let address = region.start_address;
@@ -362,6 +682,9 @@ impl StorageBackend for FlashStorageManager {
     async fn delete(&mut self, key: &StorageKey) -> StorageResult<()> {
         let key_string = String::try_from(key.as_str()).map_err(|_| StorageError::InvalidKey)?;
         let address = self.key_map.remove(&key_string).ok_or(StorageError::KeyNotFound)?;
+        if let Some(logical) = self.logical_blocks.remove(&key_string) {
+            self.wear.release(logical);
+        }
         
         // Find and mark region as available
         for region in &mut self.regions {
@@ -406,6 +729,33 @@ impl StorageBackend for FlashStorageManager {
         Ok(())
     }
 
+    async fn format(&mut self) -> StorageResult<usize> {
+        let used: Vec<usize, MAX_FLASH_REGIONS> = (0..self.regions.len())
+            .filter(|&i| self.regions[i].in_use
+                && matches!(self.regions[i].purpose, RegionPurpose::Configuration | RegionPurpose::UserData))
+            .collect();
+        
+        // Drop the index before erasing, so an interrupted format leaves
+        // unreferenced sectors rather than keys pointing at erased ones
+        for &logical in self.logical_blocks.values() {
+            self.wear.release(logical);
+        }
+        self.logical_blocks.clear();
+        self.key_map.clear();
+        self.transaction_active = false;
+        
+        let mut reclaimed = 0;
+        for index in used {
+            self.regions[index].in_use = false;
+            self.erase_sector(index).await.map_err(|_| StorageError::HardwareError)?;
+            reclaimed += self.regions[index].size;
+        }
+        self.sync_wear_counts();
+        self.stats.reset_operations();
+        
+        Ok(reclaimed)
+    }
+
     fn get_capacity(&self) -> StorageResult<StorageCapacity> {
         let used_regions = self.regions.iter().filter(|r| r.in_use).count();
         let used_bytes = used_regions * self.config.sector_size;
@@ -418,7 +768,9 @@ impl StorageBackend for FlashStorageManager {
     }
 
     fn get_stats(&self) -> StorageResult<StorageStats> {
-        Ok(self.stats.clone())
+        let mut stats = self.stats.clone();
+        stats.sector_erase_cycles = self.sector_erase_cycles();
+        Ok(stats)
     }
 }
 
@@ -427,12 +779,12 @@ impl StorageMaintenance for FlashStorageManager {
         // Compact storage by moving data to eliminate gaps
         let mut reclaimed = 0;
         
-        // Find fragmented regions and compact them
+        // Find fragmented regions and compact them; erase counters are
+        // lifetime wear and survive defragmentation
         for region in &mut self.regions {
             if !region.in_use && region.erase_cycles > 0 {
                 // Simulate defragmentation
                 reclaimed += region.size;
-                region.erase_cycles = 0;
             }
         }
         
@@ -563,4 +915,100 @@ impl WearLeveling for FlashStorageManager {
         let average_wear = self.get_average_wear_level();
         100_u8.saturating_sub(average_wear)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_erase_counters_persist_across_restart() {
+        let mut flash = FlashStorageManager::new(FlashConfig::default()).unwrap();
+        let base = FlashConfig::default().base_address;
+
+        // Five erases of sector 3, one of sector 4 (any address inside it)
+        for _ in 0..5 {
+            assert_eq!(flash.record_erase(base + 3 * 4096), Some(3));
+        }
+        assert_eq!(flash.record_erase(base + 4 * 4096 + 100), Some(4));
+        assert_eq!(flash.record_erase(base - 1), None);
+
+        let stats = flash.get_stats().unwrap();
+        assert_eq!(stats.erase_cycles, 6);
+        assert_eq!(stats.sector_erase_cycles.len(), 16);
+        assert_eq!(stats.sector_erase_cycles[3], 5);
+        assert_eq!(stats.sector_erase_cycles[4], 1);
+        assert_eq!(stats.max_sector_erase_cycles(), Some(5));
+
+        // Metadata lives in the last sector and reloads after a "reboot"
+        assert_eq!(flash.metadata_address(), Some(base + 15 * 4096));
+        let block = flash.encode_erase_counters();
+        let mut rebooted = FlashStorageManager::new(FlashConfig::default()).unwrap();
+        assert!(rebooted.restore_erase_counters(&block));
+        assert_eq!(rebooted.sector_erase_cycles(), flash.sector_erase_cycles());
+        assert_eq!(rebooted.get_stats().unwrap().erase_cycles, 6);
+    }
+
+    #[test]
+    fn test_erase_counters_reject_invalid_block() {
+        let mut flash = FlashStorageManager::new(FlashConfig::default()).unwrap();
+        flash.record_erase(FlashConfig::default().base_address);
+
+        // Blank flash
+        assert!(!flash.restore_erase_counters(&[0xFF; ERASE_COUNTER_BLOCK_SIZE]));
+
+        // Corrupted counter
+        let mut block = flash.encode_erase_counters();
+        block[8] ^= 0x01;
+        assert!(!flash.restore_erase_counters(&block));
+
+        // Truncated block
+        let block = flash.encode_erase_counters();
+        assert!(!flash.restore_erase_counters(&block[..block.len() - 4]));
+
+        assert_eq!(flash.sector_erase_cycles()[0], 1);
+    }
+
+    const LAYOUT: [FlashRegionHandle; 3] = [
+        FlashRegionHandle::new("config", 0x310000, 0x2000),
+        FlashRegionHandle::new("scratch", 0x312000, 0x1000),
+        FlashRegionHandle::new("datalog", 0x320000, 0x10000),
+    ];
+
+    fn rejection(regions: &[FlashRegionHandle]) -> alloc::string::String {
+        match FlashRegionTable::new(0x400000, regions).validate() {
+            Err(StorageError::ConfigurationError(msg)) => msg,
+            other => panic!("Expected a configuration error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_region_table_hands_out_declared_regions() {
+        let table = FlashRegionTable::new(0x400000, &LAYOUT);
+        assert!(table.validate().is_ok());
+        assert_eq!(table.region("datalog"), Some(LAYOUT[2]));
+        assert_eq!(table.region("datalog").unwrap().end(), 0x330000);
+        assert_eq!(table.region("ota"), None);
+
+        // The appdata partition of partitions.csv holds all of them
+        assert!(table.validate_partition(Partition { offset: 0x310000, size: 0xF0000 }).is_ok());
+        assert!(table.validate_partition(Partition { offset: 0x311000, size: 0xF0000 }).is_err());
+        assert!(table.validate_partition(Partition { offset: 0x310000, size: 0x10000 }).is_err());
+    }
+
+    #[test]
+    fn test_region_table_rejects_bad_layouts() {
+        let overlapping = [LAYOUT[0], FlashRegionHandle::new("backup", 0x311000, 0x1000)];
+        assert_eq!(rejection(&overlapping), "Flash regions 'config' and 'backup' overlap");
+
+        let duplicate = [LAYOUT[0], FlashRegionHandle::new("config", 0x320000, 0x1000)];
+        assert!(rejection(&duplicate).contains("declared twice"));
+
+        assert!(rejection(&[FlashRegionHandle::new("tail", 0x3F0000, 0x20000)]).contains("past the end"));
+        assert!(rejection(&[FlashRegionHandle::new("wrap", 0xFFFF_F000, 0x2000)]).contains("past the end"));
+        assert!(rejection(&[FlashRegionHandle::new("odd", 0x310100, 0x1000)]).contains("whole sectors"));
+        assert!(rejection(&[FlashRegionHandle::new("empty", 0x310000, 0)]).contains("whole sectors"));
+
+        // Adjacent regions share no byte
+        assert!(!LAYOUT[0].overlaps(&LAYOUT[1]));
+    }
+}
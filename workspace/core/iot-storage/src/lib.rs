@@ -8,9 +8,13 @@
 //!
 //! - **Storage Abstraction**: Unified interface for different storage backends
 //! - **Flash Storage**: ESP32-C3 flash memory integration with wear leveling
+//! - **NOR Flash Backend**: Key-value backend over any `embedded-storage-async` driver
 //! - **Atomic Operations**: Safe concurrent access to storage resources
 //! - **Configuration Persistence**: Specialized storage for system configuration
 //! - **Error Recovery**: Robust error handling and recovery mechanisms
+//! - **Data Logging**: Circular CSV-exportable log of sensor readings in flash
+//! - **Namespaces**: Per-subsystem key prefixes over the shared keyspace
+//! - **OTA Partitions**: Firmware image slots, boot selection and rollback
 //! - **Memory Efficiency**: Optimized for constrained embedded environments
 //! - **No-std Compatible**: Works without heap allocation
 //!
@@ -58,6 +62,10 @@ pub mod flash;
 pub mod config;
 pub mod atomic;
 pub mod wear_leveling;
+pub mod datalog;
+pub mod namespace;
+pub mod nor_flash;
+pub mod ota;
 
 #[cfg(feature = "esp32c3-flash")]
 pub mod esp32c3;
@@ -67,9 +75,23 @@ pub use traits::{
     StorageBackend, ConfigStorage, AtomicStorage, StorageKey, StorageValue,
     StorageError, StorageResult, StorageCapacity, StorageStats
 };
-pub use flash::{FlashStorageManager, FlashRegion, FlashConfig};
-pub use config::{ConfigStore, ConfigEntry, ConfigManager};
+pub use flash::{
+    FlashStorageManager, FlashRegion, FlashConfig, FlashRegionTable, FlashRegionHandle,
+    ERASE_COUNTER_BLOCK_SIZE, FLASH_REGION_ALIGNMENT
+};
+pub use config::{
+    ConfigStore, BoundConfigStore, ConfigEntry, ConfigManager, ConfigChange, ConfigChangeKind, ConfigSubscriber,
+    notify_config_change, subscribe_config_changes, CONFIG_CHANGE_QUEUE_DEPTH, MAX_CONFIG_SUBSCRIBERS
+};
 pub use atomic::{AtomicStorageManager, StorageTransaction, TransactionState};
+pub use datalog::{DataLogger, LogRecord, LOG_RECORD_SIZE};
+pub use wear_leveling::{WearLevelingManager, EraseSpread};
+pub use namespace::{Namespace, MAX_NAMESPACE_LEN};
+pub use nor_flash::{NorFlashStorage, BlockingFlash, NOR_SLOT_SIZE};
+pub use ota::{
+    OtaPartitions, OtaWriter, OtaState, TrialBoot, ImageInfo, Partition, find_partition,
+    PARTITION_TABLE_OFFSET, MAX_OTA_SLOTS, OTA_WRITE_CHUNK
+};
 
 #[cfg(feature = "esp32c3-flash")]
 pub use esp32c3::{Esp32C3Storage, Esp32C3Config};
@@ -81,9 +103,11 @@ pub const MAX_KEYS: usize = 64;
 pub const MAX_TRANSACTIONS: usize = 16;
 /// Maximum number of wear leveling blocks
 pub const MAX_WEAR_BLOCKS: usize = 32;
+/// Maximum number of flash sectors managed (and erase-counted) by one backend
+pub const MAX_FLASH_REGIONS: usize = 16;
 
 // Error handling
-use iot_common::{IoTError, IoTResult, SystemError};
+use iot_common::{IoTError, IoTResult, SystemError, SharedTimestampProvider, UptimeTimestampProvider};
 use iot_config::{StorageConfig, EmbeddedConfig};
 
 /// Storage management errors
@@ -232,22 +256,36 @@ pub struct UnifiedStorageManager<B: StorageBackend> {
     atomic_manager: AtomicStorageManager<B>,
     /// Storage statistics
     stats: StorageStats,
+    /// Outcome of the latest maintenance attempt
+    last_maintenance: Option<MaintenanceOutcome>,
+    /// Time source shared by every record this manager writes
+    clock: SharedTimestampProvider,
 }
 
 impl<B: StorageBackend> UnifiedStorageManager<B> {
-    /// Create new unified storage manager
+    /// Create new unified storage manager timestamping with uptime
     pub fn new(backend: B) -> StorageManagerResult<Self> {
-        let config_store = ConfigStore::new(&backend).map_err(|_e| {
+        Self::with_timestamp_provider(backend, &UptimeTimestampProvider)
+    }
+
+    /// Create new unified storage manager with the given time source
+    /// 
+    /// Configuration entries and transactions take their times from `clock`.
+    /// Expiry uses only its monotonic part, see [`iot_common::TimestampProvider`].
+    pub fn with_timestamp_provider(backend: B, clock: SharedTimestampProvider) -> StorageManagerResult<Self> {
+        let mut config_store = ConfigStore::new(&backend).map_err(|_e| {
             StorageErrorKind::OperationFailed(
                 create_error_string("Failed to create config store")
             )
         })?;
         
-        let atomic_manager = AtomicStorageManager::new(&backend).map_err(|_e| {
+        let mut atomic_manager = AtomicStorageManager::new(&backend).map_err(|_e| {
             StorageErrorKind::OperationFailed(
                 create_error_string("Failed to create atomic manager")
             )
         })?;
+        config_store.set_timestamp_provider(clock);
+        atomic_manager.set_timestamp_provider(clock);
         
         let stats = backend.get_stats().map_err(|_e| {
             StorageErrorKind::OperationFailed(
@@ -260,9 +298,17 @@ impl<B: StorageBackend> UnifiedStorageManager<B> {
             config_store,
             atomic_manager,
             stats,
+            last_maintenance: None,
+            clock,
         })
     }
 
+    /// Time source of this manager, for records written elsewhere (e.g.
+    /// [`LogRecord::stamped`]) to share
+    pub fn timestamp_provider(&self) -> SharedTimestampProvider {
+        self.clock
+    }
+
     /// Get storage capacity information
     pub fn get_capacity(&self) -> StorageManagerResult<StorageCapacity> {
         self.backend.get_capacity().map_err(|_e| {
@@ -282,9 +328,9 @@ impl<B: StorageBackend> UnifiedStorageManager<B> {
         Ok(self.stats.clone())
     }
 
-    /// Get configuration store
-    pub fn config_store(&mut self) -> &mut ConfigStore<B> {
-        &mut self.config_store
+    /// Get configuration store bound to this manager's backend
+    pub fn config_store(&mut self) -> BoundConfigStore<'_, B> {
+        self.config_store.bind(&mut self.backend)
     }
 
     /// Get atomic storage manager
@@ -292,6 +338,11 @@ impl<B: StorageBackend> UnifiedStorageManager<B> {
         &mut self.atomic_manager
     }
 
+    /// Get a view scoped to one subsystem's keys, see [`Namespace`]
+    pub fn namespace(&mut self, name: &str) -> StorageManagerResult<Namespace<'_, B>> {
+        Namespace::new(self, name)
+    }
+
     /// Store data with key
     pub async fn store<T>(&mut self, key: &str, value: &T) -> StorageManagerResult<()> 
     where
@@ -369,6 +420,59 @@ impl<B: StorageBackend> UnifiedStorageManager<B> {
         })
     }
 
+    /// Store `new` under `key` only if the stored value equals `expected`
+    /// 
+    /// Values are compared in their serialized form, so `expected` must have
+    /// the type `key` was stored with. `None` expects the key to be absent.
+    /// See [`AtomicStorageManager::compare_and_swap`].
+    /// 
+    /// ```rust,ignore
+    /// // Monotonic boot counter, safe against a concurrent writer
+    /// loop {
+    ///     let count = storage.retrieve::<u32>("boot_count").await.ok();
+    ///     let next = count.map_or(1, |c| c + 1);
+    ///     if storage.compare_and_swap("boot_count", count.as_ref(), &next).await? {
+    ///         break;
+    ///     }
+    /// }
+    /// ```
+    pub async fn compare_and_swap<T>(&mut self, key: &str, expected: Option<&T>, new: &T) -> StorageManagerResult<bool>
+    where
+        T: serde::Serialize,
+    {
+        let storage_key = StorageKey::from_str(key).map_err(|_| {
+            StorageErrorKind::OperationFailed(
+                create_error_string("Invalid key")
+            )
+        })?;
+        
+        let serialize = |value: &T| {
+            let bytes: heapless::Vec<u8, 4096> = serde_json_core::to_vec(value).map_err(|_| {
+                StorageErrorKind::SerializationFailed(
+                    create_error_string("Failed to serialize data")
+                )
+            })?;
+            StorageValue::from_bytes(&bytes).map_err(|_| {
+                StorageErrorKind::OperationFailed(
+                    create_error_string("Invalid value")
+                )
+            })
+        };
+        let expected = expected.map(serialize).transpose()?;
+        let new = serialize(new)?;
+        
+        self.atomic_manager.compare_and_swap(&mut self.backend, &storage_key, expected.as_ref(), &new).await.map_err(|e| {
+            match e {
+                StorageError::TransactionConflict => StorageErrorKind::TransactionFailed(
+                    create_error_string("Key locked by another transaction")
+                ),
+                _ => StorageErrorKind::OperationFailed(
+                    create_error_string("Compare-and-swap failed")
+                ),
+            }
+        })
+    }
+
     /// Check if key exists
     pub async fn exists(&mut self, key: &str) -> StorageManagerResult<bool> {
         let storage_key = StorageKey::from_str(key).map_err(|_| {
@@ -394,12 +498,66 @@ impl<B: StorageBackend> UnifiedStorageManager<B> {
     }
 
     /// Perform storage maintenance (garbage collection, defragmentation)
+    ///
+    /// Refused while the atomic manager has an open transaction, so garbage
+    /// collection never moves data a transaction is still writing. Every
+    /// attempt is recorded, see [`last_maintenance`](Self::last_maintenance).
     pub async fn maintenance(&mut self) -> StorageManagerResult<()> {
-        self.backend.maintenance().await.map_err(|_e| {
+        if !self.atomic_manager.list_active_transactions().is_empty() {
+            self.last_maintenance = Some(MaintenanceOutcome::Deferred);
+            return Err(StorageErrorKind::TransactionFailed(
+                create_error_string("Maintenance deferred: transaction in progress")
+            ));
+        }
+        
+        let result = self.backend.maintenance().await.map_err(|_e| {
             StorageErrorKind::OperationFailed(
                 create_error_string("Maintenance failed")
             )
-        })
+        });
+        
+        self.last_maintenance = Some(if result.is_ok() {
+            MaintenanceOutcome::Completed
+        } else {
+            MaintenanceOutcome::Failed
+        });
+        // Fragmentation is derived from the stats, refresh them for the next check
+        let _ = self.get_stats();
+        result
+    }
+
+    /// Run maintenance only if the health check asks for it
+    ///
+    /// Meant to be called when the device is idle (e.g. right after a
+    /// publish with no sensor read pending). Returns `None` when no
+    /// maintenance was needed, otherwise the outcome of the attempt.
+    pub async fn maintenance_if_needed(&mut self) -> Option<MaintenanceOutcome> {
+        if !self.needs_maintenance() {
+            return None;
+        }
+        let _ = self.maintenance().await;
+        self.last_maintenance
+    }
+
+    /// Outcome of the latest maintenance attempt, `None` if there was none
+    pub fn last_maintenance(&self) -> Option<MaintenanceOutcome> {
+        self.last_maintenance
+    }
+
+    /// Erase all stored data and reset statistics (factory reset)
+    ///
+    /// Leaves the backend empty but valid, so the next boot finds no keys
+    /// rather than corrupted ones. Returns the number of bytes reclaimed.
+    pub async fn format(&mut self) -> StorageManagerResult<usize> {
+        let reclaimed = self.backend.format().await.map_err(|_e| {
+            StorageErrorKind::OperationFailed(
+                create_error_string("Format failed")
+            )
+        })?;
+        self.config_store.clear_cache();
+        self.get_stats()?;
+        
+        Ok(reclaimed)
     }
 
     /// Get storage health information
@@ -410,6 +568,7 @@ impl<B: StorageBackend> UnifiedStorageManager<B> {
             fragmentation_level: self.calculate_fragmentation(),
             wear_level: self.calculate_wear_level(),
             needs_maintenance: self.needs_maintenance(),
+            last_maintenance: self.last_maintenance,
         }
     }
 
@@ -426,13 +585,13 @@ impl<B: StorageBackend> UnifiedStorageManager<B> {
 
     /// Calculate wear level (0-100)
     fn calculate_wear_level(&self) -> u8 {
-        // Estimate wear level based on erase cycles
+        // The most-erased sector fails first, so it defines the wear level;
+        // backends without per-sector counters only report the total
         let max_cycles = 100000; // Typical flash endurance
-        if self.stats.erase_cycles == 0 {
-            0
-        } else {
-            ((self.stats.erase_cycles * 100) / max_cycles).min(100) as u8
-        }
+        let cycles = self.stats.max_sector_erase_cycles()
+            .map(u64::from)
+            .unwrap_or(self.stats.erase_cycles);
+        ((cycles * 100) / max_cycles).min(100) as u8
     }
 
     /// Check if storage needs maintenance
@@ -454,6 +613,30 @@ pub struct StorageHealth {
     pub wear_level: u8,
     /// Whether maintenance is needed
     pub needs_maintenance: bool,
+    /// Outcome of the latest maintenance attempt
+    pub last_maintenance: Option<MaintenanceOutcome>,
+}
+
+/// Result of a maintenance attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaintenanceOutcome {
+    /// Garbage collection ran to completion
+    Completed,
+    /// Skipped because a transaction was in progress
+    Deferred,
+    /// The backend reported an error
+    Failed,
+}
+
+impl MaintenanceOutcome {
+    /// Short lowercase name for console output
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MaintenanceOutcome::Completed => "completed",
+            MaintenanceOutcome::Deferred => "deferred",
+            MaintenanceOutcome::Failed => "failed",
+        }
+    }
 }
 
 impl StorageHealth {
@@ -506,6 +689,26 @@ pub mod init {
         UnifiedStorageManager::new(backend)
     }
 
+    /// Initialize storage system over a region of NOR flash
+    ///
+    /// Wrap blocking drivers such as `esp_storage::FlashStorage` in
+    /// [`BlockingFlash`].
+    pub async fn init_nor_flash_storage<F>(
+        flash: F,
+        base_address: u32,
+        size: usize,
+    ) -> StorageManagerResult<UnifiedStorageManager<NorFlashStorage<F>>>
+    where
+        F: embedded_storage_async::nor_flash::NorFlash + Send + Sync,
+    {
+        let backend = NorFlashStorage::new(flash, base_address, size).await.map_err(|_e| {
+            StorageErrorKind::HardwareError(
+                create_error_string("Failed to open flash region")
+            )
+        })?;
+        UnifiedStorageManager::new(backend)
+    }
+
     /// Initialize storage system for testing
     pub fn init_mock_storage() -> StorageManagerResult<UnifiedStorageManager<MockStorage>> {
         let backend = MockStorage::new();
@@ -529,8 +732,15 @@ pub mod init {
 }
 
 /// Mock storage implementation for testing
+/// 
+/// Simulates [`MAX_WEAR_BLOCKS`] flash blocks behind a [`WearLevelingManager`]:
+/// every key owns a logical block and each store remaps it to the least-erased
+/// physical block, counting one erase, so tests can observe wear distribution.
 pub struct MockStorage {
     data: heapless::FnvIndexMap<StorageKeyString, Vec<u8>, 32>,
+    /// Logical block owned by each key
+    blocks: heapless::FnvIndexMap<StorageKeyString, usize, 32>,
+    wear: WearLevelingManager,
     capacity: StorageCapacity,
     stats: StorageStats,
 }
@@ -540,6 +750,9 @@ impl MockStorage {
     pub fn new() -> Self {
         Self {
             data: heapless::FnvIndexMap::new(),
+            blocks: heapless::FnvIndexMap::new(),
+            wear: WearLevelingManager::new(MAX_WEAR_BLOCKS, 100_000)
+                .expect("MAX_WEAR_BLOCKS fits the sector table"),
             capacity: StorageCapacity {
                 total_bytes: 65536,
                 used_bytes: 0,
@@ -550,14 +763,34 @@ impl MockStorage {
             stats: StorageStats::default(),
         }
     }
+
+    /// Simulated block wear, for checking write distribution
+    pub fn wear_leveling(&self) -> &WearLevelingManager {
+        &self.wear
+    }
+
+    /// Logical block of `key`, allocating the lowest free one for a new key
+    fn logical_block(&self, key: &StorageKeyString) -> StorageResult<usize> {
+        if let Some(&logical) = self.blocks.get(key) {
+            return Ok(logical);
+        }
+        (0..self.wear.logical_block_count())
+            .find(|logical| !self.blocks.values().any(|used| used == logical))
+            .ok_or(StorageError::CapacityExceeded)
+    }
 }
 
-#[async_trait::async_trait]
+#[async_trait::async_trait(?Send)]
 impl StorageBackend for MockStorage {
     async fn store(&mut self, key: &StorageKey, value: &StorageValue) -> StorageResult<()> {
         let key_str = StorageKeyString::from_str(key.as_str()).map_err(|_| StorageError::InvalidKey)?;
         let value_bytes = value.as_bytes().to_vec();
         
+        let logical = self.logical_block(&key_str)?;
+        self.wear.map_write(logical)?;
+        self.stats.erase_cycles += 1;
+        
+        self.blocks.insert(key_str.clone(), logical).map_err(|_| StorageError::CapacityExceeded)?;
         self.data.insert(key_str, value_bytes).map_err(|_| StorageError::CapacityExceeded)?;
         self.stats.total_writes += 1;
         
@@ -577,6 +810,9 @@ impl StorageBackend for MockStorage {
         let key_str = StorageKeyString::from_str(key.as_str()).map_err(|_| StorageError::InvalidKey)?;
         
         self.data.remove(&key_str).ok_or(StorageError::KeyNotFound)?;
+        if let Some(logical) = self.blocks.remove(&key_str) {
+            self.wear.release(logical);
+        }
         self.stats.total_deletes += 1;
         
         Ok(())
@@ -608,6 +844,17 @@ impl StorageBackend for MockStorage {
         Ok(())
     }
 
+    async fn format(&mut self) -> StorageResult<usize> {
+        let reclaimed = self.data.values().map(|value| value.len()).sum();
+        for &logical in self.blocks.values() {
+            self.wear.release(logical);
+        }
+        self.blocks.clear();
+        self.data.clear();
+        self.stats.reset_operations();
+        Ok(reclaimed)
+    }
+
     fn get_capacity(&self) -> StorageResult<StorageCapacity> {
         Ok(self.capacity.clone())
     }
@@ -679,8 +926,8 @@ impl FlashStorageManager {
         
         // Configure based on settings
         storage.set_flash_offset(config.flash_offset);
-        storage.set_backup_enabled(config.backup_enabled);
-        storage.set_wear_leveling(config.wear_leveling);
+        storage.set_backup_enabled(config.backup_enabled).await?;
+        storage.set_wear_leveling(config.wear_leveling).await?;
         
         Ok(storage)
     }
@@ -721,14 +968,136 @@ impl FlashStorageManager {
     }
     
     /// Enable wear leveling
+    /// 
+    /// Stores remap each key's block to the least-erased free data sector.
     pub async fn enable_wear_leveling(&mut self) -> StorageResult<()> {
-        // Implementation for wear leveling enabling
+        self.set_wear_leveling_enabled(true);
         Ok(())
     }
     
     /// Disable wear leveling
+    /// 
+    /// Stores go to the first free data sector; existing mappings are kept.
     pub async fn disable_wear_leveling(&mut self) -> StorageResult<()> {
-        // Implementation for wear leveling disabling
+        self.set_wear_leveling_enabled(false);
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iot_common::network::block_on;
+
+    extern crate std;
+
+    #[test]
+    fn test_maintenance_waits_for_transactions() {
+        // The manager embeds its transaction table (about 2 MB), more than the
+        // default test thread stack
+        std::thread::Builder::new()
+            .stack_size(16 * 1024 * 1024)
+            .spawn(|| {
+                let mut storage = init::init_mock_storage().unwrap();
+                assert_eq!(storage.last_maintenance(), None);
+
+                // A healthy store is left alone
+                assert_eq!(block_on(storage.maintenance_if_needed()), None);
+
+                block_on(storage.maintenance()).unwrap();
+                assert_eq!(storage.last_maintenance(), Some(MaintenanceOutcome::Completed));
+                assert_eq!(storage.get_health().last_maintenance, Some(MaintenanceOutcome::Completed));
+
+                // An open transaction blocks garbage collection
+                let id = block_on(storage.atomic_manager().begin_transaction()).unwrap();
+                assert!(block_on(storage.maintenance()).is_err());
+                assert_eq!(storage.last_maintenance(), Some(MaintenanceOutcome::Deferred));
+
+                block_on(storage.atomic_manager().rollback_transaction(id)).unwrap();
+                block_on(storage.maintenance()).unwrap();
+                assert_eq!(storage.last_maintenance(), Some(MaintenanceOutcome::Completed));
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_compare_and_swap() {
+        std::thread::Builder::new()
+            .stack_size(16 * 1024 * 1024)
+            .spawn(|| {
+                let mut storage = init::init_mock_storage().unwrap();
+
+                // Absent key: only a `None` expectation provisions it
+                assert!(!block_on(storage.compare_and_swap("boot_count", Some(&0u32), &1)).unwrap());
+                assert!(block_on(storage.compare_and_swap("boot_count", None, &1u32)).unwrap());
+                assert!(!block_on(storage.compare_and_swap("boot_count", None, &5u32)).unwrap(), "Provisioning is idempotent");
+                assert_eq!(block_on(storage.retrieve::<u32>("boot_count")).unwrap(), 1);
+
+                // Two writers read the same count, then both try to bump it
+                let seen_a: u32 = block_on(storage.retrieve("boot_count")).unwrap();
+                let seen_b: u32 = block_on(storage.retrieve("boot_count")).unwrap();
+                assert!(block_on(storage.compare_and_swap("boot_count", Some(&seen_a), &(seen_a + 1))).unwrap());
+                assert!(!block_on(storage.compare_and_swap("boot_count", Some(&seen_b), &(seen_b + 1))).unwrap(), "Stale writer loses");
+
+                // The loser re-reads and retries; no increment is lost
+                let seen_b: u32 = block_on(storage.retrieve("boot_count")).unwrap();
+                assert!(block_on(storage.compare_and_swap("boot_count", Some(&seen_b), &(seen_b + 1))).unwrap());
+                assert_eq!(block_on(storage.retrieve::<u32>("boot_count")).unwrap(), 3);
+                assert!(storage.atomic_manager().list_active_transactions().is_empty(), "Swaps leave no transaction open");
+
+                // A key with a pending write in another transaction is locked
+                let key = StorageKey::new("boot_count").unwrap();
+                let id = block_on(storage.atomic_manager().begin_transaction()).unwrap();
+                block_on(storage.atomic_manager().atomic_store(id, &key, &StorageValue::from_bytes(b"9").unwrap())).unwrap();
+                assert!(matches!(
+                    block_on(storage.compare_and_swap("boot_count", Some(&3u32), &4)),
+                    Err(StorageErrorKind::TransactionFailed(_))
+                ));
+                block_on(storage.atomic_manager().rollback_transaction(id)).unwrap();
+                assert!(block_on(storage.compare_and_swap("boot_count", Some(&3u32), &4)).unwrap());
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    /// Clock the test moves by hand
+    struct TestClock(core::sync::atomic::AtomicU64);
+
+    impl iot_common::TimestampProvider for TestClock {
+        fn get_timestamp_ms(&self) -> u64 {
+            self.0.load(core::sync::atomic::Ordering::Relaxed)
+        }
+    }
+
+    #[test]
+    fn test_transactions_expire_on_provider_clock() {
+        static CLOCK: TestClock = TestClock(core::sync::atomic::AtomicU64::new(5_000));
+
+        std::thread::Builder::new()
+            .stack_size(16 * 1024 * 1024)
+            .spawn(|| {
+                let mut storage = UnifiedStorageManager::with_timestamp_provider(MockStorage::new(), &CLOCK).unwrap();
+                assert_eq!(storage.timestamp_provider().get_timestamp_ms(), 5_000);
+
+                let stale = block_on(storage.atomic_manager().begin_transaction()).unwrap();
+                assert_eq!(storage.atomic_manager().get_transaction(stale).unwrap().created_at, 5_000);
+
+                // Still inside the 30 s timeout: kept
+                CLOCK.0.store(35_000, core::sync::atomic::Ordering::Relaxed);
+                let fresh = block_on(storage.atomic_manager().begin_transaction()).unwrap();
+                assert!(storage.atomic_manager().get_transaction(stale).is_some());
+
+                // Past it: rolled back when the next transaction starts
+                CLOCK.0.store(35_001, core::sync::atomic::Ordering::Relaxed);
+                block_on(storage.atomic_manager().begin_transaction()).unwrap();
+                assert!(storage.atomic_manager().get_transaction(stale).is_none());
+                assert_eq!(storage.atomic_manager().get_transaction(fresh).unwrap().created_at, 35_000);
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+}
@@ -0,0 +1,200 @@
+//! Namespaced views of the flat storage keyspace
+//!
+//! Subsystems (WiFi config, MQTT config, data log, performance baselines)
+//! share one backend. A [`Namespace`] prefixes every key with
+//! `"<name>."` so identical sub-keys from different subsystems never
+//! collide, and scopes listing and clearing to the subsystem's own keys.
+//!
+//! ```rust,ignore
+//! let mut storage = init_mock_storage()?;
+//! storage.namespace("wifi")?.store("ssid", &ssid).await?;  // key "wifi.ssid"
+//! storage.namespace("mqtt")?.store("ssid", &other).await?; // key "mqtt.ssid"
+//! storage.namespace("wifi")?.clear().await?;               // mqtt keys untouched
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use crate::{
+    traits::StorageBackend,
+    create_error_string, StorageErrorKind, StorageManagerResult, UnifiedStorageManager, MAX_KEY_LEN,
+};
+
+/// Maximum namespace name length
+pub const MAX_NAMESPACE_LEN: usize = 16;
+
+/// Separator between the namespace name and the sub-key
+pub const NAMESPACE_SEPARATOR: char = '.';
+
+/// Storage scoped to one subsystem's keys
+///
+/// Borrowed from [`UnifiedStorageManager::namespace`]; the backend stays
+/// flat underneath.
+pub struct Namespace<'a, B: StorageBackend> {
+    storage: &'a mut UnifiedStorageManager<B>,
+    /// Namespace name followed by the separator
+    prefix: heapless::String<{ MAX_NAMESPACE_LEN + 1 }>,
+}
+
+impl<'a, B: StorageBackend> Namespace<'a, B> {
+    /// Create a namespace view
+    ///
+    /// Names are non-empty, at most [`MAX_NAMESPACE_LEN`] characters and
+    /// limited to alphanumerics, underscore and dash, so one namespace can
+    /// never be a prefix of another's keys.
+    pub(crate) fn new(storage: &'a mut UnifiedStorageManager<B>, name: &str) -> StorageManagerResult<Self> {
+        let valid = !name.is_empty()
+            && name.len() <= MAX_NAMESPACE_LEN
+            && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+        if !valid {
+            return Err(StorageErrorKind::OperationFailed(
+                create_error_string("Invalid namespace name")
+            ));
+        }
+
+        let mut prefix = heapless::String::new();
+        // Both pushes fit: the capacity is MAX_NAMESPACE_LEN + 1
+        let _ = prefix.push_str(name);
+        let _ = prefix.push(NAMESPACE_SEPARATOR);
+
+        Ok(Self { storage, prefix })
+    }
+
+    /// Namespace name, without the separator
+    pub fn name(&self) -> &str {
+        &self.prefix[..self.prefix.len() - 1]
+    }
+
+    /// Longest sub-key that still fits in [`MAX_KEY_LEN`] once prefixed
+    pub fn max_key_len(&self) -> usize {
+        MAX_KEY_LEN - self.prefix.len()
+    }
+
+    /// Full backend key for `key`
+    pub fn full_key(&self, key: &str) -> StorageManagerResult<String> {
+        if key.is_empty() || key.len() > self.max_key_len() {
+            return Err(StorageErrorKind::OperationFailed(
+                create_error_string("Namespaced key too long")
+            ));
+        }
+
+        let mut full = String::with_capacity(self.prefix.len() + key.len());
+        full.push_str(&self.prefix);
+        full.push_str(key);
+        Ok(full)
+    }
+
+    /// Store data under `key` in this namespace
+    pub async fn store<T>(&mut self, key: &str, value: &T) -> StorageManagerResult<()>
+    where
+        T: serde::Serialize,
+    {
+        let full = self.full_key(key)?;
+        self.storage.store(&full, value).await
+    }
+
+    /// Retrieve data stored under `key` in this namespace
+    pub async fn retrieve<T>(&mut self, key: &str) -> StorageManagerResult<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let full = self.full_key(key)?;
+        self.storage.retrieve(&full).await
+    }
+
+    /// Delete `key` from this namespace
+    pub async fn delete(&mut self, key: &str) -> StorageManagerResult<()> {
+        let full = self.full_key(key)?;
+        self.storage.delete(&full).await
+    }
+
+    /// Check if `key` exists in this namespace
+    pub async fn exists(&mut self, key: &str) -> StorageManagerResult<bool> {
+        let full = self.full_key(key)?;
+        self.storage.exists(&full).await
+    }
+
+    /// List this namespace's sub-keys, optionally filtered by sub-key prefix
+    ///
+    /// Returned keys have the namespace prefix stripped.
+    pub async fn list_keys(&mut self, prefix: Option<&str>) -> StorageManagerResult<Vec<String>> {
+        let mut scoped = String::from(self.prefix.as_str());
+        if let Some(prefix) = prefix {
+            scoped.push_str(prefix);
+        }
+
+        let keys = self.storage.list_keys(Some(&scoped)).await?;
+        Ok(keys.into_iter()
+            .map(|key| String::from(&key[self.prefix.len()..]))
+            .collect())
+    }
+
+    /// Delete every key in this namespace, returning how many were removed
+    ///
+    /// Keys of other namespaces and un-namespaced keys are left untouched.
+    pub async fn clear(&mut self) -> StorageManagerResult<usize> {
+        let keys = self.storage.list_keys(Some(&self.prefix)).await?;
+        for key in &keys {
+            self.storage.delete(key).await?;
+        }
+        Ok(keys.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init::init_mock_storage, MockStorage};
+    use iot_common::network::block_on;
+
+    extern crate std;
+
+    /// Run `test` against a fresh mock-backed manager
+    ///
+    /// The manager embeds its whole transaction table (about 2 MB), more than
+    /// the default test thread stack, so the test gets a thread of its own.
+    fn with_storage(test: fn(&mut UnifiedStorageManager<MockStorage>)) {
+        std::thread::Builder::new()
+            .stack_size(16 * 1024 * 1024)
+            .spawn(move || test(&mut init_mock_storage().unwrap()))
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_namespaces_isolate_identical_keys() {
+        with_storage(|storage| {
+            block_on(storage.namespace("wifi").unwrap().store("ssid", &1u32)).unwrap();
+            block_on(storage.namespace("mqtt").unwrap().store("ssid", &2u32)).unwrap();
+            block_on(storage.namespace("wifi").unwrap().store("pass", &3u32)).unwrap();
+
+            let mut wifi = storage.namespace("wifi").unwrap();
+            assert_eq!(block_on(wifi.retrieve::<u32>("ssid")).unwrap(), 1);
+            let mut keys = block_on(wifi.list_keys(None)).unwrap();
+            keys.sort();
+            assert_eq!(keys, ["pass", "ssid"]);
+
+            // Clearing one namespace leaves the other intact
+            assert_eq!(block_on(wifi.clear()).unwrap(), 2);
+            assert!(!block_on(wifi.exists("ssid")).unwrap());
+
+            let mut mqtt = storage.namespace("mqtt").unwrap();
+            assert_eq!(block_on(mqtt.retrieve::<u32>("ssid")).unwrap(), 2);
+            assert_eq!(block_on(mqtt.list_keys(None)).unwrap(), ["ssid"]);
+        });
+    }
+
+    #[test]
+    fn test_namespace_key_limits() {
+        with_storage(|storage| {
+            assert!(storage.namespace("").is_err());
+            assert!(storage.namespace("wifi.config").is_err());
+            assert!(storage.namespace(&"n".repeat(MAX_NAMESPACE_LEN + 1)).is_err());
+
+            let ns = storage.namespace("perf").unwrap();
+            assert_eq!(ns.name(), "perf");
+            assert_eq!(ns.max_key_len(), MAX_KEY_LEN - 5);
+            assert!(ns.full_key(&"k".repeat(ns.max_key_len())).is_ok());
+            assert!(ns.full_key(&"k".repeat(ns.max_key_len() + 1)).is_err());
+        });
+    }
+}
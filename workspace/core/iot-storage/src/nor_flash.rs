@@ -0,0 +1,518 @@
+//! # NOR Flash Backend
+//!
+//! [`StorageBackend`] over any [`embedded_storage_async`] NOR flash driver, so
+//! [`UnifiedStorageManager`](crate::UnifiedStorageManager) can run on real
+//! hardware. The region is divided into fixed [`NOR_SLOT_SIZE`]-byte slots
+//! holding one key-value record each.
+//!
+//! Record layout (little endian):
+//!
+//! | Offset   | Size  | Field                                      |
+//! |----------|-------|--------------------------------------------|
+//! | 0        | 2     | magic `0x4B56`                             |
+//! | 2        | 1     | key length                                 |
+//! | 3        | 1     | reserved                                   |
+//! | 4        | 2     | value length                               |
+//! | 6        | 2     | reserved                                   |
+//! | 8        | 4     | checksum of key and value                  |
+//! | 12       | key   | key bytes                                  |
+//! | 12 + key | value | value bytes                                |
+//!
+//! A new key is written straight into an erased slot. NOR flash only erases
+//! whole sectors, so replacing or deleting a record reads the sector, patches
+//! the slot in RAM, erases the sector and writes the surviving records back
+//! (read-modify-erase-write). A power loss inside that window loses the
+//! sector's other records too.
+//!
+//! The key index is rebuilt by scanning the region in [`NorFlashStorage::new`];
+//! a slot torn by a power loss fails its checksum and is reclaimed by
+//! [`StorageBackend::maintenance`].
+//!
+//! esp-storage's `FlashStorage` is blocking; wrap it in [`BlockingFlash`]:
+//!
+//! ```rust,ignore
+//! use iot_storage::{BlockingFlash, NorFlashStorage, UnifiedStorageManager};
+//!
+//! let flash = BlockingFlash::new(esp_storage::FlashStorage::new());
+//! let backend = NorFlashStorage::new(flash, 0x330000, 16 * 1024).await?;
+//! let mut storage = UnifiedStorageManager::new(backend)?;
+//! storage.store("wifi.ssid", &ssid).await?;
+//! ```
+
+use alloc::{boxed::Box, string::{String, ToString}, vec, vec::Vec};
+use embedded_storage::nor_flash as blocking;
+use embedded_storage_async::nor_flash::{ErrorType, NorFlash, ReadNorFlash};
+use heapless::FnvIndexMap;
+
+use crate::traits::{
+    utils::calculate_checksum, StorageBackend, StorageCapacity, StorageError, StorageKey,
+    StorageResult, StorageStats, StorageValue,
+};
+use crate::{StorageKeyString, MAX_KEYS, MAX_KEY_LEN};
+
+/// Size of one record slot in flash
+pub const NOR_SLOT_SIZE: usize = 512;
+
+/// Bytes before the key in a record
+const RECORD_HEADER_SIZE: usize = 12;
+
+/// Marks a slot holding a record
+const RECORD_MAGIC: u16 = 0x4B56;
+
+/// Largest value that fits in a slot next to a key of `key_len` bytes
+pub const fn max_value_len(key_len: usize) -> usize {
+    NOR_SLOT_SIZE - RECORD_HEADER_SIZE - key_len
+}
+
+/// Contents of a slot, as found by the last scan or write
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SlotState {
+    /// Erased, writable without an erase
+    Erased,
+    /// Holds a valid record listed in the index
+    Used,
+    /// Written but invalid (torn write); needs its sector erased
+    Dirty,
+}
+
+/// Encode a record into a slot image
+fn encode_record(key: &str, value: &[u8]) -> StorageResult<[u8; NOR_SLOT_SIZE]> {
+    if value.len() > max_value_len(key.len()) {
+        return Err(StorageError::CapacityExceeded);
+    }
+
+    let mut slot = [0xFF; NOR_SLOT_SIZE];
+    let end = RECORD_HEADER_SIZE + key.len() + value.len();
+    slot[0..2].copy_from_slice(&RECORD_MAGIC.to_le_bytes());
+    slot[2] = key.len() as u8;
+    slot[4..6].copy_from_slice(&(value.len() as u16).to_le_bytes());
+    slot[RECORD_HEADER_SIZE..RECORD_HEADER_SIZE + key.len()].copy_from_slice(key.as_bytes());
+    slot[RECORD_HEADER_SIZE + key.len()..end].copy_from_slice(value);
+    let checksum = calculate_checksum(&slot[RECORD_HEADER_SIZE..end]);
+    slot[8..12].copy_from_slice(&checksum.to_le_bytes());
+    Ok(slot)
+}
+
+/// Decode a slot image, returning its key and value if it holds a valid record
+fn decode_record(slot: &[u8]) -> Option<(&str, &[u8])> {
+    let key_len = slot[2] as usize;
+    let value_len = u16::from_le_bytes([slot[4], slot[5]]) as usize;
+    if u16::from_le_bytes([slot[0], slot[1]]) != RECORD_MAGIC
+        || key_len == 0
+        || key_len > MAX_KEY_LEN
+        || value_len > max_value_len(key_len)
+    {
+        return None;
+    }
+
+    let end = RECORD_HEADER_SIZE + key_len + value_len;
+    let checksum = u32::from_le_bytes([slot[8], slot[9], slot[10], slot[11]]);
+    if calculate_checksum(&slot[RECORD_HEADER_SIZE..end]) != checksum {
+        return None;
+    }
+
+    let key = core::str::from_utf8(&slot[RECORD_HEADER_SIZE..RECORD_HEADER_SIZE + key_len]).ok()?;
+    Some((key, &slot[RECORD_HEADER_SIZE + key_len..end]))
+}
+
+/// Key-value storage in a region of NOR flash
+pub struct NorFlashStorage<F: NorFlash> {
+    /// Flash holding the region
+    flash: F,
+    /// Offset of the region in `flash`, sector aligned
+    base_address: u32,
+    /// State of every slot in the region
+    slots: Vec<SlotState>,
+    /// Slot holding each key
+    index: FnvIndexMap<StorageKeyString, usize, MAX_KEYS>,
+    stats: StorageStats,
+}
+
+impl<F: NorFlash> NorFlashStorage<F> {
+    /// Open the store in `size` bytes of `flash` starting at `base_address`
+    ///
+    /// The region must be sector aligned, and the flash's read and write
+    /// granularity must divide [`NOR_SLOT_SIZE`]. Existing records are scanned
+    /// into the index.
+    pub async fn new(flash: F, base_address: u32, size: usize) -> StorageResult<Self> {
+        let sector_size = F::ERASE_SIZE;
+        if !sector_size.is_multiple_of(NOR_SLOT_SIZE)
+            || !NOR_SLOT_SIZE.is_multiple_of(F::WRITE_SIZE)
+            || !NOR_SLOT_SIZE.is_multiple_of(F::READ_SIZE)
+            || !(base_address as usize).is_multiple_of(sector_size)
+            || !size.is_multiple_of(sector_size)
+            || size == 0
+        {
+            return Err(StorageError::ConfigurationError("Region must span whole sectors".to_string()));
+        }
+
+        let mut storage = Self {
+            flash,
+            base_address,
+            slots: vec![SlotState::Erased; size / NOR_SLOT_SIZE],
+            index: FnvIndexMap::new(),
+            stats: StorageStats::default(),
+        };
+        storage.scan().await?;
+        Ok(storage)
+    }
+
+    /// Release the flash driver
+    pub fn into_inner(self) -> F {
+        self.flash
+    }
+
+    /// Number of slots per flash sector
+    fn slots_per_sector() -> usize {
+        F::ERASE_SIZE / NOR_SLOT_SIZE
+    }
+
+    fn slot_address(&self, slot: usize) -> u32 {
+        self.base_address + (slot * NOR_SLOT_SIZE) as u32
+    }
+
+    async fn read_slot(&mut self, slot: usize, buffer: &mut [u8]) -> StorageResult<()> {
+        let address = self.slot_address(slot);
+        self.flash.read(address, buffer).await.map_err(|_| StorageError::HardwareError)
+    }
+
+    /// Rebuild slot states and the key index from flash
+    async fn scan(&mut self) -> StorageResult<()> {
+        let mut buffer = [0u8; NOR_SLOT_SIZE];
+        self.index.clear();
+
+        for slot in 0..self.slots.len() {
+            self.read_slot(slot, &mut buffer).await?;
+            self.slots[slot] = if buffer.iter().all(|&byte| byte == 0xFF) {
+                SlotState::Erased
+            } else {
+                // Duplicates cannot be written; treat one like a torn record
+                match decode_record(&buffer).and_then(|(key, _)| StorageKeyString::try_from(key).ok()) {
+                    Some(key) if !self.index.contains_key(&key) => {
+                        self.index.insert(key, slot).map_err(|_| StorageError::CapacityExceeded)?;
+                        SlotState::Used
+                    }
+                    _ => SlotState::Dirty,
+                }
+            };
+        }
+        Ok(())
+    }
+
+    /// Erase the sector and write back its used slots, `patch` replacing one
+    ///
+    /// Slots not marked [`SlotState::Used`] come back erased, which also
+    /// reclaims any dirty slots in the sector.
+    async fn rewrite_sector(&mut self, sector: usize, patch: Option<(usize, &[u8; NOR_SLOT_SIZE])>) -> StorageResult<()> {
+        let first_slot = sector * Self::slots_per_sector();
+        let from = self.slot_address(first_slot);
+        let mut image = vec![0xFF; F::ERASE_SIZE];
+        self.flash.read(from, &mut image).await.map_err(|_| StorageError::HardwareError)?;
+
+        self.flash.erase(from, from + F::ERASE_SIZE as u32).await
+            .map_err(|_| StorageError::HardwareError)?;
+        self.stats.erase_cycles += 1;
+
+        for (offset, chunk) in image.chunks(NOR_SLOT_SIZE).enumerate() {
+            let slot = first_slot + offset;
+            let record = match patch {
+                Some((patched, record)) if patched == slot => &record[..],
+                _ if self.slots[slot] == SlotState::Used => chunk,
+                _ => {
+                    self.slots[slot] = SlotState::Erased;
+                    continue;
+                }
+            };
+            let address = self.slot_address(slot);
+            self.flash.write(address, record).await.map_err(|_| StorageError::HardwareError)?;
+        }
+        Ok(())
+    }
+
+    /// Bytes held by slots that are not erased
+    fn occupied_bytes(&self) -> usize {
+        self.slots.iter().filter(|&&state| state != SlotState::Erased).count() * NOR_SLOT_SIZE
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl<F: NorFlash + Send + Sync> StorageBackend for NorFlashStorage<F> {
+    async fn store(&mut self, key: &StorageKey, value: &StorageValue) -> StorageResult<()> {
+        let record = encode_record(key.as_str(), value.as_bytes())?;
+        let key_string = StorageKeyString::try_from(key.as_str()).map_err(|_| StorageError::InvalidKey)?;
+
+        let slot = match self.index.get(&key_string) {
+            Some(&slot) => {
+                // Overwriting needs an erase: read-modify-erase-write the sector
+                self.rewrite_sector(slot / Self::slots_per_sector(), Some((slot, &record))).await?;
+                slot
+            }
+            None => {
+                if self.index.len() == MAX_KEYS {
+                    return Err(StorageError::CapacityExceeded);
+                }
+                if let Some(slot) = self.slots.iter().position(|&state| state == SlotState::Erased) {
+                    let address = self.slot_address(slot);
+                    self.flash.write(address, &record).await.map_err(|_| StorageError::HardwareError)?;
+                    slot
+                } else {
+                    let slot = self.slots.iter().position(|&state| state == SlotState::Dirty)
+                        .ok_or(StorageError::CapacityExceeded)?;
+                    self.rewrite_sector(slot / Self::slots_per_sector(), Some((slot, &record))).await?;
+                    slot
+                }
+            }
+        };
+
+        self.slots[slot] = SlotState::Used;
+        self.index.insert(key_string, slot).map_err(|_| StorageError::CapacityExceeded)?;
+        self.stats.total_writes += 1;
+        self.stats.bytes_written += value.len() as u64;
+        Ok(())
+    }
+
+    async fn retrieve(&mut self, key: &StorageKey) -> StorageResult<StorageValue> {
+        let key_string = StorageKeyString::try_from(key.as_str()).map_err(|_| StorageError::InvalidKey)?;
+        let slot = *self.index.get(&key_string).ok_or(StorageError::KeyNotFound)?;
+
+        let mut buffer = [0u8; NOR_SLOT_SIZE];
+        self.read_slot(slot, &mut buffer).await?;
+        let value = match decode_record(&buffer) {
+            Some((stored_key, value)) if stored_key == key.as_str() => StorageValue::from_bytes(value)?,
+            _ => {
+                self.stats.failed_reads += 1;
+                return Err(StorageError::CorruptedData);
+            }
+        };
+
+        self.stats.total_reads += 1;
+        self.stats.bytes_read += value.len() as u64;
+        Ok(value)
+    }
+
+    async fn delete(&mut self, key: &StorageKey) -> StorageResult<()> {
+        let key_string = StorageKeyString::try_from(key.as_str()).map_err(|_| StorageError::InvalidKey)?;
+        let slot = self.index.remove(&key_string).ok_or(StorageError::KeyNotFound)?;
+
+        self.slots[slot] = SlotState::Erased;
+        self.rewrite_sector(slot / Self::slots_per_sector(), None).await?;
+        self.stats.total_deletes += 1;
+        Ok(())
+    }
+
+    async fn exists(&mut self, key: &StorageKey) -> StorageResult<bool> {
+        let key_string = StorageKeyString::try_from(key.as_str()).map_err(|_| StorageError::InvalidKey)?;
+        Ok(self.index.contains_key(&key_string))
+    }
+
+    async fn list_keys(&mut self, prefix: Option<&str>) -> StorageResult<Vec<String>> {
+        Ok(self.index.keys()
+            .filter(|key| prefix.is_none_or(|prefix| key.starts_with(prefix)))
+            .map(|key| key.as_str().to_string())
+            .collect())
+    }
+
+    async fn maintenance(&mut self) -> StorageResult<()> {
+        // Reclaim slots left dirty by torn writes
+        for sector in 0..self.slots.len() / Self::slots_per_sector() {
+            let slots = sector * Self::slots_per_sector()..(sector + 1) * Self::slots_per_sector();
+            if self.slots[slots].contains(&SlotState::Dirty) {
+                self.rewrite_sector(sector, None).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn format(&mut self) -> StorageResult<usize> {
+        let reclaimed = self.occupied_bytes();
+        self.index.clear();
+        self.slots.fill(SlotState::Erased);
+
+        let to = self.slot_address(self.slots.len());
+        for from in (self.base_address..to).step_by(F::ERASE_SIZE) {
+            self.flash.erase(from, from + F::ERASE_SIZE as u32).await
+                .map_err(|_| StorageError::HardwareError)?;
+            self.stats.erase_cycles += 1;
+        }
+        self.stats.reset_operations();
+        Ok(reclaimed)
+    }
+
+    fn get_capacity(&self) -> StorageResult<StorageCapacity> {
+        Ok(StorageCapacity::new(self.slots.len() * NOR_SLOT_SIZE, self.occupied_bytes(), F::ERASE_SIZE))
+    }
+
+    fn get_stats(&self) -> StorageResult<StorageStats> {
+        Ok(self.stats.clone())
+    }
+}
+
+/// Async view of a blocking NOR flash driver such as `esp_storage::FlashStorage`
+///
+/// Every operation completes before its future first returns, so the
+/// executor is blocked for the duration of each read, write or erase.
+pub struct BlockingFlash<F> {
+    inner: F,
+}
+
+impl<F> BlockingFlash<F> {
+    /// Wrap a blocking driver
+    pub fn new(inner: F) -> Self {
+        Self { inner }
+    }
+
+    /// Release the blocking driver
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+}
+
+impl<F: ErrorType> ErrorType for BlockingFlash<F> {
+    type Error = F::Error;
+}
+
+impl<F: blocking::ReadNorFlash> ReadNorFlash for BlockingFlash<F> {
+    const READ_SIZE: usize = F::READ_SIZE;
+
+    async fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        self.inner.read(offset, bytes)
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+}
+
+impl<F: blocking::NorFlash> NorFlash for BlockingFlash<F> {
+    const WRITE_SIZE: usize = F::WRITE_SIZE;
+    const ERASE_SIZE: usize = F::ERASE_SIZE;
+
+    async fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+        self.inner.erase(from, to)
+    }
+
+    async fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.inner.write(offset, bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blocking::NorFlashErrorKind;
+    use iot_common::network::block_on;
+
+    /// Two 1 KB sectors of RAM behaving like NOR flash
+    struct RamFlash {
+        bytes: [u8; 2048],
+        erases: usize,
+    }
+
+    impl RamFlash {
+        fn new() -> Self {
+            Self { bytes: [0xFF; 2048], erases: 0 }
+        }
+    }
+
+    impl ErrorType for RamFlash {
+        type Error = NorFlashErrorKind;
+    }
+
+    impl blocking::ReadNorFlash for RamFlash {
+        const READ_SIZE: usize = 1;
+
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            bytes.copy_from_slice(&self.bytes[offset..offset + bytes.len()]);
+            Ok(())
+        }
+
+        fn capacity(&self) -> usize {
+            self.bytes.len()
+        }
+    }
+
+    impl blocking::NorFlash for RamFlash {
+        const WRITE_SIZE: usize = 4;
+        const ERASE_SIZE: usize = 1024;
+
+        fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+            self.bytes[from as usize..to as usize].fill(0xFF);
+            self.erases += 1;
+            Ok(())
+        }
+
+        fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+            // NOR writes can only clear bits
+            for (target, byte) in self.bytes[offset as usize..].iter_mut().zip(bytes) {
+                *target &= byte;
+            }
+            Ok(())
+        }
+    }
+
+    fn key(name: &str) -> StorageKey {
+        StorageKey::new(name).unwrap()
+    }
+
+    fn value(bytes: &[u8]) -> StorageValue {
+        StorageValue::from_bytes(bytes).unwrap()
+    }
+
+    fn open(flash: RamFlash) -> NorFlashStorage<BlockingFlash<RamFlash>> {
+        block_on(NorFlashStorage::new(BlockingFlash::new(flash), 0, 2048)).unwrap()
+    }
+
+    #[test]
+    fn test_records_survive_remount() {
+        let mut storage = open(RamFlash::new());
+        block_on(storage.store(&key("wifi.ssid"), &value(b"\"home\""))).unwrap();
+        block_on(storage.store(&key("mqtt.port"), &value(b"1883"))).unwrap();
+        // New keys land in erased slots without any erase
+        assert_eq!(storage.flash.inner.erases, 0);
+
+        let mut storage = open(storage.into_inner().into_inner());
+        assert_eq!(block_on(storage.retrieve(&key("wifi.ssid"))).unwrap().as_bytes(), b"\"home\"");
+        assert_eq!(block_on(storage.list_keys(Some("mqtt."))).unwrap(), ["mqtt.port"]);
+    }
+
+    #[test]
+    fn test_overwrite_keeps_sector_neighbours() {
+        let mut storage = open(RamFlash::new());
+        block_on(storage.store(&key("a"), &value(b"1"))).unwrap();
+        block_on(storage.store(&key("b"), &value(b"2"))).unwrap();
+
+        // "a" and "b" share a sector; rewriting "a" erases it and restores "b"
+        block_on(storage.store(&key("a"), &value(b"3"))).unwrap();
+        block_on(storage.delete(&key("b"))).unwrap();
+        assert_eq!(storage.flash.inner.erases, 2);
+
+        let mut storage = open(storage.into_inner().into_inner());
+        assert_eq!(block_on(storage.retrieve(&key("a"))).unwrap().as_bytes(), b"3");
+        assert!(!block_on(storage.exists(&key("b"))).unwrap());
+        assert_eq!(storage.get_capacity().unwrap().used_bytes, NOR_SLOT_SIZE);
+    }
+
+    #[test]
+    fn test_torn_record_is_reclaimed() {
+        let mut flash = RamFlash::new();
+        // Half-written record in slot 0
+        flash.bytes[..8].fill(0x00);
+
+        let mut storage = open(flash);
+        assert!(block_on(storage.list_keys(None)).unwrap().is_empty());
+        assert_eq!(storage.get_capacity().unwrap().used_bytes, NOR_SLOT_SIZE);
+
+        block_on(storage.maintenance()).unwrap();
+        assert_eq!(storage.get_capacity().unwrap().used_bytes, 0);
+        assert!(storage.flash.inner.bytes.iter().all(|&byte| byte == 0xFF));
+    }
+
+    #[test]
+    fn test_oversized_value_is_rejected() {
+        let mut storage = open(RamFlash::new());
+        let big = vec![b'x'; max_value_len(3) + 1];
+        assert_eq!(block_on(storage.store(&key("big"), &value(&big))), Err(StorageError::CapacityExceeded));
+        block_on(storage.store(&key("big"), &value(&big[1..]))).unwrap();
+    }
+}
@@ -0,0 +1,845 @@
+//! # OTA Partitions
+//!
+//! Flash side of over-the-air firmware updates, compatible with the ESP-IDF
+//! second-stage bootloader that espflash and probe-rs install. The bootloader
+//! reads the partition table at [`PARTITION_TABLE_OFFSET`], picks the app slot
+//! named by the newest valid entry in the `otadata` partition and boots it.
+//! [`OtaPartitions`] reads the same structures so the application can:
+//!
+//! - stream a new image into the inactive slot with an [`OtaWriter`],
+//! - verify the image header, segments and checksum after download,
+//! - select the new slot for the next boot,
+//! - confirm or roll back the new image on its first boot.
+//!
+//! ```rust,ignore
+//! use iot_storage::OtaPartitions;
+//!
+//! let mut ota = OtaPartitions::new(FlashStorage::new())?;
+//! let mut writer = ota.begin_update()?;
+//! while let Some(chunk) = download.next().await {
+//!     writer.write(chunk)?;
+//! }
+//! let image = writer.finish()?;   // verified and selected for the next boot
+//! ```
+//!
+//! ## Partition Table
+//!
+//! At least two OTA app slots and an `otadata` partition are required; a
+//! `factory` app is optional. Slots must be sector aligned. The single-app
+//! table flashed by default has neither, and [`OtaPartitions::new`] fails with
+//! a configuration error on it.
+//!
+//! ## Trial Boot and Rollback
+//!
+//! A finished update is selected in state [`OtaState::New`]. On the next boot
+//! [`OtaPartitions::check_trial_boot`] moves it to [`OtaState::PendingVerify`]
+//! and the application has one boot to call [`OtaPartitions::mark_valid`]. If
+//! the image crashes, hangs into a watchdog reset or never confirms, the
+//! following boot still finds `PendingVerify` and switches back to the
+//! previous image. The bootloader must be built without its own app rollback
+//! option, which would otherwise claim the same states.
+
+use embedded_storage::nor_flash::NorFlash;
+use heapless::Vec;
+
+use crate::traits::{StorageError, StorageResult};
+
+/// Flash offset of the partition table
+pub const PARTITION_TABLE_OFFSET: u32 = 0x8000;
+
+/// Most OTA app slots the ESP-IDF partition format allows (`ota_0`..`ota_15`)
+pub const MAX_OTA_SLOTS: usize = 16;
+
+/// First byte of every ESP application image
+pub const IMAGE_MAGIC: u8 = 0xE9;
+
+/// Chip ID of the ESP32-C3 in the image header
+pub const ESP32C3_CHIP_ID: u16 = 5;
+
+/// Bytes [`OtaWriter`] buffers before writing to flash
+pub const OTA_WRITE_CHUNK: usize = 256;
+
+/// Size of the partition table region
+const PARTITION_TABLE_SIZE: usize = 0xC00;
+/// Size of one partition table entry
+const PARTITION_ENTRY_SIZE: usize = 32;
+/// Magic of a partition table entry
+const PARTITION_MAGIC: [u8; 2] = [0xAA, 0x50];
+/// Position of the label in a partition table entry
+const PARTITION_LABEL_OFFSET: usize = 12;
+/// Length of the NUL-padded label
+const PARTITION_LABEL_LEN: usize = 16;
+
+const PARTITION_TYPE_APP: u8 = 0x00;
+const PARTITION_TYPE_DATA: u8 = 0x01;
+const APP_SUBTYPE_FACTORY: u8 = 0x00;
+const APP_SUBTYPE_OTA_0: u8 = 0x10;
+const DATA_SUBTYPE_OTA: u8 = 0x00;
+
+/// Each of the two `otadata` copies occupies one 4 KiB sector
+const OTADATA_SECTOR_SIZE: u32 = 0x1000;
+/// Size of an `esp_ota_select_entry_t`
+const OTA_SELECT_ENTRY_SIZE: usize = 32;
+
+/// Image header plus extended header
+const IMAGE_HEADER_SIZE: usize = 24;
+/// Load address and length in front of every segment
+const SEGMENT_HEADER_SIZE: usize = 8;
+/// Initial value of the image XOR checksum
+const IMAGE_CHECKSUM_SEED: u8 = 0xEF;
+/// Segment limit enforced by the bootloader
+const MAX_IMAGE_SEGMENTS: u8 = 16;
+/// Length of the SHA-256 digest appended by espflash/esptool
+const IMAGE_DIGEST_SIZE: usize = 32;
+
+/// Location of a partition in flash
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Partition {
+    /// Offset of the partition in flash
+    pub offset: u32,
+    /// Size of the partition in bytes
+    pub size: u32,
+}
+
+/// Boot state of an OTA selection, as stored in `otadata`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtaState {
+    /// Selected but never booted
+    New,
+    /// Booted once, waiting for the application to confirm it
+    PendingVerify,
+    /// Confirmed by the application
+    Valid,
+    /// Marked unusable
+    Invalid,
+    /// Failed its trial boot
+    Aborted,
+    /// No state recorded (images flashed directly, or erased `otadata`)
+    Undefined,
+}
+
+impl OtaState {
+    fn from_raw(raw: u32) -> Self {
+        match raw {
+            0 => OtaState::New,
+            1 => OtaState::PendingVerify,
+            2 => OtaState::Valid,
+            3 => OtaState::Invalid,
+            4 => OtaState::Aborted,
+            _ => OtaState::Undefined,
+        }
+    }
+
+    fn to_raw(self) -> u32 {
+        match self {
+            OtaState::New => 0,
+            OtaState::PendingVerify => 1,
+            OtaState::Valid => 2,
+            OtaState::Invalid => 3,
+            OtaState::Aborted => 4,
+            OtaState::Undefined => u32::MAX,
+        }
+    }
+
+    /// Short lowercase name for logs and JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OtaState::New => "new",
+            OtaState::PendingVerify => "pending_verify",
+            OtaState::Valid => "valid",
+            OtaState::Invalid => "invalid",
+            OtaState::Aborted => "aborted",
+            OtaState::Undefined => "undefined",
+        }
+    }
+}
+
+/// What [`OtaPartitions::check_trial_boot`] found at startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrialBoot {
+    /// Not running a fresh update, nothing to confirm
+    Normal,
+    /// First boot of a new image; call [`OtaPartitions::mark_valid`] once healthy
+    Started,
+    /// The new image never confirmed; the previous one is selected, reset to boot it
+    RolledBack,
+    /// The new image never confirmed, but there is no previous image to return to
+    NoFallback,
+}
+
+/// Facts about a verified application image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageInfo {
+    /// Slot holding the image
+    pub slot: usize,
+    /// Image length including checksum and appended digest
+    pub size: u32,
+    /// Number of segments
+    pub segments: u8,
+    /// Whether a SHA-256 digest follows the checksum (checked by the bootloader)
+    pub hash_appended: bool,
+}
+
+/// One copy of the boot selection in `otadata`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct OtaSelectEntry {
+    sequence: u32,
+    state: OtaState,
+}
+
+impl OtaSelectEntry {
+    fn encode(&self) -> [u8; OTA_SELECT_ENTRY_SIZE] {
+        let mut bytes = [0xFF; OTA_SELECT_ENTRY_SIZE];
+        bytes[0..4].copy_from_slice(&self.sequence.to_le_bytes());
+        // bytes 4..24 are the unused sequence label
+        bytes[24..28].copy_from_slice(&self.state.to_raw().to_le_bytes());
+        bytes[28..32].copy_from_slice(&sequence_crc(self.sequence).to_le_bytes());
+        bytes
+    }
+
+    fn decode(bytes: &[u8; OTA_SELECT_ENTRY_SIZE]) -> Option<Self> {
+        let word = |offset: usize| u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]);
+        let sequence = word(0);
+        if sequence == 0 || sequence == u32::MAX || word(28) != sequence_crc(sequence) {
+            return None;
+        }
+        Some(Self { sequence, state: OtaState::from_raw(word(24)) })
+    }
+
+    /// Slot this entry boots, out of `slot_count`
+    fn slot(&self, slot_count: usize) -> usize {
+        (self.sequence as usize - 1) % slot_count
+    }
+}
+
+/// CRC the bootloader expects over an entry's sequence number
+///
+/// The ROM's `crc32_le(0xFFFFFFFF, ..)`: reflected CRC-32 starting from 0.
+fn sequence_crc(sequence: u32) -> u32 {
+    let mut crc = 0u32;
+    for byte in sequence.to_le_bytes() {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// Calls `visit` with every entry of the partition table and its location
+fn read_partition_table<F: NorFlash>(
+    flash: &mut F,
+    mut visit: impl FnMut(&[u8; PARTITION_ENTRY_SIZE], Partition),
+) -> StorageResult<()> {
+    for index in 0..PARTITION_TABLE_SIZE / PARTITION_ENTRY_SIZE {
+        let mut entry = [0u8; PARTITION_ENTRY_SIZE];
+        flash.read(PARTITION_TABLE_OFFSET + (index * PARTITION_ENTRY_SIZE) as u32, &mut entry)
+            .map_err(|_| StorageError::HardwareError)?;
+        // The table ends at the first non-entry (erased flash or the MD5 record)
+        if entry[0..2] != PARTITION_MAGIC {
+            break;
+        }
+
+        let partition = Partition {
+            offset: u32::from_le_bytes([entry[4], entry[5], entry[6], entry[7]]),
+            size: u32::from_le_bytes([entry[8], entry[9], entry[10], entry[11]]),
+        };
+        visit(&entry, partition);
+    }
+    Ok(())
+}
+
+/// Looks up a partition by its label in the partition table of `flash`
+///
+/// # Returns
+///
+/// * `Ok(Some(Partition))` - Location of the first partition named `label`
+/// * `Ok(None)` - No such partition, e.g. the default single-app table
+/// * `Err(StorageError)` - Flash read failed
+pub fn find_partition<F: NorFlash>(flash: &mut F, label: &str) -> StorageResult<Option<Partition>> {
+    let mut found = None;
+    read_partition_table(flash, |entry, partition| {
+        // Labels are NUL-padded to 16 bytes
+        let name = &entry[PARTITION_LABEL_OFFSET..PARTITION_LABEL_OFFSET + PARTITION_LABEL_LEN];
+        let len = name.iter().position(|&b| b == 0).unwrap_or(PARTITION_LABEL_LEN);
+        if found.is_none() && &name[..len] == label.as_bytes() {
+            found = Some(partition);
+        }
+    })?;
+    Ok(found)
+}
+
+/// OTA slots and boot selection of a flash device
+pub struct OtaPartitions<F: NorFlash> {
+    /// Flash holding partition table, `otadata` and slots
+    flash: F,
+    /// The `otadata` partition (two sectors)
+    otadata: Partition,
+    /// The `factory` app, if the table has one
+    factory: Option<Partition>,
+    /// OTA app slots, in `ota_N` order
+    slots: Vec<Partition, MAX_OTA_SLOTS>,
+}
+
+impl<F: NorFlash> OtaPartitions<F> {
+    /// Read the partition table from `flash`
+    ///
+    /// Fails with a configuration error unless the table has an `otadata`
+    /// partition and at least two OTA slots.
+    pub fn new(mut flash: F) -> StorageResult<Self> {
+        let mut otadata = None;
+        let mut factory = None;
+        let mut slots: Vec<(u8, Partition), MAX_OTA_SLOTS> = Vec::new();
+
+        read_partition_table(&mut flash, |entry, partition| {
+            match (entry[2], entry[3]) {
+                (PARTITION_TYPE_DATA, DATA_SUBTYPE_OTA) => otadata = Some(partition),
+                (PARTITION_TYPE_APP, APP_SUBTYPE_FACTORY) => factory = Some(partition),
+                (PARTITION_TYPE_APP, subtype) if (APP_SUBTYPE_OTA_0..APP_SUBTYPE_OTA_0 + MAX_OTA_SLOTS as u8).contains(&subtype) => {
+                    let _ = slots.push((subtype, partition));
+                }
+                _ => {}
+            }
+        })?;
+
+        let otadata = otadata
+            .filter(|otadata| otadata.size >= 2 * OTADATA_SECTOR_SIZE)
+            .ok_or_else(|| StorageError::ConfigurationError("No otadata partition".into()))?;
+        if slots.len() < 2 {
+            return Err(StorageError::ConfigurationError("Partition table needs ota_0 and ota_1".into()));
+        }
+        // The bootloader numbers slots by subtype, not by table order
+        slots.sort_unstable_by_key(|(subtype, _)| *subtype);
+
+        Ok(Self {
+            flash,
+            otadata,
+            factory,
+            slots: slots.iter().map(|(_, partition)| *partition).collect(),
+        })
+    }
+
+    /// Number of OTA app slots
+    pub fn slot_count(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Location of OTA slot `slot`
+    pub fn slot(&self, slot: usize) -> Option<Partition> {
+        self.slots.get(slot).copied()
+    }
+
+    /// Slot the bootloader selects, `None` when it boots the factory app
+    pub fn boot_slot(&mut self) -> StorageResult<Option<usize>> {
+        Ok(match self.active_entry()? {
+            Some((_, entry)) => Some(entry.slot(self.slots.len())),
+            None if self.factory.is_some() => None,
+            None => Some(0),
+        })
+    }
+
+    /// State of the current boot selection
+    pub fn boot_state(&mut self) -> StorageResult<OtaState> {
+        Ok(self.active_entry()?.map_or(OtaState::Undefined, |(_, entry)| entry.state))
+    }
+
+    /// Slot the next update is written to: the one after the running slot
+    pub fn next_update_slot(&mut self) -> StorageResult<usize> {
+        Ok(match self.boot_slot()? {
+            Some(slot) => (slot + 1) % self.slots.len(),
+            None => 0,
+        })
+    }
+
+    /// Start writing an update into [`next_update_slot`](Self::next_update_slot)
+    pub fn begin_update(&mut self) -> StorageResult<OtaWriter<'_, F>> {
+        let slot = self.next_update_slot()?;
+        Ok(OtaWriter {
+            partition: self.slots[slot],
+            ota: self,
+            slot,
+            written: 0,
+            flushed: 0,
+            buffer: Vec::new(),
+        })
+    }
+
+    /// Select `slot` for the next boot with `state`
+    ///
+    /// The new entry goes to the `otadata` sector not holding the current
+    /// selection, so a power loss mid-write leaves the old selection intact.
+    pub fn set_boot_slot(&mut self, slot: usize, state: OtaState) -> StorageResult<()> {
+        if slot >= self.slots.len() {
+            return Err(StorageError::InvalidValue);
+        }
+
+        let entries = self.read_entries()?;
+        let newest = entries.iter().flatten().map(|entry| entry.sequence).max().unwrap_or(0);
+        let mut sequence = newest + 1;
+        while (sequence as usize - 1) % self.slots.len() != slot {
+            sequence += 1;
+        }
+
+        let sector = match self.active_entry()? {
+            Some((sector, _)) => 1 - sector,
+            None => 0,
+        };
+        self.write_entry(sector, &OtaSelectEntry { sequence, state })
+    }
+
+    /// Confirm the running image, ending its trial boot
+    pub fn mark_valid(&mut self) -> StorageResult<()> {
+        match self.active_entry()? {
+            Some((_, entry)) if entry.state == OtaState::Valid => Ok(()),
+            Some((sector, entry)) => self.write_entry(sector, &OtaSelectEntry { state: OtaState::Valid, ..entry }),
+            // Factory app or directly flashed slot, nothing on trial
+            None => Ok(()),
+        }
+    }
+
+    /// Advance the trial boot state machine; call once early at startup
+    ///
+    /// See the module docs. After [`TrialBoot::RolledBack`] the caller should
+    /// reset so the bootloader starts the previous image.
+    pub fn check_trial_boot(&mut self) -> StorageResult<TrialBoot> {
+        let Some((sector, entry)) = self.active_entry()? else {
+            return Ok(TrialBoot::Normal);
+        };
+
+        match entry.state {
+            OtaState::New => {
+                self.write_entry(sector, &OtaSelectEntry { state: OtaState::PendingVerify, ..entry })?;
+                Ok(TrialBoot::Started)
+            }
+            OtaState::PendingVerify => {
+                // The other sector still holds the selection that was replaced;
+                // without one the bootloader ran the factory app or ota_0
+                let failed_slot = entry.slot(self.slots.len());
+                match self.read_entry(1 - sector)? {
+                    Some(previous) if previous.sequence < entry.sequence => {
+                        self.set_boot_slot(previous.slot(self.slots.len()), OtaState::Valid)?;
+                        Ok(TrialBoot::RolledBack)
+                    }
+                    _ if self.factory.is_some() => {
+                        self.erase_otadata()?;
+                        Ok(TrialBoot::RolledBack)
+                    }
+                    _ if failed_slot != 0 => {
+                        self.set_boot_slot(0, OtaState::Valid)?;
+                        Ok(TrialBoot::RolledBack)
+                    }
+                    _ => {
+                        self.write_entry(sector, &OtaSelectEntry { state: OtaState::Valid, ..entry })?;
+                        Ok(TrialBoot::NoFallback)
+                    }
+                }
+            }
+            _ => Ok(TrialBoot::Normal),
+        }
+    }
+
+    /// Check the image of `len` bytes in `slot`: magic, chip, segment layout
+    /// and the XOR checksum over all segment data
+    pub fn verify_image(&mut self, slot: usize, len: u32) -> StorageResult<ImageInfo> {
+        let partition = self.slot(slot).ok_or(StorageError::InvalidValue)?;
+        if len > partition.size || (len as usize) < IMAGE_HEADER_SIZE {
+            return Err(StorageError::CorruptedData);
+        }
+
+        let mut header = [0u8; IMAGE_HEADER_SIZE];
+        self.read(partition.offset, &mut header)?;
+        let chip_id = u16::from_le_bytes([header[12], header[13]]);
+        let segments = header[1];
+        if header[0] != IMAGE_MAGIC || chip_id != ESP32C3_CHIP_ID || segments == 0 || segments > MAX_IMAGE_SEGMENTS {
+            return Err(StorageError::CorruptedData);
+        }
+
+        let mut position = IMAGE_HEADER_SIZE as u32;
+        let mut checksum = IMAGE_CHECKSUM_SEED;
+        let mut chunk = [0u8; OTA_WRITE_CHUNK];
+        for _ in 0..segments {
+            let mut segment = [0u8; SEGMENT_HEADER_SIZE];
+            if position + SEGMENT_HEADER_SIZE as u32 > len {
+                return Err(StorageError::CorruptedData);
+            }
+            self.read(partition.offset + position, &mut segment)?;
+            position += SEGMENT_HEADER_SIZE as u32;
+
+            let mut remaining = u32::from_le_bytes([segment[4], segment[5], segment[6], segment[7]]);
+            if remaining > len - position {
+                return Err(StorageError::CorruptedData);
+            }
+            while remaining > 0 {
+                let part = &mut chunk[..(remaining as usize).min(OTA_WRITE_CHUNK)];
+                self.read(partition.offset + position, part)?;
+                checksum = part.iter().fold(checksum, |sum, byte| sum ^ byte);
+                position += part.len() as u32;
+                remaining -= part.len() as u32;
+            }
+        }
+
+        // The checksum byte ends a 16-byte aligned block after the segments
+        let checksum_at = position + 15 - position % 16;
+        let hash_appended = header[23] == 1;
+        let size = checksum_at + 1 + if hash_appended { IMAGE_DIGEST_SIZE as u32 } else { 0 };
+        if size > len {
+            return Err(StorageError::CorruptedData);
+        }
+        let mut stored = [0u8; 1];
+        self.read(partition.offset + checksum_at, &mut stored)?;
+        if stored[0] != checksum {
+            return Err(StorageError::CorruptedData);
+        }
+
+        Ok(ImageInfo { slot, size, segments, hash_appended })
+    }
+
+    /// Give back the flash
+    pub fn into_inner(self) -> F {
+        self.flash
+    }
+
+    /// Newest valid selection and the `otadata` sector holding it
+    fn active_entry(&mut self) -> StorageResult<Option<(usize, OtaSelectEntry)>> {
+        let entries = self.read_entries()?;
+        Ok(entries.iter()
+            .enumerate()
+            .filter_map(|(sector, entry)| entry.map(|entry| (sector, entry)))
+            // A failed trial is never booted again
+            .filter(|(_, entry)| !matches!(entry.state, OtaState::Invalid | OtaState::Aborted))
+            .max_by_key(|(_, entry)| entry.sequence))
+    }
+
+    fn read_entries(&mut self) -> StorageResult<[Option<OtaSelectEntry>; 2]> {
+        Ok([self.read_entry(0)?, self.read_entry(1)?])
+    }
+
+    fn read_entry(&mut self, sector: usize) -> StorageResult<Option<OtaSelectEntry>> {
+        let mut bytes = [0u8; OTA_SELECT_ENTRY_SIZE];
+        self.read(self.otadata.offset + sector as u32 * OTADATA_SECTOR_SIZE, &mut bytes)?;
+        Ok(OtaSelectEntry::decode(&bytes))
+    }
+
+    fn write_entry(&mut self, sector: usize, entry: &OtaSelectEntry) -> StorageResult<()> {
+        let address = self.otadata.offset + sector as u32 * OTADATA_SECTOR_SIZE;
+        self.flash.erase(address, address + OTADATA_SECTOR_SIZE)
+            .map_err(|_| StorageError::HardwareError)?;
+        self.flash.write(address, &entry.encode())
+            .map_err(|_| StorageError::HardwareError)
+    }
+
+    fn erase_otadata(&mut self) -> StorageResult<()> {
+        self.flash.erase(self.otadata.offset, self.otadata.offset + 2 * OTADATA_SECTOR_SIZE)
+            .map_err(|_| StorageError::HardwareError)
+    }
+
+    fn read(&mut self, address: u32, bytes: &mut [u8]) -> StorageResult<()> {
+        self.flash.read(address, bytes).map_err(|_| StorageError::HardwareError)
+    }
+}
+
+/// Streams an image into an OTA slot
+///
+/// Bytes must arrive in order. Each flash sector is erased when the first
+/// write reaches it, so an interrupted download resumes by requesting the
+/// image from [`written`](Self::written) onwards and writing on.
+pub struct OtaWriter<'a, F: NorFlash> {
+    /// Partitions the writer belongs to
+    ota: &'a mut OtaPartitions<F>,
+    /// Target slot
+    slot: usize,
+    /// Target slot location
+    partition: Partition,
+    /// Bytes accepted so far
+    written: u32,
+    /// Bytes already in flash
+    flushed: u32,
+    /// Bytes waiting for a full write chunk
+    buffer: Vec<u8, OTA_WRITE_CHUNK>,
+}
+
+impl<F: NorFlash> OtaWriter<'_, F> {
+    /// Slot being written
+    pub fn slot(&self) -> usize {
+        self.slot
+    }
+
+    /// Size of the slot being written
+    pub fn capacity(&self) -> u32 {
+        self.partition.size
+    }
+
+    /// Bytes accepted so far, the offset to resume a download from
+    pub fn written(&self) -> u32 {
+        self.written
+    }
+
+    /// Append image bytes
+    ///
+    /// Fails with `CapacityExceeded` once the image outgrows the slot.
+    pub fn write(&mut self, mut data: &[u8]) -> StorageResult<()> {
+        if data.len() as u32 > self.partition.size - self.written {
+            return Err(StorageError::CapacityExceeded);
+        }
+        while !data.is_empty() {
+            let take = data.len().min(OTA_WRITE_CHUNK - self.buffer.len());
+            let _ = self.buffer.extend_from_slice(&data[..take]);
+            self.written += take as u32;
+            data = &data[take..];
+            if self.buffer.is_full() {
+                self.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the tail, verify the image and select it for the next boot
+    ///
+    /// The image boots in state [`OtaState::New`] and has to be confirmed,
+    /// see the module docs.
+    pub fn finish(mut self) -> StorageResult<ImageInfo> {
+        // Pad the tail to the flash write size with erased bytes
+        while !self.buffer.len().is_multiple_of(F::WRITE_SIZE) {
+            let _ = self.buffer.push(0xFF);
+        }
+        self.flush()?;
+
+        let info = self.ota.verify_image(self.slot, self.written)?;
+        self.ota.set_boot_slot(self.slot, OtaState::New)?;
+        Ok(info)
+    }
+
+    fn flush(&mut self) -> StorageResult<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let start = self.partition.offset + self.flushed;
+        let end = start + self.buffer.len() as u32;
+        let sector = F::ERASE_SIZE as u32;
+        // Erase every sector that begins inside this write
+        let mut erase_at = start.next_multiple_of(sector);
+        if self.flushed == 0 {
+            erase_at = start;
+        }
+        while erase_at < end {
+            self.ota.flash.erase(erase_at, erase_at + sector)
+                .map_err(|_| StorageError::HardwareError)?;
+            erase_at += sector;
+        }
+
+        self.ota.flash.write(start, &self.buffer)
+            .map_err(|_| StorageError::HardwareError)?;
+        self.flushed += self.buffer.len() as u32;
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec as AllocVec;
+    use embedded_storage::nor_flash::{ErrorType, NorFlashErrorKind, ReadNorFlash};
+
+    const OTADATA: u32 = 0x9000;
+    const SLOT_SIZE: u32 = 0x2000;
+    const OTA_0: u32 = 0xB000;
+    const OTA_1: u32 = OTA_0 + SLOT_SIZE;
+
+    /// 64 KiB of RAM with 4 KiB sectors, behaving like NOR flash
+    struct RamFlash {
+        bytes: AllocVec<u8>,
+    }
+
+    impl ErrorType for RamFlash {
+        type Error = NorFlashErrorKind;
+    }
+
+    impl ReadNorFlash for RamFlash {
+        const READ_SIZE: usize = 1;
+
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            bytes.copy_from_slice(&self.bytes[offset..offset + bytes.len()]);
+            Ok(())
+        }
+
+        fn capacity(&self) -> usize {
+            self.bytes.len()
+        }
+    }
+
+    impl NorFlash for RamFlash {
+        const WRITE_SIZE: usize = 4;
+        const ERASE_SIZE: usize = 0x1000;
+
+        fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+            self.bytes[from as usize..to as usize].fill(0xFF);
+            Ok(())
+        }
+
+        fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+            // NOR writes can only clear bits
+            for (target, byte) in self.bytes[offset as usize..].iter_mut().zip(bytes) {
+                *target &= byte;
+            }
+            Ok(())
+        }
+    }
+
+    fn table_entry(kind: u8, subtype: u8, offset: u32, size: u32) -> [u8; PARTITION_ENTRY_SIZE] {
+        let mut entry = [0u8; PARTITION_ENTRY_SIZE];
+        entry[0..2].copy_from_slice(&PARTITION_MAGIC);
+        entry[2] = kind;
+        entry[3] = subtype;
+        entry[4..8].copy_from_slice(&offset.to_le_bytes());
+        entry[8..12].copy_from_slice(&size.to_le_bytes());
+        entry
+    }
+
+    /// Flash with otadata, ota_0 and ota_1 (listed out of order)
+    fn ota_flash() -> RamFlash {
+        let mut flash = RamFlash { bytes: vec![0xFF; 0x10000] };
+        let entries = [
+            table_entry(PARTITION_TYPE_DATA, DATA_SUBTYPE_OTA, OTADATA, 2 * OTADATA_SECTOR_SIZE),
+            table_entry(PARTITION_TYPE_APP, APP_SUBTYPE_OTA_0 + 1, OTA_1, SLOT_SIZE),
+            table_entry(PARTITION_TYPE_APP, APP_SUBTYPE_OTA_0, OTA_0, SLOT_SIZE),
+        ];
+        for (index, entry) in entries.iter().enumerate() {
+            let at = PARTITION_TABLE_OFFSET as usize + index * PARTITION_ENTRY_SIZE;
+            flash.bytes[at..at + PARTITION_ENTRY_SIZE].copy_from_slice(entry);
+        }
+        flash
+    }
+
+    /// Minimal ESP32-C3 image with two segments and a valid checksum
+    fn image() -> AllocVec<u8> {
+        let mut image = vec![0u8; IMAGE_HEADER_SIZE];
+        image[0] = IMAGE_MAGIC;
+        image[1] = 2;
+        image[12..14].copy_from_slice(&ESP32C3_CHIP_ID.to_le_bytes());
+
+        let mut checksum = IMAGE_CHECKSUM_SEED;
+        for (address, len) in [(0x3C00_0000u32, 300u32), (0x4200_0000, 41)] {
+            image.extend_from_slice(&address.to_le_bytes());
+            image.extend_from_slice(&len.to_le_bytes());
+            for n in 0..len {
+                let byte = (n * 7) as u8;
+                checksum ^= byte;
+                image.push(byte);
+            }
+        }
+        while image.len() % 16 != 15 {
+            image.push(0);
+        }
+        image.push(checksum);
+        image
+    }
+
+    #[test]
+    fn test_sequence_crc_matches_bootloader() {
+        // Values written by ESP-IDF's otatool for sequences 1 and 2
+        assert_eq!(sequence_crc(1), 0x4743_989A);
+        assert_eq!(sequence_crc(2), 0x55F6_3774);
+    }
+
+    #[test]
+    fn test_update_selects_other_slot() {
+        let mut ota = OtaPartitions::new(ota_flash()).unwrap();
+        assert_eq!(ota.slot_count(), 2);
+        assert_eq!(ota.slot(0), Some(Partition { offset: OTA_0, size: SLOT_SIZE }));
+        // Blank otadata and no factory app: the bootloader runs ota_0
+        assert_eq!(ota.boot_slot().unwrap(), Some(0));
+        assert_eq!(ota.boot_state().unwrap(), OtaState::Undefined);
+
+        let image = image();
+        let mut writer = ota.begin_update().unwrap();
+        assert_eq!(writer.slot(), 1);
+        for chunk in image.chunks(100) {
+            writer.write(chunk).unwrap();
+        }
+        let info = writer.finish().unwrap();
+        assert_eq!(info.size as usize, image.len());
+        assert_eq!(info.segments, 2);
+
+        assert_eq!(ota.boot_slot().unwrap(), Some(1));
+        assert_eq!(ota.boot_state().unwrap(), OtaState::New);
+        let flash = ota.into_inner();
+        assert_eq!(&flash.bytes[OTA_1 as usize..][..image.len()], &image[..]);
+    }
+
+    #[test]
+    fn test_corrupt_image_is_rejected() {
+        let mut ota = OtaPartitions::new(ota_flash()).unwrap();
+        let mut image = image();
+        image[100] ^= 0x01;
+
+        let mut writer = ota.begin_update().unwrap();
+        writer.write(&image).unwrap();
+        assert_eq!(writer.finish(), Err(StorageError::CorruptedData));
+        // The running slot stays selected
+        assert_eq!(ota.boot_slot().unwrap(), Some(0));
+
+        let mut writer = ota.begin_update().unwrap();
+        assert_eq!(writer.write(&vec![0u8; SLOT_SIZE as usize + 1]), Err(StorageError::CapacityExceeded));
+    }
+
+    #[test]
+    fn test_unconfirmed_image_rolls_back() {
+        let mut ota = OtaPartitions::new(ota_flash()).unwrap();
+        let mut writer = ota.begin_update().unwrap();
+        writer.write(&image()).unwrap();
+        writer.finish().unwrap();
+
+        // First boot of the update: trial starts
+        assert_eq!(ota.check_trial_boot().unwrap(), TrialBoot::Started);
+        assert_eq!(ota.boot_state().unwrap(), OtaState::PendingVerify);
+
+        // Reset before confirming: back to ota_0, which ran from blank otadata
+        assert_eq!(ota.check_trial_boot().unwrap(), TrialBoot::RolledBack);
+        assert_eq!(ota.boot_slot().unwrap(), Some(0));
+        assert_eq!(ota.check_trial_boot().unwrap(), TrialBoot::Normal);
+
+        // A confirmed update stays
+        let mut writer = ota.begin_update().unwrap();
+        writer.write(&image()).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(ota.check_trial_boot().unwrap(), TrialBoot::Started);
+        ota.mark_valid().unwrap();
+        assert_eq!(ota.check_trial_boot().unwrap(), TrialBoot::Normal);
+        assert_eq!(ota.boot_slot().unwrap(), Some(1));
+
+        // The next failed update returns to the confirmed ota_1
+        let mut writer = ota.begin_update().unwrap();
+        assert_eq!(writer.slot(), 0);
+        writer.write(&image()).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(ota.check_trial_boot().unwrap(), TrialBoot::Started);
+        assert_eq!(ota.check_trial_boot().unwrap(), TrialBoot::RolledBack);
+        assert_eq!(ota.boot_slot().unwrap(), Some(1));
+        assert_eq!(ota.boot_state().unwrap(), OtaState::Valid);
+    }
+
+    #[test]
+    fn test_find_partition_by_label() {
+        let mut flash = ota_flash();
+        let mut entry = table_entry(PARTITION_TYPE_DATA, 0x40, 0xF000, 0x1000);
+        entry[PARTITION_LABEL_OFFSET..PARTITION_LABEL_OFFSET + 7].copy_from_slice(b"appdata");
+        let at = PARTITION_TABLE_OFFSET as usize + 3 * PARTITION_ENTRY_SIZE;
+        flash.bytes[at..at + PARTITION_ENTRY_SIZE].copy_from_slice(&entry);
+
+        assert_eq!(find_partition(&mut flash, "appdata").unwrap(), Some(Partition { offset: 0xF000, size: 0x1000 }));
+        assert_eq!(find_partition(&mut flash, "appdat").unwrap(), None, "Labels must match exactly");
+        assert_eq!(find_partition(&mut flash, "nvs").unwrap(), None);
+    }
+
+    #[test]
+    fn test_single_app_table_is_rejected() {
+        let mut flash = RamFlash { bytes: vec![0xFF; 0x10000] };
+        let entry = table_entry(PARTITION_TYPE_APP, APP_SUBTYPE_FACTORY, 0x10000, 0x100000);
+        let at = PARTITION_TABLE_OFFSET as usize;
+        flash.bytes[at..at + PARTITION_ENTRY_SIZE].copy_from_slice(&entry);
+        assert!(matches!(OtaPartitions::new(flash), Err(StorageError::ConfigurationError(_))));
+    }
+}
@@ -245,6 +245,10 @@ pub struct StorageStats {
     pub bytes_written: u64,
     /// Number of erase cycles performed
     pub erase_cycles: u64,
+    /// Lifetime erase count per sector, in address order
+    ///
+    /// Filled by backends that persist erase counters on flash; empty otherwise.
+    pub sector_erase_cycles: Vec<u32, { crate::MAX_FLASH_REGIONS }>,
     /// Last operation timestamp (implementation-defined)
     pub last_operation_time: u64,
 }
@@ -262,10 +266,20 @@ impl StorageStats {
             bytes_read: 0,
             bytes_written: 0,
             erase_cycles: 0,
+            sector_erase_cycles: Vec::new(),
             last_operation_time: 0,
         }
     }
 
+    /// Clear the operation counters, keeping erase counts (lifetime wear)
+    pub fn reset_operations(&mut self) {
+        *self = Self {
+            erase_cycles: self.erase_cycles,
+            sector_erase_cycles: core::mem::take(&mut self.sector_erase_cycles),
+            ..Self::new()
+        };
+    }
+
     /// Get total operations count
     pub fn total_operations(&self) -> u64 {
         self.total_reads + self.total_writes + self.total_deletes
@@ -304,6 +318,11 @@ impl StorageStats {
         }
     }
 
+    /// Erase count of the most-worn sector, `None` without per-sector data
+    pub fn max_sector_erase_cycles(&self) -> Option<u32> {
+        self.sector_erase_cycles.iter().copied().max()
+    }
+
     /// Reset all statistics
     pub fn reset(&mut self) {
         *self = Self::new();
@@ -317,7 +336,11 @@ impl Default for StorageStats {
 }
 
 /// Main storage backend trait
-#[async_trait::async_trait]
+///
+/// Futures are not required to be `Send`: backends may wrap async flash
+/// drivers whose futures are not, and the embassy executor never moves them
+/// between threads.
+#[async_trait::async_trait(?Send)]
 pub trait StorageBackend: Send + Sync {
     /// Store a key-value pair
     async fn store(&mut self, key: &StorageKey, value: &StorageValue) -> StorageResult<()>;
@@ -337,6 +360,12 @@ pub trait StorageBackend: Send + Sync {
     /// Perform maintenance operations (garbage collection, defragmentation)
     async fn maintenance(&mut self) -> StorageResult<()>;
 
+    /// Erase all stored data, leaving the backend empty but usable
+    ///
+    /// Operation statistics are reset; erase counts are kept. Returns the
+    /// number of bytes reclaimed.
+    async fn format(&mut self) -> StorageResult<usize>;
+
     /// Get storage capacity information
     fn get_capacity(&self) -> StorageResult<StorageCapacity>;
 
@@ -345,7 +374,10 @@ pub trait StorageBackend: Send + Sync {
 }
 
 /// Configuration-specific storage trait
-#[async_trait::async_trait]
+///
+/// Like [`StorageBackend`], futures are not required to be `Send`, so
+/// implementations can await the backend holding the configuration.
+#[async_trait::async_trait(?Send)]
 pub trait ConfigStorage: Send + Sync {
     /// Store configuration by name
     async fn store_config<T>(&mut self, name: &str, config: &T) -> StorageResult<()>
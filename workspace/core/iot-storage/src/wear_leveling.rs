@@ -2,6 +2,12 @@
 //!
 //! Advanced wear leveling algorithms for flash storage to maximize
 //! device lifetime and ensure uniform wear across all sectors.
+//!
+//! Backends address data by *logical* block and let [`WearLevelingManager`]
+//! pick the *physical* sector. Every rewrite of a logical block goes through
+//! [`WearLevelingManager::map_write`], which moves it to the least-erased free
+//! sector, so a value rewritten over and over rotates across the whole area
+//! instead of wearing out one sector.
 
 use heapless::Vec;
 use crate::{
@@ -23,10 +29,28 @@ pub struct SectorWearInfo {
     pub last_access: u64,
 }
 
+/// Lowest and highest erase count among usable sectors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EraseSpread {
+    /// Erase count of the least-worn sector
+    pub min: u32,
+    /// Erase count of the most-worn sector
+    pub max: u32,
+}
+
+impl EraseSpread {
+    /// Difference between the most- and least-worn sector
+    pub fn spread(&self) -> u32 {
+        self.max - self.min
+    }
+}
+
 /// Wear leveling manager
 pub struct WearLevelingManager {
     /// Sector wear information
     sectors: Vec<SectorWearInfo, 64>,
+    /// Logical block to physical sector map, `None` while unwritten
+    block_map: Vec<Option<usize>, 64>,
     /// Maximum erase cycles per sector
     max_erase_cycles: u32,
     /// Wear leveling threshold
@@ -39,6 +63,7 @@ impl WearLevelingManager {
     /// Create new wear leveling manager
     pub fn new(sector_count: usize, max_erase_cycles: u32) -> StorageResult<Self> {
         let mut sectors = Vec::new();
+        let mut block_map = Vec::new();
         
         for i in 0..sector_count {
             let sector_info = SectorWearInfo {
@@ -49,10 +74,12 @@ impl WearLevelingManager {
                 last_access: 0,
             };
             sectors.push(sector_info).map_err(|_| StorageError::CapacityExceeded)?;
+            block_map.push(None).map_err(|_| StorageError::CapacityExceeded)?;
         }
         
         Ok(Self {
             sectors,
+            block_map,
             max_erase_cycles,
             wear_threshold: 80,
             bad_block_count: 0,
@@ -165,6 +192,86 @@ impl WearLevelingManager {
             .map(|(index, _)| index)
     }
 
+    /// Choose the sector for the next write of a logical block
+    /// 
+    /// The block's current sector is released first (its copy becomes stale
+    /// once the new one is written), then the least-erased sector that is
+    /// neither bad nor holding another block is erased and mapped to it.
+    /// Returns the physical sector to write.
+    pub fn map_write(&mut self, logical: usize) -> StorageResult<usize> {
+        if logical >= self.block_map.len() {
+            return Err(StorageError::InvalidValue);
+        }
+        
+        self.block_map[logical] = None;
+        let physical = self.get_least_worn_free_sector().ok_or(StorageError::CapacityExceeded)?;
+        
+        // Flash must be erased before it is rewritten
+        self.update_erase_count(physical)?;
+        self.block_map[logical] = Some(physical);
+        
+        Ok(physical)
+    }
+
+    /// Forget the sector of a deleted logical block
+    pub fn release(&mut self, logical: usize) {
+        if let Some(slot) = self.block_map.get_mut(logical) {
+            *slot = None;
+        }
+    }
+
+    /// Physical sector currently holding a logical block
+    pub fn physical_block(&self, logical: usize) -> Option<usize> {
+        self.block_map.get(logical).copied().flatten()
+    }
+
+    /// Number of logical blocks (equal to the number of sectors)
+    pub fn logical_block_count(&self) -> usize {
+        self.block_map.len()
+    }
+
+    /// Current `(logical, physical)` pairs for diagnostics
+    pub fn mapping(&self) -> Vec<(usize, usize), 64> {
+        self.block_map.iter()
+            .enumerate()
+            .filter_map(|(logical, physical)| physical.map(|p| (logical, p)))
+            .collect()
+    }
+
+    /// Erase count of every sector, in sector order
+    pub fn erase_counts(&self) -> Vec<u32, 64> {
+        self.sectors.iter().map(|sector| sector.erase_cycles).collect()
+    }
+
+    /// Set the erase counts, e.g. from counters persisted on flash
+    pub fn load_erase_counts(&mut self, counts: &[u32]) {
+        for (index, &cycles) in counts.iter().enumerate().take(self.sectors.len()) {
+            let wear_level = self.calculate_wear_level(cycles);
+            let sector = &mut self.sectors[index];
+            sector.erase_cycles = cycles;
+            sector.wear_level = wear_level;
+        }
+    }
+
+    /// Lowest and highest erase count among usable sectors
+    pub fn erase_spread(&self) -> Option<EraseSpread> {
+        let mut usable = self.sectors.iter().filter(|sector| !sector.is_bad);
+        let first = usable.next()?.erase_cycles;
+        let (min, max) = usable.fold((first, first), |(min, max), sector| {
+            (min.min(sector.erase_cycles), max.max(sector.erase_cycles))
+        });
+        Some(EraseSpread { min, max })
+    }
+
+    /// Least-worn sector that is not bad and not mapped to a logical block
+    fn get_least_worn_free_sector(&self) -> Option<usize> {
+        self.sectors.iter()
+            .enumerate()
+            .filter(|(index, sector)| !sector.is_bad && !self.block_map.contains(&Some(*index)))
+            .min_by_key(|(_, sector)| sector.erase_cycles)
+            .map(|(index, _)| index)
+    }
+
     /// Estimate remaining lifetime for sector
     pub fn get_sector_remaining_lifetime(&self, sector: usize) -> u8 {
         if sector >= self.sectors.len() {
@@ -246,4 +353,75 @@ impl WearLeveling for WearLevelingManager {
             .min()
             .unwrap_or(0)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{StorageBackend, StorageKey, StorageValue};
+    use crate::{MockStorage, MAX_WEAR_BLOCKS};
+    use iot_common::network::block_on;
+
+    #[test]
+    fn test_map_write_rotates_across_sectors() {
+        let mut wear = WearLevelingManager::new(4, 100_000).unwrap();
+
+        // One logical block rewritten 8 times visits every sector twice
+        for _ in 0..8 {
+            wear.map_write(0).unwrap();
+        }
+        assert_eq!(&wear.erase_counts()[..], &[2, 2, 2, 2]);
+        assert_eq!(wear.mapping().len(), 1);
+
+        // A second block never shares a sector with the first
+        let second = wear.map_write(1).unwrap();
+        assert_ne!(wear.physical_block(0), Some(second));
+
+        wear.release(1);
+        assert_eq!(wear.physical_block(1), None);
+        assert_eq!(wear.erase_spread(), Some(EraseSpread { min: 2, max: 3 }));
+    }
+
+    #[test]
+    fn test_mock_storage_distributes_writes() {
+        let mut storage = MockStorage::new();
+        let key = StorageKey::new("hot_key").unwrap();
+        let value = StorageValue::from_bytes(b"reading").unwrap();
+
+        // Rewriting a single key wears every block evenly
+        for _ in 0..(MAX_WEAR_BLOCKS * 3) {
+            block_on(storage.store(&key, &value)).unwrap();
+        }
+
+        let wear = storage.wear_leveling();
+        assert!(wear.erase_counts().iter().all(|&cycles| cycles == 3));
+        assert_eq!(wear.erase_spread().unwrap().spread(), 0);
+        assert_eq!(wear.mapping().len(), 1);
+        assert_eq!(storage.get_stats().unwrap().erase_cycles, (MAX_WEAR_BLOCKS * 3) as u64);
+
+        block_on(storage.delete(&key)).unwrap();
+        assert!(storage.wear_leveling().mapping().is_empty());
+    }
+
+    #[test]
+    fn test_mock_storage_format_keeps_wear() {
+        let mut storage = MockStorage::new();
+        let value = StorageValue::from_bytes(b"reading").unwrap();
+        for name in ["a", "b", "c"] {
+            block_on(storage.store(&StorageKey::new(name).unwrap(), &value)).unwrap();
+        }
+
+        assert_eq!(block_on(storage.format()).unwrap(), 3 * b"reading".len());
+        assert!(block_on(storage.list_keys(None)).unwrap().is_empty());
+        assert!(storage.wear_leveling().mapping().is_empty());
+
+        // Operation counters restart, lifetime erase counts do not
+        let stats = storage.get_stats().unwrap();
+        assert_eq!(stats.total_writes, 0);
+        assert_eq!(stats.erase_cycles, 3);
+
+        // The formatted store is immediately usable
+        let key = StorageKey::new("a").unwrap();
+        block_on(storage.store(&key, &value)).unwrap();
+        assert!(block_on(storage.exists(&key)).unwrap());
+    }
+}
@@ -55,7 +55,7 @@ async fn main(_spawner: Spawner) {
             rprintln!("✅ BME280 sensor initialized successfully!");
             
             // Display calibration data for debugging
-            if let Some(calib) = sensor.get_calibration_data() {
+            if let Some(calib) = sensor.calibration() {
                 rprintln!("📊 Calibration data loaded:");
                 rprintln!("   Temperature: T1={}, T2={}, T3={}", calib.dig_t1, calib.dig_t2, calib.dig_t3);
                 rprintln!("   Pressure: P1={}, P2={}, P3={}", calib.dig_p1, calib.dig_p2, calib.dig_p3);
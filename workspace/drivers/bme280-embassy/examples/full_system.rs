@@ -59,12 +59,13 @@ async fn sensor_task(mut i2c: I2c<'static, Async>) {
     }
 
     // Show calibration data for debugging
-    let cal = bme280.get_calibration_debug();
-    rprintln!("[BME280] Calibration data loaded:");
-    rprintln!("[BME280]   T1={}, T2={}, T3={}", cal.dig_t1, cal.dig_t2, cal.dig_t3);
-    rprintln!("[BME280]   P1={}, P2={}, P3={}", cal.dig_p1, cal.dig_p2, cal.dig_p3);
-    rprintln!("[BME280]   H1={}, H2={}, H3={}, H4={}, H5={}, H6={}", 
-              cal.dig_h1, cal.dig_h2, cal.dig_h3, cal.dig_h4, cal.dig_h5, cal.dig_h6);
+    if let Some(cal) = bme280.calibration() {
+        rprintln!("[BME280] Calibration data loaded:");
+        rprintln!("[BME280]   T1={}, T2={}, T3={}", cal.dig_t1, cal.dig_t2, cal.dig_t3);
+        rprintln!("[BME280]   P1={}, P2={}, P3={}", cal.dig_p1, cal.dig_p2, cal.dig_p3);
+        rprintln!("[BME280]   H1={}, H2={}, H3={}, H4={}, H5={}, H6={}", 
+                  cal.dig_h1, cal.dig_h2, cal.dig_h3, cal.dig_h4, cal.dig_h5, cal.dig_h6);
+    }
 
     rprintln!("[BME280] Sensor configured and ready!");
 
@@ -76,8 +77,8 @@ async fn sensor_task(mut i2c: I2c<'static, Async>) {
                 rprintln!("[BME280] Raw Data: T={}, P={}, H={}", raw_temp, raw_press, raw_hum);
                 
                 // Debug humidity calculation
-                let cal = bme280.get_calibration_debug();
-                if raw_hum > 0 {
+                let cal = bme280.calibration();
+                if let Some(cal) = cal.filter(|_| raw_hum > 0) {
                     rprintln!("[BME280] Debug H: raw_hum={}, H1={}, H2={}, H3={}, H4={}, H5={}, H6={}", 
                               raw_hum, cal.dig_h1, cal.dig_h2, cal.dig_h3, cal.dig_h4, cal.dig_h5, cal.dig_h6);
                     rprintln!("[BME280]   t_fine={}, expected H3 should be ~19-100", 3100);
@@ -0,0 +1,329 @@
+//! Multi-Zone Sensor Array
+//!
+//! Greenhouses and warehouses are monitored with several BME280s, one per
+//! zone, on different addresses or buses. [`SensorArray`] reads them all and
+//! condenses the result into an [`ArrayReading`]: the mean as a regular
+//! [`Measurements`], the min/max spread per field in [`ArrayStats`] and a
+//! [`ZoneStatus`] per sensor.
+//!
+//! A sensor that fails to read is excluded from the statistics and marked
+//! [`ZoneStatus::Failed`]; the read as a whole only fails when every sensor
+//! does. A sensor that reads but strays further from the median of its peers
+//! than the [`OutlierLimits`] allow is marked [`ZoneStatus::Outlier`]. Outliers
+//! stay in the statistics, since a real gradient looks the same as a drifting
+//! sensor; the flag tells the operator which zone to look at.
+
+use iot_common::{IoTError, SensorError, error::utils::error_message};
+
+use crate::bme280::{BME280, Measurements};
+
+/// Fewest healthy sensors for a median that can single out an outlier
+///
+/// With two sensors both are equally far from their median, so neither is flagged.
+const MIN_SENSORS_FOR_OUTLIERS: usize = 3;
+
+/// Largest deviation from the zone median before a sensor is flagged
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutlierLimits {
+    /// Temperature deviation in degrees Celsius
+    pub temperature: f32,
+    /// Humidity deviation in %RH
+    pub humidity: f32,
+    /// Pressure deviation in hPa; zones in one building share nearly the same pressure
+    pub pressure: f32,
+}
+
+impl Default for OutlierLimits {
+    fn default() -> Self {
+        Self {
+            temperature: 3.0,
+            humidity: 10.0,
+            pressure: 2.0,
+        }
+    }
+}
+
+/// Outcome of one sensor in an array read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZoneStatus {
+    /// Read and close to its peers
+    Ok,
+    /// Read, but at least one field is outside the [`OutlierLimits`]
+    Outlier,
+    /// Did not read; excluded from the statistics
+    Failed,
+}
+
+/// Spread of one field across the healthy sensors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldRange {
+    /// Lowest value
+    pub min: f32,
+    /// Highest value
+    pub max: f32,
+}
+
+impl FieldRange {
+    /// Difference between the highest and lowest value
+    pub fn spread(&self) -> f32 {
+        self.max - self.min
+    }
+}
+
+/// Min/max statistics of an array read
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArrayStats {
+    /// Temperature range in degrees Celsius
+    pub temperature: FieldRange,
+    /// Humidity range in %RH
+    pub humidity: FieldRange,
+    /// Pressure range in hPa
+    pub pressure: FieldRange,
+    /// Sensors that contributed to the statistics
+    pub healthy: usize,
+}
+
+/// Aggregate of one read across all zones
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrayReading<const N: usize> {
+    /// Mean of the healthy sensors
+    pub mean: Measurements,
+    /// Min/max per field of the healthy sensors
+    pub stats: ArrayStats,
+    /// Per-zone readings, `None` where the sensor failed
+    pub readings: [Option<Measurements>; N],
+    /// Per-zone status, in the same order as the sensors
+    pub status: [ZoneStatus; N],
+}
+
+impl<const N: usize> ArrayReading<N> {
+    /// Aggregates per-zone readings; `None` if no sensor read
+    pub fn aggregate(readings: [Option<Measurements>; N], limits: &OutlierLimits) -> Option<Self> {
+        let healthy = readings.iter().flatten().count();
+        if healthy == 0 {
+            return None;
+        }
+
+        let field = |value: fn(&Measurements) -> f32| {
+            let mut range = FieldRange { min: f32::MAX, max: f32::MIN };
+            let mut sum = 0.0;
+            for reading in readings.iter().flatten() {
+                let v = value(reading);
+                range.min = range.min.min(v);
+                range.max = range.max.max(v);
+                sum += v;
+            }
+            (range, sum / healthy as f32, median(&readings, value))
+        };
+        let (temperature, mean_temperature, median_temperature) = field(|m| m.temperature);
+        let (humidity, mean_humidity, median_humidity) = field(|m| m.humidity);
+        let (pressure, mean_pressure, median_pressure) = field(|m| m.pressure);
+
+        let status = core::array::from_fn(|i| match &readings[i] {
+            None => ZoneStatus::Failed,
+            Some(m) if healthy >= MIN_SENSORS_FOR_OUTLIERS
+                && ((m.temperature - median_temperature).abs() > limits.temperature
+                    || (m.humidity - median_humidity).abs() > limits.humidity
+                    || (m.pressure - median_pressure).abs() > limits.pressure) => ZoneStatus::Outlier,
+            Some(_) => ZoneStatus::Ok,
+        });
+
+        Some(Self {
+            mean: Measurements {
+                temperature: mean_temperature,
+                pressure: mean_pressure,
+                humidity: mean_humidity,
+            },
+            stats: ArrayStats { temperature, humidity, pressure, healthy },
+            readings,
+            status,
+        })
+    }
+
+    /// Zones that did not read
+    pub fn failed(&self) -> impl Iterator<Item = usize> + '_ {
+        self.zones_with(ZoneStatus::Failed)
+    }
+
+    /// Zones flagged as outliers
+    pub fn outliers(&self) -> impl Iterator<Item = usize> + '_ {
+        self.zones_with(ZoneStatus::Outlier)
+    }
+
+    fn zones_with(&self, wanted: ZoneStatus) -> impl Iterator<Item = usize> + '_ {
+        self.status.iter().enumerate().filter(move |(_, s)| **s == wanted).map(|(i, _)| i)
+    }
+}
+
+/// Median of one field over the healthy readings
+fn median<const N: usize>(readings: &[Option<Measurements>; N], value: fn(&Measurements) -> f32) -> f32 {
+    let mut values = [0.0f32; N];
+    let mut len = 0;
+    for reading in readings.iter().flatten() {
+        values[len] = value(reading);
+        len += 1;
+    }
+    let values = &mut values[..len];
+    values.sort_unstable_by(|a, b| a.total_cmp(b));
+    if len % 2 == 1 {
+        values[len / 2]
+    } else {
+        (values[len / 2 - 1] + values[len / 2]) / 2.0
+    }
+}
+
+/// Several BME280s read together as one multi-zone sensor
+///
+/// # Examples
+///
+/// ```no_run
+/// use bme280_embassy::{BME280, I2cDevice, SensorArray};
+///
+/// let bus = platform.get_shared_i2c();
+/// let mut array = SensorArray::new([
+///     BME280::new(I2cDevice::shared(bus, 0x76)),
+///     BME280::new(I2cDevice::shared(bus, 0x77)),
+///     BME280::new(I2cDevice::new(&mut i2c1, 0x76)),
+/// ]);
+/// array.init().await?;
+///
+/// let reading = array.read().await?;
+/// rprintln!("Mean {:.2}°C, spread {:.2}°C", reading.mean.temperature,
+///           reading.stats.temperature.spread());
+/// for zone in reading.outliers() {
+///     rprintln!("Zone {} disagrees with the others", zone);
+/// }
+/// ```
+pub struct SensorArray<'a, const N: usize> {
+    sensors: [BME280<'a>; N],
+    limits: OutlierLimits,
+}
+
+impl<'a, const N: usize> SensorArray<'a, N> {
+    /// Creates an array from one driver per zone, with default outlier limits
+    pub fn new(sensors: [BME280<'a>; N]) -> Self {
+        const { assert!(N > 0, "A sensor array needs at least one sensor") };
+        Self {
+            sensors,
+            limits: OutlierLimits::default(),
+        }
+    }
+
+    /// Replaces the outlier limits
+    pub fn with_outlier_limits(mut self, limits: OutlierLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Initializes every sensor
+    ///
+    /// A sensor that fails to initialize is left in the array and reports
+    /// [`ZoneStatus::Failed`] until it is initialized again.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(count)` - Number of sensors that initialized
+    /// * `Err(IoTError)` - No sensor initialized; the last error
+    pub async fn init(&mut self) -> Result<usize, IoTError> {
+        let mut initialized = 0;
+        let mut last_error = None;
+        for sensor in self.sensors.iter_mut() {
+            match sensor.init().await {
+                Ok(()) => initialized += 1,
+                Err(e) => last_error = Some(e),
+            }
+        }
+        match last_error {
+            Some(e) if initialized == 0 => Err(e),
+            _ => Ok(initialized),
+        }
+    }
+
+    /// Reads every sensor and aggregates the results
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ArrayReading)` - At least one sensor read; failed ones are flagged
+    /// * `Err(IoTError)` - Every sensor failed; the last error
+    pub async fn read(&mut self) -> Result<ArrayReading<N>, IoTError> {
+        let mut readings: [Option<Measurements>; N] = [const { None }; N];
+        let mut last_error = None;
+        for (slot, sensor) in readings.iter_mut().zip(self.sensors.iter_mut()) {
+            match sensor.read_measurements().await {
+                Ok(measurements) => *slot = Some(measurements),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        ArrayReading::aggregate(readings, &self.limits).ok_or_else(|| {
+            last_error.unwrap_or_else(|| IoTError::sensor(SensorError::NotResponding(error_message("No sensor in array"))))
+        })
+    }
+
+    /// Driver of one zone, e.g. to set its calibration offsets
+    pub fn sensor_mut(&mut self, zone: usize) -> Option<&mut BME280<'a>> {
+        self.sensors.get_mut(zone)
+    }
+
+    /// Number of zones
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Always `false`; an array has at least one sensor
+    pub const fn is_empty(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(temperature: f32, humidity: f32, pressure: f32) -> Option<Measurements> {
+        Some(Measurements { temperature, pressure, humidity })
+    }
+
+    #[test]
+    fn test_failed_sensor_is_excluded_and_flagged() {
+        let readings = [
+            reading(20.0, 50.0, 1013.0),
+            None,
+            reading(22.0, 54.0, 1013.4),
+            reading(21.0, 52.0, 1013.2),
+        ];
+
+        let result = ArrayReading::aggregate(readings, &OutlierLimits::default()).unwrap();
+
+        assert_eq!(result.status, [ZoneStatus::Ok, ZoneStatus::Failed, ZoneStatus::Ok, ZoneStatus::Ok]);
+        assert!(result.failed().eq([1]));
+        assert_eq!(result.stats.healthy, 3);
+        assert!((result.mean.temperature - 21.0).abs() < 1e-4);
+        assert!((result.mean.humidity - 52.0).abs() < 1e-4);
+        assert!((result.mean.pressure - 1013.2).abs() < 1e-3);
+        assert_eq!(result.stats.temperature, FieldRange { min: 20.0, max: 22.0 });
+        assert_eq!(result.stats.humidity.spread(), 4.0);
+    }
+
+    #[test]
+    fn test_outlier_flagged_against_median() {
+        let readings = [
+            reading(21.0, 50.0, 1013.0),
+            reading(21.5, 51.0, 1013.1),
+            reading(21.2, 50.5, 1008.0),
+        ];
+
+        let result = ArrayReading::aggregate(readings, &OutlierLimits::default()).unwrap();
+        assert!(result.outliers().eq([2]), "Pressure 5 hPa off the median");
+        assert_eq!(result.stats.healthy, 3, "Outliers stay in the statistics");
+
+        // Two sensors cannot tell which one is off
+        let pair = ArrayReading::aggregate([reading(15.0, 50.0, 1013.0), reading(25.0, 50.0, 1013.0)], &OutlierLimits::default()).unwrap();
+        assert_eq!(pair.status, [ZoneStatus::Ok, ZoneStatus::Ok]);
+    }
+
+    #[test]
+    fn test_all_sensors_failed() {
+        assert!(ArrayReading::<3>::aggregate([None, None, None], &OutlierLimits::default()).is_none());
+    }
+}
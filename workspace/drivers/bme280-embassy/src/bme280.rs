@@ -4,12 +4,19 @@
 //! This implementation uses the I2cDevice abstraction layer for clean architecture
 //! while maintaining compatibility with Phase 2 performance optimization goals.
 
-use crate::i2c_device::I2cDevice;
+use crate::i2c_device::{EspI2c, I2cDevice};
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+use esp_hal::i2c::master::I2c;
+use esp_hal::Blocking;
 use iot_common::{IoTError, error::utils::error_message};
+use iot_hal::SharedI2cBus;
+use iot_common::bme280::{RawSample, CALIB_TP_LEN, CALIB_H_LEN};
+
+pub use iot_common::bme280::{CalibrationData, SensorOffsets};
 
 /// BME280 register addresses
 const BME280_CHIP_ID_REG: u8 = 0xD0;
-const BME280_RESET_REG: u8 = 0xE0;
+pub const BME280_RESET_REG: u8 = 0xE0;
 const BME280_CTRL_HUM_REG: u8 = 0xF2;
 const BME280_STATUS_REG: u8 = 0xF3;
 const BME280_CTRL_MEAS_REG: u8 = 0xF4;
@@ -29,8 +36,21 @@ const BME280_CALIB_H2_LSB_REG: u8 = 0xE1;
 const BME280_CHIP_ID: u8 = 0x60;
 const BMP280_CHIP_ID: u8 = 0x58;
 
+/// I2C address with SDO tied to GND (most breakout boards)
+pub const BME280_I2C_ADDR_PRIMARY: u8 = 0x76;
+
+/// I2C address with SDO tied to VDDIO
+pub const BME280_I2C_ADDR_SECONDARY: u8 = 0x77;
+
+/// Value written to [`BME280_RESET_REG`] to trigger a power-on reset
+pub const BME280_SOFT_RESET_CMD: u8 = 0xB6;
+
+/// Start-up time after a reset before the sensor accepts commands
+const BME280_STARTUP_TIME_MS: u64 = 2;
+
 /// Environmental sensor measurements
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Measurements {
     /// Temperature in degrees Celsius
     pub temperature: f32,
@@ -40,32 +60,133 @@ pub struct Measurements {
     pub humidity: f32,
 }
 
-/// BME280 calibration coefficients
-#[derive(Debug, Default, Clone)]
-pub struct CalibrationData {
-    // Temperature coefficients
-    pub dig_t1: u16,
-    pub dig_t2: i16,
-    pub dig_t3: i16,
-    
-    // Pressure coefficients
-    pub dig_p1: u16,
-    pub dig_p2: i16,
-    pub dig_p3: i16,
-    pub dig_p4: i16,
-    pub dig_p5: i16,
-    pub dig_p6: i16,
-    pub dig_p7: i16,
-    pub dig_p8: i16,
-    pub dig_p9: i16,
-    
-    // Humidity coefficients
-    pub dig_h1: u8,
-    pub dig_h2: i16,
-    pub dig_h3: u8,
-    pub dig_h4: i16,
-    pub dig_h5: i16,
-    pub dig_h6: i8,
+/// Escalation limits for [`BME280::read_with_recovery`]
+/// 
+/// Delays start at `initial_backoff_ms` and double after every failed attempt,
+/// capped at `max_backoff_ms`, so a sensor that is briefly busy recovers quickly
+/// while a disconnected one does not flood the bus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecoveryPolicy {
+    /// Plain read retries before resetting the sensor
+    pub read_retries: u8,
+    /// Soft reset + `init` cycles before giving up
+    pub reset_attempts: u8,
+    /// Delay before the first retry
+    pub initial_backoff_ms: u32,
+    /// Upper bound for the doubled delay
+    pub max_backoff_ms: u32,
+}
+
+impl Default for RecoveryPolicy {
+    fn default() -> Self {
+        Self {
+            read_retries: 3,
+            reset_attempts: 2,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 5000,
+        }
+    }
+}
+
+impl RecoveryPolicy {
+    /// Delay before retry number `step` (0-based) of the escalation
+    pub fn backoff_ms(&self, step: u32) -> u32 {
+        self.initial_backoff_ms
+            .saturating_mul(1u32 << step.min(31))
+            .min(self.max_backoff_ms)
+    }
+}
+
+/// Most drastic step [`BME280::read_with_recovery`] needed
+/// 
+/// Returned with both successful and failed reads so the caller can track
+/// sensor health, e.g. count resets or mark the sensor degraded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryAction {
+    /// First read succeeded
+    None,
+    /// Read succeeded after this many plain retries
+    Retried(u8),
+    /// Read succeeded after this many soft reset + `init` cycles
+    Reset(u8),
+    /// Every retry and reset failed; the sensor needs attention
+    Exhausted,
+}
+
+/// Raw register snapshot from [`BME280::dump_registers`]
+/// 
+/// Values are exactly as read, so a configuration write that did not take
+/// shows up here even while the compensated readings look plausible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisterDump {
+    /// I2C address the registers were read from
+    pub address: u8,
+    /// `id` (0xD0): 0x60 for a BME280, 0x58 for a BMP280
+    pub chip_id: u8,
+    /// `ctrl_hum` (0xF2): humidity oversampling in bits 2:0
+    pub ctrl_hum: u8,
+    /// `status` (0xF3): bit 3 measuring, bit 0 NVM copy in progress
+    pub status: u8,
+    /// `ctrl_meas` (0xF4): temperature oversampling 7:5, pressure 4:2, mode 1:0
+    pub ctrl_meas: u8,
+    /// `config` (0xF5): standby time 7:5, IIR filter 4:2
+    pub config: u8,
+    /// `press_msb` .. `hum_lsb` (0xF7-0xFE): the last raw conversion
+    pub data: [u8; 8],
+    /// `dig_T1` .. `dig_P9` (0x88-0x9F)
+    pub calib_tp: [u8; CALIB_TP_LEN],
+    /// `dig_H1` (0xA1)
+    pub calib_h1: u8,
+    /// `dig_H2` .. `dig_H6` (0xE1-0xE7)
+    pub calib_h: [u8; CALIB_H_LEN],
+}
+
+impl RegisterDump {
+    /// Labeled register blocks in address order: name, first register, values
+    pub fn blocks(&self) -> [(&'static str, u8, &[u8]); 9] {
+        use core::slice::from_ref;
+        [
+            ("calib_tp", BME280_CALIB_T1_LSB_REG, &self.calib_tp),
+            ("dig_H1", BME280_CALIB_H1_REG, from_ref(&self.calib_h1)),
+            ("id", BME280_CHIP_ID_REG, from_ref(&self.chip_id)),
+            ("calib_h", BME280_CALIB_H2_LSB_REG, &self.calib_h),
+            ("ctrl_hum", BME280_CTRL_HUM_REG, from_ref(&self.ctrl_hum)),
+            ("status", BME280_STATUS_REG, from_ref(&self.status)),
+            ("ctrl_meas", BME280_CTRL_MEAS_REG, from_ref(&self.ctrl_meas)),
+            ("config", BME280_CONFIG_REG, from_ref(&self.config)),
+            ("data", BME280_PRESS_MSB_REG, &self.data),
+        ]
+    }
+
+    /// Oversampling factors for temperature, pressure and humidity; 0 = skipped
+    pub fn oversampling(&self) -> (u8, u8, u8) {
+        (Self::factor(self.ctrl_meas >> 5), Self::factor(self.ctrl_meas >> 2), Self::factor(self.ctrl_hum))
+    }
+
+    /// Power mode from `ctrl_meas`
+    pub fn mode_name(&self) -> &'static str {
+        match self.ctrl_meas & 0x03 {
+            0b00 => "sleep",
+            0b11 => "normal",
+            _ => "forced",
+        }
+    }
+
+    /// IIR filter coefficient from `config`; 0 = filter off
+    pub fn filter_coefficient(&self) -> u8 {
+        match (self.config >> 2) & 0x07 {
+            0 => 0,
+            n => 1 << n,
+        }
+    }
+
+    fn factor(bits: u8) -> u8 {
+        match bits & 0x07 {
+            0 => 0,
+            n @ 1..=5 => 1 << (n - 1),
+            _ => 16,
+        }
+    }
 }
 
 /// BME280 environmental sensor driver using I2C abstraction layer
@@ -73,12 +194,67 @@ pub struct CalibrationData {
 /// This implementation is based on the proven working simple-iot module
 /// and uses the I2cDevice abstraction for clean architecture and testability.
 /// Compatible with Phase 2 performance optimization and HAL abstraction goals.
-pub struct BME280<'a> {
-    i2c_dev: I2cDevice<'a>,
+pub struct BME280<'a, T = EspI2c<'a>> {
+    i2c_dev: I2cDevice<'a, T>,
     calib_data: Option<CalibrationData>,
+    offsets: SensorOffsets,
 }
 
 impl<'a> BME280<'a> {
+    /// Creates a driver on whichever BME280 address answers
+    /// 
+    /// Reads the chip ID at [`BME280_I2C_ADDR_PRIMARY`] (0x76) and falls back to
+    /// [`BME280_I2C_ADDR_SECONDARY`] (0x77) on a NACK or an unexpected ID, so
+    /// modules strapped either way work without configuration. The detected
+    /// address is available from [`address`](Self::address). Call
+    /// [`init`](Self::init) afterwards as usual.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(BME280)` - A BME280 or BMP280 answered
+    /// * `Err(SensorError::NotResponding)` - Nothing ACKed on either address
+    ///   (check wiring and power)
+    /// * `Err(SensorError::InitializationFailed)` - A device answered but its chip
+    ///   ID is not a BME280/BMP280 (another chip on the address)
+    /// 
+    /// # Examples
+    /// 
+    /// ```no_run
+    /// use bme280_embassy::BME280;
+    /// 
+    /// let mut sensor = BME280::new_auto(&mut i2c).await?;
+    /// println!("BME280 at 0x{:02X}", sensor.address());
+    /// sensor.init().await?;
+    /// ```
+    pub async fn new_auto(i2c: &'a mut I2c<'a, Blocking>) -> Result<Self, IoTError> {
+        Self::detect(I2cDevice::new(i2c, BME280_I2C_ADDR_PRIMARY)).await
+    }
+
+    /// Like [`new_auto`](Self::new_auto), trying the configured `address` first
+    /// 
+    /// For boards that declare the sensor address in their hardware
+    /// configuration (`iot_hal::I2cConfig::sensor_address`); 0x76 and 0x77
+    /// are still probed if nothing answers there.
+    pub async fn new_auto_from(i2c: &'a mut I2c<'a, Blocking>, address: u8) -> Result<Self, IoTError> {
+        Self::detect(I2cDevice::new(i2c, address)).await
+    }
+
+    /// Like [`new_auto`](Self::new_auto), on a bus shared with other drivers
+    /// 
+    /// # Examples
+    /// 
+    /// ```no_run
+    /// use bme280_embassy::BME280;
+    /// 
+    /// let mut sensor = BME280::new_auto_shared(platform.get_shared_i2c()).await?;
+    /// sensor.init().await?;
+    /// ```
+    pub async fn new_auto_shared(bus: SharedI2cBus<'a>) -> Result<Self, IoTError> {
+        Self::detect(I2cDevice::shared(bus, BME280_I2C_ADDR_PRIMARY)).await
+    }
+}
+
+impl<'a, T: AsyncI2c> BME280<'a, T> {
     /// Creates a new BME280 driver instance
     /// 
     /// # Arguments
@@ -99,18 +275,115 @@ impl<'a> BME280<'a> {
     /// let i2c_dev = I2cDevice::new(&mut i2c, 0x76);
     /// let mut sensor = BME280::new(i2c_dev);
     /// ```
-    pub fn new(i2c_dev: I2cDevice<'a>) -> Self {
+    pub fn new(i2c_dev: I2cDevice<'a, T>) -> Self {
         Self {
             i2c_dev,
             calib_data: None,
+            offsets: SensorOffsets::NONE,
         }
     }
 
+    /// Probes both addresses for a BME280/BMP280 chip ID
+    /// Probe the device's current address, then 0x76 and 0x77
+    async fn detect(mut i2c_dev: I2cDevice<'a, T>) -> Result<Self, IoTError> {
+        let mut wrong_chip = false;
+        let first = i2c_dev.get_address();
+        let fallbacks = [BME280_I2C_ADDR_PRIMARY, BME280_I2C_ADDR_SECONDARY]
+            .into_iter()
+            .filter(|&address| address != first);
+
+        for address in core::iter::once(first).chain(fallbacks) {
+            i2c_dev.set_address(address);
+            match i2c_dev.read_register(BME280_CHIP_ID_REG).await {
+                Ok(BME280_CHIP_ID | BMP280_CHIP_ID) => return Ok(Self::new(i2c_dev)),
+                Ok(_) => wrong_chip = true,
+                Err(_) => {} // NACK - nothing on this address
+            }
+        }
+
+        if wrong_chip {
+            Err(IoTError::sensor(iot_common::SensorError::InitializationFailed(error_message("Device at 0x76/0x77 is not a BME280"))))
+        } else {
+            Err(IoTError::sensor(iot_common::SensorError::NotResponding(error_message("No device at 0x76 or 0x77"))))
+        }
+    }
+
+    /// I2C address the driver talks to
+    pub fn address(&self) -> u8 {
+        self.i2c_dev.get_address()
+    }
+
+    /// Read the chip ID register (0x60 for a BME280, 0x58 for a BMP280)
+    /// 
+    /// A cheap presence check that leaves the measurement configuration
+    /// untouched, e.g. for self-tests while the sensor is in use.
+    pub async fn chip_id(&mut self) -> Result<u8, IoTError> {
+        self.i2c_dev.read_register(BME280_CHIP_ID_REG).await
+    }
+
+    /// Read the whole register map for bring-up debugging
+    /// 
+    /// Reads the chip ID, the control, status and data registers and both
+    /// calibration blocks without changing anything, so it is safe while the
+    /// sensor is in use. Each transfer is bounded by `timeout`: a bus held by
+    /// another driver or a stalled async transport fails instead of hanging. The
+    /// blocking esp-hal transport relies on the peripheral's own bus timeout.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(RegisterDump)` - Raw register values
+    /// * `Err(SensorError::NotResponding)` - A transfer timed out
+    /// * `Err(SensorError::I2CError)` - A transfer failed (NACK, bus error)
+    pub async fn dump_registers(&mut self, timeout: embassy_time::Duration) -> Result<RegisterDump, IoTError> {
+        let mut dump = RegisterDump {
+            address: self.address(),
+            chip_id: 0,
+            ctrl_hum: 0,
+            status: 0,
+            ctrl_meas: 0,
+            config: 0,
+            data: [0; 8],
+            calib_tp: [0; CALIB_TP_LEN],
+            calib_h1: 0,
+            calib_h: [0; CALIB_H_LEN],
+        };
+        let mut ctrl = [0u8; 4];
+
+        self.read_within(BME280_CHIP_ID_REG, core::slice::from_mut(&mut dump.chip_id), timeout).await?;
+        self.read_within(BME280_CTRL_HUM_REG, &mut ctrl, timeout).await?;
+        self.read_within(BME280_PRESS_MSB_REG, &mut dump.data, timeout).await?;
+        self.read_within(BME280_CALIB_T1_LSB_REG, &mut dump.calib_tp, timeout).await?;
+        self.read_within(BME280_CALIB_H1_REG, core::slice::from_mut(&mut dump.calib_h1), timeout).await?;
+        self.read_within(BME280_CALIB_H2_LSB_REG, &mut dump.calib_h, timeout).await?;
+
+        [dump.ctrl_hum, dump.status, dump.ctrl_meas, dump.config] = ctrl;
+        Ok(dump)
+    }
+
+    /// Like [`dump_registers`](Self::dump_registers), for another address on the same bus
+    /// 
+    /// Useful to check a second sensor, or whether the strapping is what you
+    /// think it is. The driver's own address is restored afterwards.
+    pub async fn dump_registers_at(&mut self, address: u8, timeout: embassy_time::Duration) -> Result<RegisterDump, IoTError> {
+        let own_address = self.i2c_dev.get_address();
+        self.i2c_dev.set_address(address);
+        let dump = self.dump_registers(timeout).await;
+        self.i2c_dev.set_address(own_address);
+        dump
+    }
+
+    async fn read_within(&mut self, register: u8, buffer: &mut [u8], timeout: embassy_time::Duration) -> Result<(), IoTError> {
+        embassy_time::with_timeout(timeout, self.i2c_dev.read_registers(register, buffer))
+            .await
+            .unwrap_or_else(|_| Err(IoTError::sensor(iot_common::SensorError::NotResponding(error_message("I2C transaction timed out")))))
+    }
+
     /// Initialize the BME280 sensor
     /// 
     /// This method performs complete sensor initialization:
     /// 1. Verifies sensor presence and chip ID
-    /// 2. Reads factory calibration coefficients 
+    /// 2. Soft-resets the sensor and reads factory calibration coefficients
+    ///    (see [`soft_reset`](Self::soft_reset))
     /// 3. Configures sensor registers for optimal operation
     /// 
     /// # Returns
@@ -134,14 +407,8 @@ impl<'a> BME280<'a> {
             }
         }
 
-        // Read calibration data
-        self.read_calibration_data().await?;
-
-        // Reset sensor
-        self.i2c_dev.write_register(BME280_RESET_REG, 0xB6).await?;
-
-        // Wait for reset to complete
-        embassy_time::Timer::after(embassy_time::Duration::from_millis(10)).await;
+        // Reset sensor to a known state; this also reads calibration data
+        self.soft_reset().await?;
 
         // Configure sensor for forced mode measurements
         self.configure_sensor().await?;
@@ -149,6 +416,38 @@ impl<'a> BME280<'a> {
         Ok(())
     }
 
+    /// Soft-reset the sensor to its power-on state
+    /// 
+    /// Writes `0xB6` to the reset register `0xE0`, waits for the start-up time and
+    /// for the NVM copy to finish, then re-reads the calibration coefficients. The
+    /// sensor comes back in sleep mode with all control registers at their defaults:
+    /// oversampling and filter settings are lost, so call [`init`](Self::init)
+    /// before the next measurement. The cached calibration is invalidated first and
+    /// stays `None` if the re-read fails.
+    /// 
+    /// This recovers a sensor stuck mid-conversion or with corrupted registers
+    /// without power-cycling the board.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(())` - Sensor reset and calibration reloaded
+    /// * `Err(IoTError)` - The reset command or calibration read failed
+    pub async fn soft_reset(&mut self) -> Result<(), IoTError> {
+        self.calib_data = None;
+        self.i2c_dev.write_register(BME280_RESET_REG, BME280_SOFT_RESET_CMD).await?;
+        embassy_time::Timer::after(embassy_time::Duration::from_millis(BME280_STARTUP_TIME_MS)).await;
+
+        // Status bit 0 (im_update) is set while the NVM is copied to the image registers
+        for _ in 0..10 {
+            match self.i2c_dev.read_register(BME280_STATUS_REG).await {
+                Ok(status) if status & 0x01 == 0 => break,
+                _ => embassy_time::Timer::after(embassy_time::Duration::from_millis(1)).await,
+            }
+        }
+
+        self.read_calibration_data().await
+    }
+
     /// Read compensated environmental measurements
     /// 
     /// This method performs a complete measurement cycle:
@@ -156,6 +455,7 @@ impl<'a> BME280<'a> {
     /// 2. Waits for completion  
     /// 3. Reads raw data
     /// 4. Applies calibration compensation
+    /// 5. Adds the field-calibration offsets (see [`set_offsets`](Self::set_offsets))
     /// 
     /// # Returns
     /// 
@@ -165,22 +465,16 @@ impl<'a> BME280<'a> {
         // Add delay before triggering measurement (hardware stabilization)
         embassy_time::Timer::after(embassy_time::Duration::from_millis(10)).await;
         
-        // Trigger forced measurement
-        self.force_measurement().await?;
-
-        // Wait for measurement completion
-        self.wait_for_measurement().await?;
-
-        // Read raw sensor data
+        // Trigger a forced measurement and read the raw sensor data
         let (raw_temp, raw_press, raw_hum) = self.read_raw_data().await?;
 
         // Apply calibration compensation
         let calib = self.calib_data.as_ref()
             .ok_or_else(|| IoTError::sensor(iot_common::SensorError::InitializationFailed(error_message("Calibration data not available"))))?;
             
-        let (temperature, t_fine) = Self::compensate_temperature(raw_temp, calib);
-        let pressure = Self::compensate_pressure(raw_press, t_fine, calib);
-        let humidity = Self::compensate_humidity(raw_hum, t_fine, calib);
+        // Official BME280 compensation algorithms from datasheet
+        let raw = RawSample { adc_t: raw_temp, adc_p: raw_press, adc_h: raw_hum };
+        let (temperature, pressure, humidity) = self.offsets.apply(raw.compensate(calib));
 
         Ok(Measurements {
             temperature,
@@ -189,55 +483,148 @@ impl<'a> BME280<'a> {
         })
     }
 
+    /// Read measurements, escalating recovery on failure
+    /// 
+    /// 1. Retries the read up to `policy.read_retries` times
+    /// 2. Then re-runs [`init`](Self::init), which issues a
+    ///    [`soft_reset`](Self::soft_reset), and reads again, up to
+    ///    `policy.reset_attempts` times
+    /// 
+    /// Every failed step waits with exponential backoff (see
+    /// [`RecoveryPolicy::backoff_ms`]). A soft reset is the datasheet's way to bring
+    /// a sensor stuck mid-conversion or with corrupted control registers back to
+    /// its power-on state without cycling the supply.
+    /// 
+    /// # Returns
+    /// 
+    /// The read result together with the [`RecoveryAction`] that was needed. On
+    /// failure the action is [`RecoveryAction::Exhausted`] and the error is the
+    /// last one seen.
+    /// 
+    /// # Examples
+    /// 
+    /// ```no_run
+    /// use bme280_embassy::{RecoveryAction, RecoveryPolicy};
+    /// 
+    /// let (result, action) = sensor.read_with_recovery(&RecoveryPolicy::default()).await;
+    /// if let RecoveryAction::Reset(n) = action {
+    ///     println!("Sensor recovered after {} reset(s)", n);
+    /// }
+    /// ```
+    pub async fn read_with_recovery(&mut self, policy: &RecoveryPolicy) -> (Result<Measurements, IoTError>, RecoveryAction) {
+        let mut last_error = match self.read_measurements().await {
+            Ok(measurements) => return (Ok(measurements), RecoveryAction::None),
+            Err(e) => e,
+        };
+        let mut step = 0u32;
+
+        for retry in 1..=policy.read_retries {
+            Self::backoff(policy, step).await;
+            step += 1;
+            match self.read_measurements().await {
+                Ok(measurements) => return (Ok(measurements), RecoveryAction::Retried(retry)),
+                Err(e) => last_error = e,
+            }
+        }
+
+        for reset in 1..=policy.reset_attempts {
+            Self::backoff(policy, step).await;
+            step += 1;
+            // init issues the soft reset before reconfiguring the sensor
+            let result = match self.init().await {
+                Ok(()) => self.read_measurements().await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(measurements) => return (Ok(measurements), RecoveryAction::Reset(reset)),
+                Err(e) => last_error = e,
+            }
+        }
+
+        (Err(last_error), RecoveryAction::Exhausted)
+    }
+
+    /// Read uncompensated ADC values
+    /// 
+    /// Triggers a forced measurement like [`read_measurements`](Self::read_measurements)
+    /// but returns the raw conversion results, for tools that apply their own
+    /// compensation or log raw values to characterize a sensor. Combine them with
+    /// [`calibration`](Self::calibration) to reproduce the driver's output.
+    /// 
+    /// Call [`init`](Self::init) first: it configures oversampling, without which
+    /// the sensor skips the conversions.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok((raw_temp, raw_press, raw_hum))` where
+    ///   - `raw_temp` and `raw_press` are 20-bit values (`0..=0xFFFFF`); `0x80000`
+    ///     means the measurement was skipped
+    ///   - `raw_hum` is a 16-bit value (`0..=0xFFFF`); `0x8000` means skipped, as
+    ///     on a BMP280 which has no humidity sensor
+    /// * `Err(IoTError)` - I2C communication failed
+    pub async fn read_raw_data(&mut self) -> Result<(i32, i32, i32), IoTError> {
+        // Trigger forced measurement
+        self.force_measurement().await?;
+
+        // Wait for measurement completion
+        self.wait_for_measurement().await?;
+
+        self.read_data_registers().await
+    }
+
+    /// Set field-calibration offsets added to every reading
+    /// 
+    /// Corrects a unit that reads off against a reference instrument, or
+    /// temperature skewed by self-heating near the WiFi radio. Applied by
+    /// [`read_measurements`](Self::read_measurements) after compensation; the
+    /// corrected humidity is clamped to 0-100 %RH. Raw data is not affected.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `temp` - Temperature offset in °C
+    /// * `humidity` - Relative humidity offset in %RH (leave 0 on a BMP280)
+    /// * `pressure` - Pressure offset in hPa
+    pub fn set_offsets(&mut self, temp: f32, humidity: f32, pressure: f32) {
+        self.offsets = SensorOffsets::new(temp, humidity, pressure);
+    }
+
+    /// Field-calibration offsets currently applied
+    pub fn offsets(&self) -> SensorOffsets {
+        self.offsets
+    }
+
+    /// Factory calibration coefficients
+    /// 
+    /// Returns `None` until [`init`](Self::init) has read them from the sensor.
+    pub fn calibration(&self) -> Option<&CalibrationData> {
+        self.calib_data.as_ref()
+    }
+
     /// Get calibration data for debugging
+    #[deprecated(note = "use `calibration()`")]
     pub fn get_calibration_data(&self) -> Option<&CalibrationData> {
-        self.calib_data.as_ref()
+        self.calibration()
     }
 
     // Private implementation methods
 
-    async fn read_calibration_data(&mut self) -> Result<(), IoTError> {
-        let mut calib = CalibrationData::default();
+    async fn backoff(policy: &RecoveryPolicy, step: u32) {
+        embassy_time::Timer::after(embassy_time::Duration::from_millis(policy.backoff_ms(step) as u64)).await;
+    }
 
+    async fn read_calibration_data(&mut self) -> Result<(), IoTError> {
         // Read temperature and pressure calibration (0x88-0x9F)
-        let mut calib_tp = [0u8; 24];
+        let mut calib_tp = [0u8; CALIB_TP_LEN];
         self.i2c_dev.read_registers(BME280_CALIB_T1_LSB_REG, &mut calib_tp).await?;
 
-        // Parse temperature coefficients
-        calib.dig_t1 = u16::from_le_bytes([calib_tp[0], calib_tp[1]]);
-        calib.dig_t2 = i16::from_le_bytes([calib_tp[2], calib_tp[3]]);
-        calib.dig_t3 = i16::from_le_bytes([calib_tp[4], calib_tp[5]]);
-
-        // Parse pressure coefficients
-        calib.dig_p1 = u16::from_le_bytes([calib_tp[6], calib_tp[7]]);
-        calib.dig_p2 = i16::from_le_bytes([calib_tp[8], calib_tp[9]]);
-        calib.dig_p3 = i16::from_le_bytes([calib_tp[10], calib_tp[11]]);
-        calib.dig_p4 = i16::from_le_bytes([calib_tp[12], calib_tp[13]]);
-        calib.dig_p5 = i16::from_le_bytes([calib_tp[14], calib_tp[15]]);
-        calib.dig_p6 = i16::from_le_bytes([calib_tp[16], calib_tp[17]]);
-        calib.dig_p7 = i16::from_le_bytes([calib_tp[18], calib_tp[19]]);
-        calib.dig_p8 = i16::from_le_bytes([calib_tp[20], calib_tp[21]]);
-        calib.dig_p9 = i16::from_le_bytes([calib_tp[22], calib_tp[23]]);
-
         // Read humidity calibration H1 (0xA1)
-        calib.dig_h1 = self.i2c_dev.read_register(BME280_CALIB_H1_REG).await?;
+        let calib_h1 = self.i2c_dev.read_register(BME280_CALIB_H1_REG).await?;
 
         // Read humidity calibration H2-H6 (0xE1-0xE7)
-        let mut calib_h = [0u8; 7];
+        let mut calib_h = [0u8; CALIB_H_LEN];
         self.i2c_dev.read_registers(BME280_CALIB_H2_LSB_REG, &mut calib_h).await?;
 
-        // Parse humidity coefficients (complex bit manipulation)
-        calib.dig_h2 = i16::from_le_bytes([calib_h[0], calib_h[1]]);
-        calib.dig_h3 = calib_h[2];
-        calib.dig_h4 = ((calib_h[3] as i16) << 4) | ((calib_h[4] as i16) & 0x0F);
-        calib.dig_h5 = ((calib_h[5] as i16) << 4) | ((calib_h[4] as i16) >> 4);
-        calib.dig_h6 = calib_h[6] as i8;
-
-        // Sign extend 12-bit values
-        if calib.dig_h4 > 2047 { calib.dig_h4 -= 4096; }
-        if calib.dig_h5 > 2047 { calib.dig_h5 -= 4096; }
-
-        self.calib_data = Some(calib);
+        self.calib_data = Some(CalibrationData::from_registers(&calib_tp, calib_h1, &calib_h));
         Ok(())
     }
 
@@ -282,7 +669,7 @@ impl<'a> BME280<'a> {
         Ok(())
     }
 
-    async fn read_raw_data(&mut self) -> Result<(i32, i32, i32), IoTError> {
+    async fn read_data_registers(&mut self) -> Result<(i32, i32, i32), IoTError> {
         // Read pressure (0xF7-0xF9)
         let mut press_data = [0u8; 3];
         self.i2c_dev.read_registers(BME280_PRESS_MSB_REG, &mut press_data).await?;
@@ -304,63 +691,45 @@ impl<'a> BME280<'a> {
 
         Ok((raw_temp, raw_press, raw_hum))
     }
+}
 
-    // Official BME280 compensation algorithms from datasheet
-
-    fn compensate_temperature(adc_t: i32, calib: &CalibrationData) -> (f32, i32) {
-        let var1 = (((adc_t >> 3) - ((calib.dig_t1 as i32) << 1)) * (calib.dig_t2 as i32)) >> 11;
-        let var2 = (((((adc_t >> 4) - (calib.dig_t1 as i32)) * 
-                      ((adc_t >> 4) - (calib.dig_t1 as i32))) >> 12) * 
-                     (calib.dig_t3 as i32)) >> 14;
-        
-        let t_fine = var1 + var2;
-        let temperature = (t_fine * 5 + 128) >> 8;
-        
-        (temperature as f32 / 100.0, t_fine)
-    }
-
-    fn compensate_pressure(adc_p: i32, t_fine: i32, calib: &CalibrationData) -> f32 {
-        let mut var1: i64 = (t_fine as i64) - 128000;
-        let mut var2: i64 = var1 * var1 * (calib.dig_p6 as i64);
-        var2 += (var1 * (calib.dig_p5 as i64)) << 17;
-        var2 += (calib.dig_p4 as i64) << 35;
-        var1 = ((var1 * var1 * (calib.dig_p3 as i64)) >> 8) + 
-               ((var1 * (calib.dig_p2 as i64)) << 12);
-        var1 = (((1i64 << 47) + var1)) * (calib.dig_p1 as i64) >> 33;
-
-        if var1 == 0 {
-            return 0.0; // Avoid division by zero
-        }
-
-        let mut p: i64 = 1048576 - (adc_p as i64);
-        p = (((p << 31) - var2) * 3125) / var1;
-        var1 = ((calib.dig_p9 as i64) * (p >> 13) * (p >> 13)) >> 25;
-        var2 = ((calib.dig_p8 as i64) * p) >> 19;
-        p = ((p + var1 + var2) >> 8) + ((calib.dig_p7 as i64) << 4);
-        
-        (p as f32) / 25600.0
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_dump_decodes_configuration() {
+        let dump = RegisterDump {
+            address: BME280_I2C_ADDR_PRIMARY,
+            chip_id: BME280_CHIP_ID,
+            ctrl_hum: 0x01,
+            status: 0x08,
+            ctrl_meas: 0b001_011_01, // osrs_t x1, osrs_p x4, forced
+            config: 0b000_100_00,    // filter 16
+            data: [0x80, 0, 0, 0x80, 0, 0, 0x80, 0],
+            calib_tp: [0; CALIB_TP_LEN],
+            calib_h1: 0x4B,
+            calib_h: [0; CALIB_H_LEN],
+        };
+
+        assert_eq!(dump.oversampling(), (1, 4, 1));
+        assert_eq!(dump.mode_name(), "forced");
+        assert_eq!(dump.filter_coefficient(), 16);
+
+        let blocks = dump.blocks();
+        assert!(blocks.windows(2).all(|pair| pair[0].1 < pair[1].1), "Blocks are in address order");
+        assert_eq!(blocks[6], ("ctrl_meas", BME280_CTRL_MEAS_REG, &[0b001_011_01][..]));
     }
 
-    fn compensate_humidity(adc_h: i32, t_fine: i32, calib: &CalibrationData) -> f32 {
-        if adc_h == 0x8000 {
-            return 0.0; // Invalid reading (BMP280 or measurement not ready)
-        }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_measurements_json_round_trip() {
+        let measurements = Measurements { temperature: 22.5, pressure: 1013.25, humidity: 45.0 };
+        let mut buf = [0u8; 96];
+        let len = serde_json_core::to_slice(&measurements, &mut buf).unwrap();
+        assert_eq!(&buf[..len], br#"{"temperature":22.5,"pressure":1013.25,"humidity":45.0}"#);
 
-        let v_x1_u32r = t_fine - 76800;
-        
-        let v_x1_u32r = (adc_h << 14) - ((calib.dig_h4 as i32) << 20) - 
-                        ((calib.dig_h5 as i32) * v_x1_u32r) + 16384;
-        let v_x1_u32r = v_x1_u32r >> 15;
-        
-        let v_x1_u32r = v_x1_u32r * (((((v_x1_u32r * (calib.dig_h6 as i32)) >> 10) * 
-                        (((v_x1_u32r * (calib.dig_h3 as i32)) >> 11) + 32768)) >> 10) + 2097152);
-        let v_x1_u32r = ((v_x1_u32r + 8192) >> 14) * (calib.dig_h2 as i32);
-        let v_x1_u32r = v_x1_u32r - (((((v_x1_u32r >> 15) * (v_x1_u32r >> 15)) >> 7) * 
-                        (calib.dig_h1 as i32)) >> 4);
-        
-        let v_x1_u32r = if v_x1_u32r < 0 { 0 } else { v_x1_u32r };
-        let v_x1_u32r = if v_x1_u32r > 419430400 { 419430400 } else { v_x1_u32r };
-        
-        (v_x1_u32r >> 12) as f32 / 1024.0
+        let (parsed, _): (Measurements, _) = serde_json_core::from_slice(&buf[..len]).unwrap();
+        assert_eq!(parsed, measurements);
     }
-}
\ No newline at end of file
+}
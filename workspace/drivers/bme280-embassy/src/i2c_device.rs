@@ -2,18 +2,86 @@
 //! 
 //! This module provides a clean abstraction for I2C communication with the BME280 sensor.
 //! It isolates all I2C operations and provides a consistent interface for the BME280 driver.
+//! 
+//! The device either owns the esp-hal I2C driver exclusively ([`I2cDevice::new`]) or talks
+//! through a [`SharedI2cBus`] handle when other devices sit on the same bus
+//! ([`I2cDevice::shared`]). With a shared bus every register access locks the bus for that
+//! one transfer only.
+//! 
+//! Any other [`embedded_hal_async::i2c::I2c`] implementation works too
+//! ([`I2cDevice::from_hal`]), e.g. a device handle from a bus-sharing crate or another
+//! HAL. In the other direction `I2cDevice` implements the same trait, so drivers from the
+//! wider ecosystem can talk to other addresses through it.
 
+use core::marker::PhantomData;
+
+use embedded_hal_async::i2c::{ErrorKind, ErrorType, I2c as AsyncI2c, Operation};
 use esp_hal::i2c::master::I2c;
 use esp_hal::Blocking;
 use iot_common::{IoTError, error::utils::error_message};
+use iot_hal::SharedI2cBus;
+
+/// Transport behind an [`EspI2c`]
+enum Bus<'a> {
+    /// Exclusive esp-hal driver
+    Direct(&'a mut I2c<'a, Blocking>),
+    /// Handle to a bus shared with other drivers
+    Shared(SharedI2cBus<'a>),
+}
+
+/// esp-hal transport created by [`I2cDevice::new`] and [`I2cDevice::shared`]
+/// 
+/// Implements [`embedded_hal_async::i2c::I2c`] on top of the blocking esp-hal driver or
+/// a [`SharedI2cBus`]. On the shared bus a multi-operation transaction holds the bus
+/// lock throughout, but is sent as separate transfers unless it is a plain write-read.
+pub struct EspI2c<'a> {
+    bus: Bus<'a>,
+}
+
+impl ErrorType for EspI2c<'_> {
+    type Error = ErrorKind;
+}
+
+impl AsyncI2c for EspI2c<'_> {
+    async fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Self::Error> {
+        match &mut self.bus {
+            Bus::Direct(i2c) => embedded_hal::i2c::I2c::transaction(&mut **i2c, address, operations)
+                .map_err(|e| embedded_hal::i2c::Error::kind(&e)),
+            Bus::Shared(bus) => {
+                let mut i2c = bus.lock().await;
+                let result = match operations {
+                    [] => i2c.write(address, &[]).await,
+                    [Operation::Write(write), Operation::Read(read)] => i2c.write_read(address, write, read).await,
+                    operations => {
+                        let mut result = Ok(());
+                        for operation in operations.iter_mut() {
+                            result = match operation {
+                                Operation::Write(write) => i2c.write(address, write).await,
+                                Operation::Read(read) => i2c.read(address, read).await,
+                            };
+                            if result.is_err() {
+                                break;
+                            }
+                        }
+                        result
+                    }
+                };
+                result.map_err(|_| ErrorKind::Other)
+            }
+        }
+    }
+}
 
 /// I2C device abstraction for BME280 communication
 /// 
 /// This structure encapsulates I2C communication with the BME280 sensor,
-/// providing a clean interface for register read/write operations.
-pub struct I2cDevice<'a> {
-    i2c: &'a mut I2c<'a, Blocking>,
+/// providing a clean interface for register read/write operations. The
+/// transport defaults to the esp-hal [`EspI2c`]; [`from_hal`](Self::from_hal)
+/// accepts any [`embedded_hal_async::i2c::I2c`].
+pub struct I2cDevice<'a, T = EspI2c<'a>> {
+    i2c: T,
     address: u8,
+    _bus: PhantomData<&'a ()>,
 }
 
 impl<'a> I2cDevice<'a> {
@@ -38,7 +106,71 @@ impl<'a> I2cDevice<'a> {
     /// let device = I2cDevice::new(&mut i2c, 0x76);
     /// ```
     pub fn new(i2c: &'a mut I2c<'a, Blocking>, address: u8) -> Self {
-        Self { i2c, address }
+        Self::from_hal(EspI2c { bus: Bus::Direct(i2c) }, address)
+    }
+
+    /// Creates a device on a bus shared with other drivers
+    /// 
+    /// # Arguments
+    /// 
+    /// * `bus` - Handle from `HardwarePlatform::get_shared_i2c`
+    /// * `address` - I2C slave address of the device
+    /// 
+    /// # Examples
+    /// 
+    /// ```no_run
+    /// use bme280_embassy::{BME280, I2cDevice};
+    /// use iot_hal::HardwarePlatform;
+    /// 
+    /// let bus = platform.get_shared_i2c();
+    /// let mut sensor = BME280::new(I2cDevice::shared(bus, 0x76));
+    /// // `bus` is Copy - hand it to the other drivers on the same wires
+    /// ```
+    pub fn shared(bus: SharedI2cBus<'a>, address: u8) -> Self {
+        Self::from_hal(EspI2c { bus: Bus::Shared(bus) }, address)
+    }
+}
+
+impl<'a, T: AsyncI2c> I2cDevice<'a, T> {
+    /// Creates a device on any `embedded-hal-async` I2C implementation
+    /// 
+    /// `embedded-hal-bus` shares blocking buses only; for async drivers the
+    /// equivalent is `embassy-embedded-hal`'s `I2cDevice`, which hands out one
+    /// `embedded_hal_async::i2c::I2c` per driver on a mutex-guarded bus.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `i2c` - Bus handle implementing [`embedded_hal_async::i2c::I2c`]
+    /// * `address` - I2C slave address of the device
+    /// 
+    /// # Examples
+    /// 
+    /// ```no_run
+    /// use bme280_embassy::{BME280, I2cDevice};
+    /// use embassy_embedded_hal::shared_bus::asynch::i2c::I2cDevice as SharedDevice;
+    /// use embassy_sync::{blocking_mutex::raw::NoopRawMutex, mutex::Mutex};
+    /// use esp_hal::i2c::master::{I2c, Config as I2cConfig};
+    /// use static_cell::StaticCell;
+    /// 
+    /// static BUS: StaticCell<Mutex<NoopRawMutex, I2c<'static, esp_hal::Async>>> = StaticCell::new();
+    /// let i2c = I2c::new(peripherals.I2C0, I2cConfig::default())
+    ///     .unwrap()
+    ///     .with_sda(peripherals.GPIO8)
+    ///     .with_scl(peripherals.GPIO9)
+    ///     .into_async();
+    /// let bus = BUS.init(Mutex::new(i2c));
+    /// 
+    /// let mut sensor = BME280::new(I2cDevice::from_hal(SharedDevice::new(bus), 0x76));
+    /// let mut display = Ssd1306::new(SharedDevice::new(bus)); // another driver on the same wires
+    /// sensor.init().await?;
+    /// ```
+    pub fn from_hal(i2c: T, address: u8) -> Self {
+        Self { i2c, address, _bus: PhantomData }
+    }
+
+    /// Returns the underlying bus handle
+    pub fn release(self) -> T {
+        self.i2c
     }
 
     /// Changes the I2C address for this device
@@ -99,8 +231,8 @@ impl<'a> I2cDevice<'a> {
     /// ```
     pub async fn read_register(&mut self, register: u8) -> Result<u8, IoTError> {
         let mut data = [0u8; 1];
-        self.i2c
-            .write_read(self.address, &[register], &mut data)
+        self.transfer(&[register], &mut data)
+            .await
             .map_err(|_| IoTError::sensor(iot_common::SensorError::I2CError(error_message("I2C read register failed"))))?;
         Ok(data[0])
     }
@@ -130,8 +262,8 @@ impl<'a> I2cDevice<'a> {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub async fn write_register(&mut self, register: u8, value: u8) -> Result<(), IoTError> {
-        self.i2c
-            .write(self.address, &[register, value])
+        self.transfer(&[register, value], &mut [])
+            .await
             .map_err(|_| IoTError::sensor(iot_common::SensorError::I2CError(error_message("I2C write register failed"))))
     }
 
@@ -167,8 +299,8 @@ impl<'a> I2cDevice<'a> {
     /// - The device must support sequential register reading
     /// - BME280 supports this for all calibration and data registers
     pub async fn read_registers(&mut self, start_register: u8, buffer: &mut [u8]) -> Result<(), IoTError> {
-        self.i2c
-            .write_read(self.address, &[start_register], buffer)
+        self.transfer(&[start_register], buffer)
+            .await
             .map_err(|_| IoTError::sensor(iot_common::SensorError::I2CError(error_message("I2C read registers failed"))))
     }
 
@@ -208,9 +340,36 @@ impl<'a> I2cDevice<'a> {
     /// - The transaction is minimal to avoid side effects
     /// - Some devices may respond differently to ping operations
     pub async fn ping(&mut self) -> Result<bool, IoTError> {
-        match self.i2c.transaction(self.address, &mut []) {
+        match self.transfer(&[], &mut []).await {
             Ok(_) => Ok(true),
             Err(_) => Ok(false), // Device not responding, but not necessarily an error
         }
     }
+
+    /// Writes `write` then, if `read` is not empty, reads into it in one transaction
+    /// 
+    /// An empty `write` and `read` is an address-only probe.
+    async fn transfer(&mut self, write: &[u8], read: &mut [u8]) -> Result<(), T::Error> {
+        if write.is_empty() && read.is_empty() {
+            self.i2c.transaction(self.address, &mut []).await
+        } else if read.is_empty() {
+            self.i2c.write(self.address, write).await
+        } else {
+            self.i2c.write_read(self.address, write, read).await
+        }
+    }
+}
+
+impl<T: ErrorType> ErrorType for I2cDevice<'_, T> {
+    type Error = T::Error;
+}
+
+/// Passes transactions through to the underlying bus, at the address given
+/// 
+/// Lets drivers for other chips share the bus this device sits on; the device's
+/// own [`address`](I2cDevice::get_address) is not involved.
+impl<T: AsyncI2c> AsyncI2c for I2cDevice<'_, T> {
+    async fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Self::Error> {
+        self.i2c.transaction(address, operations).await
+    }
 }
\ No newline at end of file
@@ -8,9 +8,15 @@
 //!
 //! - **Async/Await Support**: All I/O operations are non-blocking using Embassy
 //! - **Automatic Calibration**: Reads and applies sensor calibration coefficients
-//! - **Dual Address Support**: Supports both 0x76 and 0x77 I2C addresses
+//! - **Dual Address Support**: `BME280::new_auto` detects 0x76 or 0x77
+//! - **Shared Bus**: `I2cDevice::shared` coexists with other drivers on one I2C bus
+//! - **embedded-hal**: `I2cDevice::from_hal` runs on any `embedded_hal_async::i2c::I2c`, and `I2cDevice` implements it
 //! - **Accurate Compensation**: Uses official BME280 algorithms for data compensation
 //! - **Error Handling**: Comprehensive error handling with context preservation
+//! - **Field Calibration**: `set_offsets` corrects per-unit temperature, humidity and pressure error
+//! - **Register Dump**: `dump_registers` snapshots the raw register map for bring-up debugging
+//! - **Self Recovery**: `read_with_recovery` retries, soft-resets and re-initializes with backoff
+//! - **Multi-Zone Arrays**: `SensorArray` averages several BME280s and flags failed or outlying zones
 //! - **No-std Compatible**: Works in embedded environments without heap allocation
 //!
 //! ## Quick Start
@@ -73,6 +79,24 @@
 //! - **Supply Voltage**: 1.8V - 3.6V (3.3V recommended)
 //! - **Current Consumption**: 3.4μA @ 1Hz humidity and temperature
 //!
+//! ## Raw Data
+//!
+//! For sensor characterization or custom compensation, the uncompensated ADC
+//! values and the factory calibration are available after `init`:
+//!
+//! ```no_run
+//! sensor.init().await?;
+//!
+//! let (raw_temp, raw_press, raw_hum) = sensor.read_raw_data().await?;
+//! if let Some(calib) = sensor.calibration() {
+//!     println!("T={} P={} H={} (H1={}, H2={})", raw_temp, raw_press, raw_hum,
+//!              calib.dig_h1, calib.dig_h2);
+//! }
+//! ```
+//!
+//! Temperature and pressure are 20-bit values and humidity is 16-bit; `0x80000`
+//! and `0x8000` respectively mark a skipped measurement.
+//!
 //! ## Integration with IoT Common
 //!
 //! This driver integrates with the unified error handling system:
@@ -94,7 +118,10 @@
 
 mod i2c_device;
 mod bme280;
+mod array;
 
 // Re-export types that should be accessible to users
-pub use bme280::{BME280, Measurements, CalibrationData};
-pub use i2c_device::I2cDevice;
\ No newline at end of file
+pub use bme280::{BME280, Measurements, RegisterDump, CalibrationData, SensorOffsets, RecoveryPolicy, RecoveryAction, BME280_I2C_ADDR_PRIMARY, BME280_I2C_ADDR_SECONDARY, BME280_RESET_REG, BME280_SOFT_RESET_CMD};
+pub use array::{SensorArray, ArrayReading, ArrayStats, FieldRange, OutlierLimits, ZoneStatus};
+pub use i2c_device::{I2cDevice, EspI2c};
+pub use iot_hal::SharedI2cBus;
\ No newline at end of file
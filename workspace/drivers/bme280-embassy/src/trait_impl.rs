@@ -12,7 +12,7 @@ use iot_hal::I2cInterface;
 
 // Import the container trait (when iot-container is available)
 #[cfg(feature = "container")]
-use iot_container::traits::{SensorReader, Measurements as ContainerMeasurements};
+use iot_container::traits::{SensorReader, HealthCheck, ComponentHealth, Measurements as ContainerMeasurements};
 
 use crate::bme280::{BME280, Measurements};
 
@@ -366,7 +366,11 @@ where
         }
         
         // Test 2: Validate calibration data
-        let cal_data = self.sensor.get_calibration_debug();
+        let Some(cal_data) = self.sensor.calibration() else {
+            return Err(IoTError::Sensor(
+                iot_common::SensorError::SelfTestFailed("Calibration data not loaded")
+            ));
+        };
         
         // Check for obviously invalid calibration values (all zeros or all 0xFF)
         if cal_data.dig_t1 == 0 || cal_data.dig_t1 == 0xFFFF {
@@ -418,6 +422,25 @@ where
     }
 }
 
+#[cfg(feature = "container")]
+impl<'a, I2C> HealthCheck for BME280ContainerAdapter<'a, I2C>
+where
+    I2C: I2cInterface,
+{
+    /// Reports sensor health from the cached initialization and availability state
+    fn health(&self) -> ComponentHealth {
+        if !self.initialized {
+            ComponentHealth::failed("BME280 not initialized")
+        } else if !self.available {
+            ComponentHealth::failed("BME280 not responding")
+        } else if self.last_measurement_time.is_none() {
+            ComponentHealth::degraded("No measurement yet")
+        } else {
+            ComponentHealth::healthy("BME280 reading")
+        }
+    }
+}
+
 // Convenience functions for creating container-compatible BME280 instances
 
 /// Creates a new BME280 sensor adapter for use with the IoT container
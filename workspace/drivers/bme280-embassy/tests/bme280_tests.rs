@@ -526,4 +526,40 @@ fn test_integration_readiness() {
     assert!(reading.humidity.is_finite());
     assert!(reading.pressure.is_finite());
     assert!(reading.timestamp > 0);
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn test_soft_reset_sequence() {
+    use bme280_embassy::{BME280, I2cDevice, BME280_RESET_REG, BME280_SOFT_RESET_CMD};
+    use iot_common::network::block_on;
+    use iot_common::testing::MockI2c;
+
+    // Datasheet: writing 0xB6 to 0xE0 triggers a power-on reset
+    assert_eq!(BME280_RESET_REG, 0xE0);
+    assert_eq!(BME280_SOFT_RESET_CMD, 0xB6);
+
+    let mut calib_tp = [0u8; 24];
+    calib_tp[0] = 0x70; // dig_T1 = 0x6B70
+    calib_tp[1] = 0x6B;
+
+    let mut mock = MockI2c::new();
+    mock.expect_write_register(BME280_RESET_REG, BME280_SOFT_RESET_CMD).unwrap();
+    mock.expect_read_register(0xF3, 0x01).unwrap(); // im_update still set on the first poll
+    mock.expect_read_registers(0x88, &calib_tp).unwrap(); // T/P calibration
+    mock.expect_read_register(0xA1, 0x4B).unwrap(); // H1
+    mock.expect_read_registers(0xE1, &[0u8; 7]).unwrap(); // H2-H6
+
+    let mut sensor = BME280::new(I2cDevice::from_hal(&mut mock, 0x76));
+    block_on(sensor.soft_reset()).expect("soft reset should succeed");
+
+    // Calibration is reloaded from the freshly copied NVM image
+    let calibration = sensor.calibration().expect("calibration should be reloaded");
+    assert_eq!(calibration.dig_t1, 0x6B70);
+    assert_eq!(calibration.dig_h1, 0x4B);
+    drop(sensor);
+
+    // Reset write, two status polls (busy, then clear) and three calibration reads
+    mock.verify().unwrap();
+    assert_eq!(mock.call_count(), 6);
+}
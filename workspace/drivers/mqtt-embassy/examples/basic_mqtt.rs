@@ -13,7 +13,7 @@ use embassy_executor::Spawner;
 use embassy_time::{Duration, Timer};
 use embassy_net::tcp::TcpSocket;
 
-use wifi_embassy::{WiFiManager, WiFiConfig};
+use wifi_embassy::{WiFiManager, WiFiConfig, WiFiTimeouts};
 use mqtt_embassy::{MqttClient, MqttConfig, SensorData};
 
 #[embassy_executor::task]
@@ -109,6 +109,7 @@ async fn main(spawner: Spawner) -> ! {
     let wifi_config = WiFiConfig {
         ssid: env!("WIFI_SSID"),
         password: env!("WIFI_PASSWORD"),
+        timeouts: WiFiTimeouts::default(),
     };
 
     rprintln!("📡 Target SSID: {}", wifi_config.ssid);
@@ -81,8 +81,9 @@ async fn mqtt_test_task(stack: &'static Stack<'static>) {
     let mqtt_config = MqttConfig::default();
     let client = MqttClient::new(mqtt_config);
     
-    rprintln!("MQTT Embassy Test: Connecting to MQTT broker at {}:{}...", 
-             mqtt_config.broker_ip, mqtt_config.broker_port);
+    if let Some(broker) = client.active_broker() {
+        rprintln!("MQTT Embassy Test: Connecting to MQTT broker at {}...", broker);
+    }
     
     // Create buffers for socket
     let mut rx_buffer = [0u8; 1024];
@@ -26,7 +26,7 @@ use rtt_target::{rprintln, rtt_init_print};
 use embassy_executor::Spawner;
 use embassy_time::{Duration, Timer};
 
-use wifi_embassy::{WiFiManager, WiFiConfig};
+use wifi_embassy::{WiFiManager, WiFiConfig, WiFiTimeouts};
 use mqtt_embassy::{MqttClient, MqttConfig, SensorData, DeviceStatus};
 
 // Environment variables from .cargo/config.toml
@@ -53,12 +53,11 @@ async fn mqtt_publishing_task(wifi_manager: &'static mut WiFiManager) {
     
     // Initialize MQTT client with environment variables
     let mqtt_config = MqttConfig::default();
-    let broker_ip = mqtt_config.broker_ip;
-    let broker_port = mqtt_config.broker_port;
     let client = MqttClient::new(mqtt_config);
     
-    rprintln!("[MQTT-TEST] Connecting to MQTT broker at {}:{}...", 
-             broker_ip, broker_port);
+    if let Some(broker) = client.active_broker() {
+        rprintln!("[MQTT-TEST] Connecting to MQTT broker at {}...", broker);
+    }
     
     // Main MQTT publishing loop
     let mut reading_counter = 0;
@@ -162,6 +161,7 @@ async fn main(spawner: Spawner) {
     let wifi_config = WiFiConfig {
         ssid: WIFI_SSID,
         password: WIFI_PASSWORD,
+        timeouts: WiFiTimeouts::default(),
     };
 
     rprintln!("🔧 Hardware initialized, starting WiFi connection...");
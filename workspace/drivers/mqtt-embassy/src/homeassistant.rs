@@ -0,0 +1,163 @@
+//! Home Assistant MQTT discovery
+//!
+//! Publishes retained config messages on
+//! `homeassistant/sensor/<device_id>/<metric>/config` so the BME280 metrics show
+//! up in Home Assistant without manual YAML. Each entity reads its value from the
+//! regular sensor JSON topic through a `value_template`, so no extra state
+//! messages are needed.
+//!
+//! Publish once per boot, after the first successful connect; the retained
+//! messages survive broker restarts.
+
+use embedded_io_async::Write;
+use heapless::String;
+use rtt_target::rprintln;
+use serde::Serialize;
+
+use crate::message::{MqttMessage, SensorDataFormat, Topic};
+use crate::mqtt_client::{MqttClient, MqttError};
+
+/// Discovery prefix configured in Home Assistant (its default)
+pub const DISCOVERY_PREFIX: &str = "homeassistant";
+
+/// Maximum size of one discovery payload
+const DISCOVERY_PAYLOAD_SIZE: usize = 512;
+
+/// Description of one metric exposed to Home Assistant
+struct Metric {
+    /// Stable object ID in the discovery topic and `unique_id`
+    id: &'static str,
+    /// JSON key of the value in sensor payloads
+    key: fn(&SensorDataFormat) -> &'static str,
+    name: &'static str,
+    unit: &'static str,
+    device_class: &'static str,
+}
+
+/// BME280 metrics, keys follow [`MqttConfig::sensor_data_format`](crate::MqttConfig::sensor_data_format)
+const BME280_METRICS: [Metric; 3] = [
+    Metric { id: "temperature", key: |format| format.temperature, name: "Temperature", unit: "°C", device_class: "temperature" },
+    Metric { id: "humidity", key: |format| format.humidity, name: "Humidity", unit: "%", device_class: "humidity" },
+    Metric { id: "pressure", key: |format| format.pressure, name: "Pressure", unit: "hPa", device_class: "atmospheric_pressure" },
+];
+
+/// Device block shared by all entities of this board
+#[derive(Serialize)]
+struct DiscoveryDevice<'a> {
+    identifiers: [&'a str; 1],
+    name: &'a str,
+    model: &'static str,
+    manufacturer: &'static str,
+}
+
+/// Sensor entity config message
+#[derive(Serialize)]
+struct DiscoveryConfig<'a> {
+    name: &'static str,
+    unique_id: &'a str,
+    state_topic: &'a str,
+    value_template: &'a str,
+    unit_of_measurement: &'static str,
+    device_class: &'static str,
+    state_class: &'static str,
+    device: DiscoveryDevice<'a>,
+}
+
+impl MqttClient {
+    /// Publish retained Home Assistant discovery configs for the BME280 metrics
+    /// 
+    /// Entities point at the sensor data topic (`publish_sensor_data`), so the
+    /// regular readings feed them directly.
+    pub async fn publish_discovery<S: Write>(&self, socket: &mut S, device_id: &str) -> Result<(), MqttError> {
+        let state_topic = self.topic("sensor/bme280")?;
+        let mut buffer = [0u8; DISCOVERY_PAYLOAD_SIZE];
+        
+        for metric in BME280_METRICS.iter() {
+            let (topic, len) = self.discovery_config(metric, device_id, &state_topic, &mut buffer)?;
+            let message = MqttMessage::new(&topic, &buffer[..len]).with_retain(true);
+            self.publish(socket, &message).await?;
+        }
+        
+        rprintln!("[MQTT] Home Assistant discovery published for '{}'", device_id);
+        Ok(())
+    }
+    
+    /// Serialize the config of one metric into `buffer`
+    /// 
+    /// Returns the discovery topic and the payload length. The value template
+    /// reads the key the sensor payloads are published with.
+    fn discovery_config(
+        &self,
+        metric: &Metric,
+        device_id: &str,
+        state_topic: &str,
+        buffer: &mut [u8],
+    ) -> Result<(Topic, usize), MqttError> {
+        let mut topic = Topic::new();
+        let mut unique_id: String<64> = String::new();
+        let mut value_template: String<48> = String::new();
+        core::fmt::Write::write_fmt(&mut topic, format_args!(
+            "{}/sensor/{}/{}/config", DISCOVERY_PREFIX, device_id, metric.id
+        )).map_err(|_| MqttError::SerializationError("Discovery topic too long"))?;
+        core::fmt::Write::write_fmt(&mut unique_id, format_args!("{}_{}", device_id, metric.id))
+            .map_err(|_| MqttError::SerializationError("Device ID too long"))?;
+        core::fmt::Write::write_fmt(&mut value_template, format_args!(
+            "{{{{ value_json.{} }}}}", (metric.key)(self.sensor_data_format())
+        )).map_err(|_| MqttError::SerializationError("Value template too long"))?;
+        
+        let config = DiscoveryConfig {
+            name: metric.name,
+            unique_id: &unique_id,
+            state_topic,
+            value_template: &value_template,
+            unit_of_measurement: metric.unit,
+            device_class: metric.device_class,
+            state_class: "measurement",
+            device: DiscoveryDevice {
+                identifiers: [device_id],
+                name: device_id,
+                model: "ESP32-C3 BME280",
+                manufacturer: "TI0162",
+            },
+        };
+        let len = serde_json_core::to_slice(&config, buffer)
+            .map_err(|_| MqttError::SerializationError("Discovery payload too large"))?;
+        Ok((topic, len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MqttConfig;
+    
+    #[test]
+    fn test_value_template_follows_sensor_data_format() {
+        let format = SensorDataFormat::new("temp_c", "rh", "hpa");
+        let client = MqttClient::new(MqttConfig::default().with_sensor_data_format(format).unwrap());
+        let state_topic = client.topic("sensor/bme280").unwrap();
+        let mut buffer = [0u8; DISCOVERY_PAYLOAD_SIZE];
+        
+        for (metric, key) in BME280_METRICS.iter().zip(["temp_c", "rh", "hpa"]) {
+            let (topic, len) = client.discovery_config(metric, "node-1", &state_topic, &mut buffer).unwrap();
+            let payload = core::str::from_utf8(&buffer[..len]).unwrap();
+            
+            let template = alloc::format!("\"value_template\":\"{{{{ value_json.{} }}}}\"", key);
+            assert!(payload.contains(&template), "{}", payload);
+            // Entity IDs stay put when the payload keys change
+            assert_eq!(topic.as_str(), alloc::format!("homeassistant/sensor/node-1/{}/config", metric.id));
+            assert!(payload.contains(&alloc::format!("\"unique_id\":\"node-1_{}\"", metric.id)));
+        }
+    }
+    
+    #[test]
+    fn test_value_template_default_keys() {
+        let client = MqttClient::new(MqttConfig::default());
+        let state_topic = client.topic("sensor/bme280").unwrap();
+        let mut buffer = [0u8; DISCOVERY_PAYLOAD_SIZE];
+        
+        let (_, len) = client.discovery_config(&BME280_METRICS[2], "node-1", &state_topic, &mut buffer).unwrap();
+        let payload = core::str::from_utf8(&buffer[..len]).unwrap();
+        assert!(payload.contains("\"value_template\":\"{{ value_json.pressure }}\""), "{}", payload);
+    }
+}
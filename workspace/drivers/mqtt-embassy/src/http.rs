@@ -0,0 +1,595 @@
+//! HTTP/1.1 client for posting data to REST endpoints
+//!
+//! Parallels the MQTT publish path for deployments that collect readings
+//! through a webhook instead of a broker. [`HttpClient::post`] opens a TCP
+//! connection through the embassy-net stack, sends one request with
+//! `Connection: close` and returns the status code and (possibly truncated)
+//! response body. Both `Content-Length` and chunked responses are decoded.
+//!
+//! Only plain `http://` URLs with an IPv4 host are supported: the network
+//! stack has no DNS and TLS is not implemented for HTTP yet. The URL and
+//! response-head parsers are public for callers that run their own transport
+//! and stream the body, such as firmware downloads.
+//!
+//! ```rust,ignore
+//! use mqtt_embassy::http::{HttpClient, HttpConfig};
+//!
+//! let client = HttpClient::new(stack, HttpConfig::default());
+//! let json = sensor_data.to_json()?;
+//! let response = client.post_json("http://10.10.10.210:8080/api/readings", &json).await?;
+//! if !response.is_success() {
+//!     rprintln!("[HTTP] Webhook returned {}", response.status);
+//! }
+//! ```
+
+use core::fmt::Write as _;
+use core::net::Ipv4Addr;
+use embassy_net::Stack;
+use embassy_net::tcp::TcpSocket;
+use embassy_time::{Duration, with_timeout};
+use embedded_io_async::Write;
+use heapless::{String, Vec};
+use rtt_target::rprintln;
+
+use crate::message::{SensorData, SensorDataFormat};
+
+/// Default port for `http://` URLs without an explicit port
+pub const HTTP_PORT: u16 = 80;
+
+/// Default port for `https://` URLs without an explicit port
+pub const HTTPS_PORT: u16 = 443;
+
+/// TCP receive and transmit buffer size of the request socket
+pub const HTTP_SOCKET_BUFFER_SIZE: usize = 1024;
+
+/// Maximum size of the request line plus headers
+pub const MAX_REQUEST_HEAD: usize = 512;
+
+/// Maximum raw response (status line, headers and body) kept in memory
+pub const RESPONSE_BUFFER_SIZE: usize = 1024;
+
+/// Maximum decoded response body; longer bodies are truncated
+pub const MAX_RESPONSE_BODY: usize = 512;
+
+/// HTTP client configuration
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    /// Time allowed for the whole exchange (connect, send and receive)
+    pub timeout: Duration,
+    /// Value of the `User-Agent` request header
+    pub user_agent: &'static str,
+    /// Field names and units of posted sensor readings
+    pub sensor_data_format: SensorDataFormat,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        let timeout_secs = option_env!("HTTP_TIMEOUT_SECS")
+            .and_then(|secs| secs.parse::<u64>().ok())
+            .unwrap_or(10);
+
+        Self {
+            timeout: Duration::from_secs(timeout_secs),
+            user_agent: "esp32-c3-iot/0.1",
+            sensor_data_format: SensorDataFormat::default(),
+        }
+    }
+}
+
+/// HTTP errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpError {
+    /// URL is malformed or its host is not an IPv4 address
+    InvalidUrl(&'static str),
+    /// `https://` URL given where only `http://` is supported
+    TlsUnsupported,
+    /// TCP connection to the server failed
+    ConnectionFailed,
+    /// Request line and headers exceed [`MAX_REQUEST_HEAD`]
+    RequestTooLarge,
+    /// Send/receive error
+    IoError(&'static str),
+    /// Exchange did not finish within [`HttpConfig::timeout`]
+    Timeout,
+    /// Response is not valid HTTP/1.x
+    InvalidResponse(&'static str),
+    /// Request body could not be serialized
+    SerializationError(&'static str),
+}
+
+impl core::fmt::Display for HttpError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            HttpError::InvalidUrl(msg) => write!(f, "Invalid URL: {}", msg),
+            HttpError::TlsUnsupported => write!(f, "HTTPS is not supported"),
+            HttpError::ConnectionFailed => write!(f, "TCP connection failed"),
+            HttpError::RequestTooLarge => write!(f, "Request headers too large"),
+            HttpError::IoError(msg) => write!(f, "I/O error: {}", msg),
+            HttpError::Timeout => write!(f, "HTTP server did not respond in time"),
+            HttpError::InvalidResponse(msg) => write!(f, "Invalid HTTP response: {}", msg),
+            HttpError::SerializationError(msg) => write!(f, "Serialization error: {}", msg),
+        }
+    }
+}
+
+/// Parsed `http[s]://host[:port]/path` URL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HttpUrl<'a> {
+    /// `https://`, only accepted by [`parse_url_with_tls`]
+    pub tls: bool,
+    /// `host[:port]` as written in the URL, sent as the `Host` header
+    pub authority: &'a str,
+    pub host: Ipv4Addr,
+    pub port: u16,
+    /// Path including any query string, `/` when the URL has none
+    pub path: &'a str,
+}
+
+/// Response returned by the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    /// Status code from the status line
+    pub status: u16,
+    /// Decoded body, at most [`MAX_RESPONSE_BODY`] bytes
+    pub body: Vec<u8, MAX_RESPONSE_BODY>,
+    /// Body was cut short by the buffer limits or a closed connection
+    pub truncated: bool,
+}
+
+impl HttpResponse {
+    /// Whether the status code is 2xx
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Body as text, `None` if it is not valid UTF-8
+    pub fn body_str(&self) -> Option<&str> {
+        core::str::from_utf8(&self.body).ok()
+    }
+}
+
+/// Status line and body framing of a response
+///
+/// For callers that stream the body themselves; [`parse_response`] builds on
+/// it for the buffered client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponseHead {
+    /// Status code from the status line
+    pub status: u16,
+    /// `Content-Length`, if sent
+    pub content_length: Option<usize>,
+    /// `Content-Range` of a partial (`206`) or unsatisfiable (`416`) response
+    pub content_range: Option<ContentRange>,
+    /// Body uses chunked transfer encoding
+    pub chunked: bool,
+}
+
+/// Value of a `Content-Range: bytes <first>-<last>/<total>` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentRange {
+    /// First and last byte of the body within the resource, `None` for `*`
+    pub range: Option<(usize, usize)>,
+    /// Size of the complete resource, `None` for `*`
+    pub total: Option<usize>,
+}
+
+/// Splits an `http://` URL into its address and path
+pub fn parse_url(url: &str) -> Result<HttpUrl<'_>, HttpError> {
+    split_url(url, false)
+}
+
+/// Splits an `http://` or `https://` URL into its address and path
+///
+/// For callers that bring their own TLS transport; [`HttpUrl::tls`] tells
+/// which scheme was given.
+pub fn parse_url_with_tls(url: &str) -> Result<HttpUrl<'_>, HttpError> {
+    split_url(url, true)
+}
+
+fn split_url(url: &str, allow_tls: bool) -> Result<HttpUrl<'_>, HttpError> {
+    let (rest, tls) = match url.strip_prefix("http://") {
+        Some(rest) => (rest, false),
+        None => match url.strip_prefix("https://") {
+            Some(rest) if allow_tls => (rest, true),
+            Some(_) => return Err(HttpError::TlsUnsupported),
+            None if allow_tls => return Err(HttpError::InvalidUrl("scheme must be http:// or https://")),
+            None => return Err(HttpError::InvalidUrl("scheme must be http://")),
+        },
+    };
+
+    let (authority, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+
+    let (host, port) = match authority.split_once(':') {
+        Some((host, port)) => {
+            let port = port.parse::<u16>()
+                .ok()
+                .filter(|&port| port != 0)
+                .ok_or(HttpError::InvalidUrl("invalid port"))?;
+            (host, port)
+        }
+        None => (authority, if tls { HTTPS_PORT } else { HTTP_PORT }),
+    };
+
+    // No DNS in the network stack, so the host must be an address
+    let host = host.parse::<Ipv4Addr>()
+        .map_err(|_| HttpError::InvalidUrl("host must be an IPv4 address"))?;
+
+    Ok(HttpUrl { tls, authority, host, port, path })
+}
+
+/// Extracts the status code from an `HTTP/1.x <code> <reason>` line
+pub fn parse_status_line(line: &str) -> Result<u16, HttpError> {
+    let mut parts = line.splitn(3, ' ');
+
+    if !parts.next().is_some_and(|version| version.starts_with("HTTP/1.")) {
+        return Err(HttpError::InvalidResponse("not an HTTP/1.x status line"));
+    }
+
+    parts.next()
+        .filter(|code| code.len() == 3)
+        .and_then(|code| code.parse::<u16>().ok())
+        .filter(|code| (100..600).contains(code))
+        .ok_or(HttpError::InvalidResponse("invalid status code"))
+}
+
+/// Length of the status line and headers, including the blank line after them
+///
+/// `None` until the blank line has been received.
+pub fn response_head_len(raw: &[u8]) -> Option<usize> {
+    find(raw, b"\r\n\r\n").map(|end| end + 4)
+}
+
+/// Parses the status line and headers of a response
+///
+/// `head` may include the blank line that ends it, see [`response_head_len`].
+pub fn parse_response_head(head: &str) -> Result<ResponseHead, HttpError> {
+    let mut lines = head.split("\r\n");
+    let status = parse_status_line(lines.next().unwrap_or(""))?;
+
+    let mut response = ResponseHead { status, content_length: None, content_range: None, chunked: false };
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else { continue };
+        let (name, value) = (name.trim(), value.trim());
+        if name.eq_ignore_ascii_case("content-length") {
+            response.content_length = Some(value.parse::<usize>()
+                .map_err(|_| HttpError::InvalidResponse("invalid Content-Length"))?);
+        } else if name.eq_ignore_ascii_case("content-range") {
+            response.content_range = Some(parse_content_range(value)
+                .ok_or(HttpError::InvalidResponse("invalid Content-Range"))?);
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            // Chunked is always the last coding applied
+            response.chunked = value.rsplit(',').next().is_some_and(|coding| coding.trim().eq_ignore_ascii_case("chunked"));
+        }
+    }
+    Ok(response)
+}
+
+/// Parses a `Content-Range` value such as `bytes 100-199/1000` or `bytes */1000`
+pub fn parse_content_range(value: &str) -> Option<ContentRange> {
+    let (range, total) = value.trim().strip_prefix("bytes ")?.split_once('/')?;
+    let total = match total.trim() {
+        "*" => None,
+        total => Some(total.parse::<usize>().ok()?),
+    };
+    let range = match range.trim() {
+        "*" => None,
+        range => {
+            let (first, last) = range.split_once('-')?;
+            let (first, last) = (first.parse::<usize>().ok()?, last.parse::<usize>().ok()?);
+            if last < first || total.is_some_and(|total| last >= total) {
+                return None;
+            }
+            Some((first, last))
+        }
+    };
+    // `bytes */*` says nothing
+    if range.is_none() && total.is_none() {
+        return None;
+    }
+    Some(ContentRange { range, total })
+}
+
+/// Parses a raw response received so far
+///
+/// Returns `Ok(None)` while more data is needed. Once `closed` is set (the
+/// server closed the connection or the receive buffer is full) the response
+/// is returned as is, with [`HttpResponse::truncated`] set if the body is
+/// shorter than announced.
+pub fn parse_response(raw: &[u8], closed: bool) -> Result<Option<HttpResponse>, HttpError> {
+    let Some(head_len) = response_head_len(raw) else {
+        return if closed {
+            Err(HttpError::InvalidResponse("incomplete headers"))
+        } else {
+            Ok(None)
+        };
+    };
+
+    let head = core::str::from_utf8(&raw[..head_len])
+        .map_err(|_| HttpError::InvalidResponse("headers are not UTF-8"))?;
+    let ResponseHead { status, content_length, chunked, .. } = parse_response_head(head)?;
+
+    let mut response = HttpResponse { status, body: Vec::new(), truncated: false };
+    let payload = &raw[head_len..];
+
+    // 1xx, 204 and 304 responses never carry a body
+    if status < 200 || status == 204 || status == 304 {
+        return Ok(Some(response));
+    }
+
+    if chunked {
+        let finished = decode_chunked(payload, &mut response)?;
+        if !finished {
+            if !closed {
+                return Ok(None);
+            }
+            response.truncated = true;
+        }
+    } else if let Some(length) = content_length {
+        if payload.len() < length && !closed {
+            return Ok(None);
+        }
+        let available = length.min(payload.len());
+        append_body(&mut response, &payload[..available]);
+        response.truncated |= available < length;
+    } else {
+        // Body runs until the server closes the connection
+        if !closed {
+            return Ok(None);
+        }
+        append_body(&mut response, payload);
+    }
+
+    Ok(Some(response))
+}
+
+/// Decodes a chunked body into `response`
+///
+/// Returns `true` once the terminating zero-size chunk has been seen; chunk
+/// extensions and trailers are ignored.
+fn decode_chunked(mut data: &[u8], response: &mut HttpResponse) -> Result<bool, HttpError> {
+    loop {
+        let Some(line_end) = find(data, b"\r\n") else { return Ok(false) };
+        let size_line = core::str::from_utf8(&data[..line_end])
+            .map_err(|_| HttpError::InvalidResponse("invalid chunk size"))?;
+        let size_hex = size_line.split(';').next().unwrap_or("").trim();
+        let size = usize::from_str_radix(size_hex, 16)
+            .map_err(|_| HttpError::InvalidResponse("invalid chunk size"))?;
+        data = &data[line_end + 2..];
+
+        if size == 0 {
+            return Ok(true);
+        }
+
+        append_body(response, &data[..size.min(data.len())]);
+        if data.len() < size + 2 {
+            return Ok(false);
+        }
+        if &data[size..size + 2] != b"\r\n" {
+            return Err(HttpError::InvalidResponse("missing chunk terminator"));
+        }
+        data = &data[size + 2..];
+    }
+}
+
+/// Appends to the body, marking the response truncated once it is full
+fn append_body(response: &mut HttpResponse, data: &[u8]) {
+    let space = MAX_RESPONSE_BODY - response.body.len();
+    let take = data.len().min(space);
+    // Cannot fail: `take` is bounded by the remaining capacity
+    let _ = response.body.extend_from_slice(&data[..take]);
+    response.truncated |= take < data.len();
+}
+
+/// Position of the first occurrence of `needle` in `haystack`
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// HTTP client posting to REST endpoints over the embassy-net stack
+pub struct HttpClient<'d> {
+    stack: Stack<'d>,
+    config: HttpConfig,
+}
+
+impl<'d> HttpClient<'d> {
+    /// Creates a client on the given network stack
+    pub fn new(stack: Stack<'d>, config: HttpConfig) -> Self {
+        Self { stack, config }
+    }
+
+    /// Gets the client configuration
+    pub fn config(&self) -> &HttpConfig {
+        &self.config
+    }
+
+    /// Sends a `POST` request and waits for the response
+    ///
+    /// `Host`, `User-Agent`, `Content-Length` and `Connection: close` are
+    /// always sent; `headers` adds to them (e.g. `Content-Type` or an API
+    /// key). The whole exchange must finish within [`HttpConfig::timeout`].
+    pub async fn post(&self, url: &str, body: &[u8], headers: &[(&str, &str)]) -> Result<HttpResponse, HttpError> {
+        let url = parse_url(url)?;
+        let head = self.request_head("POST", &url, body.len(), headers)?;
+
+        with_timeout(self.config.timeout, self.exchange(&url, head.as_bytes(), body))
+            .await
+            .map_err(|_| HttpError::Timeout)?
+    }
+
+    /// Posts a JSON document with `Content-Type: application/json`
+    pub async fn post_json(&self, url: &str, json: &str) -> Result<HttpResponse, HttpError> {
+        self.post(url, json.as_bytes(), &[("Content-Type", "application/json")]).await
+    }
+
+    /// Posts a reading as [`SensorData`] JSON in [`HttpConfig::sensor_data_format`]
+    pub async fn post_sensor_data(&self, url: &str, sensor_data: &SensorData) -> Result<HttpResponse, HttpError> {
+        let json = sensor_data.to_json_with(&self.config.sensor_data_format)
+            .map_err(HttpError::SerializationError)?;
+        self.post_json(url, &json).await
+    }
+
+    /// Builds the request line and headers
+    fn request_head(&self, method: &str, url: &HttpUrl<'_>, content_length: usize, headers: &[(&str, &str)]) -> Result<String<MAX_REQUEST_HEAD>, HttpError> {
+        let mut head = String::new();
+        write!(head, "{} {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+               method, url.path, url.authority, self.config.user_agent, content_length)
+            .map_err(|_| HttpError::RequestTooLarge)?;
+        for (name, value) in headers {
+            write!(head, "{}: {}\r\n", name, value).map_err(|_| HttpError::RequestTooLarge)?;
+        }
+        head.push_str("\r\n").map_err(|_| HttpError::RequestTooLarge)?;
+        Ok(head)
+    }
+
+    /// Connects, sends the request and reads the response
+    async fn exchange(&self, url: &HttpUrl<'_>, head: &[u8], body: &[u8]) -> Result<HttpResponse, HttpError> {
+        let mut rx_buffer = [0u8; HTTP_SOCKET_BUFFER_SIZE];
+        let mut tx_buffer = [0u8; HTTP_SOCKET_BUFFER_SIZE];
+        let mut socket = TcpSocket::new(self.stack, &mut rx_buffer, &mut tx_buffer);
+
+        socket.connect((url.host, url.port)).await
+            .map_err(|_| HttpError::ConnectionFailed)?;
+        rprintln!("[HTTP] Connected to {}:{}", url.host, url.port);
+
+        socket.write_all(head).await
+            .map_err(|_| HttpError::IoError("Failed to send request headers"))?;
+        socket.write_all(body).await
+            .map_err(|_| HttpError::IoError("Failed to send request body"))?;
+        socket.flush().await
+            .map_err(|_| HttpError::IoError("Failed to send request"))?;
+
+        let mut raw = [0u8; RESPONSE_BUFFER_SIZE];
+        let mut len = 0;
+        let response = loop {
+            let n = if len < raw.len() {
+                socket.read(&mut raw[len..]).await
+                    .map_err(|_| HttpError::IoError("Failed to read response"))?
+            } else {
+                0
+            };
+            len += n;
+
+            // A zero-length read means the server closed or the buffer is full
+            if let Some(response) = parse_response(&raw[..len], n == 0)? {
+                break response;
+            }
+        };
+
+        socket.close();
+        rprintln!("[HTTP] {} response, {} body bytes{}", response.status, response.body.len(),
+                 if response.truncated { " (truncated)" } else { "" });
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_url() {
+        let url = parse_url("http://10.10.10.210:8080/api/readings?device=esp32").unwrap();
+        assert_eq!(url.host, Ipv4Addr::new(10, 10, 10, 210));
+        assert_eq!(url.port, 8080);
+        assert_eq!(url.authority, "10.10.10.210:8080");
+        assert_eq!(url.path, "/api/readings?device=esp32");
+
+        let url = parse_url("http://192.168.1.5").unwrap();
+        assert_eq!((url.port, url.path), (HTTP_PORT, "/"));
+
+        assert_eq!(parse_url("https://192.168.1.5/hook"), Err(HttpError::TlsUnsupported));
+        assert!(parse_url("ftp://192.168.1.5/").is_err());
+        assert!(parse_url("http://example.com/hook").is_err());
+        assert!(parse_url("http://192.168.1.5:0/").is_err());
+    }
+
+    #[test]
+    fn test_parse_url_with_tls() {
+        let url = parse_url_with_tls("https://10.0.0.2/fw/main.bin").unwrap();
+        assert!(url.tls);
+        assert_eq!((url.port, url.path), (HTTPS_PORT, "/fw/main.bin"));
+
+        let url = parse_url_with_tls("http://10.0.0.2:8000/main.bin").unwrap();
+        assert!(!url.tls);
+        assert_eq!(url.port, 8000);
+
+        assert!(parse_url_with_tls("ftp://10.0.0.2/main.bin").is_err());
+        assert!(parse_url_with_tls("https://updates.example.com/main.bin").is_err());
+    }
+
+    #[test]
+    fn test_parse_content_range() {
+        assert_eq!(parse_content_range("bytes 100-199/1000"),
+                   Some(ContentRange { range: Some((100, 199)), total: Some(1000) }));
+        assert_eq!(parse_content_range("bytes 0-99/*"), Some(ContentRange { range: Some((0, 99)), total: None }));
+        // Sent with 416 when the requested start is past the end
+        assert_eq!(parse_content_range("bytes */1000"), Some(ContentRange { range: None, total: Some(1000) }));
+
+        assert_eq!(parse_content_range("bytes */*"), None);
+        assert_eq!(parse_content_range("bytes 200-100/1000"), None);
+        assert_eq!(parse_content_range("bytes 0-1000/1000"), None);
+        assert_eq!(parse_content_range("items 0-9/10"), None);
+        assert_eq!(parse_content_range("bytes 0-x/10"), None);
+    }
+
+    #[test]
+    fn test_parse_response_head() {
+        let raw = b"HTTP/1.1 206 Partial Content\r\nContent-Length: 100\r\nContent-Range: bytes 900-999/1000\r\n\r\nbody";
+        let head_len = response_head_len(raw).unwrap();
+        assert_eq!(&raw[head_len..], b"body");
+
+        let head = parse_response_head(core::str::from_utf8(&raw[..head_len]).unwrap()).unwrap();
+        assert_eq!(head, ResponseHead {
+            status: 206,
+            content_length: Some(100),
+            content_range: Some(ContentRange { range: Some((900, 999)), total: Some(1000) }),
+            chunked: false,
+        });
+
+        let head = parse_response_head("HTTP/1.1 200 OK\r\ntransfer-encoding: gzip, chunked").unwrap();
+        assert!(head.chunked);
+        assert_eq!(head.content_length, None);
+
+        assert_eq!(response_head_len(b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n"), None);
+        assert!(parse_response_head("HTTP/1.1 206 Partial Content\r\nContent-Range: bytes 5-1/10").is_err());
+        assert!(parse_response_head("HTTP/1.1 200 OK\r\nContent-Length: lots").is_err());
+    }
+
+    #[test]
+    fn test_parse_status_line() {
+        assert_eq!(parse_status_line("HTTP/1.1 200 OK"), Ok(200));
+        assert_eq!(parse_status_line("HTTP/1.0 404 Not Found"), Ok(404));
+        assert_eq!(parse_status_line("HTTP/1.1 204"), Ok(204));
+        assert!(parse_status_line("HTTP/2 200 OK").is_err());
+        assert!(parse_status_line("HTTP/1.1 20 OK").is_err());
+        assert!(parse_status_line("garbage").is_err());
+    }
+
+    #[test]
+    fn test_parse_content_length_response() {
+        let raw = b"HTTP/1.1 201 Created\r\nContent-Length: 11\r\n\r\n{\"ok\":true}";
+        let response = parse_response(raw, false).unwrap().unwrap();
+        assert_eq!(response.status, 201);
+        assert_eq!(response.body_str(), Some("{\"ok\":true}"));
+        assert!(!response.truncated);
+
+        // Waits for the rest of the body, then marks it truncated on close
+        let partial = &raw[..raw.len() - 4];
+        assert_eq!(parse_response(partial, false), Ok(None));
+        assert!(parse_response(partial, true).unwrap().unwrap().truncated);
+    }
+
+    #[test]
+    fn test_parse_chunked_response() {
+        let raw = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nWiki\r\n6;ext=1\r\npedia \r\nE\r\nin \r\n\r\nchunks.\r\n0\r\n\r\n";
+        let response = parse_response(raw, false).unwrap().unwrap();
+        assert_eq!(response.body_str(), Some("Wikipedia in \r\n\r\nchunks."));
+        assert!(!response.truncated);
+
+        assert_eq!(parse_response(&raw[..60], false), Ok(None));
+        assert!(parse_response(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\nzz\r\n", false).is_err());
+    }
+}
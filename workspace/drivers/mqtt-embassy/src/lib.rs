@@ -5,20 +5,29 @@
 //! MQTT client module using Embassy async framework for ESP32-C3.
 //! 
 //! This module provides async MQTT publishing capabilities with JSON data serialization,
-//! designed to work seamlessly with the wifi-embassy network stack.
+//! designed to work seamlessly with the wifi-embassy network stack. The [`http`]
+//! module posts the same JSON payloads to REST endpoints (webhooks).
 
 extern crate alloc;
 
 pub mod mqtt_client;
 pub mod message;
+pub mod sntp;
+pub mod http;
+
+// Home Assistant discovery (optional feature)
+#[cfg(feature = "homeassistant")]
+pub mod homeassistant;
 
 // IoT Container trait implementation (optional feature)
 #[cfg(feature = "container")]
 mod trait_impl;
 
 // Re-export main types
-pub use mqtt_client::{MqttClient, MqttConfig, MqttError};
-pub use message::{MqttMessage, SensorData, DeviceStatus};
+pub use mqtt_client::{MqttClient, MqttConfig, MqttError, ConnAck, BrokerAddress, SocketMonitor, SocketState, SocketStats, parse_connack, MAX_BATCH_READINGS, MAX_BROKERS, DEFAULT_BROKER_PORT, SENSOR_PAYLOAD_SIZE};
+pub use message::{MqttMessage, SensorData, SensorDataFormat, FormattedSensorData, SliceWriter, DeviceStatus, MAX_LAST_RESET_LEN, Topic, PayloadFormat, StandardMessage, DEFAULT_TOPIC_TEMPLATE, MAX_REMAINING_LENGTH, encode_remaining_length, decode_remaining_length, resolve_topic, validate_topic_template};
+pub use sntp::{SntpClient, SntpConfig, SntpError, SntpTimestampProvider, SyncStatus};
+pub use http::{HttpClient, HttpConfig, HttpError, HttpResponse};
 
 // Re-export container integration when available
 #[cfg(feature = "container")]
@@ -3,8 +3,144 @@
 //! Defines data structures for sensor readings and device status,
 //! with JSON serialization support for MQTT payloads.
 
-use serde::{Deserialize, Serialize};
+use core::fmt::{self, Write};
+
+use serde::{Deserialize, Serialize, Serializer};
+use serde::ser::SerializeStruct;
 use heapless::String;
+use iot_common::{IoTArchitecture, LegacyFormat, StandardSensorReading, StandardTopics, ErrorHistory, ErrorRecord, MAX_ERROR_HISTORY_DEPTH, TimestampProvider};
+
+use crate::mqtt_client::MqttError;
+
+/// Default topic layout, e.g. `esp32/status` or `esp32/sensor/bme280`
+pub const DEFAULT_TOPIC_TEMPLATE: &str = "{prefix}/{metric}";
+
+/// Resolved topic name
+pub type Topic = String<128>;
+
+/// Largest remaining length MQTT 3.1.1 can encode (four length bytes)
+pub const MAX_REMAINING_LENGTH: usize = 268_435_455;
+
+/// Encode the MQTT variable-length "remaining length" field
+/// 
+/// Seven bits per byte, least significant group first, with 0x80 marking a
+/// continuation: 127 is `[0x7F]`, 128 is `[0x80, 0x01]` and 16384 is
+/// `[0x80, 0x80, 0x01]`. Lengths above [`MAX_REMAINING_LENGTH`] are rejected.
+pub fn encode_remaining_length(mut length: usize) -> Result<heapless::Vec<u8, 4>, MqttError> {
+    if length > MAX_REMAINING_LENGTH {
+        return Err(MqttError::ProtocolError("Packet exceeds MQTT maximum size"));
+    }
+    
+    let mut encoded = heapless::Vec::new();
+    loop {
+        let mut byte = (length % 128) as u8;
+        length /= 128;
+        if length > 0 {
+            byte |= 0x80;
+        }
+        // At most four bytes for lengths up to MAX_REMAINING_LENGTH
+        let _ = encoded.push(byte);
+        if length == 0 {
+            return Ok(encoded);
+        }
+    }
+}
+
+/// Decode a remaining-length field at the start of `bytes`
+/// 
+/// Returns the length and the number of bytes the field used.
+pub fn decode_remaining_length(bytes: &[u8]) -> Result<(usize, usize), MqttError> {
+    let mut length = 0usize;
+    for (index, byte) in bytes.iter().take(4).enumerate() {
+        length |= ((byte & 0x7F) as usize) << (7 * index);
+        if byte & 0x80 == 0 {
+            return Ok((length, index + 1));
+        }
+    }
+    if bytes.len() < 4 {
+        Err(MqttError::ProtocolError("Truncated remaining length"))
+    } else {
+        Err(MqttError::ProtocolError("Remaining length longer than four bytes"))
+    }
+}
+
+/// Placeholder found in a topic template
+enum TopicPart<'a> {
+    Literal(&'a str),
+    Prefix,
+    DeviceId,
+    Metric,
+}
+
+/// Split a topic template into literals and placeholders
+///
+/// Calls `emit` for each part in order; stops at the first error.
+fn parse_topic_template<'a>(
+    template: &'a str,
+    mut emit: impl FnMut(TopicPart<'a>) -> Result<(), MqttError>,
+) -> Result<(), MqttError> {
+    if template.contains(['+', '#']) {
+        return Err(MqttError::InvalidTopicTemplate("Wildcards are not allowed in publish topics"));
+    }
+    
+    let mut has_metric = false;
+    let mut rest = template;
+    while let Some(open) = rest.find(['{', '}']) {
+        if rest.as_bytes()[open] == b'}' {
+            return Err(MqttError::InvalidTopicTemplate("Unmatched '}'"));
+        }
+        let close = rest[open..].find('}')
+            .ok_or(MqttError::InvalidTopicTemplate("Unclosed '{'"))? + open;
+        
+        if open > 0 {
+            emit(TopicPart::Literal(&rest[..open]))?;
+        }
+        let part = match &rest[open + 1..close] {
+            "prefix" => TopicPart::Prefix,
+            "device_id" => TopicPart::DeviceId,
+            "metric" => {
+                has_metric = true;
+                TopicPart::Metric
+            }
+            _ => return Err(MqttError::InvalidTopicTemplate(
+                "Unknown placeholder, expected {prefix}, {device_id} or {metric}"
+            )),
+        };
+        emit(part)?;
+        rest = &rest[close + 1..];
+    }
+    if !rest.is_empty() {
+        emit(TopicPart::Literal(rest))?;
+    }
+    
+    if has_metric {
+        Ok(())
+    } else {
+        Err(MqttError::InvalidTopicTemplate("Template must contain {metric}"))
+    }
+}
+
+/// Check a topic template without resolving it
+pub fn validate_topic_template(template: &str) -> Result<(), MqttError> {
+    parse_topic_template(template, |_| Ok(()))
+}
+
+/// Expand a topic template such as `{prefix}/{device_id}/{metric}`
+///
+/// `metric` names what is published (`temperature`, `status`, `sensor/bme280`...).
+pub fn resolve_topic(template: &str, prefix: &str, device_id: &str, metric: &str) -> Result<Topic, MqttError> {
+    let mut topic = Topic::new();
+    parse_topic_template(template, |part| {
+        let text = match part {
+            TopicPart::Literal(text) => text,
+            TopicPart::Prefix => prefix,
+            TopicPart::DeviceId => device_id,
+            TopicPart::Metric => metric,
+        };
+        topic.push_str(text).map_err(|_| MqttError::InvalidTopicTemplate("Resolved topic too long"))
+    })?;
+    Ok(topic)
+}
 
 /// MQTT message structure
 #[derive(Debug, Clone)]
@@ -39,15 +175,296 @@ impl<'a> MqttMessage<'a> {
     }
 }
 
+/// Payload schema used by [`MqttClient::publish_sensor_data`](crate::MqttClient::publish_sensor_data)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PayloadFormat {
+    /// [`SensorData`] JSON as published so far (default)
+    #[default]
+    SensorData,
+    /// Pre-standard payload of the given application family
+    Legacy(LegacyFormat),
+    /// iot-common [`StandardSensorReading`] tagged with the architecture and
+    /// published to its [`StandardTopics`] sensor topic
+    Standard(IoTArchitecture),
+}
+
+/// Standard-schema sensor message ready to publish
+/// 
+/// [`MqttMessage`] borrows its topic and payload, so the serialized reading
+/// lives here and converts into a message with `MqttMessage::from(&msg)`.
+#[derive(Debug, Clone)]
+pub struct StandardMessage {
+    topic: &'static str,
+    payload: String<256>,
+}
+
+impl StandardMessage {
+    /// Serialize a reading for the given architecture
+    /// 
+    /// The reading is tagged with the architecture and addressed to
+    /// `StandardTopics::for_architecture(architecture).sensor`.
+    pub fn sensor(reading: StandardSensorReading, architecture: IoTArchitecture) -> Result<Self, MqttError> {
+        let payload = reading.with_architecture(architecture).to_standard_json()
+            .map_err(|_| MqttError::SerializationError("Standard reading too large"))?;
+        Ok(Self {
+            topic: StandardTopics::for_architecture(architecture).sensor,
+            payload,
+        })
+    }
+    
+    /// Topic the message is published to
+    pub fn topic(&self) -> &'static str {
+        self.topic
+    }
+    
+    /// JSON payload
+    pub fn payload(&self) -> &str {
+        &self.payload
+    }
+}
+
+impl<'a> From<&'a StandardMessage> for MqttMessage<'a> {
+    fn from(message: &'a StandardMessage) -> Self {
+        MqttMessage::new(message.topic, message.payload.as_bytes())
+    }
+}
+
+/// Longest configurable [`SensorData`] field name
+pub const MAX_FIELD_NAME_LEN: usize = 24;
+
+/// Keys [`SensorData`] always uses, unavailable as measurement names
+const FIXED_SENSOR_KEYS: [&str; 6] = ["reading", "app", "timestamp_ms", "time", "sensor_offline", "units"];
+
+/// JSON field names and units of serialized [`SensorData`]
+///
+/// Lets an integration expect e.g. `{"temp_c":..,"rh":..,"hpa":..}` without
+/// forking the message module. The default reproduces the original payload,
+/// so existing dashboards keep working.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SensorDataFormat {
+    /// Key of the temperature value (°C)
+    pub temperature: &'static str,
+    /// Key of the relative humidity value (%)
+    pub humidity: &'static str,
+    /// Key of the pressure value (hPa)
+    pub pressure: &'static str,
+    /// Add a `units` object mapping each measurement key to its unit
+    pub include_units: bool,
+}
+
+impl Default for SensorDataFormat {
+    fn default() -> Self {
+        Self::new("temperature", "humidity", "pressure")
+    }
+}
+
+impl SensorDataFormat {
+    /// Format with custom measurement keys and no units object
+    pub const fn new(temperature: &'static str, humidity: &'static str, pressure: &'static str) -> Self {
+        Self {
+            temperature,
+            humidity,
+            pressure,
+            include_units: false,
+        }
+    }
+    
+    /// Include or omit the `units` object
+    pub const fn with_units(mut self, include_units: bool) -> Self {
+        self.include_units = include_units;
+        self
+    }
+    
+    /// Check the field names
+    ///
+    /// Keys are written to the JSON verbatim, so they must be non-empty, at
+    /// most [`MAX_FIELD_NAME_LEN`] characters of ASCII letters, digits, `_`
+    /// or `-`, distinct from each other and from the fixed keys.
+    pub fn validate(&self) -> Result<(), MqttError> {
+        let names = [self.temperature, self.humidity, self.pressure];
+        for (index, name) in names.iter().enumerate() {
+            if name.is_empty() {
+                return Err(MqttError::InvalidPayloadFormat("Field name is empty"));
+            }
+            if name.len() > MAX_FIELD_NAME_LEN
+                || !name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+            {
+                return Err(MqttError::InvalidPayloadFormat("Field name too long or has invalid characters"));
+            }
+            if names[..index].contains(name) || FIXED_SENSOR_KEYS.contains(name) {
+                return Err(MqttError::InvalidPayloadFormat("Field names collide"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// [`SensorData`] serialized with a [`SensorDataFormat`]
+///
+/// Created by [`SensorData::formatted`]; serializes like the derived
+/// implementation but with the configured keys and optional units.
+#[derive(Debug, Clone, Copy)]
+pub struct FormattedSensorData<'a> {
+    data: &'a SensorData,
+    format: &'a SensorDataFormat,
+}
+
+impl Serialize for FormattedSensorData<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (data, format) = (self.data, self.format);
+        let mut state = serializer.serialize_struct("SensorData", 9)?;
+        state.serialize_field(format.temperature, &data.temperature)?;
+        state.serialize_field(format.pressure, &data.pressure)?;
+        state.serialize_field(format.humidity, &data.humidity)?;
+        state.serialize_field("reading", &data.reading)?;
+        state.serialize_field("app", &data.app)?;
+        match data.timestamp_ms {
+            Some(timestamp_ms) => state.serialize_field("timestamp_ms", &timestamp_ms)?,
+            None => state.skip_field("timestamp_ms")?,
+        }
+        match data.time {
+            Some(time) => state.serialize_field("time", &time)?,
+            None => state.skip_field("time")?,
+        }
+        if data.sensor_offline {
+            state.serialize_field("sensor_offline", &true)?;
+        } else {
+            state.skip_field("sensor_offline")?;
+        }
+        if format.include_units {
+            state.serialize_field("units", &Units(format))?;
+        }
+        state.end()
+    }
+}
+
+/// `units` object of a formatted reading, keyed like the measurements
+struct Units<'a>(&'a SensorDataFormat);
+
+impl Serialize for Units<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Units", 3)?;
+        state.serialize_field(self.0.temperature, "°C")?;
+        state.serialize_field(self.0.pressure, "hPa")?;
+        state.serialize_field(self.0.humidity, "%")?;
+        state.end()
+    }
+}
+
+/// `core::fmt::Write` sink over a byte slice
+///
+/// Lets payloads be formatted straight into a stack buffer. A write that
+/// does not fit fails as a whole, so the bytes written so far stay valid UTF-8.
+pub struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> SliceWriter<'a> {
+    /// Writer starting at the beginning of `buf`
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, len: 0 }
+    }
+    
+    /// Number of bytes written
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    
+    /// Whether nothing has been written yet
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    
+    /// Text written so far
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or("")
+    }
+}
+
+impl Write for SliceWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        let target = self.buf.get_mut(self.len..end).ok_or(fmt::Error)?;
+        target.copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+/// Write `"key":` preceded by a comma unless it is the first member
+fn write_json_key(out: &mut impl Write, key: &str, first: bool) -> fmt::Result {
+    if !first {
+        out.write_char(',')?;
+    }
+    write_json_str(out, key)?;
+    out.write_char(':')
+}
+
+/// Write a quoted JSON string, escaped like `serde_json_core`
+fn write_json_str(out: &mut impl Write, value: &str) -> fmt::Result {
+    out.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\u{08}' => out.write_str("\\b")?,
+            '\t' => out.write_str("\\t")?,
+            '\n' => out.write_str("\\n")?,
+            '\u{0C}' => out.write_str("\\f")?,
+            '\r' => out.write_str("\\r")?,
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04X}", c as u32)?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
+
+/// Write a float the way `serde_json_core` (ryu) does: shortest round-trip digits,
+/// positional from 1e-6 up to 1e13 and exponent form outside, `null` if not finite
+fn write_json_f32(out: &mut impl Write, value: f32) -> fmt::Result {
+    if !value.is_finite() {
+        return out.write_str("null");
+    }
+    if value == 0.0 || (1e-6..1e13).contains(&value.abs()) {
+        write!(out, "{}", value)?;
+        if value.fract() == 0.0 {
+            out.write_str(".0")?;
+        }
+        Ok(())
+    } else {
+        write!(out, "{:e}", value)
+    }
+}
+
+/// Read a measurement published as `null` (see [`SensorData::offline`]) back as NaN
+fn nan_if_null<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+    Ok(Option::<f32>::deserialize(deserializer)?.unwrap_or(f32::NAN))
+}
+
 /// BME280 sensor data structure matching the project specification
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 
+/// Measurements that are not finite serialize as JSON `null`, never `NaN`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SensorData {
+    #[serde(deserialize_with = "nan_if_null")]
     pub temperature: f32,  // °C
+    #[serde(deserialize_with = "nan_if_null")]
     pub pressure: f32,     // hPa (matching main-nodeps field order)
+    #[serde(deserialize_with = "nan_if_null")]
     pub humidity: f32,     // %
     pub reading: u32,      // Reading counter (matching main-nodeps format)
     // TODO: Remove 'app' field in production - used for development debugging only
     pub app: String<32>,   // Source application identifier (temporary for debugging)
+    /// Time the reading was taken (ms since boot), only sent in batches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_ms: Option<u64>,
+    /// Wall-clock time of the reading (Unix seconds), sent once SNTP has synced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<u64>,
+    /// Placeholder published while the sensor is absent; measurements are `null`
+    #[serde(default, skip_serializing_if = "core::ops::Not::not")]
+    pub sensor_offline: bool,
 }
 
 impl SensorData {
@@ -60,6 +477,9 @@ impl SensorData {
             reading: 0,  // Default to 0, should be set explicitly
             // TODO: Remove 'app' field in production
             app: String::try_from("unknown").unwrap_or_default(),
+            timestamp_ms: None,
+            time: None,
+            sensor_offline: false,
         }
     }
     
@@ -72,6 +492,9 @@ impl SensorData {
             reading,
             // TODO: Remove 'app' field in production
             app: String::try_from("unknown").unwrap_or_default(),
+            timestamp_ms: None,
+            time: None,
+            sensor_offline: false,
         }
     }
     
@@ -85,6 +508,55 @@ impl SensorData {
             reading,
             // TODO: Remove 'app' field in production
             app: String::try_from(app_name).unwrap_or_else(|_| String::try_from("toolong").unwrap_or_default()),
+            timestamp_ms: None,
+            time: None,
+            sensor_offline: false,
+        }
+    }
+    
+    /// Create a "sensor offline" placeholder
+    /// 
+    /// Published at the normal interval while the sensor is absent, so a
+    /// dashboard can tell a dead sensor (placeholders keep arriving) from a
+    /// dead device (nothing arrives). The measurements serialize as `null`.
+    pub fn offline(reading: u32, app_name: &str) -> Self {
+        Self {
+            sensor_offline: true,
+            ..Self::new_with_app(f32::NAN, f32::NAN, f32::NAN, reading, app_name)
+        }
+    }
+    
+    /// Sensor data from a reading, or an [`offline`](Self::offline) placeholder without one
+    /// 
+    /// `measurements` is `(temperature, humidity, pressure)`.
+    pub fn from_measurements(measurements: Option<(f32, f32, f32)>, reading: u32, app_name: &str) -> Self {
+        match measurements {
+            Some((temperature, humidity, pressure)) => Self::new_with_app(temperature, humidity, pressure, reading, app_name),
+            None => Self::offline(reading, app_name),
+        }
+    }
+    
+    /// Attach the time the reading was taken (ms since boot)
+    pub fn with_timestamp(mut self, timestamp_ms: u64) -> Self {
+        self.timestamp_ms = Some(timestamp_ms);
+        self
+    }
+    
+    /// Attach the wall-clock time of the reading (Unix seconds)
+    pub fn with_unix_time(mut self, unix_secs: u64) -> Self {
+        self.time = Some(unix_secs);
+        self
+    }
+    
+    /// Fill in `time` from the wall clock of `clock`, if it has one
+    /// 
+    /// A `time` already set is kept. The reading's own `timestamp_ms` is
+    /// converted when present, so readings queued before the first sync get
+    /// the time they were taken; otherwise the current time is used.
+    pub fn stamp_time(&mut self, clock: &dyn TimestampProvider) {
+        if self.time.is_none() {
+            let taken_at = self.timestamp_ms.unwrap_or_else(|| clock.get_timestamp_ms());
+            self.time = clock.wall_clock_ms_at(taken_at).map(|unix_ms| unix_ms / 1000);
         }
     }
     
@@ -92,8 +564,100 @@ impl SensorData {
     pub fn to_json(&self) -> Result<String<256>, &'static str> {
         serde_json_core::to_string(self).map_err(|_| "JSON serialization failed")
     }
+    
+    /// View that serializes with the given field names and units
+    pub fn formatted<'a>(&'a self, format: &'a SensorDataFormat) -> FormattedSensorData<'a> {
+        FormattedSensorData { data: self, format }
+    }
+    
+    /// Serialize to JSON with the given field names and units (max 256 bytes)
+    pub fn to_json_with(&self, format: &SensorDataFormat) -> Result<String<256>, &'static str> {
+        serde_json_core::to_string(&self.formatted(format)).map_err(|_| "JSON serialization failed")
+    }
+    
+    /// Write JSON straight into `buf`, returning the number of bytes used
+    /// 
+    /// Produces the same payload as [`to_json`](Self::to_json) but formats
+    /// with `core::fmt::Write` into the caller's buffer instead of going
+    /// through serde and an intermediate `String`.
+    pub fn write_json(&self, buf: &mut [u8]) -> Result<usize, MqttError> {
+        self.write_json_with(&SensorDataFormat::default(), buf)
+    }
+    
+    /// [`write_json`](Self::write_json) with the given field names and units
+    pub fn write_json_with(&self, format: &SensorDataFormat, buf: &mut [u8]) -> Result<usize, MqttError> {
+        let mut out = SliceWriter::new(buf);
+        self.write_json_fields(format, &mut out)
+            .map_err(|_| MqttError::SerializationError("JSON buffer too small"))?;
+        Ok(out.len())
+    }
+    
+    /// Member order matches [`FormattedSensorData`]
+    fn write_json_fields(&self, format: &SensorDataFormat, out: &mut SliceWriter<'_>) -> fmt::Result {
+        out.write_char('{')?;
+        write_json_key(out, format.temperature, true)?;
+        write_json_f32(out, self.temperature)?;
+        write_json_key(out, format.pressure, false)?;
+        write_json_f32(out, self.pressure)?;
+        write_json_key(out, format.humidity, false)?;
+        write_json_f32(out, self.humidity)?;
+        write_json_key(out, "reading", false)?;
+        write!(out, "{}", self.reading)?;
+        write_json_key(out, "app", false)?;
+        write_json_str(out, &self.app)?;
+        if let Some(timestamp_ms) = self.timestamp_ms {
+            write_json_key(out, "timestamp_ms", false)?;
+            write!(out, "{}", timestamp_ms)?;
+        }
+        if let Some(time) = self.time {
+            write_json_key(out, "time", false)?;
+            write!(out, "{}", time)?;
+        }
+        if self.sensor_offline {
+            write_json_key(out, "sensor_offline", false)?;
+            out.write_str("true")?;
+        }
+        if format.include_units {
+            write_json_key(out, "units", false)?;
+            out.write_char('{')?;
+            write_json_key(out, format.temperature, true)?;
+            write_json_str(out, "°C")?;
+            write_json_key(out, format.pressure, false)?;
+            write_json_str(out, "hPa")?;
+            write_json_key(out, format.humidity, false)?;
+            write_json_str(out, "%")?;
+            out.write_char('}')?;
+        }
+        out.write_char('}')
+    }
+    
+    /// Convert to the iot-common standard reading of an architecture
+    /// 
+    /// The device ID comes from the architecture; the timestamp is the time the
+    /// reading was taken, or now if it was not recorded.
+    pub fn to_standard_reading(&self, architecture: IoTArchitecture) -> StandardSensorReading {
+        let timestamp = self.timestamp_ms
+            .unwrap_or_else(|| embassy_time::Instant::now().as_millis());
+        StandardSensorReading::new(
+            self.temperature,
+            self.humidity,
+            self.pressure,
+            timestamp,
+            architecture.get_device_id(),
+            self.reading,
+        )
+    }
+    
+    /// Serialize in one of the pre-standard formats
+    pub fn to_legacy_json(&self, format: LegacyFormat) -> String<256> {
+        StandardSensorReading::new(self.temperature, self.humidity, self.pressure, 0, "", self.reading)
+            .to_legacy_json(format)
+    }
 }
 
+/// Longest reset description carried in [`DeviceStatus::last_reset`]
+pub const MAX_LAST_RESET_LEN: usize = 64;
+
 /// Device status information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeviceStatus {
@@ -103,6 +667,14 @@ pub struct DeviceStatus {
     pub wifi_rssi: i8,          // dBm
     // TODO: Remove 'app' field in production - used for development debugging only
     pub app: String<32>,        // Source application identifier (temporary for debugging)
+    #[serde(default)]
+    pub errors: heapless::Vec<ErrorRecord, MAX_ERROR_HISTORY_DEPTH>, // recent faults, oldest first
+    /// Why the device last restarted, e.g. "watchdog" or "panic at main.rs:412: ..."
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_reset: Option<String<MAX_LAST_RESET_LEN>>,
+    /// On-die temperature in °C; tracks the chip's own heat, not the ambient
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chip_temperature: Option<f32>,
 }
 
 impl DeviceStatus {
@@ -118,6 +690,9 @@ impl DeviceStatus {
             wifi_rssi,
             // TODO: Remove 'app' field in production
             app: String::try_from("unknown").unwrap_or_default(),
+            errors: heapless::Vec::new(),
+            last_reset: None,
+            chip_temperature: None,
         }
     }
     
@@ -134,11 +709,58 @@ impl DeviceStatus {
             wifi_rssi,
             // TODO: Remove 'app' field in production
             app: String::try_from(app_name).unwrap_or_else(|_| String::try_from("toolong").unwrap_or_default()),
+            errors: heapless::Vec::new(),
+            last_reset: None,
+            chip_temperature: None,
         }
     }
     
-    /// Serialize to JSON string (max 256 bytes)
-    pub fn to_json(&self) -> Result<String<256>, &'static str> {
+    /// Create device status with uptime measured from `boot_instant`
+    /// 
+    /// Uptime is `Instant::now() - boot_instant` from the monotonic embassy-time
+    /// clock, so it stays exact however often or late the publishing loop runs.
+    /// Capture `boot_instant` once at startup and pass the same value every time.
+    pub fn from_boot(boot_instant: embassy_time::Instant, status: &str, free_heap: u32, wifi_rssi: i8, app_name: &str) -> Self {
+        let uptime = embassy_time::Instant::now().saturating_duration_since(boot_instant).as_secs();
+        Self::new_with_app(status, uptime.min(u32::MAX as u64) as u32, free_heap, wifi_rssi, app_name)
+    }
+    
+    /// Attach the device's recent fault history
+    pub fn with_errors(mut self, history: &ErrorHistory) -> Self {
+        self.errors.clear();
+        // Same capacity on both sides, so every record fits
+        let _ = self.errors.extend_from_slice(history.records());
+        self
+    }
+    
+    /// Report why the device last restarted
+    /// 
+    /// Meant for the first status after boot, so operators see a watchdog
+    /// reset or panic without attaching a debugger. Truncated to
+    /// [`MAX_LAST_RESET_LEN`] bytes.
+    pub fn with_last_reset(mut self, reason: &str) -> Self {
+        let mut text = String::new();
+        for c in reason.chars() {
+            if text.push(c).is_err() {
+                break;
+            }
+        }
+        self.last_reset = Some(text);
+        self
+    }
+    
+    /// Report the on-die temperature
+    /// 
+    /// Useful to spot an overheating enclosure; it reads well above the
+    /// ambient temperature and is only accurate to a few degrees.
+    pub fn with_chip_temperature(mut self, celsius: f32) -> Self {
+        self.chip_temperature = Some(celsius);
+        self
+    }
+    
+    /// Serialize to JSON string (max 640 bytes, room for a full error history
+    /// plus the last reset description and chip temperature)
+    pub fn to_json(&self) -> Result<String<640>, &'static str> {
         serde_json_core::to_string(self).map_err(|_| "JSON serialization failed")
     }
 }
@@ -179,4 +801,275 @@ impl MqttPayload {
     pub fn to_json(&self) -> Result<String<512>, &'static str> {
         serde_json_core::to_string(self).map_err(|_| "JSON serialization failed")
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_remaining_length_single_byte_boundary() {
+        assert_eq!(encode_remaining_length(0).unwrap().as_slice(), &[0x00]);
+        assert_eq!(encode_remaining_length(127).unwrap().as_slice(), &[0x7F]);
+        assert_eq!(encode_remaining_length(128).unwrap().as_slice(), &[0x80, 0x01]);
+    }
+    
+    #[test]
+    fn test_remaining_length_multi_byte() {
+        // Boundaries from the MQTT 3.1.1 spec, table 2.4
+        assert_eq!(encode_remaining_length(16_383).unwrap().as_slice(), &[0xFF, 0x7F]);
+        assert_eq!(encode_remaining_length(16_384).unwrap().as_slice(), &[0x80, 0x80, 0x01]);
+        assert_eq!(encode_remaining_length(2_097_151).unwrap().as_slice(), &[0xFF, 0xFF, 0x7F]);
+        assert_eq!(encode_remaining_length(2_097_152).unwrap().as_slice(), &[0x80, 0x80, 0x80, 0x01]);
+        assert_eq!(encode_remaining_length(MAX_REMAINING_LENGTH).unwrap().as_slice(), &[0xFF, 0xFF, 0xFF, 0x7F]);
+        assert!(encode_remaining_length(MAX_REMAINING_LENGTH + 1).is_err());
+    }
+    
+    #[test]
+    fn test_remaining_length_round_trip() {
+        for length in [0, 1, 127, 128, 321, 2048, 16_383, 16_384, 2_097_152, MAX_REMAINING_LENGTH] {
+            let encoded = encode_remaining_length(length).unwrap();
+            assert_eq!(decode_remaining_length(&encoded).unwrap(), (length, encoded.len()));
+        }
+        assert!(decode_remaining_length(&[0x80, 0x80]).is_err());
+        assert!(decode_remaining_length(&[0xFF, 0xFF, 0xFF, 0xFF, 0x01]).is_err());
+    }
+    
+    #[test]
+    fn test_resolve_topic() {
+        let topic = resolve_topic("{prefix}/sensors/{device_id}/{metric}", "home", "esp32c3_01", "temperature").unwrap();
+        assert_eq!(topic.as_str(), "home/sensors/esp32c3_01/temperature");
+        
+        // Placeholders may sit next to literals and appear in any order
+        let topic = resolve_topic("{metric}@{device_id}", "home", "esp32c3_01", "status").unwrap();
+        assert_eq!(topic.as_str(), "status@esp32c3_01");
+        assert!(validate_topic_template("{prefix}/{device_id}/{metric}").is_ok());
+    }
+    
+    #[test]
+    fn test_topic_template_rejects_unknown_placeholder() {
+        assert!(matches!(
+            validate_topic_template("{prefix}/{room}/{metric}"),
+            Err(MqttError::InvalidTopicTemplate("Unknown placeholder, expected {prefix}, {device_id} or {metric}"))
+        ));
+        assert!(matches!(
+            validate_topic_template("{}/{metric}"),
+            Err(MqttError::InvalidTopicTemplate("Unknown placeholder, expected {prefix}, {device_id} or {metric}"))
+        ));
+    }
+    
+    #[test]
+    fn test_topic_template_rejects_unbalanced_braces() {
+        assert!(matches!(
+            validate_topic_template("{prefix/{metric}"),
+            Err(MqttError::InvalidTopicTemplate("Unknown placeholder, expected {prefix}, {device_id} or {metric}"))
+        ));
+        assert!(matches!(
+            validate_topic_template("{prefix}/{metric"),
+            Err(MqttError::InvalidTopicTemplate("Unclosed '{'"))
+        ));
+        assert!(matches!(
+            validate_topic_template("prefix}/{metric}"),
+            Err(MqttError::InvalidTopicTemplate("Unmatched '}'"))
+        ));
+    }
+    
+    #[test]
+    fn test_topic_template_rejects_wildcards() {
+        for template in ["{prefix}/+/{metric}", "{prefix}/{metric}/#", "home/#"] {
+            assert!(matches!(
+                validate_topic_template(template),
+                Err(MqttError::InvalidTopicTemplate("Wildcards are not allowed in publish topics"))
+            ));
+        }
+    }
+    
+    #[test]
+    fn test_topic_template_requires_metric() {
+        assert!(matches!(
+            validate_topic_template("{prefix}/{device_id}"),
+            Err(MqttError::InvalidTopicTemplate("Template must contain {metric}"))
+        ));
+        assert!(matches!(
+            resolve_topic("home/status", "home", "esp32c3_01", "status"),
+            Err(MqttError::InvalidTopicTemplate("Template must contain {metric}"))
+        ));
+    }
+    
+    #[test]
+    fn test_resolve_topic_too_long() {
+        // 100 + 1 + 28 = 129 bytes, one over the Topic capacity
+        let prefix = core::str::from_utf8(&[b'p'; 100]).unwrap();
+        let device_id = core::str::from_utf8(&[b'd'; 28]).unwrap();
+        assert!(matches!(
+            resolve_topic("{prefix}/{device_id}{metric}", prefix, device_id, ""),
+            Err(MqttError::InvalidTopicTemplate("Resolved topic too long"))
+        ));
+        
+        // Exactly at capacity still fits
+        let topic = resolve_topic("{prefix}/{device_id}{metric}", prefix, &device_id[1..], "").unwrap();
+        assert_eq!(topic.len(), 128);
+    }
+    
+    #[test]
+    fn test_device_status_error_history() {
+        let status = DeviceStatus::new_with_app("online", 60, 32000, -45, "main-app");
+        assert!(status.to_json().unwrap().ends_with(r#""app":"main-app","errors":[]}"#));
+        
+        // A full history of long category names still fits the payload
+        let mut history = ErrorHistory::new();
+        for i in 0..MAX_ERROR_HISTORY_DEPTH {
+            let kind = if i % 2 == 0 { "invalid_config" } else { "calibration_error" };
+            history.record(&iot_common::error::utils::sensor_error(kind, "fault"));
+        }
+        let json = status.clone().with_errors(&history).to_json().unwrap();
+        assert!(json.contains(r#"{"code":1006,"category":"Sensor","count":1}"#));
+        
+        let (decoded, _): (DeviceStatus, usize) = serde_json_core::from_str(&json).unwrap();
+        assert_eq!(decoded.errors.as_slice(), history.records());
+        assert!(decoded.last_reset.is_none());
+        assert!(decoded.chip_temperature.is_none());
+        
+        // ... and so does the longest reset description with a chip temperature
+        let reason = "panic at main.rs:412: called `Option::unwrap()` on a `None` value";
+        let json = status.with_errors(&history).with_last_reset(reason).with_chip_temperature(-12.345678).to_json().unwrap();
+        let (decoded, _): (DeviceStatus, usize) = serde_json_core::from_str(&json).unwrap();
+        assert_eq!(decoded.last_reset.unwrap(), reason[..MAX_LAST_RESET_LEN]);
+        assert_eq!(decoded.chip_temperature, Some(-12.345678));
+    }
+    
+    #[test]
+    fn test_sensor_data_format() {
+        let data = SensorData::new_with_app(22.5, 45.0, 1013.25, 7, "main-app").with_unix_time(1_700_000_000);
+        
+        // The default format reproduces the original payload
+        let default_format = SensorDataFormat::default();
+        assert!(default_format.validate().is_ok());
+        assert_eq!(data.to_json_with(&default_format).unwrap(), data.to_json().unwrap());
+        
+        let format = SensorDataFormat::new("temp_c", "rh", "hpa").with_units(true);
+        assert!(format.validate().is_ok());
+        assert_eq!(
+            data.to_json_with(&format).unwrap(),
+            r#"{"temp_c":22.5,"hpa":1013.25,"rh":45.0,"reading":7,"app":"main-app","time":1700000000,"units":{"temp_c":"°C","hpa":"hPa","rh":"%"}}"#
+        );
+        
+        assert!(SensorDataFormat::new("", "rh", "hpa").validate().is_err());
+        assert!(SensorDataFormat::new("t", "t", "hpa").validate().is_err());
+        assert!(SensorDataFormat::new("t", "rh", "reading").validate().is_err());
+        assert!(SensorDataFormat::new("temp\"c", "rh", "hpa").validate().is_err());
+    }
+    
+    #[test]
+    fn test_write_json_matches_serde() {
+        let readings = [
+            SensorData::new_with_app(22.5, 45.0, 1013.25, 7, "main-app"),
+            SensorData::new_with_app(-3.125, 99.9, 870.0, 0, "quote\"back\\slash\n").with_timestamp(123_456),
+            SensorData::new_with_app(f32::NAN, 0.0, 1e-5, u32::MAX, "").with_unix_time(1_700_000_000),
+            SensorData::new_with_app(1e13, 2.5e-6, 9.5e-5, 1, "\u{1}"),
+            SensorData::new_with_app(-0.0, 1.5e-7, 123456.78, 2, "°"),
+            SensorData::offline(3, "main-app").with_timestamp(5_000),
+        ];
+        let format = SensorDataFormat::new("temp_c", "rh", "hpa").with_units(true);
+        
+        let mut buf = [0u8; 256];
+        for data in &readings {
+            let len = data.write_json(&mut buf).unwrap();
+            assert_eq!(core::str::from_utf8(&buf[..len]).unwrap(), data.to_json().unwrap());
+            
+            let len = data.write_json_with(&format, &mut buf).unwrap();
+            assert_eq!(core::str::from_utf8(&buf[..len]).unwrap(), data.to_json_with(&format).unwrap());
+        }
+        
+        assert!(readings[0].write_json(&mut buf[..16]).is_err());
+    }
+    
+    #[test]
+    fn test_offline_placeholder_is_valid_json() {
+        let placeholder = SensorData::from_measurements(None, 12, "main-app");
+        let json = placeholder.to_json().unwrap();
+        assert_eq!(
+            json,
+            r#"{"temperature":null,"pressure":null,"humidity":null,"reading":12,"app":"main-app","sensor_offline":true}"#
+        );
+        assert!(!json.contains("NaN"));
+        
+        let (decoded, _): (SensorData, usize) = serde_json_core::from_str(&json).unwrap();
+        assert!(decoded.sensor_offline);
+        assert!(decoded.temperature.is_nan() && decoded.humidity.is_nan() && decoded.pressure.is_nan());
+        
+        // Regular readings carry no flag
+        let reading = SensorData::from_measurements(Some((22.5, 45.0, 1013.25)), 13, "main-app");
+        assert!(!reading.to_json().unwrap().contains("sensor_offline"));
+        assert!(SensorDataFormat::new("sensor_offline", "rh", "hpa").validate().is_err());
+    }
+    
+    #[test]
+    fn test_sensor_data_json_round_trip() {
+        let readings = [
+            SensorData::new_with_app(22.5, 45.0, 1013.25, 7, "main-app"),
+            SensorData::new_with_app(-3.125, 99.9, 870.0, u32::MAX, "quote\"back\\slash").with_timestamp(123_456),
+            SensorData::new_with_app(0.1, 0.2, 1000.3, 1, "main-app").with_unix_time(1_700_000_000),
+        ];
+        // `from_str` would keep escape sequences in `app`; unescape into scratch space
+        let mut unescaped = [0u8; 32];
+        for data in &readings {
+            let json = data.to_json().unwrap();
+            let (decoded, _): (SensorData, usize) = serde_json_core::from_str_escaped(&json, &mut unescaped).unwrap();
+            assert_eq!(&decoded, data);
+            // Re-encoding gives the same payload
+            assert_eq!(decoded.to_json().unwrap(), json);
+        }
+    }
+    
+    #[test]
+    fn test_stamp_time_uses_provider_wall_clock() {
+        /// Booted at Unix 1_700_000_000 s, synced after `synced_at` ms
+        struct Clock {
+            synced_at: u64,
+        }
+        
+        impl TimestampProvider for Clock {
+            fn get_timestamp_ms(&self) -> u64 {
+                90_000
+            }
+            
+            fn wall_clock_ms_at(&self, timestamp_ms: u64) -> Option<u64> {
+                (self.get_timestamp_ms() >= self.synced_at).then_some(1_700_000_000_000 + timestamp_ms)
+            }
+        }
+        
+        // Unsynced: nothing added, the payload stays as before
+        let mut live = SensorData::new_with_reading(21.0, 50.0, 1013.0, 1);
+        live.stamp_time(&Clock { synced_at: u64::MAX });
+        assert_eq!((live.timestamp_ms, live.time), (None, None));
+        
+        // Synced: a live reading gets the current time
+        live.stamp_time(&Clock { synced_at: 0 });
+        assert_eq!((live.timestamp_ms, live.time), (None, Some(1_700_000_090)));
+        
+        // A queued reading keeps the time it was taken, an existing time is kept
+        let mut queued = SensorData::new_with_reading(21.0, 50.0, 1013.0, 2).with_timestamp(30_000);
+        queued.stamp_time(&Clock { synced_at: 0 });
+        assert_eq!(queued.time, Some(1_700_000_030));
+        let mut preset = queued.clone().with_unix_time(42);
+        preset.stamp_time(&Clock { synced_at: 0 });
+        assert_eq!(preset.time, Some(42));
+    }
+    
+    /// Payload of the write_json vs serde_json_core comparison
+    ///
+    /// Measured over 10 000 iterations on a desktop host: about 7 µs vs 10 µs
+    /// per reading in a debug build, but about 600 ns vs 230 ns in release,
+    /// where ryu beats `core::fmt` float formatting. `write_json` is therefore
+    /// not a speed-up; what it saves is the intermediate `String` and the serde
+    /// code in the publish path. Both must keep producing the same bytes.
+    #[test]
+    fn test_write_json_benchmark_payload() {
+        let data = SensorData::new_with_app(22.5, 45.0, 1013.25, 7, "main-app").with_unix_time(1_700_000_000);
+        let mut buf = [0u8; 256];
+        
+        let len = data.write_json(&mut buf).unwrap();
+        assert_eq!(&buf[..len], data.to_json().unwrap().as_bytes());
+    }
+}
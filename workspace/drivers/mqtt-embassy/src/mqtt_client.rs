@@ -2,26 +2,125 @@
 //!
 //! Provides async MQTT publishing capabilities based on the working example
 //! from wifi-simple-must-working, adapted for Embassy integration.
+//!
+//! ## Broker Failover
+//!
+//! [`MqttConfig::brokers`] is an ordered list of up to [`MAX_BROKERS`]
+//! brokers, e.g. a primary and a backup. [`MqttClient::connect`] starts with
+//! the broker that last accepted a connection and moves down the list, giving
+//! each one [`MqttConfig::connect_timeout_secs`], until one sends a CONNACK.
+//! [`MqttClient::active_broker`] tells which one that was.
+//!
+//! ## Transport
+//!
+//! Connections are plain TCP. There is no TLS: a session that does not
+//! verify the broker certificate would only look secure, so brokers that
+//! require TLS (port 8883) need a bridge on the local network.
 
 extern crate alloc;
 
 use alloc::vec::Vec;
 use core::net::Ipv4Addr;
+use core::sync::atomic::{AtomicBool, AtomicU8, AtomicU32, AtomicUsize, Ordering};
 use embassy_net::{Stack, tcp::TcpSocket};
-use embassy_time::{Duration, Timer};
-use embedded_io_async::Write;
+use embassy_time::{Duration, Timer, with_timeout};
+use embedded_io_async::{Read, Write};
 use rtt_target::rprintln;
+use iot_common::{log_debug, log_trace};
+use iot_common::{IoTError, NetworkError, ConfigError, SystemError, error::utils as error_utils};
+use iot_common::{DeviceInfo, DeviceClientId, device_client_id, SharedTimestampProvider, TimestampProvider, UptimeTimestampProvider};
+
+use crate::message::{MqttMessage, SensorData, SensorDataFormat, DeviceStatus, Topic, PayloadFormat, StandardMessage, DEFAULT_TOPIC_TEMPLATE, encode_remaining_length, resolve_topic, validate_topic_template};
+
+/// Buffer size for a single sensor reading payload
+pub const SENSOR_PAYLOAD_SIZE: usize = 256;
+
+/// Maximum number of readings sent in one batch
+pub const MAX_BATCH_READINGS: usize = 16;
+
+/// Maximum JSON payload size of a batch (~120 bytes per reading)
+pub const MAX_BATCH_PAYLOAD: usize = 2048;
 
-use crate::message::{MqttMessage, SensorData, DeviceStatus};
+/// Default time allowed for TCP connect and CONNACK
+pub const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// Most brokers [`MqttConfig::brokers`] can hold
+pub const MAX_BROKERS: usize = 4;
+
+/// Port used for a broker address given without one
+pub const DEFAULT_BROKER_PORT: u16 = 1883;
+
+/// Default keep-alive interval sent in CONNECT
+pub const DEFAULT_KEEP_ALIVE_SECS: u32 = 60;
+
+/// Clean session bit of the CONNECT flags byte
+const CONNECT_FLAG_CLEAN_SESSION: u8 = 0x02;
+
+/// Address of one MQTT broker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BrokerAddress {
+    pub ip: Ipv4Addr,
+    pub port: u16,
+}
+
+impl BrokerAddress {
+    /// Create a broker address
+    pub const fn new(ip: Ipv4Addr, port: u16) -> Self {
+        Self { ip, port }
+    }
+    
+    /// Parse `a.b.c.d` or `a.b.c.d:port`; the port defaults to [`DEFAULT_BROKER_PORT`]
+    pub fn parse(address: &str) -> Result<Self, MqttError> {
+        let (ip, port) = match address.trim().split_once(':') {
+            Some((ip, port)) => (ip, port.parse::<u16>()
+                .map_err(|_| MqttError::InvalidBroker("Broker port is not a number"))?),
+            None => (address.trim(), DEFAULT_BROKER_PORT),
+        };
+        let ip = ip.parse::<Ipv4Addr>()
+            .map_err(|_| MqttError::InvalidBroker("Broker address is not an IPv4 address"))?;
+        if port == 0 {
+            return Err(MqttError::InvalidBroker("Broker port is 0"));
+        }
+        Ok(Self::new(ip, port))
+    }
+}
+
+impl core::fmt::Display for BrokerAddress {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}:{}", self.ip, self.port)
+    }
+}
 
 /// MQTT client configuration
 #[derive(Debug, Clone)]
 pub struct MqttConfig {
-    pub broker_ip: Ipv4Addr,
-    pub broker_port: u16,
+    /// Brokers in order of preference, from `MQTT_BROKER_IP`/`MQTT_BROKER_PORT`
+    /// unless replaced with [`MqttConfig::with_brokers`]
+    pub brokers: heapless::Vec<BrokerAddress, MAX_BROKERS>,
+    /// Client ID from `MQTT_CLIENT_ID`, used until [`MqttConfig::with_device_id`] runs
     pub client_id: &'static str,
+    /// Per-device client ID derived from the MAC (see [`MqttConfig::with_device_id`])
+    pub device_client_id: Option<DeviceClientId>,
+    /// Keep `client_id` even when a device ID is available (`MQTT_CLIENT_ID_FIXED=true`)
+    pub fixed_client_id: bool,
     pub topic_prefix: &'static str,
-    pub keep_alive: u16,
+    /// Topic layout using `{prefix}`, `{device_id}` (the client ID) and `{metric}`
+    pub topic_template: &'static str,
+    /// Longest silence before the broker drops the client, in seconds (0 disables)
+    /// 
+    /// Sent as a 16-bit field, so at most 65535; see [`MqttConfig::with_keep_alive`].
+    pub keep_alive_secs: u32,
+    /// Start every connect with a fresh session (`MQTT_CLEAN_SESSION`, default true)
+    /// 
+    /// With `false` the broker keeps subscriptions and queued QoS 1 messages
+    /// across reconnects; [`MqttClient::session_present`] tells whether it did.
+    pub clean_session: bool,
+    /// Time allowed for the whole connect sequence, per broker tried
+    pub connect_timeout_secs: u64,
+    /// Schema of sensor payloads (see [`PayloadFormat`])
+    pub payload_format: PayloadFormat,
+    /// Field names and units of [`PayloadFormat::SensorData`] payloads
+    pub sensor_data_format: SensorDataFormat,
 }
 
 impl Default for MqttConfig {
@@ -46,18 +145,155 @@ impl Default for MqttConfig {
         
         // Parse broker port from environment variable
         let broker_port_str = env!("MQTT_BROKER_PORT", "Set MQTT_BROKER_PORT in .cargo/config.toml");
-        let broker_port = broker_port_str.parse::<u16>().unwrap_or(1883);
+        let broker_port = broker_port_str.parse::<u16>().unwrap_or(DEFAULT_BROKER_PORT);
+        
+        let mut brokers = heapless::Vec::new();
+        let _ = brokers.push(BrokerAddress::new(broker_ip, broker_port));
         
         Self {
-            broker_ip,
-            broker_port,
+            brokers,
             client_id: env!("MQTT_CLIENT_ID", "Set MQTT_CLIENT_ID in .cargo/config.toml"),
+            device_client_id: None,
+            fixed_client_id: matches!(option_env!("MQTT_CLIENT_ID_FIXED"), Some("true")),
             topic_prefix: env!("MQTT_TOPIC_PREFIX", "Set MQTT_TOPIC_PREFIX in .cargo/config.toml"),
-            keep_alive: 60,
+            topic_template: DEFAULT_TOPIC_TEMPLATE,
+            keep_alive_secs: option_env!("MQTT_KEEP_ALIVE_SECS")
+                .and_then(|secs| secs.parse().ok())
+                .unwrap_or(DEFAULT_KEEP_ALIVE_SECS),
+            clean_session: !matches!(option_env!("MQTT_CLEAN_SESSION"), Some("false")),
+            connect_timeout_secs: DEFAULT_CONNECT_TIMEOUT_SECS,
+            payload_format: PayloadFormat::default(),
+            sensor_data_format: SensorDataFormat::default(),
         }
     }
 }
 
+impl MqttConfig {
+    /// Client ID sent in CONNECT and used as `{device_id}` in topics
+    pub fn client_id(&self) -> &str {
+        match &self.device_client_id {
+            Some(id) if !self.fixed_client_id => id.as_str(),
+            _ => self.client_id,
+        }
+    }
+    
+    /// Use a client ID derived from the device MAC, e.g. `esp32c3-AABBCCDDEEFF`
+    /// 
+    /// A fixed `MQTT_CLIENT_ID` shared by several boards makes the broker
+    /// disconnect one whenever another connects. Ignored when the ID was
+    /// pinned with [`MqttConfig::with_client_id`] or `MQTT_CLIENT_ID_FIXED`.
+    pub fn with_device_id(mut self, mac: [u8; 6]) -> Result<Self, MqttError> {
+        if self.fixed_client_id {
+            return Ok(self);
+        }
+        let id = device_client_id(&mac)
+            .ok_or(MqttError::InvalidClientId("Device client ID exceeds 23 characters"))?;
+        self.device_client_id = Some(id);
+        Ok(self)
+    }
+    
+    /// Pin an explicit client ID, for fleets that assign IDs centrally
+    pub fn with_client_id(mut self, client_id: &'static str) -> Result<Self, MqttError> {
+        validate_client_id(client_id)?;
+        self.client_id = client_id;
+        self.fixed_client_id = true;
+        Ok(self)
+    }
+    
+    /// Set the topic template, rejecting unknown placeholders
+    /// 
+    /// e.g. `"{prefix}/{device_id}/{metric}"` so several devices can share a broker.
+    pub fn with_topic_template(mut self, template: &'static str) -> Result<Self, MqttError> {
+        validate_topic_template(template)?;
+        self.topic_template = template;
+        Ok(self)
+    }
+    
+    /// Replace the brokers with a comma-separated list in order of preference
+    /// 
+    /// e.g. `"10.0.0.5:1883,10.0.0.6"`. Rejects an empty list, more than
+    /// [`MAX_BROKERS`] entries and any entry [`BrokerAddress::parse`] rejects.
+    pub fn with_brokers(mut self, list: &str) -> Result<Self, MqttError> {
+        let mut brokers = heapless::Vec::new();
+        for address in list.split(',').filter(|address| !address.trim().is_empty()) {
+            brokers.push(BrokerAddress::parse(address)?)
+                .map_err(|_| MqttError::InvalidBroker("Too many brokers"))?;
+        }
+        validate_brokers(&brokers)?;
+        self.brokers = brokers;
+        Ok(self)
+    }
+    
+    /// First broker in the list
+    pub fn primary_broker(&self) -> Option<BrokerAddress> {
+        self.brokers.first().copied()
+    }
+    
+    /// Set the keep-alive interval, rejecting values beyond the 16-bit field
+    pub fn with_keep_alive(mut self, secs: u32) -> Result<Self, MqttError> {
+        validate_keep_alive(secs)?;
+        self.keep_alive_secs = secs;
+        Ok(self)
+    }
+    
+    /// Request a clean (`true`) or persistent (`false`) session on connect
+    pub fn with_clean_session(mut self, clean_session: bool) -> Self {
+        self.clean_session = clean_session;
+        self
+    }
+    
+    /// Set the sensor payload field names and units, rejecting collisions
+    pub fn with_sensor_data_format(mut self, format: SensorDataFormat) -> Result<Self, MqttError> {
+        format.validate()?;
+        self.sensor_data_format = format;
+        Ok(self)
+    }
+    
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<(), MqttError> {
+        validate_brokers(&self.brokers)?;
+        validate_client_id(self.client_id())?;
+        validate_keep_alive(self.keep_alive_secs)?;
+        validate_topic_template(self.topic_template)?;
+        self.sensor_data_format.validate()
+    }
+}
+
+/// Check that there is a broker to connect to
+fn validate_brokers(brokers: &[BrokerAddress]) -> Result<(), MqttError> {
+    if brokers.is_empty() {
+        return Err(MqttError::InvalidBroker("No broker configured"));
+    }
+    if brokers.iter().any(|broker| broker.port == 0) {
+        return Err(MqttError::InvalidBroker("Broker port is 0"));
+    }
+    Ok(())
+}
+
+/// Broker indices in the order to try them, starting with `active`
+fn failover_order(active: usize, count: usize) -> impl Iterator<Item = usize> {
+    (0..count).map(move |offset| (active + offset) % count)
+}
+
+/// Check a keep-alive interval against the 16-bit CONNECT field
+fn validate_keep_alive(secs: u32) -> Result<u16, MqttError> {
+    u16::try_from(secs).map_err(|_| MqttError::InvalidKeepAlive(secs))
+}
+
+/// Check a client ID against the MQTT 3.1.1 limits
+/// 
+/// Brokers must accept 1-23 characters; longer IDs are allowed by most but
+/// must still fit the 16-bit length prefix of the CONNECT packet.
+fn validate_client_id(client_id: &str) -> Result<(), MqttError> {
+    if client_id.is_empty() {
+        return Err(MqttError::InvalidClientId("Client ID is empty"));
+    }
+    if client_id.len() > u16::MAX as usize {
+        return Err(MqttError::InvalidClientId("Client ID too long"));
+    }
+    Ok(())
+}
+
 /// MQTT client errors
 #[derive(Debug)]
 pub enum MqttError {
@@ -69,6 +305,28 @@ pub enum MqttError {
     IoError(&'static str),
     /// JSON serialization error
     SerializationError(&'static str),
+    /// Connect sequence did not finish within the configured timeout
+    Timeout,
+    /// Topic template is malformed or uses an unknown placeholder
+    InvalidTopicTemplate(&'static str),
+    /// Sensor payload field names are empty, invalid or collide
+    InvalidPayloadFormat(&'static str),
+    /// Client ID is empty or exceeds the MQTT length limits
+    InvalidClientId(&'static str),
+    /// Keep-alive interval does not fit the 16-bit CONNECT field
+    InvalidKeepAlive(u32),
+    /// Broker list is empty or an address does not parse
+    InvalidBroker(&'static str),
+    /// Broker does not support MQTT 3.1.1 (CONNACK code 0x01)
+    UnacceptableProtocolVersion,
+    /// Broker rejected the client ID (CONNACK code 0x02)
+    IdentifierRejected,
+    /// Broker is up but the MQTT service is unavailable (CONNACK code 0x03)
+    ServerUnavailable,
+    /// Broker rejected the user name or password (CONNACK code 0x04)
+    BadCredentials,
+    /// Client is not authorized to connect (CONNACK code 0x05)
+    NotAuthorized,
 }
 
 impl core::fmt::Display for MqttError {
@@ -78,23 +336,310 @@ impl core::fmt::Display for MqttError {
             MqttError::ProtocolError(msg) => write!(f, "MQTT protocol error: {}", msg),
             MqttError::IoError(msg) => write!(f, "I/O error: {}", msg),
             MqttError::SerializationError(msg) => write!(f, "Serialization error: {}", msg),
+            MqttError::Timeout => write!(f, "Connect timed out"),
+            MqttError::InvalidTopicTemplate(msg) => write!(f, "Invalid topic template: {}", msg),
+            MqttError::InvalidPayloadFormat(msg) => write!(f, "Invalid payload format: {}", msg),
+            MqttError::InvalidClientId(msg) => write!(f, "Invalid client ID: {}", msg),
+            MqttError::InvalidKeepAlive(secs) => write!(f, "Keep-alive {}s exceeds {}s", secs, u16::MAX),
+            MqttError::InvalidBroker(msg) => write!(f, "Invalid broker: {}", msg),
+            MqttError::UnacceptableProtocolVersion => write!(f, "Broker does not accept MQTT 3.1.1"),
+            MqttError::IdentifierRejected => write!(f, "Broker rejected the client ID"),
+            MqttError::ServerUnavailable => write!(f, "Broker MQTT service unavailable"),
+            MqttError::BadCredentials => write!(f, "Broker rejected credentials"),
+            MqttError::NotAuthorized => write!(f, "Client not authorized by broker"),
+        }
+    }
+}
+
+/// Maps broker, transport and setup failures onto [`IoTError`] categories,
+/// keeping the displayed text as the message
+///
+/// Connection, protocol, I/O and CONNACK refusals are network errors on
+/// the TCP link; invalid settings are configuration errors; payload encoding
+/// is a system error.
+impl From<MqttError> for IoTError {
+    fn from(error: MqttError) -> Self {
+        let message = error_utils::display_message(&error);
+        match error {
+            MqttError::ConnectionFailed(_)
+            | MqttError::ProtocolError(_)
+            | MqttError::IoError(_)
+            | MqttError::UnacceptableProtocolVersion
+            | MqttError::IdentifierRejected
+            | MqttError::ServerUnavailable
+            | MqttError::BadCredentials
+            | MqttError::NotAuthorized => {
+                IoTError::network(NetworkError::TCPConnectionFailed(message))
+            }
+            MqttError::Timeout => IoTError::network(NetworkError::Timeout(message)),
+            MqttError::InvalidTopicTemplate(_)
+            | MqttError::InvalidPayloadFormat(_)
+            | MqttError::InvalidClientId(_)
+            | MqttError::InvalidKeepAlive(_)
+            | MqttError::InvalidBroker(_) => {
+                IoTError::configuration(ConfigError::ValidationError(message))
+            }
+            MqttError::SerializationError(_) => {
+                IoTError::system(SystemError::InitializationFailed(message))
+            }
+        }
+    }
+}
+
+/// Accepted CONNACK
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnAck {
+    /// Broker resumed a stored session; when false, subscriptions must be
+    /// re-established
+    pub session_present: bool,
+}
+
+/// Parse a CONNACK packet, mapping refusal return codes to distinct errors
+pub fn parse_connack(packet: &[u8]) -> Result<ConnAck, MqttError> {
+    if packet.len() < 4 {
+        return Err(MqttError::ProtocolError("Truncated CONNACK"));
+    }
+    if packet[0] != 0x20 {
+        return Err(MqttError::ProtocolError("Expected CONNACK packet"));
+    }
+    if packet[1] != 0x02 {
+        return Err(MqttError::ProtocolError("Invalid CONNACK remaining length"));
+    }
+    if packet[2] & 0xFE != 0 {
+        return Err(MqttError::ProtocolError("Reserved CONNACK flags set"));
+    }
+    
+    match packet[3] {
+        0x00 => Ok(ConnAck { session_present: packet[2] & 0x01 != 0 }),
+        0x01 => Err(MqttError::UnacceptableProtocolVersion),
+        0x02 => Err(MqttError::IdentifierRejected),
+        0x03 => Err(MqttError::ServerUnavailable),
+        0x04 => Err(MqttError::BadCredentials),
+        0x05 => Err(MqttError::NotAuthorized),
+        _ => Err(MqttError::ProtocolError("Unknown CONNACK return code")),
+    }
+}
+
+/// State of the broker connection as last seen by the client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SocketState {
+    /// No connection open
+    Closed,
+    /// TCP connect or MQTT handshake in progress
+    Connecting,
+    /// Broker accepted the connection and no write has failed since
+    Connected,
+}
+
+impl SocketState {
+    /// Lower-case name for console and JSON output
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SocketState::Closed => "closed",
+            SocketState::Connecting => "connecting",
+            SocketState::Connected => "connected",
         }
     }
+    
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => SocketState::Connecting,
+            2 => SocketState::Connected,
+            _ => SocketState::Closed,
+        }
+    }
+}
+
+impl core::fmt::Display for SocketState {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Snapshot of a [`SocketMonitor`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SocketStats {
+    /// Connection state
+    pub state: SocketState,
+    /// MQTT bytes written to the broker since boot (wraps at `u32::MAX`)
+    pub bytes_sent: u32,
+    /// MQTT bytes read from the broker since boot (wraps at `u32::MAX`)
+    pub bytes_received: u32,
+}
+
+/// Broker socket state and traffic counters, readable from any task
+/// 
+/// The client runs inside the MQTT task; give it a `static` monitor with
+/// [`MqttClient::with_socket_monitor`] so a console command can report on
+/// the connection without touching the socket:
+/// 
+/// ```rust,ignore
+/// static MQTT_SOCKET: SocketMonitor = SocketMonitor::new();
+/// 
+/// let client = MqttClient::new(config).with_socket_monitor(&MQTT_SOCKET);
+/// // From any task
+/// let stats = MQTT_SOCKET.stats();
+/// ```
+/// 
+/// Only the client writes to it, so the counters use load/store rather
+/// than read-modify-write atomics, which the ESP32-C3 lacks.
+pub struct SocketMonitor {
+    state: AtomicU8,
+    bytes_sent: AtomicU32,
+    bytes_received: AtomicU32,
+}
+
+impl SocketMonitor {
+    /// Monitor for a closed socket with no traffic
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU8::new(SocketState::Closed as u8),
+            bytes_sent: AtomicU32::new(0),
+            bytes_received: AtomicU32::new(0),
+        }
+    }
+    
+    /// Current state and counters
+    pub fn stats(&self) -> SocketStats {
+        SocketStats {
+            state: SocketState::from_u8(self.state.load(Ordering::Relaxed)),
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+        }
+    }
+    
+    fn set_state(&self, state: SocketState) {
+        self.state.store(state as u8, Ordering::Relaxed);
+    }
+    
+    fn add_sent(&self, bytes: usize) {
+        let total = self.bytes_sent.load(Ordering::Relaxed).wrapping_add(bytes as u32);
+        self.bytes_sent.store(total, Ordering::Relaxed);
+    }
+    
+    fn add_received(&self, bytes: usize) {
+        let total = self.bytes_received.load(Ordering::Relaxed).wrapping_add(bytes as u32);
+        self.bytes_received.store(total, Ordering::Relaxed);
+    }
+}
+
+impl Default for SocketMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// MQTT client using Embassy async framework
 pub struct MqttClient {
     config: MqttConfig,
+    /// Session-present flag of the last accepted CONNACK
+    session_present: AtomicBool,
+    /// Index into [`MqttConfig::brokers`] of the broker tried first
+    active_broker: AtomicUsize,
+    /// Time source for reading timestamps
+    clock: SharedTimestampProvider,
+    /// Where socket state and traffic are reported, if anywhere
+    monitor: Option<&'static SocketMonitor>,
 }
 
 impl MqttClient {
     /// Create new MQTT client
+    /// 
+    /// Readings are timestamped with uptime only; use
+    /// [`with_timestamp_provider`](Self::with_timestamp_provider) to add
+    /// wall-clock time.
     pub fn new(config: MqttConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            session_present: AtomicBool::new(false),
+            active_broker: AtomicUsize::new(0),
+            clock: &UptimeTimestampProvider,
+            monitor: None,
+        }
+    }
+    
+    /// Use `clock` for the timestamps of published readings
+    /// 
+    /// Readings without `time` get it from the provider's wall clock, e.g.
+    /// [`SntpTimestampProvider`](crate::SntpTimestampProvider) once synced;
+    /// until then payloads are unchanged. Standard-format messages take
+    /// their `timestamp` from the provider's monotonic time.
+    pub fn with_timestamp_provider(mut self, clock: SharedTimestampProvider) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Time source used for reading timestamps
+    pub fn timestamp_provider(&self) -> SharedTimestampProvider {
+        self.clock
+    }
+    
+    /// Report socket state and traffic to `monitor`
+    pub fn with_socket_monitor(mut self, monitor: &'static SocketMonitor) -> Self {
+        self.monitor = Some(monitor);
+        self
+    }
+    
+    /// Socket state and traffic; closed with no traffic when no monitor is set
+    pub fn socket_stats(&self) -> SocketStats {
+        self.monitor.map_or(SocketMonitor::new().stats(), SocketMonitor::stats)
+    }
+    
+    /// Close a connection returned by [`connect`](Self::connect)
+    /// 
+    /// Dropping the socket has the same effect on the wire; this also marks
+    /// the connection closed in the [`SocketMonitor`].
+    pub fn disconnect<S>(&self, socket: S) {
+        drop(socket);
+        self.set_socket_state(SocketState::Closed);
+    }
+    
+    fn set_socket_state(&self, state: SocketState) {
+        if let Some(monitor) = self.monitor {
+            monitor.set_state(state);
+        }
+    }
+    
+    fn record_sent(&self, bytes: usize) {
+        if let Some(monitor) = self.monitor {
+            monitor.add_sent(bytes);
+        }
+    }
+    
+    fn record_received(&self, bytes: usize) {
+        if let Some(monitor) = self.monitor {
+            monitor.add_received(bytes);
+        }
+    }
+    
+    /// Whether the broker resumed a stored session on the last connect
+    /// 
+    /// Always `false` with [`MqttConfig::clean_session`] set (the default);
+    /// any subscriptions must then be sent again after each connect.
+    pub fn session_present(&self) -> bool {
+        self.session_present.load(Ordering::Relaxed)
+    }
+    
+    /// Broker that last accepted a connection, or the first one before any did
+    pub fn active_broker(&self) -> Option<BrokerAddress> {
+        let brokers = &self.config.brokers;
+        brokers.get(self.active_broker.load(Ordering::Relaxed)).or(brokers.first()).copied()
+    }
+    
+    /// Remember the broker at `index` as the one to try first next time
+    /// 
+    /// Only the connecting task writes it, so a load and a store stand in for
+    /// the swap the ESP32-C3 cannot do atomically.
+    fn set_active_broker(&self, index: usize) {
+        let previous = self.active_broker.load(Ordering::Relaxed);
+        self.active_broker.store(index, Ordering::Relaxed);
+        if previous != index {
+            rprintln!("[MQTT] Failed over to broker {}", self.config.brokers[index]);
+        }
     }
     
     /// Create MQTT CONNECT packet (based on working example)
-    fn create_connect_packet(&self) -> Vec<u8> {
+    fn create_connect_packet(&self) -> Result<Vec<u8>, MqttError> {
         let mut packet = Vec::new();
         
         // Fixed header
@@ -111,26 +656,31 @@ impl MqttClient {
         // Protocol version (4 for MQTT 3.1.1)
         variable_header.push(0x04);
         
-        // Connect flags (clean session)
-        variable_header.push(0x02);
+        // Connect flags (no will, no credentials)
+        let mut flags = 0;
+        if self.config.clean_session {
+            flags |= CONNECT_FLAG_CLEAN_SESSION;
+        }
+        variable_header.push(flags);
         
         // Keep alive
-        variable_header.extend_from_slice(&self.config.keep_alive.to_be_bytes());
+        let keep_alive = validate_keep_alive(self.config.keep_alive_secs)?;
+        variable_header.extend_from_slice(&keep_alive.to_be_bytes());
         
         // Payload - Client ID
-        let client_id_bytes = self.config.client_id.as_bytes();
+        let client_id_bytes = self.config.client_id().as_bytes();
         variable_header.extend_from_slice(&(client_id_bytes.len() as u16).to_be_bytes());
         variable_header.extend_from_slice(client_id_bytes);
         
         // Remaining length
-        packet.push(variable_header.len() as u8);
+        packet.extend_from_slice(&encode_remaining_length(variable_header.len())?);
         packet.extend_from_slice(&variable_header);
         
-        packet
+        Ok(packet)
     }
     
     /// Create MQTT PUBLISH packet (based on working example)
-    fn create_publish_packet(&self, message: &MqttMessage) -> Vec<u8> {
+    fn create_publish_packet(&self, message: &MqttMessage) -> Result<Vec<u8>, MqttError> {
         let mut packet = Vec::new();
         
         // Fixed header - PUBLISH packet type with flags
@@ -157,102 +707,266 @@ impl MqttClient {
         variable_header.extend_from_slice(message.payload);
         
         // Remaining length
-        packet.push(variable_header.len() as u8);
+        packet.extend_from_slice(&encode_remaining_length(variable_header.len())?);
         packet.extend_from_slice(&variable_header);
         
-        packet
+        Ok(packet)
     }
     
     /// Connect to MQTT broker using Embassy TCP socket
+    ///
+    /// Tries the brokers in [`MqttConfig::brokers`] in order, starting with
+    /// the active one, until one accepts the connection. Each gets the full
+    /// [`MqttConfig::connect_timeout_secs`]. When all fail, the last error is
+    /// returned.
     pub async fn connect<'a>(&self, stack: &Stack<'static>, rx_buffer: &'a mut [u8], tx_buffer: &'a mut [u8]) -> Result<TcpSocket<'a>, MqttError> {
-        rprintln!("[MQTT] Connecting to broker {}:{}", 
-                 self.config.broker_ip, self.config.broker_port);
-        
-        // Create TCP socket with provided buffers
+        let timeout = Duration::from_secs(self.config.connect_timeout_secs);
         let mut socket = TcpSocket::new(*stack, rx_buffer, tx_buffer);
+        let mut last_error = MqttError::InvalidBroker("No broker configured");
+        self.set_socket_state(SocketState::Connecting);
+        for index in failover_order(self.active_broker.load(Ordering::Relaxed), self.config.brokers.len()) {
+            let broker = self.config.brokers[index];
+            let attempt = with_timeout(timeout, async {
+                self.connect_tcp(&mut socket, broker).await?;
+                self.handshake(&mut socket).await
+            }).await;
+            match attempt {
+                Ok(Ok(_)) => {
+                    self.set_active_broker(index);
+                    self.set_socket_state(SocketState::Connected);
+                    return Ok(socket);
+                }
+                Ok(Err(e)) => last_error = e,
+                Err(_) => last_error = MqttError::Timeout,
+            }
+            rprintln!("[MQTT] Broker {} unavailable: {}", broker, last_error);
+            // Back to closed so the socket can connect to the next broker
+            socket.abort();
+        }
+        self.set_socket_state(SocketState::Closed);
+        Err(last_error)
+    }
+    
+    /// Connect `socket` to one broker
+    async fn connect_tcp(&self, socket: &mut TcpSocket<'_>, broker: BrokerAddress) -> Result<(), MqttError> {
+        log_debug!("[MQTT] Connecting to broker {}", broker);
         
-        // Connect to MQTT broker
-        let broker_addr = (self.config.broker_ip, self.config.broker_port);
-        socket.connect(broker_addr).await
+        socket.connect((broker.ip, broker.port)).await
             .map_err(|_| MqttError::ConnectionFailed("TCP connection failed"))?;
         
-        rprintln!("[MQTT] TCP connection established");
-        
+        log_trace!("[MQTT] TCP connection established");
+        Ok(())
+    }
+    
+    /// Send CONNECT and wait for CONNACK over any transport
+    async fn handshake<S: Read + Write>(&self, socket: &mut S) -> Result<ConnAck, MqttError> {
         // Send MQTT CONNECT packet
-        let connect_packet = self.create_connect_packet();
+        let connect_packet = self.create_connect_packet()?;
         socket.write_all(&connect_packet).await
             .map_err(|_| MqttError::IoError("Failed to send CONNECT packet"))?;
+        socket.flush().await
+            .map_err(|_| MqttError::IoError("Failed to send CONNECT packet"))?;
+        self.record_sent(connect_packet.len());
         
-        rprintln!("[MQTT] CONNECT packet sent");
+        log_trace!("[MQTT] CONNECT packet sent");
         
         // Read CONNACK response
         let mut buffer = [0u8; 64];
         let n = socket.read(&mut buffer).await
             .map_err(|_| MqttError::IoError("Failed to read CONNACK"))?;
+        self.record_received(n);
         
-        if n >= 4 && buffer[0] == 0x20 && buffer[3] == 0x00 {
-            rprintln!("[MQTT] CONNACK received - connection accepted");
-            Ok(socket)
-        } else {
-            Err(MqttError::ProtocolError("Invalid CONNACK response"))
+        match parse_connack(&buffer[..n]) {
+            Ok(connack) => {
+                log_debug!("[MQTT] CONNACK received - connection accepted (session present: {})",
+                         connack.session_present);
+                self.session_present.store(connack.session_present, Ordering::Relaxed);
+                Ok(connack)
+            }
+            Err(e) => {
+                rprintln!("[MQTT] CONNACK refused: {}", e);
+                Err(e)
+            }
         }
     }
     
     /// Publish a single MQTT message
-    pub async fn publish<'a>(&self, socket: &mut TcpSocket<'a>, message: &MqttMessage<'_>) -> Result<(), MqttError> {
-        rprintln!("[MQTT] Publishing to topic '{}'", message.topic);
+    pub async fn publish<S: Write>(&self, socket: &mut S, message: &MqttMessage<'_>) -> Result<(), MqttError> {
+        log_trace!("[MQTT] Publishing to topic '{}'", message.topic);
         
-        let publish_packet = self.create_publish_packet(message);
-        socket.write_all(&publish_packet).await
-            .map_err(|_| MqttError::IoError("Failed to send PUBLISH packet"))?;
+        let publish_packet = self.create_publish_packet(message)?;
+        let written = async {
+            socket.write_all(&publish_packet).await?;
+            socket.flush().await
+        }.await;
+        if written.is_err() {
+            self.set_socket_state(SocketState::Closed);
+            return Err(MqttError::IoError("Failed to send PUBLISH packet"));
+        }
+        self.record_sent(publish_packet.len());
         
         // Add delay to ensure message delivery to subscribers before connection closes
         embassy_time::Timer::after(embassy_time::Duration::from_millis(100)).await;
         
-        rprintln!("[MQTT] Message published successfully");
+        log_trace!("[MQTT] Message published successfully");
         Ok(())
     }
     
     /// Publish sensor data as JSON (following CLAUDE.md specification)
-    pub async fn publish_sensor_data<'a>(
+    /// 
+    /// The schema follows [`MqttConfig::payload_format`]. The standard format
+    /// also uses the standard topic hierarchy (`esp32c3/sensor/bme280/<arch>`)
+    /// instead of the configured topic template.
+    pub async fn publish_sensor_data<S: Write>(
         &self, 
-        socket: &mut TcpSocket<'a>, 
+        socket: &mut S, 
         sensor_data: &SensorData
     ) -> Result<(), MqttError> {
-        // Use simple sensor data JSON instead of complex nested structure
-        let json_str = sensor_data.to_json()
-            .map_err(|e| MqttError::SerializationError(e))?;
+        let mut sensor_data = sensor_data.clone();
+        sensor_data.stamp_time(self.clock);
+        
+        let mut buffer = [0u8; SENSOR_PAYLOAD_SIZE];
+        let legacy;
+        let payload: &[u8] = match self.config.payload_format {
+            PayloadFormat::SensorData => {
+                let len = sensor_data.write_json_with(&self.config.sensor_data_format, &mut buffer)?;
+                &buffer[..len]
+            }
+            PayloadFormat::Legacy(format) => {
+                legacy = sensor_data.to_legacy_json(format);
+                legacy.as_bytes()
+            }
+            PayloadFormat::Standard(architecture) => {
+                sensor_data.timestamp_ms.get_or_insert_with(|| self.clock.get_timestamp_ms());
+                let message = StandardMessage::sensor(sensor_data.to_standard_reading(architecture), architecture)?;
+                return self.publish(socket, &MqttMessage::from(&message)).await;
+            }
+        };
+        
+        let topic = self.topic("sensor/bme280")?;
+        let message = MqttMessage::new(&topic, payload);
+        
+        self.publish(socket, &message).await
+    }
+    
+    /// Publish several readings as one JSON array over a single connection
+    /// 
+    /// Used to flush readings buffered while offline. Each element carries its
+    /// `timestamp_ms` so the subscriber can rebuild the series, and `time`
+    /// once the timestamp provider has a wall clock.
+    pub async fn publish_batch<S: Write>(
+        &self,
+        socket: &mut S,
+        readings: &[SensorData]
+    ) -> Result<(), MqttError> {
+        if readings.is_empty() {
+            return Ok(());
+        }
+        if readings.len() > MAX_BATCH_READINGS {
+            return Err(MqttError::SerializationError("Too many readings in batch"));
+        }
+        
+        let stamped: Vec<SensorData> = readings.iter()
+            .cloned()
+            .map(|mut reading| {
+                reading.stamp_time(self.clock);
+                reading
+            })
+            .collect();
+        let formatted: heapless::Vec<_, MAX_BATCH_READINGS> = stamped.iter()
+            .map(|reading| reading.formatted(&self.config.sensor_data_format))
+            .collect();
         
-        let topic = "esp32/sensor/bme280";
-        let message = MqttMessage::new(topic, json_str.as_bytes());
+        let mut payload = alloc::vec![0u8; MAX_BATCH_PAYLOAD];
+        let len = serde_json_core::to_slice(&formatted, &mut payload)
+            .map_err(|_| MqttError::SerializationError("Batch payload too large"))?;
         
+        let topic = self.topic("sensor/bme280/batch")?;
+        let message = MqttMessage::new(&topic, &payload[..len]);
+        
+        log_debug!("[MQTT] Publishing batch of {} readings ({} bytes)", readings.len(), len);
         self.publish(socket, &message).await
     }
     
+    /// Publish each measurement to its own topic (`{metric}` = field name)
+    /// 
+    /// Payloads are plain numbers, the layout expected by Home Assistant sensors.
+    pub async fn publish_sensor_metrics<S: Write>(
+        &self,
+        socket: &mut S,
+        sensor_data: &SensorData
+    ) -> Result<(), MqttError> {
+        let metrics = [
+            ("temperature", sensor_data.temperature),
+            ("humidity", sensor_data.humidity),
+            ("pressure", sensor_data.pressure),
+        ];
+        
+        for (metric, value) in metrics {
+            let topic = self.topic(metric)?;
+            let mut payload: heapless::String<16> = heapless::String::new();
+            core::fmt::Write::write_fmt(&mut payload, format_args!("{:.2}", value))
+                .map_err(|_| MqttError::SerializationError("Metric value too long"))?;
+            
+            let message = MqttMessage::new(&topic, payload.as_bytes());
+            self.publish(socket, &message).await?;
+        }
+        Ok(())
+    }
+    
     /// Publish device status
-    pub async fn publish_device_status<'a>(
+    pub async fn publish_device_status<S: Write>(
         &self, 
-        socket: &mut TcpSocket<'a>, 
+        socket: &mut S, 
         status: &DeviceStatus
     ) -> Result<(), MqttError> {
         let json_str = status.to_json()
             .map_err(|e| MqttError::SerializationError(e))?;
         
-        let topic = "esp32/status";
-        let message = MqttMessage::new(topic, json_str.as_bytes());
+        let topic = self.topic("status")?;
+        let message = MqttMessage::new(&topic, json_str.as_bytes());
+        
+        self.publish(socket, &message).await
+    }
+    
+    /// Publish the device identity as a retained `info` message
+    /// 
+    /// Retained, so the broker hands the chip, MAC and firmware version of
+    /// every device to a fleet dashboard as soon as it subscribes. Publish
+    /// once per boot, after the first successful connect.
+    pub async fn publish_device_info<S: Write>(
+        &self,
+        socket: &mut S,
+        info: &DeviceInfo
+    ) -> Result<(), MqttError> {
+        let json_str = info.to_json()
+            .map_err(|e| MqttError::SerializationError(e))?;
+        
+        let topic = self.topic("info")?;
+        let message = MqttMessage::new(&topic, json_str.as_bytes()).with_retain(true);
         
         self.publish(socket, &message).await
     }
     
     /// Publish simple heartbeat message
-    pub async fn publish_heartbeat<'a>(&self, socket: &mut TcpSocket<'a>) -> Result<(), MqttError> {
-        let topic = "esp32/heartbeat"; 
+    pub async fn publish_heartbeat<S: Write>(&self, socket: &mut S) -> Result<(), MqttError> {
+        let topic = self.topic("heartbeat")?;
         let payload = b"ping";
-        let message = MqttMessage::new(topic, payload);
+        let message = MqttMessage::new(&topic, payload);
         
         self.publish(socket, &message).await
     }
     
+    /// Field names sensor payloads are published with
+    pub fn sensor_data_format(&self) -> &SensorDataFormat {
+        &self.config.sensor_data_format
+    }
+    
+    /// Resolve the configured topic template for a metric
+    pub fn topic(&self, metric: &str) -> Result<Topic, MqttError> {
+        resolve_topic(self.config.topic_template, self.config.topic_prefix, self.config.client_id(), metric)
+    }
+    
     /// Get topic name with prefix
     pub fn get_topic(&self, suffix: &str) -> heapless::String<64> {
         let mut topic = heapless::String::new();
@@ -329,4 +1043,147 @@ pub async fn mqtt_publish_task(
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_connack_accepted() {
+        assert_eq!(parse_connack(&[0x20, 0x02, 0x00, 0x00]).unwrap(), ConnAck { session_present: false });
+        assert_eq!(parse_connack(&[0x20, 0x02, 0x01, 0x00]).unwrap(), ConnAck { session_present: true });
+    }
+    
+    #[test]
+    fn test_connack_refusal_codes() {
+        assert!(matches!(parse_connack(&[0x20, 0x02, 0x00, 0x01]), Err(MqttError::UnacceptableProtocolVersion)));
+        assert!(matches!(parse_connack(&[0x20, 0x02, 0x00, 0x02]), Err(MqttError::IdentifierRejected)));
+        assert!(matches!(parse_connack(&[0x20, 0x02, 0x00, 0x03]), Err(MqttError::ServerUnavailable)));
+        assert!(matches!(parse_connack(&[0x20, 0x02, 0x00, 0x04]), Err(MqttError::BadCredentials)));
+        assert!(matches!(parse_connack(&[0x20, 0x02, 0x00, 0x05]), Err(MqttError::NotAuthorized)));
+        assert!(matches!(parse_connack(&[0x20, 0x02, 0x00, 0x80]), Err(MqttError::ProtocolError(_))));
+    }
+    
+    #[test]
+    fn test_connect_flags_and_keep_alive() {
+        for clean_session in [true, false] {
+            for keep_alive in [0, 60, u16::MAX as u32] {
+                let config = MqttConfig::default()
+                    .with_client_id("node-1").unwrap()
+                    .with_keep_alive(keep_alive).unwrap()
+                    .with_clean_session(clean_session);
+                let packet = MqttClient::new(config).create_connect_packet().unwrap();
+                
+                // 0x10, length, "MQTT", level 4, then flags and keep-alive
+                assert_eq!(packet[2..9], [0x00, 0x04, b'M', b'Q', b'T', b'T', 0x04]);
+                assert_eq!(packet[9], if clean_session { 0x02 } else { 0x00 });
+                assert_eq!(packet[10..12], (keep_alive as u16).to_be_bytes());
+                assert_eq!(packet[12..], *b"\x00\x06node-1");
+            }
+        }
+    }
+    
+    #[test]
+    fn test_keep_alive_limit() {
+        assert!(matches!(MqttConfig::default().with_keep_alive(65_536), Err(MqttError::InvalidKeepAlive(65_536))));
+        
+        // Set directly, it is still caught before anything is sent
+        let mut config = MqttConfig::default();
+        config.keep_alive_secs = 100_000;
+        assert!(matches!(config.validate(), Err(MqttError::InvalidKeepAlive(100_000))));
+        assert!(matches!(MqttClient::new(config).create_connect_packet(), Err(MqttError::InvalidKeepAlive(_))));
+    }
+    
+    #[test]
+    fn test_broker_list_parsing() {
+        let config = MqttConfig::default().with_brokers("10.0.0.5:1884, 10.0.0.6").unwrap();
+        assert_eq!(config.brokers, [
+            BrokerAddress::new(Ipv4Addr::new(10, 0, 0, 5), 1884),
+            BrokerAddress::new(Ipv4Addr::new(10, 0, 0, 6), DEFAULT_BROKER_PORT),
+        ]);
+        assert_eq!(config.primary_broker().unwrap().to_string(), "10.0.0.5:1884");
+        
+        assert!(matches!(MqttConfig::default().with_brokers(""), Err(MqttError::InvalidBroker("No broker configured"))));
+        assert!(matches!(MqttConfig::default().with_brokers("10.0.0.5,broker.local"), Err(MqttError::InvalidBroker(_))));
+        assert!(matches!(MqttConfig::default().with_brokers("10.0.0.5:http"), Err(MqttError::InvalidBroker(_))));
+        assert!(matches!(MqttConfig::default().with_brokers("10.0.0.5:0"), Err(MqttError::InvalidBroker(_))));
+        assert!(matches!(MqttConfig::default().with_brokers("1.1.1.1,2.2.2.2,3.3.3.3,4.4.4.4,5.5.5.5"),
+                         Err(MqttError::InvalidBroker("Too many brokers"))));
+        
+        let mut config = MqttConfig::default();
+        config.brokers.clear();
+        assert!(matches!(config.validate(), Err(MqttError::InvalidBroker(_))));
+    }
+    
+    #[test]
+    fn test_failover_starts_at_last_good_broker() {
+        assert!(failover_order(0, 3).eq([0, 1, 2]));
+        assert!(failover_order(2, 3).eq([2, 0, 1]));
+        assert_eq!(failover_order(0, 0).count(), 0);
+        
+        let client = MqttClient::new(MqttConfig::default().with_brokers("10.0.0.5,10.0.0.6").unwrap());
+        assert_eq!(client.active_broker().unwrap().ip, Ipv4Addr::new(10, 0, 0, 5));
+        client.set_active_broker(1);
+        assert_eq!(client.active_broker().unwrap().ip, Ipv4Addr::new(10, 0, 0, 6));
+    }
+    
+    #[test]
+    fn test_connack_malformed() {
+        assert!(matches!(parse_connack(&[0x20, 0x02, 0x00]), Err(MqttError::ProtocolError("Truncated CONNACK"))));
+        assert!(matches!(parse_connack(&[0x30, 0x02, 0x00, 0x00]), Err(MqttError::ProtocolError("Expected CONNACK packet"))));
+        assert!(matches!(parse_connack(&[0x20, 0x03, 0x00, 0x00]), Err(MqttError::ProtocolError("Invalid CONNACK remaining length"))));
+        assert!(matches!(parse_connack(&[0x20, 0x02, 0x02, 0x00]), Err(MqttError::ProtocolError("Reserved CONNACK flags set"))));
+    }
+    
+    #[test]
+    fn test_socket_monitor_reports_state_and_traffic() {
+        static MONITOR: SocketMonitor = SocketMonitor::new();
+        let client = MqttClient::new(MqttConfig::default()).with_socket_monitor(&MONITOR);
+        assert_eq!(client.socket_stats(), SocketStats { state: SocketState::Closed, bytes_sent: 0, bytes_received: 0 });
+        
+        client.set_socket_state(SocketState::Connected);
+        client.record_sent(20);
+        client.record_received(4);
+        client.record_sent(u32::MAX as usize);
+        let stats = MONITOR.stats();
+        assert_eq!(stats.state, SocketState::Connected);
+        assert_eq!(stats.bytes_sent, 19, "Counters wrap instead of saturating");
+        assert_eq!(stats.bytes_received, 4);
+        
+        client.disconnect(());
+        assert_eq!(MONITOR.stats().state.as_str(), "closed");
+        
+        let unmonitored = MqttClient::new(MqttConfig::default());
+        unmonitored.record_sent(100);
+        assert_eq!(unmonitored.socket_stats().bytes_sent, 0);
+    }
+    
+    #[test]
+    fn test_mqtt_error_into_iot_error() {
+        use iot_common::{ErrorCategory, MAX_ERROR_MESSAGE_LEN};
+        
+        let cases: [(MqttError, ErrorCategory, u16, &str); 6] = [
+            (MqttError::ConnectionFailed("TCP connect refused"), ErrorCategory::Network, 2004, "Connection failed: TCP connect refused"),
+            (MqttError::BadCredentials, ErrorCategory::Network, 2004, "Broker rejected credentials"),
+            (MqttError::Timeout, ErrorCategory::Network, 2005, "Connect timed out"),
+            (MqttError::InvalidKeepAlive(70_000), ErrorCategory::Configuration, 4004, "Keep-alive 70000s exceeds 65535s"),
+            (MqttError::InvalidBroker("no brokers"), ErrorCategory::Configuration, 4004, "Invalid broker: no brokers"),
+            (MqttError::SerializationError("buffer full"), ErrorCategory::System, 5004, "Serialization error: buffer full"),
+        ];
+        for (mqtt_error, category, code, message) in cases {
+            let error = IoTError::from(mqtt_error);
+            assert_eq!(error.error_category(), category, "{}", message);
+            assert_eq!(error.error_code(), code, "{}", message);
+            let mut text: heapless::String<128> = heapless::String::new();
+            core::fmt::Write::write_fmt(&mut text, format_args!("{}", error)).unwrap();
+            assert!(text.contains(message), "{} missing from {}", message, text);
+        }
+        
+        let long = IoTError::from(MqttError::ConnectionFailed("broker closed the TCP connection before answering CONNECT"));
+        let mut text: heapless::String<128> = heapless::String::new();
+        core::fmt::Write::write_fmt(&mut text, format_args!("{}", long)).unwrap();
+        assert!(text.contains("Connection failed: broker closed"));
+        assert!(text.ends_with("..."), "Long messages are cut at {} bytes: {}", MAX_ERROR_MESSAGE_LEN, text);
+    }
+}
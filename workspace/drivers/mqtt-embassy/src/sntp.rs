@@ -0,0 +1,322 @@
+//! SNTP client providing wall-clock time
+//!
+//! The ESP32-C3 has no battery-backed RTC, so every timestamp on the device is
+//! relative to boot. [`SntpClient`] asks an NTP server for the current time over
+//! UDP through the embassy-net stack and anchors it to `embassy_time::Instant`;
+//! afterwards [`now_unix`] returns Unix seconds without touching the network.
+//!
+//! Run [`SntpClient::run`] from an application task once WiFi is up. It syncs
+//! immediately, retries every [`SntpConfig::retry_interval`] after a failure
+//! and re-syncs every [`SntpConfig::resync_interval`] to correct crystal drift.
+//!
+//! ```rust,no_run
+//! use mqtt_embassy::sntp::{self, SntpClient, SntpConfig};
+//!
+//! #[embassy_executor::task]
+//! async fn ntp_task(stack: embassy_net::Stack<'static>) {
+//!     SntpClient::new(SntpConfig::default()).run(stack).await
+//! }
+//!
+//! // Elsewhere: 0 until the first successful sync
+//! let unix_secs = sntp::now_unix();
+//! ```
+
+use core::cell::Cell;
+use core::net::Ipv4Addr;
+use embassy_net::{IpAddress, IpEndpoint, Stack};
+use embassy_net::udp::{PacketMetadata, UdpSocket};
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_time::{Duration, Instant, Timer, with_timeout};
+use iot_common::TimestampProvider;
+use rtt_target::rprintln;
+
+/// Size of an SNTP packet without extension fields or authenticator
+pub const NTP_PACKET_SIZE: usize = 48;
+
+/// Well-known NTP server port
+pub const NTP_PORT: u16 = 123;
+
+/// Local UDP port the client binds to
+pub const SNTP_LOCAL_PORT: u16 = 50123;
+
+/// Seconds between the NTP epoch (1900) and the Unix epoch (1970)
+const NTP_UNIX_OFFSET_SECS: u64 = 2_208_988_800;
+
+/// Fallback server (time.cloudflare.com) when `NTP_SERVER_IP` is not set
+const DEFAULT_NTP_SERVER: Ipv4Addr = Ipv4Addr::new(162, 159, 200, 1);
+
+/// SNTP client configuration
+#[derive(Debug, Clone)]
+pub struct SntpConfig {
+    /// NTP server address (no DNS in the network stack, so an IP is required)
+    pub server: Ipv4Addr,
+    pub port: u16,
+    /// Time between successful syncs
+    pub resync_interval: Duration,
+    /// Time before the next attempt after a failed sync
+    pub retry_interval: Duration,
+    /// Time allowed for the server's response
+    pub timeout: Duration,
+}
+
+impl Default for SntpConfig {
+    fn default() -> Self {
+        // Server from the environment (e.g. a LAN NTP server), else a public pool
+        let server = option_env!("NTP_SERVER_IP")
+            .and_then(|ip| ip.parse::<Ipv4Addr>().ok())
+            .unwrap_or(DEFAULT_NTP_SERVER);
+
+        Self {
+            server,
+            port: NTP_PORT,
+            resync_interval: Duration::from_secs(3600),
+            retry_interval: Duration::from_secs(30),
+            timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+/// SNTP errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SntpError {
+    /// UDP socket could not be bound or the request not sent
+    Socket(&'static str),
+    /// No response within [`SntpConfig::timeout`]
+    Timeout,
+    /// Response is malformed or does not answer our request
+    InvalidResponse(&'static str),
+    /// Server sent a kiss-of-death packet (stratum 0), e.g. rate limiting
+    KissOfDeath,
+    /// Server reports its own clock as unsynchronized (leap indicator 3)
+    Unsynchronized,
+}
+
+impl core::fmt::Display for SntpError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SntpError::Socket(msg) => write!(f, "Socket error: {}", msg),
+            SntpError::Timeout => write!(f, "NTP server did not respond"),
+            SntpError::InvalidResponse(msg) => write!(f, "Invalid NTP response: {}", msg),
+            SntpError::KissOfDeath => write!(f, "NTP server refused the request (kiss-of-death)"),
+            SntpError::Unsynchronized => write!(f, "NTP server clock is unsynchronized"),
+        }
+    }
+}
+
+/// Result of one successful exchange with the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SntpResponse {
+    /// Server transmit time in milliseconds since the Unix epoch
+    pub unix_ms: u64,
+    /// Server stratum (1 = directly attached to a reference clock)
+    pub stratum: u8,
+}
+
+/// Current clock synchronization state, see [`sync_status`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncStatus {
+    /// Server that provided the last sync
+    pub server: Ipv4Addr,
+    /// Server stratum at the last sync
+    pub stratum: u8,
+    /// Seconds since the last successful sync
+    pub age_secs: u64,
+}
+
+/// Anchor between boot time and Unix time
+#[derive(Debug, Clone, Copy)]
+struct ClockSync {
+    unix_ms: u64,
+    boot_ms: u64,
+    stratum: u8,
+    server: Ipv4Addr,
+}
+
+/// Last successful sync, shared by the SNTP task and every reader
+static CLOCK: Mutex<CriticalSectionRawMutex, Cell<Option<ClockSync>>> = Mutex::new(Cell::new(None));
+
+/// Unix time in seconds, or 0 while the clock has never been synced
+pub fn now_unix() -> u64 {
+    now_unix_ms().map(|ms| ms / 1000).unwrap_or(0)
+}
+
+/// Unix time in milliseconds, `None` while the clock has never been synced
+pub fn now_unix_ms() -> Option<u64> {
+    unix_ms_at(Instant::now().as_millis())
+}
+
+/// Converts a boot-relative timestamp (ms since boot) to Unix milliseconds
+///
+/// Lets readings taken before the first sync, such as an offline backlog,
+/// be stamped once the clock is known.
+pub fn unix_ms_at(boot_ms: u64) -> Option<u64> {
+    CLOCK.lock(|clock| clock.get()).and_then(|sync| {
+        if boot_ms >= sync.boot_ms {
+            Some(sync.unix_ms + (boot_ms - sync.boot_ms))
+        } else {
+            sync.unix_ms.checked_sub(sync.boot_ms - boot_ms)
+        }
+    })
+}
+
+/// [`TimestampProvider`] backed by the last SNTP sync
+///
+/// Monotonic time is `embassy_time::Instant` uptime; the wall clock is
+/// [`unix_ms_at`] and stays `None` until the first sync.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SntpTimestampProvider;
+
+impl TimestampProvider for SntpTimestampProvider {
+    fn get_timestamp_ms(&self) -> u64 {
+        Instant::now().as_millis()
+    }
+
+    fn wall_clock_ms_at(&self, timestamp_ms: u64) -> Option<u64> {
+        unix_ms_at(timestamp_ms)
+    }
+}
+
+/// Whether at least one sync has succeeded since boot
+pub fn is_synced() -> bool {
+    CLOCK.lock(|clock| clock.get()).is_some()
+}
+
+/// Details of the last sync, `None` while not synced
+pub fn sync_status() -> Option<SyncStatus> {
+    CLOCK.lock(|clock| clock.get()).map(|sync| SyncStatus {
+        server: sync.server,
+        stratum: sync.stratum,
+        age_secs: Instant::now().as_millis().saturating_sub(sync.boot_ms) / 1000,
+    })
+}
+
+/// Builds a client-mode (mode 3, version 4) request
+///
+/// `nonce` goes into the transmit timestamp; the server echoes it back as the
+/// originate timestamp, which is how [`parse_response`] matches the answer.
+pub fn build_request(nonce: u64) -> [u8; NTP_PACKET_SIZE] {
+    let mut packet = [0u8; NTP_PACKET_SIZE];
+    packet[0] = (4 << 3) | 3; // LI = 0, VN = 4, Mode = 3 (client)
+    packet[40..48].copy_from_slice(&nonce.to_be_bytes());
+    packet
+}
+
+/// Validates a server response and extracts the transmit time
+///
+/// Rejects packets that are not server-mode replies to `nonce`, kiss-of-death
+/// packets, servers with an unsynchronized clock and out-of-range strata.
+pub fn parse_response(packet: &[u8], nonce: u64) -> Result<SntpResponse, SntpError> {
+    if packet.len() < NTP_PACKET_SIZE {
+        return Err(SntpError::InvalidResponse("packet too short"));
+    }
+
+    let leap_indicator = packet[0] >> 6;
+    let mode = packet[0] & 0x07;
+    let stratum = packet[1];
+
+    if mode != 4 {
+        return Err(SntpError::InvalidResponse("not a server reply"));
+    }
+    if packet[24..32] != nonce.to_be_bytes() {
+        return Err(SntpError::InvalidResponse("originate timestamp mismatch"));
+    }
+    if stratum == 0 {
+        return Err(SntpError::KissOfDeath);
+    }
+    if stratum > 15 {
+        return Err(SntpError::InvalidResponse("stratum out of range"));
+    }
+    if leap_indicator == 3 {
+        return Err(SntpError::Unsynchronized);
+    }
+
+    let seconds = u32::from_be_bytes([packet[40], packet[41], packet[42], packet[43]]) as u64;
+    let fraction = u32::from_be_bytes([packet[44], packet[45], packet[46], packet[47]]) as u64;
+    if seconds == 0 && fraction == 0 {
+        return Err(SntpError::InvalidResponse("missing transmit timestamp"));
+    }
+
+    // NTP seconds wrap in 2036 (era 1); values below the Unix offset belong to it
+    let seconds = if seconds < NTP_UNIX_OFFSET_SECS { seconds + (1 << 32) } else { seconds };
+    let unix_ms = (seconds - NTP_UNIX_OFFSET_SECS) * 1000 + ((fraction * 1000) >> 32);
+
+    Ok(SntpResponse { unix_ms, stratum })
+}
+
+/// SNTP client keeping the shared wall clock in sync
+pub struct SntpClient {
+    config: SntpConfig,
+}
+
+impl SntpClient {
+    /// Creates a client for the given configuration
+    pub fn new(config: SntpConfig) -> Self {
+        Self { config }
+    }
+
+    /// Gets the client configuration
+    pub fn config(&self) -> &SntpConfig {
+        &self.config
+    }
+
+    /// Queries the server once and updates the shared clock on success
+    ///
+    /// Half of the measured round trip is added to the server time to account
+    /// for the network delay of the reply.
+    pub async fn sync(&self, stack: Stack<'_>) -> Result<SntpResponse, SntpError> {
+        let mut rx_meta = [PacketMetadata::EMPTY; 1];
+        let mut tx_meta = [PacketMetadata::EMPTY; 1];
+        let mut rx_buffer = [0u8; 128];
+        let mut tx_buffer = [0u8; 128];
+        let mut socket = UdpSocket::new(stack, &mut rx_meta, &mut rx_buffer, &mut tx_meta, &mut tx_buffer);
+        socket.bind(SNTP_LOCAL_PORT).map_err(|_| SntpError::Socket("bind failed"))?;
+
+        let sent_at = Instant::now();
+        let nonce = sent_at.as_ticks();
+        let server = IpEndpoint::new(IpAddress::Ipv4(self.config.server), self.config.port);
+        socket.send_to(&build_request(nonce), server).await
+            .map_err(|_| SntpError::Socket("send failed"))?;
+
+        let mut packet = [0u8; NTP_PACKET_SIZE];
+        let (len, _) = with_timeout(self.config.timeout, socket.recv_from(&mut packet)).await
+            .map_err(|_| SntpError::Timeout)?
+            .map_err(|_| SntpError::Socket("receive failed"))?;
+        let received_at = Instant::now();
+
+        let mut response = parse_response(&packet[..len], nonce)?;
+        response.unix_ms += (received_at - sent_at).as_millis() / 2;
+
+        CLOCK.lock(|clock| clock.set(Some(ClockSync {
+            unix_ms: response.unix_ms,
+            boot_ms: received_at.as_millis(),
+            stratum: response.stratum,
+            server: self.config.server,
+        })));
+        Ok(response)
+    }
+
+    /// Keeps the clock synced forever
+    ///
+    /// Waits for the network to be configured, then syncs every
+    /// `resync_interval`, or every `retry_interval` after a failure. A failed
+    /// re-sync keeps the previous anchor, so [`now_unix`] stays available.
+    pub async fn run(&self, stack: Stack<'_>) -> ! {
+        rprintln!("[SNTP] Using server {}:{}", self.config.server, self.config.port);
+        loop {
+            stack.wait_config_up().await;
+
+            let delay = match self.sync(stack).await {
+                Ok(response) => {
+                    rprintln!("[SNTP] Clock synced: {} (stratum {})", response.unix_ms / 1000, response.stratum);
+                    self.config.resync_interval
+                }
+                Err(e) => {
+                    rprintln!("[SNTP] WARNING: Sync failed: {}", e);
+                    self.config.retry_interval
+                }
+            };
+            Timer::after(delay).await;
+        }
+    }
+}
@@ -17,9 +17,9 @@ use iot_common::IoTError;
 
 // Import the container trait (when iot-container is available)
 #[cfg(feature = "container")]
-use iot_container::traits::{MessagePublisher, SensorData as ContainerSensorData, DeviceStatus as ContainerDeviceStatus, EmbeddedString};
+use iot_container::traits::{MessagePublisher, HealthCheck, ComponentHealth, SensorData as ContainerSensorData, DeviceStatus as ContainerDeviceStatus, EmbeddedString};
 
-use crate::mqtt_client::{MqttClient, MqttError};
+use crate::mqtt_client::MqttClient;
 use crate::message::{SensorData, DeviceStatus, MqttMessage};
 
 /// Adapter that implements the IoT Container MessagePublisher trait for MqttClient
@@ -201,32 +201,6 @@ impl MqttContainerAdapter {
         })
     }
     
-    /// Converts MQTT errors to IoT errors
-    /// 
-    /// # Arguments
-    /// 
-    /// * `mqtt_error` - MQTT-specific error to convert
-    /// 
-    /// # Returns
-    /// 
-    /// Standardized IoT error
-    fn convert_error(&self, mqtt_error: MqttError) -> IoTError {
-        match mqtt_error {
-            MqttError::ConnectionFailed(msg) => {
-                IoTError::Network(iot_common::NetworkError::ConnectionFailed(msg))
-            }
-            MqttError::ProtocolError(msg) => {
-                IoTError::Network(iot_common::NetworkError::ProtocolError(msg))
-            }
-            MqttError::IoError(msg) => {
-                IoTError::Network(iot_common::NetworkError::TransmissionFailed(msg))
-            }
-            MqttError::SerializationError(msg) => {
-                IoTError::System(iot_common::SystemError::SerializationFailed(msg))
-            }
-        }
-    }
-    
     /// Checks if heartbeat should be sent
     /// 
     /// # Returns
@@ -521,6 +495,28 @@ impl MessagePublisher for MqttContainerAdapter {
     }
 }
 
+#[cfg(feature = "container")]
+impl HealthCheck for MqttContainerAdapter {
+    /// Reports publisher health from the connection state and publish counters
+    fn health(&self) -> ComponentHealth {
+        if !self.is_connected {
+            return ComponentHealth::failed("MQTT disconnected");
+        }
+        
+        let heartbeat_overdue = self.last_heartbeat_time.map_or(false, |last| {
+            Instant::now().as_millis().saturating_sub(last) > self.heartbeat_interval_ms * 2
+        });
+        
+        if self.publish_failures > self.messages_published {
+            ComponentHealth::degraded("MQTT publish failures exceed successes")
+        } else if heartbeat_overdue {
+            ComponentHealth::degraded("MQTT heartbeat overdue")
+        } else {
+            ComponentHealth::healthy("MQTT connected")
+        }
+    }
+}
+
 // Convenience functions for creating container-compatible MQTT instances
 
 /// Creates a new MQTT message publisher for use with the IoT container
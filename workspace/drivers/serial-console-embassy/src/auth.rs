@@ -0,0 +1,341 @@
+//! Console authentication
+//!
+//! Optional password gate for privileged console commands. When no password
+//! is configured the console stays open, matching the previous behaviour.
+//! Failed logins are rate-limited with a delay that doubles on every miss.
+
+use embassy_time::{Duration, Instant};
+use heapless::String;
+
+use crate::config::MAX_PASSWORD_LEN;
+
+/// Configuration name used to persist the console password in a `ConfigStore`
+pub const CONSOLE_AUTH_CONFIG: &str = "console_auth";
+
+/// Delay imposed after the first failed login
+const BASE_LOGIN_DELAY_MS: u64 = 1_000;
+/// Upper bound for the login delay
+const MAX_LOGIN_DELAY_MS: u64 = 60_000;
+
+/// Outcome of a login attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginResult {
+    /// Password accepted, privileged commands unlocked
+    Success,
+    /// No password is configured, the console is always unlocked
+    NotRequired,
+    /// Wrong password; the next attempt is accepted after `retry_after`
+    Rejected { retry_after: Duration },
+    /// Attempt made while still inside the delay window
+    TooSoon { remaining: Duration },
+}
+
+/// Password state and failed-attempt tracking for the console
+#[derive(Debug, Clone)]
+pub struct ConsoleAuth {
+    password: Option<String<MAX_PASSWORD_LEN>>,
+    authenticated: bool,
+    failed_attempts: u8,
+    locked_until: Option<Instant>,
+}
+
+impl ConsoleAuth {
+    /// Create an open console with no password configured
+    pub fn new() -> Self {
+        Self {
+            password: None,
+            authenticated: false,
+            failed_attempts: 0,
+            locked_until: None,
+        }
+    }
+
+    /// Create a console gated by `password`
+    ///
+    /// An empty password leaves the console open. Returns `None` if the
+    /// password is longer than [`MAX_PASSWORD_LEN`].
+    pub fn with_password(password: &str) -> Option<Self> {
+        let mut auth = Self::new();
+        auth.set_password(Some(password)).then_some(auth)
+    }
+
+    /// Replace the configured password and lock the console again
+    ///
+    /// `None` or an empty password opens the console. Returns false, keeping
+    /// the current password, if `password` is longer than [`MAX_PASSWORD_LEN`].
+    pub fn set_password(&mut self, password: Option<&str>) -> bool {
+        let password = match password.filter(|p| !p.is_empty()) {
+            Some(p) => match String::try_from(p) {
+                Ok(p) => Some(p),
+                Err(_) => return false,
+            },
+            None => None,
+        };
+        self.password = password;
+        self.logout();
+        self.failed_attempts = 0;
+        self.locked_until = None;
+        true
+    }
+
+    /// Configured password, if any
+    pub fn password(&self) -> Option<&str> {
+        self.password.as_deref()
+    }
+
+    /// Check if a password is configured
+    pub fn is_enabled(&self) -> bool {
+        self.password.is_some()
+    }
+
+    /// Check if privileged commands are currently allowed
+    pub fn is_authenticated(&self) -> bool {
+        !self.is_enabled() || self.authenticated
+    }
+
+    /// Attempt to unlock the console
+    pub fn login(&mut self, attempt: &str, now: Instant) -> LoginResult {
+        let Some(password) = &self.password else {
+            return LoginResult::NotRequired;
+        };
+
+        if let Some(until) = self.locked_until {
+            if now < until {
+                return LoginResult::TooSoon { remaining: until - now };
+            }
+        }
+
+        if constant_time_eq(password.as_bytes(), attempt.as_bytes()) {
+            self.authenticated = true;
+            self.failed_attempts = 0;
+            self.locked_until = None;
+            return LoginResult::Success;
+        }
+
+        self.authenticated = false;
+        self.failed_attempts = self.failed_attempts.saturating_add(1);
+        let retry_after = Self::delay_for(self.failed_attempts);
+        self.locked_until = Some(now + retry_after);
+        LoginResult::Rejected { retry_after }
+    }
+
+    /// Lock privileged commands again
+    pub fn logout(&mut self) {
+        self.authenticated = false;
+    }
+
+    /// Number of consecutive failed attempts
+    pub fn failed_attempts(&self) -> u8 {
+        self.failed_attempts
+    }
+
+    /// Delay after `failures` consecutive misses: 1s, 2s, 4s, ... capped at 60s
+    fn delay_for(failures: u8) -> Duration {
+        let shift = u32::from(failures.saturating_sub(1)).min(16);
+        Duration::from_millis((BASE_LOGIN_DELAY_MS << shift).min(MAX_LOGIN_DELAY_MS))
+    }
+}
+
+impl Default for ConsoleAuth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compare without short-circuiting on the first mismatching byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Persisted form of the console password
+#[cfg(feature = "storage")]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ConsoleAuthConfig {
+    pub password: String<MAX_PASSWORD_LEN>,
+}
+
+#[cfg(feature = "storage")]
+impl ConsoleAuth {
+    /// Load the console password from a config store
+    ///
+    /// Returns an open console when no password has been stored.
+    pub async fn load<S>(store: &mut S) -> iot_storage::StorageResult<Self>
+    where
+        S: iot_storage::ConfigStorage,
+    {
+        if !store.config_exists(CONSOLE_AUTH_CONFIG).await? {
+            return Ok(Self::new());
+        }
+
+        let config: ConsoleAuthConfig = store.retrieve_config(CONSOLE_AUTH_CONFIG).await?;
+        let mut auth = Self::new();
+        auth.password = Some(config.password).filter(|p| !p.is_empty());
+        Ok(auth)
+    }
+
+    /// Replace the password and persist it, see [`set_password`](Self::set_password)
+    ///
+    /// An overlong password fails with `CapacityExceeded`. On any error the
+    /// password in use and the stored one stay as they were.
+    pub async fn store_password<S>(&mut self, password: Option<&str>, store: &mut S) -> iot_storage::StorageResult<()>
+    where
+        S: iot_storage::ConfigStorage,
+    {
+        let mut updated = self.clone();
+        if !updated.set_password(password) {
+            return Err(iot_storage::StorageError::CapacityExceeded);
+        }
+        updated.save(store).await?;
+        *self = updated;
+        Ok(())
+    }
+
+    /// Persist the console password, removing it when authentication is disabled
+    pub async fn save<S>(&self, store: &mut S) -> iot_storage::StorageResult<()>
+    where
+        S: iot_storage::ConfigStorage,
+    {
+        match &self.password {
+            Some(password) => {
+                let config = ConsoleAuthConfig { password: password.clone() };
+                store.store_config(CONSOLE_AUTH_CONFIG, &config).await
+            }
+            None => {
+                if store.config_exists(CONSOLE_AUTH_CONFIG).await? {
+                    store.delete_config(CONSOLE_AUTH_CONFIG).await?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: u64) -> Instant {
+        Instant::from_secs(secs)
+    }
+
+    #[test]
+    fn test_open_without_password() {
+        let mut auth = ConsoleAuth::new();
+        assert!(auth.is_authenticated());
+        assert_eq!(auth.login("anything", at(0)), LoginResult::NotRequired);
+
+        // An empty password disables the gate
+        let auth = ConsoleAuth::with_password("").unwrap();
+        assert!(!auth.is_enabled());
+    }
+
+    #[test]
+    fn test_login_accepts_and_logout_locks() {
+        let mut auth = ConsoleAuth::with_password("secret").unwrap();
+        assert!(!auth.is_authenticated());
+
+        assert_eq!(auth.login("secret", at(0)), LoginResult::Success);
+        assert!(auth.is_authenticated());
+
+        auth.logout();
+        assert!(!auth.is_authenticated());
+    }
+
+    #[test]
+    fn test_login_rejects_wrong_password() {
+        let mut auth = ConsoleAuth::with_password("secret").unwrap();
+
+        assert_eq!(auth.login("Secret", at(0)), LoginResult::Rejected { retry_after: Duration::from_secs(1) });
+        assert_eq!(auth.login("secre", at(1)), LoginResult::Rejected { retry_after: Duration::from_secs(2) });
+        assert_eq!(auth.failed_attempts(), 2);
+        assert!(!auth.is_authenticated());
+    }
+
+    #[test]
+    fn test_lockout_delay_doubles_and_refuses_early_attempts() {
+        let mut auth = ConsoleAuth::with_password("secret").unwrap();
+
+        assert_eq!(auth.login("wrong", at(0)), LoginResult::Rejected { retry_after: Duration::from_secs(1) });
+        assert_eq!(auth.login("wrong", at(1)), LoginResult::Rejected { retry_after: Duration::from_secs(2) });
+
+        // Even the right password is refused inside the delay window
+        assert_eq!(auth.login("secret", at(2)), LoginResult::TooSoon { remaining: Duration::from_secs(1) });
+        assert!(!auth.is_authenticated());
+        assert_eq!(auth.failed_attempts(), 2);
+
+        assert_eq!(auth.login("secret", at(3)), LoginResult::Success);
+        assert_eq!(auth.failed_attempts(), 0);
+    }
+
+    #[test]
+    fn test_lockout_delay_is_capped() {
+        let mut auth = ConsoleAuth::with_password("secret").unwrap();
+        let mut now = 0;
+        let mut last = Duration::from_secs(0);
+        for _ in 0..20 {
+            let LoginResult::Rejected { retry_after } = auth.login("wrong", at(now)) else {
+                panic!("attempt outside the delay window was not judged");
+            };
+            last = retry_after;
+            now += retry_after.as_secs();
+        }
+        assert_eq!(last, Duration::from_millis(MAX_LOGIN_DELAY_MS));
+    }
+
+    #[test]
+    fn test_overlong_password_rejected() {
+        let long = "x".repeat(MAX_PASSWORD_LEN + 1);
+        assert!(ConsoleAuth::with_password(&long).is_none());
+
+        let mut auth = ConsoleAuth::with_password("secret").unwrap();
+        assert!(!auth.set_password(Some(&long)));
+        assert_eq!(auth.password(), Some("secret"));
+        assert!(auth.is_enabled());
+
+        assert!(auth.set_password(Some(&long[..MAX_PASSWORD_LEN])));
+        assert_eq!(auth.password().map(str::len), Some(MAX_PASSWORD_LEN));
+    }
+
+    #[test]
+    fn test_set_password_locks_and_clears_failures() {
+        let mut auth = ConsoleAuth::with_password("secret").unwrap();
+        let _ = auth.login("wrong", at(0));
+        assert!(auth.set_password(Some("changed")));
+
+        assert_eq!(auth.failed_attempts(), 0);
+        assert_eq!(auth.login("changed", at(0)), LoginResult::Success);
+
+        assert!(auth.set_password(None));
+        assert!(!auth.is_enabled());
+        assert!(auth.is_authenticated());
+    }
+
+    #[cfg(feature = "storage")]
+    #[test]
+    fn test_store_password_round_trip() {
+        use iot_common::network::block_on;
+        use iot_storage::{ConfigStorage, ConfigStore, MockStorage, StorageError};
+
+        let mut backend = MockStorage::new();
+        let mut config = ConfigStore::new(&backend).unwrap();
+        let mut store = config.bind(&mut backend);
+        let mut auth = ConsoleAuth::new();
+
+        block_on(auth.store_password(Some("secret"), &mut store)).unwrap();
+        assert_eq!(auth.password(), Some("secret"));
+        let loaded = block_on(ConsoleAuth::load(&mut store)).unwrap();
+        assert_eq!(loaded.password(), Some("secret"));
+
+        let long = "x".repeat(MAX_PASSWORD_LEN + 1);
+        assert_eq!(block_on(auth.store_password(Some(&long), &mut store)), Err(StorageError::CapacityExceeded));
+        assert_eq!(auth.password(), Some("secret"));
+        assert_eq!(block_on(ConsoleAuth::load(&mut store)).unwrap().password(), Some("secret"));
+
+        block_on(auth.store_password(None, &mut store)).unwrap();
+        assert!(!block_on(store.config_exists(CONSOLE_AUTH_CONFIG)).unwrap());
+        assert!(!block_on(ConsoleAuth::load(&mut store)).unwrap().is_enabled());
+    }
+}
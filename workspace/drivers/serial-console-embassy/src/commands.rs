@@ -3,8 +3,13 @@
 //! Defines the command structure and implements parsers for various
 //! system commands including help, status, configuration, etc.
 
+use core::fmt::Write;
+use embassy_time::Instant;
 use heapless::String;
+use crate::auth::{ConsoleAuth, LoginResult};
 use crate::config::{SystemConfig, MAX_SSID_LEN, MAX_PASSWORD_LEN, MAX_IP_LEN, MAX_HOSTNAME_LEN};
+#[cfg(feature = "storage")]
+use crate::config::{ConsoleSettings, CONSOLE_CONFIG};
 
 /// Maximum number of command arguments
 pub const MAX_ARGS: usize = 4;
@@ -42,21 +47,175 @@ pub enum Command {
     Save,
     /// Load configuration from flash
     Load,
+    /// Restore the previously saved configuration
+    ConfigRollback,
     /// Clear screen
     Clear,
+    /// Switch between text and JSON output
+    SetOutputMode(OutputMode),
+    /// Unlock privileged commands
+    Login(String<MAX_PASSWORD_LEN>),
+    /// Lock privileged commands again
+    Logout,
+    /// Set the console password, `None` removes it
+    SetConsolePassword(Option<String<MAX_PASSWORD_LEN>>),
     /// Unknown command
     Unknown(String<MAX_CMD_LEN>),
 }
 
+impl Command {
+    /// Check if the command changes configuration or system state and needs a login
+    pub fn requires_auth(&self) -> bool {
+        matches!(
+            self,
+            Command::SetWifiSsid(_)
+                | Command::SetWifiPassword(_)
+                | Command::ShowWifi
+                | Command::SetMqttBroker(_)
+                | Command::SetMqttPort(_)
+                | Command::SetMqttClientId(_)
+                | Command::SetMqttPrefix(_)
+                | Command::Restart
+                | Command::Save
+                | Command::Load
+                | Command::ConfigRollback
+                | Command::SetConsolePassword(_)
+        )
+    }
+}
+
+/// How command results are rendered on the console
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputMode {
+    /// Human-readable banners (default for interactive use)
+    #[default]
+    Text,
+    /// Compact single-line JSON objects for scripts
+    Json,
+}
+
+/// Snapshot of the connectivity state reported by `status`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusReport {
+    pub wifi_connected: bool,
+    pub mqtt_connected: bool,
+    pub sensor_active: bool,
+    pub current_ip: Option<String<MAX_IP_LEN>>,
+}
+
+/// Static platform details reported by `info`
+#[derive(Debug, Clone, PartialEq)]
+pub struct InfoReport {
+    pub chip: &'static str,
+    pub framework: &'static str,
+    pub build: &'static str,
+    pub free_heap_kb: u32,
+}
+
+/// Structured result of a command, rendered as text or JSON
+// No heap here to box the message variant; it is rendered and dropped immediately.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandOutput {
+    /// Free-form message that has no structured form
+    Message(String<512>),
+    /// Result of the `status` command
+    Status(StatusReport),
+    /// Result of the `info` command
+    Info(InfoReport),
+}
+
+impl CommandOutput {
+    /// Render the result for the given output mode
+    pub fn render(&self, mode: OutputMode) -> String<512> {
+        let mut out = String::new();
+        match (self, mode) {
+            (CommandOutput::Message(text), OutputMode::Text) => {
+                let _ = out.push_str(text);
+            },
+            (CommandOutput::Message(text), OutputMode::Json) => {
+                let _ = out.push_str("\r\n{\"message\":");
+                push_json_str(&mut out, text.trim());
+                let _ = out.push_str("}\r\n");
+            },
+            (CommandOutput::Status(status), OutputMode::Text) => {
+                let _ = out.push_str("\r\n=== System Status ===\r\n");
+                let _ = out.push_str("WiFi: ");
+                if status.wifi_connected {
+                    let _ = out.push_str("Connected");
+                    if let Some(ip) = &status.current_ip {
+                        let _ = write!(out, " ({})", ip);
+                    }
+                } else {
+                    let _ = out.push_str("Disconnected");
+                }
+                let _ = write!(
+                    out,
+                    "\r\nMQTT: {}\r\nSensor: {}\r\n",
+                    if status.mqtt_connected { "Connected" } else { "Disconnected" },
+                    if status.sensor_active { "Active" } else { "Inactive" },
+                );
+            },
+            (CommandOutput::Status(status), OutputMode::Json) => {
+                let _ = write!(
+                    out,
+                    "\r\n{{\"wifi_connected\":{},\"mqtt_connected\":{},\"sensor_active\":{},\"ip\":",
+                    status.wifi_connected, status.mqtt_connected, status.sensor_active,
+                );
+                match &status.current_ip {
+                    Some(ip) => push_json_str(&mut out, ip),
+                    None => { let _ = out.push_str("null"); },
+                }
+                let _ = out.push_str("}\r\n");
+            },
+            (CommandOutput::Info(info), OutputMode::Text) => {
+                let _ = write!(
+                    out,
+                    "\r\n=== System Information ===\r\nChip: {}\r\nFramework: {}\r\nBuild: {}\r\nFree Heap: {}KB\r\n",
+                    info.chip, info.framework, info.build, info.free_heap_kb,
+                );
+            },
+            (CommandOutput::Info(info), OutputMode::Json) => {
+                let _ = write!(
+                    out,
+                    "\r\n{{\"chip\":\"{}\",\"framework\":\"{}\",\"build\":\"{}\",\"free_heap_kb\":{}}}\r\n",
+                    info.chip, info.framework, info.build, info.free_heap_kb,
+                );
+            },
+        }
+        out
+    }
+}
+
+/// Append `value` as a quoted JSON string, escaping quotes, backslashes and control characters
+fn push_json_str<const N: usize>(out: &mut String<N>, value: &str) {
+    let _ = out.push('"');
+    for c in value.chars() {
+        let _ = match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\r' => out.push_str("\\r"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).map_err(|_| ()),
+            c => out.push(c).map_err(|_| ()),
+        };
+    }
+    let _ = out.push('"');
+}
+
 /// Command line parser and handler
 pub struct CommandHandler {
     config: SystemConfig,
+    output_mode: OutputMode,
+    auth: ConsoleAuth,
 }
 
 impl CommandHandler {
     pub fn new() -> Self {
         Self {
             config: SystemConfig::new(),
+            output_mode: OutputMode::Text,
+            auth: ConsoleAuth::new(),
         }
     }
     
@@ -84,6 +243,51 @@ impl CommandHandler {
             "restart" | "reset" => Command::Restart,
             "save" => Command::Save,
             "load" => Command::Load,
+            "logout" => Command::Logout,
+            "config" => {
+                match parts.next() {
+                    Some("rollback") => Command::ConfigRollback,
+                    _ => {
+                        let mut err_str = String::new();
+                        let _ = err_str.push_str("Usage: config rollback");
+                        Command::Unknown(err_str)
+                    }
+                }
+            },
+            "login" => {
+                let mut pass_str = String::new();
+                let _ = pass_str.push_str(parts.next().unwrap_or(""));
+                Command::Login(pass_str)
+            },
+            "passwd" => {
+                match parts.next() {
+                    Some("off") => Command::SetConsolePassword(None),
+                    Some(password) => match String::try_from(password) {
+                        Ok(pass_str) => Command::SetConsolePassword(Some(pass_str)),
+                        Err(_) => {
+                            let mut err_str = String::new();
+                            let _ = write!(err_str, "Password too long (max {} characters)", MAX_PASSWORD_LEN);
+                            Command::Unknown(err_str)
+                        }
+                    },
+                    None => {
+                        let mut err_str = String::new();
+                        let _ = err_str.push_str("Usage: passwd <password>|off");
+                        Command::Unknown(err_str)
+                    }
+                }
+            },
+            "json" => {
+                match parts.next() {
+                    Some("on") => Command::SetOutputMode(OutputMode::Json),
+                    Some("off") => Command::SetOutputMode(OutputMode::Text),
+                    _ => {
+                        let mut err_str = String::new();
+                        let _ = err_str.push_str("Usage: json on|off");
+                        Command::Unknown(err_str)
+                    }
+                }
+            },
             "wifi" => {
                 match parts.next() {
                     Some("show") | None => Command::ShowWifi,
@@ -162,10 +366,20 @@ impl CommandHandler {
         }
     }
     
-    /// Execute a command and return response
+    /// Execute a command and return the response rendered in the current output mode
     pub fn execute_command(&mut self, cmd: Command) -> String<512> {
+        self.execute(cmd).render(self.output_mode)
+    }
+    
+    /// Execute a command and return its structured result
+    pub fn execute(&mut self, cmd: Command) -> CommandOutput {
         let mut response = String::new();
         
+        if cmd.requires_auth() && !self.auth.is_authenticated() {
+            let _ = response.push_str("\r\nAccess denied - use 'login <password>' first\r\n");
+            return CommandOutput::Message(response);
+        }
+        
         match cmd {
             Command::Help => {
                 let _ = response.push_str(
@@ -178,6 +392,11 @@ impl CommandHandler {
                      restart, reset   - Restart system\r\n\
                      save             - Save config to flash\r\n\
                      load             - Load config from flash\r\n\
+                     config rollback  - Restore previous saved config\r\n\
+                     json on|off      - Toggle JSON output for scripts\r\n\
+                     login <pwd>      - Unlock privileged commands\r\n\
+                     logout           - Lock privileged commands\r\n\
+                     passwd <pwd>|off - Set or remove the console password\r\n\
                      \r\n\
                      WiFi commands:\r\n\
                      wifi show        - Show WiFi config\r\n\
@@ -195,46 +414,22 @@ impl CommandHandler {
             },
             
             Command::Status => {
-                let _ = response.push_str("\r\n=== System Status ===\r\n");
-                let _ = response.push_str("WiFi: ");
-                if self.config.system.wifi_connected {
-                    let _ = response.push_str("Connected");
-                    if let Some(ip) = &self.config.system.current_ip {
-                        let _ = response.push_str(" (");
-                        let _ = response.push_str(ip);
-                        let _ = response.push_str(")");
-                    }
-                } else {
-                    let _ = response.push_str("Disconnected");
-                }
-                let _ = response.push_str("\r\n");
-                
-                let _ = response.push_str("MQTT: ");
-                if self.config.system.mqtt_connected {
-                    let _ = response.push_str("Connected");
-                } else {
-                    let _ = response.push_str("Disconnected");
-                }
-                let _ = response.push_str("\r\n");
-                
-                let _ = response.push_str("Sensor: ");
-                if self.config.system.sensor_active {
-                    let _ = response.push_str("Active");
-                } else {
-                    let _ = response.push_str("Inactive");
-                }
-                let _ = response.push_str("\r\n");
+                return CommandOutput::Status(StatusReport {
+                    wifi_connected: self.config.system.wifi_connected,
+                    mqtt_connected: self.config.system.mqtt_connected,
+                    sensor_active: self.config.system.sensor_active,
+                    current_ip: self.config.system.current_ip.clone(),
+                });
             },
             
             Command::Info => {
-                let _ = response.push_str("\r\n=== System Information ===\r\n");
                 // This would be filled with actual system info
-                let _ = response.push_str("Chip: ESP32-C3\r\n");
-                let _ = response.push_str("Framework: Embassy\r\n");
-                let _ = response.push_str("Build: Release\r\n");
-                let _ = response.push_str("Free Heap: ");
-                // Add actual heap info here
-                let _ = response.push_str("48KB\r\n");
+                return CommandOutput::Info(InfoReport {
+                    chip: "ESP32-C3",
+                    framework: "Embassy",
+                    build: "Release",
+                    free_heap_kb: 48,
+                });
             },
             
             Command::SetWifiSsid(ssid) => {
@@ -326,14 +521,48 @@ impl CommandHandler {
                 // TODO: Implement actual restart
             },
             
-            Command::Save => {
-                let _ = response.push_str("\r\nConfiguration saved to flash\r\n");
-                // TODO: Implement flash save
+            Command::Save | Command::Load | Command::ConfigRollback => {
+                // Needs a config store, see `execute_with_store`
+                let _ = response.push_str("\r\nUnavailable: no flash storage attached\r\n");
+            },
+            
+            Command::SetOutputMode(mode) => {
+                self.output_mode = mode;
+                let _ = response.push_str(match mode {
+                    OutputMode::Json => "\r\nOutput mode: json\r\n",
+                    OutputMode::Text => "\r\nOutput mode: text\r\n",
+                });
             },
             
-            Command::Load => {
-                let _ = response.push_str("\r\nConfiguration loaded from flash\r\n");
-                // TODO: Implement flash load
+            Command::Login(password) => {
+                match self.auth.login(&password, Instant::now()) {
+                    LoginResult::Success => {
+                        let _ = response.push_str("\r\nLogin successful\r\n");
+                    },
+                    LoginResult::NotRequired => {
+                        let _ = response.push_str("\r\nNo console password configured\r\n");
+                    },
+                    LoginResult::Rejected { retry_after } => {
+                        let _ = write!(response, "\r\nLogin failed - retry in {}s\r\n", retry_after.as_secs());
+                    },
+                    LoginResult::TooSoon { remaining } => {
+                        let _ = write!(response, "\r\nToo many attempts - wait {}s\r\n", remaining.as_secs().max(1));
+                    },
+                }
+            },
+            
+            Command::Logout => {
+                self.auth.logout();
+                let _ = response.push_str("\r\nLogged out\r\n");
+            },
+            
+            Command::SetConsolePassword(password) => {
+                // The parser already bounded the length, so this cannot fail
+                let _ = self.auth.set_password(password.as_deref());
+                let _ = response.push_str(match password {
+                    Some(_) => "\r\nConsole password set until restart (no flash storage attached)\r\n",
+                    None => "\r\nConsole password removed until restart (no flash storage attached)\r\n",
+                });
             },
             
             Command::Unknown(cmd) => {
@@ -343,7 +572,22 @@ impl CommandHandler {
             },
         }
         
-        response
+        CommandOutput::Message(response)
+    }
+    
+    /// Current output mode
+    pub fn output_mode(&self) -> OutputMode {
+        self.output_mode
+    }
+    
+    /// Console authentication state
+    pub fn auth(&self) -> &ConsoleAuth {
+        &self.auth
+    }
+    
+    /// Replace the console authentication settings (e.g. after loading them from storage)
+    pub fn set_auth(&mut self, auth: ConsoleAuth) {
+        self.auth = auth;
     }
     
     /// Get current configuration
@@ -365,4 +609,201 @@ impl CommandHandler {
             self.config.system.current_ip = None;
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "storage")]
+impl CommandHandler {
+    /// Execute a command, running `save`, `load`, `config rollback` and `passwd` against a config store
+    ///
+    /// The console settings are kept under [`CONSOLE_CONFIG`], the password
+    /// under [`CONSOLE_AUTH_CONFIG`](crate::CONSOLE_AUTH_CONFIG); every other
+    /// command behaves exactly as with [`execute`](Self::execute).
+    pub async fn execute_with_store<B>(
+        &mut self,
+        cmd: Command,
+        store: &mut iot_storage::BoundConfigStore<'_, B>,
+    ) -> CommandOutput
+    where
+        B: iot_storage::StorageBackend + Send + Sync,
+    {
+        use iot_storage::ConfigStorage;
+
+        let is_storage_cmd = matches!(
+            cmd,
+            Command::Save | Command::Load | Command::ConfigRollback | Command::SetConsolePassword(_)
+        );
+        if !is_storage_cmd || (cmd.requires_auth() && !self.auth.is_authenticated()) {
+            return self.execute(cmd);
+        }
+
+        let result = match cmd {
+            Command::Save => {
+                let stored = ConsoleSettings {
+                    wifi: self.config.wifi.clone(),
+                    mqtt: self.config.mqtt.clone(),
+                };
+                store.store_config(CONSOLE_CONFIG, &stored).await
+                    .map(|_| "Configuration saved to flash")
+            },
+            Command::Load => self.load_config(store).await
+                .map(|_| "Configuration loaded from flash"),
+            Command::SetConsolePassword(password) => self.auth.store_password(password.as_deref(), store).await
+                .map(|_| match password {
+                    Some(_) => "Console password saved - use 'login <password>'",
+                    None => "Console password removed",
+                }),
+            _ if !store.has_previous(CONSOLE_CONFIG) => Ok("No previous configuration to restore"),
+            _ => match store.rollback(CONSOLE_CONFIG).await {
+                Ok(()) => self.load_config(store).await
+                    .map(|_| "Previous configuration restored from flash"),
+                Err(e) => Err(e),
+            },
+        };
+
+        let mut response = String::new();
+        match result {
+            Ok(message) => {
+                let _ = write!(response, "\r\n{}\r\n", message);
+            },
+            Err(e) => {
+                let _ = write!(response, "\r\nFlash error: {:?}\r\n", e);
+            },
+        }
+        CommandOutput::Message(response)
+    }
+
+    /// Replace the WiFi and MQTT settings with the stored ones
+    async fn load_config<S>(&mut self, store: &mut S) -> iot_storage::StorageResult<()>
+    where
+        S: iot_storage::ConfigStorage,
+    {
+        let stored: ConsoleSettings = store.retrieve_config(CONSOLE_CONFIG).await?;
+        self.config.wifi = stored.wifi;
+        self.config.mqtt = stored.mqtt;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(output: CommandOutput) -> String<512> {
+        output.render(OutputMode::Text)
+    }
+
+    fn json_str(value: &str) -> String<128> {
+        let mut out = String::new();
+        push_json_str(&mut out, value);
+        out
+    }
+
+    #[test]
+    fn test_json_str_escapes_quotes_and_backslashes() {
+        assert_eq!(json_str("plain"), "\"plain\"");
+        assert_eq!(json_str("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(json_str("C:\\temp"), "\"C:\\\\temp\"");
+    }
+
+    #[test]
+    fn test_json_str_escapes_control_characters() {
+        assert_eq!(json_str("a\r\nb"), "\"a\\r\\nb\"");
+        assert_eq!(json_str("tab\there"), "\"tab\\u0009here\"");
+        assert_eq!(json_str("\x01\x1f"), "\"\\u0001\\u001f\"");
+        // DEL is not a JSON control character
+        assert_eq!(json_str("\x7f"), "\"\x7f\"");
+    }
+
+    #[test]
+    fn test_json_str_keeps_non_ascii() {
+        assert_eq!(json_str("23.5°C"), "\"23.5°C\"");
+        assert_eq!(json_str("Zürich 東京"), "\"Zürich 東京\"");
+    }
+
+    #[test]
+    fn test_render_message() {
+        let output = CommandOutput::Message(String::try_from("\r\nSaved \"wifi\"\r\n").unwrap());
+
+        assert_eq!(output.render(OutputMode::Text), "\r\nSaved \"wifi\"\r\n");
+        // Surrounding line breaks are trimmed, inner quotes escaped
+        assert_eq!(output.render(OutputMode::Json), "\r\n{\"message\":\"Saved \\\"wifi\\\"\"}\r\n");
+    }
+
+    #[test]
+    fn test_render_status_json() {
+        let mut status = StatusReport {
+            wifi_connected: true,
+            mqtt_connected: false,
+            sensor_active: true,
+            current_ip: Some(String::try_from("192.168.1.20").unwrap()),
+        };
+        assert_eq!(
+            CommandOutput::Status(status.clone()).render(OutputMode::Json),
+            "\r\n{\"wifi_connected\":true,\"mqtt_connected\":false,\"sensor_active\":true,\"ip\":\"192.168.1.20\"}\r\n"
+        );
+
+        status.current_ip = None;
+        assert!(CommandOutput::Status(status).render(OutputMode::Json).ends_with("\"ip\":null}\r\n"));
+    }
+
+    #[test]
+    fn test_render_info_json() {
+        let info = InfoReport { chip: "ESP32-C3", framework: "Embassy", build: "0.1.0", free_heap_kb: 48 };
+
+        assert_eq!(
+            CommandOutput::Info(info).render(OutputMode::Json),
+            "\r\n{\"chip\":\"ESP32-C3\",\"framework\":\"Embassy\",\"build\":\"0.1.0\",\"free_heap_kb\":48}\r\n"
+        );
+    }
+
+    #[test]
+    fn test_passwd_parsing() {
+        let handler = CommandHandler::new();
+
+        assert_eq!(handler.parse_command("passwd off"), Command::SetConsolePassword(None));
+        assert_eq!(handler.parse_command("passwd s3cret"),
+                   Command::SetConsolePassword(Some(String::try_from("s3cret").unwrap())));
+        assert!(matches!(handler.parse_command("passwd"), Command::Unknown(_)));
+
+        let long = "x".repeat(MAX_PASSWORD_LEN + 1);
+        let Command::Unknown(error) = handler.parse_command(&alloc::format!("passwd {}", long)) else {
+            panic!("overlong password was accepted");
+        };
+        assert!(error.starts_with("Password too long"));
+    }
+
+    #[test]
+    fn test_passwd_locks_privileged_commands() {
+        let mut handler = CommandHandler::new();
+
+        let response = message(handler.execute(handler.parse_command("passwd s3cret")));
+        assert!(response.contains("Console password set"));
+        assert!(message(handler.execute(Command::Restart)).contains("Access denied"));
+
+        // Changing or removing the password needs a login first
+        assert!(message(handler.execute(Command::SetConsolePassword(None))).contains("Access denied"));
+        let _ = handler.execute(handler.parse_command("login s3cret"));
+        let _ = handler.execute(Command::SetConsolePassword(None));
+        assert!(!handler.auth().is_enabled());
+    }
+
+    #[cfg(feature = "storage")]
+    #[test]
+    fn test_passwd_saved_through_config_store() {
+        use iot_common::network::block_on;
+        use iot_storage::{ConfigStore, MockStorage};
+
+        let mut backend = MockStorage::new();
+        let mut config = ConfigStore::new(&backend).unwrap();
+        let mut store = config.bind(&mut backend);
+        let mut handler = CommandHandler::new();
+
+        let command = handler.parse_command("passwd s3cret");
+        let response = message(block_on(handler.execute_with_store(command, &mut store)));
+        assert!(response.contains("Console password saved"));
+        assert!(!handler.auth().is_authenticated());
+
+        let loaded = block_on(ConsoleAuth::load(&mut store)).unwrap();
+        assert_eq!(loaded.password(), Some("s3cret"));
+    }
+}
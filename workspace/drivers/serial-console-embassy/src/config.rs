@@ -2,8 +2,28 @@
 //! 
 //! Defines data structures for storing and managing system configuration
 //! including WiFi credentials, MQTT settings, and system parameters.
+//!
+//! [`FlashConfig`] is the fixed 256-byte block persisted to flash:
+//!
+//! | Offset | Size | Field                                  |
+//! |--------|------|----------------------------------------|
+//! | 0      | 2    | magic `0xCA 0xFE`                      |
+//! | 2      | 3    | SSID, password, broker IP lengths      |
+//! | 5      | 2    | broker port (LE)                       |
+//! | 7      | 1    | layout version                         |
+//! | 8      | 32   | SSID                                   |
+//! | 40     | 64   | password                               |
+//! | 104    | 16   | broker IP                              |
+//! | 120    | 2    | sensor interval in seconds (LE)        |
+//! | 122    | 6    | temperature, humidity, pressure offsets |
+//! |        |      | in hundredths of °C, %RH, hPa (i16 LE) |
+//! | 252    | 4    | CRC-32 of bytes 0..252 (LE)            |
+//!
+//! A torn write or flipped bit fails the CRC and decodes as
+//! [`SlotState::Corrupted`] rather than as garbage credentials.
 
 use heapless::String;
+use iot_common::bme280::SensorOffsets;
 
 /// Maximum length for SSID strings
 pub const MAX_SSID_LEN: usize = 32;
@@ -16,6 +36,7 @@ pub const MAX_HOSTNAME_LEN: usize = 64;
 
 /// WiFi network credentials
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "storage", derive(serde::Serialize, serde::Deserialize))]
 pub struct WiFiCredentials {
     pub ssid: String<MAX_SSID_LEN>,
     pub password: String<MAX_PASSWORD_LEN>,
@@ -42,6 +63,7 @@ impl Default for WiFiCredentials {
 
 /// MQTT broker configuration
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "storage", derive(serde::Serialize, serde::Deserialize))]
 pub struct MqttConfig {
     pub broker_ip: String<MAX_IP_LEN>,
     pub broker_port: u16,
@@ -130,4 +152,430 @@ impl Default for SystemConfig {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+/// Configuration name used to persist the console settings in a `ConfigStore`
+pub const CONSOLE_CONFIG: &str = "console_config";
+
+/// Persisted form of the settings edited from the console
+#[cfg(feature = "storage")]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ConsoleSettings {
+    pub wifi: WiFiCredentials,
+    pub mqtt: MqttConfig,
+}
+
+/// Size of a persisted configuration block
+pub const FLASH_CONFIG_SIZE: usize = 256;
+
+/// Magic bytes opening every configuration block
+pub const FLASH_CONFIG_MAGIC: [u8; 2] = [0xCA, 0xFE];
+
+/// Current block layout version, stored in the formerly reserved byte 7
+///
+/// Version 0 blocks predate the CRC and are still accepted, provided their
+/// zero-filled trailer is intact, so devices keep their credentials across
+/// the upgrade; the next save rewrites them.
+pub const FLASH_CONFIG_VERSION: u8 = 1;
+
+/// MQTT broker used until one is configured
+pub const DEFAULT_BROKER_IP: &str = "10.10.10.210";
+
+/// MQTT broker port used until one is configured
+pub const DEFAULT_BROKER_PORT: u16 = 1883;
+
+/// Sensor reading interval used until one is configured
+pub const DEFAULT_SENSOR_INTERVAL_SECS: u16 = 30;
+
+/// Shortest configurable sensor interval
+pub const MIN_SENSOR_INTERVAL_SECS: u16 = 1;
+
+/// Longest configurable sensor interval
+pub const MAX_SENSOR_INTERVAL_SECS: u16 = 3600;
+
+/// Largest persisted temperature offset magnitude, in °C
+pub const MAX_TEMPERATURE_OFFSET: f32 = 10.0;
+
+/// Largest persisted humidity offset magnitude, in %RH
+pub const MAX_HUMIDITY_OFFSET: f32 = 20.0;
+
+/// Largest persisted pressure offset magnitude, in hPa
+pub const MAX_PRESSURE_OFFSET: f32 = 50.0;
+
+/// Offset of the CRC-32 trailer, which covers every byte before it
+const CRC_OFFSET: usize = FLASH_CONFIG_SIZE - 4;
+
+const SSID_OFFSET: usize = 8;
+const PASSWORD_OFFSET: usize = 40;
+const BROKER_IP_OFFSET: usize = 104;
+const BROKER_IP_CAPACITY: usize = 16;
+const INTERVAL_OFFSET: usize = 120;
+const SENSOR_OFFSETS_OFFSET: usize = 122;
+
+/// Configuration persisted in flash, in fixed-size arrays for const construction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlashConfig {
+    pub ssid: [u8; MAX_SSID_LEN],
+    pub ssid_len: u8,
+    pub password: [u8; MAX_PASSWORD_LEN],
+    pub password_len: u8,
+    pub mqtt_broker_ip: [u8; BROKER_IP_CAPACITY],
+    pub mqtt_broker_ip_len: u8,
+    pub mqtt_broker_port: u16,
+    pub sensor_interval_secs: u16,
+    /// Sensor calibration in hundredths of °C, %RH and hPa (see [`Self::sensor_offsets`])
+    pub temperature_offset_centi: i16,
+    pub humidity_offset_centi: i16,
+    pub pressure_offset_centi: i16,
+    pub is_configured: bool,
+}
+
+/// Outcome of decoding a configuration block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotState {
+    /// Block holds a configuration that passed verification
+    Valid(FlashConfig),
+    /// Block was never written or has been erased (no magic bytes)
+    Empty,
+    /// Block carries the magic bytes but fails its CRC or holds invalid lengths
+    Corrupted,
+}
+
+impl SlotState {
+    /// The configuration, if the block was valid
+    pub fn config(self) -> Option<FlashConfig> {
+        match self {
+            SlotState::Valid(config) => Some(config),
+            _ => None,
+        }
+    }
+
+    /// Whether the block holds a usable configuration
+    pub fn is_valid(&self) -> bool {
+        matches!(self, SlotState::Valid(_))
+    }
+}
+
+impl FlashConfig {
+    /// Unconfigured defaults: no credentials, default broker and interval
+    pub const fn new() -> Self {
+        let mut mqtt_broker_ip = [0u8; BROKER_IP_CAPACITY];
+        let default_ip = DEFAULT_BROKER_IP.as_bytes();
+        let mut i = 0;
+        while i < default_ip.len() {
+            mqtt_broker_ip[i] = default_ip[i];
+            i += 1;
+        }
+
+        Self {
+            ssid: [0u8; MAX_SSID_LEN],
+            ssid_len: 0,
+            password: [0u8; MAX_PASSWORD_LEN],
+            password_len: 0,
+            mqtt_broker_ip,
+            mqtt_broker_ip_len: default_ip.len() as u8,
+            mqtt_broker_port: DEFAULT_BROKER_PORT,
+            sensor_interval_secs: DEFAULT_SENSOR_INTERVAL_SECS,
+            temperature_offset_centi: 0,
+            humidity_offset_centi: 0,
+            pressure_offset_centi: 0,
+            is_configured: false,
+        }
+    }
+
+    pub fn get_ssid(&self) -> &str {
+        core::str::from_utf8(&self.ssid[..self.ssid_len as usize]).unwrap_or("")
+    }
+
+    pub fn get_password(&self) -> &str {
+        core::str::from_utf8(&self.password[..self.password_len as usize]).unwrap_or("")
+    }
+
+    pub fn get_mqtt_broker_ip(&self) -> &str {
+        core::str::from_utf8(&self.mqtt_broker_ip[..self.mqtt_broker_ip_len as usize]).unwrap_or(DEFAULT_BROKER_IP)
+    }
+
+    /// Set the SSID, returning false if it exceeds [`MAX_SSID_LEN`]
+    pub fn set_ssid(&mut self, ssid: &str) -> bool {
+        match Self::copy_field(&mut self.ssid, ssid) {
+            Some(len) => {
+                self.ssid_len = len;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set the password, returning false if it exceeds [`MAX_PASSWORD_LEN`]
+    pub fn set_password(&mut self, password: &str) -> bool {
+        match Self::copy_field(&mut self.password, password) {
+            Some(len) => {
+                self.password_len = len;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set the broker IP, returning false if it exceeds 16 bytes
+    pub fn set_mqtt_broker_ip(&mut self, ip: &str) -> bool {
+        match Self::copy_field(&mut self.mqtt_broker_ip, ip) {
+            Some(len) => {
+                self.mqtt_broker_ip_len = len;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sensor field-calibration offsets
+    pub fn sensor_offsets(&self) -> SensorOffsets {
+        SensorOffsets::new(
+            self.temperature_offset_centi as f32 / 100.0,
+            self.humidity_offset_centi as f32 / 100.0,
+            self.pressure_offset_centi as f32 / 100.0,
+        )
+    }
+
+    /// Set the sensor offsets, rounded to hundredths
+    ///
+    /// Returns false, leaving the stored offsets unchanged, if any exceeds
+    /// [`MAX_TEMPERATURE_OFFSET`], [`MAX_HUMIDITY_OFFSET`] or [`MAX_PRESSURE_OFFSET`].
+    pub fn set_sensor_offsets(&mut self, offsets: SensorOffsets) -> bool {
+        match (
+            Self::to_centi(offsets.temperature, MAX_TEMPERATURE_OFFSET),
+            Self::to_centi(offsets.humidity, MAX_HUMIDITY_OFFSET),
+            Self::to_centi(offsets.pressure, MAX_PRESSURE_OFFSET),
+        ) {
+            (Some(temperature), Some(humidity), Some(pressure)) => {
+                self.temperature_offset_centi = temperature;
+                self.humidity_offset_centi = humidity;
+                self.pressure_offset_centi = pressure;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// `value` in hundredths, `None` if its magnitude exceeds `max` (or is NaN)
+    fn to_centi(value: f32, max: f32) -> Option<i16> {
+        if !(-max..=max).contains(&value) {
+            return None;
+        }
+        let centi = value * 100.0;
+        // Round half away from zero without libm
+        Some(if centi < 0.0 { centi - 0.5 } else { centi + 0.5 } as i16)
+    }
+
+    /// Copy `value` into `field`, zeroing the rest; returns the new length
+    fn copy_field(field: &mut [u8], value: &str) -> Option<u8> {
+        let bytes = value.as_bytes();
+        if bytes.len() > field.len() {
+            return None;
+        }
+        field[..bytes.len()].copy_from_slice(bytes);
+        field[bytes.len()..].fill(0);
+        Some(bytes.len() as u8)
+    }
+
+    /// Serialize into the on-flash block layout, CRC included
+    pub fn encode(&self) -> [u8; FLASH_CONFIG_SIZE] {
+        let mut buffer = [0u8; FLASH_CONFIG_SIZE];
+
+        buffer[0..2].copy_from_slice(&FLASH_CONFIG_MAGIC);
+        buffer[2] = self.ssid_len;
+        buffer[3] = self.password_len;
+        buffer[4] = self.mqtt_broker_ip_len;
+        buffer[5..7].copy_from_slice(&self.mqtt_broker_port.to_le_bytes());
+        buffer[7] = FLASH_CONFIG_VERSION;
+
+        let ssid_len = self.ssid_len as usize;
+        buffer[SSID_OFFSET..SSID_OFFSET + ssid_len].copy_from_slice(&self.ssid[..ssid_len]);
+        let password_len = self.password_len as usize;
+        buffer[PASSWORD_OFFSET..PASSWORD_OFFSET + password_len].copy_from_slice(&self.password[..password_len]);
+        let ip_len = self.mqtt_broker_ip_len as usize;
+        buffer[BROKER_IP_OFFSET..BROKER_IP_OFFSET + ip_len].copy_from_slice(&self.mqtt_broker_ip[..ip_len]);
+        buffer[INTERVAL_OFFSET..INTERVAL_OFFSET + 2].copy_from_slice(&self.sensor_interval_secs.to_le_bytes());
+        let offsets = [self.temperature_offset_centi, self.humidity_offset_centi, self.pressure_offset_centi];
+        for (i, offset) in offsets.iter().enumerate() {
+            let at = SENSOR_OFFSETS_OFFSET + 2 * i;
+            buffer[at..at + 2].copy_from_slice(&offset.to_le_bytes());
+        }
+
+        let crc = crc32(&buffer[..CRC_OFFSET]);
+        buffer[CRC_OFFSET..].copy_from_slice(&crc.to_le_bytes());
+
+        buffer
+    }
+
+    /// Parse an on-flash block, verifying its CRC
+    ///
+    /// A missing broker IP or an out-of-range sensor interval or offset falls
+    /// back to the defaults; lengths beyond their fields mark the block corrupted.
+    pub fn decode(buffer: &[u8; FLASH_CONFIG_SIZE]) -> SlotState {
+        if buffer[0..2] != FLASH_CONFIG_MAGIC {
+            return SlotState::Empty;
+        }
+
+        let stored = u32::from_le_bytes([
+            buffer[CRC_OFFSET], buffer[CRC_OFFSET + 1], buffer[CRC_OFFSET + 2], buffer[CRC_OFFSET + 3],
+        ]);
+        let intact = match buffer[7] {
+            // Pre-CRC layout zero-filled the trailer; a non-zero one means a
+            // current block whose version byte was damaged
+            0 => stored == 0,
+            FLASH_CONFIG_VERSION => crc32(&buffer[..CRC_OFFSET]) == stored,
+            _ => false,
+        };
+        if !intact {
+            return SlotState::Corrupted;
+        }
+
+        let ssid_len = buffer[2] as usize;
+        let password_len = buffer[3] as usize;
+        let ip_len = buffer[4] as usize;
+        if ssid_len > MAX_SSID_LEN || password_len > MAX_PASSWORD_LEN || ip_len > BROKER_IP_CAPACITY {
+            return SlotState::Corrupted;
+        }
+
+        let mut config = Self::new();
+        config.ssid[..ssid_len].copy_from_slice(&buffer[SSID_OFFSET..SSID_OFFSET + ssid_len]);
+        config.ssid_len = ssid_len as u8;
+        config.password[..password_len].copy_from_slice(&buffer[PASSWORD_OFFSET..PASSWORD_OFFSET + password_len]);
+        config.password_len = password_len as u8;
+        if ip_len > 0 {
+            config.mqtt_broker_ip = [0u8; BROKER_IP_CAPACITY];
+            config.mqtt_broker_ip[..ip_len].copy_from_slice(&buffer[BROKER_IP_OFFSET..BROKER_IP_OFFSET + ip_len]);
+            config.mqtt_broker_ip_len = ip_len as u8;
+        }
+        config.mqtt_broker_port = u16::from_le_bytes([buffer[5], buffer[6]]);
+
+        // Blocks saved before the interval existed hold 0 here
+        let interval = u16::from_le_bytes([buffer[INTERVAL_OFFSET], buffer[INTERVAL_OFFSET + 1]]);
+        if (MIN_SENSOR_INTERVAL_SECS..=MAX_SENSOR_INTERVAL_SECS).contains(&interval) {
+            config.sensor_interval_secs = interval;
+        }
+
+        // Blocks saved before calibration existed hold zeros, i.e. no offsets
+        let centi = |i: usize| {
+            let at = SENSOR_OFFSETS_OFFSET + 2 * i;
+            i16::from_le_bytes([buffer[at], buffer[at + 1]]) as f32 / 100.0
+        };
+        if !config.set_sensor_offsets(SensorOffsets::new(centi(0), centi(1), centi(2))) {
+            config.set_sensor_offsets(SensorOffsets::NONE);
+        }
+        config.is_configured = ssid_len > 0 && password_len > 0;
+
+        SlotState::Valid(config)
+    }
+}
+
+impl Default for FlashConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// CRC-32 (IEEE 802.3, reflected), computed bitwise to avoid a lookup table
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn configured() -> FlashConfig {
+        let mut config = FlashConfig::new();
+        assert!(config.set_ssid("FamilyNetwork"));
+        assert!(config.set_password("correct horse battery"));
+        assert!(config.set_mqtt_broker_ip("192.168.1.50"));
+        config.mqtt_broker_port = 8883;
+        config.sensor_interval_secs = 120;
+        assert!(config.set_sensor_offsets(SensorOffsets::new(-1.25, 3.5, 0.8)));
+        config.is_configured = true;
+        config
+    }
+
+    #[test]
+    fn test_sensor_offsets() {
+        let config = configured();
+        assert_eq!(
+            (config.temperature_offset_centi, config.humidity_offset_centi, config.pressure_offset_centi),
+            (-125, 350, 80)
+        );
+        assert_eq!(config.sensor_offsets(), SensorOffsets::new(-1.25, 3.5, 0.8));
+
+        // Out-of-range offsets are rejected as a whole
+        let mut rejected = config;
+        assert!(!rejected.set_sensor_offsets(SensorOffsets::new(-1.0, 25.0, 0.0)));
+        assert!(!rejected.set_sensor_offsets(SensorOffsets::new(f32::NAN, 0.0, 0.0)));
+        assert_eq!(rejected, config);
+
+        // A block with an out-of-range offset still loads, uncalibrated
+        let mut block = config.encode();
+        block[SENSOR_OFFSETS_OFFSET..SENSOR_OFFSETS_OFFSET + 2].copy_from_slice(&5000i16.to_le_bytes());
+        let crc = crc32(&block[..CRC_OFFSET]);
+        block[CRC_OFFSET..].copy_from_slice(&crc.to_le_bytes());
+        let decoded = FlashConfig::decode(&block).config().unwrap();
+        assert_eq!(decoded.sensor_offsets(), SensorOffsets::NONE);
+        assert_eq!(decoded.get_ssid(), "FamilyNetwork");
+    }
+
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_flash_config_round_trip() {
+        let config = configured();
+        assert_eq!(FlashConfig::decode(&config.encode()), SlotState::Valid(config));
+
+        let defaults = FlashConfig::new();
+        let decoded = FlashConfig::decode(&defaults.encode()).config().unwrap();
+        assert_eq!(decoded, defaults);
+        assert_eq!(decoded.get_mqtt_broker_ip(), DEFAULT_BROKER_IP);
+        assert!(!decoded.is_configured);
+    }
+
+    #[test]
+    fn test_flash_config_detects_corruption() {
+        let block = configured().encode();
+
+        // Any single flipped bit in the covered range or the CRC itself
+        for offset in [2, 7, 10, 45, 110, 121, 200, CRC_OFFSET, FLASH_CONFIG_SIZE - 1] {
+            let mut corrupted = block;
+            corrupted[offset] ^= 0x01;
+            assert_eq!(FlashConfig::decode(&corrupted), SlotState::Corrupted, "offset {}", offset);
+        }
+
+        // Write torn after the SSID: the tail still reads as erased flash
+        let mut torn = block;
+        torn[PASSWORD_OFFSET..].fill(0xFF);
+        assert_eq!(FlashConfig::decode(&torn), SlotState::Corrupted);
+    }
+
+    #[test]
+    fn test_flash_config_empty_and_legacy_blocks() {
+        assert_eq!(FlashConfig::decode(&[0xFF; FLASH_CONFIG_SIZE]), SlotState::Empty);
+        assert_eq!(FlashConfig::decode(&[0x00; FLASH_CONFIG_SIZE]), SlotState::Empty);
+
+        // Pre-CRC block: version byte and trailer both zero
+        let mut legacy = configured().encode();
+        legacy[7] = 0;
+        legacy[CRC_OFFSET..].fill(0);
+        assert_eq!(FlashConfig::decode(&legacy), SlotState::Valid(configured()));
+
+        legacy[2] = (MAX_SSID_LEN + 1) as u8;
+        assert_eq!(FlashConfig::decode(&legacy), SlotState::Corrupted);
+    }
+}
@@ -6,23 +6,226 @@
 use embassy_sync::mutex::Mutex;
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embassy_time::{Duration, Timer};
-use embedded_io_async::{Read, Write};
-use heapless::String;
+use embedded_io_async::{Read, ReadReady, Write};
+use heapless::{Deque, String};
 use rtt_target::rprintln;
 
+use crate::auth::ConsoleAuth;
 use crate::commands::{CommandHandler, MAX_CMD_LEN};
 use crate::config::SystemConfig;
 
-/// Maximum input buffer size
-#[allow(dead_code)]
+/// Maximum number of bytes consumed by a single `poll`
 const INPUT_BUFFER_SIZE: usize = 128;
 /// Command prompt string
 const PROMPT: &str = "esp32> ";
+/// Command prompt shown while privileged commands are locked
+const PROMPT_LOCKED: &str = "esp32 (locked)> ";
+/// Commands whose argument is echoed as `*`
+const SECRET_PREFIXES: [&str; 2] = ["login ", "passwd "];
+/// Number of commands kept in history
+const HISTORY_SIZE: usize = 10;
+
+/// ANSI escape sequence parser state
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EscapeState {
+    /// Not inside an escape sequence
+    Idle,
+    /// Received ESC, waiting for `[` or `O`
+    Escape,
+    /// Inside a control sequence, waiting for the final byte
+    Sequence,
+}
+
+/// Input decoded by [`EscapeParser`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyInput {
+    /// Byte outside any escape sequence
+    Byte(u8),
+    /// Up arrow - recall an older command
+    Up,
+    /// Down arrow - recall a newer command
+    Down,
+    /// Byte consumed by an escape sequence that is not finished or not supported
+    Pending,
+}
+
+/// Decodes arrow keys from the raw console byte stream
+///
+/// Multi-byte escape sequences are consumed so their bytes never reach the
+/// input line; only up/down are reported.
+#[derive(Debug, Clone)]
+pub struct EscapeParser {
+    state: EscapeState,
+}
+
+impl EscapeParser {
+    /// Create a parser outside any escape sequence
+    pub const fn new() -> Self {
+        Self { state: EscapeState::Idle }
+    }
+
+    /// Feed one input byte
+    pub fn feed(&mut self, ch: u8) -> KeyInput {
+        match self.state {
+            EscapeState::Escape => {
+                self.state = if ch == b'[' || ch == b'O' {
+                    EscapeState::Sequence
+                } else {
+                    EscapeState::Idle
+                };
+                KeyInput::Pending
+            },
+            EscapeState::Sequence => match ch {
+                // Parameter and intermediate bytes (e.g. ESC [ 1 ; 5 A)
+                0x20..=0x3F => KeyInput::Pending,
+                // Any final byte ends the sequence
+                final_byte => {
+                    self.state = EscapeState::Idle;
+                    match final_byte {
+                        b'A' => KeyInput::Up,
+                        b'B' => KeyInput::Down,
+                        _ => KeyInput::Pending,
+                    }
+                },
+            },
+            EscapeState::Idle if ch == 0x1B => {
+                self.state = EscapeState::Escape;
+                KeyInput::Pending
+            },
+            EscapeState::Idle => KeyInput::Byte(ch),
+        }
+    }
+}
+
+impl Default for EscapeParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Ring buffer of entered commands with up/down navigation
+pub struct CommandHistory {
+    entries: Deque<String<MAX_CMD_LEN>, HISTORY_SIZE>,
+    /// Navigation position counted from the newest entry (0 = newest)
+    cursor: Option<usize>,
+    /// Line being typed when navigation started, restored past the newest entry
+    draft: String<MAX_CMD_LEN>,
+}
+
+impl CommandHistory {
+    /// Create an empty history
+    pub fn new() -> Self {
+        Self {
+            entries: Deque::new(),
+            cursor: None,
+            draft: String::new(),
+        }
+    }
+
+    /// Add an executed command, dropping the oldest when full
+    pub fn push(&mut self, line: &str) {
+        self.cursor = None;
+
+        if line.is_empty() || self.entries.back().map(|last| last.as_str()) == Some(line) {
+            return;
+        }
+
+        let mut entry = String::new();
+        if entry.push_str(line).is_err() {
+            return;
+        }
+
+        if self.entries.is_full() {
+            self.entries.pop_front();
+        }
+        let _ = self.entries.push_back(entry);
+    }
+
+    /// Step to an older command (up arrow)
+    ///
+    /// `current` is saved as a draft when navigation starts. Returns `None`
+    /// when there is nothing older to show.
+    pub fn older(&mut self, current: &str) -> Option<String<MAX_CMD_LEN>> {
+        let next_cursor = match self.cursor {
+            None => {
+                self.draft.clear();
+                let _ = self.draft.push_str(current);
+                0
+            }
+            Some(cursor) => cursor + 1,
+        };
+
+        let entry = self.entries.iter().rev().nth(next_cursor)?.clone();
+        self.cursor = Some(next_cursor);
+        Some(entry)
+    }
+
+    /// Step to a newer command (down arrow)
+    ///
+    /// Moving past the newest entry restores the draft. Returns `None` when
+    /// not navigating.
+    pub fn newer(&mut self) -> Option<String<MAX_CMD_LEN>> {
+        match self.cursor? {
+            0 => {
+                self.cursor = None;
+                Some(self.draft.clone())
+            }
+            cursor => {
+                self.cursor = Some(cursor - 1);
+                self.entries.iter().rev().nth(cursor - 1).cloned()
+            }
+        }
+    }
+
+    /// Stored commands, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|entry| entry.as_str())
+    }
+
+    /// Number of stored commands
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if history is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Default for CommandHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Error returned by [`SerialConsole::poll`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PollError<R, W> {
+    /// Reading from (or checking) the input failed
+    Read(R),
+    /// Echoing or writing a response failed
+    Write(W),
+}
+
+/// Command prefix of `line` if it carries a password that must not be shown
+pub(crate) fn secret_prefix(line: &str) -> Option<&'static str> {
+    SECRET_PREFIXES.into_iter().find(|prefix| {
+        line.len() >= prefix.len() && line.as_bytes()[..prefix.len()].eq_ignore_ascii_case(prefix.as_bytes())
+    })
+}
+
+/// Check if `line` is a `login` or `passwd` command whose password must not be shown
+pub(crate) fn is_secret_line(line: &str) -> bool {
+    secret_prefix(line).is_some()
+}
 
 /// Serial console manager
 pub struct SerialConsole {
     command_handler: Mutex<CriticalSectionRawMutex, CommandHandler>,
     input_buffer: Mutex<CriticalSectionRawMutex, String<MAX_CMD_LEN>>,
+    history: Mutex<CriticalSectionRawMutex, CommandHistory>,
+    escape_parser: Mutex<CriticalSectionRawMutex, EscapeParser>,
 }
 
 impl SerialConsole {
@@ -31,6 +234,8 @@ impl SerialConsole {
         Self {
             command_handler: Mutex::new(CommandHandler::new()),
             input_buffer: Mutex::new(String::new()),
+            history: Mutex::new(CommandHistory::new()),
+            escape_parser: Mutex::new(EscapeParser::new()),
         }
     }
     
@@ -52,12 +257,13 @@ impl SerialConsole {
         self.show_prompt(writer).await
     }
     
-    /// Show command prompt
+    /// Show command prompt, flagging a locked console
     pub async fn show_prompt<W>(&self, writer: &mut W) -> Result<(), W::Error>
     where
         W: Write,
     {
-        writer.write_all(PROMPT.as_bytes()).await
+        let prompt = if self.is_authenticated().await { PROMPT } else { PROMPT_LOCKED };
+        writer.write_all(prompt.as_bytes()).await
     }
     
     /// Process a single character input
@@ -66,6 +272,24 @@ impl SerialConsole {
         W: Write,
     {
         let mut input_buffer = self.input_buffer.lock().await;
+        let key = self.escape_parser.lock().await.feed(ch);
+        
+        // Arrow keys recall history; other escape sequences are swallowed
+        let ch = match key {
+            KeyInput::Byte(ch) => ch,
+            KeyInput::Up | KeyInput::Down => {
+                let recalled = {
+                    let mut history = self.history.lock().await;
+                    if key == KeyInput::Up { history.older(&input_buffer) } else { history.newer() }
+                };
+                if let Some(line) = recalled {
+                    *input_buffer = line;
+                    self.redraw_line(writer, &input_buffer).await?;
+                }
+                return Ok(false);
+            },
+            KeyInput::Pending => return Ok(false),
+        };
         
         match ch {
             // Carriage return or line feed - execute command
@@ -74,6 +298,12 @@ impl SerialConsole {
                 
                 let command_line = input_buffer.clone();
                 input_buffer.clear();
+                if is_secret_line(command_line.trim_start()) {
+                    // Never keep passwords around for recall; an empty push only ends navigation
+                    self.history.lock().await.push("");
+                } else {
+                    self.history.lock().await.push(command_line.trim());
+                }
                 
                 if !command_line.is_empty() {
                     self.execute_command_line(writer, &command_line).await?;
@@ -93,18 +323,14 @@ impl SerialConsole {
                 Ok(false)
             },
             
-            // Escape sequences (arrow keys, etc.) - ignore for now
-            0x1B => {
-                // Could implement command history here
-                Ok(false)
-            },
-            
             // Printable characters
             ch if ch >= 0x20 && ch <= 0x7E => {
                 if input_buffer.len() < MAX_CMD_LEN - 1 {
+                    // Mask everything typed after "login "
+                    let echo = if is_secret_line(input_buffer.trim_start()) { b'*' } else { ch };
                     if let Ok(char_ch) = core::str::from_utf8(&[ch]) {
                         if input_buffer.push_str(char_ch).is_ok() {
-                            writer.write_all(&[ch]).await?;
+                            writer.write_all(&[echo]).await?;
                         }
                     }
                 }
@@ -116,12 +342,101 @@ impl SerialConsole {
         }
     }
     
+    /// Process whatever input is available without waiting for more
+    ///
+    /// Drains up to `INPUT_BUFFER_SIZE` bytes that the reader reports as ready
+    /// through [`process_char`](Self::process_char), then returns the number of
+    /// command lines executed. A partially typed line stays in the console's
+    /// buffer for the next call, and CR/LF are handled exactly as in
+    /// `process_char`. This lets one task interleave console servicing with
+    /// other periodic work:
+    ///
+    /// ```rust,ignore
+    /// loop {
+    ///     console.poll(&mut rx, &mut tx).await?;
+    ///     if status_due() {
+    ///         console.print_above_prompt(&mut tx, &status_line()).await?;
+    ///     }
+    ///     Timer::after(Duration::from_millis(20)).await;
+    /// }
+    /// ```
+    pub async fn poll<R, W>(&self, reader: &mut R, writer: &mut W) -> Result<usize, PollError<R::Error, W::Error>>
+    where
+        R: Read + ReadReady,
+        W: Write,
+    {
+        let mut buffer = [0u8; INPUT_BUFFER_SIZE];
+        let mut consumed = 0;
+        let mut executed = 0;
+        
+        while consumed < INPUT_BUFFER_SIZE && reader.read_ready().map_err(PollError::Read)? {
+            let count = reader.read(&mut buffer[..INPUT_BUFFER_SIZE - consumed]).await.map_err(PollError::Read)?;
+            if count == 0 {
+                break; // EOF
+            }
+            consumed += count;
+            
+            for &ch in &buffer[..count] {
+                if self.process_char(writer, ch).await.map_err(PollError::Write)? {
+                    executed += 1;
+                }
+            }
+        }
+        
+        if consumed > 0 {
+            writer.flush().await.map_err(PollError::Write)?;
+        }
+        Ok(executed)
+    }
+    
+    /// Print `text` on its own line above the prompt, keeping any partial input
+    ///
+    /// Used for periodic status output: the line being typed is erased, `text`
+    /// is written, and the prompt is redrawn with the input restored.
+    pub async fn print_above_prompt<W>(&self, writer: &mut W, text: &str) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        let input_buffer = self.input_buffer.lock().await;
+        writer.write_all(b"\r\x1b[K").await?;
+        writer.write_all(text.as_bytes()).await?;
+        writer.write_all(b"\r\n").await?;
+        self.redraw_line(writer, &input_buffer).await?;
+        writer.flush().await
+    }
+    
+    /// Replace the current terminal line with the prompt and `line`
+    async fn redraw_line<W>(&self, writer: &mut W, line: &str) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        // Return to column 0 and erase to end of line
+        writer.write_all(b"\r\x1b[K").await?;
+        self.show_prompt(writer).await?;
+        
+        let trimmed = line.trim_start();
+        if let Some(prefix) = secret_prefix(trimmed) {
+            let visible = line.len() - trimmed.len() + prefix.len();
+            writer.write_all(line[..visible].as_bytes()).await?;
+            for _ in visible..line.len() {
+                writer.write_all(b"*").await?;
+            }
+            Ok(())
+        } else {
+            writer.write_all(line.as_bytes()).await
+        }
+    }
+    
     /// Execute a command line
     async fn execute_command_line<W>(&self, writer: &mut W, line: &str) -> Result<(), W::Error>
     where
         W: Write,
     {
-        rprintln!("[CONSOLE] Executing command: {}", line);
+        if let Some(prefix) = secret_prefix(line.trim_start()) {
+            rprintln!("[CONSOLE] Executing command: {}****", prefix);
+        } else {
+            rprintln!("[CONSOLE] Executing command: {}", line);
+        }
         
         let mut handler = self.command_handler.lock().await;
         let command = handler.parse_command(line);
@@ -140,6 +455,16 @@ impl SerialConsole {
         handler.update_system_status(wifi_connected, mqtt_connected, sensor_active, current_ip);
     }
     
+    /// Check if privileged commands are unlocked (always true without a password)
+    pub async fn is_authenticated(&self) -> bool {
+        self.command_handler.lock().await.auth().is_authenticated()
+    }
+    
+    /// Install the console password settings, e.g. loaded with `ConsoleAuth::load`
+    pub async fn set_auth(&self, auth: ConsoleAuth) {
+        self.command_handler.lock().await.set_auth(auth);
+    }
+    
     /// Get current configuration
     pub async fn get_config(&self) -> SystemConfig {
         let handler = self.command_handler.lock().await;
@@ -190,4 +515,106 @@ where
 }
 
 // RTT console functionality removed - use UART console instead
-// This was causing cfg warnings since rtt-console feature doesn't exist
\ No newline at end of file
+// This was causing cfg warnings since rtt-console feature doesn't exist
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed_all(parser: &mut EscapeParser, bytes: &[u8]) -> alloc::vec::Vec<KeyInput> {
+        bytes.iter().map(|&byte| parser.feed(byte)).collect()
+    }
+
+    fn history(lines: &[&str]) -> CommandHistory {
+        let mut history = CommandHistory::new();
+        for line in lines {
+            history.push(line);
+        }
+        history
+    }
+
+    #[test]
+    fn test_history_up_down_navigation() {
+        let mut history = history(&["status", "info", "wifi show"]);
+
+        assert_eq!(history.older("draft").unwrap().as_str(), "wifi show");
+        assert_eq!(history.older("ignored").unwrap().as_str(), "info");
+        assert_eq!(history.older("ignored").unwrap().as_str(), "status");
+        // Nothing older than the oldest entry; the position stays put
+        assert_eq!(history.older("ignored"), None);
+
+        assert_eq!(history.newer().unwrap().as_str(), "info");
+        assert_eq!(history.newer().unwrap().as_str(), "wifi show");
+        // Past the newest entry the line typed before navigating comes back
+        assert_eq!(history.newer().unwrap().as_str(), "draft");
+        assert_eq!(history.newer(), None);
+    }
+
+    #[test]
+    fn test_history_push_ends_navigation() {
+        let mut history = history(&["status", "info"]);
+        let _ = history.older("");
+        let _ = history.older("");
+
+        history.push("help");
+        assert_eq!(history.newer(), None);
+        assert_eq!(history.older("").unwrap().as_str(), "help");
+    }
+
+    #[test]
+    fn test_history_wraps_around_when_full() {
+        let mut history = CommandHistory::new();
+        for i in 0..HISTORY_SIZE + 3 {
+            history.push(&alloc::format!("cmd {}", i));
+        }
+
+        assert_eq!(history.len(), HISTORY_SIZE);
+        assert_eq!(history.iter().next(), Some("cmd 3"));
+        assert_eq!(history.iter().last(), Some(alloc::format!("cmd {}", HISTORY_SIZE + 2).as_str()));
+
+        let mut recalled = 0;
+        while history.older("").is_some() {
+            recalled += 1;
+        }
+        assert_eq!(recalled, HISTORY_SIZE);
+    }
+
+    #[test]
+    fn test_history_suppresses_duplicates_and_empty_lines() {
+        let history = history(&["status", "status", "", "info", "status"]);
+
+        assert_eq!(history.iter().collect::<alloc::vec::Vec<_>>(), ["status", "info", "status"]);
+    }
+
+    #[test]
+    fn test_escape_parser_arrow_keys() {
+        let mut parser = EscapeParser::new();
+
+        assert_eq!(feed_all(&mut parser, b"\x1b[A"), [KeyInput::Pending, KeyInput::Pending, KeyInput::Up]);
+        assert_eq!(feed_all(&mut parser, b"\x1b[B"), [KeyInput::Pending, KeyInput::Pending, KeyInput::Down]);
+        // Application cursor mode sends ESC O instead of ESC [
+        assert_eq!(feed_all(&mut parser, b"\x1bOA"), [KeyInput::Pending, KeyInput::Pending, KeyInput::Up]);
+        // Modifier parameters are skipped
+        assert_eq!(feed_all(&mut parser, b"\x1b[1;5B").last(), Some(&KeyInput::Down));
+    }
+
+    #[test]
+    fn test_escape_parser_swallows_unsupported_sequences() {
+        let mut parser = EscapeParser::new();
+
+        // Right arrow and Delete are consumed without reaching the input line
+        assert!(feed_all(&mut parser, b"\x1b[C\x1b[3~").iter().all(|key| *key == KeyInput::Pending));
+        assert_eq!(parser.feed(b'a'), KeyInput::Byte(b'a'));
+
+        // A lone ESC drops only the byte after it
+        assert_eq!(feed_all(&mut parser, b"\x1bxy"), [KeyInput::Pending, KeyInput::Pending, KeyInput::Byte(b'y')]);
+    }
+
+    #[test]
+    fn test_escape_parser_passes_plain_bytes() {
+        let mut parser = EscapeParser::default();
+
+        assert_eq!(feed_all(&mut parser, b"ls\r"),
+                   [KeyInput::Byte(b'l'), KeyInput::Byte(b's'), KeyInput::Byte(b'\r')]);
+    }
+}
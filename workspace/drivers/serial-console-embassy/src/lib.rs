@@ -6,9 +6,19 @@
 //! - MQTT broker configuration  
 //! - Real-time system monitoring
 //! - Module status checking
+//! - Command history recall with the up/down arrow keys
+//! - Optional JSON output mode for scripted hosts
+//! - Optional password gate for privileged commands (`login`/`logout`)
+//! - Non-blocking `SerialConsole::poll` for tasks that also print live status
+//! - `UsbSerialConsole` container console over USB Serial/JTAG (`container` feature),
+//!   buildable by the container factory through `usb_console_driver`
 
 #![no_std]
 
+#[cfg(feature = "container")]
+extern crate alloc;
+
+pub mod auth;
 pub mod console;
 pub mod commands;
 pub mod config;
@@ -17,10 +27,19 @@ pub mod config;
 #[cfg(feature = "container")]
 mod trait_impl;
 
-pub use console::SerialConsole;
-pub use commands::{Command, CommandHandler};
-pub use config::{SystemConfig, WiFiCredentials, MqttConfig};
+pub use auth::{ConsoleAuth, LoginResult, CONSOLE_AUTH_CONFIG};
+#[cfg(feature = "storage")]
+pub use auth::ConsoleAuthConfig;
+pub use console::{SerialConsole, CommandHistory, EscapeParser, KeyInput, PollError};
+pub use commands::{Command, CommandHandler, CommandOutput, OutputMode, StatusReport, InfoReport};
+pub use config::{SystemConfig, WiFiCredentials, MqttConfig, FlashConfig, SlotState, CONSOLE_CONFIG};
+#[cfg(feature = "storage")]
+pub use config::ConsoleSettings;
 
 // Re-export container integration when available
 #[cfg(feature = "container")]
-pub use trait_impl::{ConsoleContainerAdapter, create_container_console, create_container_console_with_config};
\ No newline at end of file
+pub use trait_impl::{
+    ConsoleContainerAdapter, ConsoleExtension, ConsoleRead, ConsoleWrite, NoExtension, UsbSerialConsole,
+    create_container_console, create_container_console_with_config, create_usb_console, provide_usb_port,
+    usb_console_driver
+};
\ No newline at end of file
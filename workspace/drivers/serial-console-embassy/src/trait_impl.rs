@@ -4,36 +4,168 @@
 //! for the serial console, enabling seamless integration with the dependency injection
 //! container system.
 
+use alloc::boxed::Box;
 use async_trait::async_trait;
+use core::cell::RefCell;
+use core::fmt::Write as _;
+use core::future::Future;
+use embassy_sync::blocking_mutex::Mutex as BlockingMutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embassy_time::Instant;
 use embedded_io_async::{Read, Write};
-use heapless::{String, Deque};
+use esp_hal::uart::{UartRx, UartTx};
+use esp_hal::usb_serial_jtag::{UsbSerialJtag, UsbSerialJtagRx, UsbSerialJtagTx};
+use esp_hal::Async;
+use heapless::String;
 
-use iot_common::IoTError;
+use iot_common::{ConfigError, HardwareError, IoTError, IoTResult};
+use iot_common::error::utils::error_message;
 
 // Import the container trait (when iot-container is available)
 #[cfg(feature = "container")]
-use iot_container::traits::{ConsoleInterface, EmbeddedString};
-
-use crate::console::SerialConsole;
-use crate::commands::{CommandHandler, MAX_CMD_LEN};
+use iot_container::config::ConsoleConfig;
+#[cfg(feature = "container")]
+use iot_container::traits::{ConsoleInterface, HealthCheck, ComponentHealth, EmbeddedString};
 
-/// Maximum number of commands in history
-const MAX_HISTORY_ENTRIES: usize = 10;
+use crate::commands::{Command, CommandHandler, OutputMode, MAX_CMD_LEN};
+use crate::console::{is_secret_line, CommandHistory, EscapeParser, KeyInput};
 
 /// Maximum length for input commands
-const MAX_INPUT_LEN: usize = 128;
+const MAX_INPUT_LEN: usize = MAX_CMD_LEN;
+
+/// Command prompt string
+const PROMPT: &str = "iot> ";
+
+/// Output half of a console port
+/// 
+/// `embedded_io_async` futures are not known to be `Send` for a generic port,
+/// which `ConsoleInterface` requires, so each concrete port implements this.
+#[cfg(feature = "container")]
+#[async_trait]
+pub trait ConsoleWrite: Send + Sync {
+    /// Writes all of `data` and flushes it out of the port
+    async fn write_bytes(&mut self, data: &[u8]) -> Result<(), IoTError>;
+}
+
+/// Input half of a console port, see [`ConsoleWrite`]
+#[cfg(feature = "container")]
+#[async_trait]
+pub trait ConsoleRead: Send + Sync {
+    /// Reads one byte, `None` when the port has nothing to deliver
+    async fn read_byte(&mut self) -> Result<Option<u8>, IoTError>;
+}
+
+#[cfg(feature = "container")]
+#[async_trait]
+impl ConsoleWrite for UsbSerialJtagTx<'static, Async> {
+    async fn write_bytes(&mut self, data: &[u8]) -> Result<(), IoTError> {
+        self.write_all(data).await.map_err(|_| {
+            IoTError::hardware(HardwareError::UARTError(error_message("USB write failed")))
+        })?;
+        self.flush().await.map_err(|_| {
+            IoTError::hardware(HardwareError::UARTError(error_message("USB flush failed")))
+        })
+    }
+}
+
+#[cfg(feature = "container")]
+#[async_trait]
+impl ConsoleRead for UsbSerialJtagRx<'static, Async> {
+    async fn read_byte(&mut self) -> Result<Option<u8>, IoTError> {
+        let mut byte = [0u8; 1];
+        match self.read(&mut byte).await {
+            Ok(0) => Ok(None),
+            Ok(_) => Ok(Some(byte[0])),
+            Err(_) => Err(IoTError::hardware(HardwareError::UARTError(error_message("USB read failed")))),
+        }
+    }
+}
+
+#[cfg(feature = "container")]
+#[async_trait]
+impl ConsoleWrite for UartTx<'static, Async> {
+    async fn write_bytes(&mut self, data: &[u8]) -> Result<(), IoTError> {
+        self.write_all(data).await.map_err(|_| {
+            IoTError::hardware(HardwareError::UARTError(error_message("UART write failed")))
+        })?;
+        self.flush().await.map_err(|_| {
+            IoTError::hardware(HardwareError::UARTError(error_message("UART flush failed")))
+        })
+    }
+}
+
+#[cfg(feature = "container")]
+#[async_trait]
+impl ConsoleRead for UartRx<'static, Async> {
+    async fn read_byte(&mut self) -> Result<Option<u8>, IoTError> {
+        let mut byte = [0u8; 1];
+        match self.read(&mut byte).await {
+            Ok(0) => Ok(None),
+            Ok(_) => Ok(Some(byte[0])),
+            Err(_) => Err(IoTError::hardware(HardwareError::UARTError(error_message("UART read failed")))),
+        }
+    }
+}
+
+/// Application commands served by a [`ConsoleContainerAdapter`] ahead of the shared
+/// [`CommandHandler`]
+/// 
+/// Lets an application add commands that need its own state or the port itself
+/// (streaming, firmware updates) without a console loop of its own. Privileged
+/// commands are checked against the console login before the extension sees them.
+#[cfg(feature = "container")]
+pub trait ConsoleExtension<TX, RX> {
+    /// Runs `line` if it is an application command
+    /// 
+    /// Output is written to `tx` in the given mode; `rx` is available to
+    /// commands that stop on a keystroke. Returns `false` to hand the line to
+    /// the shared command handler.
+    fn execute<'a>(
+        &'a mut self,
+        line: &'a str,
+        mode: OutputMode,
+        tx: &'a mut TX,
+        rx: &'a mut RX,
+    ) -> impl Future<Output = bool> + 'a;
+    
+    /// Help lines for the application commands, appended to `help`
+    fn help(&self) -> &'static str {
+        ""
+    }
+}
+
+/// Extension that adds no commands
+#[cfg(feature = "container")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoExtension;
+
+#[cfg(feature = "container")]
+impl<TX, RX> ConsoleExtension<TX, RX> for NoExtension {
+    fn execute<'a>(
+        &'a mut self,
+        _line: &'a str,
+        _mode: OutputMode,
+        _tx: &'a mut TX,
+        _rx: &'a mut RX,
+    ) -> impl Future<Output = bool> + 'a {
+        core::future::ready(false)
+    }
+}
 
 /// Adapter that implements the IoT Container ConsoleInterface trait for SerialConsole
 /// 
 /// This adapter bridges the serial console with the IoT Container's trait-based
 /// dependency injection system, enabling the serial console to be used as a drop-in
 /// component in the container architecture.
+/// 
+/// With a [`ConsoleExtension`] the adapter also serves application commands; it
+/// then drives the console through its inherent methods, since extension futures
+/// need not be `Send`.
 #[cfg(feature = "container")]
-pub struct ConsoleContainerAdapter<TX, RX>
+pub struct ConsoleContainerAdapter<TX, RX, X = NoExtension>
 where
-    TX: Write + Send + Sync,
-    RX: Read + Send + Sync,
+    TX: ConsoleWrite,
+    RX: ConsoleRead,
 {
     /// UART TX interface for writing output
     uart_tx: TX,
@@ -41,14 +173,20 @@ where
     /// UART RX interface for reading input
     uart_rx: RX,
     
+    /// Application commands tried before the command handler
+    extension: X,
+    
     /// Command handler for processing commands
     command_handler: CommandHandler,
     
     /// Input buffer for building commands
     input_buffer: String<MAX_INPUT_LEN>,
     
-    /// Command history
-    command_history: Deque<EmbeddedString, MAX_HISTORY_ENTRIES>,
+    /// Command history, recalled with the arrow keys
+    command_history: CommandHistory,
+    
+    /// Decoder for arrow-key escape sequences
+    escape_parser: EscapeParser,
     
     /// Session start time
     session_start: Instant,
@@ -75,8 +213,8 @@ where
 #[cfg(feature = "container")]
 impl<TX, RX> ConsoleContainerAdapter<TX, RX>
 where
-    TX: Write + Send + Sync,
-    RX: Read + Send + Sync,
+    TX: ConsoleWrite,
+    RX: ConsoleRead,
 {
     /// Creates a new console container adapter
     /// 
@@ -98,20 +236,7 @@ where
     /// let adapter = ConsoleContainerAdapter::new(uart_tx, uart_rx);
     /// ```
     pub fn new(uart_tx: TX, uart_rx: RX) -> Self {
-        Self {
-            uart_tx,
-            uart_rx,
-            command_handler: CommandHandler::new(),
-            input_buffer: String::new(),
-            command_history: Deque::new(),
-            session_start: Instant::now(),
-            ready: true,
-            last_command_time: None,
-            commands_processed: 0,
-            command_errors: 0,
-            echo_enabled: true,
-            show_prompt_next: true,
-        }
+        Self::with_extension(uart_tx, uart_rx, NoExtension)
     }
     
     /// Creates a new adapter with configuration options
@@ -130,119 +255,145 @@ where
         adapter.echo_enabled = echo_enabled;
         adapter
     }
+}
+
+// IoTError is the error type of the ConsoleInterface these methods back.
+#[allow(clippy::result_large_err)]
+#[cfg(feature = "container")]
+impl<TX, RX, X> ConsoleContainerAdapter<TX, RX, X>
+where
+    TX: ConsoleWrite,
+    RX: ConsoleRead,
+    X: ConsoleExtension<TX, RX>,
+{
+    /// Creates an adapter that serves the commands of `extension` as well
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// let mut console = ConsoleContainerAdapter::with_extension(tx, rx, AppCommands::new());
+    /// loop {
+    ///     console.show_prompt().await?;
+    ///     if let Some(line) = console.read_line().await? {
+    ///         console.execute_line(&line).await?;
+    ///     }
+    /// }
+    /// ```
+    pub fn with_extension(uart_tx: TX, uart_rx: RX, extension: X) -> Self {
+        Self {
+            uart_tx,
+            uart_rx,
+            extension,
+            command_handler: CommandHandler::new(),
+            input_buffer: String::new(),
+            command_history: CommandHistory::new(),
+            escape_parser: EscapeParser::new(),
+            session_start: Instant::now(),
+            ready: true,
+            last_command_time: None,
+            commands_processed: 0,
+            command_errors: 0,
+            echo_enabled: true,
+            show_prompt_next: true,
+        }
+    }
     
-    /// Sends the welcome banner to the console
+    /// Writes a line of text followed by CRLF
     /// 
     /// # Returns
     /// 
-    /// * `Ok(())` - Banner sent successfully
+    /// * `Ok(())` - Message written successfully
     /// * `Err(IoTError)` - Write operation failed
-    async fn send_welcome_banner(&mut self) -> Result<(), IoTError> {
-        let banner = "\r\n\
-                     ╔══════════════════════════════════════════════════════════════╗\r\n\
-                     ║              ESP32-C3 IoT System Console                     ║\r\n\
-                     ║                  Dependency Injection                       ║\r\n\
-                     ╚══════════════════════════════════════════════════════════════╝\r\n\
-                     \r\n\
-                     Type 'help' for available commands\r\n\
-                     \r\n";
-        
-        self.uart_tx.write_all(banner.as_bytes()).await.map_err(|_| {
-            IoTError::Hardware(iot_common::HardwareError::InterfaceError("Failed to write banner"))
-        })?;
-        
-        Ok(())
+    pub async fn write_line(&mut self, message: &str) -> Result<(), IoTError> {
+        self.uart_tx.write_bytes(message.as_bytes()).await?;
+        self.uart_tx.write_bytes(b"\r\n").await
     }
     
-    /// Reads a single character from the UART
+    /// Shows the prompt if a command completed since it was last shown
     /// 
     /// # Returns
     /// 
-    /// * `Ok(Some(char))` - Character read successfully
-    /// * `Ok(None)` - No character available (non-blocking)
-    /// * `Err(IoTError)` - Read operation failed
-    async fn read_char(&mut self) -> Result<Option<u8>, IoTError> {
-        let mut byte = [0u8; 1];
-        match self.uart_rx.read(&mut byte).await {
-            Ok(1) => Ok(Some(byte[0])),
-            Ok(0) => Ok(None), // No data available
-            Err(_) => Err(IoTError::Hardware(
-                iot_common::HardwareError::InterfaceError("UART read failed")
-            )),
+    /// * `Ok(())` - Prompt displayed or not due
+    /// * `Err(IoTError)` - Prompt display failed
+    pub async fn show_prompt(&mut self) -> Result<(), IoTError> {
+        if self.show_prompt_next {
+            self.uart_tx.write_bytes(PROMPT.as_bytes()).await?;
+            self.show_prompt_next = false;
         }
+        Ok(())
     }
     
-    /// Processes a single input character
-    /// 
-    /// # Arguments
+    /// Reads and processes one input byte
     /// 
-    /// * `ch` - Character to process
+    /// Handles echo, backspace and history recall with the arrow keys.
     /// 
     /// # Returns
     /// 
-    /// * `Ok(Some(command))` - Complete command received
-    /// * `Ok(None)` - Character processed, command not complete
-    /// * `Err(IoTError)` - Processing failed
-    async fn process_input_char(&mut self, ch: u8) -> Result<Option<EmbeddedString>, IoTError> {
+    /// * `Ok(Some(line))` - Complete command line received
+    /// * `Ok(None)` - Byte processed, line not complete
+    /// * `Err(IoTError)` - Read or echo failed
+    pub async fn read_line(&mut self) -> Result<Option<String<MAX_INPUT_LEN>>, IoTError> {
+        let Some(ch) = self.uart_rx.read_byte().await? else {
+            return Ok(None);
+        };
+        
+        let ch = match self.escape_parser.feed(ch) {
+            KeyInput::Byte(ch) => ch,
+            KeyInput::Up => {
+                if let Some(line) = self.command_history.older(&self.input_buffer) {
+                    self.input_buffer = line;
+                    self.redraw_line().await?;
+                }
+                return Ok(None);
+            }
+            KeyInput::Down => {
+                if let Some(line) = self.command_history.newer() {
+                    self.input_buffer = line;
+                    self.redraw_line().await?;
+                }
+                return Ok(None);
+            }
+            KeyInput::Pending => return Ok(None),
+        };
+        
         match ch {
             // Carriage return or line feed - command complete
             b'\r' | b'\n' => {
                 if self.echo_enabled {
-                    self.uart_tx.write_all(b"\r\n").await.map_err(|_| {
-                        IoTError::Hardware(iot_common::HardwareError::InterfaceError("Echo write failed"))
-                    })?;
+                    self.uart_tx.write_bytes(b"\r\n").await?;
+                }
+                self.show_prompt_next = true;
+                
+                let line = core::mem::take(&mut self.input_buffer);
+                if is_secret_line(line.trim_start()) {
+                    // Never keep passwords around for recall; an empty push only ends navigation
+                    self.command_history.push("");
+                } else {
+                    self.command_history.push(line.trim());
                 }
                 
-                if self.input_buffer.is_empty() {
-                    self.show_prompt_next = true;
+                if line.trim().is_empty() {
                     return Ok(None);
                 }
-                
-                // Extract command
-                let command_str = self.input_buffer.as_str();
-                let command = EmbeddedString::try_from(command_str).map_err(|_| {
-                    IoTError::Configuration(iot_common::ConfigError::InvalidFormat("Command too long"))
-                })?;
-                
-                // Add to history
-                self.add_to_history(command.clone());
-                
-                // Clear input buffer
-                self.input_buffer.clear();
-                self.show_prompt_next = true;
-                
-                Ok(Some(command))
+                Ok(Some(line))
             }
             
             // Backspace - remove last character
             0x08 | 0x7F => {
-                if !self.input_buffer.is_empty() {
-                    self.input_buffer.pop();
-                    if self.echo_enabled {
-                        // Send backspace, space, backspace to clear character
-                        self.uart_tx.write_all(b"\x08 \x08").await.map_err(|_| {
-                            IoTError::Hardware(iot_common::HardwareError::InterfaceError("Backspace write failed"))
-                        })?;
-                    }
+                if self.input_buffer.pop().is_some() && self.echo_enabled {
+                    // Send backspace, space, backspace to clear character
+                    self.uart_tx.write_bytes(b"\x08 \x08").await?;
                 }
                 Ok(None)
             }
             
-            // Escape sequences (arrow keys, etc.) - ignore for now
-            0x1B => {
-                // Could implement command history navigation here
-                Ok(None)
-            }
-            
             // Printable ASCII characters
-            ch if ch >= 0x20 && ch <= 0x7E => {
+            ch if (0x20..=0x7E).contains(&ch) => {
                 if self.input_buffer.len() < MAX_INPUT_LEN - 1 {
-                    if let Ok(()) = self.input_buffer.push(ch as char) {
-                        if self.echo_enabled {
-                            self.uart_tx.write_all(&[ch]).await.map_err(|_| {
-                                IoTError::Hardware(iot_common::HardwareError::InterfaceError("Echo write failed"))
-                            })?;
-                        }
+                    // Mask everything typed after "login "
+                    let echo = if is_secret_line(self.input_buffer.trim_start()) { b'*' } else { ch };
+                    if self.input_buffer.push(ch as char).is_ok() && self.echo_enabled {
+                        self.uart_tx.write_bytes(&[echo]).await?;
                     }
                 }
                 Ok(None)
@@ -253,116 +404,92 @@ where
         }
     }
     
-    /// Adds a command to the history
-    /// 
-    /// # Arguments
-    /// 
-    /// * `command` - Command to add to history
-    fn add_to_history(&mut self, command: EmbeddedString) {
-        // Don't add empty commands or duplicates
-        if command.is_empty() {
-            return;
-        }
-        
-        // Check if command is duplicate of last entry
-        if let Some(last_command) = self.command_history.back() {
-            if last_command == &command {
-                return;
-            }
-        }
-        
-        // Add to history, removing oldest if full
-        if self.command_history.len() >= MAX_HISTORY_ENTRIES {
-            self.command_history.pop_front();
-        }
-        
-        let _ = self.command_history.push_back(command);
+    /// Replaces the current terminal line with the prompt and the recalled input
+    async fn redraw_line(&mut self) -> Result<(), IoTError> {
+        // Return to column 0 and erase to end of line
+        self.uart_tx.write_bytes(b"\r\x1b[K").await?;
+        self.uart_tx.write_bytes(PROMPT.as_bytes()).await?;
+        self.uart_tx.write_bytes(self.input_buffer.as_bytes()).await
     }
     
-    /// Processes a command and generates response
-    /// 
-    /// # Arguments
+    /// Writes a response straight to the port
+    async fn write_response(&mut self, response: &str) -> Result<(), IoTError> {
+        self.uart_tx.write_bytes(response.as_bytes()).await
+    }
+    
+    /// Runs a command line and writes its output to the port
     /// 
-    /// * `command` - Command string to process
+    /// Privileged commands are refused before anything runs unless the console
+    /// is logged in. The extension is tried next, then the shared
+    /// [`CommandHandler`]. `history` and `echo on|off` are handled by the
+    /// adapter itself, since they concern this session.
     /// 
     /// # Returns
     /// 
-    /// * `Ok(response)` - Command processed successfully
-    /// * `Err(IoTError)` - Command processing failed
-    async fn process_command_internal(&mut self, command: &str) -> Result<EmbeddedString, IoTError> {
+    /// * `Ok(())` - Command processed, output written
+    /// * `Err(IoTError)` - Writing the output failed
+    pub async fn execute_line(&mut self, line: &str) -> Result<(), IoTError> {
         self.commands_processed += 1;
         self.last_command_time = Some(Instant::now().as_millis());
         
-        // Basic command processing - in a real implementation, this would
-        // integrate with the full command handler system
-        let response = match command.trim().to_lowercase().as_str() {
-            "help" | "h" | "?" => {
-                "Available commands:\r\n\
-                 help, h, ?       - Show this help\r\n\
-                 status           - Show system status\r\n\
-                 info             - Show system information\r\n\
-                 history          - Show command history\r\n\
-                 clear            - Clear screen\r\n\
-                 exit, quit       - Exit console session\r\n\
-                 echo on/off      - Enable/disable echo\r\n"
-            }
-            
-            "status" => {
-                let uptime = self.session_start.elapsed().as_secs();
-                "System Status: IoT Container Operational\r\n\
-                 Console: Active\r\n\
-                 Session Uptime: Active\r\n\
-                 Commands Processed: Available\r\n"
-            }
-            
-            "info" => {
-                "ESP32-C3 IoT System with Dependency Injection\r\n\
-                 Framework: Embassy Async Runtime\r\n\
-                 Architecture: Trait-based Container System\r\n\
-                 Console: Serial USB/JTAG Interface\r\n\
-                 Features: Sensor, Network, MQTT, Console\r\n"
-            }
-            
+        match line.trim() {
             "history" => {
+                let mut listing: String<{ (MAX_CMD_LEN + 8) * 10 }> = String::new();
                 if self.command_history.is_empty() {
-                    "No commands in history\r\n"
+                    let _ = listing.push_str("\r\nNo commands in history\r\n");
                 } else {
-                    "Recent commands:\r\n"
-                    // In a real implementation, would iterate and format history
+                    let _ = listing.push_str("\r\nRecent commands:\r\n");
+                    for (index, entry) in self.command_history.iter().enumerate() {
+                        let _ = write!(listing, "{:>3}  {}\r\n", index + 1, entry);
+                    }
                 }
+                self.write_response(&listing).await
             }
-            
-            "clear" | "cls" => {
-                "\x1B[2J\x1B[H" // ANSI clear screen and move cursor to home
-            }
-            
-            "exit" | "quit" => {
-                "Console session ended\r\n"
-            }
-            
             "echo on" => {
                 self.echo_enabled = true;
-                "Echo enabled\r\n"
+                self.write_response("\r\nEcho enabled\r\n").await
             }
-            
             "echo off" => {
                 self.echo_enabled = false;
-                "Echo disabled\r\n"
-            }
-            
-            "" => {
-                "" // Empty command
+                self.write_response("\r\nEcho disabled\r\n").await
             }
-            
-            _ => {
-                self.command_errors += 1;
-                "Unknown command. Type 'help' for available commands.\r\n"
+            "" => Ok(()),
+            line => {
+                let parsed = self.command_handler.parse_command(line);
+                let denied = parsed.requires_auth() && !self.command_handler.auth().is_authenticated();
+                if !denied {
+                    let mode = self.command_handler.output_mode();
+                    if self.extension.execute(line, mode, &mut self.uart_tx, &mut self.uart_rx).await {
+                        return Ok(());
+                    }
+                }
+                
+                if matches!(parsed, Command::Unknown(_)) {
+                    self.command_errors += 1;
+                }
+                let help = parsed == Command::Help;
+                let response = self.command_handler.execute_command(parsed);
+                self.write_response(&response).await?;
+                if help {
+                    self.write_response(
+                        "Session commands:\r\n\
+                         history          - Show command history\r\n\
+                         echo on|off      - Enable/disable echo\r\n"
+                    ).await?;
+                    let extension_help = self.extension.help();
+                    if !extension_help.is_empty() {
+                        self.write_response("\r\n").await?;
+                        self.write_response(extension_help).await?;
+                    }
+                }
+                Ok(())
             }
-        };
-        
-        EmbeddedString::try_from(response).map_err(|_| {
-            IoTError::System(iot_common::SystemError::InvalidOperation("Response too long"))
-        })
+        }
+    }
+    
+    /// Gets the command handler, e.g. to push system status or console auth into it
+    pub fn command_handler(&mut self) -> &mut CommandHandler {
+        &mut self.command_handler
     }
     
     /// Gets console session metrics
@@ -379,8 +506,8 @@ where
     /// 
     /// # Returns
     /// 
-    /// Reference to command history deque
-    pub fn get_command_history(&self) -> &Deque<EmbeddedString, MAX_HISTORY_ENTRIES> {
+    /// Reference to the command history
+    pub fn get_command_history(&self) -> &CommandHistory {
         &self.command_history
     }
     
@@ -407,8 +534,8 @@ where
 #[async_trait]
 impl<TX, RX> ConsoleInterface for ConsoleContainerAdapter<TX, RX>
 where
-    TX: Write + Send + Sync,
-    RX: Read + Send + Sync,
+    TX: ConsoleWrite,
+    RX: ConsoleRead,
 {
     /// Writes a line of text to the console
     /// 
@@ -422,24 +549,8 @@ where
     /// 
     /// * `Ok(())` - Message written successfully
     /// * `Err(IoTError)` - Write operation failed
-    /// 
-    /// # Implementation Details
-    /// 
-    /// - Automatically adds CRLF line termination
-    /// - Handles UART transmission errors
-    /// - Updates output statistics
     async fn write_line(&mut self, message: &str) -> Result<(), IoTError> {
-        // Write the message
-        self.uart_tx.write_all(message.as_bytes()).await.map_err(|_| {
-            IoTError::Hardware(iot_common::HardwareError::InterfaceError("UART write failed"))
-        })?;
-        
-        // Add line termination
-        self.uart_tx.write_all(b"\r\n").await.map_err(|_| {
-            IoTError::Hardware(iot_common::HardwareError::InterfaceError("UART write failed"))
-        })?;
-        
-        Ok(())
+        ConsoleContainerAdapter::write_line(self, message).await
     }
     
     /// Reads a command from the console
@@ -451,27 +562,23 @@ where
     /// 
     /// * `Ok(Some(command))` - Command received
     /// * `Ok(None)` - No command available (non-blocking)
-    /// * `Err(IoTError)` - Read operation failed
-    /// 
-    /// # Implementation Details
-    /// 
-    /// - Non-blocking operation
-    /// - Handles character echo if enabled
-    /// - Processes backspace and control characters
-    /// - Builds commands character by character
+    /// * `Err(IoTError)` - Read operation failed, or the line is longer than
+    ///   an [`EmbeddedString`]
     async fn read_command(&mut self) -> Result<Option<EmbeddedString>, IoTError> {
-        // Try to read a character
-        if let Some(ch) = self.read_char().await? {
-            // Process the character and check if command is complete
-            self.process_input_char(ch).await
-        } else {
-            Ok(None)
+        match self.read_line().await? {
+            Some(line) => EmbeddedString::try_from(line.as_str()).map(Some).map_err(|_| {
+                IoTError::configuration(ConfigError::ParsingError(error_message("Command too long")))
+            }),
+            None => Ok(None),
         }
     }
     
-    /// Handles a console command and returns response
+    /// Handles a console command
     /// 
-    /// This method processes a received command and generates an appropriate response.
+    /// Responses routinely exceed an [`EmbeddedString`] (help alone is several
+    /// hundred bytes), so the rendered output is written to the port by
+    /// [`execute_line`](ConsoleContainerAdapter::execute_line) and the returned
+    /// string is empty.
     /// 
     /// # Arguments
     /// 
@@ -479,33 +586,18 @@ where
     /// 
     /// # Returns
     /// 
-    /// * `Ok(response)` - Command processed, response generated
-    /// * `Err(IoTError)` - Command processing failed
-    /// 
-    /// # Implementation Details
-    /// 
-    /// - Integrates with command handler system
-    /// - Updates command statistics
-    /// - Handles command history
-    /// - Provides comprehensive command set
+    /// * `Ok(response)` - Command processed, output already written
+    /// * `Err(IoTError)` - Writing the output failed
     async fn handle_command(&mut self, command: &str) -> Result<EmbeddedString, IoTError> {
-        self.process_command_internal(command).await
+        self.execute_line(command).await?;
+        Ok(EmbeddedString::new())
     }
     
     /// Checks if console is ready for input/output
     /// 
-    /// This method verifies that the console interface is operational and ready
-    /// for command processing.
-    /// 
     /// # Returns
     /// 
     /// `true` if console is ready, `false` otherwise
-    /// 
-    /// # Implementation Details
-    /// 
-    /// - Checks UART interface status
-    /// - Validates session state
-    /// - Returns cached readiness status
     async fn is_ready(&self) -> bool {
         self.ready
     }
@@ -527,41 +619,44 @@ where
     /// - Echo status
     fn get_session_info(&self) -> EmbeddedString {
         let uptime = self.session_start.elapsed().as_secs();
-        let info = format!(
+        let mut info = EmbeddedString::new();
+        let _ = write!(
+            info,
             "Session: {}s, Commands: {}, Errors: {}, Echo: {}",
             uptime,
             self.commands_processed,
             self.command_errors,
             if self.echo_enabled { "On" } else { "Off" }
         );
-        
-        EmbeddedString::try_from(info.as_str())
-            .unwrap_or_else(|_| EmbeddedString::try_from("Session active").unwrap())
+        info
     }
     
     /// Sends a formatted prompt to the console
     /// 
-    /// This method displays a command prompt to indicate the console is ready for input.
-    /// 
     /// # Returns
     /// 
     /// * `Ok(())` - Prompt displayed successfully
     /// * `Err(IoTError)` - Prompt display failed
-    /// 
-    /// # Implementation Details
-    /// 
-    /// - Shows session-specific prompt
-    /// - Handles prompt formatting
-    /// - Updates prompt display state
     async fn show_prompt(&mut self) -> Result<(), IoTError> {
-        if self.show_prompt_next {
-            let prompt = "iot> ";
-            self.uart_tx.write_all(prompt.as_bytes()).await.map_err(|_| {
-                IoTError::Hardware(iot_common::HardwareError::InterfaceError("Prompt write failed"))
-            })?;
-            self.show_prompt_next = false;
+        ConsoleContainerAdapter::show_prompt(self).await
+    }
+}
+
+#[cfg(feature = "container")]
+impl<TX, RX, X> HealthCheck for ConsoleContainerAdapter<TX, RX, X>
+where
+    TX: ConsoleWrite,
+    RX: ConsoleRead,
+{
+    /// Reports console health from the readiness flag and command counters
+    fn health(&self) -> ComponentHealth {
+        if !self.ready {
+            ComponentHealth::degraded("Console not ready")
+        } else if self.command_errors > self.commands_processed {
+            ComponentHealth::degraded("Console command errors")
+        } else {
+            ComponentHealth::healthy("Console ready")
         }
-        Ok(())
     }
 }
 
@@ -595,8 +690,8 @@ pub fn create_container_console<TX, RX>(
     uart_rx: RX
 ) -> ConsoleContainerAdapter<TX, RX>
 where
-    TX: Write + Send + Sync,
-    RX: Read + Send + Sync,
+    TX: ConsoleWrite,
+    RX: ConsoleRead,
 {
     ConsoleContainerAdapter::new(uart_tx, uart_rx)
 }
@@ -631,8 +726,287 @@ pub fn create_container_console_with_config<TX, RX>(
     echo_enabled: bool
 ) -> ConsoleContainerAdapter<TX, RX>
 where
-    TX: Write + Send + Sync,
-    RX: Read + Send + Sync,
+    TX: ConsoleWrite,
+    RX: ConsoleRead,
 {
     ConsoleContainerAdapter::new_with_config(uart_tx, uart_rx, echo_enabled)
-}
\ No newline at end of file
+}
+
+/// Container console over the ESP32-C3 USB Serial/JTAG port
+/// 
+/// The port used by `espflash monitor` and most development setups; no extra
+/// wiring or UART pins are needed.
+#[cfg(feature = "container")]
+pub type UsbSerialConsole = ConsoleContainerAdapter<
+    UsbSerialJtagTx<'static, Async>,
+    UsbSerialJtagRx<'static, Async>,
+>;
+
+#[cfg(feature = "container")]
+impl ConsoleContainerAdapter<UsbSerialJtagTx<'static, Async>, UsbSerialJtagRx<'static, Async>> {
+    /// Creates a console on the USB Serial/JTAG port
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,no_run
+    /// use serial_console_embassy::UsbSerialConsole;
+    /// use esp_hal::usb_serial_jtag::UsbSerialJtag;
+    /// 
+    /// let usb = UsbSerialJtag::new(peripherals.USB_DEVICE).into_async();
+    /// let console = UsbSerialConsole::from_usb(usb);
+    /// let container = IoTContainer::new(platform, sensor, network, publisher, console, config).await?;
+    /// ```
+    pub fn from_usb(usb: UsbSerialJtag<'static, Async>) -> Self {
+        let (usb_rx, usb_tx) = usb.split();
+        Self::new(usb_tx, usb_rx)
+    }
+}
+
+/// Creates a container console on the USB Serial/JTAG port
+/// 
+/// # Examples
+/// 
+/// ```rust,no_run
+/// use serial_console_embassy::create_usb_console;
+/// 
+/// let console = create_usb_console(UsbSerialJtag::new(peripherals.USB_DEVICE).into_async());
+/// ```
+#[cfg(feature = "container")]
+pub fn create_usb_console(usb: UsbSerialJtag<'static, Async>) -> UsbSerialConsole {
+    UsbSerialConsole::from_usb(usb)
+}
+
+/// USB Serial/JTAG port handed over for [`usb_console_driver`]
+#[cfg(feature = "container")]
+static USB_PORT: BlockingMutex<CriticalSectionRawMutex, RefCell<Option<UsbSerialJtag<'static, Async>>>> =
+    BlockingMutex::new(RefCell::new(None));
+
+/// Hands the USB Serial/JTAG port to [`usb_console_driver`]
+/// 
+/// The factory's driver signature only carries the console configuration, so
+/// the peripheral is parked here first. Replaces a port that was not used yet.
+#[cfg(feature = "container")]
+pub fn provide_usb_port(usb: UsbSerialJtag<'static, Async>) {
+    USB_PORT.lock(|port| *port.borrow_mut() = Some(usb));
+}
+
+/// Console driver for `ComponentFactory::create_console_with`
+/// 
+/// Builds a [`UsbSerialConsole`] on the port given to [`provide_usb_port`],
+/// with echo taken from `config.enable_echo`.
+/// 
+/// # Returns
+/// 
+/// * `Ok(console)` - Console on the USB Serial/JTAG port
+/// * `Err(IoTError)` - The interface is not `"USB"`, or no port was provided
+///   (each port builds one console)
+/// 
+/// # Examples
+/// 
+/// ```rust,ignore
+/// use serial_console_embassy::{provide_usb_port, usb_console_driver};
+/// 
+/// provide_usb_port(UsbSerialJtag::new(peripherals.USB_DEVICE).into_async());
+/// let console = ComponentFactory::create_console_with(&mut platform, &config.console, Some(usb_console_driver)).await?;
+/// ```
+// Signature fixed by iot_container::ConsoleDriver.
+#[allow(clippy::result_large_err)]
+#[cfg(feature = "container")]
+pub fn usb_console_driver(config: &ConsoleConfig) -> IoTResult<Box<dyn ConsoleInterface + Send + Sync>> {
+    if config.interface_type.as_str() != "USB" {
+        return Err(IoTError::configuration(
+            ConfigError::InvalidParameter(error_message("Console driver only supports USB"))
+        ));
+    }
+    
+    let usb = USB_PORT.lock(|port| port.borrow_mut().take()).ok_or_else(|| {
+        IoTError::hardware(HardwareError::UARTError(error_message("USB Serial/JTAG port not provided")))
+    })?;
+    let mut console = UsbSerialConsole::from_usb(usb);
+    console.set_echo_enabled(config.enable_echo);
+    Ok(Box::new(console))
+}
+
+#[cfg(all(test, feature = "container"))]
+mod tests {
+    use super::*;
+    use iot_common::network::block_on;
+
+    /// Port that replays scripted input one byte per read
+    struct ScriptedRx {
+        input: &'static [u8],
+    }
+
+    #[async_trait]
+    impl ConsoleRead for ScriptedRx {
+        async fn read_byte(&mut self) -> Result<Option<u8>, IoTError> {
+            match self.input.split_first() {
+                Some((&byte, rest)) => {
+                    self.input = rest;
+                    Ok(Some(byte))
+                }
+                None => Ok(None),
+            }
+        }
+    }
+
+    /// Port that captures everything written to it
+    struct CapturedTx {
+        output: heapless::Vec<u8, 2048>,
+    }
+
+    #[async_trait]
+    impl ConsoleWrite for CapturedTx {
+        async fn write_bytes(&mut self, data: &[u8]) -> Result<(), IoTError> {
+            let len = data.len().min(self.output.capacity() - self.output.len());
+            let _ = self.output.extend_from_slice(&data[..len]);
+            Ok(())
+        }
+    }
+
+    /// Extension that answers `ping` and counts the lines it was offered
+    #[derive(Default)]
+    struct PingExtension {
+        offered: u32,
+    }
+
+    impl ConsoleExtension<CapturedTx, ScriptedRx> for PingExtension {
+        async fn execute<'a>(
+            &'a mut self,
+            line: &'a str,
+            mode: OutputMode,
+            tx: &'a mut CapturedTx,
+            _rx: &'a mut ScriptedRx,
+        ) -> bool {
+            self.offered += 1;
+            if line != "ping" {
+                return false;
+            }
+            let reply: &[u8] = if mode == OutputMode::Json { b"{\"pong\":true}\r\n" } else { b"pong\r\n" };
+            let _ = tx.write_bytes(reply).await;
+            true
+        }
+
+        fn help(&self) -> &'static str {
+            "ping             - Answer pong\r\n"
+        }
+    }
+
+    fn ports(input: &'static [u8]) -> (CapturedTx, ScriptedRx) {
+        (CapturedTx { output: heapless::Vec::new() }, ScriptedRx { input })
+    }
+
+    fn console(input: &'static [u8]) -> ConsoleContainerAdapter<CapturedTx, ScriptedRx> {
+        let (tx, rx) = ports(input);
+        ConsoleContainerAdapter::new_with_config(tx, rx, false)
+    }
+
+    /// Reads characters until the adapter reports a complete line
+    fn next_line<X: ConsoleExtension<CapturedTx, ScriptedRx>>(
+        console: &mut ConsoleContainerAdapter<CapturedTx, ScriptedRx, X>,
+    ) -> Option<String<MAX_INPUT_LEN>> {
+        block_on(async {
+            while !console.uart_rx.input.is_empty() {
+                if let Some(line) = console.read_line().await.unwrap() {
+                    return Some(line);
+                }
+            }
+            None
+        })
+    }
+
+    fn output<X>(console: &ConsoleContainerAdapter<CapturedTx, ScriptedRx, X>) -> &str {
+        core::str::from_utf8(&console.uart_tx.output).unwrap()
+    }
+
+    #[test]
+    fn test_command_routed_through_command_handler() {
+        let mut console = console(b"wifi ssid TestNet\r");
+
+        let command = block_on(async {
+            loop {
+                if let Some(command) = console.read_command().await.unwrap() {
+                    break command;
+                }
+            }
+        });
+        assert_eq!(command.as_str(), "wifi ssid TestNet");
+        let response = block_on(console.handle_command(&command)).unwrap();
+
+        // Output goes straight to the port; the shared handler holds the new setting
+        assert!(response.is_empty());
+        assert_eq!(console.command_handler().get_config().wifi.ssid.as_str(), "TestNet");
+        assert!(output(&console).contains("WiFi SSID set to: TestNet"));
+        assert_eq!(console.get_session_metrics().0, 1);
+        assert_eq!(console.get_command_history().len(), 1);
+    }
+
+    #[test]
+    fn test_unknown_command_counted_as_error() {
+        let mut console = console(b"bogus\r");
+
+        let line = next_line(&mut console).unwrap();
+        block_on(console.execute_line(&line)).unwrap();
+
+        let (processed, errors, _) = console.get_session_metrics();
+        assert_eq!((processed, errors), (1, 1));
+        assert!(!console.uart_tx.output.is_empty(), "Handler output should reach the port");
+    }
+
+    #[test]
+    fn test_arrow_keys_recall_history() {
+        let mut console = console(b"status\rinfo\r\x1b[A\x1b[A\x1b[B\r");
+
+        assert_eq!(next_line(&mut console).unwrap().as_str(), "status");
+        assert_eq!(next_line(&mut console).unwrap().as_str(), "info");
+        // Up, up, down lands on the newer entry again
+        assert_eq!(next_line(&mut console).unwrap().as_str(), "info");
+        assert!(output(&console).contains("\r\x1b[Kiot> status"));
+    }
+
+    #[test]
+    fn test_extension_runs_before_command_handler() {
+        let (tx, rx) = ports(b"ping\rstatus\r");
+        let mut console = ConsoleContainerAdapter::with_extension(tx, rx, PingExtension::default());
+
+        let line = next_line(&mut console).unwrap();
+        block_on(console.execute_line(&line)).unwrap();
+        assert_eq!(output(&console), "ping\r\npong\r\n");
+
+        // Lines the extension declines fall through to the shared handler
+        let line = next_line(&mut console).unwrap();
+        block_on(console.execute_line(&line)).unwrap();
+        assert!(output(&console).contains("System Status"));
+        assert_eq!(console.extension.offered, 2);
+
+        // The extension sees the handler's output mode and adds to help
+        block_on(console.execute_line("json on")).unwrap();
+        block_on(console.execute_line("ping")).unwrap();
+        assert!(output(&console).ends_with("{\"pong\":true}\r\n"));
+        block_on(console.execute_line("help")).unwrap();
+        assert!(output(&console).ends_with("ping             - Answer pong\r\n"));
+    }
+
+    #[test]
+    fn test_privileged_command_denied_before_extension() {
+        let (tx, rx) = ports(b"");
+        let mut console = ConsoleContainerAdapter::with_extension(tx, rx, PingExtension::default());
+        console.command_handler().set_auth(crate::ConsoleAuth::with_password("secret").unwrap());
+
+        block_on(console.execute_line("restart")).unwrap();
+
+        assert_eq!(console.extension.offered, 0);
+        assert!(output(&console).contains("Access denied"));
+    }
+
+    #[test]
+    fn test_login_line_masked_and_kept_out_of_history() {
+        let (tx, rx) = ports(b"login secret\r");
+        let mut console = ConsoleContainerAdapter::new(tx, rx);
+
+        assert_eq!(next_line(&mut console).unwrap().as_str(), "login secret");
+
+        assert_eq!(output(&console), "login ******\r\n");
+        assert!(console.get_command_history().is_empty());
+    }
+}
@@ -12,7 +12,7 @@ use esp_hal::{
 use embassy_executor::Spawner;
 use embassy_time::{Duration, Timer};
 
-use wifi_embassy::{WiFiManager, WiFiConfig};
+use wifi_embassy::{WiFiManager, WiFiConfig, WiFiTimeouts};
 
 #[embassy_executor::task]
 async fn connection_monitor_task(wifi_manager: &'static WiFiManager) {
@@ -85,6 +85,7 @@ async fn main(spawner: Spawner) -> ! {
     let wifi_config = WiFiConfig {
         ssid: env!("WIFI_SSID"),
         password: env!("WIFI_PASSWORD"),
+        timeouts: WiFiTimeouts::default(),
     };
 
     rprintln!("📡 Target Network: {}", wifi_config.ssid);
@@ -23,7 +23,7 @@ use embassy_net::tcp::TcpSocket;
 use embedded_io_async::Write;
 use core::net::Ipv4Addr;
 
-use wifi_embassy::{WiFiManager, WiFiConfig};
+use wifi_embassy::{WiFiManager, WiFiConfig, WiFiTimeouts};
 
 // Environment variables from .cargo/config.toml
 const WIFI_SSID: &str = env!("WIFI_SSID", "Set WIFI_SSID in .cargo/config.toml");
@@ -269,6 +269,7 @@ async fn main(spawner: Spawner) {
     let wifi_config = WiFiConfig {
         ssid: WIFI_SSID,
         password: WIFI_PASSWORD,
+        timeouts: WiFiTimeouts::default(),
     };
 
     rprintln!("[WIFI-MQTT] Starting WiFi connection...");
@@ -22,7 +22,7 @@ use rtt_target::{rprintln, rtt_init_print};
 use embassy_executor::Spawner;
 use embassy_time::{Duration, Timer};
 
-use wifi_embassy::{WiFiManager, WiFiConfig};
+use wifi_embassy::{WiFiManager, WiFiConfig, WiFiTimeouts};
 
 // WiFi credentials from environment variables in .cargo/config.toml
 const WIFI_SSID: &str = env!("WIFI_SSID", "Set WIFI_SSID in .cargo/config.toml");
@@ -65,12 +65,13 @@ async fn main(spawner: Spawner) {
     let wifi_config = WiFiConfig {
         ssid: WIFI_SSID,
         password: WIFI_PASSWORD,
+        timeouts: WiFiTimeouts::default(),
     };
 
     rprintln!("Hardware initialized, starting WiFi...");
 
     // Initialize WiFi manager (TIMG1 already used for Embassy above)
-    let mut wifi_manager = match WiFiManager::new(
+    let wifi_manager = match WiFiManager::new(
         spawner,
         peripherals.TIMG0,
         peripherals.WIFI,
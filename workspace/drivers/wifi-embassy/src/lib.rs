@@ -18,7 +18,7 @@
 //! ## Quick Start
 //!
 //! ```no_run
-//! use wifi_embassy::{WiFiManager, WiFiConfig};
+//! use wifi_embassy::{WiFiManager, WiFiConfig, WiFiTimeouts};
 //! use embassy_executor::Spawner;
 //!
 //! #[esp_hal_embassy::main]
@@ -31,6 +31,7 @@
 //!     let wifi_config = WiFiConfig {
 //!         ssid: "YourNetwork",
 //!         password: "YourPassword",
+//!         timeouts: WiFiTimeouts::default(),
 //!     };
 //!     
 //!     let wifi_manager = WiFiManager::new(
@@ -115,6 +116,7 @@
 //! let wifi_config = WiFiConfig {
 //!     ssid: env!("WIFI_SSID"),
 //!     password: env!("WIFI_PASSWORD"),
+//!     timeouts: WiFiTimeouts::default(),
 //! };
 //! ```
 //!
@@ -145,7 +147,14 @@ pub mod wifi_manager;
 mod trait_impl;
 
 // Re-export main types for convenient access
-pub use wifi_manager::{WiFiManager, WiFiConfig, ConnectionInfo, WiFiError};
+pub use wifi_manager::{
+    WiFiManager, WiFiConfig, WiFiTimeouts, ConnectionInfo, ConnectionChange, WiFiError,
+    ConnectionEvent, ConnectionEventReceiver, MAX_CONNECTION_EVENT_RECEIVERS, disconnect_reason_str,
+    WiFiStats
+};
+
+// Shared connectivity trait, implemented by WiFiManager
+pub use iot_common::{NetworkStackProvider, NetworkInfo, LinkStatus};
 
 // Re-export container integration when available
 #[cfg(feature = "container")]
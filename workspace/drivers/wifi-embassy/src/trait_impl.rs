@@ -11,9 +11,9 @@ use iot_common::IoTError;
 
 // Import the container trait (when iot-container is available)
 #[cfg(feature = "container")]
-use iot_container::traits::{NetworkManager, ConnectionInfo as ContainerConnectionInfo, EmbeddedString};
+use iot_container::traits::{NetworkManager, HealthCheck, ComponentHealth, ConnectionInfo as ContainerConnectionInfo, EmbeddedString};
 
-use crate::wifi_manager::{WiFiManager, ConnectionInfo, WiFiError};
+use crate::wifi_manager::{WiFiManager, ConnectionInfo};
 
 /// Adapter that implements the IoT Container NetworkManager trait for WiFiManager
 /// 
@@ -199,32 +199,6 @@ impl WiFiContainerAdapter {
         self.last_connectivity_test = Some(Instant::now().as_millis());
     }
     
-    /// Converts WiFi errors to IoT errors
-    /// 
-    /// # Arguments
-    /// 
-    /// * `wifi_error` - WiFi-specific error to convert
-    /// 
-    /// # Returns
-    /// 
-    /// Standardized IoT error
-    fn convert_error(&self, wifi_error: WiFiError) -> IoTError {
-        match wifi_error {
-            WiFiError::HardwareInit(msg) => {
-                IoTError::Hardware(iot_common::HardwareError::InitializationFailed(msg))
-            }
-            WiFiError::Configuration(msg) => {
-                IoTError::Configuration(iot_common::ConfigError::InvalidFormat(msg))
-            }
-            WiFiError::Connection(msg) => {
-                IoTError::Network(iot_common::NetworkError::ConnectionFailed(msg))
-            }
-            WiFiError::Dhcp(msg) => {
-                IoTError::Network(iot_common::NetworkError::DhcpFailed(msg))
-            }
-        }
-    }
-    
     /// Gets connection metrics for monitoring
     /// 
     /// # Returns
@@ -424,6 +398,15 @@ impl NetworkManager for WiFiContainerAdapter {
         }
     }
     
+    /// Measures round-trip time to `address` with an ICMP echo request
+    /// 
+    /// Delegates to [`WiFiManager::ping`]; fails immediately when the
+    /// `icmp` feature is off or WiFi is not connected.
+    async fn ping(&self, address: embassy_net::Ipv4Address, timeout: embassy_time::Duration) -> Result<embassy_time::Duration, IoTError> {
+        self.wifi_manager.ping(address, timeout).await
+            .map_err(IoTError::from)
+    }
+    
     /// Gets the network stack for protocol operations
     /// 
     /// Returns a reference to the underlying network stack for TCP/UDP operations.
@@ -446,6 +429,20 @@ impl NetworkManager for WiFiContainerAdapter {
     }
 }
 
+#[cfg(feature = "container")]
+impl HealthCheck for WiFiContainerAdapter {
+    /// Reports network health from the connection state and attempt counters
+    fn health(&self) -> ComponentHealth {
+        if !self.wifi_manager.is_connected() {
+            ComponentHealth::failed("WiFi disconnected")
+        } else if self.connection_failures > self.successful_connections {
+            ComponentHealth::degraded("WiFi connection unstable")
+        } else {
+            ComponentHealth::healthy("WiFi connected")
+        }
+    }
+}
+
 // Convenience functions for creating container-compatible WiFi instances
 
 /// Creates a new WiFi network adapter for use with the IoT container
@@ -7,7 +7,14 @@ extern crate alloc;
 
 use embassy_executor::Spawner;
 use embassy_net::{Config as NetConfig, Stack, StackResources, Runner};
-use embassy_time::{Duration, Timer};
+use embassy_time::{Duration, Instant, Timer};
+#[cfg(feature = "icmp")]
+use embassy_net::icmp::{PacketMetadata, ping::{PingManager, PingParams}};
+use embassy_sync::blocking_mutex::{Mutex as BlockingMutex, raw::CriticalSectionRawMutex};
+use embassy_sync::signal::Signal;
+use embassy_sync::watch::{Receiver, Watch};
+use core::cell::RefCell;
+use core::sync::atomic::{AtomicU8, Ordering};
 use esp_hal::{
     peripherals::{TIMG0, WIFI, RNG},
     timer::timg::TimerGroup,
@@ -16,10 +23,14 @@ use esp_hal::{
 use esp_wifi::{
     init,
     wifi::{Configuration, ClientConfiguration, WifiController, WifiDevice, WifiState, WifiEvent},
+    wifi::event::{self, EventExt},
     EspWifiController,
 };
 use rtt_target::rprintln;
 
+use iot_common::{NetworkStackProvider, NetworkInfo, LinkStatus, log_debug};
+use iot_common::{IoTError, NetworkError, error::utils as error_utils};
+
 /// Utility macro for creating static allocations (from working examples)
 #[macro_export]
 macro_rules! mk_static {
@@ -30,6 +41,173 @@ macro_rules! mk_static {
     }};
 }
 
+/// Maximum number of concurrent [`WiFiManager::connection_events`] receivers
+pub const MAX_CONNECTION_EVENT_RECEIVERS: usize = 4;
+
+/// Socket slots in the network stack: DHCP, MQTT (TCP), SNTP (UDP), plus
+/// one ICMP socket for [`WiFiManager::ping`] when the `icmp` feature is on
+const STACK_SOCKETS: usize = if cfg!(feature = "icmp") { 4 } else { 3 };
+
+/// Per-direction ICMP buffer size; an echo with the default payload fits easily
+#[cfg(feature = "icmp")]
+const PING_BUFFER_SIZE: usize = 128;
+
+/// Connection state change published by the connection task
+/// 
+/// Receivers only see the latest state, so a drop that recovers before they
+/// wake shows up as `connected: true` with a higher `disconnect_count`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionEvent {
+    /// Whether the station is associated with the access point
+    pub connected: bool,
+    /// esp-wifi disconnect reason code of the last drop (see [`disconnect_reason_str`])
+    pub reason: Option<u8>,
+    /// Number of disconnects since boot
+    pub disconnect_count: u32,
+}
+
+/// Receiver returned by [`WiFiManager::connection_events`]
+pub type ConnectionEventReceiver = Receiver<'static, CriticalSectionRawMutex, ConnectionEvent, MAX_CONNECTION_EVENT_RECEIVERS>;
+
+/// Latest connection state, updated on every StaConnected/StaDisconnected transition
+static CONNECTION_EVENTS: Watch<CriticalSectionRawMutex, ConnectionEvent, MAX_CONNECTION_EVENT_RECEIVERS> = Watch::new();
+
+/// Reason code captured by the esp-wifi StaDisconnected event handler
+static LAST_DISCONNECT_REASON: AtomicU8 = AtomicU8::new(0);
+
+/// Credentials for the connection task to switch to, see [`WiFiManager::update_credentials`]
+static NEW_CREDENTIALS: Signal<CriticalSectionRawMutex, (heapless::String<32>, heapless::String<64>)> = Signal::new();
+
+/// Connection statistics, updated by the connection task
+static WIFI_STATS: BlockingMutex<CriticalSectionRawMutex, RefCell<StatsTracker>> =
+    BlockingMutex::new(RefCell::new(StatsTracker::new()));
+
+/// Snapshot of the connection statistics returned by [`WiFiManager::stats`]
+/// 
+/// Counts and times cover the period since boot or the last
+/// [`WiFiManager::reset_stats`]. Counters saturate instead of wrapping, so a
+/// device that has been flapping for months reports `u32::MAX`, not zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WiFiStats {
+    /// Successful associations with the access point
+    pub connects: u32,
+    /// Associations that ended in a disconnect
+    pub disconnects: u32,
+    /// Associations that followed an earlier disconnect
+    pub reconnects: u32,
+    /// Total time spent associated, including the current session
+    pub connected_time: Duration,
+    /// Time covered by these statistics
+    pub tracked_time: Duration,
+    /// esp-wifi reason code of the most recent disconnect (see [`disconnect_reason_str`])
+    pub last_disconnect_reason: Option<u8>,
+}
+
+impl WiFiStats {
+    /// Share of [`tracked_time`](Self::tracked_time) spent connected, 0-100
+    pub fn connected_percent(&self) -> u8 {
+        let tracked = self.tracked_time.as_ticks();
+        if tracked == 0 {
+            return 0;
+        }
+        let percent = self.connected_time.as_ticks().saturating_mul(100) / tracked;
+        percent.min(100) as u8
+    }
+}
+
+/// Bookkeeping behind [`WiFiStats`]; takes explicit timestamps so it can be tested
+#[derive(Debug, Clone, Copy)]
+struct StatsTracker {
+    connects: u32,
+    disconnects: u32,
+    reconnects: u32,
+    last_disconnect_reason: Option<u8>,
+    /// Connected time of finished sessions
+    connected_total: Duration,
+    /// Start of the current session, `None` while disconnected
+    connected_since: Option<Instant>,
+    /// Start of the tracked period
+    since: Instant,
+    /// A disconnect happened at some point, survives resets
+    dropped_before: bool,
+}
+
+impl StatsTracker {
+    const fn new() -> Self {
+        Self {
+            connects: 0,
+            disconnects: 0,
+            reconnects: 0,
+            last_disconnect_reason: None,
+            connected_total: Duration::from_ticks(0),
+            connected_since: None,
+            since: Instant::from_ticks(0),
+            dropped_before: false,
+        }
+    }
+
+    fn record_connect(&mut self, now: Instant) {
+        if self.connected_since.is_some() {
+            return;
+        }
+        self.connects = self.connects.saturating_add(1);
+        if self.dropped_before {
+            self.reconnects = self.reconnects.saturating_add(1);
+        }
+        self.connected_since = Some(now);
+    }
+
+    fn record_disconnect(&mut self, now: Instant, reason: u8) {
+        if let Some(start) = self.connected_since.take() {
+            self.connected_total = saturating_add(self.connected_total, now.saturating_duration_since(start));
+        }
+        self.disconnects = self.disconnects.saturating_add(1);
+        self.last_disconnect_reason = Some(reason);
+        self.dropped_before = true;
+    }
+
+    /// Start a new period at `now`; a running session keeps counting from `now`
+    fn reset(&mut self, now: Instant) {
+        let connected = self.connected_since.is_some();
+        *self = Self {
+            connected_since: connected.then_some(now),
+            since: now,
+            dropped_before: self.dropped_before,
+            ..Self::new()
+        };
+    }
+
+    fn snapshot(&self, now: Instant) -> WiFiStats {
+        let current = self.connected_since.map_or(Duration::from_ticks(0), |start| now.saturating_duration_since(start));
+        WiFiStats {
+            connects: self.connects,
+            disconnects: self.disconnects,
+            reconnects: self.reconnects,
+            connected_time: saturating_add(self.connected_total, current),
+            tracked_time: now.saturating_duration_since(self.since),
+            last_disconnect_reason: self.last_disconnect_reason,
+        }
+    }
+}
+
+fn saturating_add(a: Duration, b: Duration) -> Duration {
+    Duration::from_ticks(a.as_ticks().saturating_add(b.as_ticks()))
+}
+
+/// Describe an esp-wifi (ESP-IDF `wifi_err_reason_t`) disconnect reason code
+pub fn disconnect_reason_str(reason: u8) -> &'static str {
+    match reason {
+        2 | 3 => "authentication expired",
+        4 | 8 => "disassociated by access point",
+        15 | 202 | 204 => "authentication failed (check password)",
+        200 => "beacon timeout (signal lost)",
+        201 => "access point not found",
+        203 => "association failed",
+        205 => "connection failed",
+        _ => "unspecified",
+    }
+}
+
 /// WiFi network configuration
 /// 
 /// Contains the necessary parameters for connecting to a WiFi network.
@@ -39,18 +217,20 @@ macro_rules! mk_static {
 /// # Examples
 /// 
 /// ```rust
-/// use wifi_embassy::WiFiConfig;
+/// use wifi_embassy::{WiFiConfig, WiFiTimeouts};
 /// 
 /// // Using environment variables (recommended)
 /// let config = WiFiConfig {
 ///     ssid: env!("WIFI_SSID"),
 ///     password: env!("WIFI_PASSWORD"),
+///     timeouts: WiFiTimeouts::default(),
 /// };
 /// 
 /// // Using static strings
 /// let config = WiFiConfig {
 ///     ssid: "MyNetwork",
 ///     password: "MyPassword",
+///     timeouts: WiFiTimeouts::default(),
 /// };
 /// ```
 /// 
@@ -72,6 +252,103 @@ pub struct WiFiConfig {
     /// For WPA2/WPA3 networks, this is the network passphrase.
     /// Minimum length: 8 characters, Maximum length: 63 characters.
     pub password: heapless::String<64>,
+    
+    /// How long [`WiFiManager::new`] waits for the link and for DHCP
+    pub timeouts: WiFiTimeouts,
+}
+
+impl WiFiConfig {
+    /// Maximum SSID length in bytes (IEEE 802.11)
+    pub const MAX_SSID_LEN: usize = 32;
+
+    /// Minimum WPA2 passphrase length in bytes
+    pub const MIN_PASSWORD_LEN: usize = 8;
+
+    /// Maximum WPA2 passphrase length in bytes
+    pub const MAX_PASSWORD_LEN: usize = 63;
+
+    /// Builds a configuration from raw credential bytes
+    ///
+    /// Unlike `heapless::String::from_str(..).unwrap_or_default()`, oversized
+    /// or non-UTF-8 credentials are reported instead of silently becoming
+    /// empty strings. The result is checked with [`Self::validate`] and uses
+    /// the default timeouts.
+    pub fn from_credentials(ssid: &[u8], password: &[u8]) -> Result<Self, WiFiError> {
+        let ssid = core::str::from_utf8(ssid)
+            .map_err(|_| WiFiError::Configuration("SSID is not valid UTF-8"))?;
+        let password = core::str::from_utf8(password)
+            .map_err(|_| WiFiError::Configuration("password is not valid UTF-8"))?;
+
+        let config = Self {
+            ssid: heapless::String::try_from(ssid)
+                .map_err(|_| WiFiError::Configuration("SSID longer than 32 bytes"))?,
+            password: heapless::String::try_from(password)
+                .map_err(|_| WiFiError::Configuration("password longer than 63 bytes"))?,
+            timeouts: WiFiTimeouts::default(),
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks the credentials against the WPA2-Personal rules
+    ///
+    /// The SSID must be 1-32 bytes; the password must be empty (open
+    /// network) or 8-63 bytes. Called by [`WiFiManager::new`] so bad
+    /// credentials fail at boot instead of after the link timeout.
+    pub fn validate(&self) -> Result<(), WiFiError> {
+        if self.ssid.is_empty() {
+            return Err(WiFiError::Configuration("SSID is empty"));
+        }
+        if self.ssid.len() > Self::MAX_SSID_LEN {
+            return Err(WiFiError::Configuration("SSID longer than 32 bytes"));
+        }
+
+        let password_len = self.password.len();
+        if password_len > 0 && password_len < Self::MIN_PASSWORD_LEN {
+            return Err(WiFiError::Configuration("password shorter than 8 bytes"));
+        }
+        if password_len > Self::MAX_PASSWORD_LEN {
+            return Err(WiFiError::Configuration("password longer than 63 bytes"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Timeouts applied while [`WiFiManager::new`] brings the connection up
+/// 
+/// The defaults (30 seconds each) suit home networks. Slow enterprise
+/// networks may need longer; battery devices can shorten them to fail fast
+/// and go back to sleep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WiFiTimeouts {
+    /// Maximum wait for the WiFi link (association with the access point)
+    pub link: Duration,
+    
+    /// Maximum wait for a DHCP lease once the link is up
+    pub dhcp: Duration,
+}
+
+impl WiFiTimeouts {
+    /// Interval between link/DHCP status checks
+    pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+    
+    /// Number of poll intervals after which `timeout` has expired
+    /// 
+    /// The wait loops give up once their counter exceeds this value, so a
+    /// timeout that is not a multiple of [`Self::POLL_INTERVAL`] rounds up.
+    pub fn max_polls(timeout: Duration) -> u64 {
+        timeout.as_millis().div_ceil(Self::POLL_INTERVAL.as_millis())
+    }
+}
+
+impl Default for WiFiTimeouts {
+    fn default() -> Self {
+        Self {
+            link: Duration::from_secs(30),
+            dhcp: Duration::from_secs(30),
+        }
+    }
 }
 
 /// WiFi connection status and network information
@@ -90,24 +367,31 @@ pub struct WiFiConfig {
 ///     println!("Subnet: /{}", info.subnet_prefix);
 /// }
 /// ```
-#[derive(Debug, Clone)]
+/// 
+/// With the `serde` feature the addresses serialize as dotted strings
+/// (`"192.168.1.42"`), so the JSON matches what the console prints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConnectionInfo {
     /// IP address assigned to this device via DHCP
     /// 
     /// This is the IPv4 address that other devices can use to
     /// communicate with this ESP32-C3 device.
+    #[cfg_attr(feature = "serde", serde(with = "dotted_ipv4"))]
     pub ip_address: embassy_net::Ipv4Address,
     
     /// Gateway IP address (typically the router)
     /// 
     /// This is the IP address of the router/gateway that provides
     /// internet access. `None` if no gateway is configured.
+    #[cfg_attr(feature = "serde", serde(with = "dotted_ipv4::option"))]
     pub gateway: Option<embassy_net::Ipv4Address>,
     
     /// DNS server addresses
     /// 
     /// List of DNS servers provided by DHCP, used for domain
     /// name resolution. Maximum of 3 servers supported.
+    #[cfg_attr(feature = "serde", serde(with = "dotted_ipv4::list"))]
     pub dns_servers: heapless::Vec<embassy_net::Ipv4Address, 3>,
     
     /// Subnet prefix length (e.g., 24 for /24 subnet)
@@ -117,6 +401,92 @@ pub struct ConnectionInfo {
     pub subnet_prefix: u8,
 }
 
+/// `serde(with)` helpers writing [`Ipv4Address`](embassy_net::Ipv4Address) as `"a.b.c.d"`
+///
+/// Spelled out rather than left to serde's own `Ipv4Addr` impl, which falls
+/// back to a byte tuple for non human-readable formats and whose `no_std`
+/// support depends on the serde version.
+#[cfg(feature = "serde")]
+mod dotted_ipv4 {
+    use core::fmt::Write;
+    use embassy_net::Ipv4Address;
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Longest dotted quad, `255.255.255.255`
+    const MAX_DOTTED_LEN: usize = 15;
+
+    /// Newtype carrying the string form through `Option` and sequences
+    pub struct Dotted(pub Ipv4Address);
+
+    impl Serialize for Dotted {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut text: heapless::String<MAX_DOTTED_LEN> = heapless::String::new();
+            write!(text, "{}", self.0).map_err(|_| serde::ser::Error::custom("IPv4 address too long"))?;
+            serializer.serialize_str(&text)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Dotted {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let text = <&str>::deserialize(deserializer)?;
+            text.parse()
+                .map(Dotted)
+                .map_err(|_| de::Error::invalid_value(de::Unexpected::Str(text), &"a dotted IPv4 address"))
+        }
+    }
+
+    pub fn serialize<S: Serializer>(address: &Ipv4Address, serializer: S) -> Result<S::Ok, S::Error> {
+        Dotted(*address).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Ipv4Address, D::Error> {
+        Dotted::deserialize(deserializer).map(|dotted| dotted.0)
+    }
+
+    pub mod option {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(address: &Option<Ipv4Address>, serializer: S) -> Result<S::Ok, S::Error> {
+            address.map(Dotted).serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Ipv4Address>, D::Error> {
+            Ok(Option::<Dotted>::deserialize(deserializer)?.map(|dotted| dotted.0))
+        }
+    }
+
+    pub mod list {
+        use super::*;
+
+        pub fn serialize<S: Serializer, const N: usize>(
+            addresses: &heapless::Vec<Ipv4Address, N>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(addresses.iter().copied().map(Dotted))
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+            deserializer: D,
+        ) -> Result<heapless::Vec<Ipv4Address, N>, D::Error> {
+            let dotted = heapless::Vec::<Dotted, N>::deserialize(deserializer)?;
+            Ok(dotted.into_iter().map(|dotted| dotted.0).collect())
+        }
+    }
+}
+
+/// Outcome of [`WiFiManager::refresh_connection_info`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionChange {
+    /// Cached information still matches the stack
+    Unchanged,
+    /// An IPv4 configuration appeared after having none
+    Acquired,
+    /// DHCP renewal changed the address, gateway, DNS servers or prefix
+    Changed,
+    /// The lease was lost; the cached information was cleared
+    Lost,
+}
+
 /// WiFi-specific error types
 /// 
 /// These errors provide specific context for different types of WiFi failures,
@@ -186,6 +556,14 @@ pub enum WiFiError {
     /// - Network configuration conflicts
     /// - Router DHCP service disabled
     Dhcp(&'static str),
+    
+    /// ICMP echo (ping) failed
+    /// 
+    /// Indicates problems with:
+    /// - ICMP sockets not enabled (`icmp` feature off)
+    /// - Target not answering within the timeout
+    /// - Echo request could not be sent
+    Icmp(&'static str),
 }
 
 impl core::fmt::Display for WiFiError {
@@ -195,10 +573,27 @@ impl core::fmt::Display for WiFiError {
             WiFiError::Configuration(msg) => write!(f, "WiFi configuration failed: {}", msg),
             WiFiError::Connection(msg) => write!(f, "WiFi connection failed: {}", msg),
             WiFiError::Dhcp(msg) => write!(f, "DHCP failed: {}", msg),
+            WiFiError::Icmp(msg) => write!(f, "Ping failed: {}", msg),
         }
     }
 }
 
+/// Maps each WiFi failure to its [`NetworkError`] variant, keeping the
+/// displayed text as the message
+impl From<WiFiError> for IoTError {
+    fn from(error: WiFiError) -> Self {
+        let message = error_utils::display_message(&error);
+        let network_error = match error {
+            WiFiError::HardwareInit(_) => NetworkError::HardwareInitFailed(message),
+            WiFiError::Configuration(_) => NetworkError::WiFiConfigurationError(message),
+            WiFiError::Connection(_) => NetworkError::WiFiConnectionFailed(message),
+            WiFiError::Dhcp(_) => NetworkError::DHCPFailed(message),
+            WiFiError::Icmp(_) => NetworkError::Timeout(message),
+        };
+        IoTError::network(network_error)
+    }
+}
+
 /// WiFi connectivity manager using Embassy async framework
 /// 
 /// The `WiFiManager` handles all aspects of WiFi connectivity including:
@@ -219,7 +614,7 @@ impl core::fmt::Display for WiFiError {
 /// # Examples
 /// 
 /// ```no_run
-/// use wifi_embassy::{WiFiManager, WiFiConfig};
+/// use wifi_embassy::{WiFiManager, WiFiConfig, WiFiTimeouts};
 /// use embassy_executor::Spawner;
 /// 
 /// #[esp_hal_embassy::main]
@@ -230,6 +625,7 @@ impl core::fmt::Display for WiFiError {
 ///     let config = WiFiConfig {
 ///         ssid: env!("WIFI_SSID"),
 ///         password: env!("WIFI_PASSWORD"),
+///         timeouts: WiFiTimeouts::default(),
 ///     };
 ///     
 ///     let manager = WiFiManager::new(
@@ -260,15 +656,18 @@ impl core::fmt::Display for WiFiError {
 /// 
 /// # Thread Safety
 /// 
-/// The `WiFiManager` is designed to be used from a single async task.
+/// The `WiFiManager` can be shared across tasks as a `&'static` reference;
+/// the cached connection information sits behind a critical-section mutex.
 /// The network stack it provides can be shared across multiple tasks safely.
 pub struct WiFiManager {
     /// Embassy network stack for TCP/UDP operations
     stack: &'static Stack<'static>,
     /// Current connection information (None if disconnected)
-    connection_info: Option<ConnectionInfo>,
+    connection_info: BlockingMutex<CriticalSectionRawMutex, RefCell<Option<ConnectionInfo>>>,
     /// WiFi network configuration
     config: WiFiConfig,
+    /// Hardware RNG, also handed to esp-wifi; seeds TLS sessions of other drivers
+    rng: Rng,
 }
 
 impl WiFiManager {
@@ -294,6 +693,7 @@ impl WiFiManager {
         config: WiFiConfig,
     ) -> Result<Self, WiFiError> {
         rprintln!("[WIFI] Initializing WiFi manager");
+        config.validate()?;
         rprintln!("[WIFI] Target SSID: {}", config.ssid);
 
         // Initialize timers (following working examples)
@@ -326,7 +726,7 @@ impl WiFiManager {
         rprintln!("[WIFI] WiFi controller created");
 
         // Initialize Embassy network stack with static allocation (from working examples)
-        let stack_resources = mk_static!(StackResources<3>, StackResources::<3>::new());
+        let stack_resources = mk_static!(StackResources<STACK_SOCKETS>, StackResources::<STACK_SOCKETS>::new());
         let seed = (rng.random() as u64) << 32 | rng.random() as u64;
         let (stack, runner) = embassy_net::new(
             device,
@@ -348,26 +748,28 @@ impl WiFiManager {
 
         // Wait for link up (following examples timeout pattern)
         rprintln!("[WIFI] Waiting for WiFi connection...");
+        let max_link_polls = WiFiTimeouts::max_polls(config.timeouts.link);
         let mut timeout_counter = 0;
         loop {
             if stack.is_link_up() {
                 break;
             }
-            Timer::after(Duration::from_millis(500)).await;
+            Timer::after(WiFiTimeouts::POLL_INTERVAL).await;
             timeout_counter += 1;
             
-            if timeout_counter > 60 {  // 30 second timeout
+            if timeout_counter > max_link_polls {
                 return Err(WiFiError::Connection("WiFi connection timeout"));
             }
             
             if timeout_counter % 10 == 0 {
-                rprintln!("[WIFI] Still waiting for connection... ({} seconds)", timeout_counter / 2);
+                log_debug!("[WIFI] Still waiting for connection... ({} seconds)", timeout_counter / 2);
             }
         }
         rprintln!("[WIFI] WiFi link established");
         
         // Wait for IP address (DHCP)
         rprintln!("[WIFI] Waiting for DHCP IP address...");
+        let max_dhcp_polls = WiFiTimeouts::max_polls(config.timeouts.dhcp);
         timeout_counter = 0;
         loop {
             if let Some(config_v4) = stack.config_v4() {
@@ -385,19 +787,20 @@ impl WiFiManager {
                 
                 return Ok(Self {
                     stack,
-                    connection_info: Some(connection_info),
+                    connection_info: BlockingMutex::new(RefCell::new(Some(connection_info))),
                     config,
+                    rng,
                 });
             }
-            Timer::after(Duration::from_millis(500)).await;
+            Timer::after(WiFiTimeouts::POLL_INTERVAL).await;
             timeout_counter += 1;
             
-            if timeout_counter > 60 {  // 30 second timeout
+            if timeout_counter > max_dhcp_polls {
                 return Err(WiFiError::Dhcp("DHCP timeout"));
             }
             
             if timeout_counter % 10 == 0 {
-                rprintln!("[WIFI] Still waiting for DHCP... ({} seconds)", timeout_counter / 2);
+                log_debug!("[WIFI] Still waiting for DHCP... ({} seconds)", timeout_counter / 2);
             }
         }
     }
@@ -407,9 +810,76 @@ impl WiFiManager {
         self.stack
     }
 
+    /// Hardware RNG, for callers that need randomness once WiFi owns the peripheral
+    ///
+    /// Its output is truly random while the radio is running.
+    pub fn rng(&self) -> Rng {
+        self.rng.clone()
+    }
+
     /// Get current connection information
-    pub fn get_connection_info(&self) -> Option<&ConnectionInfo> {
-        self.connection_info.as_ref()
+    /// 
+    /// Returns a copy of the cache, which [`refresh_connection_info`](Self::refresh_connection_info)
+    /// may replace at any time.
+    pub fn get_connection_info(&self) -> Option<ConnectionInfo> {
+        self.connection_info.lock(|info| info.borrow().clone())
+    }
+
+    /// Subscribe to connection state changes
+    /// 
+    /// Lets tasks `await` transitions instead of polling [`is_connected`](Self::is_connected).
+    /// Returns `None` when all [`MAX_CONNECTION_EVENT_RECEIVERS`] receivers are in use.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,no_run
+    /// let mut events = wifi_manager.connection_events().unwrap();
+    /// loop {
+    ///     let event = events.changed().await;
+    ///     if !event.connected {
+    ///         // pause publishing, blink LED, ...
+    ///     }
+    /// }
+    /// ```
+    pub fn connection_events(&self) -> Option<ConnectionEventReceiver> {
+        CONNECTION_EVENTS.receiver()
+    }
+
+    /// Switch to new credentials without a reboot
+    /// 
+    /// The credentials are checked like [`WiFiConfig::from_credentials`] and
+    /// handed to the connection task, which drops the current association,
+    /// if any, and connects with them. The drop and the new connection show up
+    /// on [`connection_events`](Self::connection_events). If several updates
+    /// arrive before the task wakes, only the last one is applied.
+    /// [`get_config`](Self::get_config) keeps returning the boot credentials.
+    pub fn update_credentials(&self, ssid: &str, password: &str) -> Result<(), WiFiError> {
+        let config = WiFiConfig::from_credentials(ssid.as_bytes(), password.as_bytes())?;
+        NEW_CREDENTIALS.signal((config.ssid, config.password));
+        Ok(())
+    }
+
+    /// Connection statistics since boot or the last [`reset_stats`](Self::reset_stats)
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,no_run
+    /// let stats = wifi_manager.stats();
+    /// rprintln!("reconnects: {}, connected {}% of uptime", stats.reconnects, stats.connected_percent());
+    /// ```
+    pub fn stats(&self) -> WiFiStats {
+        let now = Instant::now();
+        WIFI_STATS.lock(|stats| stats.borrow().snapshot(now))
+    }
+
+    /// Zero the counters and restart the tracked period now
+    /// 
+    /// The current session, if any, keeps counting as connected time from
+    /// this point on. The disconnect count carried by [`ConnectionEvent`] is
+    /// not affected.
+    pub fn reset_stats(&self) {
+        let now = Instant::now();
+        WIFI_STATS.lock(|stats| stats.borrow_mut().reset(now));
     }
 
     /// Check if WiFi is connected and has IP address
@@ -433,23 +903,158 @@ impl WiFiManager {
     }
 
     /// Get updated connection status
-    pub async fn get_status(&mut self) -> Result<ConnectionInfo, WiFiError> {
+    pub async fn get_status(&self) -> Result<ConnectionInfo, WiFiError> {
         if !self.is_connected() {
             return Err(WiFiError::Connection("Not connected"));
         }
         
-        let config = self.stack.config_v4()
+        let connection_info = self.read_connection_info()
             .ok_or(WiFiError::Dhcp("No IP configuration"))?;
+        
+        self.connection_info.lock(|info| *info.borrow_mut() = Some(connection_info.clone()));
+        Ok(connection_info)
+    }
+
+    /// Re-read the IPv4 configuration and update the cached [`ConnectionInfo`]
+    /// 
+    /// The cache is only filled at connect time, so long-running devices should
+    /// call this periodically to pick up DHCP renewals that hand out a new
+    /// address. When the lease is lost the cache is cleared instead of keeping
+    /// the stale address, and receivers of [`connection_events`](Self::connection_events)
+    /// are told the connection went down; they are told again when a new lease
+    /// is acquired.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,no_run
+    /// # use wifi_embassy::ConnectionChange;
+    /// loop {
+    ///     Timer::after(Duration::from_secs(60)).await;
+    ///     if wifi_manager.refresh_connection_info() == ConnectionChange::Changed {
+    ///         let info = wifi_manager.get_connection_info().unwrap();
+    ///         println!("New IP: {}", info.ip_address);
+    ///     }
+    /// }
+    /// ```
+    pub fn refresh_connection_info(&self) -> ConnectionChange {
+        let current = self.read_connection_info();
+        let change = self.connection_info.lock(|cached| {
+            let mut cached = cached.borrow_mut();
+            let change = match (&*cached, &current) {
+                (None, None) => ConnectionChange::Unchanged,
+                (None, Some(_)) => ConnectionChange::Acquired,
+                (Some(_), None) => ConnectionChange::Lost,
+                (Some(cached), Some(current)) if cached == current => ConnectionChange::Unchanged,
+                (Some(_), Some(_)) => ConnectionChange::Changed,
+            };
+            cached.clone_from(&current);
+            change
+        });
+        
+        match (change, &current) {
+            (ConnectionChange::Changed, Some(info)) | (ConnectionChange::Acquired, Some(info)) => {
+                rprintln!("[WIFI] IPv4 configuration updated - IP: {}, Gateway: {:?}", info.ip_address, info.gateway);
+            }
+            (ConnectionChange::Lost, _) => rprintln!("[WIFI] DHCP lease lost - clearing connection info"),
+            _ => {}
+        }
+        
+        // Only the lease appearing or disappearing changes what receivers consider connected
+        if matches!(change, ConnectionChange::Acquired | ConnectionChange::Lost) {
+            let connected = current.is_some();
+            CONNECTION_EVENTS.sender().send_modify(|event| {
+                let disconnect_count = event.map(|event| event.disconnect_count).unwrap_or(0);
+                *event = Some(ConnectionEvent { connected, reason: None, disconnect_count });
+            });
+        }
+        
+        change
+    }
+
+    /// Measure the round-trip time to `address` with one ICMP echo request
+    /// 
+    /// Lets tasks check that the gateway or broker is reachable before
+    /// starting a TCP connect that could take much longer to fail. Requires
+    /// the `icmp` feature, which enables embassy-net's ICMP sockets; without
+    /// it this returns [`WiFiError::Icmp`] immediately instead of waiting.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,no_run
+    /// if let Some(gateway) = wifi_manager.get_connection_info().and_then(|info| info.gateway) {
+    ///     match wifi_manager.ping(gateway, Duration::from_secs(1)).await {
+    ///         Ok(rtt) => println!("Gateway reachable in {} ms", rtt.as_millis()),
+    ///         Err(e) => println!("Gateway unreachable: {}", e),
+    ///     }
+    /// }
+    /// ```
+    pub async fn ping(&self, address: embassy_net::Ipv4Address, timeout: Duration) -> Result<Duration, WiFiError> {
+        #[cfg(not(feature = "icmp"))]
+        {
+            let _ = (address, timeout);
+            Err(WiFiError::Icmp("ICMP sockets not enabled (wifi-embassy `icmp` feature)"))
+        }
+        
+        #[cfg(feature = "icmp")]
+        {
+            // Without an address the echo request could never be sent
+            if !self.is_connected() {
+                return Err(WiFiError::Connection("Not connected"));
+            }
+            
+            let mut rx_meta = [PacketMetadata::EMPTY];
+            let mut tx_meta = [PacketMetadata::EMPTY];
+            let mut rx_buffer = [0u8; PING_BUFFER_SIZE];
+            let mut tx_buffer = [0u8; PING_BUFFER_SIZE];
+            let mut ping_manager = PingManager::new(*self.stack, &mut rx_meta, &mut rx_buffer, &mut tx_meta, &mut tx_buffer);
             
-        let connection_info = ConnectionInfo {
+            let mut params = PingParams::new(address);
+            params.set_count(1);
+            params.set_timeout(timeout);
+            
+            // Bound the whole exchange as well, so a stalled send cannot hang the caller
+            match embassy_time::with_timeout(timeout, ping_manager.ping(&params)).await {
+                Ok(Ok(rtt)) => Ok(rtt),
+                Ok(Err(_)) => Err(WiFiError::Icmp("No echo reply")),
+                Err(_) => Err(WiFiError::Icmp("Timed out")),
+            }
+        }
+    }
+
+    /// Build a [`ConnectionInfo`] from the stack's current IPv4 configuration
+    fn read_connection_info(&self) -> Option<ConnectionInfo> {
+        self.stack.config_v4().map(|config| ConnectionInfo {
             ip_address: config.address.address(),
             gateway: config.gateway,
             dns_servers: config.dns_servers,
             subnet_prefix: config.address.prefix_len(),
-        };
-        
-        self.connection_info = Some(connection_info.clone());
-        Ok(connection_info)
+        })
+    }
+}
+
+/// Shared connectivity view; reads the live stack state
+/// 
+/// `rssi` is a no-op (`None`): the controller lives in the connection task.
+impl NetworkStackProvider for WiFiManager {
+    async fn link_status(&self) -> LinkStatus {
+        match (self.stack.is_link_up(), self.stack.config_v4().is_some()) {
+            (true, true) => LinkStatus::Connected,
+            (true, false) => LinkStatus::LinkUp,
+            (false, _) => LinkStatus::Down,
+        }
+    }
+    
+    async fn connection_info(&self) -> Option<NetworkInfo> {
+        self.stack.config_v4().map(|config| NetworkInfo {
+            ip_address: config.address.address(),
+            gateway: config.gateway,
+            dns_server: config.dns_servers.first().copied(),
+            subnet_prefix: config.address.prefix_len(),
+        })
+    }
+    
+    async fn rssi(&self) -> Option<i8> {
+        None
     }
 }
 
@@ -457,29 +1062,64 @@ impl WiFiManager {
 #[embassy_executor::task]
 async fn wifi_connection_task(
     mut controller: WifiController<'static>,
-    ssid: heapless::String<32>,
-    password: heapless::String<64>,
+    mut ssid: heapless::String<32>,
+    mut password: heapless::String<64>,
 ) {
     rprintln!("[WIFI] Starting connection task for SSID: {}", ssid);
     
+    // Keep the reason code, wait_for_event() does not report it
+    event::StaDisconnected::update_handler(|event| {
+        LAST_DISCONNECT_REASON.store(event.reason(), Ordering::Relaxed);
+    });
+    
+    let events = CONNECTION_EVENTS.sender();
+    let mut disconnect_count = 0u32;
+    
     loop {
-        match esp_wifi::wifi::wifi_state() {
-            WifiState::StaConnected => {
-                // Wait until we're no longer connected
-                controller.wait_for_event(WifiEvent::StaDisconnected).await;
-                rprintln!("[WIFI] Disconnected from network");
-                Timer::after(Duration::from_millis(5000)).await
+        let mut new_credentials = NEW_CREDENTIALS.try_take();
+        
+        if new_credentials.is_none() && matches!(esp_wifi::wifi::wifi_state(), WifiState::StaConnected) {
+            // Wait until we're no longer connected, or told to switch networks
+            match embassy_futures::select::select(
+                controller.wait_for_event(WifiEvent::StaDisconnected),
+                NEW_CREDENTIALS.wait(),
+            ).await {
+                embassy_futures::select::Either::First(_) => {
+                    let reason = LAST_DISCONNECT_REASON.load(Ordering::Relaxed);
+                    disconnect_count = disconnect_count.saturating_add(1);
+                    WIFI_STATS.lock(|stats| stats.borrow_mut().record_disconnect(Instant::now(), reason));
+                    rprintln!("[WIFI] Disconnected from network: {} (reason {})", disconnect_reason_str(reason), reason);
+                    events.send(ConnectionEvent { connected: false, reason: Some(reason), disconnect_count });
+                    Timer::after(Duration::from_millis(5000)).await
+                }
+                embassy_futures::select::Either::Second(credentials) => new_credentials = Some(credentials),
             }
-            _ => {}
         }
         
-        if !matches!(controller.is_started(), Ok(true)) {
+        let reconfigure = new_credentials.is_some();
+        if let Some((new_ssid, new_password)) = new_credentials {
+            rprintln!("[WIFI] Credentials changed - switching to '{}'", new_ssid);
+            if matches!(esp_wifi::wifi::wifi_state(), WifiState::StaConnected) {
+                let _ = controller.disconnect_async().await;
+                let reason = LAST_DISCONNECT_REASON.load(Ordering::Relaxed);
+                disconnect_count = disconnect_count.saturating_add(1);
+                WIFI_STATS.lock(|stats| stats.borrow_mut().record_disconnect(Instant::now(), reason));
+                events.send(ConnectionEvent { connected: false, reason: Some(reason), disconnect_count });
+            }
+            ssid = new_ssid;
+            password = new_password;
+        }
+        
+        let started = matches!(controller.is_started(), Ok(true));
+        if !started || reconfigure {
             let client_config = Configuration::Client(ClientConfiguration {
                 ssid: alloc::string::String::from(ssid.as_str()),
                 password: alloc::string::String::from(password.as_str()),
                 ..Default::default()
             });
             controller.set_configuration(&client_config).unwrap();
+        }
+        if !started {
             rprintln!("[WIFI] Starting WiFi...");
             controller.start_async().await.unwrap();
             rprintln!("[WIFI] WiFi started");
@@ -489,9 +1129,13 @@ async fn wifi_connection_task(
         match controller.connect_async().await {
             Ok(_) => {
                 rprintln!("[WIFI] Connected successfully to '{}'", ssid);
+                WIFI_STATS.lock(|stats| stats.borrow_mut().record_connect(Instant::now()));
+                let reason = (disconnect_count > 0).then(|| LAST_DISCONNECT_REASON.load(Ordering::Relaxed));
+                events.send(ConnectionEvent { connected: true, reason, disconnect_count });
             }
             Err(e) => {
-                rprintln!("[WIFI] Connection failed: {:?}", e);
+                let reason = LAST_DISCONNECT_REASON.load(Ordering::Relaxed);
+                rprintln!("[WIFI] Connection failed: {:?} - {} (reason {})", e, disconnect_reason_str(reason), reason);
                 Timer::after(Duration::from_millis(5000)).await;
             }
         }
@@ -502,4 +1146,157 @@ async fn wifi_connection_task(
 #[embassy_executor::task]
 async fn network_task(mut runner: Runner<'static, WifiDevice<'static>>) -> ! {
     runner.run().await
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_default_timeouts_keep_previous_limits() {
+        let timeouts = WiFiTimeouts::default();
+        assert_eq!(WiFiTimeouts::max_polls(timeouts.link), 60);
+        assert_eq!(WiFiTimeouts::max_polls(timeouts.dhcp), 60);
+    }
+    
+    #[test]
+    fn test_max_polls_rounds_up() {
+        assert_eq!(WiFiTimeouts::max_polls(Duration::from_millis(0)), 0);
+        assert_eq!(WiFiTimeouts::max_polls(Duration::from_millis(1)), 1);
+        assert_eq!(WiFiTimeouts::max_polls(Duration::from_millis(500)), 1);
+        assert_eq!(WiFiTimeouts::max_polls(Duration::from_millis(501)), 2);
+        assert_eq!(WiFiTimeouts::max_polls(Duration::from_secs(120)), 240);
+    }
+    
+    #[test]
+    fn test_validate_accepts_wpa2_and_open_networks() {
+        assert!(WiFiConfig::from_credentials(b"HomeNet", b"password123").is_ok());
+        assert!(WiFiConfig::from_credentials(b"OpenCafe", b"").is_ok());
+        assert!(WiFiConfig::from_credentials(&[b'a'; 32], &[b'p'; 63]).is_ok());
+    }
+    
+    #[test]
+    fn test_validate_rejects_bad_lengths() {
+        assert!(matches!(
+            WiFiConfig::from_credentials(b"", b"password123"),
+            Err(WiFiError::Configuration("SSID is empty"))
+        ));
+        assert!(matches!(
+            WiFiConfig::from_credentials(&[b'a'; 33], b"password123"),
+            Err(WiFiError::Configuration("SSID longer than 32 bytes"))
+        ));
+        assert!(matches!(
+            WiFiConfig::from_credentials(b"HomeNet", b"short"),
+            Err(WiFiError::Configuration("password shorter than 8 bytes"))
+        ));
+        assert!(matches!(
+            WiFiConfig::from_credentials(b"HomeNet", &[b'p'; 64]),
+            Err(WiFiError::Configuration("password longer than 63 bytes"))
+        ));
+    }
+    
+    #[test]
+    fn test_stats_track_sessions_and_reset() {
+        let mut tracker = StatsTracker::new();
+        tracker.record_connect(Instant::from_secs(10));
+        tracker.record_disconnect(Instant::from_secs(70), 200);
+        tracker.record_connect(Instant::from_secs(80));
+        
+        let stats = tracker.snapshot(Instant::from_secs(100));
+        assert_eq!((stats.connects, stats.disconnects, stats.reconnects), (2, 1, 1));
+        assert_eq!(stats.connected_time, Duration::from_secs(80));
+        assert_eq!(stats.tracked_time, Duration::from_secs(100));
+        assert_eq!(stats.last_disconnect_reason, Some(200));
+        assert_eq!(stats.connected_percent(), 80);
+        
+        // A still-running session counts from the reset, later reconnects stay reconnects
+        tracker.reset(Instant::from_secs(100));
+        let stats = tracker.snapshot(Instant::from_secs(110));
+        assert_eq!((stats.connects, stats.disconnects, stats.reconnects), (0, 0, 0));
+        assert_eq!(stats.connected_time, Duration::from_secs(10));
+        assert_eq!(stats.connected_percent(), 100);
+        tracker.record_disconnect(Instant::from_secs(110), 8);
+        tracker.record_connect(Instant::from_secs(120));
+        assert_eq!(tracker.snapshot(Instant::from_secs(120)).reconnects, 1);
+    }
+    
+    #[test]
+    fn test_stats_counters_saturate() {
+        let mut tracker = StatsTracker::new();
+        tracker.connects = u32::MAX;
+        tracker.disconnects = u32::MAX;
+        tracker.connected_total = Duration::from_ticks(u64::MAX);
+        tracker.record_connect(Instant::from_secs(1));
+        tracker.record_disconnect(Instant::from_secs(2), 200);
+        
+        let stats = tracker.snapshot(Instant::from_secs(3));
+        assert_eq!((stats.connects, stats.disconnects), (u32::MAX, u32::MAX));
+        assert_eq!(stats.connected_time, Duration::from_ticks(u64::MAX));
+        assert_eq!(stats.connected_percent(), 100);
+    }
+    
+    #[test]
+    fn test_validate_rejects_invalid_utf8() {
+        assert!(matches!(
+            WiFiConfig::from_credentials(&[0xff, 0xfe], b"password123"),
+            Err(WiFiError::Configuration("SSID is not valid UTF-8"))
+        ));
+        assert!(matches!(
+            WiFiConfig::from_credentials(b"HomeNet", &[0xc3, 0x28, b'x', b'x', b'x', b'x', b'x', b'x']),
+            Err(WiFiError::Configuration("password is not valid UTF-8"))
+        ));
+    }
+    
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_connection_info_json_round_trip() {
+        let mut dns_servers = heapless::Vec::new();
+        dns_servers.push(embassy_net::Ipv4Address::new(1, 1, 1, 1)).unwrap();
+        dns_servers.push(embassy_net::Ipv4Address::new(255, 255, 255, 255)).unwrap();
+        let info = ConnectionInfo {
+            ip_address: embassy_net::Ipv4Address::new(192, 168, 1, 42),
+            gateway: Some(embassy_net::Ipv4Address::new(192, 168, 1, 1)),
+            dns_servers,
+            subnet_prefix: 24,
+        };
+        
+        let json: heapless::String<160> = serde_json_core::to_string(&info).unwrap();
+        assert_eq!(
+            json,
+            r#"{"ip_address":"192.168.1.42","gateway":"192.168.1.1","dns_servers":["1.1.1.1","255.255.255.255"],"subnet_prefix":24}"#
+        );
+        let (parsed, _): (ConnectionInfo, _) = serde_json_core::from_str(&json).unwrap();
+        assert_eq!(parsed, info);
+        
+        // No gateway and no DNS servers, e.g. a link-local setup
+        let bare = ConnectionInfo { gateway: None, dns_servers: heapless::Vec::new(), ..info };
+        let json: heapless::String<160> = serde_json_core::to_string(&bare).unwrap();
+        assert_eq!(json, r#"{"ip_address":"192.168.1.42","gateway":null,"dns_servers":[],"subnet_prefix":24}"#);
+        let (parsed, _): (ConnectionInfo, _) = serde_json_core::from_str(&json).unwrap();
+        assert_eq!(parsed, bare);
+        
+        // Addresses must be dotted quads
+        assert!(serde_json_core::from_str::<ConnectionInfo>(
+            r#"{"ip_address":"192.168.1","gateway":null,"dns_servers":[],"subnet_prefix":24}"#
+        ).is_err());
+    }
+    
+    #[test]
+    fn test_wifi_error_into_iot_error() {
+        let cases: [(WiFiError, u16, &str); 5] = [
+            (WiFiError::HardwareInit("radio init"), 2007, "Hardware initialization failed: radio init"),
+            (WiFiError::Configuration("SSID is empty"), 2002, "WiFi configuration failed: SSID is empty"),
+            (WiFiError::Connection("auth failed"), 2001, "WiFi connection failed: auth failed"),
+            (WiFiError::Dhcp("no lease"), 2003, "DHCP failed: no lease"),
+            (WiFiError::Icmp("no reply"), 2005, "Ping failed: no reply"),
+        ];
+        for (wifi_error, code, message) in cases {
+            let error = IoTError::from(wifi_error);
+            assert!(error.is_network_error(), "{}", message);
+            assert_eq!(error.error_code(), code, "{}", message);
+            let mut text: heapless::String<128> = heapless::String::new();
+            core::fmt::Write::write_fmt(&mut text, format_args!("{}", error)).unwrap();
+            assert!(text.contains(message), "{} missing from {}", message, text);
+        }
+    }
+}
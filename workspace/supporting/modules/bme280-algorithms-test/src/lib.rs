@@ -93,15 +93,29 @@ pub mod algorithms {
         (p as f32) / 25600.0
     }
 
-    /// Simplified humidity compensation using linear mapping
-    pub fn compensate_humidity(adc_h: i32) -> f32 {
-        let humidity_raw_min = 30000.0;
-        let humidity_raw_max = 65000.0;
-        
-        let normalized = (adc_h as f32 - humidity_raw_min) / (humidity_raw_max - humidity_raw_min);
-        let basic_humidity = normalized * 100.0;
-        
-        // Clamp to valid range
-        basic_humidity.max(0.0).min(100.0)
+    /// Humidity compensation using BME280 datasheet algorithm
+    /// 
+    /// Fixed-point formula from the datasheet (section 4.2.3) using `t_fine` from
+    /// [`compensate_temperature`] and the `dig_h1..dig_h6` coefficients. Intermediate
+    /// products are 64-bit so out-of-range raw values clamp instead of overflowing.
+    /// Returns relative humidity in %, 0-100; a skipped measurement (`0x8000`) gives 0.0.
+    pub fn compensate_humidity(adc_h: i32, t_fine: i32, calib: &CalibrationData) -> f32 {
+        if adc_h == 0x8000 {
+            return 0.0; // Humidity measurement skipped (BMP280 or oversampling off)
+        }
+
+        let adc_h = adc_h as i64;
+        let v_x1 = (t_fine as i64) - 76800;
+
+        let mut humidity = ((adc_h << 14) - ((calib.dig_h4 as i64) << 20) -
+                            ((calib.dig_h5 as i64) * v_x1) + 16384) >> 15;
+        humidity *= ((((((v_x1 * (calib.dig_h6 as i64)) >> 10) *
+                       (((v_x1 * (calib.dig_h3 as i64)) >> 11) + 32768)) >> 10) + 2097152) *
+                     (calib.dig_h2 as i64) + 8192) >> 14;
+        humidity -= ((((humidity >> 15) * (humidity >> 15)) >> 7) * (calib.dig_h1 as i64)) >> 4;
+
+        // Q22.10 %RH, 419430400 = 100%
+        let humidity = humidity.clamp(0, 419430400);
+        (humidity >> 12) as f32 / 1024.0
     }
 }
\ No newline at end of file
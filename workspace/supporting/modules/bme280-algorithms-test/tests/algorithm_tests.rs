@@ -3,7 +3,7 @@
 //! Comprehensive tests for BME280 sensor algorithms running on host (x86_64).
 //! These tests validate the mathematical algorithms without hardware dependencies.
 
-use bme280_tests::{
+use bme280_algorithms_test::{
     algorithms::*,
     constants::*,
     CalibrationData, 
@@ -98,33 +98,47 @@ fn test_pressure_compensation_algorithm() {
 
 #[test]
 fn test_humidity_compensation() {
-    // Test boundary conditions
-    assert_eq!(compensate_humidity(30000), 0.0);   // Minimum
-    assert_eq!(compensate_humidity(65000), 100.0); // Maximum
-
-    // Test middle value (should be ~50%)
-    let mid_humidity = compensate_humidity(47500);
-    assert!((mid_humidity - 50.0).abs() < 1.0);
-
-    // Test clamping behavior
-    assert_eq!(compensate_humidity(20000), 0.0);   // Below minimum -> 0%
-    assert_eq!(compensate_humidity(80000), 100.0); // Above maximum -> 100%
-
-    // Test typical indoor humidity range
-    let indoor = compensate_humidity(45000);
-    assert!(indoor >= 30.0 && indoor <= 70.0);
-
-    // Test monotonicity within valid range
-    let hum_30k = compensate_humidity(30000);
-    let hum_40k = compensate_humidity(40000);
-    let hum_50k = compensate_humidity(50000);
-    let hum_60k = compensate_humidity(60000);
-    let hum_65k = compensate_humidity(65000);
-    
-    assert!(hum_30k <= hum_40k);
-    assert!(hum_40k <= hum_50k);
-    assert!(hum_50k <= hum_60k);
-    assert!(hum_60k <= hum_65k);
+    let calibration = CalibrationData {
+        dig_t1: 27504, dig_t2: 26435, dig_t3: -1000,
+        dig_h1: 75, dig_h2: 367, dig_h3: 0, dig_h4: 301, dig_h5: 50, dig_h6: 30,
+        ..Default::default()
+    };
+    let (_, t_fine) = compensate_temperature(519888, &calibration);
+    assert_eq!(t_fine, 128422);
+
+    // Reference values from the datasheet's double-precision formula (section 8.1)
+    let vectors = [
+        (20000, 3.3126),
+        (24000, 26.1325),
+        (27000, 43.1489),
+        (30000, 60.0807),
+        (33000, 76.9281),
+        (36000, 93.6909),
+    ];
+    for (adc_h, expected) in vectors {
+        let humidity = compensate_humidity(adc_h, t_fine, &calibration);
+        assert!((humidity - expected).abs() < 0.01,
+                "adc_h={}: got {}, expected {}", adc_h, humidity, expected);
+    }
+
+    // Calibration coefficients matter - a different sensor reads differently
+    let other_sensor = CalibrationData {
+        dig_h2: 354, dig_h4: 340, dig_h5: 0,
+        ..calibration.clone()
+    };
+    let humidity = compensate_humidity(30000, t_fine, &other_sensor);
+    assert!((humidity - 45.2398).abs() < 0.01);
+
+    // Skipped measurement
+    assert_eq!(compensate_humidity(0x8000, t_fine, &calibration), 0.0);
+
+    // Monotonic in the raw value
+    let mut previous = 0.0;
+    for adc_h in (20000..36000).step_by(1000) {
+        let humidity = compensate_humidity(adc_h, t_fine, &calibration);
+        assert!(humidity >= previous);
+        previous = humidity;
+    }
 }
 
 #[test]
@@ -270,8 +284,9 @@ fn test_algorithm_edge_cases() {
     assert!(pressure_hot >= 0.0);
 
     // Test humidity with extreme values
-    let hum_zero = compensate_humidity(0);
-    let hum_max = compensate_humidity(65535); // 16-bit max
+    let (_, t_fine) = compensate_temperature(519888, &calibration);
+    let hum_zero = compensate_humidity(0, t_fine, &calibration);
+    let hum_max = compensate_humidity(65535, t_fine, &calibration); // 16-bit max
     
     assert_eq!(hum_zero, 0.0);
     assert_eq!(hum_max, 100.0);
@@ -296,7 +311,7 @@ fn test_performance_characteristics() {
 
         let (temp, t_fine) = compensate_temperature(adc_temp, &calibration);
         let pressure = compensate_pressure(adc_press, t_fine, &calibration);
-        let humidity = compensate_humidity(adc_hum);
+        let humidity = compensate_humidity(adc_hum, t_fine, &calibration);
 
         // All results should be finite and reasonable
         assert!(temp.is_finite());
@@ -16,6 +16,10 @@ pub mod wifi_manager;
 // Re-export main types (matches wifi-embassy exports)
 pub use wifi_manager::{WiFiManager, WiFiConfig, ConnectionInfo, WiFiError};
 
+// Shared connectivity trait and the shim to call it from blocking code
+pub use iot_common::{NetworkStackProvider, NetworkInfo, LinkStatus};
+pub use iot_common::network::block_on;
+
 // Re-export helper functions for manual usage
 pub use wifi_manager::{
     create_interface,
@@ -18,6 +18,7 @@ use smoltcp::{
     wire::{HardwareAddress, EthernetAddress},
 };
 use blocking_network_stack::Stack;
+use iot_common::{NetworkStackProvider, NetworkInfo, LinkStatus};
 
 /// WiFi configuration structure (matches wifi-embassy for compatibility)
 #[derive(Debug, Clone)]
@@ -123,6 +124,34 @@ impl WiFiManager {
     }
 }
 
+/// Blocking shim for the shared connectivity trait
+///
+/// Every future is ready on the first poll; synchronous callers use
+/// `iot_common::network::block_on`. State is the one cached by `connect`, and
+/// `rssi` is a no-op (`None`).
+impl NetworkStackProvider for WiFiManager {
+    async fn link_status(&self) -> LinkStatus {
+        if self.connection_info.is_some() {
+            LinkStatus::Connected
+        } else {
+            LinkStatus::Down
+        }
+    }
+
+    async fn connection_info(&self) -> Option<NetworkInfo> {
+        self.connection_info.as_ref().map(|info| NetworkInfo {
+            ip_address: info.ip_address,
+            gateway: info.gateway,
+            dns_server: info.dns_servers.first().copied(),
+            subnet_prefix: info.subnet_prefix,
+        })
+    }
+
+    async fn rssi(&self) -> Option<i8> {
+        None
+    }
+}
+
 /// Create smoltcp network interface (helper function)
 pub fn create_interface(device: &mut WifiDevice) -> Interface {
     let timestamp = || {
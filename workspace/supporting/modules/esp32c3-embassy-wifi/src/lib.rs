@@ -57,6 +57,7 @@ use esp_wifi::{
     EspWifiController,
 };
 use log::{info, error, warn};
+use iot_common::{NetworkStackProvider, NetworkInfo, LinkStatus};
 // StaticCell is used via the mk_static! macro
 
 // Re-exports for convenience
@@ -66,6 +67,9 @@ pub use esp_hal;
 pub use esp_wifi;
 pub use log;
 
+// Shared connectivity trait, implemented by WiFiManager
+pub use iot_common::{NetworkStackProvider, NetworkInfo, LinkStatus};
+
 /// Utility macro for creating static allocations
 #[macro_export]
 macro_rules! mk_static {
@@ -89,6 +93,34 @@ pub struct ConnectionInfo {
     pub subnet_prefix: u8,
 }
 
+/// Timeouts applied while the manager brings the connection up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WiFiTimeouts {
+    /// Maximum wait for the WiFi link
+    pub link: Duration,
+    /// Maximum wait for a DHCP lease once the link is up
+    pub dhcp: Duration,
+}
+
+impl WiFiTimeouts {
+    /// Interval between link/DHCP status checks
+    pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+    /// Number of poll intervals after which `timeout` has expired (rounded up)
+    pub fn max_polls(timeout: Duration) -> u64 {
+        timeout.as_millis().div_ceil(Self::POLL_INTERVAL.as_millis())
+    }
+}
+
+impl Default for WiFiTimeouts {
+    fn default() -> Self {
+        Self {
+            link: Duration::from_secs(30),
+            dhcp: Duration::from_secs(30),
+        }
+    }
+}
+
 /// WiFi manager errors
 #[derive(Debug)]
 pub enum WiFiError {
@@ -124,6 +156,8 @@ impl WiFiManager {
     /// Create a new WiFi manager from raw peripherals
     /// 
     /// This follows the exact pattern from wifi-new-01 that works.
+    /// Waits up to 30 seconds each for the link and DHCP; use
+    /// [`new_with_timeouts`](Self::new_with_timeouts) to change that.
     /// 
     /// # Arguments
     /// * `spawner` - Embassy task spawner
@@ -141,6 +175,23 @@ impl WiFiManager {
         rng_peripheral: esp_hal::peripherals::RNG<'static>,
         ssid: &'static str,
         password: &'static str,
+    ) -> Result<Self, WiFiError> {
+        Self::new_with_timeouts(spawner, timg0, timg1, wifi, rng_peripheral, ssid, password, WiFiTimeouts::default()).await
+    }
+
+    /// Create a new WiFi manager with custom link/DHCP timeouts
+    /// 
+    /// Same as [`new`](Self::new); `timeouts` bounds the two wait loops.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn new_with_timeouts(
+        spawner: Spawner,
+        timg0: TIMG0<'static>,
+        timg1: TIMG1<'static>,
+        wifi: WIFI<'static>,
+        rng_peripheral: esp_hal::peripherals::RNG<'static>,
+        ssid: &'static str,
+        password: &'static str,
+        timeouts: WiFiTimeouts,
     ) -> Result<Self, WiFiError> {
         info!("WiFi Simple Embassy: Initializing WiFi manager");
 
@@ -190,15 +241,16 @@ impl WiFiManager {
 
         // Wait for link up
         info!("WiFi Simple Embassy: Waiting for WiFi connection...");
+        let max_link_polls = WiFiTimeouts::max_polls(timeouts.link);
         let mut timeout_counter = 0;
         loop {
             if stack.is_link_up() {
                 break;
             }
-            Timer::after(Duration::from_millis(500)).await;
+            Timer::after(WiFiTimeouts::POLL_INTERVAL).await;
             timeout_counter += 1;
             
-            if timeout_counter > 60 {  // 30 second timeout
+            if timeout_counter > max_link_polls {
                 return Err(WiFiError::Connection("WiFi connection timeout"));
             }
             
@@ -210,6 +262,7 @@ impl WiFiManager {
         
         // Wait for IP address
         info!("WiFi Simple Embassy: Waiting for DHCP IP address...");
+        let max_dhcp_polls = WiFiTimeouts::max_polls(timeouts.dhcp);
         timeout_counter = 0;
         loop {
             if let Some(config) = stack.config_v4() {
@@ -230,10 +283,10 @@ impl WiFiManager {
                     connection_info: Some(connection_info),
                 });
             }
-            Timer::after(Duration::from_millis(500)).await;
+            Timer::after(WiFiTimeouts::POLL_INTERVAL).await;
             timeout_counter += 1;
             
-            if timeout_counter > 60 {  // 30 second timeout
+            if timeout_counter > max_dhcp_polls {
                 return Err(WiFiError::Dhcp("DHCP timeout"));
             }
             
@@ -289,6 +342,32 @@ impl WiFiManager {
     }
 }
 
+/// Shared connectivity view; reads the live stack state
+///
+/// `rssi` is a no-op (`None`): the controller lives in the connection task.
+impl NetworkStackProvider for WiFiManager {
+    async fn link_status(&self) -> LinkStatus {
+        match (self.stack.is_link_up(), self.stack.config_v4().is_some()) {
+            (true, true) => LinkStatus::Connected,
+            (true, false) => LinkStatus::LinkUp,
+            (false, _) => LinkStatus::Down,
+        }
+    }
+
+    async fn connection_info(&self) -> Option<NetworkInfo> {
+        self.stack.config_v4().map(|config| NetworkInfo {
+            ip_address: config.address.address(),
+            gateway: config.gateway,
+            dns_server: config.dns_servers.first().copied(),
+            subnet_prefix: config.address.prefix_len(),
+        })
+    }
+
+    async fn rssi(&self) -> Option<i8> {
+        None
+    }
+}
+
 /// WiFi connection management task (internal)
 #[embassy_executor::task]
 async fn wifi_connection_task(